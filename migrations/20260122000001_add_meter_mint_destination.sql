@@ -0,0 +1,9 @@
+-- Add optional per-meter minting destination wallet
+-- Created: 2026-01-22
+-- When NULL, tokens minted from the meter's readings go to the owner's wallet.
+
+-- 1. meter_registry (read by the reading processor when minting)
+ALTER TABLE meter_registry ADD COLUMN IF NOT EXISTS mint_destination_wallet VARCHAR(88);
+
+-- 2. meters (user-facing meter listing)
+ALTER TABLE meters ADD COLUMN IF NOT EXISTS mint_destination_wallet VARCHAR(88);
@@ -0,0 +1,17 @@
+-- Track per-user liquidity provider shares in AMM pools
+-- Created: 2026-01-22
+
+-- AmmService maintains the pool-wide share supply alongside reserves
+ALTER TABLE liquidity_pools ADD COLUMN IF NOT EXISTS total_supply NUMERIC(20, 9) NOT NULL DEFAULT 0;
+
+CREATE TABLE IF NOT EXISTS lp_positions (
+    pool_id UUID NOT NULL REFERENCES liquidity_pools(id) ON DELETE CASCADE,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    shares NUMERIC(20, 9) NOT NULL DEFAULT 0,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (pool_id, user_id),
+    CONSTRAINT chk_lp_positions_shares CHECK (shares >= 0)
+);
+
+CREATE INDEX IF NOT EXISTS idx_lp_positions_user ON lp_positions(user_id);
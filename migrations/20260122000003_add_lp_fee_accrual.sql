@@ -0,0 +1,24 @@
+-- LP fee accrual: per-pool fee-per-share accumulators and per-position checkpoints
+-- Created: 2026-01-22
+
+-- Cumulative swap fees per LP share, one accumulator per pool token
+ALTER TABLE liquidity_pools ADD COLUMN IF NOT EXISTS fee_growth_a NUMERIC(38, 18) NOT NULL DEFAULT 0;
+ALTER TABLE liquidity_pools ADD COLUMN IF NOT EXISTS fee_growth_b NUMERIC(38, 18) NOT NULL DEFAULT 0;
+
+-- Accumulator snapshot at the position's last checkpoint, plus fees settled
+-- into the position (on share changes) but not yet claimed
+ALTER TABLE lp_positions ADD COLUMN IF NOT EXISTS fee_checkpoint_a NUMERIC(38, 18) NOT NULL DEFAULT 0;
+ALTER TABLE lp_positions ADD COLUMN IF NOT EXISTS fee_checkpoint_b NUMERIC(38, 18) NOT NULL DEFAULT 0;
+ALTER TABLE lp_positions ADD COLUMN IF NOT EXISTS fees_owed_a NUMERIC(20, 9) NOT NULL DEFAULT 0;
+ALTER TABLE lp_positions ADD COLUMN IF NOT EXISTS fees_owed_b NUMERIC(20, 9) NOT NULL DEFAULT 0;
+
+CREATE TABLE IF NOT EXISTS lp_fee_claims (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    pool_id UUID NOT NULL REFERENCES liquidity_pools(id) ON DELETE CASCADE,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    amount_a NUMERIC(20, 9) NOT NULL DEFAULT 0,
+    amount_b NUMERIC(20, 9) NOT NULL DEFAULT 0,
+    claimed_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_lp_fee_claims_user ON lp_fee_claims(user_id, claimed_at DESC);
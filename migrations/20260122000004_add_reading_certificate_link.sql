@@ -0,0 +1,8 @@
+-- Link meter readings to the ERC certificate they were aggregated into
+-- Created: 2026-01-22
+-- A reading can back at most one certificate; NULL means not yet certified.
+
+ALTER TABLE meter_readings ADD COLUMN IF NOT EXISTS erc_certificate_id UUID;
+
+CREATE INDEX IF NOT EXISTS idx_meter_readings_erc_certificate
+    ON meter_readings(erc_certificate_id);
@@ -0,0 +1,10 @@
+-- Durable slot cursor for the blockchain event processor
+-- Created: 2026-01-22
+-- Stores the last slot the processor has fully handled so restarts resume
+-- from there instead of rescanning.
+
+CREATE TABLE IF NOT EXISTS event_processor_cursor (
+    name VARCHAR(64) PRIMARY KEY,
+    last_processed_slot BIGINT NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
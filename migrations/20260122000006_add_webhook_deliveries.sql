@@ -0,0 +1,23 @@
+-- Durable webhook delivery queue with dead-lettering
+-- Created: 2026-01-22
+-- Webhooks are enqueued here and delivered by a background worker with
+-- exponential backoff; deliveries that exhaust max_attempts are dead-lettered.
+
+CREATE TABLE IF NOT EXISTS webhook_deliveries (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    event_type VARCHAR(64) NOT NULL,
+    payload JSONB NOT NULL,
+    status VARCHAR(20) NOT NULL DEFAULT 'pending',
+    attempts INTEGER NOT NULL DEFAULT 0,
+    max_attempts INTEGER NOT NULL DEFAULT 5,
+    last_error TEXT,
+    next_retry_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    delivered_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CONSTRAINT chk_webhook_delivery_status
+        CHECK (status IN ('pending', 'failed', 'delivered', 'dead_letter'))
+);
+
+CREATE INDEX IF NOT EXISTS idx_webhook_deliveries_processing
+    ON webhook_deliveries(status, next_retry_at);
@@ -0,0 +1,24 @@
+-- Persisted meter alerts for daily digest emails
+-- Created: 2026-01-22
+-- Alerts raised during reading ingestion are stored here so a scheduled job
+-- can batch each owner's unresolved alerts into a single digest email.
+
+CREATE TABLE IF NOT EXISTS meter_alerts (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    meter_serial VARCHAR(255) NOT NULL,
+    alert_type VARCHAR(64) NOT NULL,
+    severity VARCHAR(20) NOT NULL,
+    message TEXT NOT NULL,
+    value DOUBLE PRECISION NOT NULL,
+    threshold DOUBLE PRECISION NOT NULL,
+    resolved_at TIMESTAMPTZ,
+    digested_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CONSTRAINT chk_meter_alert_severity
+        CHECK (severity IN ('info', 'warning', 'critical'))
+);
+
+CREATE INDEX IF NOT EXISTS idx_meter_alerts_pending_digest
+    ON meter_alerts(user_id, created_at)
+    WHERE resolved_at IS NULL AND digested_at IS NULL;
@@ -0,0 +1,11 @@
+-- Client-supplied idempotency key for order creation
+-- Created: 2026-01-22
+-- A retried create request carrying the same client_order_id returns the
+-- original order instead of creating (and escrowing) a duplicate.
+
+ALTER TABLE trading_orders
+    ADD COLUMN IF NOT EXISTS client_order_id VARCHAR(64);
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_trading_orders_client_order_id
+    ON trading_orders(user_id, client_order_id)
+    WHERE client_order_id IS NOT NULL;
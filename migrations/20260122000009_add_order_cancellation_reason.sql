@@ -0,0 +1,7 @@
+-- Reason an order was closed without filling
+-- Created: 2026-01-22
+-- Records why the engine or service cancelled or expired an order (dust,
+-- expired, insufficient funds, user request) so it can be shown to traders.
+
+ALTER TABLE trading_orders
+    ADD COLUMN IF NOT EXISTS cancellation_reason TEXT;
@@ -0,0 +1,7 @@
+-- Index audit events by settlement
+-- Created: 2026-01-22
+-- Settlement lifecycle events are stored in user_activities with the
+-- settlement id in metadata; this keeps the per-settlement trail lookup fast.
+
+CREATE INDEX IF NOT EXISTS idx_user_activities_settlement_id
+    ON user_activities ((metadata->>'settlement_id'));
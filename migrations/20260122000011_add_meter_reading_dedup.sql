@@ -0,0 +1,14 @@
+-- Deduplicate Meter Readings
+-- Created: 2026-01-22
+-- Gateways retry readings on flaky links; a reading is identified by its meter
+-- and timestamp so a retry must not be ingested (and minted) twice.
+
+-- Keep the first copy of any reading already ingested more than once
+DELETE FROM meter_readings a
+USING meter_readings b
+WHERE a.meter_serial = b.meter_serial
+  AND a.reading_timestamp = b.reading_timestamp
+  AND (COALESCE(a.created_at, 'epoch'), a.id) > (COALESCE(b.created_at, 'epoch'), b.id);
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_meter_readings_serial_timestamp
+    ON meter_readings (meter_serial, reading_timestamp);
@@ -0,0 +1,31 @@
+-- Meter Reading Quarantine
+-- Created: 2026-01-22
+-- Readings that fail anomaly checks (negative energy, power above the meter's
+-- rating, future timestamps) are held here for manual review instead of
+-- being minted. Per-meter bounds override the gateway-wide defaults.
+
+ALTER TABLE meter_registry ADD COLUMN IF NOT EXISTS max_power_kw DOUBLE PRECISION;
+ALTER TABLE meter_registry ADD COLUMN IF NOT EXISTS max_kwh_per_interval DOUBLE PRECISION;
+
+CREATE TABLE IF NOT EXISTS quarantined_readings (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    meter_serial VARCHAR(255) NOT NULL,
+    meter_id UUID REFERENCES meter_registry(id) ON DELETE SET NULL,
+    user_id UUID REFERENCES users(id) ON DELETE SET NULL,
+    kwh_amount DOUBLE PRECISION NOT NULL,
+    reading_timestamp TIMESTAMPTZ NOT NULL,
+    anomaly_type VARCHAR(50) NOT NULL,
+    reason TEXT NOT NULL,
+    payload JSONB NOT NULL,
+    review_status VARCHAR(20) NOT NULL DEFAULT 'pending',
+    reviewed_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    reviewed_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_quarantined_readings_pending
+    ON quarantined_readings(created_at)
+    WHERE review_status = 'pending';
+
+CREATE INDEX IF NOT EXISTS idx_quarantined_readings_meter
+    ON quarantined_readings(meter_serial, reading_timestamp);
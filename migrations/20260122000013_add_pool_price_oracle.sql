@@ -0,0 +1,22 @@
+-- AMM Pool Price Oracle
+-- Created: 2026-01-22
+-- Cumulative price accumulators on each pool, advanced on every reserve
+-- change, plus periodic snapshots used to compute time-weighted prices.
+
+-- Running sum of (spot price * seconds) since pool creation, one per direction.
+-- price_cumulative_a tracks token A priced in token B (reserve_b / reserve_a).
+ALTER TABLE liquidity_pools ADD COLUMN IF NOT EXISTS price_cumulative_a NUMERIC(48, 18) NOT NULL DEFAULT 0;
+ALTER TABLE liquidity_pools ADD COLUMN IF NOT EXISTS price_cumulative_b NUMERIC(48, 18) NOT NULL DEFAULT 0;
+ALTER TABLE liquidity_pools ADD COLUMN IF NOT EXISTS price_last_updated TIMESTAMPTZ NOT NULL DEFAULT NOW();
+
+-- Accumulator snapshots; a TWAP is the difference between two of them
+CREATE TABLE IF NOT EXISTS pool_price_observations (
+    id BIGSERIAL PRIMARY KEY,
+    pool_id UUID NOT NULL REFERENCES liquidity_pools(id) ON DELETE CASCADE,
+    price_cumulative_a NUMERIC(48, 18) NOT NULL,
+    price_cumulative_b NUMERIC(48, 18) NOT NULL,
+    observed_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_pool_price_observations_pool_time
+    ON pool_price_observations(pool_id, observed_at DESC);
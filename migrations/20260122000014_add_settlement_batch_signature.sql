@@ -0,0 +1,9 @@
+-- Settlement Batch Signature
+-- Created: 2026-01-22
+-- Settlements whose transfers were packed into one multi-transfer Solana
+-- transaction share its signature here, alongside transaction_hash.
+
+ALTER TABLE settlements ADD COLUMN IF NOT EXISTS batch_signature TEXT;
+
+CREATE INDEX IF NOT EXISTS idx_settlements_batch_signature
+    ON settlements(batch_signature) WHERE batch_signature IS NOT NULL;
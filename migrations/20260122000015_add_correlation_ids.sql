@@ -0,0 +1,15 @@
+-- Energy Correlation IDs
+-- Created: 2026-01-22
+-- A correlation id is assigned when a meter reading is ingested and follows
+-- it through minting, so the reading, its mint transaction and the on-chain
+-- confirmation can be stitched into one timeline.
+
+ALTER TABLE meter_readings ADD COLUMN IF NOT EXISTS correlation_id UUID;
+
+CREATE INDEX IF NOT EXISTS idx_meter_readings_correlation_id
+    ON meter_readings(correlation_id) WHERE correlation_id IS NOT NULL;
+
+ALTER TABLE blockchain_events ADD COLUMN IF NOT EXISTS correlation_id UUID;
+
+CREATE INDEX IF NOT EXISTS idx_blockchain_events_correlation_id
+    ON blockchain_events(correlation_id) WHERE correlation_id IS NOT NULL;
@@ -0,0 +1,20 @@
+-- Refresh Tokens
+-- Created: 2026-01-22
+-- Long-lived, per-device refresh tokens exchanged for short access tokens.
+-- Only a SHA-256 hash of each token is stored. Refreshing rotates the token:
+-- the old row is revoked and points at its replacement.
+
+CREATE TABLE IF NOT EXISTS refresh_tokens (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    token_hash TEXT NOT NULL UNIQUE,
+    device_id TEXT,
+    expires_at TIMESTAMPTZ NOT NULL,
+    revoked_at TIMESTAMPTZ,
+    replaced_by UUID REFERENCES refresh_tokens(id),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    last_used_at TIMESTAMPTZ
+);
+
+CREATE INDEX IF NOT EXISTS idx_refresh_tokens_user_active
+    ON refresh_tokens(user_id) WHERE revoked_at IS NULL;
@@ -0,0 +1,34 @@
+-- Settlement Dead Letters
+-- Created: 2026-01-22
+-- Settlements that failed with a non-retryable error are recorded here so
+-- admins can list them and replay them once the root cause is fixed.
+
+-- The failure path writes these already; the original schema lacked them
+ALTER TABLE settlements ADD COLUMN IF NOT EXISTS error_message TEXT;
+
+ALTER TABLE settlements DROP CONSTRAINT IF EXISTS chk_settlement_status;
+ALTER TABLE settlements ADD CONSTRAINT chk_settlement_status CHECK (status IN (
+    'pending', 'processing', 'completed', 'failed', 'permanently_failed',
+    'pending_bridge', 'bridging_initiated'
+));
+
+CREATE TABLE IF NOT EXISTS settlement_dead_letters (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    settlement_id UUID NOT NULL REFERENCES settlements(id) ON DELETE CASCADE,
+    error_message TEXT NOT NULL,
+    retry_count INTEGER NOT NULL DEFAULT 0,
+    failed_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    replayed_at TIMESTAMPTZ,
+    replayed_by UUID REFERENCES users(id)
+);
+
+-- At most one open dead letter per settlement
+CREATE UNIQUE INDEX IF NOT EXISTS idx_settlement_dead_letters_open
+    ON settlement_dead_letters(settlement_id) WHERE replayed_at IS NULL;
+
+-- Settlements that were already permanently failed before this table existed
+INSERT INTO settlement_dead_letters (settlement_id, error_message, retry_count, failed_at)
+SELECT id, COALESCE(error_message, 'unknown'), COALESCE(retry_count, 0), updated_at
+FROM settlements
+WHERE status = 'permanently_failed'
+ON CONFLICT DO NOTHING;
@@ -0,0 +1,17 @@
+-- Grid Zone Attributes
+-- Created: 2026-01-22
+-- Physical attributes of each zone's feeder, used to model transmission loss
+-- from distance and voltage instead of a flat per-pair value.
+
+CREATE TABLE IF NOT EXISTS grid_zone_attributes (
+    zone_id INTEGER PRIMARY KEY,
+    name VARCHAR(100),
+    -- Zone centroid, for the distance between zones
+    latitude DOUBLE PRECISION NOT NULL,
+    longitude DOUBLE PRECISION NOT NULL,
+    voltage_kv DECIMAL(8,2) NOT NULL CHECK (voltage_kv > 0),
+    line_type VARCHAR(20) NOT NULL DEFAULT 'overhead'
+        CHECK (line_type IN ('overhead', 'underground')),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
@@ -0,0 +1,10 @@
+-- Urgent Settlements
+-- Created: 2026-01-22
+-- Settlements flagged urgent are executed as soon as they are pending, even
+-- outside the configured settlement windows (SETTLEMENT_WINDOWS).
+
+ALTER TABLE settlements ADD COLUMN IF NOT EXISTS urgent BOOLEAN NOT NULL DEFAULT FALSE;
+
+CREATE INDEX IF NOT EXISTS idx_settlements_pending_urgent
+    ON settlements (created_at)
+    WHERE status = 'pending' AND urgent = TRUE;
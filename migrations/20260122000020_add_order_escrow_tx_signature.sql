@@ -0,0 +1,7 @@
+-- Order Escrow Signatures
+-- Created: 2026-01-22
+-- Signature of the transfer that locked an order's escrow on-chain. Orders
+-- without one hold their escrow only in the database, so unlocking them needs
+-- no on-chain refund and leaves refund_tx_signature NULL.
+
+ALTER TABLE trading_orders ADD COLUMN IF NOT EXISTS escrow_tx_signature VARCHAR(128);
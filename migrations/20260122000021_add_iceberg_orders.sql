@@ -0,0 +1,10 @@
+-- Iceberg Orders
+-- Created: 2026-01-22
+-- An iceberg order shows only display_quantity on the public book. When the
+-- shown slice fills, the next slice is revealed and queues behind the orders
+-- already resting at its price from display_refreshed_at.
+
+ALTER TABLE trading_orders
+    ADD COLUMN IF NOT EXISTS display_quantity NUMERIC(20, 8)
+        CHECK (display_quantity IS NULL OR display_quantity > 0),
+    ADD COLUMN IF NOT EXISTS display_refreshed_at TIMESTAMPTZ;
@@ -0,0 +1,9 @@
+-- Order Time In Force
+-- Created: 2026-01-22
+-- GTC orders rest until filled, cancelled or expired. IOC orders have their
+-- unfilled remainder cancelled after one matching pass; FOK orders are
+-- cancelled outright unless that pass fills them in full.
+
+ALTER TABLE trading_orders
+    ADD COLUMN IF NOT EXISTS time_in_force VARCHAR(3) NOT NULL DEFAULT 'GTC'
+        CHECK (time_in_force IN ('GTC', 'IOC', 'FOK'));
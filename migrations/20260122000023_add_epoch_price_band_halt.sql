@@ -0,0 +1,8 @@
+-- Epoch Price Band Halt
+-- Created: 2026-01-22
+-- Records when clearing for an epoch was halted because a match breached the
+-- price band, so later clearing runs for that epoch stop early.
+
+ALTER TABLE market_epochs
+    ADD COLUMN IF NOT EXISTS halted_at TIMESTAMPTZ,
+    ADD COLUMN IF NOT EXISTS halt_reason TEXT;
@@ -0,0 +1,14 @@
+-- Settlement Retry Overrides
+-- Created: 2026-01-22
+-- Error message patterns that override the built-in retry classification of
+-- failed settlements, so a new provider error format can be handled without
+-- a release. Loaded at startup and by the admin reload endpoint.
+
+CREATE TABLE IF NOT EXISTS settlement_retry_overrides (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    -- Matched case-insensitively as a substring of the error message
+    pattern TEXT NOT NULL UNIQUE CHECK (length(trim(pattern)) > 0),
+    retryable BOOLEAN NOT NULL,
+    note TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
@@ -0,0 +1,19 @@
+-- Platform Revenue Recipients
+-- Created: 2026-01-22
+-- Tags each revenue row with the party it is owed to, so components split
+-- between the treasury, the grid operator and the insurance fund can be
+-- reported per recipient. Existing rows all went to the treasury.
+
+ALTER TABLE platform_revenue
+ADD COLUMN IF NOT EXISTS recipient VARCHAR(32) NOT NULL DEFAULT 'platform_treasury';
+
+ALTER TABLE platform_revenue
+ADD CONSTRAINT chk_revenue_recipient CHECK (
+    recipient IN (
+        'platform_treasury',
+        'grid_operator',
+        'insurance_fund'
+    )
+);
+
+CREATE INDEX IF NOT EXISTS idx_platform_revenue_recipient ON platform_revenue (recipient, created_at);
@@ -0,0 +1,31 @@
+-- Treasury Sweeps
+-- Created: 2026-01-22
+-- On-chain payouts of recorded platform revenue to each recipient's wallet.
+-- A sweep claims the unswept revenue rows of one recipient before sending the
+-- transfer, so rows recorded meanwhile wait for the next sweep, and stamps
+-- them with the signature once it lands.
+
+CREATE TABLE IF NOT EXISTS treasury_sweeps (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    recipient VARCHAR(32) NOT NULL,
+    wallet_address VARCHAR(64) NOT NULL,
+    amount NUMERIC(20, 8) NOT NULL DEFAULT 0,
+    revenue_count INTEGER NOT NULL DEFAULT 0,
+    status VARCHAR(20) NOT NULL DEFAULT 'pending',
+    signature VARCHAR(128),
+    error_message TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    completed_at TIMESTAMPTZ,
+    CONSTRAINT chk_treasury_sweep_status CHECK (
+        status IN ('pending', 'completed', 'failed')
+    )
+);
+
+CREATE INDEX IF NOT EXISTS idx_treasury_sweeps_recipient ON treasury_sweeps (recipient, created_at DESC);
+
+ALTER TABLE platform_revenue
+ADD COLUMN IF NOT EXISTS sweep_id UUID REFERENCES treasury_sweeps (id),
+ADD COLUMN IF NOT EXISTS swept_at TIMESTAMPTZ,
+ADD COLUMN IF NOT EXISTS sweep_signature VARCHAR(128);
+
+CREATE INDEX IF NOT EXISTS idx_platform_revenue_unswept ON platform_revenue (recipient) WHERE sweep_id IS NULL;
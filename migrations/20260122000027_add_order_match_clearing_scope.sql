@@ -0,0 +1,13 @@
+-- Order Match Clearing Scope
+-- Created: 2026-01-22
+-- Whether a match stayed inside one grid zone or crossed zones, so zonal
+-- clearing can be audited. Matches recorded before this are left unset.
+
+ALTER TABLE order_matches
+ADD COLUMN IF NOT EXISTS clearing_scope VARCHAR(16);
+
+ALTER TABLE order_matches
+ADD CONSTRAINT chk_order_match_clearing_scope CHECK (
+    clearing_scope IS NULL
+    OR clearing_scope IN ('intra_zone', 'cross_zone')
+);
@@ -0,0 +1,39 @@
+-- Faucet Grants
+-- Created: 2026-01-22
+-- Audit trail of dev faucet requests, also used for its cooldown and daily
+-- caps. A grant is recorded as pending before anything is airdropped, so
+-- concurrent requests count against the caps, and a retry carrying the same
+-- client_request_id finds it instead of airdropping twice. Failed grants do
+-- not count and do not block a retry with the same key.
+
+CREATE TABLE IF NOT EXISTS faucet_grants (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    wallet_address VARCHAR(64) NOT NULL,
+    user_id UUID REFERENCES users (id),
+    client_request_id VARCHAR(64),
+    amount_sol NUMERIC(20, 9) NOT NULL DEFAULT 0,
+    mint_tokens_kwh NUMERIC(20, 9) NOT NULL DEFAULT 0,
+    deposit_fiat NUMERIC(20, 8) NOT NULL DEFAULT 0,
+    promote_to_role VARCHAR(32),
+    status VARCHAR(20) NOT NULL DEFAULT 'pending',
+    sol_tx_signature VARCHAR(128),
+    token_tx_signature VARCHAR(128),
+    message TEXT,
+    error_message TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    completed_at TIMESTAMPTZ,
+    CONSTRAINT chk_faucet_grant_status CHECK (
+        status IN ('pending', 'completed', 'failed')
+    )
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_faucet_grants_request_key
+ON faucet_grants (wallet_address, client_request_id)
+WHERE client_request_id IS NOT NULL AND status <> 'failed';
+
+CREATE INDEX IF NOT EXISTS idx_faucet_grants_recent ON faucet_grants (created_at DESC)
+WHERE status <> 'failed';
+
+CREATE INDEX IF NOT EXISTS idx_faucet_grants_user ON faucet_grants (user_id, created_at DESC);
+
+CREATE INDEX IF NOT EXISTS idx_faucet_grants_wallet ON faucet_grants (wallet_address, created_at DESC);
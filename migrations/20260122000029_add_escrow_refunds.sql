@@ -0,0 +1,21 @@
+-- On-chain Escrow Refunds
+-- Created: 2026-01-22
+-- How much of an order's escrow is still held on-chain, and one row per
+-- on-chain refund. Unlocks refund at most the remaining on-chain amount, so
+-- escrow raised only in the database (order edits) is never refunded on-chain,
+-- and each partial refund keeps its own signature.
+
+ALTER TABLE trading_orders
+ADD COLUMN IF NOT EXISTS onchain_escrow_amount NUMERIC(20, 8) NOT NULL DEFAULT 0;
+
+CREATE TABLE IF NOT EXISTS escrow_refunds (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    order_id UUID NOT NULL REFERENCES trading_orders (id),
+    user_id UUID NOT NULL REFERENCES users (id),
+    amount NUMERIC(20, 8) NOT NULL,
+    asset_type VARCHAR(20) NOT NULL,
+    signature VARCHAR(128) NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_escrow_refunds_order ON escrow_refunds (order_id, created_at);
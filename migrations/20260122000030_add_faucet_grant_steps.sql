@@ -0,0 +1,11 @@
+-- Faucet Grant Steps
+-- Created: 2026-01-22
+-- Record each step of a faucet grant as it succeeds. A grant that fails part
+-- way still counts the steps it performed against the caps, and a retry with
+-- the same client_request_id resumes it, skipping the steps already done.
+
+ALTER TABLE faucet_grants ADD COLUMN IF NOT EXISTS fiat_deposited_at TIMESTAMPTZ;
+ALTER TABLE faucet_grants ADD COLUMN IF NOT EXISTS role_promoted_at TIMESTAMPTZ;
+
+-- When the grant was created or last resumed
+ALTER TABLE faucet_grants ADD COLUMN IF NOT EXISTS updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW();
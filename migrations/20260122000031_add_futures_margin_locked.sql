@@ -0,0 +1,8 @@
+-- Futures Margin Locks
+-- Created: 2026-01-22
+-- Whether a position's margin_used is held in the owner's locked_amount.
+-- Positions opened before margin was locked have nothing to release when they
+-- close or are liquidated.
+
+ALTER TABLE futures_positions
+ADD COLUMN IF NOT EXISTS margin_locked BOOLEAN NOT NULL DEFAULT FALSE;
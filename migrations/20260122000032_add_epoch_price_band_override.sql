@@ -0,0 +1,9 @@
+-- Epoch Price Band Override
+-- Created: 2026-01-22
+-- Records an admin resuming clearing for an epoch halted by the price band.
+-- The band is no longer applied to an overridden epoch, so clearing does not
+-- halt again on the same match.
+
+ALTER TABLE market_epochs
+    ADD COLUMN IF NOT EXISTS price_band_overridden_at TIMESTAMPTZ,
+    ADD COLUMN IF NOT EXISTS price_band_overridden_by UUID REFERENCES users (id);
@@ -0,0 +1,6 @@
+-- Meter Readings Created-At Index
+-- Created: 2026-01-22
+-- The CO2-avoided tally reads only readings stored since its last pass rather
+-- than every reading; BRIN suits the append-only created_at.
+
+CREATE INDEX IF NOT EXISTS idx_meter_readings_created_at ON meter_readings USING BRIN (created_at);
@@ -3,7 +3,7 @@
 //! Minimal version for testing Simulator → Gateway → Anchor flow.
 
 use crate::auth::jwt::{ApiKeyService, JwtService};
-use crate::config::Config;
+use crate::config::{Config, ReloadableConfig, ValidatedSolanaPrograms};
 use crate::services;
 
 /// Application state shared across handlers.
@@ -17,10 +17,16 @@ pub struct AppState {
     pub redis: redis::Client,
     /// Application configuration
     pub config: Config,
+    /// Hot-reloadable subset of the configuration (reloaded on SIGHUP)
+    pub runtime_config: ReloadableConfig,
+    /// Solana program IDs parsed and validated at startup
+    pub solana_programs: ValidatedSolanaPrograms,
     /// JWT authentication service
     pub jwt_service: JwtService,
     /// API key authentication service
     pub api_key_service: ApiKeyService,
+    /// Per-user / per-IP request rate limiter
+    pub rate_limiter: crate::middleware::RateLimiter,
     /// Authentication service
     pub auth: services::AuthService,
     /// Optional email service
@@ -42,6 +48,8 @@ pub struct AppState {
     pub settlement: services::SettlementService,
     pub market_clearing_engine: services::OrderMatchingEngine,
     pub futures_service: services::FuturesService,
+    /// Constant product liquidity pools and token swaps
+    pub amm_service: services::AmmService,
     pub dashboard_service: services::DashboardService,
     pub event_processor: services::EventProcessorService,
     pub price_monitor: services::PriceMonitor,
@@ -51,7 +59,32 @@ pub struct AppState {
     pub erc_service: services::ErcService,
     pub notification_dispatcher: services::NotificationDispatcher,
     pub blockchain_task_service: services::BlockchainTaskService,
+    /// Anomaly screening for incoming meter readings
+    pub meter_reading_validator: services::validation::MeterReadingValidator,
+    /// DB versus on-chain balance reconciliation
+    pub reconciliation: services::ReconciliationService,
+    /// Zone-to-zone wheeling charges and loss factors, shared with matching
+    pub grid_topology: services::GridTopologyService,
+    /// Renewable mix and CO2-avoided accounting
+    pub sustainability: services::SustainabilityService,
+    /// Per-user balances, open exposure and settled flows
+    pub trading_account: services::TradingAccountService,
+    /// On-chain payout of recorded platform revenue
+    pub treasury: services::TreasuryService,
+    /// Authority wallet presence and SOL balance checks
+    pub authority_monitor: services::blockchain::AuthorityMonitor,
+    /// Heartbeats of the matching, settlement and event processor loops
+    pub task_heartbeats: services::TaskHeartbeats,
+    /// Decides whether this replica runs the singleton background jobs
+    pub job_coordinator: services::BackgroundJobCoordinator,
+    /// Meter reading export to InfluxDB; `None` when not configured
+    pub influx_writer: Option<services::InfluxWriter>,
     
+    /// Cancelled when the process starts shutting down
+    pub shutdown: tokio_util::sync::CancellationToken,
+    /// Background tasks that must finish their current unit of work before exit
+    pub background_tasks: tokio_util::task::TaskTracker,
+
     /// Prometheus metrics handle
     pub metrics_handle: metrics_exporter_prometheus::PrometheusHandle,
     /// HTTP Client for external requests (Simulator, etc.)
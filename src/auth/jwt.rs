@@ -1,70 +1,212 @@
-use jsonwebtoken::{decode, encode, Algorithm, DecodingKey, EncodingKey, Header, Validation};
+use chrono::{DateTime, Utc};
+use jsonwebtoken::{decode, decode_header, encode, Algorithm, DecodingKey, EncodingKey, Header, Validation};
+use serde::Deserialize;
+use std::collections::HashMap;
 use uuid::Uuid;
 use std::env;
 
 use crate::auth::Claims;
 use crate::error::{ApiError, Result};
+use crate::services::cache::{CacheKeys, CacheService};
+
+/// Key id used when no keyring is configured and tokens are signed with `JWT_SECRET`
+pub const DEFAULT_KID: &str = "default";
+
+/// One entry of the `JWT_KEYS` keyring
+#[derive(Debug, Clone, Deserialize)]
+pub struct JwtKeyConfig {
+    pub secret: String,
+    /// Tokens signed with this key are rejected from this time on
+    #[serde(default)]
+    pub retire_at: Option<DateTime<Utc>>,
+}
 
+#[derive(Clone)]
+struct VerificationKey {
+    decoding_key: DecodingKey,
+    retire_at: Option<DateTime<Utc>>,
+}
+
+impl VerificationKey {
+    fn is_retired(&self, now: DateTime<Utc>) -> bool {
+        self.retire_at.is_some_and(|retire_at| retire_at <= now)
+    }
+}
+
+/// Signs tokens with the current key and verifies them against a keyring
+///
+/// Tokens carry the signing key's id in their `kid` header, so the signing
+/// secret can be rotated by adding a new key, switching `JWT_CURRENT_KID` to
+/// it and giving the old key a `retire_at` past the longest token lifetime.
+/// Tokens issued before rotation have no `kid` and are checked against every
+/// key that has not been retired.
 #[derive(Clone)]
 pub struct JwtService {
+    current_kid: String,
     encoding_key: EncodingKey,
-    decoding_key: DecodingKey,
+    keys: HashMap<String, VerificationKey>,
     validation: Validation,
+    /// Where logged-out token ids are recorded; no revocation check when `None`
+    revocations: Option<CacheService>,
 }
 
 impl JwtService {
+    /// Load the keyring from `JWT_KEYS` (a JSON map of kid to
+    /// `{"secret": ..., "retire_at": ...}`) and sign with `JWT_CURRENT_KID`
+    ///
+    /// Without `JWT_KEYS`, `JWT_SECRET` is the only key, under [`DEFAULT_KID`].
     pub fn new() -> Result<Self> {
-        let secret = env::var("JWT_SECRET")
-            .map_err(|_| ApiError::Internal("JWT_SECRET environment variable not set".to_string()))?;
-        
-        let encoding_key = EncodingKey::from_secret(secret.as_ref());
-        let decoding_key = DecodingKey::from_secret(secret.as_ref());
-        
+        let keyring = match env::var("JWT_KEYS") {
+            Ok(json) => serde_json::from_str::<HashMap<String, JwtKeyConfig>>(&json)
+                .map_err(|e| ApiError::Internal(format!("Invalid JWT_KEYS: {}", e)))?,
+            Err(_) => {
+                let secret = env::var("JWT_SECRET")
+                    .map_err(|_| ApiError::Internal("JWT_SECRET environment variable not set".to_string()))?;
+                HashMap::from([(DEFAULT_KID.to_string(), JwtKeyConfig { secret, retire_at: None })])
+            }
+        };
+        let current_kid = env::var("JWT_CURRENT_KID").unwrap_or_else(|_| DEFAULT_KID.to_string());
+
+        Self::with_keyring(keyring, &current_kid)
+    }
+
+    pub fn with_keyring(keyring: HashMap<String, JwtKeyConfig>, current_kid: &str) -> Result<Self> {
+        let current = keyring.get(current_kid).ok_or_else(|| {
+            ApiError::Internal(format!("JWT signing key '{}' is not in the keyring", current_kid))
+        })?;
+        if current.retire_at.is_some_and(|retire_at| retire_at <= Utc::now()) {
+            return Err(ApiError::Internal(format!(
+                "JWT signing key '{}' is already retired",
+                current_kid
+            )));
+        }
+        let encoding_key = EncodingKey::from_secret(current.secret.as_ref());
+
+        let keys = keyring
+            .into_iter()
+            .map(|(kid, key)| {
+                let verification_key = VerificationKey {
+                    decoding_key: DecodingKey::from_secret(key.secret.as_ref()),
+                    retire_at: key.retire_at,
+                };
+                (kid, verification_key)
+            })
+            .collect();
+
         let mut validation = Validation::new(Algorithm::HS256);
         validation.set_issuer(&["api-gateway"]);
         validation.validate_exp = true;
-        
+
         Ok(Self {
+            current_kid: current_kid.to_string(),
             encoding_key,
-            decoding_key,
+            keys,
             validation,
+            revocations: None,
         })
     }
-    
+
+    /// Reject tokens whose `jti` has been revoked in the cache
+    pub fn with_revocations(mut self, cache: CacheService) -> Self {
+        self.revocations = Some(cache);
+        self
+    }
+
+    /// Key id new tokens are signed with
+    pub fn current_kid(&self) -> &str {
+        &self.current_kid
+    }
+
     pub fn encode_token(&self, claims: &Claims) -> Result<String> {
-        let header = Header::new(Algorithm::HS256);
-        
+        let mut header = Header::new(Algorithm::HS256);
+        header.kid = Some(self.current_kid.clone());
+
         encode(&header, claims, &self.encoding_key)
             .map_err(|e| ApiError::Internal(format!("Failed to encode JWT: {}", e)))
     }
-    
+
+    /// Decode a token and reject it if it has been revoked
+    ///
+    /// Use this to authenticate a request. Logged-out tokens stay denied until
+    /// they expire; a Redis outage lets them through rather than locking
+    /// everyone out.
+    pub async fn verify_token(&self, token: &str) -> Result<Claims> {
+        let claims = self.decode_token(token)?;
+        if let Some(cache) = &self.revocations {
+            if cache
+                .exists(&CacheKeys::revoked_token(&claims.jti))
+                .await
+                .unwrap_or(false)
+            {
+                return Err(ApiError::Unauthorized("Token has been revoked".to_string()));
+            }
+        }
+        Ok(claims)
+    }
+
+    /// Check signature, issuer, expiry and signing key only, not revocation
     pub fn decode_token(&self, token: &str) -> Result<Claims> {
-        let token_data = decode::<Claims>(token, &self.decoding_key, &self.validation)
-            .map_err(|e| match e.kind() {
-                jsonwebtoken::errors::ErrorKind::ExpiredSignature => {
-                    ApiError::Unauthorized("Token has expired".to_string())
-                }
-                jsonwebtoken::errors::ErrorKind::InvalidToken => {
-                    ApiError::Unauthorized("Invalid token".to_string())
+        let header = decode_header(token)
+            .map_err(|_| ApiError::Unauthorized("Invalid token".to_string()))?;
+        let now = Utc::now();
+
+        match header.kid {
+            Some(kid) => {
+                let key = self
+                    .keys
+                    .get(&kid)
+                    .ok_or_else(|| ApiError::Unauthorized("Unknown token signing key".to_string()))?;
+                if key.is_retired(now) {
+                    return Err(ApiError::Unauthorized(
+                        "Token signing key has been retired".to_string(),
+                    ));
                 }
-                jsonwebtoken::errors::ErrorKind::InvalidSignature => {
-                    ApiError::Unauthorized("Invalid token signature".to_string())
+                decode::<Claims>(token, &key.decoding_key, &self.validation)
+                    .map(|data| data.claims)
+                    .map_err(Self::decode_error)
+            }
+            None => {
+                let mut last_error = None;
+                for key in self.keys.values().filter(|key| !key.is_retired(now)) {
+                    match decode::<Claims>(token, &key.decoding_key, &self.validation) {
+                        Ok(data) => return Ok(data.claims),
+                        Err(e) if matches!(e.kind(), jsonwebtoken::errors::ErrorKind::InvalidSignature) => {
+                            last_error = Some(e)
+                        }
+                        Err(e) => return Err(Self::decode_error(e)),
+                    }
                 }
-                _ => ApiError::Internal(format!("JWT decode error: {}", e)),
-            })?;
-        
-        Ok(token_data.claims)
+                Err(last_error
+                    .map(Self::decode_error)
+                    .unwrap_or_else(|| ApiError::Unauthorized("Invalid token signature".to_string())))
+            }
+        }
+    }
+
+    fn decode_error(e: jsonwebtoken::errors::Error) -> ApiError {
+        match e.kind() {
+            jsonwebtoken::errors::ErrorKind::ExpiredSignature => {
+                ApiError::Unauthorized("Token has expired".to_string())
+            }
+            jsonwebtoken::errors::ErrorKind::InvalidToken => {
+                ApiError::Unauthorized("Invalid token".to_string())
+            }
+            jsonwebtoken::errors::ErrorKind::InvalidSignature => {
+                ApiError::Unauthorized("Invalid token signature".to_string())
+            }
+            _ => ApiError::Internal(format!("JWT decode error: {}", e)),
+        }
     }
     
-    pub fn validate_token(&self, token: &str) -> Result<bool> {
-        match self.decode_token(token) {
+    pub async fn validate_token(&self, token: &str) -> Result<bool> {
+        match self.verify_token(token).await {
             Ok(claims) => Ok(!claims.is_expired()),
             Err(_) => Ok(false),
         }
     }
     
-    pub fn refresh_token(&self, old_token: &str) -> Result<String> {
-        let claims = self.decode_token(old_token)?;
+    pub async fn refresh_token(&self, old_token: &str) -> Result<String> {
+        let claims = self.verify_token(old_token).await?;
         
         // Create new claims with extended expiration
         let new_claims = Claims::new(
@@ -145,6 +287,80 @@ mod tests {
         assert_eq!(claims.role, decoded_claims.role);
     }
     
+    fn keyring(keys: &[(&str, &str, Option<DateTime<Utc>>)]) -> HashMap<String, JwtKeyConfig> {
+        keys.iter()
+            .map(|(kid, secret, retire_at)| {
+                let key = JwtKeyConfig {
+                    secret: secret.to_string(),
+                    retire_at: *retire_at,
+                };
+                (kid.to_string(), key)
+            })
+            .collect()
+    }
+
+    fn claims() -> Claims {
+        Claims::new(Uuid::new_v4(), "test_user".to_string(), "user".to_string())
+    }
+
+    #[test]
+    fn test_rotated_key_still_verifies_old_tokens() {
+        let old = JwtService::with_keyring(keyring(&[("k1", "first_secret_0123456789", None)]), "k1").unwrap();
+        let token = old.encode_token(&claims()).unwrap();
+        assert_eq!(decode_header(&token).unwrap().kid.as_deref(), Some("k1"));
+
+        let rotated = JwtService::with_keyring(
+            keyring(&[
+                ("k1", "first_secret_0123456789", None),
+                ("k2", "second_secret_0123456789", None),
+            ]),
+            "k2",
+        )
+        .unwrap();
+        assert!(rotated.decode_token(&token).is_ok());
+        let new_token = rotated.encode_token(&claims()).unwrap();
+        assert_eq!(decode_header(&new_token).unwrap().kid.as_deref(), Some("k2"));
+        assert!(old.decode_token(&new_token).is_err());
+    }
+
+    #[test]
+    fn test_retired_key_is_rejected() {
+        let old = JwtService::with_keyring(keyring(&[("k1", "first_secret_0123456789", None)]), "k1").unwrap();
+        let token = old.encode_token(&claims()).unwrap();
+
+        let retired_at = Utc::now() - chrono::Duration::minutes(1);
+        let rotated = JwtService::with_keyring(
+            keyring(&[
+                ("k1", "first_secret_0123456789", Some(retired_at)),
+                ("k2", "second_secret_0123456789", None),
+            ]),
+            "k2",
+        )
+        .unwrap();
+        assert!(rotated.decode_token(&token).is_err());
+        assert!(JwtService::with_keyring(keyring(&[("k1", "first_secret_0123456789", Some(retired_at))]), "k1").is_err());
+    }
+
+    #[test]
+    fn test_token_without_kid_checks_active_keys() {
+        let legacy = encode(
+            &Header::new(Algorithm::HS256),
+            &claims(),
+            &EncodingKey::from_secret(b"first_secret_0123456789"),
+        )
+        .unwrap();
+
+        let service = JwtService::with_keyring(
+            keyring(&[
+                ("k1", "first_secret_0123456789", None),
+                ("k2", "second_secret_0123456789", None),
+            ]),
+            "k2",
+        )
+        .unwrap();
+        assert!(service.decode_token(&legacy).is_ok());
+    }
+
     #[test]
     fn test_api_key_generation() {
         setup_test_env();
@@ -138,10 +138,10 @@ pub async fn auth_middleware(
         request.extensions_mut().insert(claims);
         return next.run(request).await;
     }
-    // Try JWT decoding if API key didn't match
-
-
-    match state.jwt_service.decode_token(token) {
+    // Try JWT decoding if API key didn't match; the verification key is
+    // picked from the keyring by the token's `kid` header, and logged-out
+    // tokens are rejected
+    match state.jwt_service.verify_token(token).await {
         Ok(claims) => {
             info!("🔓 JWT authenticated: {} (user_id: {})", claims.username, claims.sub);
             // Add claims to request extensions for use in handlers
@@ -150,7 +150,7 @@ pub async fn auth_middleware(
         }
         Err(_) => Response::builder()
             .status(StatusCode::UNAUTHORIZED)
-            .body(Body::from("Invalid or expired token"))
+            .body(Body::from("Invalid, expired or revoked token"))
             .unwrap_or_else(|_| Response::new(Body::from("Unauthorized"))),
     }
 }
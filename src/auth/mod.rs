@@ -22,6 +22,9 @@ pub struct Claims {
     pub exp: i64,           // Expiration time
     pub iat: i64,           // Issued at
     pub iss: String,        // Issuer
+    /// Token id, used to revoke this token before it expires
+    #[serde(default)]
+    pub jti: Uuid,
 }
 
 impl Claims {
@@ -36,6 +39,7 @@ impl Claims {
             exp: exp.timestamp(),
             iat: now.timestamp(),
             iss: "api-gateway".to_string(),
+            jti: Uuid::new_v4(),
         }
     }
     
@@ -103,10 +103,9 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!("Buyer ATA: {}", buyer_ata);
     
     // Amount
-    // Logic: energy_amount * 1_000_000_000
-    let amount_decimal = settlement.energy_amount * rust_decimal::Decimal::from(1_000_000_000);
-    let amount_u64 = amount_decimal.trunc().to_string().parse::<u64>()?;
-    println!("Transfer Amount: {}", amount_u64);
+    let decimals = blockchain.decimals_for(&mint).await?;
+    let amount_u64 = api_gateway::utils::units::to_atomic(settlement.energy_amount, decimals)?;
+    println!("Transfer Amount: {} ({} decimals)", amount_u64, decimals);
     
     // Transfer
     let sig = blockchain.transfer_tokens(
@@ -115,7 +114,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         &buyer_ata,
         &mint,
         amount_u64,
-        9
+        decimals
     ).await?;
     
     println!("Transfer Success! Sig: {}", sig);
@@ -0,0 +1,275 @@
+//! CORS origin matching and allowed methods/headers
+//!
+//! `CORS_ALLOWED_ORIGINS` entries match a request `Origin` exactly, or, when
+//! written as `*.example.com` or `https://*.example.com`, match any subdomain
+//! of `example.com` (not `example.com` itself). A leading `*.` label is the
+//! only wildcard accepted; anything else is an exact origin.
+//!
+//! `CORS_ALLOWED_METHODS` and `CORS_ALLOWED_HEADERS` replace the built-in
+//! lists. Unlike the origins they are read once at startup. Neither accepts
+//! `*`: the layer allows credentials, which a wildcard cannot be combined
+//! with, so every method and header has to be listed.
+
+use std::str::FromStr;
+
+use axum::http::{HeaderName, Method};
+use tracing::warn;
+
+pub const DEFAULT_CORS_ALLOWED_METHODS: &str = "GET,POST,PUT,PATCH,DELETE,OPTIONS";
+pub const DEFAULT_CORS_ALLOWED_HEADERS: &str = "authorization,content-type,accept";
+
+/// One `CORS_ALLOWED_ORIGINS` entry
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OriginRule {
+    /// Matches this origin only
+    Exact(String),
+    /// Matches `scheme://<label>.<domain>`; any scheme when `scheme` is `None`
+    Subdomain {
+        scheme: Option<String>,
+        domain: String,
+    },
+}
+
+impl OriginRule {
+    /// Whether a request `Origin` header value satisfies this rule
+    pub fn matches(&self, origin: &str) -> bool {
+        let origin = origin.trim_end_matches('/');
+        match self {
+            Self::Exact(allowed) => origin.eq_ignore_ascii_case(allowed),
+            Self::Subdomain { scheme, domain } => {
+                let Some((origin_scheme, host)) = origin.split_once("://") else {
+                    return false;
+                };
+                if scheme.as_deref().is_some_and(|s| !s.eq_ignore_ascii_case(origin_scheme)) {
+                    return false;
+                }
+                let host = host.to_ascii_lowercase();
+                match host.strip_suffix(domain.as_str()).and_then(|rest| rest.strip_suffix('.')) {
+                    Some(subdomain) => {
+                        !subdomain.is_empty() && !subdomain.contains(['/', ':', '@'])
+                    }
+                    None => false,
+                }
+            }
+        }
+    }
+}
+
+impl FromStr for OriginRule {
+    type Err = String;
+
+    fn from_str(entry: &str) -> Result<Self, Self::Err> {
+        let entry = entry.trim().trim_end_matches('/');
+        if entry.is_empty() {
+            return Err("empty origin".to_string());
+        }
+
+        let (scheme, rest) = match entry.split_once("://") {
+            Some((scheme, rest)) => (Some(scheme), rest),
+            None => (None, entry),
+        };
+
+        if let Some(domain) = rest.strip_prefix("*.") {
+            if domain.is_empty() || domain.contains('*') || domain.contains('/') {
+                return Err(format!("invalid wildcard origin '{}'", entry));
+            }
+            return Ok(Self::Subdomain {
+                scheme: scheme.map(str::to_ascii_lowercase),
+                domain: domain.to_ascii_lowercase(),
+            });
+        }
+
+        if entry.contains('*') {
+            return Err(format!(
+                "'{}' has a wildcard outside a leading '*.' label",
+                entry
+            ));
+        }
+        if scheme.is_none() {
+            return Err(format!("origin '{}' needs a scheme, e.g. https://{}", entry, entry));
+        }
+        Ok(Self::Exact(entry.to_string()))
+    }
+}
+
+/// Parsed `CORS_ALLOWED_ORIGINS`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct OriginRules(pub Vec<OriginRule>);
+
+impl OriginRules {
+    /// Parse `CORS_ALLOWED_ORIGINS`, dropping invalid entries with a warning
+    ///
+    /// Used on reload, where failing would leave the old list in place
+    /// without notice; startup validation rejects invalid entries outright.
+    pub fn parse_lenient(value: &str) -> Self {
+        Self(
+            split_list(value)
+                .filter_map(|entry| match entry.parse::<OriginRule>() {
+                    Ok(rule) => Some(rule),
+                    Err(e) => {
+                        warn!("⚠️ Ignoring CORS origin: {}", e);
+                        None
+                    }
+                })
+                .collect(),
+        )
+    }
+
+    pub fn is_allowed(&self, origin: &str) -> bool {
+        self.0.iter().any(|rule| rule.matches(origin))
+    }
+}
+
+impl FromStr for OriginRules {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        split_list(value)
+            .map(OriginRule::from_str)
+            .collect::<Result<Vec<_>, _>>()
+            .map(Self)
+    }
+}
+
+/// Parsed `CORS_ALLOWED_METHODS`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CorsMethods(pub Vec<Method>);
+
+impl FromStr for CorsMethods {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let methods = split_list(value)
+            .map(|method| {
+                reject_wildcard(method, "methods")?;
+                Method::from_bytes(method.to_ascii_uppercase().as_bytes())
+                    .map_err(|_| format!("invalid HTTP method '{}'", method))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        if methods.is_empty() {
+            return Err("no methods given".to_string());
+        }
+        Ok(Self(methods))
+    }
+}
+
+impl CorsMethods {
+    /// Read `CORS_ALLOWED_METHODS`, keeping the defaults when unset or invalid
+    ///
+    /// Invalid values are reported by config validation before this runs.
+    pub fn from_env() -> Self {
+        from_env_or_default("CORS_ALLOWED_METHODS", DEFAULT_CORS_ALLOWED_METHODS)
+    }
+}
+
+/// Parsed `CORS_ALLOWED_HEADERS`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CorsHeaders(pub Vec<HeaderName>);
+
+impl FromStr for CorsHeaders {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let headers = split_list(value)
+            .map(|header| {
+                reject_wildcard(header, "headers")?;
+                HeaderName::from_bytes(header.to_ascii_lowercase().as_bytes())
+                    .map_err(|_| format!("invalid header name '{}'", header))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        if headers.is_empty() {
+            return Err("no headers given".to_string());
+        }
+        Ok(Self(headers))
+    }
+}
+
+impl CorsHeaders {
+    /// Read `CORS_ALLOWED_HEADERS`, keeping the defaults when unset or invalid
+    pub fn from_env() -> Self {
+        from_env_or_default("CORS_ALLOWED_HEADERS", DEFAULT_CORS_ALLOWED_HEADERS)
+    }
+}
+
+fn from_env_or_default<T>(var: &str, default: &str) -> T
+where
+    T: FromStr<Err = String>,
+{
+    let parse_default = || default.parse::<T>().unwrap_or_else(|e| panic!("invalid default {}: {}", var, e));
+    match std::env::var(var) {
+        Ok(value) => value.parse::<T>().unwrap_or_else(|e| {
+            tracing::error!("Invalid {}, using the defaults: {}", var, e);
+            parse_default()
+        }),
+        Err(_) => parse_default(),
+    }
+}
+
+/// `*` parses as a method and as a header name, but is not usable with
+/// credentialed CORS
+fn reject_wildcard(item: &str, kind: &str) -> Result<(), String> {
+    if item == "*" {
+        return Err(format!(
+            "'*' is not supported for allowed {} because credentials are allowed; list them explicitly",
+            kind
+        ));
+    }
+    Ok(())
+}
+
+fn split_list(value: &str) -> impl Iterator<Item = &str> {
+    value.split(',').map(str::trim).filter(|item| !item.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rules(spec: &str) -> OriginRules {
+        spec.parse().unwrap()
+    }
+
+    #[test]
+    fn test_exact_origins_do_not_match_by_prefix() {
+        let rules = rules("https://gridtokenx.com,http://localhost:3000");
+        assert!(rules.is_allowed("https://gridtokenx.com"));
+        assert!(rules.is_allowed("http://localhost:3000"));
+        assert!(!rules.is_allowed("https://gridtokenx.com.attacker.io"));
+        assert!(!rules.is_allowed("http://localhost:30001"));
+        assert!(!rules.is_allowed("https://evil.com"));
+        assert!(!rules.is_allowed(""));
+    }
+
+    #[test]
+    fn test_wildcard_matches_subdomains_only() {
+        let rules = rules("https://*.gridtokenx.com,*.example.org");
+        assert!(rules.is_allowed("https://app.gridtokenx.com"));
+        assert!(rules.is_allowed("https://a.b.gridtokenx.com"));
+        assert!(!rules.is_allowed("https://gridtokenx.com"));
+        assert!(!rules.is_allowed("http://app.gridtokenx.com"));
+        assert!(!rules.is_allowed("https://evilgridtokenx.com"));
+        assert!(!rules.is_allowed("https://app.gridtokenx.com.attacker.io"));
+        assert!(!rules.is_allowed("https://app.gridtokenx.com:8443"));
+
+        assert!(rules.is_allowed("http://docs.example.org"));
+        assert!(rules.is_allowed("https://docs.example.org"));
+    }
+
+    #[test]
+    fn test_invalid_entries_are_rejected() {
+        assert!("https://app.*.gridtokenx.com".parse::<OriginRules>().is_err());
+        assert!("*".parse::<OriginRules>().is_err());
+        assert!("gridtokenx.com".parse::<OriginRules>().is_err());
+
+        assert_eq!(
+            "get, Post".parse::<CorsMethods>().unwrap(),
+            CorsMethods(vec![Method::GET, Method::POST])
+        );
+        assert!("GET,NOT A METHOD".parse::<CorsMethods>().is_err());
+        assert!("x-trace-id,bad header".parse::<CorsHeaders>().is_err());
+
+        let wildcard = "GET,*".parse::<CorsMethods>().unwrap_err();
+        assert!(wildcard.contains("'*' is not supported"), "{}", wildcard);
+        assert!("*".parse::<CorsHeaders>().is_err());
+    }
+}
@@ -1,10 +1,18 @@
 use anyhow::Result;
+use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
+use solana_sdk::pubkey::Pubkey;
+use std::collections::HashMap;
 use std::env;
+use std::str::FromStr;
 
+pub mod cors;
+pub mod reloadable;
 pub mod tokenization;
-pub use tokenization::{TokenizationConfig, ValidationError};
-// Removed unused imports: ConfigError
+mod validation;
+pub use reloadable::{ReloadableConfig, RuntimeSettings};
+pub use tokenization::{ConfigError, TokenizationConfig, ValidationError};
+pub use validation::format_config_errors;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Config {
@@ -24,6 +32,9 @@ pub struct Config {
     pub request_timeout: u64,
     pub rate_limit_window: u64,
     pub log_level: String,
+    /// Log line format; read before the rest of the config to set up logging
+    #[serde(default)]
+    pub log_format: LogFormat,
     pub audit_log_enabled: bool,
     pub test_mode: bool,
     pub email: EmailConfig,
@@ -36,6 +47,32 @@ pub struct Config {
     pub cors_allowed_origins: Vec<String>,
     pub currency_token_mint: String,
     pub currency_decimals: u8,
+    /// Token decimals per mint address, from `MINT_DECIMALS`; mints not
+    /// listed are read from chain
+    pub mint_decimals: HashMap<String, u8>,
+    /// Largest request body accepted by any route, in bytes
+    pub max_request_body_bytes: usize,
+    /// Most readings accepted in one batch submission
+    pub max_batch_readings: usize,
+    /// Lifetime of an order placed without an expiry time, in hours
+    pub order_default_expiry_hours: i64,
+    /// Longest lifetime an order may request; later expiries are clamped, in hours
+    pub order_max_expiry_hours: i64,
+    /// How often zone tariffs are re-read from `zone_rates`, in seconds
+    pub zone_rates_refresh_interval_secs: u64,
+    /// How often the cached dashboard metrics are recomputed, in seconds
+    pub dashboard_metrics_interval_secs: u64,
+    /// How often futures positions are marked and checked for liquidation, in seconds
+    pub futures_mark_price_interval_secs: u64,
+    pub grid_loss: GridLossConfig,
+    pub rpc_proxy: RpcProxyConfig,
+    pub emission_factors: EmissionFactors,
+    pub influx: InfluxConfig,
+    pub price_band: PriceBandConfig,
+    pub leader_election: LeaderElectionConfig,
+    pub authority_funding: AuthorityFundingConfig,
+    pub treasury: TreasuryConfig,
+    pub faucet: FaucetConfig,
 }
 
 /// Solana program IDs configuration - moved from hardcoded values
@@ -60,6 +97,46 @@ impl Default for SolanaProgramsConfig {
     }
 }
 
+impl SolanaProgramsConfig {
+    /// Parse every program id, reporting all invalid ones at once
+    pub fn validated(&self) -> Result<ValidatedSolanaPrograms, Vec<ConfigError>> {
+        let mut errors = Vec::new();
+        let mut parse = |var: &str, value: &str| {
+            Pubkey::from_str(value).unwrap_or_else(|_| {
+                errors.push(ConfigError::InvalidPubkey {
+                    var: var.to_string(),
+                    value: value.to_string(),
+                });
+                Pubkey::default()
+            })
+        };
+
+        let programs = ValidatedSolanaPrograms {
+            registry: parse("SOLANA_REGISTRY_PROGRAM_ID", &self.registry_program_id),
+            oracle: parse("SOLANA_ORACLE_PROGRAM_ID", &self.oracle_program_id),
+            governance: parse("SOLANA_GOVERNANCE_PROGRAM_ID", &self.governance_program_id),
+            energy_token: parse("SOLANA_ENERGY_TOKEN_PROGRAM_ID", &self.energy_token_program_id),
+            trading: parse("SOLANA_TRADING_PROGRAM_ID", &self.trading_program_id),
+        };
+
+        if errors.is_empty() {
+            Ok(programs)
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+/// Solana program IDs parsed once at startup
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ValidatedSolanaPrograms {
+    pub registry: Pubkey,
+    pub oracle: Pubkey,
+    pub governance: Pubkey,
+    pub energy_token: Pubkey,
+    pub trading: Pubkey,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct EventProcessorConfig {
     pub enabled: bool,
@@ -68,6 +145,454 @@ pub struct EventProcessorConfig {
     pub max_retries: u32,
     pub webhook_url: Option<String>,
     pub webhook_secret: Option<String>,
+    /// How often queued webhook deliveries are sent or retried
+    pub webhook_delivery_interval_secs: u64,
+}
+
+/// Emission factors for CO2-avoided accounting, in kg CO2 per kWh
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EmissionFactors {
+    /// Grid mix displaced by local renewable generation
+    pub grid: f64,
+    /// Lifecycle factor per renewable source, keyed by lowercase meter type;
+    /// sources not listed are not counted as renewable
+    pub sources: HashMap<String, f64>,
+}
+
+/// Development JWT secret that must never sign tokens outside development
+pub(crate) const INSECURE_DEFAULT_JWT_SECRET: &str = "supersecretjwtkey";
+
+const DEFAULT_GRID_EMISSION_FACTOR: f64 = 0.431; // Thailand grid average
+const DEFAULT_RENEWABLE_EMISSION_FACTORS: &str = "solar:0.041,wind:0.011,hydro:0.024,biomass:0.230";
+
+impl EmissionFactors {
+    fn from_env() -> Result<Self> {
+        Ok(Self {
+            grid: env::var("GRID_EMISSION_FACTOR")
+                .unwrap_or_else(|_| DEFAULT_GRID_EMISSION_FACTOR.to_string())
+                .parse()
+                .map_err(|e| anyhow::anyhow!("Invalid GRID_EMISSION_FACTOR: {}", e))?,
+            sources: parse_emission_factors(
+                &env::var("RENEWABLE_EMISSION_FACTORS")
+                    .unwrap_or_else(|_| DEFAULT_RENEWABLE_EMISSION_FACTORS.to_string()),
+            )
+            .map_err(|e| anyhow::anyhow!("Invalid RENEWABLE_EMISSION_FACTORS: {}", e))?,
+        })
+    }
+
+    /// CO2 avoided per kWh generated by `source`, or `None` if the source is
+    /// not renewable
+    pub fn avoided_per_kwh(&self, source: &str) -> Option<f64> {
+        self.sources
+            .get(&source.to_lowercase())
+            .map(|factor| (self.grid - factor).max(0.0))
+    }
+}
+
+impl Default for EmissionFactors {
+    fn default() -> Self {
+        Self {
+            grid: DEFAULT_GRID_EMISSION_FACTOR,
+            sources: parse_emission_factors(DEFAULT_RENEWABLE_EMISSION_FACTORS)
+                .expect("default emission factors parse"),
+        }
+    }
+}
+
+/// Export of meter readings to InfluxDB as time-series points
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InfluxConfig {
+    /// False when `INFLUXDB_ENABLED=false` or no token is configured
+    pub enabled: bool,
+    pub url: String,
+    pub token: String,
+    pub org: String,
+    pub bucket: String,
+    /// Points per write request; a full batch is written without waiting
+    pub batch_size: usize,
+    /// Longest a point waits in a partial batch
+    pub flush_interval_ms: u64,
+    /// Retries of a failed write before its batch is dropped
+    pub max_retries: u32,
+}
+
+impl InfluxConfig {
+    fn from_env() -> Result<Self> {
+        let token = env::var("INFLUXDB_TOKEN").unwrap_or_default().trim().to_string();
+        let enabled: bool = env::var("INFLUXDB_ENABLED")
+            .unwrap_or_else(|_| "true".to_string())
+            .parse()
+            .map_err(|e| anyhow::anyhow!("Invalid INFLUXDB_ENABLED: {}", e))?;
+
+        Ok(Self {
+            enabled: enabled && !token.is_empty(),
+            url: env::var("INFLUXDB_URL")
+                .unwrap_or_else(|_| "http://localhost:8086".to_string())
+                .trim_end_matches('/')
+                .to_string(),
+            token,
+            org: env::var("INFLUXDB_ORG").unwrap_or_else(|_| "gridtokenx".to_string()),
+            bucket: env::var("INFLUXDB_BUCKET").unwrap_or_else(|_| "energy_readings".to_string()),
+            batch_size: env::var("INFLUXDB_BATCH_SIZE")
+                .unwrap_or_else(|_| "500".to_string())
+                .parse()
+                .map_err(|e| anyhow::anyhow!("Invalid INFLUXDB_BATCH_SIZE: {}", e))?,
+            flush_interval_ms: env::var("INFLUXDB_FLUSH_INTERVAL_MS")
+                .unwrap_or_else(|_| "1000".to_string())
+                .parse()
+                .map_err(|e| anyhow::anyhow!("Invalid INFLUXDB_FLUSH_INTERVAL_MS: {}", e))?,
+            max_retries: env::var("INFLUXDB_MAX_RETRIES")
+                .unwrap_or_else(|_| "3".to_string())
+                .parse()
+                .map_err(|e| anyhow::anyhow!("Invalid INFLUXDB_MAX_RETRIES: {}", e))?,
+        })
+    }
+}
+
+/// Redis lease deciding which replica runs the singleton background jobs
+///
+/// Matching, settlement and the other jobs that must not run twice only run
+/// on the replica holding the lease; HTTP is served by every replica.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LeaderElectionConfig {
+    /// When false every replica runs the jobs; only safe with one replica
+    pub enabled: bool,
+    /// Name of this replica in the lease and logs
+    pub instance_id: String,
+    /// How long the lease lasts without renewal, i.e. the failover delay
+    pub lease_ttl_ms: u64,
+    /// How often the holder renews, and followers try to take over
+    pub renew_interval_ms: u64,
+}
+
+impl LeaderElectionConfig {
+    fn from_env() -> Result<Self> {
+        let instance_id = env::var("INSTANCE_ID")
+            .or_else(|_| env::var("HOSTNAME"))
+            .map(|name| name.trim().to_string())
+            .ok()
+            .filter(|name| !name.is_empty())
+            .unwrap_or_else(|| "gateway".to_string());
+
+        Ok(Self {
+            enabled: env::var("LEADER_ELECTION_ENABLED")
+                .unwrap_or_else(|_| "true".to_string())
+                .parse()
+                .map_err(|e| anyhow::anyhow!("Invalid LEADER_ELECTION_ENABLED: {}", e))?,
+            // Unique per process even when replicas share a hostname
+            instance_id: format!("{}-{}", instance_id, &uuid::Uuid::new_v4().simple().to_string()[..8]),
+            lease_ttl_ms: env::var("LEADER_LEASE_TTL_MS")
+                .unwrap_or_else(|_| "15000".to_string())
+                .parse()
+                .map_err(|e| anyhow::anyhow!("Invalid LEADER_LEASE_TTL_MS: {}", e))?,
+            renew_interval_ms: env::var("LEADER_RENEW_INTERVAL_MS")
+                .unwrap_or_else(|_| "5000".to_string())
+                .parse()
+                .map_err(|e| anyhow::anyhow!("Invalid LEADER_RENEW_INTERVAL_MS: {}", e))?,
+        })
+    }
+}
+
+/// SOL balance the authority wallet must keep
+///
+/// The authority pays transaction fees and token account rent for every
+/// settlement, so settlement stalls once it runs dry.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuthorityFundingConfig {
+    /// Below this the gateway reports not ready
+    pub min_balance_sol: f64,
+    /// Below this the balance is reported low, ahead of running out
+    pub low_balance_sol: f64,
+    /// Seconds between balance checks
+    pub check_interval_secs: u64,
+}
+
+impl AuthorityFundingConfig {
+    fn from_env() -> Result<Self> {
+        Ok(Self {
+            min_balance_sol: env::var("AUTHORITY_MIN_BALANCE_SOL")
+                .unwrap_or_else(|_| "0.05".to_string())
+                .parse()
+                .map_err(|e| anyhow::anyhow!("Invalid AUTHORITY_MIN_BALANCE_SOL: {}", e))?,
+            low_balance_sol: env::var("AUTHORITY_LOW_BALANCE_SOL")
+                .unwrap_or_else(|_| "1".to_string())
+                .parse()
+                .map_err(|e| anyhow::anyhow!("Invalid AUTHORITY_LOW_BALANCE_SOL: {}", e))?,
+            check_interval_secs: env::var("AUTHORITY_BALANCE_CHECK_INTERVAL_SECS")
+                .unwrap_or_else(|_| "60".to_string())
+                .parse()
+                .map_err(|e| anyhow::anyhow!("Invalid AUTHORITY_BALANCE_CHECK_INTERVAL_SECS: {}", e))?,
+        })
+    }
+}
+
+/// Periodic on-chain sweep of recorded platform revenue
+///
+/// Revenue owed to each recipient is paid in currency tokens from the
+/// platform authority to that recipient's wallet. Recipients without a wallet
+/// accumulate until one is configured.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TreasuryConfig {
+    /// Run the scheduled sweep; the admin trigger works regardless
+    pub sweep_enabled: bool,
+    /// Seconds between scheduled sweeps
+    pub sweep_interval_secs: u64,
+    /// Unswept revenue below this stays for a later sweep
+    pub min_sweep_amount: Decimal,
+    pub platform_treasury_wallet: Option<String>,
+    pub grid_operator_wallet: Option<String>,
+    pub insurance_fund_wallet: Option<String>,
+}
+
+impl TreasuryConfig {
+    fn from_env() -> Result<Self> {
+        let wallet = |var: &str| env::var(var).ok().map(|v| v.trim().to_string()).filter(|v| !v.is_empty());
+
+        Ok(Self {
+            sweep_enabled: env::var("TREASURY_SWEEP_ENABLED")
+                .unwrap_or_else(|_| "false".to_string())
+                .parse()
+                .map_err(|e| anyhow::anyhow!("Invalid TREASURY_SWEEP_ENABLED: {}", e))?,
+            sweep_interval_secs: env::var("TREASURY_SWEEP_INTERVAL_SECS")
+                .unwrap_or_else(|_| "86400".to_string())
+                .parse()
+                .map_err(|e| anyhow::anyhow!("Invalid TREASURY_SWEEP_INTERVAL_SECS: {}", e))?,
+            min_sweep_amount: env::var("TREASURY_SWEEP_MIN_AMOUNT")
+                .unwrap_or_else(|_| "0".to_string())
+                .parse()
+                .map_err(|e| anyhow::anyhow!("Invalid TREASURY_SWEEP_MIN_AMOUNT: {}", e))?,
+            platform_treasury_wallet: wallet("TREASURY_WALLET_PLATFORM_TREASURY"),
+            grid_operator_wallet: wallet("TREASURY_WALLET_GRID_OPERATOR"),
+            insurance_fund_wallet: wallet("TREASURY_WALLET_INSURANCE_FUND"),
+        })
+    }
+}
+
+/// Limits on the dev faucet
+///
+/// Caps apply over a rolling 24 hours to granted SOL and minted kWh tokens,
+/// per recipient (the wallet, or every wallet of the user it is linked to)
+/// and across all recipients.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FaucetConfig {
+    /// Seconds a recipient waits between grants; zero disables the cooldown
+    pub cooldown_secs: u64,
+    pub daily_sol_per_user: Decimal,
+    pub daily_kwh_per_user: Decimal,
+    pub daily_sol_global: Decimal,
+    pub daily_kwh_global: Decimal,
+    pub daily_fiat_per_user: Decimal,
+    pub daily_fiat_global: Decimal,
+    /// Seconds after which a grant still pending is considered abandoned and
+    /// failed, so a retry with its `client_request_id` can resume it
+    pub pending_timeout_secs: u64,
+}
+
+impl FaucetConfig {
+    fn from_env() -> Result<Self> {
+        let decimal = |var: &str, default: &str| -> Result<Decimal> {
+            env::var(var)
+                .unwrap_or_else(|_| default.to_string())
+                .trim()
+                .parse()
+                .map_err(|e| anyhow::anyhow!("Invalid {}: {}", var, e))
+        };
+
+        Ok(Self {
+            cooldown_secs: env::var("FAUCET_COOLDOWN_SECS")
+                .unwrap_or_else(|_| "60".to_string())
+                .parse()
+                .map_err(|e| anyhow::anyhow!("Invalid FAUCET_COOLDOWN_SECS: {}", e))?,
+            daily_sol_per_user: decimal("FAUCET_DAILY_SOL_PER_USER", "5")?,
+            daily_kwh_per_user: decimal("FAUCET_DAILY_KWH_PER_USER", "1000")?,
+            daily_sol_global: decimal("FAUCET_DAILY_SOL_GLOBAL", "100")?,
+            daily_kwh_global: decimal("FAUCET_DAILY_KWH_GLOBAL", "100000")?,
+            daily_fiat_per_user: decimal("FAUCET_DAILY_FIAT_PER_USER", "10000")?,
+            daily_fiat_global: decimal("FAUCET_DAILY_FIAT_GLOBAL", "1000000")?,
+            pending_timeout_secs: env::var("FAUCET_PENDING_TIMEOUT_SECS")
+                .unwrap_or_else(|_| "300".to_string())
+                .parse()
+                .map_err(|e| anyhow::anyhow!("Invalid FAUCET_PENDING_TIMEOUT_SECS: {}", e))?,
+        })
+    }
+}
+
+/// Per-epoch circuit breaker on clearing prices
+///
+/// A match priced more than `max_deviation_pct` away from the reference price
+/// is rejected and halts clearing for the rest of its epoch. The reference is
+/// `reference_price` when set, otherwise the last epoch's clearing price.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PriceBandConfig {
+    /// Allowed deviation in percent; zero disables the band
+    pub max_deviation_pct: Decimal,
+    /// Fixed or oracle-fed reference price per kWh
+    pub reference_price: Option<Decimal>,
+}
+
+impl PriceBandConfig {
+    fn from_env() -> Result<Self> {
+        let reference_price = match env::var("PRICE_BAND_REFERENCE_PRICE") {
+            Ok(value) if !value.trim().is_empty() => Some(
+                value
+                    .trim()
+                    .parse()
+                    .map_err(|e| anyhow::anyhow!("Invalid PRICE_BAND_REFERENCE_PRICE: {}", e))?,
+            ),
+            _ => None,
+        };
+
+        Ok(Self {
+            max_deviation_pct: env::var("PRICE_BAND_MAX_DEVIATION_PCT")
+                .unwrap_or_else(|_| "20".to_string())
+                .trim()
+                .parse()
+                .map_err(|e| anyhow::anyhow!("Invalid PRICE_BAND_MAX_DEVIATION_PCT: {}", e))?,
+            reference_price,
+        })
+    }
+
+    pub fn enabled(&self) -> bool {
+        self.max_deviation_pct > Decimal::ZERO
+    }
+}
+
+/// Solana JSON-RPC methods clients may call through `/api/v1/rpc`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RpcProxyConfig {
+    /// Methods forwarded to the validator; everything else is rejected
+    pub allowed_methods: Vec<String>,
+    /// Allowed methods that need a valid bearer token
+    pub authenticated_methods: Vec<String>,
+    /// Allowed methods that need the admin role
+    pub admin_methods: Vec<String>,
+    /// Most calls accepted in one JSON-RPC batch
+    pub max_batch_size: usize,
+    /// Upstream timeout for each call, including each call of a batch
+    pub request_timeout_ms: u64,
+}
+
+/// How log lines are written (`LOG_FORMAT`)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogFormat {
+    /// Human-readable lines
+    #[default]
+    Text,
+    /// One JSON object per line, with the request's trace id, for log aggregation
+    Json,
+}
+
+impl LogFormat {
+    pub fn from_env() -> Result<Self> {
+        match env::var("LOG_FORMAT") {
+            Ok(value) => value.parse(),
+            Err(_) => Ok(LogFormat::default()),
+        }
+    }
+}
+
+impl FromStr for LogFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(value: &str) -> Result<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "text" | "pretty" => Ok(LogFormat::Text),
+            "json" => Ok(LogFormat::Json),
+            other => Err(anyhow::anyhow!("unknown log format '{}' (expected text or json)", other)),
+        }
+    }
+}
+
+/// Who may call an RPC method through the proxy
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RpcMethodAccess {
+    Denied,
+    Public,
+    Authenticated,
+    Admin,
+}
+
+const DEFAULT_RPC_ALLOWED_METHODS: &str = "getAccountInfo,getBalance,getBlockHeight,getEpochInfo,\
+getFeeForMessage,getHealth,getLatestBlockhash,getMinimumBalanceForRentExemption,getMultipleAccounts,\
+getSignatureStatuses,getSignaturesForAddress,getSlot,getTokenAccountBalance,getTokenAccountsByOwner,\
+getTransaction,getVersion,isBlockhashValid,sendTransaction,simulateTransaction";
+const DEFAULT_RPC_AUTHENTICATED_METHODS: &str = "sendTransaction,simulateTransaction";
+const DEFAULT_RPC_ADMIN_METHODS: &str = "requestAirdrop";
+
+impl RpcProxyConfig {
+    fn from_env() -> Result<Self> {
+        let methods = |var: &str, default: &str| -> Vec<String> {
+            env::var(var)
+                .unwrap_or_else(|_| default.to_string())
+                .split(',')
+                .map(|s| s.trim().to_string())
+                .filter(|s| !s.is_empty())
+                .collect()
+        };
+
+        Ok(Self {
+            allowed_methods: methods("RPC_ALLOWED_METHODS", DEFAULT_RPC_ALLOWED_METHODS),
+            authenticated_methods: methods("RPC_AUTHENTICATED_METHODS", DEFAULT_RPC_AUTHENTICATED_METHODS),
+            admin_methods: methods("RPC_ADMIN_METHODS", DEFAULT_RPC_ADMIN_METHODS),
+            max_batch_size: env::var("RPC_MAX_BATCH_SIZE")
+                .unwrap_or_else(|_| "20".to_string())
+                .parse()
+                .map_err(|e| anyhow::anyhow!("Invalid RPC_MAX_BATCH_SIZE: {}", e))?,
+            request_timeout_ms: env::var("RPC_REQUEST_TIMEOUT_MS")
+                .unwrap_or_else(|_| "5000".to_string())
+                .parse()
+                .map_err(|e| anyhow::anyhow!("Invalid RPC_REQUEST_TIMEOUT_MS: {}", e))?,
+        })
+    }
+
+    /// Access required for `method`; method names are case-sensitive
+    pub fn access(&self, method: &str) -> RpcMethodAccess {
+        let listed = |methods: &[String]| methods.iter().any(|m| m == method);
+
+        if !listed(&self.allowed_methods) {
+            RpcMethodAccess::Denied
+        } else if listed(&self.admin_methods) {
+            RpcMethodAccess::Admin
+        } else if listed(&self.authenticated_methods) {
+            RpcMethodAccess::Authenticated
+        } else {
+            RpcMethodAccess::Public
+        }
+    }
+}
+
+/// Parameters of the distance and voltage based transmission loss model
+///
+/// Resistive loss grows with line length and falls with the square of the
+/// line voltage, so the loss between two zones is
+/// `base_loss + loss_per_km * km * (reference_voltage_kv / kv)^2`, scaled by
+/// `underground_factor` for cable routes and capped at `max_loss`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GridLossConfig {
+    /// Model losses from zone attributes where both zones have them
+    pub enabled: bool,
+    /// Loss on every delivery, including within one zone
+    pub base_loss: Decimal,
+    /// Loss per km of line at the reference voltage
+    pub loss_per_km: Decimal,
+    pub reference_voltage_kv: Decimal,
+    /// Applied when either zone is fed by underground cable
+    pub underground_factor: Decimal,
+    pub max_loss: Decimal,
+}
+
+impl Default for GridLossConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            base_loss: Decimal::new(1, 2),           // 1%
+            loss_per_km: Decimal::new(4, 4),         // 0.04% per km at 22 kV
+            reference_voltage_kv: Decimal::new(22, 0),
+            underground_factor: Decimal::new(7, 1),  // larger conductors, lower I²R loss
+            max_loss: Decimal::new(15, 2),           // 15%
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -83,6 +608,11 @@ pub struct EmailConfig {
     pub verification_required: bool,
     pub verification_enabled: bool,
     pub auto_login_after_verification: bool,
+    /// Delivery backend: "smtp" or "http"
+    pub transport: String,
+    /// Endpoint for the http transport
+    pub http_api_url: Option<String>,
+    pub http_api_key: Option<String>,
 }
 
 impl Config {
@@ -106,7 +636,7 @@ impl Config {
                     .map_err(|_| anyhow::anyhow!("JWT_SECRET environment variable is required"))?;
                 let env_name = env::var("ENVIRONMENT").unwrap_or_else(|_| "development".to_string());
                 
-                if env_name != "development" && secret == "supersecretjwtkey" {
+                if env_name != "development" && secret == INSECURE_DEFAULT_JWT_SECRET {
                      return Err(anyhow::anyhow!("FATAL SECURITY ERROR: You are using the default insecure JWT_SECRET in a non-development environment!"));
                 }
                 secret
@@ -139,6 +669,7 @@ impl Config {
                 .parse()?,
             log_level: env::var("LOG_LEVEL")
                 .map_err(|_| anyhow::anyhow!("LOG_LEVEL environment variable is required"))?,
+            log_format: LogFormat::from_env()?,
             audit_log_enabled: env::var("AUDIT_LOG_ENABLED")
                 .map_err(|_| anyhow::anyhow!("AUDIT_LOG_ENABLED environment variable is required"))?
                 .parse()?,
@@ -177,6 +708,9 @@ impl Config {
                     .unwrap_or_else(|_| "true".to_string())
                     .parse()
                     .map_err(|e| anyhow::anyhow!("Invalid EMAIL_AUTO_LOGIN_AFTER_VERIFICATION: {}", e))?,
+                transport: env::var("EMAIL_TRANSPORT").unwrap_or_else(|_| "smtp".to_string()),
+                http_api_url: env::var("EMAIL_HTTP_API_URL").ok(),
+                http_api_key: env::var("EMAIL_HTTP_API_KEY").ok(),
             },
             tokenization: TokenizationConfig::from_env()
                 .map_err(|e| anyhow::anyhow!("Failed to load tokenization config: {}", e))?,
@@ -199,6 +733,10 @@ impl Config {
                     .map_err(|e| anyhow::anyhow!("Invalid EVENT_PROCESSOR_MAX_RETRIES: {}", e))?,
                 webhook_url: env::var("EVENT_PROCESSOR_WEBHOOK_URL").ok(),
                 webhook_secret: env::var("EVENT_PROCESSOR_WEBHOOK_SECRET").ok(),
+                webhook_delivery_interval_secs: env::var("WEBHOOK_DELIVERY_INTERVAL_SECS")
+                    .unwrap_or_else(|_| "10".to_string())
+                    .parse()
+                    .map_err(|e| anyhow::anyhow!("Invalid WEBHOOK_DELIVERY_INTERVAL_SECS: {}", e))?,
             },
             solana_programs: SolanaProgramsConfig {
                 registry_program_id: env::var("SOLANA_REGISTRY_PROGRAM_ID")
@@ -218,6 +756,8 @@ impl Config {
                 .unwrap_or_else(|_| "9".to_string()) // Default: 9 decimals
                 .parse()
                 .map_err(|e| anyhow::anyhow!("Invalid CURRENCY_DECIMALS: {}", e))?,
+            mint_decimals: parse_mint_decimals(&env::var("MINT_DECIMALS").unwrap_or_default())
+                .map_err(|e| anyhow::anyhow!("Invalid MINT_DECIMALS: {}", e))?,
             simulator_user_id: env::var("SIMULATOR_USER_ID")
                 .unwrap_or_else(|_| "63c1d015-6765-4843-9ca3-5ba21ee54d7e".to_string()),
             encryption_secret: env::var("ENCRYPTION_SECRET").map_err(|_| {
@@ -229,6 +769,163 @@ impl Config {
                 .map(|s| s.trim().to_string())
                 .filter(|s| !s.is_empty())
                 .collect(),
+            max_request_body_bytes: env::var("MAX_REQUEST_BODY_BYTES")
+                .unwrap_or_else(|_| "2097152".to_string()) // Default: 2 MiB
+                .parse()
+                .map_err(|e| anyhow::anyhow!("Invalid MAX_REQUEST_BODY_BYTES: {}", e))?,
+            max_batch_readings: env::var("MAX_BATCH_READINGS")
+                .unwrap_or_else(|_| "1000".to_string())
+                .parse()
+                .map_err(|e| anyhow::anyhow!("Invalid MAX_BATCH_READINGS: {}", e))?,
+            order_default_expiry_hours: env::var("ORDER_DEFAULT_EXPIRY_HOURS")
+                .unwrap_or_else(|_| "24".to_string())
+                .parse()
+                .map_err(|e| anyhow::anyhow!("Invalid ORDER_DEFAULT_EXPIRY_HOURS: {}", e))?,
+            order_max_expiry_hours: env::var("ORDER_MAX_EXPIRY_HOURS")
+                .unwrap_or_else(|_| "720".to_string()) // Default: 30 days
+                .parse()
+                .map_err(|e| anyhow::anyhow!("Invalid ORDER_MAX_EXPIRY_HOURS: {}", e))?,
+            zone_rates_refresh_interval_secs: env::var("ZONE_RATES_REFRESH_INTERVAL_SECS")
+                .unwrap_or_else(|_| "300".to_string())
+                .parse()
+                .map_err(|e| anyhow::anyhow!("Invalid ZONE_RATES_REFRESH_INTERVAL_SECS: {}", e))?,
+            dashboard_metrics_interval_secs: env::var("DASHBOARD_METRICS_INTERVAL_SECS")
+                .unwrap_or_else(|_| "5".to_string())
+                .parse()
+                .map_err(|e| anyhow::anyhow!("Invalid DASHBOARD_METRICS_INTERVAL_SECS: {}", e))?,
+            futures_mark_price_interval_secs: env::var("FUTURES_MARK_PRICE_INTERVAL_SECS")
+                .unwrap_or_else(|_| "10".to_string())
+                .parse()
+                .map_err(|e| anyhow::anyhow!("Invalid FUTURES_MARK_PRICE_INTERVAL_SECS: {}", e))?,
+            grid_loss: GridLossConfig::from_env()?,
+            rpc_proxy: RpcProxyConfig::from_env()?,
+            emission_factors: EmissionFactors::from_env()?,
+            influx: InfluxConfig::from_env()?,
+            price_band: PriceBandConfig::from_env()?,
+            leader_election: LeaderElectionConfig::from_env()?,
+            authority_funding: AuthorityFundingConfig::from_env()?,
+            treasury: TreasuryConfig::from_env()?,
+            faucet: FaucetConfig::from_env()?,
         })
     }
 }
+
+impl GridLossConfig {
+    fn from_env() -> Result<Self> {
+        let defaults = Self::default();
+        let decimal = |var: &str, default: Decimal| -> Result<Decimal> {
+            match env::var(var) {
+                Ok(value) => value
+                    .trim()
+                    .parse()
+                    .map_err(|e| anyhow::anyhow!("Invalid {}: {}", var, e)),
+                Err(_) => Ok(default),
+            }
+        };
+
+        Ok(Self {
+            enabled: env::var("GRID_LOSS_MODEL_ENABLED")
+                .unwrap_or_else(|_| "true".to_string())
+                .parse()
+                .map_err(|e| anyhow::anyhow!("Invalid GRID_LOSS_MODEL_ENABLED: {}", e))?,
+            base_loss: decimal("GRID_LOSS_BASE", defaults.base_loss)?,
+            loss_per_km: decimal("GRID_LOSS_PER_KM", defaults.loss_per_km)?,
+            reference_voltage_kv: decimal("GRID_LOSS_REFERENCE_KV", defaults.reference_voltage_kv)?,
+            underground_factor: decimal("GRID_LOSS_UNDERGROUND_FACTOR", defaults.underground_factor)?,
+            max_loss: decimal("GRID_LOSS_MAX", defaults.max_loss)?,
+        })
+    }
+}
+
+/// Parse `source:kg_per_kwh` pairs separated by commas
+fn parse_emission_factors(value: &str) -> Result<HashMap<String, f64>> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+        .map(|entry| {
+            let (source, factor) = entry
+                .split_once(':')
+                .ok_or_else(|| anyhow::anyhow!("expected source:factor, got '{}'", entry))?;
+            let factor: f64 = factor
+                .trim()
+                .parse()
+                .map_err(|e| anyhow::anyhow!("invalid factor for '{}': {}", source.trim(), e))?;
+            if factor.is_nan() || factor < 0.0 {
+                return Err(anyhow::anyhow!("factor for '{}' must not be negative", source.trim()));
+            }
+            Ok((source.trim().to_lowercase(), factor))
+        })
+        .collect()
+}
+
+/// Parse `mint:decimals` pairs separated by commas
+fn parse_mint_decimals(value: &str) -> Result<HashMap<String, u8>> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+        .map(|entry| {
+            let (mint, decimals) = entry
+                .split_once(':')
+                .ok_or_else(|| anyhow::anyhow!("expected mint:decimals, got '{}'", entry))?;
+            let mint = mint.trim();
+            Pubkey::from_str(mint).map_err(|e| anyhow::anyhow!("invalid mint '{}': {}", mint, e))?;
+            let decimals = decimals
+                .trim()
+                .parse::<u8>()
+                .map_err(|e| anyhow::anyhow!("invalid decimals for '{}': {}", mint, e))?;
+            Ok((mint.to_string(), decimals))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_mint_decimals() {
+        let usdc = "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v";
+        let energy = "EhRVEDVt5vqPW4rReavMy9dKbief3JKG2eAoXJLFL14M";
+        let parsed = parse_mint_decimals(&format!("{}:6, {}:9", usdc, energy)).unwrap();
+        assert_eq!(parsed.get(usdc), Some(&6));
+        assert_eq!(parsed.get(energy), Some(&9));
+
+        assert!(parse_mint_decimals("").unwrap().is_empty());
+        assert!(parse_mint_decimals("not-a-mint:6").is_err());
+        assert!(parse_mint_decimals(&format!("{}:256", usdc)).is_err());
+        assert!(parse_mint_decimals(usdc).is_err());
+    }
+
+    #[test]
+    fn test_emission_factors() {
+        let factors = EmissionFactors::default();
+        let solar = factors.avoided_per_kwh("Solar").unwrap();
+        assert!((solar - 0.390).abs() < 1e-9);
+        assert_eq!(factors.avoided_per_kwh("residential"), None);
+
+        assert!(parse_emission_factors("solar:-0.1").is_err());
+        assert!(parse_emission_factors("solar").is_err());
+        assert_eq!(parse_emission_factors(" Wind:0.011 ").unwrap().get("wind"), Some(&0.011));
+    }
+
+    #[test]
+    fn test_rpc_method_access() {
+        let list = |methods: &str| methods.split(',').map(str::to_string).collect::<Vec<_>>();
+        let rpc = RpcProxyConfig {
+            allowed_methods: list("getBalance,sendTransaction,requestAirdrop"),
+            authenticated_methods: list("sendTransaction"),
+            admin_methods: list("requestAirdrop,sendTransaction"),
+            max_batch_size: 20,
+            request_timeout_ms: 5000,
+        };
+
+        assert_eq!(rpc.access("getBalance"), RpcMethodAccess::Public);
+        assert_eq!(rpc.access("requestAirdrop"), RpcMethodAccess::Admin);
+        // Admin wins over authenticated when a method is in both lists
+        assert_eq!(rpc.access("sendTransaction"), RpcMethodAccess::Admin);
+        assert_eq!(rpc.access("getProgramAccounts"), RpcMethodAccess::Denied);
+        assert_eq!(rpc.access("getbalance"), RpcMethodAccess::Denied);
+    }
+}
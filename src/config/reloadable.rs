@@ -0,0 +1,177 @@
+//! Hot-reloadable runtime settings
+//!
+//! A small subset of configuration can be changed without restarting the
+//! gateway: edit `.env` (or the process environment) and send `SIGHUP`.
+//!
+//! Hot-reloadable:
+//! - `MATCHING_INTERVAL_SECS` — picked up after the current matching cycle
+//! - `SETTLEMENT_FEE_RATE` — applies to settlements created after the reload
+//! - `CORS_ALLOWED_ORIGINS` — applies to the next request; allowed methods
+//!   and headers are fixed at startup
+//!
+//! Everything else in [`Config`](super::Config) (database, Redis, Solana RPC,
+//! program ids, secrets, email, ports) still requires a restart.
+
+use std::str::FromStr;
+use std::sync::{Arc, RwLock};
+
+use rust_decimal::Decimal;
+use tracing::{info, warn};
+
+use super::cors::OriginRules;
+
+const DEFAULT_MATCHING_INTERVAL_SECS: u64 = 5;
+const DEFAULT_CORS_ALLOWED_ORIGINS: &str =
+    "http://localhost:3000,http://localhost:4000,https://gridtokenx.com";
+
+/// Values that are safe to swap while the gateway is running
+#[derive(Debug, Clone, PartialEq)]
+pub struct RuntimeSettings {
+    pub matching_interval_secs: u64,
+    pub settlement_fee_rate: Decimal,
+    pub cors_allowed_origins: OriginRules,
+}
+
+impl RuntimeSettings {
+    /// Read the hot-reloadable values from the environment
+    pub fn from_env() -> Self {
+        let matching_interval_secs = std::env::var("MATCHING_INTERVAL_SECS")
+            .ok()
+            .and_then(|v| v.parse::<u64>().ok())
+            .filter(|secs| *secs > 0)
+            .unwrap_or(DEFAULT_MATCHING_INTERVAL_SECS);
+
+        let settlement_fee_rate = std::env::var("SETTLEMENT_FEE_RATE")
+            .ok()
+            .and_then(|v| Decimal::from_str(&v).ok())
+            .unwrap_or_else(|| Decimal::new(1, 2)); // 1% platform fee
+
+        let cors_allowed_origins = OriginRules::parse_lenient(
+            &std::env::var("CORS_ALLOWED_ORIGINS")
+                .unwrap_or_else(|_| DEFAULT_CORS_ALLOWED_ORIGINS.to_string()),
+        );
+
+        Self {
+            matching_interval_secs,
+            settlement_fee_rate,
+            cors_allowed_origins,
+        }
+    }
+
+    /// Whether a request `Origin` header value is allowed by CORS
+    pub fn is_origin_allowed(&self, origin: &str) -> bool {
+        self.cors_allowed_origins.is_allowed(origin)
+    }
+}
+
+/// Shared handle to the current [`RuntimeSettings`]
+///
+/// Cloning is cheap; all clones observe a reload.
+#[derive(Debug, Clone)]
+pub struct ReloadableConfig {
+    inner: Arc<RwLock<RuntimeSettings>>,
+}
+
+impl ReloadableConfig {
+    pub fn new(settings: RuntimeSettings) -> Self {
+        Self {
+            inner: Arc::new(RwLock::new(settings)),
+        }
+    }
+
+    pub fn from_env() -> Self {
+        Self::new(RuntimeSettings::from_env())
+    }
+
+    /// Copy of the current settings
+    pub fn snapshot(&self) -> RuntimeSettings {
+        self.inner
+            .read()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .clone()
+    }
+
+    pub fn matching_interval_secs(&self) -> u64 {
+        self.snapshot().matching_interval_secs
+    }
+
+    pub fn settlement_fee_rate(&self) -> Decimal {
+        self.snapshot().settlement_fee_rate
+    }
+
+    pub fn is_origin_allowed(&self, origin: &str) -> bool {
+        self.inner
+            .read()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .is_origin_allowed(origin)
+    }
+
+    /// Replace the current settings
+    pub fn set(&self, settings: RuntimeSettings) {
+        *self
+            .inner
+            .write()
+            .unwrap_or_else(|poisoned| poisoned.into_inner()) = settings;
+    }
+
+    /// Re-read `.env` and the environment and swap in the new values
+    pub fn reload(&self) -> RuntimeSettings {
+        if let Err(e) = dotenvy::dotenv_override() {
+            warn!("⚠️ Could not re-read .env during config reload: {}", e);
+        }
+
+        let settings = RuntimeSettings::from_env();
+        let previous = self.snapshot();
+        if previous == settings {
+            info!("🔄 Config reload: no hot-reloadable values changed");
+        } else {
+            info!(
+                "🔄 Config reloaded: matching_interval_secs={} (was {}), settlement_fee_rate={} (was {}), cors_allowed_origins={:?}",
+                settings.matching_interval_secs,
+                previous.matching_interval_secs,
+                settings.settlement_fee_rate,
+                previous.settlement_fee_rate,
+                settings.cors_allowed_origins
+            );
+        }
+
+        self.set(settings.clone());
+        settings
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn settings(origins: &[&str]) -> RuntimeSettings {
+        RuntimeSettings {
+            matching_interval_secs: 5,
+            settlement_fee_rate: Decimal::new(1, 2),
+            cors_allowed_origins: origins.join(",").parse().unwrap(),
+        }
+    }
+
+    #[test]
+    fn test_set_is_visible_through_clones() {
+        let handle = ReloadableConfig::new(settings(&["http://localhost:3000"]));
+        let clone = handle.clone();
+
+        let mut updated = settings(&["https://gridtokenx.com"]);
+        updated.matching_interval_secs = 30;
+        handle.set(updated);
+
+        assert_eq!(clone.matching_interval_secs(), 30);
+        assert!(clone.is_origin_allowed("https://gridtokenx.com"));
+        assert!(!clone.is_origin_allowed("http://localhost:3000"));
+    }
+
+    #[test]
+    fn test_parse_origins_trims_and_skips_empty_and_invalid() {
+        let rules = OriginRules::parse_lenient(" http://a.com, ,http://b.com ,a.*.com");
+        assert_eq!(rules.0.len(), 2);
+        assert!(rules.is_allowed("http://a.com"));
+        assert!(rules.is_allowed("http://b.com"));
+        assert!(!rules.is_allowed("http://a.com.b.com"));
+    }
+}
@@ -406,6 +406,19 @@ pub enum ConfigError {
     #[error("Environment variable {0} is missing or invalid")]
     MissingVariable(String),
 
+    #[error("{var}={value:?} is invalid: {reason}")]
+    InvalidValue {
+        var: String,
+        value: String,
+        reason: String,
+    },
+
+    #[error("{var}={value:?} is not a valid Solana public key")]
+    InvalidPubkey { var: String, value: String },
+
+    #[error("{var} is too weak: {reason}")]
+    WeakSecret { var: String, reason: String },
+
     #[error("Configuration validation failed: {0}")]
     ValidationFailed(String),
 
@@ -0,0 +1,550 @@
+//! Startup configuration validation
+//!
+//! Collects every configuration problem in one pass so a misconfigured
+//! deployment can be fixed without a restart per variable.
+
+use std::collections::HashMap;
+use std::env;
+use std::fmt::Display;
+use std::str::FromStr;
+
+use rust_decimal::Decimal;
+use solana_sdk::pubkey::Pubkey;
+
+use super::cors::{CorsHeaders, CorsMethods, OriginRules};
+use super::{Config, ConfigError, LogFormat, INSECURE_DEFAULT_JWT_SECRET};
+use crate::auth::jwt::JwtKeyConfig;
+use crate::services::settlement::{EnergyRounding, RevenueSplit};
+
+/// Minimum length for JWT_SECRET and each JWT_KEYS secret
+const MIN_JWT_SECRET_LEN: usize = 32;
+
+/// Minimum length for ENCRYPTION_SECRET
+const MIN_ENCRYPTION_SECRET_LEN: usize = 32;
+
+/// Minimum estimated entropy for ENCRYPTION_SECRET, in bits
+const MIN_ENCRYPTION_SECRET_ENTROPY_BITS: f64 = 96.0;
+
+/// Variables `Config::from_env` refuses to start without
+const REQUIRED_VARS: &[&str] = &[
+    "ENVIRONMENT",
+    "PORT",
+    "DATABASE_URL",
+    "REDIS_URL",
+    "JWT_SECRET",
+    "SOLANA_RPC_URL",
+    "SOLANA_WS_URL",
+    "ENERGY_TOKEN_MINT",
+    "ENGINEERING_API_KEY",
+    "MAX_CONNECTIONS",
+    "REDIS_POOL_SIZE",
+    "REQUEST_TIMEOUT",
+    "RATE_LIMIT_WINDOW",
+    "LOG_LEVEL",
+    "AUDIT_LOG_ENABLED",
+    "ENCRYPTION_SECRET",
+];
+
+impl Config {
+    /// Validate the loaded configuration, returning every problem found
+    pub fn validate(&self) -> Result<(), Vec<ConfigError>> {
+        let mut errors = Self::check_env();
+        errors.extend(self.check_values());
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    /// Check the raw environment for missing required variables and values
+    /// that will not parse
+    ///
+    /// Useful when `Config::from_env` fails, since it stops at the first problem.
+    pub fn check_env() -> Vec<ConfigError> {
+        let mut errors: Vec<ConfigError> = REQUIRED_VARS
+            .iter()
+            .filter(|name| env::var(name).map(|v| v.trim().is_empty()).unwrap_or(true))
+            .map(|name| ConfigError::MissingVariable(name.to_string()))
+            .collect();
+
+        check_parse::<u16>("PORT", &mut errors);
+        check_parse::<u32>("MAX_CONNECTIONS", &mut errors);
+        check_parse::<u32>("REDIS_POOL_SIZE", &mut errors);
+        check_parse::<u64>("REQUEST_TIMEOUT", &mut errors);
+        check_parse::<u64>("RATE_LIMIT_WINDOW", &mut errors);
+        check_parse::<bool>("AUDIT_LOG_ENABLED", &mut errors);
+        check_parse::<LogFormat>("LOG_FORMAT", &mut errors);
+        check_parse::<i64>("JWT_EXPIRATION", &mut errors);
+        check_parse::<bool>("TEST_MODE", &mut errors);
+        check_parse::<u16>("SMTP_PORT", &mut errors);
+        check_parse::<i64>("EMAIL_VERIFICATION_EXPIRY_HOURS", &mut errors);
+        check_parse::<bool>("EMAIL_VERIFICATION_REQUIRED", &mut errors);
+        check_parse::<bool>("EMAIL_VERIFICATION_ENABLED", &mut errors);
+        check_parse::<bool>("EMAIL_AUTO_LOGIN_AFTER_VERIFICATION", &mut errors);
+        check_parse::<bool>("EVENT_PROCESSOR_ENABLED", &mut errors);
+        check_parse::<u64>("EVENT_PROCESSOR_POLLING_INTERVAL_SECS", &mut errors);
+        check_parse::<usize>("EVENT_PROCESSOR_BATCH_SIZE", &mut errors);
+        check_parse::<u32>("EVENT_PROCESSOR_MAX_RETRIES", &mut errors);
+        check_parse::<u64>("WEBHOOK_DELIVERY_INTERVAL_SECS", &mut errors);
+        check_parse::<u8>("CURRENCY_DECIMALS", &mut errors);
+        check_parse::<usize>("MAX_REQUEST_BODY_BYTES", &mut errors);
+        check_parse::<usize>("MAX_BATCH_READINGS", &mut errors);
+        check_parse::<i64>("ORDER_DEFAULT_EXPIRY_HOURS", &mut errors);
+        check_parse::<i64>("ORDER_MAX_EXPIRY_HOURS", &mut errors);
+        check_parse::<u64>("ZONE_RATES_REFRESH_INTERVAL_SECS", &mut errors);
+        check_parse::<u64>("DASHBOARD_METRICS_INTERVAL_SECS", &mut errors);
+        check_parse::<u64>("FUTURES_MARK_PRICE_INTERVAL_SECS", &mut errors);
+        check_parse::<bool>("GRID_LOSS_MODEL_ENABLED", &mut errors);
+        check_parse::<Decimal>("GRID_LOSS_BASE", &mut errors);
+        check_parse::<Decimal>("GRID_LOSS_PER_KM", &mut errors);
+        check_parse::<Decimal>("GRID_LOSS_REFERENCE_KV", &mut errors);
+        check_parse::<Decimal>("GRID_LOSS_UNDERGROUND_FACTOR", &mut errors);
+        check_parse::<Decimal>("GRID_LOSS_MAX", &mut errors);
+        check_parse::<f64>("GRID_EMISSION_FACTOR", &mut errors);
+        check_parse::<usize>("RPC_MAX_BATCH_SIZE", &mut errors);
+        check_parse::<u64>("RPC_REQUEST_TIMEOUT_MS", &mut errors);
+        check_parse::<bool>("INFLUXDB_ENABLED", &mut errors);
+        check_parse::<usize>("INFLUXDB_BATCH_SIZE", &mut errors);
+        check_parse::<u64>("INFLUXDB_FLUSH_INTERVAL_MS", &mut errors);
+        check_parse::<u32>("INFLUXDB_MAX_RETRIES", &mut errors);
+        check_parse::<Decimal>("PRICE_BAND_MAX_DEVIATION_PCT", &mut errors);
+        check_parse::<Decimal>("PRICE_BAND_REFERENCE_PRICE", &mut errors);
+        check_parse::<bool>("LEADER_ELECTION_ENABLED", &mut errors);
+        check_parse::<f64>("AUTHORITY_MIN_BALANCE_SOL", &mut errors);
+        check_parse::<f64>("AUTHORITY_LOW_BALANCE_SOL", &mut errors);
+        check_parse::<u64>("AUTHORITY_BALANCE_CHECK_INTERVAL_SECS", &mut errors);
+        check_parse::<u64>("LEADER_LEASE_TTL_MS", &mut errors);
+        check_parse::<u64>("LEADER_RENEW_INTERVAL_MS", &mut errors);
+        check_parse::<bool>("TREASURY_SWEEP_ENABLED", &mut errors);
+        check_parse::<u64>("TREASURY_SWEEP_INTERVAL_SECS", &mut errors);
+        check_parse::<Decimal>("TREASURY_SWEEP_MIN_AMOUNT", &mut errors);
+        check_parse::<u64>("FAUCET_COOLDOWN_SECS", &mut errors);
+        check_parse::<Decimal>("FAUCET_DAILY_SOL_PER_USER", &mut errors);
+        check_parse::<Decimal>("FAUCET_DAILY_KWH_PER_USER", &mut errors);
+        check_parse::<Decimal>("FAUCET_DAILY_SOL_GLOBAL", &mut errors);
+        check_parse::<Decimal>("FAUCET_DAILY_KWH_GLOBAL", &mut errors);
+        check_parse::<Decimal>("FAUCET_DAILY_FIAT_PER_USER", &mut errors);
+        check_parse::<Decimal>("FAUCET_DAILY_FIAT_GLOBAL", &mut errors);
+        check_parse::<u64>("FAUCET_PENDING_TIMEOUT_SECS", &mut errors);
+        check_parse::<RevenueSplit>("REVENUE_SPLIT_PLATFORM_FEE", &mut errors);
+        check_parse::<RevenueSplit>("REVENUE_SPLIT_WHEELING_CHARGE", &mut errors);
+        check_parse::<RevenueSplit>("REVENUE_SPLIT_LOSS_COST", &mut errors);
+        check_parse::<EnergyRounding>("SETTLEMENT_ENERGY_ROUNDING", &mut errors);
+        check_parse::<OriginRules>("CORS_ALLOWED_ORIGINS", &mut errors);
+        check_parse::<CorsMethods>("CORS_ALLOWED_METHODS", &mut errors);
+        check_parse::<CorsHeaders>("CORS_ALLOWED_HEADERS", &mut errors);
+
+        errors
+    }
+
+    /// Semantic checks on values that parsed successfully
+    fn check_values(&self) -> Vec<ConfigError> {
+        let mut errors = Vec::new();
+
+        if let Err(program_errors) = self.solana_programs.validated() {
+            errors.extend(program_errors);
+        }
+
+        let treasury = &self.treasury;
+        let pubkeys = [
+            ("ENERGY_TOKEN_MINT", Some(&self.energy_token_mint)),
+            ("CURRENCY_TOKEN_MINT", Some(&self.currency_token_mint)),
+            ("TREASURY_WALLET_PLATFORM_TREASURY", treasury.platform_treasury_wallet.as_ref()),
+            ("TREASURY_WALLET_GRID_OPERATOR", treasury.grid_operator_wallet.as_ref()),
+            ("TREASURY_WALLET_INSURANCE_FUND", treasury.insurance_fund_wallet.as_ref()),
+        ];
+        for (var, value) in pubkeys {
+            let Some(value) = value else {
+                continue;
+            };
+            if Pubkey::from_str(value).is_err() {
+                errors.push(ConfigError::InvalidPubkey {
+                    var: var.to_string(),
+                    value: value.clone(),
+                });
+            }
+        }
+
+        errors.extend(check_jwt_secret("JWT_SECRET", &self.jwt_secret, &self.environment));
+        if let Ok(keys) = env::var("JWT_KEYS") {
+            errors.extend(check_jwt_keys(&keys, &self.environment));
+        }
+
+        if self.encryption_secret.len() < MIN_ENCRYPTION_SECRET_LEN {
+            errors.push(ConfigError::WeakSecret {
+                var: "ENCRYPTION_SECRET".to_string(),
+                reason: format!(
+                    "{} characters, at least {} required",
+                    self.encryption_secret.len(),
+                    MIN_ENCRYPTION_SECRET_LEN
+                ),
+            });
+        } else {
+            let bits = estimate_entropy_bits(&self.encryption_secret);
+            if bits < MIN_ENCRYPTION_SECRET_ENTROPY_BITS {
+                errors.push(ConfigError::WeakSecret {
+                    var: "ENCRYPTION_SECRET".to_string(),
+                    reason: format!(
+                        "estimated entropy {:.0} bits, at least {:.0} required \
+                         (generate one with `openssl rand -hex 32`)",
+                        bits, MIN_ENCRYPTION_SECRET_ENTROPY_BITS
+                    ),
+                });
+            }
+        }
+
+        if uuid::Uuid::parse_str(&self.simulator_user_id).is_err() {
+            errors.push(ConfigError::InvalidValue {
+                var: "SIMULATOR_USER_ID".to_string(),
+                value: self.simulator_user_id.clone(),
+                reason: "expected a UUID".to_string(),
+            });
+        }
+
+        if self.max_request_body_bytes == 0 {
+            errors.push(ConfigError::InvalidValue {
+                var: "MAX_REQUEST_BODY_BYTES".to_string(),
+                value: "0".to_string(),
+                reason: "must be greater than zero".to_string(),
+            });
+        }
+        if self.max_batch_readings == 0 {
+            errors.push(ConfigError::InvalidValue {
+                var: "MAX_BATCH_READINGS".to_string(),
+                value: "0".to_string(),
+                reason: "must be greater than zero".to_string(),
+            });
+        }
+
+        for (var, hours) in [
+            ("ORDER_DEFAULT_EXPIRY_HOURS", self.order_default_expiry_hours),
+            ("ORDER_MAX_EXPIRY_HOURS", self.order_max_expiry_hours),
+        ] {
+            if hours <= 0 {
+                errors.push(ConfigError::InvalidValue {
+                    var: var.to_string(),
+                    value: hours.to_string(),
+                    reason: "must be greater than zero".to_string(),
+                });
+            }
+        }
+        if self.order_default_expiry_hours > self.order_max_expiry_hours {
+            errors.push(ConfigError::IncompatibleValues(format!(
+                "ORDER_DEFAULT_EXPIRY_HOURS ({}) exceeds ORDER_MAX_EXPIRY_HOURS ({})",
+                self.order_default_expiry_hours, self.order_max_expiry_hours
+            )));
+        }
+
+        for (var, value) in [
+            ("RPC_MAX_BATCH_SIZE", self.rpc_proxy.max_batch_size as u64),
+            ("RPC_REQUEST_TIMEOUT_MS", self.rpc_proxy.request_timeout_ms),
+            ("INFLUXDB_BATCH_SIZE", self.influx.batch_size as u64),
+            ("INFLUXDB_FLUSH_INTERVAL_MS", self.influx.flush_interval_ms),
+            ("LEADER_RENEW_INTERVAL_MS", self.leader_election.renew_interval_ms),
+            ("AUTHORITY_BALANCE_CHECK_INTERVAL_SECS", self.authority_funding.check_interval_secs),
+            ("TREASURY_SWEEP_INTERVAL_SECS", self.treasury.sweep_interval_secs),
+            ("WEBHOOK_DELIVERY_INTERVAL_SECS", self.event_processor.webhook_delivery_interval_secs),
+            ("ZONE_RATES_REFRESH_INTERVAL_SECS", self.zone_rates_refresh_interval_secs),
+            ("DASHBOARD_METRICS_INTERVAL_SECS", self.dashboard_metrics_interval_secs),
+            ("FUTURES_MARK_PRICE_INTERVAL_SECS", self.futures_mark_price_interval_secs),
+        ] {
+            if value == 0 {
+                errors.push(ConfigError::InvalidValue {
+                    var: var.to_string(),
+                    value: "0".to_string(),
+                    reason: "must be greater than zero".to_string(),
+                });
+            }
+        }
+
+        // A lease renewed less than twice per TTL lapses on one slow renewal
+        let election = &self.leader_election;
+        if election.lease_ttl_ms < election.renew_interval_ms.saturating_mul(2) {
+            errors.push(ConfigError::IncompatibleValues(format!(
+                "LEADER_LEASE_TTL_MS ({}) must be at least twice LEADER_RENEW_INTERVAL_MS ({})",
+                election.lease_ttl_ms, election.renew_interval_ms
+            )));
+        }
+
+        let loss = &self.grid_loss;
+        for (var, value) in [
+            ("GRID_LOSS_BASE", loss.base_loss),
+            ("GRID_LOSS_PER_KM", loss.loss_per_km),
+            ("GRID_LOSS_UNDERGROUND_FACTOR", loss.underground_factor),
+        ] {
+            if value < Decimal::ZERO {
+                errors.push(ConfigError::InvalidValue {
+                    var: var.to_string(),
+                    value: value.to_string(),
+                    reason: "must not be negative".to_string(),
+                });
+            }
+        }
+        if loss.reference_voltage_kv <= Decimal::ZERO {
+            errors.push(ConfigError::InvalidValue {
+                var: "GRID_LOSS_REFERENCE_KV".to_string(),
+                value: loss.reference_voltage_kv.to_string(),
+                reason: "must be greater than zero".to_string(),
+            });
+        }
+        if loss.max_loss < loss.base_loss || loss.max_loss >= Decimal::ONE {
+            errors.push(ConfigError::InvalidValue {
+                var: "GRID_LOSS_MAX".to_string(),
+                value: loss.max_loss.to_string(),
+                reason: "must be at least GRID_LOSS_BASE and below 1".to_string(),
+            });
+        }
+
+        let funding = &self.authority_funding;
+        for (var, value) in [
+            ("AUTHORITY_MIN_BALANCE_SOL", funding.min_balance_sol),
+            ("AUTHORITY_LOW_BALANCE_SOL", funding.low_balance_sol),
+        ] {
+            if !value.is_finite() || value < 0.0 {
+                errors.push(ConfigError::InvalidValue {
+                    var: var.to_string(),
+                    value: value.to_string(),
+                    reason: "must be a non-negative number".to_string(),
+                });
+            }
+        }
+        if funding.low_balance_sol < funding.min_balance_sol {
+            errors.push(ConfigError::IncompatibleValues(format!(
+                "AUTHORITY_LOW_BALANCE_SOL ({}) must be at least AUTHORITY_MIN_BALANCE_SOL ({})",
+                funding.low_balance_sol, funding.min_balance_sol
+            )));
+        }
+
+        if treasury.min_sweep_amount < Decimal::ZERO {
+            errors.push(ConfigError::InvalidValue {
+                var: "TREASURY_SWEEP_MIN_AMOUNT".to_string(),
+                value: treasury.min_sweep_amount.to_string(),
+                reason: "must not be negative".to_string(),
+            });
+        }
+
+        let faucet = &self.faucet;
+        for (var, cap) in [
+            ("FAUCET_DAILY_SOL_PER_USER", faucet.daily_sol_per_user),
+            ("FAUCET_DAILY_KWH_PER_USER", faucet.daily_kwh_per_user),
+            ("FAUCET_DAILY_SOL_GLOBAL", faucet.daily_sol_global),
+            ("FAUCET_DAILY_KWH_GLOBAL", faucet.daily_kwh_global),
+            ("FAUCET_DAILY_FIAT_PER_USER", faucet.daily_fiat_per_user),
+            ("FAUCET_DAILY_FIAT_GLOBAL", faucet.daily_fiat_global),
+        ] {
+            if cap < Decimal::ZERO {
+                errors.push(ConfigError::InvalidValue {
+                    var: var.to_string(),
+                    value: cap.to_string(),
+                    reason: "must not be negative".to_string(),
+                });
+            }
+        }
+        if faucet.pending_timeout_secs == 0 {
+            errors.push(ConfigError::InvalidValue {
+                var: "FAUCET_PENDING_TIMEOUT_SECS".to_string(),
+                value: "0".to_string(),
+                reason: "must be at least 1".to_string(),
+            });
+        }
+
+        let band = &self.price_band;
+        if band.max_deviation_pct < Decimal::ZERO {
+            errors.push(ConfigError::InvalidValue {
+                var: "PRICE_BAND_MAX_DEVIATION_PCT".to_string(),
+                value: band.max_deviation_pct.to_string(),
+                reason: "must not be negative".to_string(),
+            });
+        }
+        if let Some(reference) = band.reference_price.filter(|price| *price <= Decimal::ZERO) {
+            errors.push(ConfigError::InvalidValue {
+                var: "PRICE_BAND_REFERENCE_PRICE".to_string(),
+                value: reference.to_string(),
+                reason: "must be greater than zero".to_string(),
+            });
+        }
+
+        match self.email.transport.as_str() {
+            "smtp" => {}
+            "http" => {
+                if self.email.http_api_url.is_none() {
+                    errors.push(ConfigError::IncompatibleValues(
+                        "EMAIL_TRANSPORT=http requires EMAIL_HTTP_API_URL".to_string(),
+                    ));
+                }
+            }
+            other => errors.push(ConfigError::InvalidValue {
+                var: "EMAIL_TRANSPORT".to_string(),
+                value: other.to_string(),
+                reason: "expected \"smtp\" or \"http\"".to_string(),
+            }),
+        }
+
+        errors
+    }
+}
+
+/// Render a list of configuration errors as one line per problem
+pub fn format_config_errors(errors: &[ConfigError]) -> String {
+    errors
+        .iter()
+        .map(|e| format!("  - {}", e))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Record an error if `name` is set but does not parse as `T`
+fn check_parse<T>(name: &str, errors: &mut Vec<ConfigError>)
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    if let Ok(value) = env::var(name) {
+        // Empty required variables are already reported as missing
+        if value.trim().is_empty() && REQUIRED_VARS.contains(&name) {
+            return;
+        }
+        if let Err(e) = value.parse::<T>() {
+            errors.push(ConfigError::InvalidValue {
+                var: name.to_string(),
+                value,
+                reason: e.to_string(),
+            });
+        }
+    }
+}
+
+/// Check a JWT signing secret's length, and that it is not the well-known
+/// development default outside development
+fn check_jwt_secret(var: &str, secret: &str, environment: &str) -> Option<ConfigError> {
+    if environment != "development" && secret == INSECURE_DEFAULT_JWT_SECRET {
+        return Some(ConfigError::WeakSecret {
+            var: var.to_string(),
+            reason: "the insecure development default is not allowed outside development".to_string(),
+        });
+    }
+    if secret.len() < MIN_JWT_SECRET_LEN {
+        return Some(ConfigError::WeakSecret {
+            var: var.to_string(),
+            reason: format!(
+                "{} characters, at least {} required",
+                secret.len(),
+                MIN_JWT_SECRET_LEN
+            ),
+        });
+    }
+    None
+}
+
+/// Check every secret in the `JWT_KEYS` keyring, which replaces `JWT_SECRET`
+/// for signing and verification when set
+fn check_jwt_keys(keys: &str, environment: &str) -> Vec<ConfigError> {
+    let keyring: HashMap<String, JwtKeyConfig> = match serde_json::from_str(keys) {
+        Ok(keyring) => keyring,
+        Err(e) => {
+            return vec![ConfigError::InvalidValue {
+                var: "JWT_KEYS".to_string(),
+                value: "<redacted>".to_string(),
+                reason: e.to_string(),
+            }]
+        }
+    };
+
+    let mut kids: Vec<&String> = keyring.keys().collect();
+    kids.sort();
+    kids.into_iter()
+        .filter_map(|kid| check_jwt_secret(&format!("JWT_KEYS[{}]", kid), &keyring[kid].secret, environment))
+        .collect()
+}
+
+/// Estimate the entropy of a secret in bits from its character distribution
+fn estimate_entropy_bits(secret: &str) -> f64 {
+    let len = secret.chars().count();
+    if len == 0 {
+        return 0.0;
+    }
+
+    let mut counts: HashMap<char, usize> = HashMap::new();
+    for c in secret.chars() {
+        *counts.entry(c).or_insert(0) += 1;
+    }
+
+    let per_char: f64 = counts
+        .values()
+        .map(|&count| {
+            let p = count as f64 / len as f64;
+            -p * p.log2()
+        })
+        .sum();
+
+    per_char * len as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_entropy_of_repeated_character_is_zero() {
+        assert_eq!(estimate_entropy_bits(&"a".repeat(64)), 0.0);
+        assert_eq!(estimate_entropy_bits(""), 0.0);
+    }
+
+    #[test]
+    fn test_entropy_of_random_hex_secret_passes() {
+        let secret = "861b5a3ad74e8bbacfeabfda25d332484b169a7c3bc476a6054a08cfc078b3b9";
+        assert!(estimate_entropy_bits(secret) >= MIN_ENCRYPTION_SECRET_ENTROPY_BITS);
+    }
+
+    #[test]
+    fn test_entropy_of_low_variety_secret_fails() {
+        let secret = "abababababababababababababababab";
+        assert!(estimate_entropy_bits(secret) < MIN_ENCRYPTION_SECRET_ENTROPY_BITS);
+    }
+
+    #[test]
+    fn test_jwt_keys_secrets_are_checked() {
+        let strong = "861b5a3ad74e8bbacfeabfda25d33248";
+        let keys = format!(
+            r#"{{"old": {{"secret": "short"}}, "new": {{"secret": "{}"}}, "dev": {{"secret": "{}"}}}}"#,
+            strong, INSECURE_DEFAULT_JWT_SECRET
+        );
+
+        let errors = check_jwt_keys(&keys, "production");
+        let vars: Vec<String> = errors
+            .iter()
+            .map(|e| match e {
+                ConfigError::WeakSecret { var, .. } => var.clone(),
+                other => panic!("unexpected error: {}", other),
+            })
+            .collect();
+        assert_eq!(vars, vec!["JWT_KEYS[dev]", "JWT_KEYS[old]"]);
+
+        // The default is tolerated in development, but still too short
+        let errors = check_jwt_keys(&keys, "development");
+        assert_eq!(errors.len(), 2);
+
+        assert!(matches!(
+            check_jwt_keys("not json", "production").as_slice(),
+            [ConfigError::InvalidValue { .. }]
+        ));
+    }
+
+    #[test]
+    fn test_format_config_errors_lists_every_problem() {
+        let errors = vec![
+            ConfigError::MissingVariable("PORT".to_string()),
+            ConfigError::InvalidPubkey {
+                var: "ENERGY_TOKEN_MINT".to_string(),
+                value: "not-a-key".to_string(),
+            },
+        ];
+        let report = format_config_errors(&errors);
+
+        assert_eq!(report.lines().count(), 2);
+        assert!(report.contains("PORT"));
+        assert!(report.contains("ENERGY_TOKEN_MINT=\"not-a-key\""));
+    }
+}
@@ -401,6 +401,9 @@ pub enum ApiError {
     #[error("Blockchain error: {0}")]
     Blockchain(String),
 
+    #[error("Token transfer failed: {0}")]
+    Settlement(#[from] crate::services::blockchain::SettlementError),
+
     #[error("External service error: {0}")]
     ExternalService(String),
 
@@ -601,7 +604,7 @@ impl ApiError {
             ApiError::Conflict(_) => ErrorCode::Conflict,
             ApiError::Database(_) => ErrorCode::QueryFailed,
             ApiError::Redis(_) => ErrorCode::ExternalServiceError,
-            ApiError::Blockchain(_) => ErrorCode::BlockchainTransactionFailed,
+            ApiError::Blockchain(_) | ApiError::Settlement(_) => ErrorCode::BlockchainTransactionFailed,
             ApiError::ExternalService(_) => ErrorCode::ExternalServiceError,
             ApiError::Configuration(_) => ErrorCode::ConfigurationError,
             ApiError::Internal(_) => ErrorCode::InternalServerError,
@@ -648,7 +651,12 @@ impl ApiError {
             | ApiError::ValidationWithField { .. }
             | ApiError::WithCode(ErrorCode::InvalidInput, _)
             | ApiError::WithCode(ErrorCode::InvalidWalletAddress, _)
-            | ApiError::WithCode(ErrorCode::InvalidAmount, _) => StatusCode::BAD_REQUEST,
+            | ApiError::WithCode(ErrorCode::InvalidAmount, _)
+            | ApiError::WithCodeAndDetails(
+                ErrorCode::InvalidInput | ErrorCode::InvalidAmount | ErrorCode::InsufficientBalance,
+                _,
+                _,
+            ) => StatusCode::BAD_REQUEST,
 
             ApiError::NotFound(_) | ApiError::WithCode(ErrorCode::NotFound, _) => {
                 StatusCode::NOT_FOUND
@@ -659,6 +667,7 @@ impl ApiError {
             | ApiError::WithCode(ErrorCode::AlreadyExists, _) => StatusCode::CONFLICT,
 
             ApiError::Blockchain(_)
+            | ApiError::Settlement(_)
             | ApiError::ExternalService(_)
             | ApiError::WithCode(ErrorCode::BlockchainConnectionFailed, _)
             | ApiError::WithCode(ErrorCode::ExternalServiceUnavailable, _)
@@ -678,6 +687,10 @@ impl ApiError {
 
     /// Log error with appropriate level
     fn log_error(&self, request_id: &str) {
+        if matches!(self, ApiError::Database(sqlx::Error::PoolTimedOut)) {
+            crate::middleware::metrics::track_db_pool_acquire_timeout();
+        }
+
         match self.status_code() {
             status if status.is_server_error() => {
                 error!(
@@ -700,7 +713,10 @@ impl ApiError {
 
 impl IntoResponse for ApiError {
     fn into_response(self) -> Response {
-        let request_id = Uuid::new_v4().to_string();
+        // Same id as the request's log lines and x-request-id header
+        let request_id = crate::middleware::TraceId::current()
+            .map(|trace_id| trace_id.0)
+            .unwrap_or_else(|| Uuid::new_v4().to_string());
         let status = self.status_code();
         let code = self.error_code();
 
@@ -743,7 +759,7 @@ impl ApiError {
             ApiError::Validation(_) => "validation_error",
             ApiError::Database(_) => "database_error",
             ApiError::Redis(_) => "cache_error",
-            ApiError::Blockchain(_) => "blockchain_error",
+            ApiError::Blockchain(_) | ApiError::Settlement(_) => "blockchain_error",
             ApiError::ExternalService(_) => "external_service_error",
             ApiError::Configuration(_) => "configuration_error",
             ApiError::NotFound(_) => "not_found",
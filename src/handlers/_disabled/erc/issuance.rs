@@ -208,3 +208,4 @@ pub async fn issue_certificate(
         metadata: updated_certificate.metadata,
     }))
 }
+
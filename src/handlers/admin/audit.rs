@@ -0,0 +1,157 @@
+use axum::{
+    extract::{Query, State},
+    Json,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tracing::{info, instrument};
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+use crate::{
+    error::{ApiError, Result},
+    services::audit_logger::{AuditEventFilter, AuditEventRecord},
+    utils::{PaginationMeta, PaginationParams, SortOrder},
+    AppState,
+};
+
+/// Largest page the audit log endpoint will return
+const MAX_AUDIT_PAGE_SIZE: u32 = 100;
+
+/// Query parameters for searching the audit log
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct AuditLogQuery {
+    /// Only events for this user
+    pub user_id: Option<Uuid>,
+
+    /// Only events of this type, e.g. "order_created"
+    pub event_type: Option<String>,
+
+    /// Events at or after this time (ISO 8601)
+    pub from: Option<DateTime<Utc>>,
+
+    /// Events before this time (ISO 8601)
+    pub to: Option<DateTime<Utc>>,
+
+    /// Only events referring to this order or settlement
+    pub entity_id: Option<Uuid>,
+
+    /// Page number (1-indexed)
+    #[serde(default = "default_page")]
+    pub page: u32,
+
+    /// Number of items per page (max 100)
+    #[serde(default = "default_page_size")]
+    pub page_size: u32,
+}
+
+fn default_page() -> u32 {
+    1
+}
+
+fn default_page_size() -> u32 {
+    50
+}
+
+impl AuditLogQuery {
+    fn pagination(&self) -> PaginationParams {
+        PaginationParams {
+            page: self.page.max(1),
+            page_size: self.page_size.clamp(1, MAX_AUDIT_PAGE_SIZE),
+            sort_by: None,
+            sort_order: SortOrder::Desc,
+        }
+    }
+
+    fn filter(&self) -> AuditEventFilter {
+        AuditEventFilter {
+            user_id: self.user_id,
+            event_type: self.event_type.clone().filter(|t| !t.trim().is_empty()),
+            from: self.from,
+            to: self.to,
+            entity_id: self.entity_id,
+        }
+    }
+}
+
+/// A page of audit log entries
+#[derive(Debug, Serialize, ToSchema)]
+pub struct AuditLogResponse {
+    pub data: Vec<AuditEventRecord>,
+    pub pagination: PaginationMeta,
+}
+
+/// Search the audit log
+///
+/// GET /api/v1/admin/audit
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/audit",
+    tag = "admin",
+    params(AuditLogQuery),
+    responses(
+        (status = 200, description = "Matching audit events, newest first", body = AuditLogResponse),
+        (status = 400, description = "Invalid date range"),
+        (status = 403, description = "Forbidden - Admin only")
+    ),
+    security(("bearer_auth" = []))
+)]
+#[instrument(skip(state))]
+pub async fn search_audit_log(
+    State(state): State<AppState>,
+    Query(query): Query<AuditLogQuery>,
+) -> Result<Json<AuditLogResponse>> {
+    if let (Some(from), Some(to)) = (query.from, query.to) {
+        if from >= to {
+            return Err(ApiError::BadRequest(
+                "'from' must be earlier than 'to'".to_string(),
+            ));
+        }
+    }
+
+    let pagination = query.pagination();
+    let filter = query.filter();
+    info!("📜 Admin: Searching audit log {:?}", filter);
+
+    let (data, total) = state
+        .audit_logger
+        .search_events(&filter, pagination.limit(), pagination.offset())
+        .await?;
+
+    Ok(Json(AuditLogResponse {
+        data,
+        pagination: PaginationMeta::new(&pagination, total),
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn query(page: u32, page_size: u32) -> AuditLogQuery {
+        AuditLogQuery {
+            user_id: None,
+            event_type: Some("  ".to_string()),
+            from: None,
+            to: None,
+            entity_id: None,
+            page,
+            page_size,
+        }
+    }
+
+    #[test]
+    fn test_page_size_is_capped() {
+        let pagination = query(0, 10_000).pagination();
+        assert_eq!(pagination.page, 1);
+        assert_eq!(pagination.limit(), MAX_AUDIT_PAGE_SIZE as i64);
+
+        assert_eq!(query(3, 0).pagination().limit(), 1);
+        assert_eq!(query(3, 20).pagination().offset(), 40);
+    }
+
+    #[test]
+    fn test_blank_event_type_is_ignored() {
+        assert!(query(1, 20).filter().event_type.is_none());
+    }
+}
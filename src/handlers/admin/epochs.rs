@@ -0,0 +1,178 @@
+use axum::{
+    extract::{Path, State},
+    Json,
+};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use tracing::{error, info, instrument};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::{
+    auth::middleware::AuthenticatedUser,
+    database::schema::types::EpochStatus,
+    error::{ApiError, Result},
+    services::market_clearing::{HaltResolution, HaltResolutionResult, PriceBandStatus},
+    AppState,
+};
+
+/// Result of a manual epoch clearing run
+#[derive(Debug, Serialize, ToSchema)]
+pub struct EpochClearResponse {
+    pub epoch_id: Uuid,
+    /// Number of order matches created
+    pub matches: usize,
+    /// Total matched volume in kWh
+    pub total_volume: Decimal,
+}
+
+/// Force order matching for an epoch
+///
+/// POST /api/v1/admin/epochs/{id}/clear
+#[utoipa::path(
+    post,
+    path = "/api/v1/admin/epochs/{id}/clear",
+    tag = "admin",
+    params(
+        ("id" = Uuid, Path, description = "Market epoch ID")
+    ),
+    responses(
+        (status = 200, description = "Epoch cleared", body = EpochClearResponse),
+        (status = 404, description = "Epoch not found"),
+        (status = 409, description = "Epoch already settled, or halted by the price band until resolved"),
+        (status = 403, description = "Forbidden - Admin only")
+    ),
+    security(("bearer_auth" = []))
+)]
+#[instrument(skip(state))]
+pub async fn clear_epoch(
+    State(state): State<AppState>,
+    Path(epoch_id): Path<Uuid>,
+) -> Result<Json<EpochClearResponse>> {
+    let epoch = state
+        .market_clearing
+        .get_epoch_by_id(epoch_id)
+        .await
+        .map_err(|e| {
+            error!("Failed to load epoch {}: {}", epoch_id, e);
+            ApiError::Internal("Failed to load epoch".to_string())
+        })?
+        .ok_or_else(|| ApiError::NotFound(format!("Epoch {} not found", epoch_id)))?;
+
+    if epoch.status == EpochStatus::Settled {
+        return Err(ApiError::Conflict(format!(
+            "Epoch {} is already settled",
+            epoch_id
+        )));
+    }
+
+    let halted = state.market_clearing.is_epoch_halted(epoch_id).await.map_err(|e| {
+        error!("Failed to load epoch {}: {}", epoch_id, e);
+        ApiError::Internal("Failed to load epoch".to_string())
+    })?;
+    if halted {
+        return Err(ApiError::Conflict(format!(
+            "Clearing for epoch {} is halted by the price band; resolve it via /epochs/{}/resolve-halt",
+            epoch_id, epoch_id
+        )));
+    }
+
+    info!("🧹 Admin: Forcing clearing for epoch {} ({})", epoch_id, epoch.status);
+
+    let matches = state
+        .market_clearing
+        .run_order_matching(epoch_id)
+        .await
+        .map_err(|e| {
+            error!("Failed to clear epoch {}: {}", epoch_id, e);
+            ApiError::Internal(format!("Failed to clear epoch: {}", e))
+        })?;
+
+    let total_volume = matches.iter().map(|m| m.matched_amount).sum();
+
+    Ok(Json(EpochClearResponse {
+        epoch_id,
+        matches: matches.len(),
+        total_volume,
+    }))
+}
+
+/// Price band (circuit breaker) configuration, the current reference price
+/// and recently halted epochs
+///
+/// GET /api/v1/admin/price-band
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/price-band",
+    tag = "admin",
+    responses(
+        (status = 200, description = "Price band status", body = PriceBandStatus),
+        (status = 403, description = "Forbidden - Admin only")
+    ),
+    security(("bearer_auth" = []))
+)]
+#[instrument(skip(state))]
+pub async fn get_price_band(State(state): State<AppState>) -> Result<Json<PriceBandStatus>> {
+    let status = state.market_clearing.price_band_status().await.map_err(|e| {
+        error!("Failed to load price band status: {}", e);
+        ApiError::Internal("Failed to load price band status".to_string())
+    })?;
+    Ok(Json(status))
+}
+
+/// How to resolve an epoch halted by the price band
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct ResolveHaltRequest {
+    pub resolution: HaltResolution,
+    /// Recorded in the logs and, for cancelled orders, the audit log
+    pub reason: String,
+}
+
+/// Resume clearing for a halted epoch without the price band, or cancel its
+/// open orders and release their escrow
+///
+/// POST /api/v1/admin/epochs/{id}/resolve-halt
+#[utoipa::path(
+    post,
+    path = "/api/v1/admin/epochs/{id}/resolve-halt",
+    tag = "admin",
+    params(
+        ("id" = Uuid, Path, description = "Market epoch ID")
+    ),
+    request_body = ResolveHaltRequest,
+    responses(
+        (status = 200, description = "Halt resolved", body = HaltResolutionResult),
+        (status = 400, description = "Missing reason"),
+        (status = 409, description = "Epoch is not halted"),
+        (status = 403, description = "Forbidden - Admin only")
+    ),
+    security(("bearer_auth" = []))
+)]
+#[instrument(skip(state, user))]
+pub async fn resolve_epoch_halt(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+    Path(epoch_id): Path<Uuid>,
+    Json(payload): Json<ResolveHaltRequest>,
+) -> Result<Json<HaltResolutionResult>> {
+    let reason = payload.reason.trim();
+    if reason.is_empty() {
+        return Err(ApiError::BadRequest("A reason is required".to_string()));
+    }
+
+    info!(
+        "🚦 Admin {}: Resolving price band halt on epoch {} ({:?})",
+        user.0.sub, epoch_id, payload.resolution
+    );
+
+    let result = state
+        .market_clearing
+        .resolve_price_band_halt(epoch_id, user.0.sub, payload.resolution, reason)
+        .await
+        .map_err(|e| match e.downcast::<ApiError>() {
+            Ok(api_error) => api_error,
+            Err(e) => ApiError::Internal(format!("Failed to resolve halt: {}", e)),
+        })?;
+
+    Ok(Json(result))
+}
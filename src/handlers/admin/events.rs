@@ -0,0 +1,54 @@
+use axum::{extract::State, Json};
+use tracing::{error, instrument};
+
+use crate::{
+    error::{ApiError, Result},
+    services::event_processor::{EventProcessorStats, ReplayStatus},
+    AppState,
+};
+
+/// Get event processor confirmation statistics
+///
+/// GET /api/v1/admin/event-processor/stats
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/event-processor/stats",
+    tag = "admin",
+    responses(
+        (status = 200, description = "Event processor statistics", body = EventProcessorStats),
+        (status = 403, description = "Forbidden - Admin only"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(("bearer_auth" = []))
+)]
+#[instrument(skip(state))]
+pub async fn get_event_processor_stats(
+    State(state): State<AppState>,
+) -> Result<Json<EventProcessorStats>> {
+    let stats = state.event_processor.get_stats().await.map_err(|e| {
+        error!("Failed to get event processor stats: {}", e);
+        ApiError::Internal("Failed to get event processor stats".to_string())
+    })?;
+
+    Ok(Json(stats))
+}
+
+/// Get the status of the current or last event replay job
+///
+/// GET /api/v1/admin/event-processor/replay
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/event-processor/replay",
+    tag = "admin",
+    responses(
+        (status = 200, description = "Replay status, null if no replay has run", body = Option<ReplayStatus>),
+        (status = 403, description = "Forbidden - Admin only")
+    ),
+    security(("bearer_auth" = []))
+)]
+#[instrument(skip(state))]
+pub async fn get_replay_status(
+    State(state): State<AppState>,
+) -> Result<Json<Option<ReplayStatus>>> {
+    Ok(Json(state.event_processor.get_replay_status()))
+}
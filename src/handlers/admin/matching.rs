@@ -0,0 +1,76 @@
+use axum::{extract::State, Json};
+use tracing::{info, instrument};
+
+use crate::{
+    error::Result,
+    services::order_matching_engine::types::MatchingEngineStatus,
+    AppState,
+};
+
+/// Get the order matching engine status
+///
+/// GET /api/v1/admin/matching/status
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/matching/status",
+    tag = "admin",
+    responses(
+        (status = 200, description = "Matching engine status", body = MatchingEngineStatus),
+        (status = 403, description = "Forbidden - Admin only")
+    ),
+    security(("bearer_auth" = []))
+)]
+#[instrument(skip(state))]
+pub async fn get_matching_status(
+    State(state): State<AppState>,
+) -> Result<Json<MatchingEngineStatus>> {
+    Ok(Json(state.market_clearing_engine.status().await))
+}
+
+/// Pause the order matching engine
+///
+/// POST /api/v1/admin/matching/pause
+///
+/// Returns once any in-progress matching cycle has finished.
+#[utoipa::path(
+    post,
+    path = "/api/v1/admin/matching/pause",
+    tag = "admin",
+    responses(
+        (status = 200, description = "Matching engine paused", body = MatchingEngineStatus),
+        (status = 403, description = "Forbidden - Admin only")
+    ),
+    security(("bearer_auth" = []))
+)]
+#[instrument(skip(state))]
+pub async fn pause_matching(
+    State(state): State<AppState>,
+) -> Result<Json<MatchingEngineStatus>> {
+    if state.market_clearing_engine.stop().await {
+        info!("⏸️ Order matching paused by admin");
+    }
+    Ok(Json(state.market_clearing_engine.status().await))
+}
+
+/// Resume the order matching engine
+///
+/// POST /api/v1/admin/matching/resume
+#[utoipa::path(
+    post,
+    path = "/api/v1/admin/matching/resume",
+    tag = "admin",
+    responses(
+        (status = 200, description = "Matching engine resumed", body = MatchingEngineStatus),
+        (status = 403, description = "Forbidden - Admin only")
+    ),
+    security(("bearer_auth" = []))
+)]
+#[instrument(skip(state))]
+pub async fn resume_matching(
+    State(state): State<AppState>,
+) -> Result<Json<MatchingEngineStatus>> {
+    if state.market_clearing_engine.start().await {
+        info!("▶️ Order matching resumed by admin");
+    }
+    Ok(Json(state.market_clearing_engine.status().await))
+}
@@ -0,0 +1,25 @@
+//! Admin-only handlers.
+//!
+//! Routes are assembled in `router::admin` behind `require_admin_role`.
+
+pub mod audit;
+pub mod epochs;
+pub mod events;
+pub mod matching;
+pub mod orders;
+pub mod reconciliation;
+pub mod revenue;
+pub mod settlements;
+pub mod trace;
+pub mod treasury;
+
+pub use audit::*;
+pub use epochs::*;
+pub use events::*;
+pub use matching::*;
+pub use orders::*;
+pub use reconciliation::*;
+pub use revenue::*;
+pub use settlements::*;
+pub use trace::*;
+pub use treasury::*;
@@ -0,0 +1,68 @@
+use axum::{
+    extract::{Path, State},
+    Json,
+};
+use serde::Deserialize;
+use tracing::{info, instrument};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::{
+    auth::middleware::AuthenticatedUser,
+    error::{ApiError, Result},
+    services::market_clearing::ForceCancelResult,
+    AppState,
+};
+
+/// Why an admin is force-cancelling an order
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct ForceCancelRequest {
+    /// Recorded in the audit log
+    pub reason: String,
+}
+
+/// Cancel a stuck order regardless of owner and refund its unfilled escrow
+///
+/// POST /api/v1/admin/orders/{id}/force-cancel
+#[utoipa::path(
+    post,
+    path = "/api/v1/admin/orders/{id}/force-cancel",
+    tag = "admin",
+    params(
+        ("id" = Uuid, Path, description = "Trading order ID")
+    ),
+    request_body = ForceCancelRequest,
+    responses(
+        (status = 200, description = "Order cancelled and escrow refunded", body = ForceCancelResult),
+        (status = 400, description = "Missing reason"),
+        (status = 404, description = "Order not found"),
+        (status = 409, description = "Order is filled, settled or already closed"),
+        (status = 403, description = "Forbidden - Admin only")
+    ),
+    security(("bearer_auth" = []))
+)]
+#[instrument(skip(state, user))]
+pub async fn force_cancel_order(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+    Path(order_id): Path<Uuid>,
+    Json(payload): Json<ForceCancelRequest>,
+) -> Result<Json<ForceCancelResult>> {
+    let reason = payload.reason.trim();
+    if reason.is_empty() {
+        return Err(ApiError::BadRequest("A reason is required".to_string()));
+    }
+
+    info!("🛑 Admin {}: Force-cancelling order {}", user.0.sub, order_id);
+
+    let result = state
+        .market_clearing
+        .force_cancel_order(order_id, user.0.sub, reason)
+        .await
+        .map_err(|e| match e.downcast::<ApiError>() {
+            Ok(api_error) => api_error,
+            Err(e) => ApiError::Internal(format!("Failed to force-cancel order: {}", e)),
+        })?;
+
+    Ok(Json(result))
+}
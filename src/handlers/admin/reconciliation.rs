@@ -0,0 +1,37 @@
+use axum::{
+    extract::{Path, State},
+    Json,
+};
+use tracing::{info, instrument};
+use uuid::Uuid;
+
+use crate::{error::Result, services::reconciliation::UserReconciliation, AppState};
+
+/// Compare a user's on-chain energy balance with the DB-derived balance
+///
+/// GET /api/v1/admin/reconciliation/{user_id}
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/reconciliation/{user_id}",
+    tag = "admin",
+    params(
+        ("user_id" = Uuid, Path, description = "User to reconcile")
+    ),
+    responses(
+        (status = 200, description = "Balance reconciliation for the user", body = UserReconciliation),
+        (status = 400, description = "User has no wallet connected"),
+        (status = 403, description = "Forbidden - Admin only"),
+        (status = 404, description = "User not found")
+    ),
+    security(("bearer_auth" = []))
+)]
+#[instrument(skip(state))]
+pub async fn reconcile_user_balance(
+    State(state): State<AppState>,
+    Path(user_id): Path<Uuid>,
+) -> Result<Json<UserReconciliation>> {
+    info!("⚖️ Admin: Reconciling balance for user {}", user_id);
+
+    let report = state.reconciliation.reconcile_user(user_id).await?;
+    Ok(Json(report))
+}
@@ -0,0 +1,99 @@
+use axum::{
+    extract::{Query, State},
+    Json,
+};
+use chrono::{DateTime, Duration, Utc};
+use serde::Deserialize;
+use tracing::{info, instrument};
+
+use crate::{
+    error::Result,
+    services::settlement::{RevenueByRecipient, RevenueReconciliation},
+    AppState,
+};
+
+/// Default reconciliation window when `from` is omitted
+const DEFAULT_RECONCILIATION_DAYS: i64 = 30;
+
+/// Default window of the revenue-by-recipient report when `from` is omitted
+const DEFAULT_RECIPIENT_REPORT_DAYS: i64 = 30;
+
+#[derive(Debug, Deserialize)]
+pub struct ReconcileRevenueQuery {
+    pub from: Option<DateTime<Utc>>,
+    pub to: Option<DateTime<Utc>>,
+}
+
+/// Reconcile platform revenue against completed settlements
+///
+/// GET /api/v1/admin/revenue/reconcile
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/revenue/reconcile",
+    tag = "admin",
+    params(
+        ("from" = Option<String>, Query, description = "Window start (RFC 3339), defaults to 30 days ago"),
+        ("to" = Option<String>, Query, description = "Window end (RFC 3339), defaults to now")
+    ),
+    responses(
+        (status = 200, description = "Revenue reconciliation report", body = RevenueReconciliation),
+        (status = 400, description = "Invalid reconciliation window"),
+        (status = 403, description = "Forbidden - Admin only")
+    ),
+    security(("bearer_auth" = []))
+)]
+#[instrument(skip(state))]
+pub async fn reconcile_revenue(
+    State(state): State<AppState>,
+    Query(query): Query<ReconcileRevenueQuery>,
+) -> Result<Json<RevenueReconciliation>> {
+    let to = query.to.unwrap_or_else(Utc::now);
+    let from = query
+        .from
+        .unwrap_or_else(|| to - Duration::days(DEFAULT_RECONCILIATION_DAYS));
+
+    info!("🧾 Admin: Reconciling platform revenue from {} to {}", from, to);
+
+    let report = state.settlement.reconcile_revenue(from, to).await?;
+    Ok(Json(report))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RevenueByRecipientQuery {
+    pub from: Option<DateTime<Utc>>,
+    pub to: Option<DateTime<Utc>>,
+}
+
+/// Platform revenue totalled per recipient
+///
+/// GET /api/v1/admin/revenue/by-recipient
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/revenue/by-recipient",
+    tag = "admin",
+    params(
+        ("from" = Option<String>, Query, description = "Window start (RFC 3339), defaults to 30 days ago"),
+        ("to" = Option<String>, Query, description = "Window end (RFC 3339), defaults to now")
+    ),
+    responses(
+        (status = 200, description = "Revenue by recipient and the splits in effect", body = RevenueByRecipient),
+        (status = 400, description = "Invalid window"),
+        (status = 403, description = "Forbidden - Admin only")
+    ),
+    security(("bearer_auth" = []))
+)]
+#[instrument(skip(state))]
+pub async fn get_revenue_by_recipient(
+    State(state): State<AppState>,
+    Query(query): Query<RevenueByRecipientQuery>,
+) -> Result<Json<RevenueByRecipient>> {
+    let to = query.to.unwrap_or_else(Utc::now);
+    let from = query
+        .from
+        .unwrap_or_else(|| to - Duration::days(DEFAULT_RECIPIENT_REPORT_DAYS));
+
+    info!("💰 Admin: Revenue by recipient from {} to {}", from, to);
+
+    let report = state.settlement.revenue_by_recipient(from, to).await?;
+    Ok(Json(report))
+}
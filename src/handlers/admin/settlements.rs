@@ -0,0 +1,258 @@
+use axum::{
+    extract::{Path, State},
+    Json,
+};
+use serde::Serialize;
+use tracing::{info, instrument};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::{
+    auth::middleware::AuthenticatedUser,
+    error::Result,
+    services::{
+        audit_logger::AuditEventRecord,
+        settlement::{RetryOverrides, SettlementDeadLetter, SettlementPathReport},
+    },
+    AppState,
+};
+
+/// Result of a manual settlement flush
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SettlementFlushResponse {
+    /// Settlements completed successfully in this run
+    pub processed: usize,
+}
+
+/// Result of replaying a dead-lettered settlement
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SettlementReplayResponse {
+    pub settlement_id: Uuid,
+    /// Status after the replay; `pending` until the next settlement run
+    pub status: String,
+}
+
+/// Result of expediting a pending settlement
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SettlementExpediteResponse {
+    pub settlement_id: Uuid,
+    /// Always true; the settlement goes out on the next run, in or out of a window
+    pub urgent: bool,
+    pub status: String,
+}
+
+/// Dry-run the on-chain settlement path for a settlement
+///
+/// POST /api/v1/admin/settlements/{id}/validate
+#[utoipa::path(
+    post,
+    path = "/api/v1/admin/settlements/{id}/validate",
+    tag = "admin",
+    params(
+        ("id" = Uuid, Path, description = "Settlement ID")
+    ),
+    responses(
+        (status = 200, description = "Per-step validation report", body = SettlementPathReport),
+        (status = 404, description = "Settlement not found"),
+        (status = 403, description = "Forbidden - Admin only")
+    ),
+    security(("bearer_auth" = []))
+)]
+#[instrument(skip(state))]
+pub async fn validate_settlement_path(
+    State(state): State<AppState>,
+    Path(settlement_id): Path<Uuid>,
+) -> Result<Json<SettlementPathReport>> {
+    info!("🧪 Admin: Validating settlement path for {}", settlement_id);
+
+    let report = state.settlement.validate_settlement_path(settlement_id).await?;
+    Ok(Json(report))
+}
+
+/// Process all pending settlements now instead of waiting for the background
+/// interval, including outside the settlement windows
+///
+/// POST /api/v1/admin/settlements/process
+#[utoipa::path(
+    post,
+    path = "/api/v1/admin/settlements/process",
+    tag = "admin",
+    responses(
+        (status = 200, description = "Pending settlements processed", body = SettlementFlushResponse),
+        (status = 403, description = "Forbidden - Admin only")
+    ),
+    security(("bearer_auth" = []))
+)]
+#[instrument(skip(state))]
+pub async fn process_pending_settlements(
+    State(state): State<AppState>,
+) -> Result<Json<SettlementFlushResponse>> {
+    info!("🧹 Admin: Flushing pending settlements");
+
+    let processed = state.settlement.drain_pending_settlements().await?;
+    Ok(Json(SettlementFlushResponse { processed }))
+}
+
+/// Flag a pending settlement urgent so it is executed outside the settlement windows
+///
+/// POST /api/v1/admin/settlements/{id}/expedite
+#[utoipa::path(
+    post,
+    path = "/api/v1/admin/settlements/{id}/expedite",
+    tag = "admin",
+    params(
+        ("id" = Uuid, Path, description = "Settlement ID")
+    ),
+    responses(
+        (status = 200, description = "Settlement flagged urgent", body = SettlementExpediteResponse),
+        (status = 400, description = "Settlement is not pending"),
+        (status = 404, description = "Settlement not found"),
+        (status = 403, description = "Forbidden - Admin only")
+    ),
+    security(("bearer_auth" = []))
+)]
+#[instrument(skip(state, user))]
+pub async fn expedite_settlement(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+    Path(settlement_id): Path<Uuid>,
+) -> Result<Json<SettlementExpediteResponse>> {
+    info!("⚡ Admin {}: Expediting settlement {}", user.0.sub, settlement_id);
+
+    let settlement = state.settlement.mark_urgent(settlement_id).await?;
+    Ok(Json(SettlementExpediteResponse {
+        settlement_id: settlement.id,
+        urgent: true,
+        status: settlement.status.to_string(),
+    }))
+}
+
+/// Audit trail for one settlement: started, completed/failed and escrow events
+///
+/// GET /api/v1/admin/settlements/{id}/audit
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/settlements/{id}/audit",
+    tag = "admin",
+    params(
+        ("id" = Uuid, Path, description = "Settlement ID")
+    ),
+    responses(
+        (status = 200, description = "Settlement audit events, oldest first", body = Vec<AuditEventRecord>),
+        (status = 403, description = "Forbidden - Admin only")
+    ),
+    security(("bearer_auth" = []))
+)]
+#[instrument(skip(state))]
+pub async fn get_settlement_audit_trail(
+    State(state): State<AppState>,
+    Path(settlement_id): Path<Uuid>,
+) -> Result<Json<Vec<AuditEventRecord>>> {
+    info!("📜 Admin: Fetching audit trail for settlement {}", settlement_id);
+
+    let events = state.audit_logger.get_settlement_events(settlement_id).await?;
+    Ok(Json(events))
+}
+
+/// Permanently failed settlements waiting for a manual replay
+///
+/// GET /api/v1/admin/settlements/dead-letters
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/settlements/dead-letters",
+    tag = "admin",
+    responses(
+        (status = 200, description = "Dead-lettered settlements, oldest first", body = Vec<SettlementDeadLetter>),
+        (status = 403, description = "Forbidden - Admin only")
+    ),
+    security(("bearer_auth" = []))
+)]
+#[instrument(skip(state))]
+pub async fn list_dead_letter_settlements(
+    State(state): State<AppState>,
+) -> Result<Json<Vec<SettlementDeadLetter>>> {
+    info!("🪦 Admin: Listing dead-lettered settlements");
+
+    let dead_letters = state.settlement.list_permanent_failures().await?;
+    Ok(Json(dead_letters))
+}
+
+/// Requeue a permanently failed settlement once its root cause is fixed
+///
+/// POST /api/v1/admin/settlements/{id}/replay
+#[utoipa::path(
+    post,
+    path = "/api/v1/admin/settlements/{id}/replay",
+    tag = "admin",
+    params(
+        ("id" = Uuid, Path, description = "Settlement ID")
+    ),
+    responses(
+        (status = 200, description = "Settlement requeued as pending", body = SettlementReplayResponse),
+        (status = 400, description = "Settlement is not permanently failed"),
+        (status = 404, description = "Settlement not found"),
+        (status = 403, description = "Forbidden - Admin only")
+    ),
+    security(("bearer_auth" = []))
+)]
+#[instrument(skip(state, user))]
+pub async fn replay_settlement(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+    Path(settlement_id): Path<Uuid>,
+) -> Result<Json<SettlementReplayResponse>> {
+    info!("♻️ Admin {}: Replaying settlement {}", user.0.sub, settlement_id);
+
+    let settlement = state.settlement.force_retry(settlement_id, user.0.sub).await?;
+    Ok(Json(SettlementReplayResponse {
+        settlement_id: settlement.id,
+        status: settlement.status.to_string(),
+    }))
+}
+
+/// Retry classification overrides currently in effect
+///
+/// GET /api/v1/admin/settlements/retry-overrides
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/settlements/retry-overrides",
+    tag = "admin",
+    responses(
+        (status = 200, description = "Override patterns, environment first", body = RetryOverrides),
+        (status = 403, description = "Forbidden - Admin only")
+    ),
+    security(("bearer_auth" = []))
+)]
+#[instrument(skip(state))]
+pub async fn get_retry_overrides(State(state): State<AppState>) -> Result<Json<RetryOverrides>> {
+    info!("🔁 Admin: Listing settlement retry overrides");
+
+    Ok(Json(state.settlement.retry_overrides()))
+}
+
+/// Reload retry overrides from the `settlement_retry_overrides` table
+///
+/// Takes effect on this replica immediately; the others pick the change up
+/// on their next periodic refresh (`SETTLEMENT_RETRY_OVERRIDES_REFRESH_SECS`).
+///
+/// POST /api/v1/admin/settlements/retry-overrides/reload
+#[utoipa::path(
+    post,
+    path = "/api/v1/admin/settlements/retry-overrides/reload",
+    tag = "admin",
+    responses(
+        (status = 200, description = "Override patterns now in effect", body = RetryOverrides),
+        (status = 403, description = "Forbidden - Admin only")
+    ),
+    security(("bearer_auth" = []))
+)]
+#[instrument(skip(state, user))]
+pub async fn reload_retry_overrides(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+) -> Result<Json<RetryOverrides>> {
+    info!("🔁 Admin {}: Reloading settlement retry overrides", user.0.sub);
+
+    let overrides = state.settlement.reload_retry_overrides().await?;
+    Ok(Json(overrides))
+}
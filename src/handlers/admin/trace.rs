@@ -0,0 +1,56 @@
+use axum::{
+    extract::{Path, State},
+    Json,
+};
+use tracing::{error, instrument};
+use uuid::Uuid;
+
+use crate::{
+    error::{ApiError, Result},
+    services::event_processor::{CorrelationTrace, TraceEntry},
+    AppState,
+};
+
+/// Timeline of a meter reading from ingestion through minting to on-chain confirmation
+///
+/// GET /api/v1/admin/trace/{correlation_id}
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/trace/{correlation_id}",
+    tag = "admin",
+    params(
+        ("correlation_id" = Uuid, Path, description = "Correlation id returned when the reading was ingested")
+    ),
+    responses(
+        (status = 200, description = "Reading, mint and confirmation timeline", body = CorrelationTrace),
+        (status = 403, description = "Forbidden - Admin only"),
+        (status = 404, description = "Nothing recorded for this correlation id")
+    ),
+    security(("bearer_auth" = []))
+)]
+#[instrument(skip(state))]
+pub async fn get_correlation_trace(
+    State(state): State<AppState>,
+    Path(correlation_id): Path<Uuid>,
+) -> Result<Json<CorrelationTrace>> {
+    let mut entries = state
+        .event_processor
+        .get_correlation_entries(correlation_id)
+        .await
+        .map_err(|e| {
+            error!("Failed to load trace {}: {}", correlation_id, e);
+            ApiError::Internal("Failed to load trace".to_string())
+        })?;
+
+    let audit_events = state.audit_logger.get_correlation_events(correlation_id).await?;
+    entries.extend(audit_events.into_iter().map(TraceEntry::from));
+
+    if entries.is_empty() {
+        return Err(ApiError::NotFound(format!(
+            "Nothing recorded for correlation id {}",
+            correlation_id
+        )));
+    }
+
+    Ok(Json(CorrelationTrace::new(correlation_id, entries)))
+}
@@ -0,0 +1,31 @@
+use axum::{extract::State, Json};
+use tracing::{info, instrument};
+
+use crate::{
+    error::Result,
+    services::treasury::TreasurySweepReport,
+    AppState,
+};
+
+/// Sweep unswept platform revenue to the recipients' treasury wallets now
+///
+/// POST /api/v1/admin/treasury/sweep
+#[utoipa::path(
+    post,
+    path = "/api/v1/admin/treasury/sweep",
+    tag = "admin",
+    responses(
+        (status = 200, description = "Outcome per revenue recipient", body = TreasurySweepReport),
+        (status = 403, description = "Forbidden - Admin only")
+    ),
+    security(("bearer_auth" = []))
+)]
+#[instrument(skip(state))]
+pub async fn sweep_treasury(
+    State(state): State<AppState>,
+) -> Result<Json<TreasurySweepReport>> {
+    info!("🏦 Admin: Sweeping platform revenue to treasury wallets");
+
+    let report = state.treasury.sweep_revenue().await?;
+    Ok(Json(report))
+}
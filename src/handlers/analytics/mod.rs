@@ -4,6 +4,7 @@ pub mod types;
 pub mod admin;
 pub mod zones;
 pub mod zone_rates;
+pub mod sustainability;
 
 use axum::{routing::{get, post, put, delete}, Router, middleware::from_fn};
 use crate::AppState;
@@ -16,6 +17,7 @@ pub fn routes() -> Router<AppState> {
         .route("/my-history", get(user::get_user_wealth_history))
         .route("/transactions", get(user::get_user_transactions))
         .route("/zones/trading", get(zones::get_zone_trading_stats))
+        .route("/sustainability", get(sustainability::get_sustainability_report))
         .route("/admin/stats", get(admin::get_admin_stats).layer(from_fn(require_admin_role)))
         .route("/admin/activity", get(admin::get_admin_activity).layer(from_fn(require_admin_role)))
         .route("/admin/health", get(admin::get_system_health).layer(from_fn(require_admin_role)))
@@ -23,7 +25,9 @@ pub fn routes() -> Router<AppState> {
         // Zone rates CRUD
         .route("/zone-rates", get(zone_rates::list_zone_rates))
         .route("/zone-rates", post(zone_rates::create_zone_rate).layer(from_fn(require_admin_role)))
+        .route("/zone-rates/refresh", post(zone_rates::refresh_zone_rates).layer(from_fn(require_admin_role)))
         .route("/zone-rates/{from_zone}/{to_zone}", get(zone_rates::get_zone_rate_by_zones))
+        .route("/zone-rates/{from_zone}/{to_zone}", put(zone_rates::set_zone_pair_rate).layer(from_fn(require_admin_role)))
         .route("/zone-rates/{id}", put(zone_rates::update_zone_rate).layer(from_fn(require_admin_role)))
         .route("/zone-rates/{id}", delete(zone_rates::delete_zone_rate).layer(from_fn(require_admin_role)))
 }
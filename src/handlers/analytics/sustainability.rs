@@ -0,0 +1,62 @@
+use axum::{
+    extract::{Query, State},
+    response::Json,
+};
+use chrono::Utc;
+use serde::Deserialize;
+use tracing::info;
+use utoipa::IntoParams;
+
+use crate::auth::middleware::AuthenticatedUser;
+use crate::auth::Role;
+use crate::error::{ApiError, Result};
+use crate::services::sustainability::SustainabilityReport;
+use crate::AppState;
+
+use super::types::parse_timeframe;
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct SustainabilityQuery {
+    /// Timeframe: 1h, 24h, 7d, 30d (default: all time)
+    pub timeframe: Option<String>,
+}
+
+/// Get renewable mix and CO2-avoided figures
+///
+/// Admins see every user's breakdown; other users see only their own.
+#[utoipa::path(
+    get,
+    path = "/api/v1/analytics/sustainability",
+    params(SustainabilityQuery),
+    responses(
+        (status = 200, description = "Sustainability report retrieved", body = SustainabilityReport),
+        (status = 401, description = "Unauthorized")
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn get_sustainability_report(
+    user: AuthenticatedUser,
+    State(state): State<AppState>,
+    Query(params): Query<SustainabilityQuery>,
+) -> Result<Json<SustainabilityReport>> {
+    info!("🌱 Fetching sustainability report");
+
+    let since = params
+        .timeframe
+        .as_deref()
+        .map(parse_timeframe)
+        .transpose()?
+        .map(|duration| Utc::now() - duration);
+
+    let mut report = state
+        .sustainability
+        .report(since)
+        .await
+        .map_err(|e| ApiError::Internal(format!("Failed to compute sustainability report: {}", e)))?;
+
+    if user.0.role.parse::<Role>().ok() != Some(Role::Admin) {
+        report.by_user.retain(|entry| entry.user_id == user.0.sub);
+    }
+
+    Ok(Json(report))
+}
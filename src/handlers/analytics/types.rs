@@ -220,9 +220,3 @@ pub struct TransactionQuery {
     pub limit: Option<i32>,
     pub offset: Option<i32>,
 }
-
-#[derive(Debug, Serialize, ToSchema)]
-pub struct UserTransactionsResponse {
-    pub transactions: Vec<UserTransaction>,
-    pub total: i64,
-}
@@ -8,6 +8,7 @@ use uuid::Uuid;
 
 use crate::auth::middleware::AuthenticatedUser;
 use crate::error::Result;
+use crate::handlers::common::Paginated;
 use crate::AppState;
 
 use super::types::*;
@@ -115,7 +116,7 @@ pub async fn get_user_wealth_history(
     path = "/api/v1/analytics/transactions",
     params(TransactionQuery),
     responses(
-        (status = 200, description = "User transaction history retrieved", body = UserTransactionsResponse),
+        (status = 200, description = "User transaction history retrieved", body = Paginated<UserTransaction>),
         (status = 401, description = "Unauthorized")
     ),
     security(("bearer_auth" = []))
@@ -124,9 +125,9 @@ pub async fn get_user_transactions(
     user: AuthenticatedUser,
     State(state): State<AppState>,
     Query(params): Query<TransactionQuery>,
-) -> Result<Json<UserTransactionsResponse>> {
-    let limit = params.limit.unwrap_or(50).min(100);
-    let offset = params.offset.unwrap_or(0);
+) -> Result<Json<Paginated<UserTransaction>>> {
+    let limit = params.limit.unwrap_or(50).clamp(1, 100);
+    let offset = params.offset.unwrap_or(0).max(0);
 
     let mut where_conditions = vec!["user_id = $1".to_string()];
     let mut bind_count = 2;
@@ -208,10 +209,12 @@ pub async fn get_user_transactions(
 
     let transactions = sqlx_query.fetch_all(&state.db).await.unwrap_or_default();
 
-    Ok(Json(UserTransactionsResponse {
+    Ok(Json(Paginated::new(
         transactions,
         total,
-    }))
+        limit as i64,
+        offset as i64,
+    )))
 }
 
 // ==================== HELPER FUNCTIONS ====================
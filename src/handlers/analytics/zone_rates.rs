@@ -6,6 +6,7 @@ use axum::{
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
+use tracing::{info, warn};
 
 use crate::AppState;
 use crate::error::{ApiError, Result};
@@ -43,6 +44,38 @@ pub struct UpdateZoneRateRequest {
     pub is_active: bool,
 }
 
+/// New rate for a zone pair
+#[derive(Debug, Deserialize)]
+pub struct SetZonePairRateRequest {
+    pub wheeling_charge: Decimal,
+    pub loss_factor: Decimal,
+    pub description: Option<String>,
+}
+
+/// Result of reloading the matching engine's tariff cache
+#[derive(Debug, Serialize)]
+pub struct ZoneRatesRefreshResponse {
+    pub rates_loaded: usize,
+}
+
+fn validate_rate(wheeling_charge: Decimal, loss_factor: Decimal) -> Result<()> {
+    if wheeling_charge < Decimal::ZERO {
+        return Err(ApiError::BadRequest("Wheeling charge must not be negative".to_string()));
+    }
+    if loss_factor < Decimal::ZERO || loss_factor >= Decimal::ONE {
+        return Err(ApiError::BadRequest("Loss factor must be at least 0 and below 1".to_string()));
+    }
+    Ok(())
+}
+
+/// Apply a tariff change to matching right away instead of at the next
+/// periodic refresh; the write has already succeeded, so failures only warn
+async fn reload_topology(state: &AppState) {
+    if let Err(e) = state.grid_topology.load_rates().await {
+        warn!("Zone rate saved but tariff cache reload failed: {}", e);
+    }
+}
+
 /// List all zone rates
 pub async fn list_zone_rates(
     State(state): State<AppState>,
@@ -110,6 +143,8 @@ pub async fn create_zone_rate(
     State(state): State<AppState>,
     Json(req): Json<CreateZoneRateRequest>,
 ) -> Result<(StatusCode, Json<ZoneRateResponse>)> {
+    validate_rate(req.wheeling_charge, req.loss_factor)?;
+
     let rate = sqlx::query_as::<_, ZoneRateResponse>(
         r#"
         INSERT INTO zone_rates (from_zone_id, to_zone_id, wheeling_charge, loss_factor, description)
@@ -135,15 +170,90 @@ pub async fn create_zone_rate(
     .await
     .map_err(ApiError::Database)?;
 
+    reload_topology(&state).await;
     Ok((StatusCode::CREATED, Json(rate)))
 }
 
+/// Set the rate for a zone pair, replacing the pair's active rate
+pub async fn set_zone_pair_rate(
+    State(state): State<AppState>,
+    Path((from_zone, to_zone)): Path<(i32, i32)>,
+    Json(req): Json<SetZonePairRateRequest>,
+) -> Result<Json<ZoneRateResponse>> {
+    validate_rate(req.wheeling_charge, req.loss_factor)?;
+
+    let mut tx = state.db.begin().await.map_err(ApiError::Database)?;
+
+    // Retire the current rate rather than overwrite it, keeping tariff history
+    sqlx::query(
+        r#"
+        UPDATE zone_rates
+        SET is_active = FALSE, effective_until = NOW(), updated_at = NOW()
+        WHERE from_zone_id = $1 AND to_zone_id = $2 AND is_active = TRUE
+        "#
+    )
+    .bind(from_zone)
+    .bind(to_zone)
+    .execute(&mut *tx)
+    .await
+    .map_err(ApiError::Database)?;
+
+    let rate = sqlx::query_as::<_, ZoneRateResponse>(
+        r#"
+        INSERT INTO zone_rates (from_zone_id, to_zone_id, wheeling_charge, loss_factor, description)
+        VALUES ($1, $2, $3, $4, $5)
+        RETURNING 
+            id,
+            from_zone_id,
+            to_zone_id,
+            wheeling_charge,
+            loss_factor,
+            description,
+            is_active,
+            effective_from,
+            effective_until
+        "#
+    )
+    .bind(from_zone)
+    .bind(to_zone)
+    .bind(req.wheeling_charge)
+    .bind(req.loss_factor)
+    .bind(&req.description)
+    .fetch_one(&mut *tx)
+    .await
+    .map_err(ApiError::Database)?;
+
+    tx.commit().await.map_err(ApiError::Database)?;
+
+    info!(
+        "Zone rate {} -> {} set: wheeling {} THB/kWh, loss {}",
+        from_zone, to_zone, rate.wheeling_charge, rate.loss_factor
+    );
+    reload_topology(&state).await;
+    Ok(Json(rate))
+}
+
+/// Reload the matching engine's tariff cache from `zone_rates`
+pub async fn refresh_zone_rates(
+    State(state): State<AppState>,
+) -> Result<Json<ZoneRatesRefreshResponse>> {
+    let rates_loaded = state
+        .grid_topology
+        .load_rates()
+        .await
+        .map_err(ApiError::Database)?;
+
+    Ok(Json(ZoneRatesRefreshResponse { rates_loaded }))
+}
+
 /// Update an existing zone rate
 pub async fn update_zone_rate(
     State(state): State<AppState>,
     Path(id): Path<i32>,
     Json(req): Json<UpdateZoneRateRequest>,
 ) -> Result<Json<ZoneRateResponse>> {
+    validate_rate(req.wheeling_charge, req.loss_factor)?;
+
     let rate = sqlx::query_as::<_, ZoneRateResponse>(
         r#"
         UPDATE zone_rates
@@ -176,7 +286,10 @@ pub async fn update_zone_rate(
     .map_err(ApiError::Database)?;
 
     match rate {
-        Some(r) => Ok(Json(r)),
+        Some(r) => {
+            reload_topology(&state).await;
+            Ok(Json(r))
+        }
         None => Err(ApiError::NotFound("Zone rate not found".to_string())),
     }
 }
@@ -200,7 +313,10 @@ pub async fn delete_zone_rate(
     .map_err(ApiError::Database)?;
 
     match result {
-        Some(_) => Ok(StatusCode::NO_CONTENT),
+        Some(_) => {
+            reload_topology(&state).await;
+            Ok(StatusCode::NO_CONTENT)
+        }
         None => Err(ApiError::NotFound("Zone rate not found".to_string())),
     }
 }
@@ -92,6 +92,7 @@ pub async fn login(
                         Json(AuthResponse {
                             access_token: "invalid_credentials".to_string(),
                             expires_in: 0,
+                            refresh_token: None,
                             user: UserResponse {
                                 id: Uuid::nil(),
                                 username: String::new(),
@@ -114,6 +115,7 @@ pub async fn login(
                         Json(AuthResponse {
                             access_token: String::new(),
                             expires_in: 0,
+                            refresh_token: None,
                             user: UserResponse {
                                 id: Uuid::nil(),
                                 username: String::new(),
@@ -140,6 +142,7 @@ pub async fn login(
                 Json(AuthResponse {
                     access_token: "user_not_found".to_string(),
                     expires_in: 0,
+                    refresh_token: None,
                     user: UserResponse {
                         id: Uuid::nil(),
                         username: String::new(),
@@ -162,6 +165,7 @@ pub async fn login(
                 Json(AuthResponse {
                     access_token: String::new(),
                     expires_in: 0,
+                    refresh_token: None,
                     user: UserResponse {
                         id: Uuid::nil(),
                         username: String::new(),
@@ -185,11 +189,20 @@ pub async fn login(
         format!("token_{}_{}", user.username, user.id)
     });
 
+    let refresh_token = match state.auth.issue_refresh_token(user.id, request.device_id.as_deref()).await {
+        Ok(refresh_token) => Some(refresh_token),
+        Err(e) => {
+            tracing::warn!("⚠️ Failed to issue refresh token for {}: {}", user.username, e);
+            None
+        }
+    };
+
     info!("✅ Login successful for: {} (email: {}, wallet: {:?})", user.username, user.email, user.wallet_address);
 
     Json(AuthResponse {
         access_token: token,
         expires_in: 86400,
+        refresh_token,
         user: UserResponse {
             id: user.id,
             username: user.username,
@@ -306,6 +319,7 @@ pub async fn verify_email(
         Some(AuthResponse {
             access_token: token,
             expires_in: 86400,
+            refresh_token: None,
             user: UserResponse {
                 id: user_id,
                 username,
@@ -6,6 +6,7 @@ use axum::{
 use tracing::{info, error};
 use uuid::Uuid;
 use crate::auth::middleware::AuthenticatedUser;
+use crate::handlers::common::Paginated;
 use crate::AppState;
 use super::super::types::{
     MeterResponse, MeterFilterParams, ReadingFilterParams, MeterReadingResponse, MeterStats,
@@ -37,10 +38,10 @@ pub async fn get_my_meters(
     
     info!("📊 Get meters request");
 
-    if let Ok(claims) = state.jwt_service.decode_token(token) {
+    if let Ok(claims) = state.jwt_service.verify_token(token).await {
         // Query meters from database including coordinates
-        let meters_result = sqlx::query_as::<_, (Uuid, String, String, String, bool, Option<String>, Option<f64>, Option<f64>, Option<i32>)>(
-            "SELECT m.id, m.serial_number, m.meter_type, m.location, m.is_verified, u.wallet_address, m.latitude, m.longitude, m.zone_id
+        let meters_result = sqlx::query_as::<_, (Uuid, String, String, String, bool, Option<String>, Option<f64>, Option<f64>, Option<i32>, Option<String>)>(
+            "SELECT m.id, m.serial_number, m.meter_type, m.location, m.is_verified, u.wallet_address, m.latitude, m.longitude, m.zone_id, m.mint_destination_wallet
              FROM meters m
              JOIN users u ON m.user_id = u.id
              WHERE m.user_id = $1"
@@ -50,7 +51,7 @@ pub async fn get_my_meters(
         .await;
 
         if let Ok(meters) = meters_result {
-            let responses: Vec<MeterResponse> = meters.iter().map(|(id, serial, mtype, loc, verified, wallet, lat, lng, zone)| {
+            let responses: Vec<MeterResponse> = meters.iter().map(|(id, serial, mtype, loc, verified, wallet, lat, lng, zone, mint_dest)| {
                 MeterResponse {
                     id: *id,
                     serial_number: serial.clone(),
@@ -61,6 +62,7 @@ pub async fn get_my_meters(
                     latitude: *lat,
                     longitude: *lng,
                     zone_id: *zone,
+                    mint_destination_wallet: mint_dest.clone(),
                 }
             }).collect();
             
@@ -86,8 +88,8 @@ pub async fn get_registered_meters(
 ) -> Json<Vec<MeterResponse>> {
     info!("📊 Get all registered meters");
     
-    let meters_result = sqlx::query_as::<_, (Uuid, String, String, String, bool, Option<String>, Option<f64>, Option<f64>, Option<i32>)>(
-        "SELECT m.id, m.serial_number, m.meter_type, m.location, m.is_verified, u.wallet_address, m.latitude, m.longitude, m.zone_id
+    let meters_result = sqlx::query_as::<_, (Uuid, String, String, String, bool, Option<String>, Option<f64>, Option<f64>, Option<i32>, Option<String>)>(
+        "SELECT m.id, m.serial_number, m.meter_type, m.location, m.is_verified, u.wallet_address, m.latitude, m.longitude, m.zone_id, m.mint_destination_wallet
          FROM meters m
          JOIN users u ON m.user_id = u.id
          WHERE m.is_verified = true"
@@ -97,7 +99,7 @@ pub async fn get_registered_meters(
 
     match meters_result {
         Ok(meters) => {
-            let responses: Vec<MeterResponse> = meters.iter().map(|(id, serial, mtype, loc, verified, wallet, lat, lng, zone)| {
+            let responses: Vec<MeterResponse> = meters.iter().map(|(id, serial, mtype, loc, verified, wallet, lat, lng, zone, mint_dest)| {
                 MeterResponse {
                     id: *id,
                     serial_number: serial.clone(),
@@ -108,6 +110,7 @@ pub async fn get_registered_meters(
                     latitude: *lat,
                     longitude: *lng,
                     zone_id: *zone,
+                    mint_destination_wallet: mint_dest.clone(),
                 }
             }).collect();
             
@@ -139,26 +142,26 @@ pub async fn get_registered_meters_filtered(
     
     let query = match params.status.as_deref() {
         Some("verified") | Some("active") => {
-            "SELECT m.id, m.serial_number, m.meter_type, m.location, m.is_verified, u.wallet_address, m.latitude, m.longitude, m.zone_id
+            "SELECT m.id, m.serial_number, m.meter_type, m.location, m.is_verified, u.wallet_address, m.latitude, m.longitude, m.zone_id, m.mint_destination_wallet
              FROM meters m JOIN users u ON m.user_id = u.id WHERE m.is_verified = true"
         }
         Some("pending") => {
-            "SELECT m.id, m.serial_number, m.meter_type, m.location, m.is_verified, u.wallet_address, m.latitude, m.longitude, m.zone_id
+            "SELECT m.id, m.serial_number, m.meter_type, m.location, m.is_verified, u.wallet_address, m.latitude, m.longitude, m.zone_id, m.mint_destination_wallet
              FROM meters m JOIN users u ON m.user_id = u.id WHERE m.is_verified = false"
         }
         _ => {
-            "SELECT m.id, m.serial_number, m.meter_type, m.location, m.is_verified, u.wallet_address, m.latitude, m.longitude, m.zone_id
+            "SELECT m.id, m.serial_number, m.meter_type, m.location, m.is_verified, u.wallet_address, m.latitude, m.longitude, m.zone_id, m.mint_destination_wallet
              FROM meters m JOIN users u ON m.user_id = u.id"
         }
     };
 
-    let meters_result = sqlx::query_as::<_, (Uuid, String, String, String, bool, Option<String>, Option<f64>, Option<f64>, Option<i32>)>(query)
+    let meters_result = sqlx::query_as::<_, (Uuid, String, String, String, bool, Option<String>, Option<f64>, Option<f64>, Option<i32>, Option<String>)>(query)
         .fetch_all(&state.db)
         .await;
 
     match meters_result {
         Ok(meters) => {
-            let responses: Vec<MeterResponse> = meters.iter().map(|(id, serial, mtype, loc, verified, wallet, lat, lng, zone)| {
+            let responses: Vec<MeterResponse> = meters.iter().map(|(id, serial, mtype, loc, verified, wallet, lat, lng, zone, mint_dest)| {
                 MeterResponse {
                     id: *id,
                     serial_number: serial.clone(),
@@ -169,6 +172,7 @@ pub async fn get_registered_meters_filtered(
                     latitude: *lat,
                     longitude: *lng,
                     zone_id: *zone,
+                    mint_destination_wallet: mint_dest.clone(),
                 }
             }).collect();
             Json(responses)
@@ -186,7 +190,7 @@ pub async fn get_registered_meters_filtered(
     path = "/api/v1/meters/readings",
     params(ReadingFilterParams),
     responses(
-        (status = 200, description = "List of readings", body = Vec<MeterReadingResponse>),
+        (status = 200, description = "List of readings", body = Paginated<MeterReadingResponse>),
         (status = 401, description = "Unauthorized")
     ),
     security(
@@ -198,11 +202,20 @@ pub async fn get_my_readings(
     State(state): State<AppState>,
     AuthenticatedUser(claims): AuthenticatedUser,
     axum::extract::Query(params): axum::extract::Query<ReadingFilterParams>,
-) -> Json<Vec<MeterReadingResponse>> {
+) -> Json<Paginated<MeterReadingResponse>> {
     info!("📊 Get readings request for user {}", claims.sub);
 
-    let limit = params.limit.unwrap_or(50).min(1000);
-    let offset = params.offset.unwrap_or(0);
+    let limit = params.limit.unwrap_or(50).clamp(1, 1000);
+    let offset = params.offset.unwrap_or(0).max(0);
+
+    let total = sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM meter_readings WHERE user_id = $1")
+        .bind(claims.sub)
+        .fetch_one(&state.db)
+        .await
+        .unwrap_or_else(|e| {
+            info!("⚠️ Error counting readings: {}", e);
+            0
+        });
         
         // We query meter_readings. Note: Partition key is reading_timestamp.
         // We order by reading_timestamp DESC.
@@ -229,15 +242,15 @@ pub async fn get_my_readings(
 
     match readings_result {
         Ok(readings) => {
-            info!("✅ Returning {} readings", readings.len());
-            return Json(readings);
+            info!("✅ Returning {} of {} readings", readings.len(), total);
+            return Json(Paginated::new(readings, total, limit, offset));
         }
         Err(e) => {
             info!("⚠️ Error fetching readings: {}", e);
         }
     }
     
-    Json(vec![])
+    Json(Paginated::new(vec![], total, limit, offset))
 }
 
 /// Get aggregated meter stats for the user
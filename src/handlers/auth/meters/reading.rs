@@ -8,11 +8,15 @@ use uuid::Uuid;
 use crate::AppState;
 use super::super::types::{
     CreateReadingRequest, CreateReadingResponse, CreateReadingParams, 
-    CreateBatchReadingRequest, BatchReadingResponse,
+    CreateBatchReadingRequest, BatchReadingResponse, BatchReadingResult,
+    ReadingIngestStatus,
 };
-use crate::services::meter_analyzer::{check_alerts, calculate_health_score};
+use crate::services::validation::ScreeningOutcome;
+use crate::services::meter_analyzer::{check_alerts, calculate_health_score, record_alerts};
+use crate::middleware::spawn_in_request;
 use rust_decimal::prelude::ToPrimitive;
 use serde_json;
+use std::collections::HashSet;
 
 /// Create a new reading for a meter
 /// Query params:
@@ -48,50 +52,100 @@ pub async fn create_reading(
     path = "/api/v1/meters/batch/readings",
     request_body = CreateBatchReadingRequest,
     responses(
-        (status = 200, description = "Batch processed", body = BatchReadingResponse)
+        (status = 200, description = "Batch processed", body = BatchReadingResponse),
+        (status = 400, description = "Too many readings in one batch"),
+        (status = 413, description = "Request body too large")
     ),
     tag = "meters"
 )]
 pub async fn create_batch_readings(
     State(state): State<AppState>,
     Json(request): Json<CreateBatchReadingRequest>,
-) -> Json<BatchReadingResponse> {
-    let mut success_count = 0;
-    let mut failed_count = 0;
-    
+) -> crate::error::Result<Json<BatchReadingResponse>> {
+    let max_readings = state.config.max_batch_readings;
+    if request.readings.len() > max_readings {
+        warn!(
+            "🚫 Rejected batch of {} readings (limit {})",
+            request.readings.len(),
+            max_readings
+        );
+        return Err(crate::error::ApiError::BadRequest(format!(
+            "Batch contains {} readings; at most {} are accepted per request",
+            request.readings.len(),
+            max_readings
+        )));
+    }
+
     info!("📊 Processing batch of {} readings", request.readings.len());
-    
-    let futures = request.readings.into_iter().map(|reading| {
+
+    // Gateways resending a batch can repeat the same reading within it
+    let mut seen = HashSet::new();
+    let futures = request.readings.into_iter().enumerate().map(|(index, reading)| {
         let state = state.clone();
+        let serial = reading.meter_serial.clone().or_else(|| reading.meter_id.clone());
+        let repeated = match (&serial, reading.timestamp) {
+            (Some(serial), Some(timestamp)) => !seen.insert((serial.clone(), timestamp)),
+            _ => false,
+        };
         async move {
-            let serial = reading.meter_serial.clone().or_else(|| reading.meter_id.clone());
-            if let Some(serial) = serial {
-                let params = CreateReadingParams {
-                    auto_mint: Some(true),
-                    timeout_secs: Some(30),
+            let timestamp = reading.timestamp;
+            let Some(serial) = serial else {
+                return BatchReadingResult {
+                    index,
+                    meter_serial: None,
+                    timestamp,
+                    status: ReadingIngestStatus::Rejected,
+                    message: "Missing meter_serial or meter_id".to_string(),
                 };
-                let _ = internal_create_reading(&state, serial, params, reading).await;
-                Ok::<_, ()>(true)
-            } else {
-                Ok::<_, ()>(false)
+            };
+            if repeated {
+                return BatchReadingResult {
+                    index,
+                    meter_serial: Some(serial),
+                    timestamp,
+                    status: ReadingIngestStatus::Duplicate,
+                    message: "Reading repeated within batch".to_string(),
+                };
+            }
+
+            let params = CreateReadingParams {
+                auto_mint: Some(true),
+                timeout_secs: Some(30),
+            };
+            let response = internal_create_reading(&state, serial, params, reading).await;
+            BatchReadingResult {
+                index,
+                meter_serial: Some(response.serial_number),
+                timestamp: Some(response.timestamp),
+                status: response.status,
+                message: response.message,
             }
         }
     });
 
     let results = futures::future::join_all(futures).await;
-    
-    for res in results {
-        match res {
-            Ok(true) => success_count += 1,
-            _ => failed_count += 1,
-        }
-    }
-    
-    Json(BatchReadingResponse {
+
+    let count = |status: ReadingIngestStatus| results.iter().filter(|r| r.status == status).count();
+    let success_count = count(ReadingIngestStatus::Accepted);
+    let duplicate_count = count(ReadingIngestStatus::Duplicate);
+    let quarantined_count = count(ReadingIngestStatus::Quarantined);
+    let failed_count = count(ReadingIngestStatus::Rejected);
+
+    Ok(Json(BatchReadingResponse {
         success_count,
+        duplicate_count,
+        quarantined_count,
         failed_count,
-        message: format!("Processed {} readings ({} failed)", success_count + failed_count, failed_count),
-    })
+        message: format!(
+            "Processed {} readings ({} accepted, {} duplicate, {} quarantined, {} failed)",
+            results.len(),
+            success_count,
+            duplicate_count,
+            quarantined_count,
+            failed_count
+        ),
+        results,
+    }))
 }
 
 /// Internal shared logic for creating a reading
@@ -99,12 +153,79 @@ pub async fn internal_create_reading(
     state: &AppState,
     serial: String,
     params: CreateReadingParams,
-    request: CreateReadingRequest,
+    mut request: CreateReadingRequest,
 ) -> CreateReadingResponse {
     let reading_id = Uuid::new_v4();
+    let correlation_id = Uuid::new_v4();
     let timestamp = request.timestamp.unwrap_or_else(chrono::Utc::now);
+    // Pin the timestamp so the worker deduplicates on the same value
+    request.timestamp = Some(timestamp);
+
+    // Retries from field gateways must not be ingested (and minted) twice.
+    // The unique (meter_serial, reading_timestamp) index backs this up for
+    // retries that race past the check.
+    match reading_exists(&state.db, &serial, timestamp).await {
+        Ok(true) => {
+            debug!("🔁 Duplicate reading for {} at {}", serial, timestamp);
+            return CreateReadingResponse {
+                id: reading_id,
+                serial_number: serial,
+                kwh: request.kwh,
+                timestamp,
+                minted: false,
+                tx_signature: None,
+                correlation_id: None,
+                status: ReadingIngestStatus::Duplicate,
+                message: "Reading already received for this meter and timestamp".to_string(),
+            };
+        }
+        Ok(false) => {}
+        Err(e) => warn!("⚠️ Duplicate check failed for {}: {}", serial, e),
+    }
 
-    // 0. Oracle Validation (Sanity check before queuing)
+    // Quarantine physically impossible readings for review instead of minting them
+    match state.meter_reading_validator.screen(&serial, &request, timestamp).await {
+        Ok(ScreeningOutcome::Clean) => {}
+        Ok(ScreeningOutcome::Quarantined { quarantine_id, anomaly, user_id, wallet_address }) => {
+            state
+                .websocket_service
+                .broadcast_meter_reading_validation_failed(
+                    &user_id,
+                    wallet_address.as_deref().unwrap_or_default(),
+                    &serial,
+                    request.kwh,
+                    &anomaly.to_string(),
+                )
+                .await;
+            return CreateReadingResponse {
+                id: quarantine_id,
+                serial_number: serial,
+                kwh: request.kwh,
+                timestamp,
+                minted: false,
+                tx_signature: None,
+                correlation_id: None,
+                status: ReadingIngestStatus::Quarantined,
+                message: format!("Reading quarantined for review: {}", anomaly),
+            };
+        }
+        Err(e) => {
+            error!("❌ Anomaly screening failed for {}: {}", serial, e);
+            return CreateReadingResponse {
+                id: reading_id,
+                serial_number: serial,
+                kwh: request.kwh,
+                timestamp,
+                minted: false,
+                tx_signature: None,
+                correlation_id: None,
+                status: ReadingIngestStatus::Rejected,
+                message: "Reading could not be validated, please retry".to_string(),
+            };
+        }
+    }
+
+    // Oracle Validation (Sanity check before queuing)
     if let Err(e) = crate::services::validation::OracleValidator::validate_reading(
         &serial,
         &request,
@@ -119,6 +240,8 @@ pub async fn internal_create_reading(
             timestamp,
             minted: false,
             tx_signature: None,
+            correlation_id: None,
+            status: ReadingIngestStatus::Rejected,
             message: format!("Oracle Validation Failed: {}", e),
         };
     }
@@ -129,13 +252,14 @@ pub async fn internal_create_reading(
         params,
         request: request.clone(),
         retry_count: 0,
+        correlation_id,
     };
 
-    let (_queued, message) = match state.cache_service.push_reading(&task).await {
-        Ok(_) => (true, "Reading queued for processing".to_string()),
+    let (status, message, correlation_id) = match state.cache_service.push_reading(&task).await {
+        Ok(_) => (ReadingIngestStatus::Accepted, "Reading queued for processing".to_string(), Some(correlation_id)),
         Err(e) => {
             error!("❌ Failed to queue reading for {}: {}", serial, e);
-            (false, format!("Failed to queue reading: {}", e))
+            (ReadingIngestStatus::Rejected, format!("Failed to queue reading: {}", e), None)
         }
     };
 
@@ -146,6 +270,8 @@ pub async fn internal_create_reading(
         timestamp,
         minted: false, // Will be processed asynchronously
         tx_signature: None,
+        correlation_id,
+        status,
         message,
     }
 }
@@ -163,6 +289,7 @@ pub async fn process_reading_task(
     let serial = task.serial;
     let params = task.params;
     let request = task.request;
+    let correlation_id = task.correlation_id;
     
     let auto_mint = params.auto_mint.unwrap_or(true);
     let timeout_secs = params.timeout_secs.unwrap_or(30);
@@ -179,8 +306,8 @@ pub async fn process_reading_task(
         return Err(anyhow::anyhow!("Oracle Validation Failed: {}", e));
     }
 
-    // 1. Resolve Meter Context (ID, User, Wallet, Zone)
-    let (meter_id, user_id, wallet_address, zone_id) = match resolve_meter_context(state, &serial, &request.wallet_address).await {
+    // 1. Resolve Meter Context (ID, User, Wallet, Zone, Mint Destination)
+    let (meter_id, user_id, wallet_address, zone_id, mint_wallet) = match resolve_meter_context(state, &serial, &request.wallet_address).await {
         Ok(ctx) => ctx,
         Err(err_msg) => {
             error!("❌ Failed to resolve context for {}: {}", serial, err_msg);
@@ -188,7 +315,54 @@ pub async fn process_reading_task(
         }
     };
 
-    // 2. Process Blockchain Minting with Aggregation Threshold
+    // 2. Persist Reading to Database
+    //
+    // Inserted before aggregation so a duplicate (same meter and timestamp)
+    // is dropped here and never counted towards a mint.
+    let reading_id = Uuid::new_v4();
+    let timestamp = request.timestamp.unwrap_or_else(chrono::Utc::now);
+    let health_score = calculate_health_score(&request);
+
+    match persist_reading_to_db(
+        state,
+        reading_id,
+        correlation_id,
+        &serial,
+        meter_id,
+        user_id,
+        &wallet_address,
+        timestamp,
+        &request,
+        health_score,
+    ).await {
+        Ok(true) => {}
+        Ok(false) => {
+            info!("🔁 Skipping duplicate reading for {} at {}", serial, timestamp);
+            return Ok(());
+        }
+        Err(e) => {
+            error!("❌ CRITICAL: Failed to save reading {} to DB: {}", reading_id, e);
+            return Err(anyhow::anyhow!("Database error: {}", e));
+        }
+    }
+
+    // 3. Check for alerts
+    let alerts = check_alerts(&serial, &request);
+    if !alerts.is_empty() {
+        for alert in &alerts {
+            warn!("⚠️ Meter Alert: {} - {}", alert.alert_type, alert.message);
+            let alert_json = serde_json::json!({
+                "type": "meter_alert",
+                "data": alert
+            });
+            state.websocket_service.broadcast_to_channel("alerts", alert_json).await;
+        }
+        if let Err(e) = record_alerts(&state.db, user_id, &alerts).await {
+            warn!("⚠️ Failed to persist meter alerts for {}: {}", serial, e);
+        }
+    }
+
+    // 4. Process Blockchain Minting with Aggregation Threshold
     let (minted, tx_signature, mut _message) = if auto_mint && request.kwh > 0.0 {
         // Atomic Upsert and Increment
         let threshold = state.config.tokenization.mint_threshold;
@@ -215,7 +389,7 @@ pub async fn process_reading_task(
                 
                 if current_total >= threshold {
                     info!("🚀 Threshold reached for {}: {} kWh >= {} kWh. Triggering mint.", serial, current_total, threshold);
-                    let (m, sig, msg) = process_minting(state, timeout_secs, &wallet_address, current_total, &serial).await;
+                    let (m, sig, msg) = process_minting(state, timeout_secs, &mint_wallet, current_total, &serial).await;
                     
                     if m {
                         // Reset balance on success
@@ -243,80 +417,73 @@ pub async fn process_reading_task(
         (false, None, "Reading recorded (auto_mint disabled or negative kwh)".to_string())
     };
 
-    // 2.5 Check for alerts and calculate health score
-    let alerts = check_alerts(&serial, &request);
-    if !alerts.is_empty() {
-        for alert in &alerts {
-            warn!("⚠️ Meter Alert: {} - {}", alert.alert_type, alert.message);
-            let alert_json = serde_json::json!({
-                "type": "meter_alert",
-                "data": alert
-            });
-            state.websocket_service.broadcast_to_channel("alerts", alert_json).await;
+    if minted {
+        if let Err(e) = mark_reading_minted(state, reading_id, timestamp, &tx_signature).await {
+            error!("❌ Failed to record mint for reading {}: {}", reading_id, e);
         }
+        state.audit_logger.log_async(crate::services::AuditEvent::EnergyMinted {
+            user_id,
+            reading_id,
+            correlation_id,
+            meter_serial: serial.clone(),
+            reading_kwh: request.kwh.to_string(),
+            signature: tx_signature.clone(),
+        });
     }
-    
-    let health_score = calculate_health_score(&request);
 
-    // 3. Persist Reading to Database
-    let reading_id = Uuid::new_v4();
-    let timestamp = request.timestamp.unwrap_or_else(chrono::Utc::now);
+    info!("✅ Successfully processed queued reading {} for {}", reading_id, serial);
 
-    if let Err(e) = persist_reading_to_db(
-        state, 
-        reading_id, 
-        &serial, 
-        meter_id, 
-        user_id, 
-        &wallet_address, 
-        timestamp, 
-        &request, 
-        minted, 
-        &tx_signature,
-        health_score,
-    ).await {
-        error!("❌ CRITICAL: Failed to save reading {} to DB: {}", reading_id, e);
-        return Err(anyhow::anyhow!("Database error: {}", e));
-    } else {
-        info!("✅ Successfully processed queued reading {} for {}", reading_id, serial);
-        
-        // 4. Trigger Post-Processing (Async)
-        let surplus = request.surplus_energy.unwrap_or(if request.kwh > 0.0 { request.kwh } else { 0.0 });
-        let deficit = request.deficit_energy.unwrap_or(if request.kwh < 0.0 { request.kwh.abs() } else { 0.0 });
-        
-        let power_val = request.power.or_else(|| {
-             // Net power = generated - consumed
-             match (request.power_generated, request.power_consumed) {
-                 (Some(gen), Some(cons)) => Some(gen - cons),
-                 _ => request.voltage.zip(request.current).map(|(v, i)| v * i * request.power_factor.unwrap_or(1.0) / 1000.0) // kW
-             }
-        });
+    // 5. Trigger Post-Processing (Async)
+    let surplus = request.surplus_energy.unwrap_or(if request.kwh > 0.0 { request.kwh } else { 0.0 });
+    let deficit = request.deficit_energy.unwrap_or(if request.kwh < 0.0 { request.kwh.abs() } else { 0.0 });
+    
+    let power_val = request.power.or_else(|| {
+         // Net power = generated - consumed
+         match (request.power_generated, request.power_consumed) {
+             (Some(gen), Some(cons)) => Some(gen - cons),
+             _ => request.voltage.zip(request.current).map(|(v, i)| v * i * request.power_factor.unwrap_or(1.0) / 1000.0) // kW
+         }
+    });
 
-        // Update aggregate grid status in dashboard service
-        let power_gen = request.power_generated.unwrap_or(if request.kwh > 0.0 { power_val.unwrap_or(0.0) } else { 0.0 });
-        let power_cons = request.power_consumed.unwrap_or(if request.kwh < 0.0 { power_val.unwrap_or(0.0).abs() } else { 0.0 });
+    // Update aggregate grid status in dashboard service
+    let power_gen = request.power_generated.unwrap_or(if request.kwh > 0.0 { power_val.unwrap_or(0.0) } else { 0.0 });
+    let power_cons = request.power_consumed.unwrap_or(if request.kwh < 0.0 { power_val.unwrap_or(0.0).abs() } else { 0.0 });
 
-        info!("📥 Processing power metrics for {}: gen={:.2}kW, cons={:.2}kW (raw kwh={:.4})", serial, power_gen, power_cons, request.kwh);
+    info!("📥 Processing power metrics for {}: gen={:.2}kW, cons={:.2}kW (raw kwh={:.4})", serial, power_gen, power_cons, request.kwh);
 
-        let _ = state.dashboard_service.handle_meter_reading(request.kwh, &serial, zone_id, power_gen, power_cons).await;
+    let _ = state.dashboard_service.handle_meter_reading(request.kwh, &serial, zone_id, power_gen, power_cons).await;
 
-        trigger_post_processing(
-            state.clone(),
-            serial.clone(),
-            meter_id,
-            user_id,
-            surplus,
-            deficit,
-            request.max_sell_price,
-            request.max_buy_price,
-            request.kwh,
-            wallet_address,
-            power_val,
-            request.voltage,
-            request.current
-        ).await;
+    if let Some(influx_writer) = &state.influx_writer {
+        influx_writer.record(crate::services::influx_writer::ReadingPoint {
+            meter_serial: serial.clone(),
+            zone_id,
+            meter_type: request.meter_type.clone(),
+            kwh: request.kwh,
+            power: power_val,
+            voltage: request.voltage,
+            current: request.current,
+            energy_generated: request.energy_generated,
+            energy_consumed: request.energy_consumed,
+            timestamp,
+        });
     }
 
+    trigger_post_processing(
+        state.clone(),
+        serial.clone(),
+        meter_id,
+        user_id,
+        surplus,
+        deficit,
+        request.max_sell_price,
+        request.max_buy_price,
+        request.kwh,
+        wallet_address,
+        power_val,
+        request.voltage,
+        request.current
+    ).await;
+
     Ok(())
 }
 
@@ -326,9 +493,9 @@ async fn resolve_meter_context(
     state: &AppState,
     serial: &str,
     request_wallet: &Option<String>
-) -> Result<(Uuid, Uuid, String, Option<i32>), String> {
-    let meter_info = sqlx::query_as::<_, (Uuid, Uuid, Option<String>, Option<i32>)>(
-        "SELECT m.id, m.user_id, u.wallet_address, m.zone_id FROM meter_registry m JOIN users u ON m.user_id = u.id WHERE m.meter_serial = $1"
+) -> Result<(Uuid, Uuid, String, Option<i32>, String), String> {
+    let meter_info = sqlx::query_as::<_, (Uuid, Uuid, Option<String>, Option<i32>, Option<String>)>(
+        "SELECT m.id, m.user_id, u.wallet_address, m.zone_id, m.mint_destination_wallet FROM meter_registry m JOIN users u ON m.user_id = u.id WHERE m.meter_serial = $1"
     )
     .bind(serial)
     .fetch_optional(&state.db)
@@ -336,10 +503,14 @@ async fn resolve_meter_context(
     .map_err(|e| format!("Database lookup error: {}", e))?;
  
     match meter_info {
-        Some((mid, uid, Some(w), zid)) => Ok((mid, uid, w, zid)),
-        Some((mid, uid, None, zid)) => {
+        Some((mid, uid, Some(w), zid, dest)) => {
+            let mint_wallet = resolve_mint_destination(dest.as_deref(), &w).to_string();
+            Ok((mid, uid, w, zid, mint_wallet))
+        },
+        Some((mid, uid, None, zid, dest)) => {
             if let Some(req_w) = request_wallet {
-                Ok((mid, uid, req_w.clone(), zid))
+                let mint_wallet = resolve_mint_destination(dest.as_deref(), req_w).to_string();
+                Ok((mid, uid, req_w.clone(), zid, mint_wallet))
             } else {
                 Err("Wallet address required (not found on user profile)".to_string())
             }
@@ -348,6 +519,16 @@ async fn resolve_meter_context(
     }
 }
 
+/// Pick the wallet that receives tokens minted from a meter.
+///
+/// Uses the meter's configured destination when set, otherwise the owner's wallet.
+fn resolve_mint_destination<'a>(meter_destination: Option<&'a str>, owner_wallet: &'a str) -> &'a str {
+    match meter_destination {
+        Some(destination) if !destination.trim().is_empty() => destination,
+        _ => owner_wallet,
+    }
+}
+
 async fn process_minting(
     state: &AppState,
     timeout_secs: u64,
@@ -409,19 +590,34 @@ async fn process_minting(
     }
 }
 
+/// Whether a reading for this meter and timestamp was already stored
+async fn reading_exists(
+    db: &sqlx::PgPool,
+    serial: &str,
+    timestamp: chrono::DateTime<chrono::Utc>,
+) -> Result<bool, sqlx::Error> {
+    sqlx::query_scalar::<_, bool>(
+        "SELECT EXISTS(SELECT 1 FROM meter_readings WHERE meter_serial = $1 AND reading_timestamp = $2)",
+    )
+    .bind(serial)
+    .bind(timestamp)
+    .fetch_one(db)
+    .await
+}
+
+/// Insert a reading; returns `false` if the meter already has one at this timestamp
 async fn persist_reading_to_db(
     state: &AppState,
     reading_id: Uuid,
+    correlation_id: Uuid,
     serial: &str,
     meter_id: Uuid,
     user_id: Uuid,
     wallet_address: &str,
     timestamp: chrono::DateTime<chrono::Utc>,
     request: &CreateReadingRequest,
-    minted: bool,
-    tx_signature: &Option<String>,
     health_score: f64,
-) -> Result<(), sqlx::Error> {
+) -> Result<bool, sqlx::Error> {
     // Calculate derived energy values if not provided
     let (def_gen, def_cons) = if request.kwh > 0.0 { (request.kwh, 0.0) } else { (0.0, request.kwh.abs()) };
     
@@ -440,11 +636,12 @@ async fn persist_reading_to_db(
             latitude, longitude, battery_level, weather_condition, health_score,
             rec_eligible, carbon_offset, max_sell_price, max_buy_price,
             meter_signature, meter_type,
-            minted, mint_tx_signature, created_at
+            minted, mint_tx_signature, correlation_id, created_at
          ) VALUES ($1, $2, $3, $4, $5, $6, $6, $7, $8, $9, $10, $11, 
                    $12, $13, $14, $15, $16, $17, $18, 
                    $19, $20, $21, $22, $23,
-                   $24, $25, $26, $27, $28, $29, $30, $31, NOW())"
+                   $24, $25, $26, $27, $28, $29, FALSE, NULL, $30, NOW())
+         ON CONFLICT (meter_serial, reading_timestamp) DO NOTHING"
     )
     .bind(reading_id)
     .bind(serial)
@@ -482,8 +679,24 @@ async fn persist_reading_to_db(
     // Security
     .bind(&request.meter_signature)
     .bind(&request.meter_type)
-    // Minting status
-    .bind(minted)
+    .bind(correlation_id)
+    .execute(&state.db)
+    .await
+    .map(|result| result.rows_affected() > 0)
+}
+
+async fn mark_reading_minted(
+    state: &AppState,
+    reading_id: Uuid,
+    timestamp: chrono::DateTime<chrono::Utc>,
+    tx_signature: &Option<String>,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        "UPDATE meter_readings SET minted = TRUE, mint_tx_signature = $3
+         WHERE id = $1 AND reading_timestamp = $2",
+    )
+    .bind(reading_id)
+    .bind(timestamp)
     .bind(tx_signature.clone())
     .execute(&state.db)
     .await
@@ -511,7 +724,7 @@ async fn trigger_post_processing(
     // Broadcast real-time meter update
     let ws_meter_serial = serial.clone();
     let ws_wallet = wallet_address.clone();
-    tokio::spawn(async move {
+    spawn_in_request(async move {
         websocket.broadcast_meter_reading_received(
             &user_id,
             &ws_wallet,
@@ -531,7 +744,7 @@ async fn trigger_post_processing(
     let sell_price = max_sell_price.map(|p| rust_decimal::Decimal::from_f64_retain(p).unwrap_or_default());
     let buy_price = max_buy_price.map(|p| rust_decimal::Decimal::from_f64_retain(p).unwrap_or_default());
 
-    tokio::spawn(async move {
+    spawn_in_request(async move {
         // Handle Surplus -> Sell Order
         if surplus_val > rust_decimal::Decimal::ZERO {
             match sell_price {
@@ -547,6 +760,7 @@ async fn trigger_post_processing(
                         None,
                         Some(meter_id),
                         None,
+                        None,
                     ).await;
                     if let Err(e) = res {
                         error!("❌ [Auto-P2P] Failed to create Sell order for {}: {}", serial, e);
@@ -571,6 +785,7 @@ async fn trigger_post_processing(
                         None,
                         Some(meter_id),
                         None,
+                        None,
                     ).await;
                     if let Err(e) = res {
                         error!("❌ [Auto-P2P] Failed to create Buy order for {}: {}", serial, e);
@@ -581,3 +796,22 @@ async fn trigger_post_processing(
         }
     });
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const OWNER_WALLET: &str = "DYw8jCTfwHNRJhhmFcbXvVDTqWMEVFBX6ZKUmG5CNSKK";
+    const POOL_WALLET: &str = "9WzDXwBbmkg8ZTbNMqUxvQRAyrZzDsGYdLVL9zYtAWWM";
+
+    #[test]
+    fn test_mint_destination_uses_meter_wallet_when_set() {
+        assert_eq!(resolve_mint_destination(Some(POOL_WALLET), OWNER_WALLET), POOL_WALLET);
+    }
+
+    #[test]
+    fn test_mint_destination_falls_back_to_owner() {
+        assert_eq!(resolve_mint_destination(None, OWNER_WALLET), OWNER_WALLET);
+        assert_eq!(resolve_mint_destination(Some("  "), OWNER_WALLET), OWNER_WALLET);
+    }
+}
@@ -5,6 +5,8 @@ use axum::{
 use tracing::info;
 use uuid::Uuid;
 use crate::auth::middleware::AuthenticatedUser;
+use crate::auth::Role;
+use crate::error::{ApiError, Result};
 use crate::AppState;
 use super::super::types::{
     MeterResponse, RegisterMeterRequest, RegisterMeterResponse,
@@ -38,6 +40,17 @@ pub async fn register_meter(
     let meter_type = request.meter_type.unwrap_or_else(|| "solar".to_string());
     let location = request.location.unwrap_or_else(|| "Not specified".to_string());
 
+    // Validate optional minting destination before touching the database
+    if let Some(destination) = &request.mint_destination_wallet {
+        if let Err(e) = crate::services::BlockchainService::parse_pubkey(destination) {
+            return Json(RegisterMeterResponse {
+                success: false,
+                message: format!("Invalid mint destination wallet: {}", e),
+                meter: None,
+            });
+        }
+    }
+
     // Check if meter serial already exists
     let existing = sqlx::query_as::<_, (Uuid,)>(
         "SELECT id FROM meters WHERE serial_number = $1"
@@ -56,8 +69,8 @@ pub async fn register_meter(
 
     // Insert meter into database with coordinates and zone
     let insert_result = sqlx::query(
-        "INSERT INTO meters (id, user_id, serial_number, meter_type, location, latitude, longitude, zone_id, is_verified, created_at, updated_at)
-         VALUES ($1, $2, $3, $4, $5, $6, $7, $8, true, NOW(), NOW())"
+        "INSERT INTO meters (id, user_id, serial_number, meter_type, location, latitude, longitude, zone_id, mint_destination_wallet, is_verified, created_at, updated_at)
+         VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, true, NOW(), NOW())"
     )
     .bind(meter_id)
     .bind(user_id)
@@ -67,6 +80,7 @@ pub async fn register_meter(
     .bind(request.latitude)
     .bind(request.longitude)
     .bind(request.zone_id)
+    .bind(&request.mint_destination_wallet)
     .execute(&state.db)
     .await;
 
@@ -76,8 +90,8 @@ pub async fn register_meter(
             
             // Sync to meter_registry for FK constraints
             let _ = sqlx::query(
-                "INSERT INTO meter_registry (id, user_id, meter_serial, meter_type, location_address, meter_key_hash, verification_method, verification_status, zone_id)
-                 VALUES ($1, $2, $3, $4, $5, 'mock_hash', 'serial', 'verified', $6)"
+                "INSERT INTO meter_registry (id, user_id, meter_serial, meter_type, location_address, meter_key_hash, verification_method, verification_status, zone_id, mint_destination_wallet)
+                 VALUES ($1, $2, $3, $4, $5, 'mock_hash', 'serial', 'verified', $6, $7)"
             )
             .bind(meter_id)
             .bind(user_id)
@@ -85,6 +99,7 @@ pub async fn register_meter(
             .bind(&meter_type)
             .bind(&location)
             .bind(request.zone_id)
+            .bind(&request.mint_destination_wallet)
             .execute(&state.db)
             .await
             .map_err(|e| tracing::error!("Failed to sync meter_registry: {}", e));
@@ -115,6 +130,7 @@ pub async fn register_meter(
                     latitude: request.latitude,
                     longitude: request.longitude,
                     zone_id: request.zone_id,
+                    mint_destination_wallet: request.mint_destination_wallet,
                 }),
             })
         }
@@ -212,6 +228,9 @@ pub async fn verify_meter(
 }
 
 /// Update meter status via PATCH
+///
+/// Only the meter's owner or an admin may update it; the mint destination
+/// decides where the meter's minted tokens go.
 #[utoipa::path(
     patch,
     path = "/api/v1/meters/{serial}",
@@ -221,17 +240,49 @@ pub async fn verify_meter(
     ),
     responses(
         (status = 200, description = "Status updated", body = RegisterMeterResponse),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Meter belongs to another user"),
         (status = 404, description = "Meter not found")
     ),
+    security(
+        ("jwt_token" = [])
+    ),
     tag = "meters"
 )]
 pub async fn update_meter_status(
     State(state): State<AppState>,
+    AuthenticatedUser(claims): AuthenticatedUser,
     Path(serial): Path<String>,
     Json(request): Json<UpdateMeterStatusRequest>,
-) -> Json<RegisterMeterResponse> {
+) -> Result<Json<RegisterMeterResponse>> {
     info!("🔧 Update meter {} request: {:?}", serial, request);
 
+    if let Some(destination) = &request.mint_destination_wallet {
+        if let Err(e) = crate::services::BlockchainService::parse_pubkey(destination) {
+            return Ok(Json(RegisterMeterResponse {
+                success: false,
+                message: format!("Invalid mint destination wallet: {}", e),
+                meter: None,
+            }));
+        }
+    }
+
+    let mut tx = state.db.begin().await.map_err(ApiError::Database)?;
+
+    let owner = sqlx::query_scalar::<_, Option<Uuid>>(
+        "SELECT user_id FROM meters WHERE serial_number = $1 FOR UPDATE",
+    )
+    .bind(&serial)
+    .fetch_optional(&mut *tx)
+    .await
+    .map_err(ApiError::Database)?
+    .ok_or_else(|| ApiError::NotFound(format!("Meter {} not found", serial)))?;
+
+    let is_admin = matches!(claims.role.parse::<Role>(), Ok(Role::Admin));
+    if owner != Some(claims.sub) && !is_admin {
+        return Err(ApiError::Forbidden(format!("Meter {} belongs to another user", serial)));
+    }
+
     // Build dynamic query
     let mut query_builder = sqlx::QueryBuilder::<sqlx::Postgres>::new("UPDATE meters SET updated_at = NOW()");
     
@@ -255,36 +306,45 @@ pub async fn update_meter_status(
         query_builder.push(", longitude = ");
         query_builder.push_bind(lng);
     }
+
+    if let Some(destination) = &request.mint_destination_wallet {
+        query_builder.push(", mint_destination_wallet = ");
+        query_builder.push_bind(destination);
+    }
     
     query_builder.push(" WHERE serial_number = ");
     query_builder.push_bind(&serial);
 
-    let query = query_builder.build();
-    let update_result = query.execute(&state.db).await;
+    query_builder
+        .build()
+        .execute(&mut *tx)
+        .await
+        .map_err(ApiError::Database)?;
 
-    match update_result {
-        Ok(result) if result.rows_affected() > 0 => {
-            // Also sync to meter_registry if zone_id updated
-            if let Some(zone_id) = request.zone_id {
-                let _ = sqlx::query("UPDATE meter_registry SET zone_id = $1 WHERE meter_serial = $2")
-                    .bind(zone_id)
-                    .bind(&serial)
-                    .execute(&state.db)
-                    .await;
-            }
+    // Minting reads meter_registry, so it must change with meters or not at all
+    if let Some(zone_id) = request.zone_id {
+        sqlx::query("UPDATE meter_registry SET zone_id = $1 WHERE meter_serial = $2")
+            .bind(zone_id)
+            .bind(&serial)
+            .execute(&mut *tx)
+            .await
+            .map_err(ApiError::Database)?;
+    }
 
-            Json(RegisterMeterResponse {
-                success: true,
-                message: format!("Meter {} updated successfully", serial),
-                meter: None,
-            })
-        }
-        _ => {
-            Json(RegisterMeterResponse {
-                success: false,
-                message: format!("Meter {} not found or no changes made", serial),
-                meter: None,
-            })
-        }
+    if let Some(destination) = &request.mint_destination_wallet {
+        sqlx::query("UPDATE meter_registry SET mint_destination_wallet = $1 WHERE meter_serial = $2")
+            .bind(destination)
+            .bind(&serial)
+            .execute(&mut *tx)
+            .await
+            .map_err(ApiError::Database)?;
     }
+
+    tx.commit().await.map_err(ApiError::Database)?;
+
+    Ok(Json(RegisterMeterResponse {
+        success: true,
+        message: format!("Meter {} updated successfully", serial),
+        meter: None,
+    }))
 }
@@ -22,6 +22,7 @@ pub mod login;
 pub mod registration;
 pub mod password_reset;
 pub mod profile;
+pub mod session;
 pub mod meters;
 pub mod wallets;
 pub mod status;
@@ -38,6 +39,7 @@ pub use login::{login, verify_email};
 pub use registration::{register, resend_verification};
 pub use password_reset::{forgot_password, reset_password, change_password};
 pub use profile::profile;
+pub use session::{refresh_token, logout};
 pub use meters::{
     get_my_meters, register_meter, get_registered_meters, 
     get_registered_meters_filtered, update_meter_status, verify_meter, create_reading,
@@ -49,11 +51,13 @@ pub use status::{system_status, meter_status, readiness_probe, liveness_probe};
 // Re-export types
 pub use types::{
     LoginRequest, AuthResponse, UserResponse,
+    RefreshTokenRequest, RefreshTokenResponse, LogoutRequest, LogoutResponse,
     RegistrationRequest, RegistrationResponse, 
     ForgotPasswordRequest, ResetPasswordRequest,
     MeterResponse, PublicMeterResponse, RegisterMeterRequest, RegisterMeterResponse,
     TokenBalanceResponse, VerifyEmailResponse, VerifyMeterRequest,
     MeterFilterParams, UpdateMeterStatusRequest, CreateReadingRequest, CreateReadingResponse,
+    ReadingIngestStatus,
     MeterStats, GetTrendsQuery, TrendRecord, TrendResponse,
 };
 pub use status::{
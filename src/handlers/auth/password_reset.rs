@@ -258,7 +258,7 @@ pub async fn change_password(
     let token = auth_header.strip_prefix("Bearer ").unwrap_or(auth_header);
     
     // Decode token to get user ID
-    let claims = match state.jwt_service.decode_token(token) {
+    let claims = match state.jwt_service.verify_token(token).await {
         Ok(c) => c,
         Err(_) => {
             return Json(VerifyEmailResponse::simple(
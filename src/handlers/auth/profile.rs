@@ -43,7 +43,7 @@ pub async fn profile(
     info!("👤 Profile request");
 
     // Try to decode token and get user from database
-    if let Ok(claims) = state.jwt_service.decode_token(token) {
+    if let Ok(claims) = state.jwt_service.verify_token(token).await {
         let user_result = sqlx::query_as::<_, UserRow>(
             "SELECT id, username, email, role::text as role, first_name, last_name, wallet_address, balance, locked_amount, locked_energy
              FROM users WHERE id = $1"
@@ -111,7 +111,7 @@ pub async fn update_wallet(
         .ok_or(crate::ApiError::Unauthorized("Missing token".to_string()))?;
 
     let token = auth_header.strip_prefix("Bearer ").unwrap_or(auth_header);
-    let claims = state.jwt_service.decode_token(token)
+    let claims = state.jwt_service.verify_token(token).await
         .map_err(|_| crate::ApiError::Unauthorized("Invalid token".to_string()))?;
 
     info!("💼 Update wallet request for user: {}", claims.sub);
@@ -174,7 +174,7 @@ pub async fn generate_wallet(
         .ok_or(crate::ApiError::Unauthorized("Missing token".to_string()))?;
 
     let token = auth_header.strip_prefix("Bearer ").unwrap_or(auth_header);
-    let claims = state.jwt_service.decode_token(token)
+    let claims = state.jwt_service.verify_token(token).await
         .map_err(|_| crate::ApiError::Unauthorized("Invalid token".to_string()))?;
 
     info!("🔑 Wallet generation request for user: {}", claims.sub);
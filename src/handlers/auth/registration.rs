@@ -133,6 +133,7 @@ pub async fn register(
     let auth = AuthResponse {
         access_token: token,
         expires_in: 86400,
+        refresh_token: None,
         user,
     };
 
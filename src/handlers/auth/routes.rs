@@ -13,6 +13,7 @@ use super::{
     registration::register,
     password_reset::{forgot_password, reset_password, change_password},
     profile::{profile, update_wallet, generate_wallet},
+    session::{refresh_token, logout},
     meters::{
         get_my_meters, register_meter,
         get_registered_meters_filtered, update_meter_status, create_reading,
@@ -30,6 +31,8 @@ use super::{
 pub fn v1_auth_routes() -> Router<AppState> {
     Router::new()
         .route("/token", post(login))  // POST /api/v1/auth/token
+        .route("/refresh", post(refresh_token))  // POST /api/v1/auth/refresh
+        .route("/logout", post(logout))  // POST /api/v1/auth/logout
         .route("/verify", get(verify_email))  // GET /api/v1/auth/verify
         .route("/forgot-password", post(forgot_password))  // POST /api/v1/auth/forgot-password
         .route("/reset-password", post(reset_password))  // POST /api/v1/auth/reset-password
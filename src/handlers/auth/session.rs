@@ -0,0 +1,102 @@
+//! Session Handlers Module
+//!
+//! Refresh token exchange and logout.
+
+use axum::{extract::State, http::HeaderMap, Json};
+use chrono::Utc;
+use tracing::{info, warn};
+
+use crate::error::{ApiError, Result};
+use crate::services::cache::CacheKeys;
+use crate::AppState;
+use super::types::{LogoutRequest, LogoutResponse, RefreshTokenRequest, RefreshTokenResponse};
+
+/// Exchange a refresh token for a new access token
+///
+/// The refresh token is rotated: the response carries its replacement and
+/// the presented token stops working.
+#[utoipa::path(
+    post,
+    path = "/api/v1/auth/refresh",
+    request_body = RefreshTokenRequest,
+    responses(
+        (status = 200, description = "New access and refresh tokens", body = RefreshTokenResponse),
+        (status = 401, description = "Refresh token invalid, expired or revoked")
+    ),
+    tag = "auth"
+)]
+pub async fn refresh_token(
+    State(state): State<AppState>,
+    Json(request): Json<RefreshTokenRequest>,
+) -> Result<Json<RefreshTokenResponse>> {
+    let session = state.auth.rotate_refresh_token(&request.refresh_token).await?;
+
+    let claims = crate::auth::Claims::new(session.user_id, session.username, session.role);
+    let access_token = state.jwt_service.encode_token(&claims)?;
+
+    info!("🔄 Refreshed session for user {}", session.user_id);
+
+    Ok(Json(RefreshTokenResponse {
+        access_token,
+        expires_in: claims.exp - claims.iat,
+        refresh_token: session.refresh_token,
+    }))
+}
+
+/// Log out: revoke the bearer access token and, if given, the refresh token
+#[utoipa::path(
+    post,
+    path = "/api/v1/auth/logout",
+    request_body = LogoutRequest,
+    responses(
+        (status = 200, description = "Tokens revoked", body = LogoutResponse),
+        (status = 401, description = "No valid access or refresh token presented")
+    ),
+    security(
+        ("jwt_token" = [])
+    ),
+    tag = "auth"
+)]
+pub async fn logout(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    request: Option<Json<LogoutRequest>>,
+) -> Result<Json<LogoutResponse>> {
+    let request = request.map(|Json(r)| r).unwrap_or_default();
+
+    let token = headers
+        .get("Authorization")
+        .and_then(|h| h.to_str().ok())
+        .and_then(|h| h.strip_prefix("Bearer "));
+
+    // Deny the access token's id until the token would have expired anyway
+    let mut access_token_revoked = false;
+    if let Some(claims) = token.and_then(|t| state.jwt_service.decode_token(t).ok()) {
+        let ttl = (claims.exp - Utc::now().timestamp()).max(1) as u64;
+        match state
+            .cache_service
+            .set_with_ttl(&CacheKeys::revoked_token(&claims.jti), &true, ttl)
+            .await
+        {
+            Ok(()) => access_token_revoked = true,
+            Err(e) => warn!("⚠️ Failed to revoke access token for {}: {}", claims.sub, e),
+        }
+        info!("👋 Logout for user {}", claims.sub);
+    }
+
+    let refresh_token_revoked = match &request.refresh_token {
+        Some(refresh_token) => state.auth.revoke_refresh_token(refresh_token).await?,
+        None => false,
+    };
+
+    if !access_token_revoked && !refresh_token_revoked {
+        return Err(ApiError::Unauthorized(
+            "No valid access or refresh token to revoke".to_string(),
+        ));
+    }
+
+    Ok(Json(LogoutResponse {
+        access_token_revoked,
+        refresh_token_revoked,
+    }))
+}
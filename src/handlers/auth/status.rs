@@ -4,6 +4,7 @@
 
 use axum::{
     extract::State,
+    http::StatusCode,
     Json,
 };
 use serde::Serialize;
@@ -223,38 +224,86 @@ async fn get_meter_counts(state: &AppState) -> MeterCounts {
     }
 }
 
-/// Simple readiness probe for kubernetes/docker
+/// Readiness probe for kubernetes/docker
+///
+/// Not ready (503) when the database is unhealthy, the Solana RPC is
+/// unreachable, or the authority wallet is missing or below its minimum SOL
+/// balance, since settlements cannot proceed without them. A stale slot or a
+/// low authority balance only degrades and does not fail readiness. Also not
+/// ready when the matching, settlement or event processor loop has stopped
+/// beating, so a background task that died silently gets the pod restarted.
 #[utoipa::path(
     get,
     path = "/api/v1/status/ready",
     responses(
-        (status = 200, description = "Readiness probe result", body = ReadinessResponse),
+        (status = 200, description = "Ready to serve traffic", body = ReadinessResponse),
+        (status = 503, description = "A required dependency is down", body = ReadinessResponse),
     ),
     tag = "status"
 )]
 pub async fn readiness_probe(
     State(state): State<AppState>,
-) -> Json<ReadinessResponse> {
+) -> (StatusCode, Json<ReadinessResponse>) {
+    use crate::services::health_check::{
+        HealthCheckStatus, AUTHORITY_WALLET_DEPENDENCY, BACKGROUND_TASKS_DEPENDENCY,
+        SOLANA_RPC_DEPENDENCY,
+    };
+
     let health = state.health_checker.perform_health_check().await;
-    
-    let db_passed = health.dependencies.iter()
-        .find(|d| d.name == "PostgreSQL")
-        .map(|d| d.status == crate::services::health_check::HealthCheckStatus::Healthy)
-        .unwrap_or(false);
-    
-    Json(ReadinessResponse {
-        ready: health.status == "healthy",
-        checks: vec![
-            CheckResult {
-                name: "database".to_string(),
-                passed: db_passed,
-            },
-            CheckResult {
-                name: "overall".to_string(),
-                passed: health.status == "healthy" || health.status == "degraded",
-            }
-        ],
-    })
+    let dependency_status = |name: &str| {
+        health
+            .dependencies
+            .iter()
+            .find(|d| d.name == name)
+            .map(|d| d.status.clone())
+    };
+
+    let db_passed = dependency_status("PostgreSQL") == Some(HealthCheckStatus::Healthy);
+    let rpc_passed = matches!(
+        dependency_status(SOLANA_RPC_DEPENDENCY),
+        Some(HealthCheckStatus::Healthy | HealthCheckStatus::Degraded)
+    );
+    // Not reported when no monitor is attached
+    let authority_passed =
+        dependency_status(AUTHORITY_WALLET_DEPENDENCY) != Some(HealthCheckStatus::Unhealthy);
+    let background_tasks_passed =
+        dependency_status(BACKGROUND_TASKS_DEPENDENCY) != Some(HealthCheckStatus::Unhealthy);
+    let ready = db_passed && rpc_passed && authority_passed && background_tasks_passed;
+
+    let status = if ready {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+
+    (
+        status,
+        Json(ReadinessResponse {
+            ready,
+            checks: vec![
+                CheckResult {
+                    name: "database".to_string(),
+                    passed: db_passed,
+                },
+                CheckResult {
+                    name: "solana_rpc".to_string(),
+                    passed: rpc_passed,
+                },
+                CheckResult {
+                    name: "authority_wallet".to_string(),
+                    passed: authority_passed,
+                },
+                CheckResult {
+                    name: "background_tasks".to_string(),
+                    passed: background_tasks_passed,
+                },
+                CheckResult {
+                    name: "overall".to_string(),
+                    passed: health.status == "healthy" || health.status == "degraded",
+                },
+            ],
+        }),
+    )
 }
 
 #[derive(Debug, Serialize, ToSchema)]
@@ -36,6 +36,9 @@ pub struct LoginRequest {
     /// Username or Email address of the user
     pub username: String,
     pub password: String,
+    /// Device the issued refresh token is bound to
+    #[serde(default)]
+    pub device_id: Option<String>,
 }
 
 /// Auth Response (Token)
@@ -43,9 +46,41 @@ pub struct LoginRequest {
 pub struct AuthResponse {
     pub access_token: String,
     pub expires_in: i64,
+    /// Exchange at `POST /api/v1/auth/refresh` for a new access token
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub refresh_token: Option<String>,
     pub user: UserResponse,
 }
 
+/// Refresh Token Request
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct RefreshTokenRequest {
+    pub refresh_token: String,
+}
+
+/// Refresh Token Response; the presented refresh token is no longer valid
+#[derive(Debug, Serialize, ToSchema)]
+pub struct RefreshTokenResponse {
+    pub access_token: String,
+    pub expires_in: i64,
+    pub refresh_token: String,
+}
+
+/// Logout Request
+#[derive(Debug, Default, Deserialize, ToSchema)]
+pub struct LogoutRequest {
+    /// Refresh token of this device, revoked along with the access token
+    #[serde(default)]
+    pub refresh_token: Option<String>,
+}
+
+/// Logout Response
+#[derive(Debug, Serialize, ToSchema)]
+pub struct LogoutResponse {
+    pub access_token_revoked: bool,
+    pub refresh_token_revoked: bool,
+}
+
 // ============================================================================
 // User Types
 // ============================================================================
@@ -178,6 +213,9 @@ pub struct MeterResponse {
     /// Zone ID for the meter
     #[serde(skip_serializing_if = "Option::is_none")]
     pub zone_id: Option<i32>,
+    /// Wallet receiving tokens minted from this meter (owner's wallet when unset)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mint_destination_wallet: Option<String>,
 }
 
 /// Public Meter Response (for unauthenticated public API)
@@ -265,6 +303,8 @@ pub struct RegisterMeterRequest {
     pub longitude: Option<f64>,
     /// Zone ID for the meter
     pub zone_id: Option<i32>,
+    /// Optional wallet to receive minted tokens instead of the owner's wallet
+    pub mint_destination_wallet: Option<String>,
 }
 
 /// Meter Registration Response
@@ -294,6 +334,7 @@ pub struct UpdateMeterStatusRequest {
     pub zone_id: Option<i32>,
     pub latitude: Option<f64>,
     pub longitude: Option<f64>,
+    pub mint_destination_wallet: Option<String>,
 }
 
 /// Create reading request for v1 API with full telemetry support
@@ -355,6 +396,20 @@ impl crate::handlers::meter::types::ReadingData for CreateReadingRequest {
     fn thd_current(&self) -> Option<f64> { self.thd_current }
 }
 
+/// Outcome of submitting one reading
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ReadingIngestStatus {
+    /// New reading, queued for processing
+    Accepted,
+    /// Same meter and timestamp already received; not processed again
+    Duplicate,
+    /// Failed anomaly checks; held for manual review and not minted
+    Quarantined,
+    /// Failed validation or could not be queued
+    Rejected,
+}
+
 /// Create reading response
 #[derive(Debug, Serialize, ToSchema)]
 pub struct CreateReadingResponse {
@@ -364,6 +419,9 @@ pub struct CreateReadingResponse {
     pub timestamp: chrono::DateTime<chrono::Utc>,
     pub minted: bool,
     pub tx_signature: Option<String>,
+    /// Follows the reading through minting; see `GET /api/v1/admin/trace/{correlation_id}`
+    pub correlation_id: Option<Uuid>,
+    pub status: ReadingIngestStatus,
     pub message: String,
 }
 
@@ -373,11 +431,24 @@ pub struct CreateBatchReadingRequest {
     pub readings: Vec<CreateReadingRequest>,
 }
 
+/// Per-reading result within a batch, in request order
+#[derive(Debug, Serialize, ToSchema)]
+pub struct BatchReadingResult {
+    pub index: usize,
+    pub meter_serial: Option<String>,
+    pub timestamp: Option<chrono::DateTime<chrono::Utc>>,
+    pub status: ReadingIngestStatus,
+    pub message: String,
+}
+
 /// Batch reading response
 #[derive(Debug, Serialize, ToSchema)]
 pub struct BatchReadingResponse {
     pub success_count: usize,
+    pub duplicate_count: usize,
+    pub quarantined_count: usize,
     pub failed_count: usize,
+    pub results: Vec<BatchReadingResult>,
     pub message: String,
 }
 
@@ -8,4 +8,4 @@ pub mod response;
 
 // Re-export commonly used types
 pub use extractors::{DateRangeParams, PaginationParams, SearchParams, SortOrder, ValidatedUuid};
-pub use response::{ApiResponse, ListResponse, PaginatedResponse};
+pub use response::{ApiResponse, ListResponse, Paginated, PaginatedResponse};
@@ -169,6 +169,32 @@ impl<T: Serialize> PaginatedResponse<T> {
     }
 }
 
+/// Offset-paginated list response
+///
+/// `total` counts every row matching the filters, so clients can page with
+/// `offset + limit` while `has_more` is true.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct Paginated<T: Serialize> {
+    pub items: Vec<T>,
+    pub total: i64,
+    pub limit: i64,
+    pub offset: i64,
+    pub has_more: bool,
+}
+
+impl<T: Serialize> Paginated<T> {
+    pub fn new(items: Vec<T>, total: i64, limit: i64, offset: i64) -> Self {
+        let has_more = offset + (items.len() as i64) < total;
+        Self {
+            items,
+            total,
+            limit,
+            offset,
+            has_more,
+        }
+    }
+}
+
 /// Simple list response without pagination
 #[derive(Debug, Serialize, ToSchema)]
 pub struct ListResponse<T: Serialize> {
@@ -208,4 +234,16 @@ mod tests {
         assert!(response.pagination.has_next);
         assert!(!response.pagination.has_prev);
     }
+
+    #[test]
+    fn test_paginated_has_more() {
+        let first = Paginated::new(vec![1, 2], 5, 2, 0);
+        assert!(first.has_more);
+
+        let last = Paginated::new(vec![5], 5, 2, 4);
+        assert!(!last.has_more);
+
+        let past_end: Paginated<i32> = Paginated::new(vec![], 5, 2, 10);
+        assert!(!past_end.has_more);
+    }
 }
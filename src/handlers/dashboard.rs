@@ -13,7 +13,7 @@ pub fn v1_dashboard_routes() -> Router<crate::AppState> {
     path = "/api/dashboard/metrics",
     tag = "Dashboard",
     responses(
-        (status = 200, description = "Dashboard metrics", body = DashboardMetrics),
+        (status = 200, description = "Dashboard metrics as of the last background refresh", body = DashboardMetrics),
         (status = 500, description = "Internal server error")
     )
 )]
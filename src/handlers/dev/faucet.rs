@@ -1,9 +1,18 @@
-use axum::{extract::State, response::Json};
+use axum::{
+    extract::State,
+    http::{header, HeaderValue},
+    response::{IntoResponse, Json, Response},
+};
+use rust_decimal::prelude::{FromPrimitive, ToPrimitive};
+use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 use solana_sdk::pubkey::Pubkey;
 use std::str::FromStr;
+use uuid::Uuid;
 
-use crate::error::{ApiError, Result};
+use crate::error::{ApiError, ErrorCode, Result};
+use crate::services::faucet::{self, FaucetAmounts, FaucetGrant, FaucetLedger, FaucetReservation, FaucetThrottle};
+use crate::utils::units::to_atomic;
 use crate::AppState;
 
 #[derive(Debug, Deserialize, Serialize, utoipa::ToSchema)]
@@ -13,6 +22,8 @@ pub struct FaucetRequest {
     pub mint_tokens_kwh: Option<f64>,
     pub deposit_fiat: Option<f64>,
     pub promote_to_role: Option<String>,
+    /// Client-generated idempotency key; retries with the same key return the original grant
+    pub client_request_id: Option<String>,
 }
 
 #[derive(Debug, Serialize, utoipa::ToSchema)]
@@ -21,6 +32,9 @@ pub struct FaucetResponse {
     pub message: String,
     pub sol_tx_signature: Option<String>,
     pub token_tx_signature: Option<String>,
+    pub grant_id: Uuid,
+    /// True when this is the earlier grant for the same `client_request_id`
+    pub replayed: bool,
 }
 
 /// Request funds from the developer faucet
@@ -32,139 +46,194 @@ pub struct FaucetResponse {
     request_body = FaucetRequest,
     responses(
         (status = 200, description = "Funds requested successfully", body = FaucetResponse),
-        (status = 400, description = "Invalid request"),
+        (status = 400, description = "Invalid request, wallet not linked to a user or amount above the daily cap"),
+        (status = 409, description = "A request with the same client_request_id is in progress"),
+        (status = 429, description = "Cooldown or daily cap reached; Retry-After gives the wait in seconds"),
         (status = 500, description = "Internal server error")
     )
 )]
 pub async fn request_faucet(
     State(state): State<AppState>,
     Json(payload): Json<FaucetRequest>,
-) -> Result<Json<FaucetResponse>> {
+) -> Result<Response> {
     tracing::info!("Faucet request for wallet: {}", payload.wallet_address);
 
     let wallet_pubkey = Pubkey::from_str(&payload.wallet_address)
         .map_err(|_| ApiError::BadRequest("Invalid wallet address".to_string()))?;
+    if payload.client_request_id.as_ref().is_some_and(|key| key.is_empty() || key.len() > 64) {
+        return Err(ApiError::BadRequest("client_request_id must be 1-64 characters".to_string()));
+    }
+
+    let amounts = FaucetAmounts {
+        sol: requested_amount(payload.amount_sol)?,
+        kwh: requested_amount(payload.mint_tokens_kwh)?,
+        fiat: requested_amount(payload.deposit_fiat)?,
+    };
+    faucet::check_request(&state.config.faucet, &amounts)?;
+
+    let ledger = FaucetLedger::new(state.db.clone(), state.config.faucet.clone());
+    let user_id = ledger
+        .find_user_for_wallet(&payload.wallet_address)
+        .await?
+        .ok_or_else(|| {
+            ApiError::BadRequest("Wallet is not linked to a user; link it before using the faucet".to_string())
+        })?;
+
+    let grant = match ledger
+        .reserve(
+            &payload.wallet_address,
+            user_id,
+            payload.client_request_id.as_deref(),
+            &amounts,
+            payload.promote_to_role.as_deref(),
+        )
+        .await?
+    {
+        FaucetReservation::Granted(grant) => grant,
+        FaucetReservation::Throttled(throttle) => return Ok(throttled_response(throttle)),
+        FaucetReservation::Existing(grant) if grant.status == "completed" => {
+            return Ok(Json(FaucetResponse {
+                success: true,
+                message: grant.message.unwrap_or_default(),
+                sol_tx_signature: grant.sol_tx_signature,
+                token_tx_signature: grant.token_tx_signature,
+                grant_id: grant.id,
+                replayed: true,
+            })
+            .into_response());
+        }
+        FaucetReservation::Existing(_) => {
+            return Err(ApiError::Conflict(
+                "A faucet request with this client_request_id is still in progress".to_string(),
+            ));
+        }
+    };
 
-    let mut sol_sig = None;
-    let mut token_sig = None;
+    let grant_id = grant.id;
+    match grant_funds(&state, &ledger, grant, &wallet_pubkey, user_id).await {
+        Ok(response) => {
+            ledger.complete(grant_id, &response.message).await?;
+            Ok(Json(response).into_response())
+        }
+        Err(e) => {
+            if let Err(record_err) = ledger.fail(grant_id, &e.to_string()).await {
+                tracing::error!("Failed to record faucet grant {} as failed: {}", grant_id, record_err);
+            }
+            Err(e)
+        }
+    }
+}
+
+/// Positive requested amount, zero when absent or not positive
+fn requested_amount(amount: Option<f64>) -> Result<Decimal> {
+    match amount.filter(|amount| *amount > 0.0) {
+        Some(amount) => Decimal::from_f64(amount)
+            .ok_or_else(|| ApiError::BadRequest("Invalid amount".to_string())),
+        None => Ok(Decimal::ZERO),
+    }
+}
+
+/// 429 with the seconds until the next request is allowed
+fn throttled_response(throttle: FaucetThrottle) -> Response {
+    let secs = throttle.retry_after.num_seconds().max(1) as u64;
+    tracing::warn!("🚰 Faucet request throttled: {} (retry in {}s)", throttle.reason, secs);
+
+    let mut response = ApiError::WithCode(
+        ErrorCode::RateLimitExceeded,
+        format!("Faucet {} reached, next request allowed in {}s", throttle.reason, secs),
+    )
+    .into_response();
+    response
+        .headers_mut()
+        .insert(header::RETRY_AFTER, HeaderValue::from(secs));
+    response
+}
+
+/// Perform the steps of a reserved grant that have not been done yet
+///
+/// Each step is recorded as soon as it succeeds, so a failure part way
+/// leaves the completed steps on the grant for the caps and for a retry.
+async fn grant_funds(
+    state: &AppState,
+    ledger: &FaucetLedger,
+    grant: FaucetGrant,
+    wallet_pubkey: &Pubkey,
+    user_id: Uuid,
+) -> Result<FaucetResponse> {
+    let mut sol_sig = grant.sol_tx_signature.clone();
+    let mut token_sig = grant.token_tx_signature.clone();
     let mut messages = Vec::new();
 
     // 1. Airdrop SOL
-    if let Some(amount) = payload.amount_sol {
-        if amount > 0.0 {
-            match state
+    if grant.amount_sol > Decimal::ZERO {
+        if sol_sig.is_none() {
+            let amount = grant.amount_sol.to_f64().unwrap_or_default();
+            let sig = state
                 .wallet_service
-                .request_airdrop(&wallet_pubkey, amount)
+                .request_airdrop(wallet_pubkey, amount)
                 .await
-            {
-                Ok(sig) => {
-                    sol_sig = Some(sig.to_string());
-                    messages.push(format!("Airdropped {} SOL", amount));
-                }
-                Err(e) => {
+                .map_err(|e| {
                     tracing::error!("Faucet Airdrop failed: {}", e);
-                    // Don't fail the whole request, but note it? 
-                    // Or fail? Let's fail if requested explicitly.
-                    return Err(ApiError::Internal(format!("Failed to airdrop SOL: {}", e)));
-                }
-            }
+                    ApiError::Internal(format!("Failed to airdrop SOL: {}", e))
+                })?
+                .to_string();
+            ledger.record_sol_airdrop(grant.id, &sig).await?;
+            sol_sig = Some(sig);
         }
+        messages.push(format!("Airdropped {} SOL", grant.amount_sol));
     }
 
     // 2. Mint Tokens
-    if let Some(kwh) = payload.mint_tokens_kwh {
-        if kwh > 0.0 {
-            // Calculate atomic amount: kwh * 10^9
-            let amount_atomic = (kwh * 1_000_000_000.0) as u64;
-            
-            match state
+    if grant.mint_tokens_kwh > Decimal::ZERO {
+        if token_sig.is_none() {
+            let mint = crate::services::BlockchainService::parse_pubkey(&state.config.energy_token_mint)
+                .map_err(|e| ApiError::Internal(format!("Invalid energy token mint: {}", e)))?;
+            let decimals = state
+                .blockchain_service
+                .decimals_for(&mint)
+                .await
+                .map_err(|e| ApiError::Internal(format!("Failed to resolve energy mint decimals: {}", e)))?;
+            let amount_atomic = to_atomic(grant.mint_tokens_kwh, decimals)
+                .map_err(|e| ApiError::BadRequest(format!("Invalid token amount: {}", e)))?;
+
+            let sig = state
                 .blockchain_service
-                .mint_tokens_direct(&wallet_pubkey, amount_atomic as f64)
+                .mint_tokens_direct(wallet_pubkey, amount_atomic)
                 .await
-            {
-                Ok(sig) => {
-                    token_sig = Some(sig.to_string());
-                    messages.push(format!("Minted {} kWh tokens", kwh));
-                }
-                Err(e) => {
+                .map_err(|e| {
                     tracing::error!("Faucet Minting failed: {}", e);
-                     return Err(ApiError::Internal(format!("Failed to mint tokens: {}", e)));
-                }
-            }
+                    ApiError::Internal(format!("Failed to mint tokens: {}", e))
+                })?
+                .to_string();
+            ledger.record_token_mint(grant.id, &sig).await?;
+            token_sig = Some(sig);
         }
+        messages.push(format!("Minted {} kWh tokens", grant.mint_tokens_kwh));
     }
 
     // 3. Deposit Fiat (Cash) - Only for dev testing
-    if let Some(fiat_amount) = payload.deposit_fiat {
-        if fiat_amount > 0.0 {
-            // Find user by wallet address in user_wallets or users table
-            let user_info = sqlx::query!(
-                r#"
-                SELECT user_id FROM user_wallets WHERE wallet_address = $1
-                UNION
-                SELECT id as user_id FROM users WHERE wallet_address = $1
-                LIMIT 1
-                "#,
-                payload.wallet_address
-            )
-            .fetch_optional(&state.db)
-            .await
-            .map_err(|e| ApiError::Internal(format!("DB error: {}", e)))?;
-
-            if let Some(u) = user_info {
-                use rust_decimal::prelude::FromPrimitive;
-                let amount_dec = rust_decimal::Decimal::from_f64(fiat_amount)
-                    .ok_or(ApiError::BadRequest("Invalid amount".to_string()))?;
-
-                sqlx::query!(
-                    "UPDATE users SET balance = balance + $1 WHERE id = $2",
-                    amount_dec,
-                    u.user_id
-                )
-                .execute(&state.db)
+    if grant.deposit_fiat > Decimal::ZERO {
+        if grant.fiat_deposited_at.is_none() {
+            ledger
+                .deposit_fiat(grant.id, user_id, grant.deposit_fiat)
                 .await
                 .map_err(|e| ApiError::Internal(format!("Failed to deposit funds: {}", e)))?;
-                
-                messages.push(format!("Deposited {} THB", fiat_amount));
-            } else {
-                 messages.push(format!("Wallet {} not linked to user, skipped fiat deposit", payload.wallet_address));
-            }
         }
+        messages.push(format!("Deposited {} THB", grant.deposit_fiat));
     }
 
     // 4. Promote to Role - Only for dev testing
-    if let Some(role) = &payload.promote_to_role {
-        // Find user by wallet address
-        let user_info = sqlx::query!(
-             r#"
-            SELECT user_id FROM user_wallets WHERE wallet_address = $1
-            UNION
-            SELECT id as user_id FROM users WHERE wallet_address = $1
-            LIMIT 1
-            "#,
-            payload.wallet_address
-        )
-        .fetch_optional(&state.db)
-        .await
-        .map_err(|e| ApiError::Internal(format!("DB error: {}", e)))?;
-
-        if let Some(u) = user_info {
-             sqlx::query!(
-                "UPDATE users SET role = $1::text::user_role WHERE id = $2",
-                role,
-                u.user_id
-            )
-            .execute(&state.db)
-            .await
-             .map_err(|e| ApiError::Internal(format!("Failed to update role: {}", e)))?;
-             
-             messages.push(format!("Promoted user to role: {}", role));
-        } else {
-             messages.push(format!("Wallet {} not linked to user, skipped role promotion", payload.wallet_address));
+    if let Some(role) = &grant.promote_to_role {
+        if grant.role_promoted_at.is_none() {
+            ledger
+                .promote_role(grant.id, user_id, role)
+                .await
+                .map_err(|e| ApiError::Internal(format!("Failed to update role: {}", e)))?;
         }
+        messages.push(format!("Promoted user to role: {}", role));
     }
 
-    Ok(Json(FaucetResponse {
+    Ok(FaucetResponse {
         success: true,
         message: if messages.is_empty() {
             "No actions requested".to_string()
@@ -173,5 +242,7 @@ pub async fn request_faucet(
         },
         sol_tx_signature: sol_sig,
         token_tx_signature: token_sig,
-    }))
+        grant_id: grant.id,
+        replayed: false,
+    })
 }
@@ -0,0 +1,156 @@
+//! Renewable Energy Certificate Handler
+//!
+//! Bulk issuance of certificates from confirmed meter readings
+
+use axum::{extract::State, response::Json};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use tracing::{error, info};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::auth::middleware::AuthenticatedUser;
+use crate::error::{ApiError, Result};
+use crate::services::erc::{BatchReadingStatus, ErcCertificate};
+use crate::AppState;
+
+/// Request to aggregate confirmed meter readings into certificates
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct BatchIssueErcRequest {
+    /// Generator the readings belong to
+    pub user_id: Uuid,
+    pub reading_ids: Vec<Uuid>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ErcCertificateResponse {
+    pub id: Uuid,
+    pub certificate_id: String,
+    pub user_id: Option<Uuid>,
+    pub wallet_address: String,
+    #[schema(value_type = String)]
+    pub kwh_amount: Option<Decimal>,
+    pub issue_date: Option<chrono::DateTime<chrono::Utc>>,
+    pub expiry_date: Option<chrono::DateTime<chrono::Utc>>,
+    pub issuer_wallet: Option<String>,
+    pub status: String,
+    pub blockchain_tx_signature: Option<String>,
+    pub metadata: Option<serde_json::Value>,
+}
+
+impl From<ErcCertificate> for ErcCertificateResponse {
+    fn from(c: ErcCertificate) -> Self {
+        Self {
+            id: c.id,
+            certificate_id: c.certificate_id,
+            user_id: c.user_id,
+            wallet_address: c.wallet_address,
+            kwh_amount: c.kwh_amount,
+            issue_date: c.issue_date,
+            expiry_date: c.expiry_date,
+            issuer_wallet: c.issuer_wallet,
+            status: c.status,
+            blockchain_tx_signature: c.blockchain_tx_signature,
+            metadata: c.metadata,
+        }
+    }
+}
+
+/// Per-reading outcome of a batch issuance
+#[derive(Debug, Serialize, ToSchema)]
+pub struct BatchReadingResultResponse {
+    pub reading_id: Uuid,
+    /// "issued" or "skipped"
+    pub status: String,
+    pub certificate_id: Option<String>,
+    pub reason: Option<String>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct BatchIssueErcResponse {
+    pub certificates: Vec<ErcCertificateResponse>,
+    pub readings: Vec<BatchReadingResultResponse>,
+}
+
+/// Check if user has REC authority role
+fn require_rec_authority(user: &crate::auth::Claims) -> Result<()> {
+    if user.role != "rec" && user.role != "admin" {
+        return Err(ApiError::Forbidden("REC authority role required".to_string()));
+    }
+    Ok(())
+}
+
+/// Issue certificates in bulk from a set of confirmed meter readings
+/// POST /api/v1/erc/issue/batch
+#[utoipa::path(
+    post,
+    path = "/api/v1/erc/issue/batch",
+    tag = "erc",
+    security(("bearer_auth" = [])),
+    request_body = BatchIssueErcRequest,
+    responses(
+        (status = 200, description = "Batch issuance report", body = BatchIssueErcResponse),
+        (status = 400, description = "Invalid request data"),
+        (status = 403, description = "Requires REC authority role"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn issue_certificate_batch(
+    State(state): State<AppState>,
+    AuthenticatedUser(user): AuthenticatedUser,
+    Json(request): Json<BatchIssueErcRequest>,
+) -> Result<Json<BatchIssueErcResponse>> {
+    require_rec_authority(&user)?;
+
+    if request.reading_ids.is_empty() {
+        return Err(ApiError::BadRequest("reading_ids must not be empty".to_string()));
+    }
+
+    info!(
+        "REC authority {} batch issuing certificates from {} readings for user {}",
+        user.sub,
+        request.reading_ids.len(),
+        request.user_id
+    );
+
+    let issuer_wallet: Option<String> =
+        sqlx::query_scalar("SELECT wallet_address FROM users WHERE id = $1")
+            .bind(user.sub)
+            .fetch_one(&state.db)
+            .await
+            .map_err(|e| {
+                error!("Failed to fetch issuer: {}", e);
+                ApiError::Internal("Failed to fetch issuer data".to_string())
+            })?;
+
+    let issuer_wallet = issuer_wallet
+        .ok_or_else(|| ApiError::BadRequest("Issuer wallet address not set".to_string()))?;
+
+    let report = state
+        .erc_service
+        .issue_batch(request.user_id, &issuer_wallet, request.reading_ids)
+        .await
+        .map_err(|e| {
+            error!("Failed to batch issue certificates: {}", e);
+            ApiError::Internal(format!("Failed to batch issue certificates: {}", e))
+        })?;
+
+    let readings = report
+        .readings
+        .into_iter()
+        .map(|r| BatchReadingResultResponse {
+            reading_id: r.reading_id,
+            status: match r.status {
+                BatchReadingStatus::Issued => "issued".to_string(),
+                BatchReadingStatus::Skipped => "skipped".to_string(),
+            },
+            certificate_id: r.certificate_id,
+            reason: r.reason,
+        })
+        .collect();
+
+    Ok(Json(BatchIssueErcResponse {
+        certificates: report.certificates.into_iter().map(Into::into).collect(),
+        readings,
+    }))
+}
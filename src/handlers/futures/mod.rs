@@ -105,7 +105,10 @@ pub async fn get_positions(
 #[derive(Deserialize, IntoParams)]
 pub struct GetCandlesRequest {
     pub product_id: Uuid,
+    /// Candle interval: 1m, 5m, 1h or 1d
     pub interval: String,
+    /// Number of most recent candles to return (default 100, max 1000)
+    pub limit: Option<i64>,
 }
 
 /// Get candles for a product
@@ -122,7 +125,7 @@ pub async fn get_candles(
     State(state): State<AppState>,
     Query(req): Query<GetCandlesRequest>,
 ) -> Result<Json<ApiResponse<Vec<crate::services::futures::Candle>>>, ApiError> {
-    let candles = state.futures_service.get_candles(req.product_id, req.interval).await?;
+    let candles = state.futures_service.get_candles(req.product_id, req.interval, req.limit).await?;
     Ok(Json(ApiResponse::success(candles)))
 }
 
@@ -0,0 +1,116 @@
+//! Grid Topology Handler
+//!
+//! Exposes the wheeling charges and loss factors the matching engine applies
+//! between zones, so traders can see why cross-zone trades cost more
+
+use axum::{extract::{Query, State}, response::Json};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use tracing::info;
+use utoipa::{IntoParams, ToSchema};
+
+use crate::error::{ApiError, Result};
+use crate::services::grid_topology::{LandedCostBreakdown, ZoneRouteCost};
+use crate::AppState;
+
+/// Zones and the delivery cost between each pair
+#[derive(Debug, Serialize, ToSchema)]
+pub struct GridTopologyResponse {
+    /// Known zone ids, ascending; rows and columns of the matrices follow this order
+    pub zones: Vec<i32>,
+    /// `wheeling_charges[i][j]`: THB per kWh from `zones[i]` to `zones[j]`
+    #[schema(value_type = Vec<Vec<String>>)]
+    pub wheeling_charges: Vec<Vec<Decimal>>,
+    /// `loss_factors[i][j]`: share lost from `zones[i]` to `zones[j]`
+    #[schema(value_type = Vec<Vec<String>>)]
+    pub loss_factors: Vec<Vec<Decimal>>,
+    /// The same costs as one entry per zone pair
+    pub routes: Vec<ZoneRouteCost>,
+}
+
+/// A hypothetical trade to price
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct CostPreviewQuery {
+    /// Seller's zone; unknown zones get the default cross-grid rates
+    pub seller_zone: Option<i32>,
+    /// Buyer's zone
+    pub buyer_zone: Option<i32>,
+    /// Energy amount in kWh
+    #[param(value_type = String)]
+    pub amount: Decimal,
+    /// Seller's price per kWh
+    #[param(value_type = String)]
+    pub price: Decimal,
+}
+
+/// Get the zone list with wheeling charge and loss factor matrices
+#[utoipa::path(
+    get,
+    path = "/api/v1/grid/topology",
+    tag = "grid",
+    responses(
+        (status = 200, description = "Zones and delivery costs between them", body = GridTopologyResponse),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn get_grid_topology(State(state): State<AppState>) -> Result<Json<GridTopologyResponse>> {
+    info!("🗺️ Getting grid topology");
+
+    let zones = sqlx::query_scalar::<_, i32>(
+        r#"
+        SELECT zone_id FROM meters WHERE zone_id IS NOT NULL
+        UNION
+        SELECT zone_id FROM meter_registry WHERE zone_id IS NOT NULL
+        UNION
+        SELECT from_zone_id FROM zone_rates WHERE is_active = TRUE
+        UNION
+        SELECT to_zone_id FROM zone_rates WHERE is_active = TRUE
+        ORDER BY 1
+        "#,
+    )
+    .fetch_all(&state.db)
+    .await
+    .map_err(ApiError::Database)?;
+
+    let routes = state.grid_topology.route_costs(&zones);
+    let matrix = |cost: fn(&ZoneRouteCost) -> Decimal| -> Vec<Vec<Decimal>> {
+        routes.chunks(zones.len().max(1)).map(|row| row.iter().map(cost).collect()).collect()
+    };
+
+    Ok(Json(GridTopologyResponse {
+        wheeling_charges: matrix(|route| route.wheeling_charge),
+        loss_factors: matrix(|route| route.loss_factor),
+        zones,
+        routes,
+    }))
+}
+
+/// Preview the landed cost of a trade between two zones
+#[utoipa::path(
+    get,
+    path = "/api/v1/grid/cost-preview",
+    tag = "grid",
+    params(CostPreviewQuery),
+    responses(
+        (status = 200, description = "Landed cost breakdown", body = LandedCostBreakdown),
+        (status = 400, description = "Amount must be positive and price non-negative")
+    )
+)]
+pub async fn preview_trade_cost(
+    State(state): State<AppState>,
+    Query(query): Query<CostPreviewQuery>,
+) -> Result<Json<LandedCostBreakdown>> {
+    if query.amount <= Decimal::ZERO {
+        return Err(ApiError::BadRequest("Amount must be positive".to_string()));
+    }
+    if query.price < Decimal::ZERO {
+        return Err(ApiError::BadRequest("Price must not be negative".to_string()));
+    }
+
+    Ok(Json(state.grid_topology.landed_cost(
+        query.seller_zone,
+        query.buyer_zone,
+        query.amount,
+        query.price,
+    )))
+}
@@ -1,18 +1,27 @@
 //! Handlers module - Reorganized structure
 //!
 //! Provides API handlers organized by domain:
+//! - `admin/` - Admin-only handlers (reconciliation, operations)
 //! - `auth/` - Authentication handlers (login, register, profile)
 //! - `meter/` - Meter management handlers (readings, registration)
 //! - `blockchain/` - Blockchain interaction handlers
 //! - `websocket/` - WebSocket handlers
 //! - `notifications` - Push notification handlers
+//! - `erc` - Renewable energy certificate issuance
+//! - `grid` - Grid topology and delivery cost transparency
+//! - `transactions` - Transaction status lookup
+//! - `swap` - AMM pools, swaps and pool price oracle
+//! - `webhooks` - Signed inbound partner callbacks
 //! - `common/` - Shared utilities (extractors, response types)
 //! - `_disabled/` - Disabled/legacy handlers (not exported)
 
 // Domain handlers
+pub mod admin;
 pub mod auth;
 pub mod blockchain;
 pub mod carbon;
+pub mod erc;
+pub mod grid;
 pub mod meter;
 pub mod dev;
 pub mod trading;
@@ -24,6 +33,9 @@ pub mod rpc;
 pub mod proxy;
 pub mod notifications;
 pub mod wallets;
+pub mod transactions;
+pub mod swap;
+pub mod webhooks;
 
 // Shared utilities
 pub mod common;
@@ -31,7 +43,7 @@ pub mod common;
 // Re-export commonly used types from common
 pub use common::{
     DateRangeParams, PaginationParams, SearchParams, SortOrder, ValidatedUuid,
-    ApiResponse, ListResponse, PaginatedResponse,
+    ApiResponse, ListResponse, Paginated, PaginatedResponse,
 };
 
 // Re-export V1 route builders (new RESTful API)
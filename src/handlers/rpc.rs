@@ -1,62 +1,195 @@
 use axum::{
     extract::State,
-    response::IntoResponse,
+    http::{header::AUTHORIZATION, HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
     Json,
 };
 use serde_json::Value;
 use crate::app_state::AppState;
-use tracing::{error, debug};
+use crate::auth::{Claims, Role};
+use crate::config::RpcMethodAccess;
+use crate::middleware::metrics::track_rpc_request;
+use std::time::Duration;
+use tracing::{error, debug, warn};
 
-/// Proxy RPC requests to Solana validator
-pub async fn rpc_handler(
-    State(state): State<AppState>,
-    Json(payload): Json<Value>,
-) -> impl IntoResponse {
+/// JSON-RPC: the request is not a valid request object
+const INVALID_REQUEST: i64 = -32600;
+/// JSON-RPC: the method does not exist or is not available
+const METHOD_NOT_FOUND: i64 = -32601;
+const INTERNAL_ERROR: i64 = -32603;
+/// Server-defined: the upstream call did not finish in time
+const UPSTREAM_TIMEOUT: i64 = -32000;
+/// Server-defined: the method needs a bearer token
+const UNAUTHORIZED: i64 = -32001;
+/// Server-defined: the caller's role may not use the method
+const FORBIDDEN: i64 = -32003;
+
+fn error_object(code: i64, message: &str, id: Option<&Value>) -> Value {
+    serde_json::json!({
+        "jsonrpc": "2.0",
+        "error": {
+            "code": code,
+            "message": message
+        },
+        "id": id
+    })
+}
+
+fn rpc_error(status: StatusCode, code: i64, message: &str, id: Option<&Value>) -> Response {
+    (status, Json(error_object(code, message, id))).into_response()
+}
+
+/// Claims of a valid, unrevoked bearer token, if the request carries one
+async fn bearer_claims(state: &AppState, headers: &HeaderMap) -> Option<Claims> {
+    let token = headers
+        .get(AUTHORIZATION)
+        .and_then(|header| header.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))?;
+    state.jwt_service.verify_token(token).await.ok()
+}
+
+/// Check one call against the allowlist and its method-level auth,
+/// returning its method or the error to answer it with
+///
+/// `claims` caches the bearer token lookup across the calls of a batch.
+async fn authorize<'a>(
+    state: &AppState,
+    headers: &HeaderMap,
+    claims: &mut Option<Option<Claims>>,
+    call: &'a Value,
+) -> Result<&'a str, (StatusCode, Value)> {
+    let id = call.get("id");
+    let Some(method) = call.get("method").and_then(Value::as_str) else {
+        track_rpc_request("other", "invalid");
+        return Err((StatusCode::OK, error_object(INVALID_REQUEST, "Missing method", id)));
+    };
+
+    let access = state.config.rpc_proxy.access(method);
+    if access == RpcMethodAccess::Denied {
+        warn!("Rejected RPC method not in allowlist: {}", method);
+        track_rpc_request("other", "rejected");
+        return Err((StatusCode::OK, error_object(METHOD_NOT_FOUND, "Method not found or not permitted", id)));
+    }
+
+    if matches!(access, RpcMethodAccess::Authenticated | RpcMethodAccess::Admin) {
+        if claims.is_none() {
+            *claims = Some(bearer_claims(state, headers).await);
+        }
+        let Some(user) = claims.as_ref().and_then(Option::as_ref) else {
+            track_rpc_request(method, "unauthorized");
+            return Err((StatusCode::UNAUTHORIZED, error_object(UNAUTHORIZED, "Authentication required", id)));
+        };
+        if access == RpcMethodAccess::Admin && user.role.parse::<Role>().ok() != Some(Role::Admin) {
+            warn!("User {} denied admin RPC method {}", user.sub, method);
+            track_rpc_request(method, "forbidden");
+            return Err((StatusCode::FORBIDDEN, error_object(FORBIDDEN, "Admin access required", id)));
+        }
+    }
+
+    Ok(method)
+}
+
+/// Send one call upstream, bounded by the configured per-call timeout
+async fn forward(state: &AppState, method: &str, call: &Value) -> (StatusCode, Value) {
     let rpc_url = &state.config.solana_rpc_url;
-    
-    debug!("Proxying RPC request to {}", rpc_url);
+    let timeout = Duration::from_millis(state.config.rpc_proxy.request_timeout_ms);
+    let id = call.get("id");
+
+    debug!("Proxying RPC {} to {}", method, rpc_url);
 
-    let client = reqwest::Client::new();
-    
-    let res = match client.post(rpc_url)
-        .json(&payload)
+    let res = match state.http_client.post(rpc_url)
+        .timeout(timeout)
+        .json(call)
         .send()
         .await {
             Ok(res) => res,
+            Err(e) if e.is_timeout() => {
+                warn!("RPC {} timed out after {:?}", method, timeout);
+                track_rpc_request(method, "timeout");
+                return (StatusCode::GATEWAY_TIMEOUT, error_object(UPSTREAM_TIMEOUT, "Upstream request timed out", id));
+            }
             Err(e) => {
                 error!("Failed to proxy RPC request: {}", e);
-                return (
-                    axum::http::StatusCode::BAD_GATEWAY,
-                    Json(serde_json::json!({
-                        "jsonrpc": "2.0",
-                        "error": {
-                            "code": -32603,
-                            "message": "Internal error proxying request"
-                        },
-                        "id": payload.get("id")
-                    }))
-                ).into_response();
+                track_rpc_request(method, "upstream_error");
+                return (StatusCode::BAD_GATEWAY, error_object(INTERNAL_ERROR, "Internal error proxying request", id));
             }
         };
 
     let status = res.status();
-    let body: Value = match res.json().await {
-        Ok(b) => b,
+    match res.json().await {
+        Ok(body) => {
+            track_rpc_request(method, "forwarded");
+            (status, body)
+        }
         Err(e) => {
-             error!("Failed to parse RPC response: {}", e);
-             return (
-                axum::http::StatusCode::BAD_GATEWAY,
-                Json(serde_json::json!({
-                    "jsonrpc": "2.0",
-                    "error": {
-                        "code": -32603,
-                        "message": "Invalid JSON response from upstream"
-                    },
-                    "id": payload.get("id")
-                }))
-            ).into_response();
+            error!("Failed to parse RPC response: {}", e);
+            track_rpc_request(method, "upstream_error");
+            (StatusCode::BAD_GATEWAY, error_object(INTERNAL_ERROR, "Invalid JSON response from upstream", id))
         }
+    }
+}
+
+/// Proxy RPC requests to Solana validator
+///
+/// Only methods in `RPC_ALLOWED_METHODS` are forwarded; sensitive ones also
+/// need a bearer token or the admin role. Each call of a batch is
+/// authorized and sent on its own, concurrently, and the responses are
+/// returned in request order; calls without an `id` are notifications and
+/// get no response, so a batch of only notifications returns 204 No Content.
+pub async fn rpc_handler(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(payload): Json<Value>,
+) -> impl IntoResponse {
+    let mut claims = None;
+
+    let Value::Array(calls) = &payload else {
+        let (status, body) = match authorize(&state, &headers, &mut claims, &payload).await {
+            Ok(method) => forward(&state, method, &payload).await,
+            Err(rejection) => rejection,
+        };
+        return (status, Json(body)).into_response();
     };
 
-    (status, Json(body)).into_response()
+    let max_batch_size = state.config.rpc_proxy.max_batch_size;
+    if calls.is_empty() {
+        return rpc_error(StatusCode::OK, INVALID_REQUEST, "Empty batch", None);
+    }
+    if calls.len() > max_batch_size {
+        return rpc_error(
+            StatusCode::OK,
+            INVALID_REQUEST,
+            &format!("Batch of {} calls exceeds the limit of {}", calls.len(), max_batch_size),
+            None,
+        );
+    }
+
+    let mut authorized = Vec::with_capacity(calls.len());
+    for call in calls {
+        authorized.push(authorize(&state, &headers, &mut claims, call).await);
+    }
+
+    let responses = futures::future::join_all(calls.iter().zip(authorized).map(|(call, auth)| {
+        let state = &state;
+        async move {
+            match auth {
+                Ok(method) => forward(state, method, call).await.1,
+                Err((_, error)) => error,
+            }
+        }
+    }))
+    .await;
+
+    let responses: Vec<Value> = calls
+        .iter()
+        .zip(responses)
+        .filter(|(call, _)| call.get("id").is_some())
+        .map(|(_, response)| response)
+        .collect();
+
+    // A batch of only notifications gets no body, not an empty array
+    if responses.is_empty() {
+        return StatusCode::NO_CONTENT.into_response();
+    }
+    (StatusCode::OK, Json(Value::Array(responses))).into_response()
 }
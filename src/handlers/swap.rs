@@ -0,0 +1,229 @@
+//! AMM Swap Handler
+//!
+//! Pool listing, swap quotes and execution, and time-weighted pool prices
+
+use crate::auth::middleware::AuthenticatedUser;
+use crate::error::{ApiError, Result};
+use crate::models::amm::SwapQuote;
+use crate::services::amm::{PoolTwap, SwapTransaction};
+use crate::AppState;
+use axum::{
+    extract::{Path, Query, State},
+    Json,
+};
+use std::time::Duration;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+use validator::{Validate, ValidationError};
+
+fn validate_positive_decimal(amount: &Decimal) -> std::result::Result<(), ValidationError> {
+    if amount <= &Decimal::ZERO {
+        return Err(ValidationError::new("amount_must_be_positive"));
+    }
+    Ok(())
+}
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct QuoteRequest {
+    pub pool_id: Uuid,
+    #[validate(length(min = 1))]
+    pub input_token: String,
+    #[validate(custom(function = "validate_positive_decimal"))]
+    #[schema(value_type = String)]
+    pub input_amount: Decimal,
+}
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct ExecuteSwapRequest {
+    pub pool_id: Uuid,
+    #[validate(length(min = 1))]
+    pub input_token: String,
+    #[validate(custom(function = "validate_positive_decimal"))]
+    #[schema(value_type = String)]
+    pub input_amount: Decimal,
+    #[validate(custom(function = "validate_positive_decimal"))]
+    #[schema(value_type = String)]
+    pub min_output_amount: Decimal,
+}
+
+/// Default TWAP window when none is requested
+const DEFAULT_TWAP_WINDOW_SECS: u64 = 3600;
+/// Longest TWAP window accepted (7 days)
+const MAX_TWAP_WINDOW_SECS: u64 = 7 * 24 * 3600;
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct TwapQuery {
+    /// Averaging window in seconds (default 1 hour)
+    pub window_secs: Option<u64>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct PoolResponse {
+    pub id: Uuid,
+    pub token_a: String,
+    pub token_b: String,
+    #[schema(value_type = String)]
+    pub reserve_a: Decimal,
+    #[schema(value_type = String)]
+    pub reserve_b: Decimal,
+    #[schema(value_type = String)]
+    pub fee_rate: Decimal,
+}
+
+/// Get a quote for a swap
+/// POST /api/v1/amm/quote
+#[utoipa::path(
+    post,
+    path = "/api/v1/amm/quote",
+    tag = "amm",
+    request_body = QuoteRequest,
+    responses(
+        (status = 200, description = "Expected output at the current reserves", body = SwapQuote),
+        (status = 400, description = "Invalid amount, unknown token or empty pool"),
+        (status = 404, description = "Pool not found")
+    )
+)]
+pub async fn get_quote(
+    State(state): State<AppState>,
+    Json(payload): Json<QuoteRequest>,
+) -> Result<Json<SwapQuote>> {
+    payload
+        .validate()
+        .map_err(|e| ApiError::BadRequest(e.to_string()))?;
+
+    let quote = state
+        .amm_service
+        .calculate_swap_output(payload.pool_id, &payload.input_token, payload.input_amount)
+        .await?;
+
+    Ok(Json(quote))
+}
+
+/// Execute a swap (admin only)
+/// POST /api/v1/amm/swap
+///
+/// Only pool reserves change; user token balances are not settled yet.
+#[utoipa::path(
+    post,
+    path = "/api/v1/amm/swap",
+    tag = "amm",
+    security(("bearer_auth" = [])),
+    request_body = ExecuteSwapRequest,
+    responses(
+        (status = 200, description = "Completed swap", body = SwapTransaction),
+        (status = 400, description = "Invalid amount or slippage tolerance exceeded"),
+        (status = 403, description = "Admin role required"),
+        (status = 404, description = "Pool not found"),
+        (status = 409, description = "Pool busy, retry")
+    )
+)]
+pub async fn execute_swap(
+    State(state): State<AppState>,
+    AuthenticatedUser(user): AuthenticatedUser,
+    Json(payload): Json<ExecuteSwapRequest>,
+) -> Result<Json<SwapTransaction>> {
+    payload
+        .validate()
+        .map_err(|e| ApiError::BadRequest(e.to_string()))?;
+
+    let transaction = state
+        .amm_service
+        .execute_swap(
+            user.sub,
+            payload.pool_id,
+            payload.input_token,
+            payload.input_amount,
+            payload.min_output_amount,
+        )
+        .await?;
+
+    Ok(Json(transaction))
+}
+
+/// List all available liquidity pools
+/// GET /api/v1/amm/pools
+#[utoipa::path(
+    get,
+    path = "/api/v1/amm/pools",
+    tag = "amm",
+    responses(
+        (status = 200, description = "Liquidity pools and their reserves", body = Vec<PoolResponse>)
+    )
+)]
+pub async fn list_pools(
+    State(state): State<AppState>,
+) -> Result<Json<Vec<PoolResponse>>> {
+    let pools = state.amm_service.list_pools().await?;
+
+    let response = pools
+        .into_iter()
+        .map(|p| PoolResponse {
+            id: p.id,
+            token_a: p.token_a,
+            token_b: p.token_b,
+            reserve_a: p.reserve_a,
+            reserve_b: p.reserve_b,
+            fee_rate: p.fee_rate,
+        })
+        .collect();
+
+    Ok(Json(response))
+}
+
+/// Get user's swap history
+/// GET /api/v1/amm/swaps
+#[utoipa::path(
+    get,
+    path = "/api/v1/amm/swaps",
+    tag = "amm",
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "The user's 50 most recent swaps", body = Vec<SwapTransaction>),
+        (status = 401, description = "Unauthorized")
+    )
+)]
+pub async fn get_swap_history(
+    State(state): State<AppState>,
+    AuthenticatedUser(user): AuthenticatedUser,
+) -> Result<Json<Vec<SwapTransaction>>> {
+    let history = state.amm_service.get_user_swap_history(user.sub).await?;
+    Ok(Json(history))
+}
+
+/// Get a pool's time-weighted average price
+/// GET /api/v1/amm/pools/{id}/twap
+#[utoipa::path(
+    get,
+    path = "/api/v1/amm/pools/{id}/twap",
+    tag = "amm",
+    params(
+        ("id" = Uuid, Path, description = "Pool ID"),
+        TwapQuery
+    ),
+    responses(
+        (status = 200, description = "Average prices over the covered window", body = PoolTwap),
+        (status = 400, description = "Window out of range or not enough price history"),
+        (status = 404, description = "Pool not found or no price observations yet")
+    )
+)]
+pub async fn get_pool_twap(
+    State(state): State<AppState>,
+    Path(pool_id): Path<Uuid>,
+    Query(query): Query<TwapQuery>,
+) -> Result<Json<PoolTwap>> {
+    let window_secs = query.window_secs.unwrap_or(DEFAULT_TWAP_WINDOW_SECS);
+    if window_secs == 0 || window_secs > MAX_TWAP_WINDOW_SECS {
+        return Err(ApiError::BadRequest(format!(
+            "window_secs must be between 1 and {}",
+            MAX_TWAP_WINDOW_SECS
+        )));
+    }
+
+    let twap = state
+        .amm_service
+        .get_twap(pool_id, Duration::from_secs(window_secs))
+        .await?;
+    Ok(Json(twap))
+}
@@ -36,13 +36,7 @@ pub async fn get_blockchain_market_data(
     info!("Fetching blockchain trading market data");
 
     // Get the Trading program ID
-    let trading_program_id = _state
-        .blockchain_service
-        .trading_program_id()
-        .map_err(|e| {
-            error!("Failed to parse trading program ID: {}", e);
-            ApiError::Internal(format!("Invalid program ID: {}", e))
-        })?;
+    let trading_program_id = _state.blockchain_service.trading_program_id();
 
     // Derive the market PDA
     // Market PDA seeds: ["market"]
@@ -155,6 +149,11 @@ pub async fn create_blockchain_order(
         _ => return Err(ApiError::BadRequest("Invalid order type".into())),
     };
 
+    let expires_at = _state
+        .market_clearing
+        .order_expiry(None, now)
+        .map_err(|e| ApiError::BadRequest(e.message))?;
+
     // Convert u64 to Decimal
     let energy_amount = rust_decimal::Decimal::from(payload.energy_amount);
     let price = rust_decimal::Decimal::from(payload.price_per_kwh);
@@ -174,7 +173,7 @@ pub async fn create_blockchain_order(
         price,
         rust_decimal::Decimal::ZERO,
         OrderStatus::Pending as OrderStatus,
-        now + chrono::Duration::days(1),
+        expires_at,
         now,
         epoch.id
     )
@@ -55,7 +55,12 @@ pub async fn create_conditional_order(
 
     let order_id = Uuid::new_v4();
     let now = Utc::now();
-    let expires_at = payload.expiry_time.unwrap_or_else(|| now + Duration::days(7));
+    // Conditional orders wait for their trigger, so they default to a week
+    // rather than the regular order lifetime; both are capped at the max
+    let expires_at = state
+        .market_clearing
+        .order_expiry(Some(payload.expiry_time.unwrap_or_else(|| now + Duration::days(7))), now)
+        .map_err(|e| ApiError::BadRequest(e.message))?;
     
     // Determine order type based on limit_price
     let order_type = if payload.limit_price.is_some() {
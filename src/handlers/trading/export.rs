@@ -1,20 +1,33 @@
 //! Order History Export Handler
 //!
-//! Exports trading history in CSV format
+//! Exports trading history in CSV format, and streams settlement history as
+//! CSV or newline-delimited JSON
 
 use axum::{
+    body::Body,
     extract::{State, Query},
     response::{IntoResponse, Response},
     http::{header, StatusCode},
 };
 use chrono::{DateTime, Utc, NaiveDate};
+use futures::{SinkExt, StreamExt};
+use rust_decimal::Decimal;
 use serde::Deserialize;
 use tracing::{info, error};
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
 
 use crate::auth::middleware::AuthenticatedUser;
 use crate::database::schema::types::{OrderSide, OrderStatus, OrderType};
+use crate::error::{ApiError, Result};
+use crate::middleware::spawn_in_request;
 use crate::AppState;
 
+/// Rendered rows buffered between the database cursor and the response body
+const HISTORY_EXPORT_BUFFER_ROWS: usize = 64;
+
+const HISTORY_CSV_HEADER: &str = "settlement_id,epoch_id,role,counterparty_id,energy_amount_kwh,price_per_kwh,total_amount,fee_amount,wheeling_charge,loss_cost,net_amount,status,created_at,processed_at,tx_signature\n";
+
 /// Query params for export
 #[derive(Debug, Deserialize)]
 pub struct ExportQuery {
@@ -268,3 +281,274 @@ pub async fn export_json(
         serde_json::to_string_pretty(&response).unwrap_or_default(),
     ).into_response()
 }
+
+/// Output format for the settlement history export
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum HistoryExportFormat {
+    #[default]
+    Csv,
+    /// Newline-delimited JSON, one settlement per line
+    Json,
+}
+
+/// Query params for the settlement history export
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct HistoryExportQuery {
+    /// `csv` (default) or `json`
+    #[serde(default)]
+    pub format: HistoryExportFormat,
+    /// Start date (YYYY-MM-DD), inclusive
+    pub start_date: Option<NaiveDate>,
+    /// End date (YYYY-MM-DD), inclusive
+    pub end_date: Option<NaiveDate>,
+}
+
+/// One settlement from the user's point of view
+#[derive(Debug, sqlx::FromRow)]
+struct SettlementExportRow {
+    id: Uuid,
+    epoch_id: Uuid,
+    role: String,
+    counterparty_id: Uuid,
+    energy_amount: Decimal,
+    price_per_kwh: Decimal,
+    total_amount: Decimal,
+    fee_amount: Decimal,
+    wheeling_charge: Option<Decimal>,
+    loss_cost: Option<Decimal>,
+    net_amount: Decimal,
+    status: String,
+    created_at: Option<DateTime<Utc>>,
+    processed_at: Option<DateTime<Utc>>,
+    tx_signature: Option<String>,
+}
+
+impl SettlementExportRow {
+    fn to_csv_line(&self) -> String {
+        fn opt<T: ToString>(value: &Option<T>) -> String {
+            value.as_ref().map(ToString::to_string).unwrap_or_default()
+        }
+        fn ts(value: &Option<DateTime<Utc>>) -> String {
+            value.map(|t| t.to_rfc3339()).unwrap_or_default()
+        }
+
+        format!(
+            "{},{},{},{},{},{},{},{},{},{},{},{},{},{},{}\n",
+            self.id,
+            self.epoch_id,
+            self.role,
+            self.counterparty_id,
+            self.energy_amount,
+            self.price_per_kwh,
+            self.total_amount,
+            self.fee_amount,
+            opt(&self.wheeling_charge),
+            opt(&self.loss_cost),
+            self.net_amount,
+            self.status,
+            ts(&self.created_at),
+            ts(&self.processed_at),
+            opt(&self.tx_signature),
+        )
+    }
+
+    /// Amounts are written as strings so no precision is lost
+    fn to_json_line(&self) -> String {
+        let value = serde_json::json!({
+            "settlement_id": self.id,
+            "epoch_id": self.epoch_id,
+            "role": self.role,
+            "counterparty_id": self.counterparty_id,
+            "energy_amount_kwh": self.energy_amount.to_string(),
+            "price_per_kwh": self.price_per_kwh.to_string(),
+            "total_amount": self.total_amount.to_string(),
+            "fee_amount": self.fee_amount.to_string(),
+            "wheeling_charge": self.wheeling_charge.map(|d| d.to_string()),
+            "loss_cost": self.loss_cost.map(|d| d.to_string()),
+            "net_amount": self.net_amount.to_string(),
+            "status": self.status,
+            "created_at": self.created_at,
+            "processed_at": self.processed_at,
+            "tx_signature": self.tx_signature,
+        });
+        format!("{}\n", value)
+    }
+}
+
+/// Export the user's full settlement history
+/// GET /api/v1/trading/history/export
+///
+/// Rows are streamed from a database cursor into a chunked response, so the
+/// full history is never held in memory.
+#[utoipa::path(
+    get,
+    path = "/api/v1/trading/history/export",
+    tag = "trading",
+    params(HistoryExportQuery),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "CSV (text/csv) or NDJSON (application/x-ndjson) download"),
+        (status = 400, description = "Invalid date range"),
+        (status = 401, description = "Unauthorized")
+    )
+)]
+pub async fn export_trading_history(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+    Query(params): Query<HistoryExportQuery>,
+) -> Result<Response> {
+    if let (Some(start), Some(end)) = (params.start_date, params.end_date) {
+        if start > end {
+            return Err(ApiError::BadRequest(
+                "start_date must not be after end_date".to_string(),
+            ));
+        }
+    }
+
+    info!(
+        "Exporting settlement history ({:?}) for user: {}",
+        params.format, user.0.sub
+    );
+
+    let start = params.start_date
+        .and_then(|d| d.and_hms_opt(0, 0, 0))
+        .map(|dt| DateTime::<Utc>::from_naive_utc_and_offset(dt, Utc));
+    // Exclusive upper bound at the start of the day after end_date
+    let end = params.end_date
+        .and_then(|d| d.succ_opt())
+        .and_then(|d| d.and_hms_opt(0, 0, 0))
+        .map(|dt| DateTime::<Utc>::from_naive_utc_and_offset(dt, Utc));
+
+    let format = params.format;
+    let user_id = user.0.sub;
+    let db = state.db.clone();
+    let (mut tx, rx) = futures::channel::mpsc::channel::<std::result::Result<String, std::io::Error>>(
+        HISTORY_EXPORT_BUFFER_ROWS,
+    );
+
+    spawn_in_request(async move {
+        if format == HistoryExportFormat::Csv
+            && tx.send(Ok(HISTORY_CSV_HEADER.to_string())).await.is_err()
+        {
+            return;
+        }
+
+        let mut rows = sqlx::query_as::<_, SettlementExportRow>(
+            r#"
+            SELECT
+                s.id, s.epoch_id,
+                CASE WHEN s.buyer_id = $1 THEN 'buyer' ELSE 'seller' END AS role,
+                CASE WHEN s.buyer_id = $1 THEN s.seller_id ELSE s.buyer_id END AS counterparty_id,
+                s.energy_amount, s.price_per_kwh, s.total_amount, s.fee_amount,
+                s.wheeling_charge, s.loss_cost, s.net_amount, s.status,
+                s.created_at, s.processed_at,
+                s.transaction_hash AS tx_signature
+            FROM settlements s
+            WHERE (s.buyer_id = $1 OR s.seller_id = $1)
+              AND ($2::timestamptz IS NULL OR s.created_at >= $2)
+              AND ($3::timestamptz IS NULL OR s.created_at < $3)
+            ORDER BY s.created_at ASC
+            "#,
+        )
+        .bind(user_id)
+        .bind(start)
+        .bind(end)
+        .fetch(&db);
+
+        let mut exported = 0usize;
+        while let Some(row) = rows.next().await {
+            let chunk = match row {
+                Ok(row) => match format {
+                    HistoryExportFormat::Csv => row.to_csv_line(),
+                    HistoryExportFormat::Json => row.to_json_line(),
+                },
+                Err(e) => {
+                    // Aborting the body tells the client the download is incomplete
+                    error!("Settlement export for user {} failed after {} rows: {}", user_id, exported, e);
+                    let _ = tx.send(Err(std::io::Error::other(e.to_string()))).await;
+                    return;
+                }
+            };
+            if tx.send(Ok(chunk)).await.is_err() {
+                // Client went away
+                return;
+            }
+            exported += 1;
+        }
+
+        info!("Exported {} settlements for user {}", exported, user_id);
+    });
+
+    let (content_type, extension) = match format {
+        HistoryExportFormat::Csv => ("text/csv; charset=utf-8", "csv"),
+        HistoryExportFormat::Json => ("application/x-ndjson", "ndjson"),
+    };
+    let filename = format!(
+        "gridtokenx_settlements_{}.{}",
+        Utc::now().format("%Y%m%d_%H%M%S"),
+        extension
+    );
+
+    Ok((
+        StatusCode::OK,
+        [
+            (header::CONTENT_TYPE, content_type.to_string()),
+            (header::CONTENT_DISPOSITION, format!("attachment; filename=\"{}\"", filename)),
+        ],
+        Body::from_stream(rx),
+    )
+        .into_response())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row() -> SettlementExportRow {
+        SettlementExportRow {
+            id: Uuid::nil(),
+            epoch_id: Uuid::nil(),
+            role: "seller".to_string(),
+            counterparty_id: Uuid::nil(),
+            energy_amount: Decimal::new(125, 1),
+            price_per_kwh: Decimal::new(35, 1),
+            total_amount: Decimal::new(4375, 2),
+            fee_amount: Decimal::new(44, 2),
+            wheeling_charge: None,
+            loss_cost: Some(Decimal::new(12, 2)),
+            net_amount: Decimal::new(4319, 2),
+            status: "completed".to_string(),
+            created_at: None,
+            processed_at: None,
+            tx_signature: Some("5sig".to_string()),
+        }
+    }
+
+    #[test]
+    fn test_csv_line_matches_header_columns() {
+        let line = row().to_csv_line();
+        assert_eq!(
+            line.trim_end().split(',').count(),
+            HISTORY_CSV_HEADER.trim_end().split(',').count()
+        );
+        assert!(line.contains(",12.5,3.5,43.75,0.44,,0.12,43.19,completed,"));
+        assert!(line.ends_with(",5sig\n"));
+    }
+
+    #[test]
+    fn test_json_line_keeps_decimal_precision() {
+        let line = row().to_json_line();
+        assert!(line.ends_with('\n'));
+        let value: serde_json::Value = serde_json::from_str(line.trim_end()).unwrap();
+        assert_eq!(value["total_amount"], "43.75");
+        assert_eq!(value["wheeling_charge"], serde_json::Value::Null);
+        assert_eq!(value["role"], "seller");
+    }
+
+    #[test]
+    fn test_format_defaults_to_csv() {
+        let query: HistoryExportQuery = serde_json::from_str("{}").unwrap();
+        assert_eq!(query.format, HistoryExportFormat::Csv);
+    }
+}
@@ -0,0 +1,41 @@
+//! Trading Exposure Endpoint
+//!
+//! Portfolio view of the caller's balances, open orders and recent settlements
+
+use axum::{extract::State, response::Json};
+use tracing::error;
+
+use crate::auth::middleware::AuthenticatedUser;
+use crate::error::{ApiError, Result};
+use crate::services::trading_account::TradingExposure;
+use crate::AppState;
+
+/// Get the caller's trading exposure
+/// GET /api/v1/trading/exposure
+#[utoipa::path(
+    get,
+    path = "/api/v1/trading/exposure",
+    tag = "trading",
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Balances, open order exposure and 24h settled flows", body = TradingExposure),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "User not found")
+    )
+)]
+pub async fn get_exposure(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+) -> Result<Json<TradingExposure>> {
+    let exposure = state
+        .trading_account
+        .exposure(user.0.sub)
+        .await
+        .map_err(|e| {
+            error!("Failed to compute exposure for user {}: {}", user.0.sub, e);
+            ApiError::Internal("Failed to compute trading exposure".to_string())
+        })?
+        .ok_or_else(|| ApiError::NotFound("User not found".to_string()))?;
+
+    Ok(Json(exposure))
+}
@@ -111,11 +111,19 @@ pub async fn get_orderbook(State(state): State<AppState>) -> Result<Json<super::
     // Get buy orders
     let buy_orders = sqlx::query(
         r#"
-        SELECT o.energy_amount, o.price_per_kwh, u.username
+        SELECT
+            CASE
+                WHEN o.display_quantity IS NULL THEN o.energy_amount
+                ELSE LEAST(
+                    o.energy_amount - COALESCE(o.filled_amount, 0),
+                    o.display_quantity - MOD(COALESCE(o.filled_amount, 0), o.display_quantity)
+                )
+            END AS energy_amount,
+            o.price_per_kwh, u.username
         FROM trading_orders o
         JOIN users u ON o.user_id = u.id
         WHERE o.side = 'buy' AND o.status = 'pending'
-        ORDER BY o.price_per_kwh DESC, o.created_at ASC
+        ORDER BY o.price_per_kwh DESC, COALESCE(o.display_refreshed_at, o.created_at) ASC
         LIMIT 50
         "#,
     )
@@ -126,11 +134,19 @@ pub async fn get_orderbook(State(state): State<AppState>) -> Result<Json<super::
     // Get sell orders
     let sell_orders = sqlx::query(
         r#"
-        SELECT o.energy_amount, o.price_per_kwh, u.username
+        SELECT
+            CASE
+                WHEN o.display_quantity IS NULL THEN o.energy_amount
+                ELSE LEAST(
+                    o.energy_amount - COALESCE(o.filled_amount, 0),
+                    o.display_quantity - MOD(COALESCE(o.filled_amount, 0), o.display_quantity)
+                )
+            END AS energy_amount,
+            o.price_per_kwh, u.username
         FROM trading_orders o
         JOIN users u ON o.user_id = u.id
         WHERE o.side = 'sell' AND o.status = 'pending'
-        ORDER BY o.price_per_kwh ASC, o.created_at ASC
+        ORDER BY o.price_per_kwh ASC, COALESCE(o.display_refreshed_at, o.created_at) ASC
         LIMIT 50
         "#,
     )
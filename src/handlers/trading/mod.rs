@@ -1,11 +1,13 @@
 pub mod blockchain;
 pub mod conditional;
 pub mod export;
+pub mod exposure;
 pub mod market_data;
 pub mod orders;
 pub mod p2p;
 pub mod price_alerts;
 pub mod recurring;
+pub mod settlements;
 pub mod status;
 pub mod types;
 pub mod routes;
@@ -14,11 +16,13 @@ pub mod revenue;
 pub use blockchain::*;
 pub use conditional::*;
 pub use export::*;
+pub use exposure::*;
 pub use market_data::*;
 pub use orders::*;
 pub use p2p::*;
 pub use price_alerts::*;
 pub use recurring::*;
+pub use settlements::*;
 pub use status::*;
 pub use types::*;
 pub use revenue::*;
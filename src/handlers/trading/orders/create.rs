@@ -4,8 +4,9 @@ use chrono::Utc;
 
 use crate::auth::middleware::AuthenticatedUser;
 use crate::database::schema::types::OrderStatus;
-use crate::error::{ApiError, Result};
-use crate::models::trading::CreateOrderRequest;
+use crate::error::{ApiError, ErrorCode, Result};
+use crate::models::trading::{CreateOrderRequest, OrderCloseReason};
+use crate::services::market_clearing::{OrderOptions, OrderRejected};
 use crate::AppState;
 use crate::handlers::websocket::broadcaster::broadcast_p2p_order_update;
 
@@ -21,7 +22,7 @@ use crate::handlers::trading::types::CreateOrderResponse;
     security(("bearer_auth" = [])),
     responses(
         (status = 200, description = "Order created successfully", body = CreateOrderResponse),
-        (status = 400, description = "Invalid order parameters"),
+        (status = 400, description = "Invalid order parameters or order rejected; `error.details` carries the rejection reason"),
         (status = 401, description = "Unauthorized"),
         (status = 500, description = "Internal server error")
     )
@@ -33,6 +34,34 @@ pub async fn create_order(
 ) -> Result<Json<CreateOrderResponse>> {
     tracing::info!("Creating trading order for user: {}", user.0.sub);
 
+    // Idempotent retry: return the order already created with this key
+    if let Some(client_order_id) = payload.client_order_id.as_deref() {
+        if client_order_id.is_empty() || client_order_id.len() > 64 {
+            return Err(ApiError::BadRequest(
+                "client_order_id must be 1-64 characters".to_string(),
+            ));
+        }
+
+        let existing = state
+            .market_clearing
+            .find_order_by_client_id(user.0.sub, client_order_id)
+            .await
+            .map_err(|e| {
+                tracing::error!("Failed to look up client_order_id: {}", e);
+                ApiError::Internal("Failed to check for duplicate order".to_string())
+            })?;
+
+        if let Some((id, status, created_at)) = existing {
+            tracing::info!("Returning existing order {} for client_order_id {}", id, client_order_id);
+            return Ok(Json(CreateOrderResponse {
+                id,
+                status,
+                created_at,
+                message: "Order already exists for this client_order_id.".to_string(),
+            }));
+        }
+    }
+
     // Verify signature if provided (P2P orders)
     if let (Some(signature), Some(timestamp)) = (&payload.signature, payload.timestamp) {
         use hmac::{Hmac, Mac};
@@ -135,7 +164,7 @@ pub async fn create_order(
     // Call MarketClearingService to handle order creation (DB + On-Chain)
     let order_id = state
         .market_clearing
-        .create_order(
+        .create_order_with_options(
             user.0.sub,
             payload.side,
             payload.order_type,
@@ -145,9 +174,18 @@ pub async fn create_order(
             zone_id,
             payload.meter_id,
             payload.session_token.as_deref(),
+            payload.client_order_id.as_deref(),
+            OrderOptions {
+                display_quantity: payload.display_quantity,
+                time_in_force: payload.time_in_force.unwrap_or_default(),
+            },
         )
         .await
         .map_err(|e| {
+            if let Some(rejected) = e.downcast_ref::<OrderRejected>() {
+                tracing::info!("Order rejected for user {}: {} ({})", user.0.sub, rejected.message, rejected.reason);
+                return order_rejected_error(rejected);
+            }
             tracing::error!("Failed to create order via service: {}", e);
             ApiError::Internal(format!("Order creation failed: {}", e))
         })?;
@@ -183,3 +221,14 @@ pub async fn create_order(
         ),
     }))
 }
+
+/// Map a service-side rejection to a 400 carrying the reason code in `details`
+fn order_rejected_error(rejected: &OrderRejected) -> ApiError {
+    let code = match rejected.reason {
+        OrderCloseReason::InsufficientFunds | OrderCloseReason::InsufficientEnergy => {
+            ErrorCode::InsufficientBalance
+        }
+        _ => ErrorCode::InvalidInput,
+    };
+    ApiError::with_details(code, rejected.message.clone(), rejected.reason.as_str())
+}
@@ -1,12 +1,14 @@
 use axum::{
-    extract::{Path, State},
+    extract::{Path, Query, State},
     response::Json,
 };
 use uuid::Uuid;
 
 use crate::auth::middleware::AuthenticatedUser;
 use crate::error::{ApiError, Result};
-use crate::models::trading::{CreateOrderRequest, TradingOrder};
+use crate::models::trading::{CreateOrderRequest, OrderCloseReason, ReduceOrderRequest, TradingOrder};
+use crate::handlers::trading::types::CancelOrdersQuery;
+use crate::services::market_clearing::{BulkCancelResult, CancelFilter};
 use crate::AppState;
 
 /// Cancel a trading order
@@ -55,38 +57,82 @@ pub async fn cancel_order(
 
     // 3. Update status to cancelled
     let updated_order = sqlx::query_as::<_, crate::models::trading::TradingOrderDb>(
-        "UPDATE trading_orders SET status = 'cancelled', updated_at = NOW() WHERE id = $1 RETURNING *"
+        "UPDATE trading_orders SET status = 'cancelled', cancellation_reason = $2, updated_at = NOW() WHERE id = $1 RETURNING *"
     )
     .bind(order_id)
+    .bind(OrderCloseReason::UserCancelled.as_str())
     .fetch_one(&state.db)
     .await
     .map_err(ApiError::Database)?;
+    state.market_clearing.invalidate_order_book().await;
 
     // 4. Refund Escrow for remaining portion
     use rust_decimal::Decimal;
     use crate::database::schema::types::OrderSide;
     
     let remaining_amount = updated_order.energy_amount - updated_order.filled_amount.unwrap_or(Decimal::ZERO);
+    let mut released = Decimal::ZERO;
     if remaining_amount > Decimal::ZERO {
         match updated_order.side {
             OrderSide::Buy => {
                 let refund_value = remaining_amount * updated_order.price_per_kwh;
                 if let Err(e) = state.market_clearing.unlock_funds(user.0.sub, order_id, refund_value, "Order Cancelled").await {
                     tracing::error!("Failed to refund funds for cancelled order {}: {}", order_id, e);
+                } else {
+                    released = refund_value;
                 }
             }
             OrderSide::Sell => {
                 if let Err(e) = state.market_clearing.unlock_energy(user.0.sub, order_id, remaining_amount, "Order Cancelled").await {
                     tracing::error!("Failed to unlock energy for cancelled order {}: {}", order_id, e);
+                } else {
+                    released = remaining_amount;
                 }
             }
         }
     }
 
+    state
+        .websocket_service
+        .broadcast_order_cancelled(order_id, user.0.sub, updated_order.side, OrderCloseReason::UserCancelled, released)
+        .await;
+
     // 5. Return updated order
     Ok(Json(updated_order.into()))
 }
 
+/// Cancel all open orders of the caller
+///
+/// Optionally limited to one side and/or zone. Orders that fail to cancel
+/// are listed in the response and left open; the rest are still cancelled.
+#[utoipa::path(
+    delete,
+    path = "/api/v1/trading/orders",
+    tag = "trading",
+    security(("bearer_auth" = [])),
+    params(CancelOrdersQuery),
+    responses(
+        (status = 200, description = "Open orders cancelled", body = BulkCancelResult)
+    )
+)]
+pub async fn cancel_all_orders(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+    Query(query): Query<CancelOrdersQuery>,
+) -> Result<Json<BulkCancelResult>> {
+    let filter = CancelFilter {
+        side: query.side,
+        zone_id: query.zone,
+    };
+    let result = state
+        .market_clearing
+        .cancel_all_orders(user.0.sub, filter)
+        .await
+        .map_err(|e| ApiError::Internal(format!("Failed to cancel orders: {}", e)))?;
+
+    Ok(Json(result))
+}
+
 /// Update a trading order
 #[utoipa::path(
     put,
@@ -193,7 +239,53 @@ pub async fn update_order(
     .fetch_one(&state.db)
     .await
     .map_err(ApiError::Database)?;
+    state.market_clearing.invalidate_order_book().await;
 
     // 6. Return updated order
     Ok(Json(updated_order.into()))
 }
+
+/// Reduce the size of a trading order
+///
+/// Removes `reduce_by` kWh from the unfilled portion of the order and releases
+/// the corresponding escrow. The order cannot be reduced below its filled amount.
+#[utoipa::path(
+    patch,
+    path = "/api/v1/trading/orders/{id}/reduce",
+    tag = "trading",
+    request_body = ReduceOrderRequest,
+    security(("bearer_auth" = [])),
+    params(
+        ("id" = Uuid, Path, description = "Order ID to reduce")
+    ),
+    responses(
+        (status = 200, description = "Order reduced successfully", body = TradingOrder),
+        (status = 404, description = "Order not found"),
+        (status = 400, description = "Reduction exceeds the unfilled amount or order is not open")
+    )
+)]
+pub async fn reduce_order(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+    Path(order_id): Path<Uuid>,
+    Json(payload): Json<ReduceOrderRequest>,
+) -> Result<Json<TradingOrder>> {
+    state
+        .market_clearing
+        .reduce_order(order_id, user.0.sub, payload.reduce_by)
+        .await
+        .map_err(|e| match e.downcast::<ApiError>() {
+            Ok(api_error) => api_error,
+            Err(e) => ApiError::Internal(format!("Failed to reduce order: {}", e)),
+        })?;
+
+    let updated_order = sqlx::query_as::<_, crate::models::trading::TradingOrderDb>(
+        "SELECT * FROM trading_orders WHERE id = $1",
+    )
+    .bind(order_id)
+    .fetch_one(&state.db)
+    .await
+    .map_err(ApiError::Database)?;
+
+    Ok(Json(updated_order.into()))
+}
@@ -3,5 +3,5 @@ pub mod management;
 pub mod queries;
 
 pub use create::create_order;
-pub use management::{cancel_order, update_order};
-pub use queries::{get_order_book, get_user_orders, get_my_trades, get_token_balance};
+pub use management::{cancel_all_orders, cancel_order, reduce_order, update_order};
+pub use queries::{get_order, get_order_book, get_order_book_depth, get_user_orders, get_my_trades, get_token_balance};
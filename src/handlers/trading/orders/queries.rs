@@ -1,16 +1,29 @@
 use axum::{
-    extract::{Query, State},
+    extract::{Path, Query, State},
     response::Json,
 };
+use sqlx::{FromRow, Row};
 use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
 
 use crate::auth::middleware::AuthenticatedUser;
+use crate::auth::Role;
 use crate::error::{ApiError, Result};
+use crate::handlers::common::Paginated;
 use crate::models::trading::{TradingOrder, TradingOrderDb};
 use crate::utils::PaginationParams;
 use crate::AppState;
 
-use crate::handlers::trading::types::{OrderQuery, TradingOrdersResponse};
+use crate::handlers::trading::types::{
+    DepthQuery, OrderDetailResponse, OrderMatchDetail, OrderQuery, OrderSettlementDetail,
+    TradingOrdersResponse,
+};
+use crate::services::market_clearing::OrderBookDepth;
+use crate::services::settlement::{settlement_from_row, SETTLEMENT_SELECT};
+
+/// Default and maximum price levels returned by the depth endpoint
+const DEFAULT_DEPTH_LEVELS: usize = 20;
+const MAX_DEPTH_LEVELS: usize = 100;
 
 /// Get user's trading orders
 /// GET /api/trading/orders
@@ -83,7 +96,7 @@ pub async fn get_user_orders(
 
     // Build data query with sorting
     let query = format!(
-        "SELECT id, user_id, order_type, side, energy_amount, price_per_kwh, filled_amount, status, expires_at, created_at, filled_at, epoch_id, zone_id, meter_id, refund_tx_signature, order_pda, session_token, trigger_price, trigger_type, trigger_status, trailing_offset, triggered_at 
+        "SELECT id, user_id, order_type, side, energy_amount, price_per_kwh, filled_amount, status, expires_at, created_at, filled_at, epoch_id, zone_id, meter_id, refund_tx_signature, order_pda, session_token, cancellation_reason, trigger_price, trigger_type, trigger_status, trailing_offset, triggered_at, display_quantity, display_refreshed_at, time_in_force 
          FROM trading_orders 
          WHERE {} 
          ORDER BY {} {}
@@ -136,6 +149,131 @@ pub async fn get_user_orders(
     }))
 }
 
+/// Get one order with its matches, settlements and on-chain signatures
+/// GET /api/v1/trading/orders/{id}
+#[utoipa::path(
+    get,
+    path = "/api/v1/trading/orders/{id}",
+    tag = "trading",
+    security(("bearer_auth" = [])),
+    params(
+        ("id" = Uuid, Path, description = "Order ID")
+    ),
+    responses(
+        (status = 200, description = "Order with its full lifecycle", body = OrderDetailResponse),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "Order not found"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn get_order(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+    Path(order_id): Path<Uuid>,
+) -> Result<Json<OrderDetailResponse>> {
+    let row = sqlx::query("SELECT * FROM trading_orders WHERE id = $1")
+        .bind(order_id)
+        .fetch_optional(&state.db)
+        .await
+        .map_err(ApiError::Database)?;
+
+    // Someone else's order is reported as missing rather than forbidden
+    let is_admin = matches!(Role::from_str(&user.0.role), Ok(Role::Admin));
+    let (order, blockchain_tx_signature) = match row {
+        Some(row) => {
+            let order = TradingOrderDb::from_row(&row).map_err(ApiError::Database)?;
+            let signature: Option<String> = row.try_get("blockchain_tx_signature").map_err(ApiError::Database)?;
+            (order, signature)
+        }
+        None => return Err(ApiError::NotFound(format!("Order {} not found", order_id))),
+    };
+    if order.user_id != user.0.sub && !is_admin {
+        return Err(ApiError::NotFound(format!("Order {} not found", order_id)));
+    }
+
+    let matches = sqlx::query_as::<_, OrderMatchDetail>(
+        r#"
+        SELECT id,
+               CASE WHEN buy_order_id = $1 THEN sell_order_id ELSE buy_order_id END AS counterparty_order_id,
+               matched_amount, match_price, match_time, status, settlement_id
+        FROM order_matches
+        WHERE buy_order_id = $1 OR sell_order_id = $1
+        ORDER BY match_time ASC
+        "#,
+    )
+    .bind(order_id)
+    .fetch_all(&state.db)
+    .await
+    .map_err(ApiError::Database)?;
+
+    let settlement_rows = sqlx::query(&format!(
+        r#"{}
+        WHERE s.buy_order_id = $1 OR s.sell_order_id = $1
+           OR s.id IN (SELECT settlement_id FROM order_matches WHERE buy_order_id = $1 OR sell_order_id = $1)
+        ORDER BY s.created_at ASC"#,
+        SETTLEMENT_SELECT
+    ))
+    .bind(order_id)
+    .fetch_all(&state.db)
+    .await
+    .map_err(ApiError::Database)?;
+
+    let settlements = settlement_rows
+        .iter()
+        .map(|row| settlement_from_row(row).map(OrderSettlementDetail::from))
+        .collect::<std::result::Result<Vec<_>, _>>()
+        .map_err(ApiError::Database)?;
+
+    let order: TradingOrder = order.into();
+    let remaining_amount = (order.energy_amount - order.filled_amount).max(rust_decimal::Decimal::ZERO);
+
+    Ok(Json(OrderDetailResponse {
+        order,
+        remaining_amount,
+        blockchain_tx_signature,
+        matches,
+        settlements,
+    }))
+}
+
+/// Get aggregated order book depth
+/// GET /api/v1/trading/orderbook/depth
+#[utoipa::path(
+    get,
+    path = "/api/v1/trading/orderbook/depth",
+    tag = "trading",
+    params(DepthQuery),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Bid and ask price levels with cumulative volume", body = OrderBookDepth),
+        (status = 400, description = "Invalid levels parameter"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn get_order_book_depth(
+    State(state): State<AppState>,
+    Query(params): Query<DepthQuery>,
+) -> Result<Json<OrderBookDepth>> {
+    let levels = params.levels.unwrap_or(DEFAULT_DEPTH_LEVELS);
+    if levels == 0 || levels > MAX_DEPTH_LEVELS {
+        return Err(ApiError::BadRequest(format!(
+            "levels must be between 1 and {}",
+            MAX_DEPTH_LEVELS
+        )));
+    }
+
+    let depth = state
+        .market_clearing
+        .get_order_book_depth(levels, params.zone_id)
+        .await
+        .map_err(|e| {
+            tracing::error!("Failed to aggregate order book depth: {}", e);
+            ApiError::Internal("Failed to fetch order book depth".to_string())
+        })?;
+
+    Ok(Json(depth))
+}
+
 /// Get public order book
 /// GET /api/trading/orderbook
 #[utoipa::path(
@@ -207,7 +345,7 @@ pub async fn get_order_book(
 
     // Build data query
     let query = format!(
-        "SELECT id, user_id, order_type, side, energy_amount, price_per_kwh, filled_amount, status, expires_at, created_at, filled_at, epoch_id, zone_id, meter_id, refund_tx_signature, order_pda, session_token, trigger_price, trigger_type, trigger_status, trailing_offset, triggered_at 
+        "SELECT id, user_id, order_type, side, energy_amount, price_per_kwh, filled_amount, status, expires_at, created_at, filled_at, epoch_id, zone_id, meter_id, refund_tx_signature, order_pda, session_token, cancellation_reason, trigger_price, trigger_type, trigger_status, trailing_offset, triggered_at, display_quantity, display_refreshed_at, time_in_force 
          FROM trading_orders 
          WHERE {} 
          ORDER BY {} {}
@@ -238,7 +376,7 @@ pub async fn get_order_book(
             ApiError::Database(e)
         })?
         .into_iter()
-        .map(|db_order| db_order.into())
+        .map(|db_order| TradingOrder::from(db_order).public_view())
         .collect::<Vec<TradingOrder>>();
 
     let pagination = crate::utils::PaginationMeta::new(
@@ -263,12 +401,10 @@ pub async fn get_order_book(
     get,
     path = "/api/v1/trading/trades",
     tag = "trading",
-    params(
-        ("limit" = Option<i32>, Query, description = "Maximum number of trades to return (default: 20)")
-    ),
+    params(TradeHistoryParams),
     security(("bearer_auth" = [])),
     responses(
-        (status = 200, description = "User's trade history"),
+        (status = 200, description = "User's trade history", body = Paginated<TradeRecord>),
         (status = 401, description = "Unauthorized"),
         (status = 500, description = "Internal server error")
     )
@@ -277,10 +413,28 @@ pub async fn get_my_trades(
     State(_state): State<AppState>,
     user: AuthenticatedUser,
     Query(params): Query<TradeHistoryParams>,
-) -> Result<Json<TradeHistoryResponse>> {
+) -> Result<Json<Paginated<TradeRecord>>> {
     tracing::info!("Fetching trade history for user: {}", user.0.sub);
 
-    let limit = params.limit.unwrap_or(20).min(100);
+    let limit = params.limit.unwrap_or(20).clamp(1, 100);
+    let offset = params.offset.unwrap_or(0).max(0);
+
+    let total = sqlx::query_scalar::<_, i64>(
+        r#"
+        SELECT COUNT(*)
+        FROM order_matches om
+        JOIN trading_orders buy_order ON om.buy_order_id = buy_order.id
+        JOIN trading_orders sell_order ON om.sell_order_id = sell_order.id
+        WHERE buy_order.user_id = $1 OR sell_order.user_id = $1
+        "#,
+    )
+    .bind(user.0.sub)
+    .fetch_one(&_state.db)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to count trade history: {}", e);
+        ApiError::Database(e)
+    })?;
 
     // Query order_matches where user was either buyer or seller
     let trades = sqlx::query_as::<_, TradeRecord>(
@@ -311,11 +465,12 @@ pub async fn get_my_trades(
         LEFT JOIN settlements s ON om.settlement_id = s.id
         WHERE buy_order.user_id = $1 OR sell_order.user_id = $1
         ORDER BY om.match_time DESC
-        LIMIT $2
+        LIMIT $2 OFFSET $3
         "#,
     )
     .bind(user.0.sub)
     .bind(limit)
+    .bind(offset)
     .fetch_all(&_state.db)
     .await
     .map_err(|e| {
@@ -323,12 +478,15 @@ pub async fn get_my_trades(
         ApiError::Database(e)
     })?;
 
-    Ok(Json(TradeHistoryResponse { trades }))
+    Ok(Json(Paginated::new(trades, total, limit as i64, offset as i64)))
 }
 
 #[derive(Debug, serde::Deserialize, IntoParams)]
 pub struct TradeHistoryParams {
+    /// Maximum number of trades to return (default: 20, max: 100)
     pub limit: Option<i32>,
+    /// Number of trades to skip (default: 0)
+    pub offset: Option<i32>,
 }
 
 #[derive(Debug, serde::Serialize, sqlx::FromRow, ToSchema)]
@@ -354,11 +512,6 @@ pub struct TradeRecord {
     pub seller_zone_id: Option<i32>,
 }
 
-#[derive(Debug, serde::Serialize, ToSchema)]
-pub struct TradeHistoryResponse {
-    pub trades: Vec<TradeRecord>,
-}
-
 /// Get user's GRID token balance
 /// GET /api/v1/trading/balance
 #[utoipa::path(
@@ -1,16 +1,18 @@
 use axum::{
-    routing::{delete, get, post},
+    routing::{delete, get, patch, post},
     Router,
 };
 
 use crate::app_state::AppState;
-use super::orders::{create_order, cancel_order, update_order, get_order_book, get_user_orders, get_my_trades, get_token_balance};
+use super::orders::{create_order, cancel_order, cancel_all_orders, reduce_order, update_order, get_order, get_order_book, get_order_book_depth, get_user_orders, get_my_trades, get_token_balance};
 use super::blockchain::{get_blockchain_market_data, match_blockchain_orders};
 use super::conditional::{create_conditional_order, list_conditional_orders, cancel_conditional_order};
 use super::recurring::{create_recurring_order, list_recurring_orders, get_recurring_order, cancel_recurring_order, pause_recurring_order, resume_recurring_order};
 use super::price_alerts::{create_price_alert, list_price_alerts, delete_price_alert};
-use super::export::{export_csv, export_json};
+use super::export::{export_csv, export_json, export_trading_history};
+use super::exposure::get_exposure;
 use super::p2p::{calculate_p2p_cost, get_p2p_market_prices};
+use super::settlements::get_settlement_detail;
 use super::status::{get_matching_status, get_settlement_stats};
 use super::revenue::{get_revenue_summary, get_revenue_records};
 
@@ -18,8 +20,9 @@ use super::revenue::{get_revenue_summary, get_revenue_records};
 pub fn v1_trading_routes() -> Router<AppState> {
     Router::new()
         // Orders
-        .route("/orders", post(create_order).get(get_user_orders))
-        .route("/orders/{id}", delete(cancel_order).put(update_order))
+        .route("/orders", post(create_order).get(get_user_orders).delete(cancel_all_orders))
+        .route("/orders/{id}", get(get_order).delete(cancel_order).put(update_order))
+        .route("/orders/{id}/reduce", patch(reduce_order))
         
         // Conditional Orders (Stop-Loss/Take-Profit)
         .route("/conditional", post(create_conditional_order).get(list_conditional_orders))
@@ -38,9 +41,11 @@ pub fn v1_trading_routes() -> Router<AppState> {
         // Export
         .route("/export/csv", get(export_csv))
         .route("/export/json", get(export_json))
+        .route("/history/export", get(export_trading_history))
         
         // Order Book
         .route("/orderbook", get(get_order_book))
+        .route("/orderbook/depth", get(get_order_book_depth))
         
         // Trade History
         .route("/trades", get(get_my_trades))
@@ -48,6 +53,9 @@ pub fn v1_trading_routes() -> Router<AppState> {
         // Token Balance
         .route("/balance", get(get_token_balance))
         
+        // Exposure / Portfolio
+        .route("/exposure", get(get_exposure))
+        
         // Market Data
         .route("/market/blockchain", get(get_blockchain_market_data))
         
@@ -58,6 +66,7 @@ pub fn v1_trading_routes() -> Router<AppState> {
         // Status & Monitoring
         .route("/matching-status", get(get_matching_status))
         .route("/settlement-stats", get(get_settlement_stats))
+        .route("/settlements/{id}", get(get_settlement_detail))
         
         // Revenue (Admin)
         .route("/revenue/summary", get(get_revenue_summary))
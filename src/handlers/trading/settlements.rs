@@ -0,0 +1,48 @@
+//! Settlement Receipt Endpoint
+//!
+//! Authoritative view of what a completed trade moved: the settlement, its
+//! revenue rows, the escrow behind it and its on-chain signatures
+
+use axum::{
+    extract::{Path, State},
+    response::Json,
+};
+use std::str::FromStr;
+use uuid::Uuid;
+
+use crate::auth::middleware::AuthenticatedUser;
+use crate::auth::Role;
+use crate::error::Result;
+use crate::services::settlement::SettlementDetail;
+use crate::AppState;
+
+/// Get a settlement with its revenue breakdown and escrow state
+/// GET /api/v1/trading/settlements/{id}
+#[utoipa::path(
+    get,
+    path = "/api/v1/trading/settlements/{id}",
+    tag = "trading",
+    params(
+        ("id" = Uuid, Path, description = "Settlement ID")
+    ),
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Settlement, revenue rows, escrow records and signatures", body = SettlementDetail),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "Settlement not found")
+    )
+)]
+pub async fn get_settlement_detail(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+    Path(settlement_id): Path<Uuid>,
+) -> Result<Json<SettlementDetail>> {
+    // Only the two parties and admins may read it; others see it as missing
+    let is_admin = matches!(Role::from_str(&user.0.role), Ok(Role::Admin));
+    let detail = state
+        .settlement
+        .get_settlement_detail(settlement_id, user.0.sub, is_admin)
+        .await?;
+
+    Ok(Json(detail))
+}
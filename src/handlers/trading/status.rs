@@ -3,13 +3,18 @@
 //! Provides status information for matching engine and settlements
 
 use axum::{extract::State, response::Json};
-use serde::Serialize;
-use sqlx::Row;
+use serde::{Deserialize, Serialize};
+use sqlx::{PgPool, Row};
 use utoipa::ToSchema;
 
 use crate::error::{ApiError, Result};
+use crate::services::cache::CacheKeys;
+use crate::services::settlement::SettlementScheduleStatus;
 use crate::AppState;
 
+/// Settlement stats are polled by dashboards; serve them from Redis this long
+const SETTLEMENT_STATS_CACHE_TTL_SECS: u64 = 10;
+
 /// Matching engine status response
 #[derive(Debug, Serialize, ToSchema)]
 pub struct MatchingStatus {
@@ -30,7 +35,7 @@ pub struct PriceRange {
 }
 
 /// Settlement statistics response
-#[derive(Debug, Serialize, ToSchema)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct SettlementStatusResponse {
     pub pending_count: i64,
     pub processing_count: i64,
@@ -38,10 +43,13 @@ pub struct SettlementStatusResponse {
     pub failed_count: i64,
     pub total_settled_value: f64,
     pub recent_settlements: Vec<RecentSettlement>,
+    /// Current or next settlement window; not cached with the counts
+    #[serde(default)]
+    pub schedule: Option<SettlementScheduleStatus>,
 }
 
 /// Recent settlement info
-#[derive(Debug, Serialize, ToSchema)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct RecentSettlement {
     pub id: String,
     pub status: String,
@@ -163,6 +171,20 @@ pub async fn get_matching_status(
 pub async fn get_settlement_stats(
     State(state): State<AppState>,
 ) -> Result<Json<SettlementStatusResponse>> {
+    let mut stats = state
+        .cache_service
+        .get_or_load(
+            &CacheKeys::settlement_stats(),
+            SETTLEMENT_STATS_CACHE_TTL_SECS,
+            || load_settlement_stats(&state.db),
+        )
+        .await?;
+    stats.schedule = Some(state.settlement.schedule_status());
+
+    Ok(Json(stats))
+}
+
+async fn load_settlement_stats(db: &PgPool) -> Result<SettlementStatusResponse> {
     // Get settlement counts by status
     let stats = sqlx::query(
         r#"
@@ -175,7 +197,7 @@ pub async fn get_settlement_stats(
         FROM settlements
         "#
     )
-    .fetch_one(&state.db)
+    .fetch_one(db)
     .await
     .map_err(ApiError::Database)?;
 
@@ -194,7 +216,7 @@ pub async fn get_settlement_stats(
         LIMIT 10
         "#
     )
-    .fetch_all(&state.db)
+    .fetch_all(db)
     .await
     .map_err(ApiError::Database)?;
 
@@ -217,12 +239,13 @@ pub async fn get_settlement_stats(
         })
         .collect();
 
-    Ok(Json(SettlementStatusResponse {
+    Ok(SettlementStatusResponse {
         pending_count,
         processing_count,
         completed_count,
         failed_count,
         total_settled_value,
         recent_settlements,
-    }))
+        schedule: None,
+    })
 }
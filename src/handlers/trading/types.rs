@@ -35,6 +35,26 @@ pub struct OrderQuery {
     pub sort_order: crate::utils::SortOrder,
 }
 
+/// Query parameters for cancelling open orders in bulk
+#[derive(Debug, Deserialize, ToSchema, IntoParams)]
+pub struct CancelOrdersQuery {
+    /// Only cancel orders on this side (buy/sell)
+    pub side: Option<OrderSide>,
+
+    /// Only cancel orders in this grid zone
+    pub zone: Option<i32>,
+}
+
+/// Query parameters for aggregated order book depth
+#[derive(Debug, Deserialize, ToSchema, IntoParams)]
+pub struct DepthQuery {
+    /// Price levels per side (1-100, default 20)
+    pub levels: Option<usize>,
+
+    /// Only include orders in this grid zone
+    pub zone_id: Option<i32>,
+}
+
 fn default_page() -> u32 {
     1
 }
@@ -111,6 +131,74 @@ pub struct CreateOrderResponse {
     pub message: String,
 }
 
+/// One order with everything that happened to it since placement
+#[derive(Debug, Serialize, ToSchema)]
+pub struct OrderDetailResponse {
+    pub order: TradingOrder,
+    /// Unfilled part of the order (kWh)
+    #[schema(value_type = String)]
+    pub remaining_amount: rust_decimal::Decimal,
+    /// Signature of the on-chain order creation, if it was submitted
+    pub blockchain_tx_signature: Option<String>,
+    pub matches: Vec<OrderMatchDetail>,
+    /// Settlements paying out `matches`, oldest first
+    pub settlements: Vec<OrderSettlementDetail>,
+}
+
+/// A match of the order against a counterparty order
+#[derive(Debug, Serialize, ToSchema, sqlx::FromRow)]
+pub struct OrderMatchDetail {
+    pub id: Uuid,
+    pub counterparty_order_id: Uuid,
+    #[schema(value_type = String)]
+    pub matched_amount: rust_decimal::Decimal,
+    #[schema(value_type = String)]
+    pub match_price: rust_decimal::Decimal,
+    pub match_time: Option<DateTime<Utc>>,
+    pub status: String,
+    pub settlement_id: Option<Uuid>,
+}
+
+/// Settlement of one of the order's matches
+#[derive(Debug, Serialize, ToSchema)]
+pub struct OrderSettlementDetail {
+    pub id: Uuid,
+    pub match_id: Option<Uuid>,
+    pub status: String,
+    #[schema(value_type = String)]
+    pub energy_amount: rust_decimal::Decimal,
+    #[schema(value_type = String)]
+    pub price_per_kwh: rust_decimal::Decimal,
+    #[schema(value_type = String)]
+    pub total_amount: rust_decimal::Decimal,
+    #[schema(value_type = String)]
+    pub fee_amount: rust_decimal::Decimal,
+    /// Transfer signature of the settlement
+    pub blockchain_tx: Option<String>,
+    /// Multi-transfer transaction the settlement was part of, if batched
+    pub batch_signature: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub confirmed_at: Option<DateTime<Utc>>,
+}
+
+impl From<crate::services::settlement::Settlement> for OrderSettlementDetail {
+    fn from(s: crate::services::settlement::Settlement) -> Self {
+        Self {
+            id: s.id,
+            match_id: s.match_id,
+            status: s.status.to_string(),
+            energy_amount: s.energy_amount,
+            price_per_kwh: s.price,
+            total_amount: s.total_value,
+            fee_amount: s.fee_amount,
+            blockchain_tx: s.blockchain_tx,
+            batch_signature: s.batch_signature,
+            created_at: s.created_at,
+            confirmed_at: s.confirmed_at,
+        }
+    }
+}
+
 /// Trading statistics for user
 #[derive(Debug, Serialize, ToSchema)]
 pub struct TradingStats {
@@ -0,0 +1,100 @@
+//! Transaction Status Handler
+//!
+//! Status of P2P orders, AMM swaps and blockchain transactions by id
+
+use axum::{extract::State, response::Json};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::str::FromStr;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::auth::middleware::AuthenticatedUser;
+use crate::auth::Role;
+use crate::error::{ApiError, Result};
+use crate::models::transaction::TransactionResponse;
+use crate::services::transaction::query::TransactionQueryService;
+use crate::AppState;
+
+/// Most ids accepted by one batch status request
+pub const MAX_BATCH_STATUS_IDS: usize = 100;
+
+/// Transaction ids to resolve
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct BatchTransactionStatusRequest {
+    pub ids: Vec<Uuid>,
+}
+
+/// Status of each id that was found, keyed by id
+#[derive(Debug, Serialize, ToSchema)]
+pub struct BatchTransactionStatusResponse {
+    pub transactions: HashMap<Uuid, TransactionResponse>,
+}
+
+/// Get the status of several transactions at once
+/// POST /api/v1/transactions/status/batch
+#[utoipa::path(
+    post,
+    path = "/api/v1/transactions/status/batch",
+    tag = "transactions",
+    request_body = BatchTransactionStatusRequest,
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Found transactions keyed by id; unknown ids are omitted", body = BatchTransactionStatusResponse),
+        (status = 400, description = "No ids or more than 100 ids"),
+        (status = 401, description = "Unauthorized")
+    )
+)]
+pub async fn get_transaction_statuses(
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+    Json(request): Json<BatchTransactionStatusRequest>,
+) -> Result<Json<BatchTransactionStatusResponse>> {
+    let ids = dedup_ids(request.ids)?;
+
+    let mut transactions = TransactionQueryService::new(state.db.clone())
+        .get_transaction_statuses(&ids)
+        .await?;
+
+    // Other users' transactions are reported as missing, like the single-id lookup
+    let is_admin = matches!(Role::from_str(&user.0.role), Ok(Role::Admin));
+    if !is_admin {
+        transactions.retain(|_, tx| tx.user_id == user.0.sub);
+    }
+
+    Ok(Json(BatchTransactionStatusResponse { transactions }))
+}
+
+/// Requested ids without duplicates, rejecting empty or oversized batches
+fn dedup_ids(ids: Vec<Uuid>) -> Result<Vec<Uuid>> {
+    let mut unique = ids;
+    unique.sort_unstable();
+    unique.dedup();
+
+    if unique.is_empty() {
+        return Err(ApiError::BadRequest("At least one transaction id is required".to_string()));
+    }
+    if unique.len() > MAX_BATCH_STATUS_IDS {
+        return Err(ApiError::BadRequest(format!(
+            "At most {} transaction ids can be requested at once",
+            MAX_BATCH_STATUS_IDS
+        )));
+    }
+    Ok(unique)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_batch_size_is_bounded_after_dedup() {
+        assert!(dedup_ids(vec![]).is_err());
+
+        let id = Uuid::new_v4();
+        assert_eq!(dedup_ids(vec![id; 150]).unwrap(), vec![id]);
+
+        let too_many: Vec<Uuid> = (0..=MAX_BATCH_STATUS_IDS).map(|_| Uuid::new_v4()).collect();
+        assert!(matches!(dedup_ids(too_many), Err(ApiError::BadRequest(_))));
+    }
+}
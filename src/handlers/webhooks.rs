@@ -0,0 +1,68 @@
+//! Inbound Partner Webhooks
+//!
+//! Callbacks from external systems. Every route here sits behind
+//! `verify_webhook_signature`, so handlers only see bodies signed with the
+//! shared webhook secret.
+
+use axum::{extract::State, http::StatusCode, response::Json};
+use serde::Deserialize;
+use tracing::info;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::error::{ApiError, Result};
+use crate::AppState;
+
+/// Settlement status reported by an external grid operator
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct SettlementCallback {
+    /// Settlement the operator is reporting on
+    pub settlement_id: Uuid,
+    /// Operator-side status, e.g. `delivered` or `disputed`
+    pub status: String,
+    /// Operator's own reference for the delivery
+    pub reference: Option<String>,
+}
+
+/// Receive a settlement status callback from an external grid operator
+#[utoipa::path(
+    post,
+    path = "/api/v1/webhooks/settlements",
+    tag = "webhooks",
+    request_body = SettlementCallback,
+    params(
+        ("X-Webhook-Signature" = String, Header, description = "sha256=<hex> HMAC of the raw body")
+    ),
+    responses(
+        (status = 202, description = "Callback accepted"),
+        (status = 401, description = "Missing or invalid webhook signature"),
+        (status = 404, description = "Unknown settlement")
+    )
+)]
+pub async fn settlement_callback(
+    State(state): State<AppState>,
+    Json(callback): Json<SettlementCallback>,
+) -> Result<StatusCode> {
+    let known = sqlx::query_scalar::<_, bool>(
+        "SELECT EXISTS(SELECT 1 FROM settlements WHERE id = $1)",
+    )
+    .bind(callback.settlement_id)
+    .fetch_one(&state.db)
+    .await
+    .map_err(ApiError::Database)?;
+    if !known {
+        return Err(ApiError::NotFound(format!(
+            "Settlement {} not found",
+            callback.settlement_id
+        )));
+    }
+
+    info!(
+        "📨 Grid operator reported settlement {} as {} (reference: {})",
+        callback.settlement_id,
+        callback.status,
+        callback.reference.as_deref().unwrap_or("-")
+    );
+
+    Ok(StatusCode::ACCEPTED)
+}
@@ -200,3 +200,28 @@ pub async fn broadcast_settlement_complete(
 
     Ok(())
 }
+
+/// Notify a certificate owner that their ERC has expired
+pub async fn broadcast_certificate_expired(
+    certificate_id: String,
+    user_id: Uuid,
+    expiry_date: chrono::DateTime<chrono::Utc>,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let message = WsMessage::CertificateExpired {
+        certificate_id: certificate_id.clone(),
+        user_id,
+        expiry_date,
+        timestamp: chrono::Utc::now(),
+    };
+
+    let manager = get_connection_manager();
+    manager.send_to_user(user_id, message).await?;
+
+    tracing::info!(
+        "📢 Sent certificate expiry notice to user {}: {}",
+        user_id,
+        certificate_id
+    );
+
+    Ok(())
+}
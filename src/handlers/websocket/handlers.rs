@@ -6,11 +6,12 @@ use axum::{
 use futures::{SinkExt, StreamExt};
 use serde_json::{json, Value};
 use tracing::{info, error};
+use std::sync::Arc;
 use uuid::Uuid;
 
-
 use super::types::WsParams;
 use super::get_connection_manager;
+use crate::services::websocket::TokenAuthenticator;
 use crate::AppState;
 
 #[utoipa::path(
@@ -70,10 +71,8 @@ async fn websocket_handler_internal(
 
     // Validate token if provided
     if let Some(token) = &params.token {
-        // Decode and validate JWT token using the JWT service from state
-        match state.jwt_service.decode_token(token) {
-            Ok(claims) => {
-                let user_id = claims.sub;
+        match authenticate_token(&state, token).await {
+            Some(user_id) => {
                 info!(
                     "📡 Authenticated WebSocket connection for user: {} (channel: {})",
                     user_id, channel_name
@@ -84,8 +83,8 @@ async fn websocket_handler_internal(
                     handle_authenticated_socket(socket, user_id, state).await;
                 }))
             }
-            Err(e) => {
-                info!("❌ WebSocket auth failed: {:?}", e);
+            None => {
+                info!("❌ WebSocket auth failed for channel: {}", channel_name);
                 let error_response = (
                     axum::http::StatusCode::UNAUTHORIZED,
                     Json(json!({
@@ -109,6 +108,12 @@ async fn websocket_handler_internal(
     }
 }
 
+/// Resolve a JWT to its user, rejecting revoked tokens like the HTTP auth middleware
+async fn authenticate_token(state: &AppState, token: &str) -> Option<Uuid> {
+    let claims = state.jwt_service.verify_token(token).await.ok()?;
+    Some(claims.sub)
+}
+
 /// Handle authenticated WebSocket connection
 async fn handle_authenticated_socket(socket: WebSocket, user_id: Uuid, _state: AppState) {
     let (mut sender, mut receiver) = socket.split();
@@ -168,24 +173,60 @@ async fn handle_authenticated_socket(socket: WebSocket, user_id: Uuid, _state: A
 /// - Order matches
 /// - Transaction updates
 /// - Market statistics
+///
+/// Anonymous connections receive the public feeds only. Authenticating, with
+/// a `token` query parameter or a `{"type": "auth", "token": "..."}` message,
+/// adds the user's own trades, settlement updates and order cancellations.
 #[utoipa::path(
     get,
     path = "/api/market/ws",
     tag = "websocket",
+    params(
+        ("token" = Option<String>, Query, description = "Optional JWT for the user's private events")
+    ),
     responses(
         (status = 101, description = "WebSocket connection upgraded"),
+        (status = 401, description = "Unauthorized - Invalid or revoked token"),
         (status = 500, description = "Internal server error")
     )
 )]
 pub async fn market_websocket_handler(
     ws: WebSocketUpgrade,
     State(state): State<AppState>,
-) -> impl IntoResponse {
+    Query(params): Query<WsParams>,
+) -> Result<Response, Response> {
     info!("📡 New WebSocket connection request for market feed");
 
-    ws.on_upgrade(move |socket| async move {
-        state.websocket_service.register_client(socket).await;
-    })
+    let user_id = match &params.token {
+        Some(token) => match authenticate_token(&state, token).await {
+            Some(user_id) => Some(user_id),
+            None => {
+                info!("❌ Market feed auth failed");
+                let error_response = (
+                    axum::http::StatusCode::UNAUTHORIZED,
+                    Json(json!({
+                        "error": "unauthorized",
+                        "message": "Invalid or expired token"
+                    })),
+                );
+                return Err(error_response.into_response());
+            }
+        },
+        None => None,
+    };
+
+    let auth_state = state.clone();
+    let authenticator: TokenAuthenticator = Arc::new(move |token: String| {
+        let state = auth_state.clone();
+        Box::pin(async move { authenticate_token(&state, &token).await })
+    });
+
+    Ok(ws.on_upgrade(move |socket| async move {
+        state
+            .websocket_service
+            .register_client(socket, user_id, Some(authenticator))
+            .await;
+    }))
 }
 
 /// Get WebSocket connection statistics
@@ -200,9 +241,10 @@ pub async fn market_websocket_handler(
         (status = 200, description = "WebSocket statistics")
     )
 )]
-pub async fn websocket_stats(State(_state): State<AppState>) -> Json<Value> {
+pub async fn websocket_stats(State(state): State<AppState>) -> Json<Value> {
     let stats = json!({
-        "active_connections": 0,
+        "active_connections": state.websocket_service.client_count().await,
+        "authenticated_connections": state.websocket_service.authenticated_client_count().await,
         "channels": ["order-book", "orders", "matches", "epochs"],
         "uptime_seconds": 0,
         "status": "WebSocket infrastructure ready"
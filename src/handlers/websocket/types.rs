@@ -82,6 +82,13 @@ pub enum WsMessage {
         transaction_signature: Option<String>,
         timestamp: chrono::DateTime<chrono::Utc>,
     },
+    /// ERC certificate reached its expiry date
+    CertificateExpired {
+        certificate_id: String,
+        user_id: Uuid,
+        expiry_date: chrono::DateTime<chrono::Utc>,
+        timestamp: chrono::DateTime<chrono::Utc>,
+    },
 }
 
 /// Order book entry
@@ -4,11 +4,11 @@
 
 use anyhow::Result;
 use std::net::SocketAddr;
-use tracing::{info, warn};
+use tracing::{error, info, warn};
 use tracing_subscriber::EnvFilter;
 
 use api_gateway::{
-    config::Config,
+    config::{format_config_errors, Config, LogFormat},
     router,
     startup,
     utils,
@@ -19,10 +19,25 @@ async fn main() -> Result<()> {
     // Load .env file first
     dotenvy::dotenv().ok();
 
-    // Initialize tracing
-    tracing_subscriber::fmt()
-        .with_env_filter(EnvFilter::from_default_env())
-        .init();
+    // Initialize tracing; LOG_FORMAT is read ahead of the rest of the config
+    // so that config errors are logged in the chosen format
+    let log_format = LogFormat::from_env().unwrap_or_else(|e| {
+        eprintln!("{}, falling back to text logs", e);
+        LogFormat::Text
+    });
+    match log_format {
+        LogFormat::Text => tracing_subscriber::fmt()
+            .with_env_filter(EnvFilter::from_default_env())
+            .init(),
+        // The span list carries the request's trace_id on every line logged
+        // while handling it
+        LogFormat::Json => tracing_subscriber::fmt()
+            .json()
+            .with_current_span(false)
+            .with_span_list(true)
+            .with_env_filter(EnvFilter::from_default_env())
+            .init(),
+    }
 
     info!("🚀 Starting GridTokenX API Gateway");
     info!("📊 Full-featured build with all endpoints enabled");
@@ -32,8 +47,22 @@ async fn main() -> Result<()> {
         warn!("⚠️ Secret validation warning: {}", e);
     }
 
-    // Load configuration
-    let config = Config::from_env()?;
+    // Load and validate configuration, reporting every problem at once
+    let config = match Config::from_env() {
+        Ok(config) => config,
+        Err(e) => {
+            let errors = Config::check_env();
+            if !errors.is_empty() {
+                error!("❌ Invalid configuration:\n{}", format_config_errors(&errors));
+                anyhow::bail!("Invalid configuration: {} problem(s) found", errors.len());
+            }
+            return Err(e);
+        }
+    };
+    if let Err(errors) = config.validate() {
+        error!("❌ Invalid configuration:\n{}", format_config_errors(&errors));
+        anyhow::bail!("Invalid configuration: {} problem(s) found", errors.len());
+    }
     info!(
         "Loaded configuration for environment: {}",
         config.environment
@@ -45,6 +74,16 @@ async fn main() -> Result<()> {
     // Spawn background tasks (minimal - mostly no-ops)
     startup::spawn_background_tasks(&app_state, &config).await;
 
+    // Keep shutdown handles; the router takes ownership of the state
+    let shutdown = app_state.shutdown.clone();
+    let background_tasks = app_state.background_tasks.clone();
+    let drain_timeout = std::time::Duration::from_secs(
+        std::env::var("SHUTDOWN_DRAIN_TIMEOUT_SECS")
+            .ok()
+            .and_then(|v| v.parse::<u64>().ok())
+            .unwrap_or(30),
+    );
+
     // Build minimal API router
     let app = router::build_router(app_state)
         .layer(tower_http::compression::CompressionLayer::new());
@@ -57,10 +96,17 @@ async fn main() -> Result<()> {
 
     let listener = tokio::net::TcpListener::bind(addr).await?;
 
-    // Setup graceful shutdown
+    // Setup graceful shutdown: stop background work as soon as the signal arrives
+    let signal_shutdown = shutdown.clone();
     axum::serve(listener, app)
-        .with_graceful_shutdown(startup::shutdown_signal())
+        .with_graceful_shutdown(async move {
+            startup::shutdown_signal().await;
+            signal_shutdown.cancel();
+        })
         .await?;
 
+    // Wait for the in-flight matching cycle and settlement batch to finish recording
+    startup::drain_background_tasks(&shutdown, &background_tasks, drain_timeout).await;
+
     Ok(())
 }
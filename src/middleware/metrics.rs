@@ -3,9 +3,53 @@ use axum::{
     middleware::Next,
     response::Response,
 };
-use metrics::{counter, gauge, histogram};
+use metrics::{counter, describe_counter, describe_gauge, describe_histogram, gauge, histogram, Unit};
+use metrics_exporter_prometheus::{BuildError, Matcher, PrometheusBuilder};
 use std::time::Instant;
 
+/// Bucket bounds for `matching_cycle_duration_seconds`
+const MATCHING_CYCLE_BUCKETS: &[f64] = &[0.01, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0];
+/// Bucket bounds for `matching_candidates_per_order`
+const MATCH_CANDIDATE_BUCKETS: &[f64] = &[0.0, 1.0, 2.0, 5.0, 10.0, 25.0, 50.0, 100.0, 250.0, 1000.0];
+
+/// Prometheus exporter with histogram buckets for the matching engine
+/// metrics, which are rendered as summaries otherwise
+pub fn prometheus_builder() -> Result<PrometheusBuilder, BuildError> {
+    PrometheusBuilder::new()
+        .set_buckets_for_metric(
+            Matcher::Full("matching_cycle_duration_seconds".to_string()),
+            MATCHING_CYCLE_BUCKETS,
+        )?
+        .set_buckets_for_metric(
+            Matcher::Full("matching_candidates_per_order".to_string()),
+            MATCH_CANDIDATE_BUCKETS,
+        )
+}
+
+/// Describe the matching engine metrics; call once the recorder is installed
+pub fn register_matching_metrics() {
+    describe_histogram!(
+        "matching_cycle_duration_seconds",
+        Unit::Seconds,
+        "Wall time of one order matching cycle"
+    );
+    describe_histogram!(
+        "matching_candidates_per_order",
+        Unit::Count,
+        "Sell orders whose landed cost fits a buy order's limit, per buy order evaluated"
+    );
+    describe_gauge!(
+        "matching_open_orders",
+        Unit::Count,
+        "Open orders seen by the last matching cycle, by side and zone"
+    );
+    describe_counter!(
+        "orders_dust_cancelled_total",
+        Unit::Count,
+        "Orders cancelled because their unfilled remainder fell below the minimum trade size"
+    );
+}
+
 /// Metrics middleware that tracks request metrics
 pub async fn metrics_middleware(request: Request, next: Next) -> Response {
     let method = request.method().clone();
@@ -100,6 +144,12 @@ pub fn track_order_matched(order_type: &str, amount: f64) {
     histogram!("order_match_amount", "type" => order_type.to_string()).record(amount);
 }
 
+/// Track a P2P match by whether it stayed inside one grid zone
+pub fn track_zone_match(scope: &str, amount: f64) {
+    counter!("order_matches_by_scope_total", "scope" => scope.to_string()).increment(1);
+    histogram!("order_match_amount_by_scope", "scope" => scope.to_string()).record(amount);
+}
+
 /// Track WebSocket connections
 pub fn track_websocket_connection(connected: bool) {
     if connected {
@@ -124,6 +174,28 @@ pub fn track_database_operation(operation: &str, duration_ms: f64, success: bool
     ).increment(1);
 }
 
+/// Track database connection pool occupancy
+pub fn track_db_pool(size: u32, idle: usize, max_connections: u32) {
+    let active = (size as usize).saturating_sub(idle);
+    gauge!("db_pool_connections", "state" => "active").set(active as f64);
+    gauge!("db_pool_connections", "state" => "idle").set(idle as f64);
+    gauge!("db_pool_size").set(size as f64);
+    gauge!("db_pool_max_connections").set(max_connections as f64);
+    if max_connections > 0 {
+        gauge!("db_pool_utilization_ratio").set(active as f64 / max_connections as f64);
+    }
+}
+
+/// Track how long it took to acquire a pooled connection
+pub fn track_db_pool_acquire(wait_seconds: f64) {
+    histogram!("db_pool_acquire_wait_seconds").record(wait_seconds);
+}
+
+/// Track a connection acquire that hit the pool timeout
+pub fn track_db_pool_acquire_timeout() {
+    counter!("db_pool_acquire_timeouts_total").increment(1);
+}
+
 /// Track blockchain operations
 pub fn track_blockchain_operation(operation: &str, duration_ms: f64, success: bool) {
     histogram!(
@@ -186,6 +258,84 @@ pub fn track_settlement(success: bool) {
     counter!("settlements_total", "success" => success.to_string()).increment(1);
 }
 
+/// Track the adaptive delay between settlements
+pub fn track_settlement_delay(delay_ms: f64) {
+    gauge!("settlement_adaptive_delay_ms").set(delay_ms);
+}
+
+/// Track the duration of one matching cycle
+pub fn track_matching_cycle(duration_secs: f64, success: bool) {
+    histogram!(
+        "matching_cycle_duration_seconds",
+        "success" => success.to_string()
+    ).record(duration_secs);
+}
+
+/// Track how many sell orders were eligible for one buy order
+pub fn track_match_candidates(count: usize) {
+    histogram!("matching_candidates_per_order").record(count as f64);
+}
+
+/// Track open orders for one side and zone (`none` when the orders carry no zone)
+pub fn track_open_orders(side: &str, zone_id: Option<i32>, count: usize) {
+    let zone = zone_id.map(|z| z.to_string()).unwrap_or_else(|| "none".to_string());
+    gauge!("matching_open_orders", "side" => side.to_string(), "zone" => zone).set(count as f64);
+}
+
+/// Track an order cancelled for a dust remainder
+pub fn track_dust_cancellation(side: &str) {
+    counter!("orders_dust_cancelled_total", "side" => side.to_string()).increment(1);
+}
+
+/// Track a match between two orders of the same user that was prevented
+pub fn track_self_trade_prevented(policy: &str) {
+    counter!("self_trades_prevented_total", "policy" => policy.to_string()).increment(1);
+}
+
+/// Track an epoch whose clearing was halted by the price band
+pub fn track_price_band_breach() {
+    counter!("price_band_breaches_total").increment(1);
+}
+
+/// Track a JSON-RPC call through the proxy; `method` should be "other" for
+/// methods outside the allowlist to bound label cardinality
+pub fn track_rpc_request(method: &str, outcome: &str) {
+    counter!("rpc_proxy_requests_total", "method" => method.to_string(), "outcome" => outcome.to_string()).increment(1);
+}
+
+/// Track meter reading points exported to InfluxDB by outcome
+/// ("written", "dropped")
+pub fn track_influx_points(outcome: &str, count: usize) {
+    counter!("influx_points_total", "outcome" => outcome.to_string()).increment(count as u64);
+}
+
+/// Track an authority wallet's SOL balance and whether it is below the
+/// low-balance mark, labelled by the role it pays for
+pub fn track_authority_balance(role: &str, balance_sol: f64, low: bool) {
+    gauge!("authority_balance_sol", "role" => role.to_string()).set(balance_sol);
+    gauge!("authority_balance_low", "role" => role.to_string()).set(if low { 1.0 } else { 0.0 });
+}
+
+/// Track whether this instance holds the background job lease
+pub fn track_job_leadership(is_leader: bool) {
+    gauge!("background_jobs_leader").set(if is_leader { 1.0 } else { 0.0 });
+}
+
+/// Track a background loop restarted after a panic
+pub fn track_task_restart(task: &str) {
+    counter!("background_task_restarts_total", "task" => task.to_string()).increment(1);
+}
+
+/// Track a treasury sweep to one revenue recipient
+pub fn track_treasury_sweep(recipient: &str, success: bool) {
+    counter!(
+        "treasury_sweeps_total",
+        "recipient" => recipient.to_string(),
+        "success" => success.to_string()
+    )
+    .increment(1);
+}
+
 /// Track platform revenue (fees and wheeling)
 pub fn track_revenue(fee_type: &str, amount_sol: f64) {
     counter!("platform_revenue_total", "type" => fee_type.to_string()).increment(amount_sol as u64);
@@ -209,6 +359,11 @@ mod tests {
         track_trading_operation("cancel_order", false);
     }
 
+    #[test]
+    fn test_prometheus_builder_accepts_matching_buckets() {
+        assert!(prometheus_builder().is_ok());
+    }
+
     #[test]
     fn test_track_websocket_connection() {
         track_websocket_connection(true);
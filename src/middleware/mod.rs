@@ -3,10 +3,16 @@
 pub mod json_validation;
 pub mod metrics;
 pub mod metrics_middleware;
+pub mod rate_limit;
 pub mod request_logger;
 pub mod security_headers;
+pub mod trace_id;
+pub mod webhook_signature;
 
 pub use json_validation::json_validation_middleware;
 pub use metrics::{active_requests_middleware, metrics_middleware};
+pub use rate_limit::{rate_limit_middleware, RateLimitConfig, RateLimitTier, RateLimiter};
 pub use request_logger::{auth_logger_middleware, request_logger_middleware};
 pub use security_headers::add_security_headers;
+pub use trace_id::{spawn_in_request, trace_id_middleware, TraceId};
+pub use webhook_signature::verify_webhook_signature;
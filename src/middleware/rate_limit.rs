@@ -0,0 +1,337 @@
+//! Per-client rate limiting
+//!
+//! Requests are counted in a sliding window keyed on the authenticated user
+//! (taken from a valid bearer token) or, for anonymous callers, the client IP.
+//! Each key has an independent budget per [`RateLimitTier`], so hammering the
+//! login endpoint does not eat into a user's read budget and vice versa.
+
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use axum::{
+    body::Body,
+    extract::State,
+    http::{header, HeaderValue, Method, Request},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use dashmap::DashMap;
+use tracing::warn;
+
+use crate::constants::rate_limit::MAX_REQUESTS_PER_USER;
+use crate::error::ApiError;
+use crate::services::AuditEvent;
+use crate::utils::extract_ip_address;
+use crate::AppState;
+
+/// Default budget per window for `/auth/*` and user registration
+const DEFAULT_AUTH_MAX_REQUESTS: u32 = 10;
+/// Default budget per window for order creation
+const DEFAULT_ORDER_MAX_REQUESTS: u32 = 30;
+/// Default budget per window for other writes
+const DEFAULT_WRITE_MAX_REQUESTS: u32 = 60;
+
+/// Entries idle for this many windows are dropped by [`RateLimiter::prune`]
+const IDLE_WINDOWS_BEFORE_PRUNE: u32 = 2;
+
+/// Endpoint class a request is budgeted against
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RateLimitTier {
+    /// Login, registration and password flows
+    Auth,
+    /// `POST /trading/orders`
+    OrderCreate,
+    /// Any other non-GET request
+    Write,
+    /// GET / HEAD / OPTIONS
+    Read,
+}
+
+impl RateLimitTier {
+    /// Classify a request by method and path (relative to `/api/v1` or absolute)
+    pub fn classify(method: &Method, path: &str) -> Self {
+        let path = path.trim_start_matches("/api/v1").trim_end_matches('/');
+
+        if path.starts_with("/auth/") || (path == "/users" && method == Method::POST) {
+            return RateLimitTier::Auth;
+        }
+        if method == Method::POST && path == "/trading/orders" {
+            return RateLimitTier::OrderCreate;
+        }
+        match *method {
+            Method::GET | Method::HEAD | Method::OPTIONS => RateLimitTier::Read,
+            _ => RateLimitTier::Write,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RateLimitTier::Auth => "auth",
+            RateLimitTier::OrderCreate => "order_create",
+            RateLimitTier::Write => "write",
+            RateLimitTier::Read => "read",
+        }
+    }
+}
+
+/// Requests allowed per window for each tier
+#[derive(Debug, Clone, PartialEq)]
+pub struct RateLimitConfig {
+    pub window: Duration,
+    pub auth_max_requests: u32,
+    pub order_max_requests: u32,
+    pub write_max_requests: u32,
+    pub read_max_requests: u32,
+}
+
+impl RateLimitConfig {
+    /// Build limits for a window of `window_secs` (`RATE_LIMIT_WINDOW`), with
+    /// per-tier budgets from `RATE_LIMIT_{AUTH,ORDER,WRITE,READ}_MAX`
+    pub fn from_env(window_secs: u64) -> Self {
+        fn max_from_env(name: &str, default: u32) -> u32 {
+            std::env::var(name)
+                .ok()
+                .and_then(|v| v.parse::<u32>().ok())
+                .filter(|n| *n > 0)
+                .unwrap_or(default)
+        }
+
+        Self {
+            window: Duration::from_secs(window_secs.max(1)),
+            auth_max_requests: max_from_env("RATE_LIMIT_AUTH_MAX", DEFAULT_AUTH_MAX_REQUESTS),
+            order_max_requests: max_from_env("RATE_LIMIT_ORDER_MAX", DEFAULT_ORDER_MAX_REQUESTS),
+            write_max_requests: max_from_env("RATE_LIMIT_WRITE_MAX", DEFAULT_WRITE_MAX_REQUESTS),
+            read_max_requests: max_from_env("RATE_LIMIT_READ_MAX", MAX_REQUESTS_PER_USER),
+        }
+    }
+
+    pub fn max_requests(&self, tier: RateLimitTier) -> u32 {
+        match tier {
+            RateLimitTier::Auth => self.auth_max_requests,
+            RateLimitTier::OrderCreate => self.order_max_requests,
+            RateLimitTier::Write => self.write_max_requests,
+            RateLimitTier::Read => self.read_max_requests,
+        }
+    }
+}
+
+/// Sliding-window counter: the previous window's count is weighted by how
+/// much of it still overlaps the trailing window
+#[derive(Debug, Clone, Copy)]
+struct WindowCounter {
+    window_start: Instant,
+    current: u32,
+    previous: u32,
+}
+
+impl WindowCounter {
+    fn new(now: Instant) -> Self {
+        Self {
+            window_start: now,
+            current: 0,
+            previous: 0,
+        }
+    }
+
+    /// Count the request if it fits, otherwise return how long until it would
+    fn try_acquire(&mut self, now: Instant, window: Duration, max: u32) -> Result<(), Duration> {
+        let elapsed = now.saturating_duration_since(self.window_start);
+        if elapsed >= window * 2 {
+            self.previous = 0;
+            self.current = 0;
+            self.window_start = now;
+        } else if elapsed >= window {
+            self.previous = self.current;
+            self.current = 0;
+            self.window_start += window;
+        }
+
+        let into_window = now.saturating_duration_since(self.window_start);
+        let overlap = 1.0 - into_window.as_secs_f64() / window.as_secs_f64();
+        let estimated = self.previous as f64 * overlap + self.current as f64;
+
+        if estimated + 1.0 <= max as f64 {
+            self.current += 1;
+            return Ok(());
+        }
+
+        // Time until enough of the previous window has slid out, or until
+        // this window rolls over if the current window alone is full
+        let retry_after = if self.current < max && self.previous > 0 {
+            let needed = (estimated + 1.0 - max as f64) / self.previous as f64;
+            window.mul_f64(needed.min(1.0))
+        } else {
+            window - into_window
+        };
+        Err(retry_after.max(Duration::from_secs(1)))
+    }
+}
+
+/// Shared in-memory rate limiter; cloning is cheap and clones share counters
+#[derive(Debug, Clone)]
+pub struct RateLimiter {
+    config: Arc<RateLimitConfig>,
+    counters: Arc<DashMap<(String, RateLimitTier), WindowCounter>>,
+}
+
+impl RateLimiter {
+    pub fn new(config: RateLimitConfig) -> Self {
+        Self {
+            config: Arc::new(config),
+            counters: Arc::new(DashMap::new()),
+        }
+    }
+
+    pub fn config(&self) -> &RateLimitConfig {
+        &self.config
+    }
+
+    /// Count one request for `key`; `Err` carries the `Retry-After` duration
+    pub fn check(&self, key: &str, tier: RateLimitTier) -> Result<(), Duration> {
+        self.check_at(key, tier, Instant::now())
+    }
+
+    fn check_at(&self, key: &str, tier: RateLimitTier, now: Instant) -> Result<(), Duration> {
+        let max = self.config.max_requests(tier);
+        self.counters
+            .entry((key.to_string(), tier))
+            .or_insert_with(|| WindowCounter::new(now))
+            .try_acquire(now, self.config.window, max)
+    }
+
+    /// Drop counters that have been idle long enough to carry no weight
+    pub fn prune(&self) {
+        let idle = self.config.window * IDLE_WINDOWS_BEFORE_PRUNE;
+        let now = Instant::now();
+        self.counters
+            .retain(|_, counter| now.saturating_duration_since(counter.window_start) < idle);
+    }
+}
+
+/// Reject requests over their tier budget with `429 Too Many Requests`
+pub async fn rate_limit_middleware(
+    State(state): State<AppState>,
+    request: Request<Body>,
+    next: Next,
+) -> Response {
+    let path = request.uri().path().to_string();
+    let tier = RateLimitTier::classify(request.method(), &path);
+    let ip = extract_ip_address(request.headers());
+
+    let user_id = request
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|h| h.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "))
+        .and_then(|token| state.jwt_service.decode_token(token).ok())
+        .map(|claims| claims.sub);
+    let key = match user_id {
+        Some(user_id) => format!("user:{}", user_id),
+        None => format!("ip:{}", ip),
+    };
+
+    match state.rate_limiter.check(&key, tier) {
+        Ok(()) => next.run(request).await,
+        Err(retry_after) => {
+            warn!(
+                "🚦 Rate limit exceeded: key={} tier={} path={}",
+                key,
+                tier.as_str(),
+                path
+            );
+            metrics::counter!("rate_limit_rejections_total", "tier" => tier.as_str())
+                .increment(1);
+            state.audit_logger.log_async(AuditEvent::RateLimitExceeded {
+                ip,
+                endpoint: path,
+            });
+
+            let secs = retry_after.as_secs_f64().ceil() as u64;
+            let mut response = ApiError::RateLimitExceeded(format!(
+                "Too many {} requests, retry in {}s",
+                tier.as_str(),
+                secs
+            ))
+            .into_response();
+            response
+                .headers_mut()
+                .insert(header::RETRY_AFTER, HeaderValue::from(secs));
+            response
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn limiter(max: u32) -> RateLimiter {
+        RateLimiter::new(RateLimitConfig {
+            window: Duration::from_secs(60),
+            auth_max_requests: max,
+            order_max_requests: max,
+            write_max_requests: max,
+            read_max_requests: max * 10,
+        })
+    }
+
+    #[test]
+    fn test_classify_tiers() {
+        assert_eq!(
+            RateLimitTier::classify(&Method::POST, "/api/v1/auth/token"),
+            RateLimitTier::Auth
+        );
+        assert_eq!(
+            RateLimitTier::classify(&Method::POST, "/api/v1/users"),
+            RateLimitTier::Auth
+        );
+        assert_eq!(
+            RateLimitTier::classify(&Method::POST, "/api/v1/trading/orders"),
+            RateLimitTier::OrderCreate
+        );
+        assert_eq!(
+            RateLimitTier::classify(&Method::GET, "/api/v1/trading/orders"),
+            RateLimitTier::Read
+        );
+        assert_eq!(
+            RateLimitTier::classify(&Method::DELETE, "/api/v1/trading/orders/abc"),
+            RateLimitTier::Write
+        );
+    }
+
+    #[test]
+    fn test_rejects_over_budget_with_retry_after() {
+        let limiter = limiter(3);
+        let now = Instant::now();
+        for _ in 0..3 {
+            assert!(limiter.check_at("ip:1.2.3.4", RateLimitTier::Auth, now).is_ok());
+        }
+        let retry_after = limiter
+            .check_at("ip:1.2.3.4", RateLimitTier::Auth, now)
+            .unwrap_err();
+        assert!(retry_after >= Duration::from_secs(1));
+        assert!(retry_after <= Duration::from_secs(60));
+
+        // Separate tier and separate key have their own budgets
+        assert!(limiter.check_at("ip:1.2.3.4", RateLimitTier::Read, now).is_ok());
+        assert!(limiter.check_at("ip:5.6.7.8", RateLimitTier::Auth, now).is_ok());
+    }
+
+    #[test]
+    fn test_window_slides() {
+        let limiter = limiter(2);
+        let start = Instant::now();
+        assert!(limiter.check_at("user:a", RateLimitTier::Write, start).is_ok());
+        assert!(limiter.check_at("user:a", RateLimitTier::Write, start).is_ok());
+
+        // Halfway into the next window, half of the previous count still applies
+        let later = start + Duration::from_secs(90);
+        assert!(limiter.check_at("user:a", RateLimitTier::Write, later).is_ok());
+        assert!(limiter.check_at("user:a", RateLimitTier::Write, later).is_err());
+
+        // Two full windows later the slate is clean
+        let much_later = start + Duration::from_secs(180);
+        assert!(limiter.check_at("user:a", RateLimitTier::Write, much_later).is_ok());
+        assert!(limiter.check_at("user:a", RateLimitTier::Write, much_later).is_ok());
+    }
+}
@@ -0,0 +1,123 @@
+// Trace id middleware - correlates every log line of one request
+//
+// Each request gets a trace id, taken from an incoming `x-request-id` header
+// or generated, which is recorded on a `request` span wrapping the rest of the
+// stack and echoed back in the response header. Error responses report the
+// same id, and work spawned with `spawn_in_request` keeps it.
+
+use axum::{
+    extract::Request,
+    http::HeaderValue,
+    middleware::Next,
+    response::Response,
+};
+use std::future::Future;
+use tokio::task::JoinHandle;
+use tracing::{info_span, Instrument};
+use uuid::Uuid;
+
+/// Header carrying the trace id in both directions
+pub const TRACE_ID_HEADER: &str = "x-request-id";
+
+/// Longest caller-supplied id that is accepted as-is
+const MAX_TRACE_ID_LEN: usize = 128;
+
+tokio::task_local! {
+    static CURRENT_TRACE_ID: TraceId;
+}
+
+/// Correlation id of one API request; also available as a request extension
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TraceId(pub String);
+
+impl TraceId {
+    /// Trace id of the request being handled by the current task, if any
+    pub fn current() -> Option<TraceId> {
+        CURRENT_TRACE_ID.try_with(Clone::clone).ok()
+    }
+
+    /// Keep the caller's id when it is short printable ASCII, so ids from an
+    /// upstream proxy line up; otherwise generate one
+    fn from_header(value: Option<&HeaderValue>) -> Self {
+        value
+            .and_then(|v| v.to_str().ok())
+            .map(str::trim)
+            .filter(|v| !v.is_empty() && v.len() <= MAX_TRACE_ID_LEN)
+            .filter(|v| v.bytes().all(|b| b.is_ascii_graphic()))
+            .map(|v| TraceId(v.to_string()))
+            .unwrap_or_else(|| TraceId(Uuid::new_v4().to_string()))
+    }
+}
+
+impl std::fmt::Display for TraceId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// Assign the request a trace id and run the rest of the stack in its span
+pub async fn trace_id_middleware(mut request: Request, next: Next) -> Response {
+    let trace_id = TraceId::from_header(request.headers().get(TRACE_ID_HEADER));
+    request.extensions_mut().insert(trace_id.clone());
+
+    let span = info_span!(
+        "request",
+        trace_id = %trace_id,
+        method = %request.method(),
+        path = %request.uri().path(),
+    );
+
+    let mut response = CURRENT_TRACE_ID
+        .scope(trace_id.clone(), next.run(request).instrument(span))
+        .await;
+
+    if let Ok(value) = HeaderValue::from_str(&trace_id.0) {
+        response.headers_mut().insert(TRACE_ID_HEADER, value);
+    }
+    response
+}
+
+/// `tokio::spawn` that keeps the current request's span and trace id, for
+/// work a handler hands off to run after it has responded
+pub fn spawn_in_request<F>(future: F) -> JoinHandle<F::Output>
+where
+    F: Future + Send + 'static,
+    F::Output: Send + 'static,
+{
+    let future = future.in_current_span();
+    match TraceId::current() {
+        Some(trace_id) => tokio::spawn(CURRENT_TRACE_ID.scope(trace_id, future)),
+        None => tokio::spawn(future),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_caller_trace_id_is_kept_only_when_sane() {
+        let upstream = HeaderValue::from_static("edge-7f3a9c");
+        assert_eq!(TraceId::from_header(Some(&upstream)).0, "edge-7f3a9c");
+
+        let spaced = HeaderValue::from_static("not a trace id");
+        assert_ne!(TraceId::from_header(Some(&spaced)).0, "not a trace id");
+
+        let long = HeaderValue::from_str(&"a".repeat(MAX_TRACE_ID_LEN + 1)).unwrap();
+        assert!(TraceId::from_header(Some(&long)).0.len() <= MAX_TRACE_ID_LEN);
+
+        assert!(Uuid::parse_str(&TraceId::from_header(None).0).is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_spawned_work_keeps_trace_id() {
+        let trace_id = TraceId("req-1".to_string());
+        let seen = CURRENT_TRACE_ID
+            .scope(trace_id.clone(), async { spawn_in_request(async { TraceId::current() }).await })
+            .await
+            .unwrap();
+        assert_eq!(seen, Some(trace_id));
+
+        assert_eq!(TraceId::current(), None);
+    }
+}
@@ -0,0 +1,60 @@
+//! Inbound webhook signature verification.
+//!
+//! Partner callbacks (e.g. settlement updates from an external grid operator)
+//! must send `X-Webhook-Signature: sha256=<hex>`, the HMAC-SHA256 of the raw
+//! request body keyed with `EVENT_PROCESSOR_WEBHOOK_SECRET`. Apply with
+//! `from_fn_with_state(app_state, verify_webhook_signature)`; requests with a
+//! missing or invalid signature are rejected with 401 before reaching the
+//! handler.
+
+use axum::{
+    body::Body,
+    extract::{Request, State},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+
+use crate::error::ApiError;
+use crate::AppState;
+
+/// Header carrying the body signature
+pub const WEBHOOK_SIGNATURE_HEADER: &str = "X-Webhook-Signature";
+
+/// Largest inbound webhook body accepted for verification
+const MAX_WEBHOOK_BODY_BYTES: usize = 1024 * 1024;
+
+/// Reject inbound webhooks whose body signature doesn't match the configured secret
+pub async fn verify_webhook_signature(
+    State(state): State<AppState>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let (parts, body) = request.into_parts();
+
+    let Some(signature) = parts
+        .headers
+        .get(WEBHOOK_SIGNATURE_HEADER)
+        .and_then(|h| h.to_str().ok())
+        .map(str::to_string)
+    else {
+        return ApiError::Unauthorized("Missing webhook signature".to_string()).into_response();
+    };
+
+    let bytes = match axum::body::to_bytes(body, MAX_WEBHOOK_BODY_BYTES).await {
+        Ok(bytes) => bytes,
+        Err(_) => {
+            return ApiError::BadRequest("Invalid webhook body".to_string()).into_response();
+        }
+    };
+
+    if !state.webhook_service.verify_signature(&bytes, &signature) {
+        tracing::warn!(
+            "Rejected inbound webhook with invalid signature: {} {}",
+            parts.method,
+            parts.uri
+        );
+        return ApiError::Unauthorized("Invalid webhook signature".to_string()).into_response();
+    }
+
+    next.run(Request::from_parts(parts, Body::from(bytes))).await
+}
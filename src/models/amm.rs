@@ -0,0 +1,128 @@
+use chrono::{DateTime, Utc};
+use rust_decimal::{Decimal, RoundingStrategy};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// Decimal places stored for pool reserves and swap amounts
+const AMOUNT_SCALE: u32 = 9;
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct LiquidityPool {
+    pub id: Uuid,
+    pub name: String,
+    pub token_a: String,
+    pub token_b: String,
+    pub reserve_a: Decimal,
+    pub reserve_b: Decimal,
+    pub total_supply: Decimal,
+    pub fee_rate: Decimal,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl LiquidityPool {
+    /// Quote a swap of `input_amount` of `input_token` against the current
+    /// reserves using the constant product formula (x * y = k)
+    ///
+    /// The fee is taken from the input before pricing, and the output is
+    /// rounded down so the pool never pays out more than `k` allows.
+    pub fn calculate_swap(&self, input_token: &str, input_amount: Decimal) -> Result<SwapQuote, String> {
+        if input_amount <= Decimal::ZERO {
+            return Err("Input amount must be positive".to_string());
+        }
+
+        let (input_reserve, output_reserve, output_token) = if input_token == self.token_a {
+            (self.reserve_a, self.reserve_b, &self.token_b)
+        } else if input_token == self.token_b {
+            (self.reserve_b, self.reserve_a, &self.token_a)
+        } else {
+            return Err(format!("Token {} is not in pool {}", input_token, self.name));
+        };
+
+        if input_reserve <= Decimal::ZERO || output_reserve <= Decimal::ZERO {
+            return Err("Pool has no liquidity".to_string());
+        }
+
+        let fee_amount = (input_amount * self.fee_rate).round_dp(AMOUNT_SCALE);
+        let net_input = input_amount - fee_amount;
+        let output_amount = (output_reserve * net_input / (input_reserve + net_input))
+            .round_dp_with_strategy(AMOUNT_SCALE, RoundingStrategy::ToZero);
+
+        if output_amount <= Decimal::ZERO {
+            return Err("Input amount too small to produce any output".to_string());
+        }
+
+        // Shortfall of the execution price against the pre-swap spot price
+        let spot_price = output_reserve / input_reserve;
+        let execution_price = output_amount / input_amount;
+        let price_impact = ((spot_price - execution_price) / spot_price).round_dp(6);
+
+        Ok(SwapQuote {
+            pool_id: self.id,
+            input_token: input_token.to_string(),
+            input_amount,
+            output_token: output_token.clone(),
+            output_amount,
+            fee_amount,
+            price_impact,
+        })
+    }
+}
+
+/// Expected result of a swap at the pool's current reserves
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct SwapQuote {
+    pub pool_id: Uuid,
+    pub input_token: String,
+    #[schema(value_type = String)]
+    pub input_amount: Decimal,
+    pub output_token: String,
+    #[schema(value_type = String)]
+    pub output_amount: Decimal,
+    /// Part of the input kept as the LP fee
+    #[schema(value_type = String)]
+    pub fee_amount: Decimal,
+    /// Fraction by which the execution price falls short of the spot price
+    #[schema(value_type = String)]
+    pub price_impact: Decimal,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct CreatePoolRequest {
+    pub token_a: String,
+    pub token_b: String,
+    pub fee_rate: Decimal,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct AddLiquidityRequest {
+    pub pool_id: Uuid,
+    pub amount_a: Decimal,
+    pub amount_b: Decimal,
+    /// Fail instead of minting fewer shares than this
+    pub min_shares: Option<Decimal>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct RemoveLiquidityRequest {
+    pub pool_id: Uuid,
+    pub shares: Decimal,
+    /// Fail instead of returning less of token A than this
+    pub min_amount_a: Option<Decimal>,
+    /// Fail instead of returning less of token B than this
+    pub min_amount_b: Option<Decimal>,
+}
+
+/// Result of adding or removing liquidity
+#[derive(Debug, Clone, Serialize)]
+pub struct LiquidityOperationResponse {
+    pub pool_id: Uuid,
+    /// Shares minted or burned
+    pub shares: Decimal,
+    pub amount_a: Decimal,
+    pub amount_b: Decimal,
+    /// Pool share supply after the operation
+    pub total_supply: Decimal,
+}
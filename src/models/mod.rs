@@ -1,3 +1,4 @@
+pub mod amm;
 pub mod notification;
 pub mod trading;
 pub mod transaction;
@@ -32,6 +32,12 @@ pub struct TradingOrder {
     pub session_token: Option<String>,
     pub is_confidential: bool,
     pub energy_source: Option<String>, // 'solar', 'wind', 'battery'
+    /// Why the order was cancelled or expired, if it was
+    pub cancellation_reason: Option<OrderCloseReason>,
+    /// Slice shown on the public book for an iceberg order
+    #[schema(value_type = Option<String>)]
+    pub display_quantity: Option<Decimal>,
+    pub time_in_force: TimeInForce,
 }
 
 #[derive(Debug, Clone, FromRow)]
@@ -55,12 +61,73 @@ pub struct TradingOrderDb {
     pub session_token: Option<String>,
     pub is_confidential: bool,
     pub energy_source: Option<String>,
+    #[sqlx(default)]
+    pub cancellation_reason: Option<String>,
     // Conditional order fields
     pub trigger_price: Option<Decimal>,
     pub trigger_type: Option<TriggerType>,
     pub trigger_status: Option<TriggerStatus>,
     pub trailing_offset: Option<Decimal>,
     pub triggered_at: Option<DateTime<Utc>>,
+    // Iceberg order fields
+    #[sqlx(default)]
+    pub display_quantity: Option<Decimal>,
+    #[sqlx(default)]
+    pub display_refreshed_at: Option<DateTime<Utc>>,
+    #[sqlx(default)]
+    pub time_in_force: Option<String>,
+}
+
+impl TradingOrderDb {
+    /// Quantity shown on the public book (see [`visible_quantity`])
+    pub fn visible_quantity(&self) -> Decimal {
+        visible_quantity(
+            self.energy_amount,
+            self.filled_amount.unwrap_or(Decimal::ZERO),
+            self.display_quantity,
+        )
+    }
+
+    /// Time the order queues from at its price: creation, or the reveal of
+    /// the current iceberg slice
+    pub fn priority_time(&self) -> Option<DateTime<Utc>> {
+        self.display_refreshed_at.or(self.created_at)
+    }
+
+    pub fn time_in_force(&self) -> TimeInForce {
+        self.time_in_force
+            .as_deref()
+            .and_then(TimeInForce::from_db)
+            .unwrap_or_default()
+    }
+}
+
+/// Quantity of an order shown on the public book
+///
+/// An iceberg order shows what is left of its current slice of
+/// `display_quantity`; any other order shows its whole remaining amount.
+pub fn visible_quantity(energy_amount: Decimal, filled_amount: Decimal, display_quantity: Option<Decimal>) -> Decimal {
+    let remaining = (energy_amount - filled_amount).max(Decimal::ZERO);
+    match display_quantity {
+        Some(display) if display > Decimal::ZERO => remaining.min(display - filled_amount % display),
+        _ => remaining,
+    }
+}
+
+/// Whether a fill from `filled_before` to `filled_after` used up the shown
+/// slice of an iceberg order and revealed another
+pub fn slice_refreshed(
+    energy_amount: Decimal,
+    filled_before: Decimal,
+    filled_after: Decimal,
+    display_quantity: Option<Decimal>,
+) -> bool {
+    match display_quantity {
+        Some(display) if display > Decimal::ZERO => {
+            filled_after < energy_amount && (filled_after / display).floor() > (filled_before / display).floor()
+        }
+        _ => false,
+    }
 }
 
 impl From<TradingOrderDb> for TradingOrder {
@@ -85,6 +152,146 @@ impl From<TradingOrderDb> for TradingOrder {
             session_token: db.session_token,
             is_confidential: db.is_confidential,
             energy_source: db.energy_source,
+            cancellation_reason: db
+                .cancellation_reason
+                .as_deref()
+                .and_then(OrderCloseReason::from_db),
+            display_quantity: db.display_quantity,
+            time_in_force: db.time_in_force.as_deref().and_then(TimeInForce::from_db).unwrap_or_default(),
+        }
+    }
+}
+
+impl TradingOrder {
+    /// Public view of the order: an iceberg order is shown as if only its
+    /// current slice had been placed
+    pub fn public_view(mut self) -> Self {
+        if self.display_quantity.is_some() {
+            let visible = visible_quantity(self.energy_amount, self.filled_amount, self.display_quantity);
+            self.energy_amount = visible;
+            self.filled_amount = Decimal::ZERO;
+            self.display_quantity = None;
+        }
+        self
+    }
+}
+
+/// Why an order was rejected at creation or closed without filling
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum OrderCloseReason {
+    /// Energy amount was zero or negative
+    InvalidAmount,
+    /// Limit order submitted without a price
+    MissingLimitPrice,
+    /// Limit price was zero or negative
+    InvalidPrice,
+    /// Requested expiry time was already in the past
+    InvalidExpiry,
+    /// Balance did not cover the buy escrow
+    InsufficientFunds,
+    /// Wallet did not hold enough energy for the sell order
+    InsufficientEnergy,
+    /// Remaining amount fell below the minimum tradable size
+    Dust,
+    /// Order passed its expiry time before filling
+    Expired,
+    /// Cancelled by the order owner
+    UserCancelled,
+    /// Force-cancelled by an admin
+    AdminCancelled,
+    /// Would have matched another order from the same user
+    SelfTrade,
+    /// Unfilled remainder of an immediate-or-cancel order after its matching pass
+    ImmediateOrCancel,
+    /// Fill-or-kill order could not be filled in full in one matching pass
+    FillOrKill,
+}
+
+impl OrderCloseReason {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            OrderCloseReason::InvalidAmount => "invalid_amount",
+            OrderCloseReason::MissingLimitPrice => "missing_limit_price",
+            OrderCloseReason::InvalidPrice => "invalid_price",
+            OrderCloseReason::InvalidExpiry => "invalid_expiry",
+            OrderCloseReason::InsufficientFunds => "insufficient_funds",
+            OrderCloseReason::InsufficientEnergy => "insufficient_energy",
+            OrderCloseReason::Dust => "dust",
+            OrderCloseReason::Expired => "expired",
+            OrderCloseReason::UserCancelled => "user_cancelled",
+            OrderCloseReason::AdminCancelled => "admin_cancelled",
+            OrderCloseReason::SelfTrade => "self_trade",
+            OrderCloseReason::ImmediateOrCancel => "immediate_or_cancel",
+            OrderCloseReason::FillOrKill => "fill_or_kill",
+        }
+    }
+
+    /// Parse the value stored in `trading_orders.cancellation_reason`
+    pub fn from_db(value: &str) -> Option<Self> {
+        match value {
+            "invalid_amount" => Some(OrderCloseReason::InvalidAmount),
+            "missing_limit_price" => Some(OrderCloseReason::MissingLimitPrice),
+            "invalid_price" => Some(OrderCloseReason::InvalidPrice),
+            "invalid_expiry" => Some(OrderCloseReason::InvalidExpiry),
+            "insufficient_funds" => Some(OrderCloseReason::InsufficientFunds),
+            "insufficient_energy" => Some(OrderCloseReason::InsufficientEnergy),
+            "dust" => Some(OrderCloseReason::Dust),
+            "expired" => Some(OrderCloseReason::Expired),
+            "user_cancelled" => Some(OrderCloseReason::UserCancelled),
+            "admin_cancelled" => Some(OrderCloseReason::AdminCancelled),
+            "self_trade" => Some(OrderCloseReason::SelfTrade),
+            "immediate_or_cancel" => Some(OrderCloseReason::ImmediateOrCancel),
+            "fill_or_kill" => Some(OrderCloseReason::FillOrKill),
+            _ => None,
+        }
+    }
+}
+
+impl std::fmt::Display for OrderCloseReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// How long an order stays on the book
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum TimeInForce {
+    /// Good til cancelled: rests until filled, cancelled or expired
+    #[default]
+    Gtc,
+    /// Immediate or cancel: whatever one matching pass leaves unfilled is cancelled
+    Ioc,
+    /// Fill or kill: filled in full by one matching pass or cancelled outright
+    Fok,
+}
+
+impl TimeInForce {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TimeInForce::Gtc => "GTC",
+            TimeInForce::Ioc => "IOC",
+            TimeInForce::Fok => "FOK",
+        }
+    }
+
+    /// Parse the value stored in `trading_orders.time_in_force`
+    pub fn from_db(value: &str) -> Option<Self> {
+        match value {
+            "GTC" => Some(TimeInForce::Gtc),
+            "IOC" => Some(TimeInForce::Ioc),
+            "FOK" => Some(TimeInForce::Fok),
+            _ => None,
+        }
+    }
+
+    /// Why the unfilled remainder is cancelled after a matching pass, if it is
+    pub fn close_reason(&self) -> Option<OrderCloseReason> {
+        match self {
+            TimeInForce::Gtc => None,
+            TimeInForce::Ioc => Some(OrderCloseReason::ImmediateOrCancel),
+            TimeInForce::Fok => Some(OrderCloseReason::FillOrKill),
         }
     }
 }
@@ -111,11 +318,15 @@ pub struct CreateOrderRequest {
     #[schema(value_type = String, example = "10.5")]
     pub energy_amount: Decimal,
     
+    /// Limit price. For a market buy, the optional highest landed cost per
+    /// kWh accepted (slippage guard); ignored for market sells
     #[schema(value_type = String, example = "0.15")]
     pub price_per_kwh: Option<Decimal>,
 
     pub order_type: OrderType,
 
+    /// Defaults to `ORDER_DEFAULT_EXPIRY_HOURS` from now and is clamped to
+    /// `ORDER_MAX_EXPIRY_HOURS`; past times are rejected
     pub expiry_time: Option<DateTime<Utc>>,
 
     pub zone_id: Option<i32>,
@@ -130,6 +341,20 @@ pub struct CreateOrderRequest {
 
     /// Session token for wallet decryption (auto-trading)
     pub session_token: Option<String>,
+
+    /// Client-generated idempotency key; retries with the same key return the original order
+    #[validate(length(min = 1, max = 64))]
+    pub client_order_id: Option<String>,
+
+    /// Limit orders only: show at most this much (kWh) on the public book at
+    /// a time. Each slice revealed after a fill queues behind orders already
+    /// resting at its price
+    #[schema(value_type = Option<String>, example = "2.5")]
+    pub display_quantity: Option<Decimal>,
+
+    /// Defaults to GTC. IOC and FOK orders get one matching pass: IOC
+    /// cancels what it leaves unfilled, FOK cancels unless it fills in full
+    pub time_in_force: Option<TimeInForce>,
 }
 
 #[derive(Debug, Deserialize, Validate, ToSchema)]
@@ -140,6 +365,14 @@ pub struct UpdateOrderRequest {
     pub price_per_kwh: Option<Decimal>,
 }
 
+/// Request to reduce the size of a resting order
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct ReduceOrderRequest {
+    /// Amount of energy (kWh) to remove from the order
+    #[schema(value_type = String)]
+    pub reduce_by: Decimal,
+}
+
 #[derive(Debug, Clone, Serialize, ToSchema)]
 pub struct MarketData {
     pub current_epoch: u64,
@@ -30,6 +30,27 @@ impl FromStr for TransactionStatus {
     }
 }
 
+impl TransactionStatus {
+    /// Status of a P2P order from its `trading_orders.status`
+    pub fn from_order_status(status: &str) -> Self {
+        match status {
+            "filled" | "settled" => TransactionStatus::Settled,
+            "cancelled" | "expired" => TransactionStatus::Failed,
+            "partially_filled" => TransactionStatus::Processing,
+            _ => TransactionStatus::Pending,
+        }
+    }
+
+    /// Status of a blockchain transaction from its `blockchain_transactions.status`
+    pub fn from_chain_status(status: &str) -> Self {
+        match status {
+            "Confirmed" | "Finalized" => TransactionStatus::Confirmed,
+            "Failed" => TransactionStatus::Failed,
+            _ => TransactionStatus::Pending,
+        }
+    }
+}
+
 impl std::fmt::Display for TransactionStatus {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let s = match self {
@@ -73,6 +94,18 @@ impl FromStr for TransactionType {
 }
 
 impl TransactionType {
+    /// Type of a blockchain transaction from its instruction name
+    pub fn from_instruction(instruction: Option<&str>) -> Self {
+        match instruction {
+            Some("place_order") => TransactionType::EnergyTrade,
+            Some("swap") => TransactionType::Swap,
+            Some("mint") => TransactionType::TokenMint,
+            Some("transfer") => TransactionType::TokenTransfer,
+            Some("vote") => TransactionType::GovernanceVote,
+            _ => TransactionType::RegistryUpdate,
+        }
+    }
+
     pub fn as_str(&self) -> &'static str {
         match self {
             TransactionType::EnergyTrade => "energy_trade",
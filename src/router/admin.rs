@@ -1,18 +1,85 @@
-//! Admin-only routes (placeholder for future expansion).
+//! Admin-only routes.
 //!
-//! Most admin routes are currently in protected.rs.
-//! This module can be used to separate admin routes further if needed.
+//! Every route here is wrapped in `require_admin_role`; the caller is expected
+//! to apply `auth_middleware` when nesting so claims are available.
+
+use axum::{
+    middleware::from_fn,
+    routing::{get, post},
+    Router,
+};
 
-use axum::Router;
 use crate::app_state::AppState;
+use crate::auth::middleware::require_admin_role;
+use crate::handlers::admin;
 
 /// Build admin-only routes.
-///
-/// Currently, admin routes are integrated into protected_routes.
-/// This function is provided for future expansion when admin routes
-/// need to be separated into their own module with different middleware.
 pub fn admin_routes() -> Router<AppState> {
     Router::new()
-    // Future: Add admin-specific routes here with different middleware
-    // e.g., IP whitelist, additional audit logging, etc.
+        // Event processor
+        .route(
+            "/event-processor/stats",
+            get(admin::get_event_processor_stats),
+        )
+        .route("/event-processor/replay", get(admin::get_replay_status))
+        // Audit log
+        .route("/audit", get(admin::search_audit_log))
+        // Epochs
+        .route("/epochs/{id}/clear", post(admin::clear_epoch))
+        .route("/epochs/{id}/resolve-halt", post(admin::resolve_epoch_halt))
+        .route("/price-band", get(admin::get_price_band))
+        // Matching engine
+        .route("/matching/status", get(admin::get_matching_status))
+        .route("/matching/pause", post(admin::pause_matching))
+        .route("/matching/resume", post(admin::resume_matching))
+        // Orders
+        .route("/orders/{id}/force-cancel", post(admin::force_cancel_order))
+        // Reconciliation
+        .route(
+            "/reconciliation/{user_id}",
+            get(admin::reconcile_user_balance),
+        )
+        // Revenue
+        .route("/revenue/reconcile", get(admin::reconcile_revenue))
+        .route("/revenue/by-recipient", get(admin::get_revenue_by_recipient))
+        .route("/treasury/sweep", post(admin::sweep_treasury))
+        // Settlements
+        .route(
+            "/settlements/process",
+            post(admin::process_pending_settlements),
+        )
+        .route(
+            "/settlements/{id}/validate",
+            post(admin::validate_settlement_path),
+        )
+        .route(
+            "/settlements/{id}/audit",
+            get(admin::get_settlement_audit_trail),
+        )
+        .route(
+            "/settlements/dead-letters",
+            get(admin::list_dead_letter_settlements),
+        )
+        .route(
+            "/settlements/{id}/replay",
+            post(admin::replay_settlement),
+        )
+        .route(
+            "/settlements/{id}/expedite",
+            post(admin::expedite_settlement),
+        )
+        .route(
+            "/settlements/retry-overrides",
+            get(admin::get_retry_overrides),
+        )
+        .route(
+            "/settlements/retry-overrides/reload",
+            post(admin::reload_retry_overrides),
+        )
+        // Tracing
+        .route(
+            "/trace/{correlation_id}",
+            get(admin::get_correlation_trace),
+        )
+        .layer(from_fn(require_admin_role))
 }
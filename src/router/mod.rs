@@ -4,21 +4,28 @@
 
 use axum::{routing::{get, post}, Router, extract::State, middleware};
 use tower::ServiceBuilder;
-use tower_http::{cors::CorsLayer, timeout::TimeoutLayer, trace::TraceLayer};
+use tower_http::{
+    cors::CorsLayer, limit::RequestBodyLimitLayer, timeout::TimeoutLayer, trace::TraceLayer,
+};
 use utoipa::OpenApi;
 use utoipa_swagger_ui::SwaggerUi;
 
+pub mod admin;
 pub mod dev;
 pub mod public;
 
 use crate::app_state::AppState;
+use crate::config::cors::{CorsHeaders, CorsMethods};
 use crate::handlers::{
     // V1 RESTful routes
     v1_auth_routes, v1_users_routes, v1_meters_routes, v1_wallets_routes, v1_status_routes,
     v1_trading_routes, v1_dashboard_routes,
 };
-use crate::auth::middleware::auth_middleware;
-use crate::middleware::{metrics_middleware, active_requests_middleware};
+use crate::auth::middleware::{auth_middleware, require_admin_role};
+use crate::middleware::{
+    metrics_middleware, active_requests_middleware, rate_limit_middleware, trace_id_middleware,
+    verify_webhook_signature,
+};
 
 /// OpenAPI documentation for GridTokenX API
 #[derive(OpenApi)]
@@ -33,11 +40,19 @@ use crate::middleware::{metrics_middleware, active_requests_middleware};
         (name = "users", description = "User management"),
         (name = "trading", description = "P2P Energy Trading"),
         (name = "meters", description = "Smart Meter management"),
+        (name = "grid", description = "Grid topology and delivery costs"),
+        (name = "webhooks", description = "Signed inbound partner callbacks"),
+        (name = "transactions", description = "Transaction status tracking"),
+        (name = "erc", description = "Renewable energy certificates"),
+        (name = "amm", description = "AMM liquidity pools and swaps"),
+        (name = "admin", description = "Platform administration"),
         (name = "dev", description = "Developer tools")
     ),
     paths(
         crate::handlers::auth::login::login,
         crate::handlers::auth::login::verify_email,
+        crate::handlers::auth::session::refresh_token,
+        crate::handlers::auth::session::logout,
         crate::handlers::auth::registration::register,
         crate::handlers::auth::registration::resend_verification,
         crate::handlers::auth::profile::profile,
@@ -54,11 +69,25 @@ use crate::middleware::{metrics_middleware, active_requests_middleware};
         crate::handlers::auth::meters::get_my_readings,
         crate::handlers::trading::orders::create::create_order,
         crate::handlers::trading::orders::queries::get_user_orders,
+        crate::handlers::trading::orders::queries::get_order,
         crate::handlers::trading::orders::management::cancel_order,
+        crate::handlers::trading::orders::management::cancel_all_orders,
+        crate::handlers::trading::orders::management::reduce_order,
         crate::handlers::trading::orders::management::update_order,
         crate::handlers::trading::orders::queries::get_order_book,
+        crate::handlers::trading::orders::queries::get_order_book_depth,
         crate::handlers::trading::orders::queries::get_my_trades,
         crate::handlers::trading::orders::queries::get_token_balance,
+        crate::handlers::trading::exposure::get_exposure,
+        crate::handlers::trading::settlements::get_settlement_detail,
+        crate::handlers::transactions::get_transaction_statuses,
+        crate::handlers::swap::list_pools,
+        crate::handlers::swap::get_quote,
+        crate::handlers::swap::get_pool_twap,
+        crate::handlers::swap::execute_swap,
+        crate::handlers::swap::get_swap_history,
+        crate::handlers::erc::issue_certificate_batch,
+        crate::handlers::trading::export::export_trading_history,
         crate::handlers::trading::blockchain::get_blockchain_market_data,
         crate::handlers::trading::blockchain::match_blockchain_orders,
         crate::handlers::auth::wallets::token_balance,
@@ -74,6 +103,7 @@ use crate::middleware::{metrics_middleware, active_requests_middleware};
         crate::handlers::analytics::admin::get_admin_activity,
         crate::handlers::analytics::admin::get_system_health,
         crate::handlers::analytics::admin::get_zone_economic_insights,
+        crate::handlers::analytics::sustainability::get_sustainability_report,
         crate::handlers::futures::get_products,
         crate::handlers::futures::create_order,
         crate::handlers::futures::get_my_orders,
@@ -84,13 +114,43 @@ use crate::middleware::{metrics_middleware, active_requests_middleware};
         crate::handlers::meter::stub::get_meter_health,
         crate::handlers::meter::get_zones,
         crate::handlers::meter::get_zone_stats,
+        crate::handlers::grid::get_grid_topology,
+        crate::handlers::grid::preview_trade_cost,
+        crate::handlers::webhooks::settlement_callback,
         crate::handlers::dev::metrics::get_metrics,
         crate::handlers::dashboard::get_dashboard_metrics,
+        crate::handlers::admin::audit::search_audit_log,
+        crate::handlers::admin::epochs::clear_epoch,
+        crate::handlers::admin::epochs::get_price_band,
+        crate::handlers::admin::epochs::resolve_epoch_halt,
+        crate::handlers::admin::orders::force_cancel_order,
+        crate::handlers::admin::events::get_event_processor_stats,
+        crate::handlers::admin::events::get_replay_status,
+        crate::handlers::admin::matching::get_matching_status,
+        crate::handlers::admin::matching::pause_matching,
+        crate::handlers::admin::matching::resume_matching,
+        crate::handlers::admin::reconciliation::reconcile_user_balance,
+        crate::handlers::admin::revenue::reconcile_revenue,
+        crate::handlers::admin::revenue::get_revenue_by_recipient,
+        crate::handlers::admin::treasury::sweep_treasury,
+        crate::handlers::admin::trace::get_correlation_trace,
+        crate::handlers::admin::settlements::validate_settlement_path,
+        crate::handlers::admin::settlements::process_pending_settlements,
+        crate::handlers::admin::settlements::get_settlement_audit_trail,
+        crate::handlers::admin::settlements::list_dead_letter_settlements,
+        crate::handlers::admin::settlements::replay_settlement,
+        crate::handlers::admin::settlements::expedite_settlement,
+        crate::handlers::admin::settlements::get_retry_overrides,
+        crate::handlers::admin::settlements::reload_retry_overrides,
     ),
     components(
         schemas(
             crate::handlers::auth::types::LoginRequest,
             crate::handlers::auth::types::AuthResponse,
+            crate::handlers::auth::types::RefreshTokenRequest,
+            crate::handlers::auth::types::RefreshTokenResponse,
+            crate::handlers::auth::types::LogoutRequest,
+            crate::handlers::auth::types::LogoutResponse,
             crate::handlers::auth::types::UserResponse,
             crate::handlers::auth::types::RegistrationRequest,
             crate::handlers::auth::types::RegistrationResponse,
@@ -107,15 +167,22 @@ use crate::middleware::{metrics_middleware, active_requests_middleware};
             crate::handlers::auth::types::UpdateMeterStatusRequest,
             crate::handlers::auth::types::CreateReadingRequest,
             crate::handlers::auth::types::CreateReadingResponse,
+            crate::handlers::auth::types::ReadingIngestStatus,
             crate::handlers::auth::types::MeterReadingResponse,
             crate::models::trading::TradingOrder,
+            crate::models::trading::OrderCloseReason,
+            crate::models::trading::TimeInForce,
             crate::models::trading::CreateOrderRequest,
             crate::models::trading::UpdateOrderRequest,
+            crate::models::trading::ReduceOrderRequest,
             crate::models::trading::MarketData,
             crate::models::trading::OrderBook,
             crate::models::trading::Trade,
             crate::handlers::trading::types::TradingOrdersResponse,
             crate::handlers::trading::types::CreateOrderResponse,
+            crate::handlers::trading::types::OrderDetailResponse,
+            crate::handlers::trading::types::OrderMatchDetail,
+            crate::handlers::trading::types::OrderSettlementDetail,
             crate::handlers::trading::types::TradingStats,
             crate::handlers::trading::types::BlockchainMarketData,
             crate::handlers::trading::types::CreateBlockchainOrderRequest,
@@ -123,8 +190,17 @@ use crate::middleware::{metrics_middleware, active_requests_middleware};
             crate::handlers::trading::types::MatchOrdersResponse,
             crate::handlers::trading::types::MarketStats,
             crate::handlers::trading::orders::queries::TradeRecord,
-            crate::handlers::trading::orders::queries::TradeHistoryResponse,
             crate::handlers::trading::orders::queries::TokenBalanceResponse,
+            crate::services::trading_account::TradingExposure,
+            crate::services::trading_account::OpenSideExposure,
+            crate::services::trading_account::SettledSummary,
+            crate::handlers::trading::export::HistoryExportFormat,
+            crate::handlers::trading::types::DepthQuery,
+            crate::services::market_clearing::OrderBookDepth,
+            crate::services::market_clearing::DepthLevel,
+            crate::handlers::trading::types::CancelOrdersQuery,
+            crate::services::market_clearing::BulkCancelResult,
+            crate::services::market_clearing::BulkCancelFailure,
             crate::database::schema::types::OrderSide,
             crate::database::schema::types::OrderType,
             crate::database::schema::types::OrderStatus,
@@ -138,6 +214,10 @@ use crate::middleware::{metrics_middleware, active_requests_middleware};
             crate::handlers::auth::status::CheckResult,
             crate::handlers::auth::status::LivenessResponse,
             crate::handlers::analytics::types::MarketAnalytics,
+            crate::services::sustainability::SustainabilityReport,
+            crate::services::sustainability::SourceSustainability,
+            crate::services::sustainability::ZoneSustainability,
+            crate::services::sustainability::UserSustainability,
             crate::handlers::analytics::types::MarketOverview,
             crate::handlers::analytics::types::TradingVolume,
             crate::handlers::analytics::types::PriceStatistics,
@@ -150,7 +230,6 @@ use crate::middleware::{metrics_middleware, active_requests_middleware};
             crate::handlers::analytics::types::UserWealthHistory,
             crate::handlers::analytics::types::WealthPoint,
             crate::handlers::analytics::types::UserTransaction,
-            crate::handlers::analytics::types::UserTransactionsResponse,
             crate::handlers::analytics::types::ZoneTradeStats,
             crate::handlers::analytics::types::ZoneRevenueBreakdown,
             crate::handlers::analytics::types::ZoneEconomicInsights,
@@ -158,8 +237,31 @@ use crate::middleware::{metrics_middleware, active_requests_middleware};
             crate::services::audit_logger::types::AuditEventRecord,
             crate::services::health_check::types::DetailedHealthStatus,
             crate::services::health_check::types::DependencyHealth,
+            crate::services::health_check::types::DatabasePoolStats,
             crate::services::health_check::types::HealthCheckStatus,
             crate::services::health_check::types::SystemMetrics,
+            crate::services::event_processor::EventProcessorStats,
+            crate::services::event_processor::ReplayStatus,
+            crate::handlers::admin::audit::AuditLogResponse,
+            crate::utils::PaginationMeta,
+            crate::handlers::admin::epochs::EpochClearResponse,
+            crate::handlers::admin::orders::ForceCancelRequest,
+            crate::services::market_clearing::ForceCancelResult,
+            crate::services::market_clearing::PriceBandStatus,
+            crate::services::market_clearing::PriceReferenceSource,
+            crate::services::market_clearing::HaltedEpoch,
+            crate::handlers::admin::epochs::ResolveHaltRequest,
+            crate::services::market_clearing::HaltResolution,
+            crate::services::market_clearing::HaltResolutionResult,
+            crate::handlers::admin::settlements::SettlementFlushResponse,
+            crate::handlers::admin::settlements::SettlementReplayResponse,
+            crate::handlers::admin::settlements::SettlementExpediteResponse,
+            crate::services::settlement::SettlementDeadLetter,
+            crate::services::settlement::RetryOverrides,
+            crate::services::settlement::RetryOverride,
+            crate::services::settlement::RetryRuleSource,
+            crate::services::order_matching_engine::types::MatchingEngineStatus,
+            crate::services::order_matching_engine::types::MatchingCycleSummary,
             crate::handlers::futures::CreateFuturesOrderRequest,
             crate::services::futures::FuturesProduct,
             crate::services::futures::FuturesPosition,
@@ -175,6 +277,45 @@ use crate::middleware::{metrics_middleware, active_requests_middleware};
             crate::handlers::auth::types::TrendRecord,
             crate::handlers::meter::ZoneSummary,
             crate::handlers::meter::ZoneStats,
+            crate::handlers::grid::GridTopologyResponse,
+            crate::handlers::webhooks::SettlementCallback,
+            crate::services::grid_topology::ZoneRouteCost,
+            crate::services::grid_topology::LandedCostBreakdown,
+            crate::services::reconciliation::UserReconciliation,
+            crate::services::event_processor::CorrelationTrace,
+            crate::services::event_processor::TraceEntry,
+            crate::services::settlement::RevenueReconciliation,
+            crate::services::settlement::RevenueDiscrepancy,
+            crate::services::settlement::RevenueByRecipient,
+            crate::services::settlement::RecipientRevenue,
+            crate::services::settlement::RevenueRecipient,
+            crate::services::settlement::RevenueSplits,
+            crate::services::settlement::RevenueSplit,
+            crate::services::settlement::RevenueShare,
+            crate::services::settlement::SettlementDetail,
+            crate::services::settlement::SettlementRevenueEntry,
+            crate::services::settlement::SettlementEscrowEntry,
+            crate::handlers::erc::BatchIssueErcRequest,
+            crate::handlers::erc::BatchIssueErcResponse,
+            crate::handlers::erc::BatchReadingResultResponse,
+            crate::handlers::erc::ErcCertificateResponse,
+            crate::handlers::transactions::BatchTransactionStatusRequest,
+            crate::handlers::transactions::BatchTransactionStatusResponse,
+            crate::handlers::swap::QuoteRequest,
+            crate::handlers::swap::PoolResponse,
+            crate::handlers::swap::ExecuteSwapRequest,
+            crate::services::amm::SwapTransaction,
+            crate::models::amm::SwapQuote,
+            crate::services::amm::PoolTwap,
+            crate::models::transaction::TransactionResponse,
+            crate::models::transaction::TransactionType,
+            crate::models::transaction::TransactionStatus,
+            crate::services::treasury::TreasurySweepReport,
+            crate::services::treasury::RecipientSweep,
+            crate::services::treasury::SweepStatus,
+            crate::services::settlement::SettlementPathReport,
+            crate::services::settlement::SettlementPathStep,
+            crate::services::settlement::SettlementPathStepStatus,
         )
     )
 )]
@@ -218,6 +359,9 @@ pub fn build_router(app_state: AppState) -> Router {
     let meters_routes = v1_meters_routes()
         .layer(middleware::from_fn_with_state(app_state.clone(), auth_middleware));
 
+    let admin_routes = admin::admin_routes()
+        .layer(middleware::from_fn_with_state(app_state.clone(), auth_middleware));
+
     // Public routes (no auth required)
     let public_routes = Router::new()
         .route("/meters", get(crate::handlers::auth::meters::public_get_meters))
@@ -225,6 +369,16 @@ pub fn build_router(app_state: AppState) -> Router {
         .route("/grid-status/history", get(crate::handlers::auth::meters::public_grid_history))
         .route("/meters/batch/readings", post(crate::handlers::auth::meters::create_batch_readings));
 
+    // Grid topology transparency (no auth required)
+    let grid_routes = Router::new()
+        .route("/topology", get(crate::handlers::grid::get_grid_topology))
+        .route("/cost-preview", get(crate::handlers::grid::preview_trade_cost));
+
+    // Partner callbacks, authenticated by body signature rather than user token
+    let webhook_routes = Router::new()
+        .route("/settlements", post(crate::handlers::webhooks::settlement_callback))
+        .layer(middleware::from_fn_with_state(app_state.clone(), verify_webhook_signature));
+
     // Simulator routes (no auth required for meter registration)
     let simulator_routes = Router::new()
         .route("/meters/register", post(crate::handlers::meter::stub::register_meter_by_id));
@@ -252,6 +406,34 @@ pub fn build_router(app_state: AppState) -> Router {
         .route("/transfer", post(crate::handlers::carbon::transfer_credits))
         .layer(middleware::from_fn_with_state(app_state.clone(), auth_middleware));
 
+    // Transaction status routes (auth required)
+    let transactions_routes = Router::new()
+        .route("/status/batch", post(crate::handlers::transactions::get_transaction_statuses))
+        .layer(middleware::from_fn_with_state(app_state.clone(), auth_middleware));
+
+    // Certificate issuance routes (auth required, REC authority role checked per handler)
+    let erc_routes = Router::new()
+        .route("/issue/batch", post(crate::handlers::erc::issue_certificate_batch))
+        .layer(middleware::from_fn_with_state(app_state.clone(), auth_middleware));
+
+    // AMM routes (pool data and quotes are public, history requires auth).
+    // Swaps move pool reserves without settling user token balances, so they
+    // stay admin-only until balances are debited and credited with them.
+    let amm_routes = Router::new()
+        .route("/pools", get(crate::handlers::swap::list_pools))
+        .route("/pools/{id}/twap", get(crate::handlers::swap::get_pool_twap))
+        .route("/quote", post(crate::handlers::swap::get_quote))
+        .merge(
+            Router::new()
+                .route(
+                    "/swap",
+                    post(crate::handlers::swap::execute_swap)
+                        .layer(middleware::from_fn(require_admin_role)),
+                )
+                .route("/swaps", get(crate::handlers::swap::get_swap_history))
+                .layer(middleware::from_fn_with_state(app_state.clone(), auth_middleware)),
+        );
+
     let v1_api = Router::new()
         .nest("/auth", v1_auth_routes())       // POST /api/v1/auth/token, GET /api/v1/auth/verify
         .nest("/users", v1_users_routes())     // POST /api/v1/users, GET /api/v1/users/me
@@ -259,16 +441,24 @@ pub fn build_router(app_state: AppState) -> Router {
         .nest("/wallets", v1_wallets_routes()) // GET /api/v1/wallets/{address}/balance (legacy)
         .nest("/user-wallets", user_wallets_routes) // Multi-wallet management
         .nest("/carbon", carbon_routes)        // Carbon credits tracking
+        .nest("/transactions", transactions_routes) // POST /api/v1/transactions/status/batch
+        .nest("/erc", erc_routes)              // POST /api/v1/erc/issue/batch
         .nest("/status", v1_status_routes())   // GET /api/v1/status
         .nest("/trading", trading_routes)      // POST /api/v1/trading/orders
         .nest("/futures", futures_routes)      // /api/v1/futures
         .nest("/analytics", analytics_routes)  // /api/v1/analytics
         .nest("/dashboard", v1_dashboard_routes()) // /api/v1/dashboard/metrics
         .nest("/notifications", notifications_routes) // /api/v1/notifications
+        .nest("/admin", admin_routes)          // /api/v1/admin (admin role required)
         .nest("/dev", dev::dev_routes())       // POST /api/v1/dev/faucet
         .nest("/public", public_routes)        // GET /api/v1/public/meters (no auth)
+        .nest("/grid", grid_routes)            // GET /api/v1/grid/topology (no auth)
+        .nest("/amm", amm_routes)              // POST /api/v1/amm/swap, GET /api/v1/amm/pools/{id}/twap
         .nest("/simulator", simulator_routes)  // POST /api/v1/simulator/meters/register (no auth)
-        .route("/rpc", axum::routing::post(crate::handlers::rpc::rpc_handler)); // /api/v1/rpc
+        .nest("/webhooks", webhook_routes)     // POST /api/v1/webhooks/settlements (signed body)
+        .route("/rpc", axum::routing::post(crate::handlers::rpc::rpc_handler)) // /api/v1/rpc
+        // Stricter budgets for auth and order creation than for reads
+        .layer(middleware::from_fn_with_state(app_state.clone(), rate_limit_middleware));
 
     // Proxy routes implementation (at root /api/*)
     let proxy_routes = Router::new()
@@ -284,37 +474,29 @@ pub fn build_router(app_state: AppState) -> Router {
         .nest("/api/v1", v1_api)
         .layer(
             ServiceBuilder::new()
+                .layer(middleware::from_fn(trace_id_middleware))
                 .layer(middleware::from_fn(metrics_middleware))
                 .layer(middleware::from_fn(active_requests_middleware))
                 .layer(TraceLayer::new_for_http())
+                // Replace axum's fixed 2 MiB extractor limit with the configured cap (413 when exceeded)
+                .layer(axum::extract::DefaultBodyLimit::disable())
+                .layer(RequestBodyLimitLayer::new(app_state.config.max_request_body_bytes))
                 .layer(TimeoutLayer::with_status_code(
                     axum::http::StatusCode::REQUEST_TIMEOUT,
                     std::time::Duration::from_secs(900),
                 ))
                 .layer({
-                    let allowed_origins = app_state.config.cors_allowed_origins.clone();
+                    // Read through the shared handle so SIGHUP reloads apply immediately
+                    let runtime_config = app_state.runtime_config.clone();
                     CorsLayer::new()
                         .allow_origin(tower_http::cors::AllowOrigin::predicate(
                             move |origin: &axum::http::HeaderValue, _request_parts: &axum::http::request::Parts| {
                                 let origin_str = origin.to_str().unwrap_or("");
-                                allowed_origins.iter().any(|allowed| {
-                                    origin_str == allowed || origin_str.starts_with(allowed)
-                                })
+                                runtime_config.is_origin_allowed(origin_str)
                             },
                         ))
-                        .allow_methods([
-                            axum::http::Method::GET,
-                            axum::http::Method::POST,
-                            axum::http::Method::PUT,
-                            axum::http::Method::PATCH,
-                            axum::http::Method::DELETE,
-                            axum::http::Method::OPTIONS,
-                        ])
-                        .allow_headers([
-                            axum::http::header::AUTHORIZATION,
-                            axum::http::header::CONTENT_TYPE,
-                            axum::http::header::ACCEPT,
-                        ])
+                        .allow_methods(CorsMethods::from_env().0)
+                        .allow_headers(CorsHeaders::from_env().0)
                         .allow_credentials(true)
                 }),
         )
@@ -164,11 +164,6 @@ fn admin_routes() -> Router<AppState> {
         .route("/governance/unpause", post(governance::emergency_unpause))
         // Token admin routes
         .route("/tokens/mint", post(token::mint_tokens))
-        // AMM Routes
-        .route("/swap/quote", post(handlers::swap::get_quote))
-        .route("/swap/execute", post(handlers::swap::execute_swap))
-        .route("/swap/pools", get(handlers::swap::list_pools))
-        .route("/swap/history", get(handlers::swap::get_swap_history))
         // Transaction routes
         .nest("/api/tx", transaction_routes())
         // Trading admin routes
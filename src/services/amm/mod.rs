@@ -0,0 +1,1195 @@
+pub mod types;
+
+use std::future::Future;
+use std::time::Duration;
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use rust_decimal::prelude::*;
+use rust_decimal::Decimal;
+use sqlx::{PgPool, Postgres, Transaction};
+use tracing::{debug, info, instrument};
+use uuid::Uuid;
+
+use crate::error::ApiError;
+use crate::services::WebSocketService;
+
+use crate::models::amm::{
+    AddLiquidityRequest, CreatePoolRequest, LiquidityOperationResponse, LiquidityPool,
+    RemoveLiquidityRequest, SwapQuote,
+};
+
+pub use types::*;
+
+/// Default number of re-reads before an optimistic pool update gives up
+const DEFAULT_OPTIMISTIC_MAX_RETRIES: u32 = 5;
+
+/// Default minimum spacing between stored price observations for a pool
+const DEFAULT_PRICE_OBSERVATION_INTERVAL_SECS: i64 = 60;
+
+/// How concurrent operations on the same pool are serialized
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PoolConcurrency {
+    /// `SELECT ... FOR UPDATE`; operations on a pool queue behind each other
+    Locking,
+    /// Read without locking and only write if the reserves are unchanged,
+    /// re-reading up to `max_retries` times when another operation got there first
+    Optimistic { max_retries: u32 },
+}
+
+impl PoolConcurrency {
+    /// `AMM_POOL_CONCURRENCY` (`lock` or `optimistic`, default `lock`) and
+    /// `AMM_OPTIMISTIC_MAX_RETRIES`
+    pub fn from_env() -> Self {
+        match std::env::var("AMM_POOL_CONCURRENCY").as_deref() {
+            Ok("optimistic") => PoolConcurrency::Optimistic {
+                max_retries: std::env::var("AMM_OPTIMISTIC_MAX_RETRIES")
+                    .ok()
+                    .and_then(|v| v.parse::<u32>().ok())
+                    .unwrap_or(DEFAULT_OPTIMISTIC_MAX_RETRIES),
+            },
+            _ => PoolConcurrency::Locking,
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct AmmService {
+    db: PgPool,
+    concurrency: PoolConcurrency,
+    /// Minimum seconds between snapshots in `pool_price_observations`
+    observation_interval_secs: i64,
+    websocket_service: Option<WebSocketService>,
+}
+
+impl AmmService {
+    pub fn new(db: PgPool) -> Self {
+        let observation_interval_secs = std::env::var("AMM_PRICE_OBSERVATION_INTERVAL_SECS")
+            .ok()
+            .and_then(|v| v.parse::<i64>().ok())
+            .filter(|v| *v > 0)
+            .unwrap_or(DEFAULT_PRICE_OBSERVATION_INTERVAL_SECS);
+
+        Self {
+            db,
+            concurrency: PoolConcurrency::from_env(),
+            observation_interval_secs,
+            websocket_service: None,
+        }
+    }
+
+    /// Set the WebSocket service for broadcasting swap events
+    pub fn with_websocket(mut self, ws_service: WebSocketService) -> Self {
+        self.websocket_service = Some(ws_service);
+        self
+    }
+
+    pub fn with_concurrency(mut self, concurrency: PoolConcurrency) -> Self {
+        self.concurrency = concurrency;
+        self
+    }
+
+    /// Get a liquidity pool by ID
+    pub async fn get_pool(&self, pool_id: Uuid) -> Result<LiquidityPool, ApiError> {
+        sqlx::query_as::<_, LiquidityPool>(
+            r#"
+            SELECT id, name, token_a, token_b, reserve_a, reserve_b, total_supply, fee_rate, created_at, updated_at
+            FROM liquidity_pools
+            WHERE id = $1
+            "#,
+        )
+        .bind(pool_id)
+        .fetch_optional(&self.db)
+        .await
+        .map_err(ApiError::Database)?
+        .ok_or_else(|| ApiError::NotFound("Liquidity pool not found".to_string()))
+    }
+
+    /// List all available liquidity pools
+    pub async fn list_pools(&self) -> Result<Vec<LiquidityPool>, ApiError> {
+        sqlx::query_as::<_, LiquidityPool>(
+            r#"
+            SELECT id, name, token_a, token_b, reserve_a, reserve_b, total_supply, fee_rate, created_at, updated_at
+            FROM liquidity_pools
+            ORDER BY name ASC
+            "#,
+        )
+        .fetch_all(&self.db)
+        .await
+        .map_err(ApiError::Database)
+    }
+
+    /// Create a new liquidity pool
+    pub async fn create_pool(&self, request: CreatePoolRequest) -> Result<LiquidityPool, ApiError> {
+        let pool_id = Uuid::new_v4();
+        let name = format!("{}-{}", request.token_a, request.token_b);
+
+        sqlx::query_as::<_, LiquidityPool>(
+            r#"
+            INSERT INTO liquidity_pools (
+                id, name, token_a, token_b, reserve_a, reserve_b, total_supply, fee_rate, created_at, updated_at
+            )
+            VALUES ($1, $2, $3, $4, 0, 0, 0, $5, NOW(), NOW())
+            RETURNING id, name, token_a, token_b, reserve_a, reserve_b, total_supply, fee_rate, created_at, updated_at
+            "#,
+        )
+        .bind(pool_id)
+        .bind(name)
+        .bind(request.token_a)
+        .bind(request.token_b)
+        .bind(request.fee_rate)
+        .fetch_one(&self.db)
+        .await
+        .map_err(|e| {
+            if e.to_string().contains("unique constraint") {
+                ApiError::BadRequest("Pool already exists for these tokens".to_string())
+            } else {
+                ApiError::Database(e)
+            }
+        })
+    }
+
+    /// Add liquidity to a pool and credit the minted shares to `user_id`
+    pub async fn add_liquidity(
+        &self,
+        user_id: Uuid,
+        request: AddLiquidityRequest,
+    ) -> Result<LiquidityOperationResponse, ApiError> {
+        retry_on_conflict(self.concurrency, request.pool_id, |lock| {
+            self.try_add_liquidity(user_id, &request, lock)
+        })
+        .await
+    }
+
+    /// One attempt at [`Self::add_liquidity`]; `None` if the pool changed underneath
+    async fn try_add_liquidity(
+        &self,
+        user_id: Uuid,
+        request: &AddLiquidityRequest,
+        lock: bool,
+    ) -> Result<Option<LiquidityOperationResponse>, ApiError> {
+        let mut tx = self.db.begin().await.map_err(ApiError::Database)?;
+
+        let pool = Self::read_pool(&mut tx, request.pool_id, lock).await?;
+
+        // Calculate shares to mint
+        let shares = if pool.total_supply == Decimal::ZERO {
+            // Initial liquidity: sqrt(a * b)
+            let product = request.amount_a * request.amount_b;
+            product.sqrt().unwrap_or(Decimal::ZERO)
+        } else {
+            // Subsequent liquidity: min(a * supply / reserve_a, b * supply / reserve_b)
+            let share_a = (request.amount_a * pool.total_supply) / pool.reserve_a;
+            let share_b = (request.amount_b * pool.total_supply) / pool.reserve_b;
+            share_a.min(share_b)
+        };
+
+        if let Some(min_shares) = request.min_shares {
+            if shares < min_shares {
+                return Err(ApiError::BadRequest(format!(
+                    "Slippage tolerance exceeded. Shares {} < min {}",
+                    shares, min_shares
+                )));
+            }
+        }
+
+        if shares <= Decimal::ZERO {
+            return Err(ApiError::BadRequest(
+                "Insufficient liquidity added".to_string(),
+            ));
+        }
+
+        // Update pool
+        let new_reserve_a = pool.reserve_a + request.amount_a;
+        let new_reserve_b = pool.reserve_b + request.amount_b;
+        let new_total_supply = pool.total_supply + shares;
+
+        let update = PoolUpdate {
+            reserve_a: new_reserve_a,
+            reserve_b: new_reserve_b,
+            total_supply: new_total_supply,
+            fee_growth_a: Decimal::ZERO,
+            fee_growth_b: Decimal::ZERO,
+        };
+        if !Self::write_pool(&mut tx, &pool, &update, lock).await? {
+            return Ok(None);
+        }
+
+        // Settle fees earned by existing shares before the balance changes
+        self.checkpoint_position_fees(&mut tx, request.pool_id, user_id)
+            .await?;
+
+        // Credit the provider's position; new positions start at the current accumulator
+        sqlx::query(
+            r#"
+            INSERT INTO lp_positions (
+                pool_id, user_id, shares, fee_checkpoint_a, fee_checkpoint_b, created_at, updated_at
+            )
+            SELECT $1, $2, $3, fee_growth_a, fee_growth_b, NOW(), NOW()
+            FROM liquidity_pools WHERE id = $1
+            ON CONFLICT (pool_id, user_id)
+            DO UPDATE SET shares = lp_positions.shares + EXCLUDED.shares, updated_at = NOW()
+            "#,
+        )
+        .bind(request.pool_id)
+        .bind(user_id)
+        .bind(shares)
+        .execute(&mut *tx)
+        .await
+        .map_err(ApiError::Database)?;
+
+        tx.commit().await.map_err(ApiError::Database)?;
+
+        info!(
+            "Liquidity added to pool {} by user {}: {} shares",
+            request.pool_id, user_id, shares
+        );
+
+        Ok(Some(LiquidityOperationResponse {
+            pool_id: request.pool_id,
+            shares,
+            amount_a: request.amount_a,
+            amount_b: request.amount_b,
+            total_supply: new_total_supply,
+        }))
+    }
+
+    /// Remove liquidity from a pool, burning shares held by `user_id`
+    pub async fn remove_liquidity(
+        &self,
+        user_id: Uuid,
+        request: RemoveLiquidityRequest,
+    ) -> Result<LiquidityOperationResponse, ApiError> {
+        retry_on_conflict(self.concurrency, request.pool_id, |lock| {
+            self.try_remove_liquidity(user_id, &request, lock)
+        })
+        .await
+    }
+
+    /// One attempt at [`Self::remove_liquidity`]; `None` if the pool changed underneath
+    async fn try_remove_liquidity(
+        &self,
+        user_id: Uuid,
+        request: &RemoveLiquidityRequest,
+        lock: bool,
+    ) -> Result<Option<LiquidityOperationResponse>, ApiError> {
+        let mut tx = self.db.begin().await.map_err(ApiError::Database)?;
+
+        let pool = Self::read_pool(&mut tx, request.pool_id, lock).await?;
+
+        if request.shares <= Decimal::ZERO || request.shares > pool.total_supply {
+            return Err(ApiError::BadRequest("Invalid share amount".to_string()));
+        }
+
+        // Lock the provider's position; they may only burn shares they own
+        let held_shares: Decimal = sqlx::query_scalar(
+            r#"
+            SELECT shares FROM lp_positions
+            WHERE pool_id = $1 AND user_id = $2
+            FOR UPDATE
+            "#,
+        )
+        .bind(request.pool_id)
+        .bind(user_id)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(ApiError::Database)?
+        .unwrap_or(Decimal::ZERO);
+
+        if request.shares > held_shares {
+            return Err(ApiError::BadRequest(format!(
+                "Insufficient LP shares. Requested {} > held {}",
+                request.shares, held_shares
+            )));
+        }
+
+        // Calculate amounts to return
+        let amount_a = (request.shares * pool.reserve_a) / pool.total_supply;
+        let amount_b = (request.shares * pool.reserve_b) / pool.total_supply;
+
+        if let Some(min_a) = request.min_amount_a {
+            if amount_a < min_a {
+                return Err(ApiError::BadRequest(format!(
+                    "Slippage tolerance exceeded. Amount A {} < min {}",
+                    amount_a, min_a
+                )));
+            }
+        }
+
+        if let Some(min_b) = request.min_amount_b {
+            if amount_b < min_b {
+                return Err(ApiError::BadRequest(format!(
+                    "Slippage tolerance exceeded. Amount B {} < min {}",
+                    amount_b, min_b
+                )));
+            }
+        }
+
+        // Update pool
+        let new_reserve_a = pool.reserve_a - amount_a;
+        let new_reserve_b = pool.reserve_b - amount_b;
+        let new_total_supply = pool.total_supply - request.shares;
+
+        let update = PoolUpdate {
+            reserve_a: new_reserve_a,
+            reserve_b: new_reserve_b,
+            total_supply: new_total_supply,
+            fee_growth_a: Decimal::ZERO,
+            fee_growth_b: Decimal::ZERO,
+        };
+        if !Self::write_pool(&mut tx, &pool, &update, lock).await? {
+            return Ok(None);
+        }
+
+        // Settle fees earned by the burned shares before the balance changes
+        self.checkpoint_position_fees(&mut tx, request.pool_id, user_id)
+            .await?;
+
+        sqlx::query(
+            r#"
+            UPDATE lp_positions
+            SET shares = shares - $1, updated_at = NOW()
+            WHERE pool_id = $2 AND user_id = $3
+            "#,
+        )
+        .bind(request.shares)
+        .bind(request.pool_id)
+        .bind(user_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(ApiError::Database)?;
+
+        tx.commit().await.map_err(ApiError::Database)?;
+
+        info!(
+            "Liquidity removed from pool {} by user {}: {} shares",
+            request.pool_id, user_id, request.shares
+        );
+
+        Ok(Some(LiquidityOperationResponse {
+            pool_id: request.pool_id,
+            shares: request.shares,
+            amount_a,
+            amount_b,
+            total_supply: new_total_supply,
+        }))
+    }
+
+    /// Get a user's LP positions with the reserves their shares currently represent
+    pub async fn get_user_lp_positions(&self, user_id: Uuid) -> Result<Vec<LpPosition>, ApiError> {
+        sqlx::query_as::<_, LpPosition>(
+            r#"
+            SELECT
+                p.pool_id,
+                lp.name as pool_name,
+                lp.token_a,
+                lp.token_b,
+                p.shares,
+                lp.total_supply as pool_total_supply,
+                CASE WHEN lp.total_supply > 0 THEN p.shares * lp.reserve_a / lp.total_supply ELSE 0 END as underlying_a,
+                CASE WHEN lp.total_supply > 0 THEN p.shares * lp.reserve_b / lp.total_supply ELSE 0 END as underlying_b,
+                p.updated_at
+            FROM lp_positions p
+            JOIN liquidity_pools lp ON lp.id = p.pool_id
+            WHERE p.user_id = $1 AND p.shares > 0
+            ORDER BY lp.name ASC
+            "#,
+        )
+        .bind(user_id)
+        .fetch_all(&self.db)
+        .await
+        .map_err(ApiError::Database)
+    }
+
+    /// Fees accrued to a user's position in a pool that have not been claimed yet
+    pub async fn get_accrued_fees(
+        &self,
+        user_id: Uuid,
+        pool_id: Uuid,
+    ) -> Result<AccruedFees, ApiError> {
+        let row = sqlx::query_as::<_, (String, String, Decimal, Decimal, Decimal, Decimal, Decimal, Decimal, Decimal)>(
+            r#"
+            SELECT lp.token_a, lp.token_b, p.shares,
+                   lp.fee_growth_a, lp.fee_growth_b,
+                   p.fee_checkpoint_a, p.fee_checkpoint_b,
+                   p.fees_owed_a, p.fees_owed_b
+            FROM lp_positions p
+            JOIN liquidity_pools lp ON lp.id = p.pool_id
+            WHERE p.pool_id = $1 AND p.user_id = $2
+            "#,
+        )
+        .bind(pool_id)
+        .bind(user_id)
+        .fetch_optional(&self.db)
+        .await
+        .map_err(ApiError::Database)?
+        .ok_or_else(|| ApiError::NotFound("LP position not found".to_string()))?;
+
+        let (token_a, token_b, shares, growth_a, growth_b, checkpoint_a, checkpoint_b, owed_a, owed_b) = row;
+
+        Ok(AccruedFees {
+            pool_id,
+            user_id,
+            token_a,
+            token_b,
+            amount_a: owed_a + Self::accrued_fee(shares, growth_a, checkpoint_a),
+            amount_b: owed_b + Self::accrued_fee(shares, growth_b, checkpoint_b),
+        })
+    }
+
+    /// Claim all fees accrued to a user's position and record the payout
+    #[instrument(skip(self))]
+    pub async fn claim_fees(&self, user_id: Uuid, pool_id: Uuid) -> Result<LpFeeClaim, ApiError> {
+        let mut tx = self.db.begin().await.map_err(ApiError::Database)?;
+
+        // Lock the pool so no swap moves the accumulator mid-claim
+        sqlx::query("SELECT id FROM liquidity_pools WHERE id = $1 FOR UPDATE")
+            .bind(pool_id)
+            .fetch_optional(&mut *tx)
+            .await
+            .map_err(ApiError::Database)?
+            .ok_or_else(|| ApiError::NotFound("Liquidity pool not found".to_string()))?;
+
+        let (amount_a, amount_b) = self
+            .checkpoint_position_fees(&mut tx, pool_id, user_id)
+            .await?;
+
+        if amount_a <= Decimal::ZERO && amount_b <= Decimal::ZERO {
+            return Err(ApiError::BadRequest("No fees to claim".to_string()));
+        }
+
+        sqlx::query(
+            r#"
+            UPDATE lp_positions
+            SET fees_owed_a = 0, fees_owed_b = 0, updated_at = NOW()
+            WHERE pool_id = $1 AND user_id = $2
+            "#,
+        )
+        .bind(pool_id)
+        .bind(user_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(ApiError::Database)?;
+
+        let claim = sqlx::query_as::<_, LpFeeClaim>(
+            r#"
+            INSERT INTO lp_fee_claims (id, pool_id, user_id, amount_a, amount_b, claimed_at)
+            VALUES ($1, $2, $3, $4, $5, NOW())
+            RETURNING id, pool_id, user_id, amount_a, amount_b, claimed_at
+            "#,
+        )
+        .bind(Uuid::new_v4())
+        .bind(pool_id)
+        .bind(user_id)
+        .bind(amount_a)
+        .bind(amount_b)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(ApiError::Database)?;
+
+        tx.commit().await.map_err(ApiError::Database)?;
+
+        info!(
+            "LP fees claimed from pool {} by user {}: {} / {}",
+            pool_id, user_id, amount_a, amount_b
+        );
+
+        Ok(claim)
+    }
+
+    /// Read a pool inside `tx`, taking the row lock when `lock` is set
+    async fn read_pool(
+        tx: &mut Transaction<'_, Postgres>,
+        pool_id: Uuid,
+        lock: bool,
+    ) -> Result<LiquidityPool, ApiError> {
+        let query = format!(
+            "SELECT id, name, token_a, token_b, reserve_a, reserve_b, total_supply, fee_rate, created_at, updated_at \
+             FROM liquidity_pools WHERE id = $1{}",
+            if lock { " FOR UPDATE" } else { "" }
+        );
+
+        sqlx::query_as::<_, LiquidityPool>(&query)
+            .bind(pool_id)
+            .fetch_optional(&mut **tx)
+            .await
+            .map_err(ApiError::Database)?
+            .ok_or_else(|| ApiError::NotFound("Liquidity pool not found".to_string()))
+    }
+
+    /// Write new pool state read as `pool`
+    ///
+    /// Without the row lock the update only applies if reserves and supply
+    /// still match what was read; `false` means another operation won and the
+    /// caller should start over. The update takes the row lock either way, so
+    /// the fee accumulator is stable for the rest of the transaction.
+    ///
+    /// The price accumulators are advanced by the price that held since the
+    /// last update (the reserves as read, before this change) times the
+    /// seconds elapsed.
+    async fn write_pool(
+        tx: &mut Transaction<'_, Postgres>,
+        pool: &LiquidityPool,
+        update: &PoolUpdate,
+        locked: bool,
+    ) -> Result<bool, ApiError> {
+        let result = sqlx::query(
+            r#"
+            UPDATE liquidity_pools
+            SET reserve_a = $1, reserve_b = $2, total_supply = $3,
+                fee_growth_a = fee_growth_a + $4, fee_growth_b = fee_growth_b + $5,
+                price_cumulative_a = price_cumulative_a + CASE
+                    WHEN reserve_a > 0 AND reserve_b > 0
+                    THEN reserve_b / reserve_a * EXTRACT(EPOCH FROM NOW() - price_last_updated)
+                    ELSE 0 END,
+                price_cumulative_b = price_cumulative_b + CASE
+                    WHEN reserve_a > 0 AND reserve_b > 0
+                    THEN reserve_a / reserve_b * EXTRACT(EPOCH FROM NOW() - price_last_updated)
+                    ELSE 0 END,
+                price_last_updated = NOW(),
+                updated_at = NOW()
+            WHERE id = $6
+              AND ($7 OR (reserve_a = $8 AND reserve_b = $9 AND total_supply = $10))
+            "#,
+        )
+        .bind(update.reserve_a)
+        .bind(update.reserve_b)
+        .bind(update.total_supply)
+        .bind(update.fee_growth_a)
+        .bind(update.fee_growth_b)
+        .bind(pool.id)
+        .bind(locked)
+        .bind(pool.reserve_a)
+        .bind(pool.reserve_b)
+        .bind(pool.total_supply)
+        .execute(&mut **tx)
+        .await
+        .map_err(ApiError::Database)?;
+
+        Ok(result.rows_affected() == 1)
+    }
+
+    /// Roll fees earned since the position's last checkpoint into `fees_owed_*`
+    /// and move the checkpoint to the pool's current accumulator.
+    ///
+    /// Must be called with the pool row locked. Returns the total owed amounts.
+    async fn checkpoint_position_fees(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        pool_id: Uuid,
+        user_id: Uuid,
+    ) -> Result<(Decimal, Decimal), ApiError> {
+        let row = sqlx::query_as::<_, (Decimal, Decimal, Decimal, Decimal, Decimal, Decimal, Decimal)>(
+            r#"
+            SELECT p.shares, lp.fee_growth_a, lp.fee_growth_b,
+                   p.fee_checkpoint_a, p.fee_checkpoint_b,
+                   p.fees_owed_a, p.fees_owed_b
+            FROM lp_positions p
+            JOIN liquidity_pools lp ON lp.id = p.pool_id
+            WHERE p.pool_id = $1 AND p.user_id = $2
+            FOR UPDATE OF p
+            "#,
+        )
+        .bind(pool_id)
+        .bind(user_id)
+        .fetch_optional(&mut **tx)
+        .await
+        .map_err(ApiError::Database)?;
+
+        let Some((shares, growth_a, growth_b, checkpoint_a, checkpoint_b, owed_a, owed_b)) = row
+        else {
+            return Ok((Decimal::ZERO, Decimal::ZERO));
+        };
+
+        let owed_a = owed_a + Self::accrued_fee(shares, growth_a, checkpoint_a);
+        let owed_b = owed_b + Self::accrued_fee(shares, growth_b, checkpoint_b);
+
+        sqlx::query(
+            r#"
+            UPDATE lp_positions
+            SET fees_owed_a = $1, fees_owed_b = $2,
+                fee_checkpoint_a = $3, fee_checkpoint_b = $4, updated_at = NOW()
+            WHERE pool_id = $5 AND user_id = $6
+            "#,
+        )
+        .bind(owed_a)
+        .bind(owed_b)
+        .bind(growth_a)
+        .bind(growth_b)
+        .bind(pool_id)
+        .bind(user_id)
+        .execute(&mut **tx)
+        .await
+        .map_err(ApiError::Database)?;
+
+        Ok((owed_a, owed_b))
+    }
+
+    /// Increase in the fee-per-share accumulator from a single swap fee
+    fn fee_growth_delta(fee_amount: Decimal, total_supply: Decimal) -> Decimal {
+        if total_supply <= Decimal::ZERO {
+            return Decimal::ZERO;
+        }
+        fee_amount / total_supply
+    }
+
+    /// Fees earned by `shares` since the accumulator stood at `checkpoint`
+    fn accrued_fee(shares: Decimal, fee_growth: Decimal, checkpoint: Decimal) -> Decimal {
+        (shares * (fee_growth - checkpoint)).round_dp(9)
+    }
+
+    /// Price accumulators advanced by `elapsed_secs` at the spot price of the
+    /// given reserves; an empty side contributes nothing
+    fn accumulate_prices(
+        cumulative: (Decimal, Decimal),
+        reserve_a: Decimal,
+        reserve_b: Decimal,
+        elapsed_secs: i64,
+    ) -> (Decimal, Decimal) {
+        if reserve_a <= Decimal::ZERO || reserve_b <= Decimal::ZERO || elapsed_secs <= 0 {
+            return cumulative;
+        }
+        let elapsed = Decimal::from(elapsed_secs);
+        (
+            cumulative.0 + reserve_b / reserve_a * elapsed,
+            cumulative.1 + reserve_a / reserve_b * elapsed,
+        )
+    }
+
+    /// Average price between two accumulator readings `elapsed_secs` apart
+    fn time_weighted_price(start: Decimal, end: Decimal, elapsed_secs: i64) -> Option<Decimal> {
+        (elapsed_secs > 0).then(|| ((end - start) / Decimal::from(elapsed_secs)).round_dp(9))
+    }
+
+    /// Store an accumulator snapshot unless one was taken within the
+    /// observation interval. Call after [`Self::write_pool`] in the same
+    /// transaction so the snapshot matches the new `price_last_updated`.
+    async fn record_price_observation(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        pool_id: Uuid,
+    ) -> Result<(), ApiError> {
+        sqlx::query(
+            r#"
+            INSERT INTO pool_price_observations (pool_id, price_cumulative_a, price_cumulative_b, observed_at)
+            SELECT id, price_cumulative_a, price_cumulative_b, price_last_updated
+            FROM liquidity_pools
+            WHERE id = $1
+              AND NOT EXISTS (
+                  SELECT 1 FROM pool_price_observations
+                  WHERE pool_id = $1 AND observed_at > NOW() - make_interval(secs => $2)
+              )
+            "#,
+        )
+        .bind(pool_id)
+        .bind(self.observation_interval_secs as f64)
+        .execute(&mut **tx)
+        .await
+        .map_err(ApiError::Database)?;
+
+        Ok(())
+    }
+
+    /// Time-weighted average prices over the trailing `window`
+    ///
+    /// Starts from the newest snapshot at least `window` old, or the oldest
+    /// snapshot when the pool is younger than that; the returned `from` and
+    /// `window_secs` report the span actually covered.
+    pub async fn get_twap(&self, pool_id: Uuid, window: Duration) -> Result<PoolTwap, ApiError> {
+        let window_secs = i64::try_from(window.as_secs())
+            .map_err(|_| ApiError::BadRequest("TWAP window is too large".to_string()))?;
+
+        let current = sqlx::query_as::<_, PoolPriceState>(
+            r#"
+            SELECT token_a, token_b, reserve_a, reserve_b,
+                   price_cumulative_a, price_cumulative_b, price_last_updated, NOW() AS now
+            FROM liquidity_pools
+            WHERE id = $1
+            "#,
+        )
+        .bind(pool_id)
+        .fetch_optional(&self.db)
+        .await
+        .map_err(ApiError::Database)?
+        .ok_or_else(|| ApiError::NotFound("Liquidity pool not found".to_string()))?;
+
+        let start = sqlx::query_as::<_, PoolPriceObservation>(
+            r#"
+            SELECT price_cumulative_a, price_cumulative_b, observed_at
+            FROM pool_price_observations
+            WHERE pool_id = $1
+            ORDER BY (observed_at <= $2) DESC,
+                     CASE WHEN observed_at <= $2 THEN observed_at END DESC,
+                     observed_at ASC
+            LIMIT 1
+            "#,
+        )
+        .bind(pool_id)
+        .bind(current.now - chrono::Duration::seconds(window_secs))
+        .fetch_optional(&self.db)
+        .await
+        .map_err(ApiError::Database)?
+        .ok_or_else(|| ApiError::NotFound(format!("No price observations for pool {} yet", pool_id)))?;
+
+        let (end_a, end_b) = Self::accumulate_prices(
+            (current.price_cumulative_a, current.price_cumulative_b),
+            current.reserve_a,
+            current.reserve_b,
+            (current.now - current.price_last_updated).num_seconds(),
+        );
+        let covered_secs = (current.now - start.observed_at).num_seconds();
+
+        let (Some(price_a_in_b), Some(price_b_in_a)) = (
+            Self::time_weighted_price(start.price_cumulative_a, end_a, covered_secs),
+            Self::time_weighted_price(start.price_cumulative_b, end_b, covered_secs),
+        ) else {
+            return Err(ApiError::BadRequest(format!(
+                "Not enough price history for pool {} yet",
+                pool_id
+            )));
+        };
+
+        Ok(PoolTwap {
+            pool_id,
+            token_a: current.token_a,
+            token_b: current.token_b,
+            price_a_in_b,
+            price_b_in_a,
+            window_secs: covered_secs,
+            from: start.observed_at,
+            to: current.now,
+        })
+    }
+
+    /// Calculate swap output based on Constant Product Formula (x * y = k)
+    pub async fn calculate_swap_output(
+        &self,
+        pool_id: Uuid,
+        input_token: &str,
+        input_amount: Decimal,
+    ) -> Result<SwapQuote, ApiError> {
+        let pool = self.get_pool(pool_id).await?;
+
+        pool.calculate_swap(input_token, input_amount)
+            .map_err(|e| ApiError::BadRequest(e.to_string()))
+    }
+
+    /// Execute a swap transaction
+    #[instrument(skip(self))]
+    pub async fn execute_swap(
+        &self,
+        user_id: Uuid,
+        pool_id: Uuid,
+        input_token: String,
+        input_amount: Decimal,
+        min_output_amount: Decimal,
+    ) -> Result<SwapTransaction, ApiError> {
+        retry_on_conflict(self.concurrency, pool_id, |lock| {
+            self.try_execute_swap(user_id, pool_id, &input_token, input_amount, min_output_amount, lock)
+        })
+        .await
+    }
+
+    /// One attempt at [`Self::execute_swap`]; `None` if the pool changed underneath
+    async fn try_execute_swap(
+        &self,
+        user_id: Uuid,
+        pool_id: Uuid,
+        input_token: &str,
+        input_amount: Decimal,
+        min_output_amount: Decimal,
+        lock: bool,
+    ) -> Result<Option<SwapTransaction>, ApiError> {
+        let mut tx = self.db.begin().await.map_err(ApiError::Database)?;
+
+        let pool = Self::read_pool(&mut tx, pool_id, lock).await?;
+
+        if input_amount <= Decimal::ZERO {
+            return Err(ApiError::BadRequest(
+                "Input amount must be positive".to_string(),
+            ));
+        }
+
+        // Calculate swap using model logic
+        let quote = pool
+            .calculate_swap(input_token, input_amount)
+            .map_err(|e| ApiError::BadRequest(e.to_string()))?;
+
+        // Slippage check
+        if quote.output_amount < min_output_amount {
+            return Err(ApiError::BadRequest(format!(
+                "Slippage tolerance exceeded. Output {} < min {}",
+                quote.output_amount, min_output_amount
+            )));
+        }
+
+        // Determine which reserve to update. The fee is held outside the reserves
+        // and credited to LPs through the fee-per-share accumulator.
+        let net_input = input_amount - quote.fee_amount;
+        let growth_delta = Self::fee_growth_delta(quote.fee_amount, pool.total_supply);
+        let (new_reserve_a, new_reserve_b, growth_a, growth_b) = if input_token == pool.token_a {
+            (
+                pool.reserve_a + net_input,
+                pool.reserve_b - quote.output_amount,
+                growth_delta,
+                Decimal::ZERO,
+            )
+        } else {
+            (
+                pool.reserve_a - quote.output_amount,
+                pool.reserve_b + net_input,
+                Decimal::ZERO,
+                growth_delta,
+            )
+        };
+
+        // Update pool in DB
+        // Note: total_supply doesn't change for swaps
+        let update = PoolUpdate {
+            reserve_a: new_reserve_a,
+            reserve_b: new_reserve_b,
+            total_supply: pool.total_supply,
+            fee_growth_a: growth_a,
+            fee_growth_b: growth_b,
+        };
+        if !Self::write_pool(&mut tx, &pool, &update, lock).await? {
+            return Ok(None);
+        }
+        self.record_price_observation(&mut tx, pool_id).await?;
+        let (input_reserve, output_reserve) = if input_token == pool.token_a {
+            (update.reserve_a, update.reserve_b)
+        } else {
+            (update.reserve_b, update.reserve_a)
+        };
+
+        // Record transaction
+        let swap_tx = sqlx::query_as::<_, SwapTransaction>(
+            r#"
+            INSERT INTO swap_transactions (
+                id, pool_id, user_id, input_token, input_amount, output_token, output_amount, fee_amount,
+                status, slippage_tolerance, tx_hash, created_at
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, 'completed', NULL, NULL, NOW())
+            RETURNING id, pool_id, user_id, input_token, input_amount, output_token, output_amount, fee_amount,
+                      status, slippage_tolerance, tx_hash, created_at
+            "#,
+        )
+        .bind(Uuid::new_v4())
+        .bind(pool_id)
+        .bind(user_id)
+        .bind(input_token)
+        .bind(input_amount)
+        .bind(if input_token == pool.token_a {
+            pool.token_b
+        } else {
+            pool.token_a
+        })
+        .bind(quote.output_amount)
+        .bind(quote.fee_amount)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(ApiError::Database)?;
+
+        // Commit transaction
+        tx.commit().await.map_err(ApiError::Database)?;
+
+        info!(
+            "Swap executed successfully: {} -> {}",
+            swap_tx.id, quote.output_amount
+        );
+
+        if let Some(ws_service) = &self.websocket_service {
+            ws_service
+                .broadcast_swap_executed(
+                    pool_id,
+                    &swap_tx.input_token,
+                    &swap_tx.output_token,
+                    swap_tx.input_amount,
+                    swap_tx.output_amount,
+                    input_reserve,
+                    output_reserve,
+                )
+                .await;
+        }
+
+        Ok(Some(swap_tx))
+    }
+
+    /// Get user swap history
+    pub async fn get_user_swap_history(
+        &self,
+        user_id: Uuid,
+    ) -> Result<Vec<SwapTransaction>, ApiError> {
+        sqlx::query_as::<_, SwapTransaction>(
+            r#"
+            SELECT id, pool_id, user_id, input_token, input_amount, output_token, output_amount, fee_amount, 
+                   status, slippage_tolerance, tx_hash, created_at
+            FROM swap_transactions
+            WHERE user_id = $1
+            ORDER BY created_at DESC
+            LIMIT 50
+            "#,
+        )
+        .bind(user_id)
+        .fetch_all(&self.db)
+        .await
+        .map_err(ApiError::Database)
+    }
+}
+
+/// Pool reserves and price accumulators as of `now` (database clock)
+#[derive(sqlx::FromRow)]
+struct PoolPriceState {
+    token_a: String,
+    token_b: String,
+    reserve_a: Decimal,
+    reserve_b: Decimal,
+    price_cumulative_a: Decimal,
+    price_cumulative_b: Decimal,
+    price_last_updated: DateTime<Utc>,
+    now: DateTime<Utc>,
+}
+
+/// New pool state computed by an operation; fee growth values are increments
+struct PoolUpdate {
+    reserve_a: Decimal,
+    reserve_b: Decimal,
+    total_supply: Decimal,
+    fee_growth_a: Decimal,
+    fee_growth_b: Decimal,
+}
+
+/// Run a pool operation under the configured concurrency mode
+///
+/// `attempt(lock)` returns `Ok(None)` when its guarded write lost a race. With
+/// locking that cannot happen; optimistically the attempt is repeated until
+/// it wins or the retry budget is spent.
+async fn retry_on_conflict<T, F, Fut>(
+    concurrency: PoolConcurrency,
+    pool_id: Uuid,
+    mut attempt: F,
+) -> Result<T, ApiError>
+where
+    F: FnMut(bool) -> Fut,
+    Fut: Future<Output = Result<Option<T>, ApiError>>,
+{
+    match concurrency {
+        PoolConcurrency::Locking => attempt(true).await?.ok_or_else(|| {
+            ApiError::Internal(format!("Locked update of pool {} matched no rows", pool_id))
+        }),
+        PoolConcurrency::Optimistic { max_retries } => {
+            for retry in 0..=max_retries {
+                if let Some(result) = attempt(false).await? {
+                    return Ok(result);
+                }
+                debug!("Pool {} changed during update, retry {}/{}", pool_id, retry + 1, max_retries);
+            }
+            Err(ApiError::Conflict(format!(
+                "Pool {} is busy, please retry",
+                pool_id
+            )))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    #[test]
+    fn test_fees_split_proportionally_between_lps() {
+        // LP1 holds 300 shares, LP2 holds 100 shares
+        let lp1_shares = Decimal::from(300);
+        let lp2_shares = Decimal::from(100);
+        let total_supply = lp1_shares + lp2_shares;
+
+        let mut fee_growth = Decimal::ZERO;
+        let checkpoint = fee_growth;
+        let fees = ["1.2", "0.4", "2.0", "0.004"];
+        let mut total_fees = Decimal::ZERO;
+        for fee in fees {
+            let fee = Decimal::from_str(fee).unwrap();
+            fee_growth += AmmService::fee_growth_delta(fee, total_supply);
+            total_fees += fee;
+        }
+
+        let lp1_fees = AmmService::accrued_fee(lp1_shares, fee_growth, checkpoint);
+        let lp2_fees = AmmService::accrued_fee(lp2_shares, fee_growth, checkpoint);
+
+        assert_eq!(lp1_fees, Decimal::from_str("2.703").unwrap());
+        assert_eq!(lp2_fees, Decimal::from_str("0.901").unwrap());
+        assert_eq!(lp1_fees, lp2_fees * Decimal::from(3));
+        assert_eq!(lp1_fees + lp2_fees, total_fees);
+    }
+
+    #[test]
+    fn test_late_lp_does_not_earn_earlier_fees() {
+        let early_shares = Decimal::from(100);
+
+        // First swap while only the early LP is in the pool
+        let mut fee_growth = AmmService::fee_growth_delta(Decimal::from(1), early_shares);
+
+        // A second LP joins with 100 shares, checkpointed at the current accumulator
+        let late_shares = Decimal::from(100);
+        let late_checkpoint = fee_growth;
+        fee_growth += AmmService::fee_growth_delta(Decimal::from(2), early_shares + late_shares);
+
+        let early = AmmService::accrued_fee(early_shares, fee_growth, Decimal::ZERO);
+        let late = AmmService::accrued_fee(late_shares, fee_growth, late_checkpoint);
+
+        assert_eq!(early, Decimal::from(2));
+        assert_eq!(late, Decimal::from(1));
+    }
+
+    /// In-memory stand-in for the guarded `UPDATE ... WHERE reserve_a = $old`
+    #[derive(Default)]
+    struct CasPool {
+        reserves: std::sync::Mutex<(Decimal, Decimal)>,
+    }
+
+    impl CasPool {
+        fn compare_and_set(&self, old: (Decimal, Decimal), new: (Decimal, Decimal)) -> bool {
+            let mut reserves = self.reserves.lock().unwrap();
+            if *reserves != old {
+                return false;
+            }
+            *reserves = new;
+            true
+        }
+    }
+
+    #[tokio::test]
+    async fn test_optimistic_swaps_do_not_lose_updates() {
+        let pool = CasPool::default();
+        *pool.reserves.lock().unwrap() = (Decimal::from(1000), Decimal::from(1000));
+        let swap_in = Decimal::from(10);
+        let pool = &pool;
+
+        let swap = || async move {
+            retry_on_conflict(
+                PoolConcurrency::Optimistic { max_retries: 32 },
+                Uuid::nil(),
+                move |_lock| async move {
+                    let read = *pool.reserves.lock().unwrap();
+                    // Let the other swaps read the same reserves
+                    tokio::task::yield_now().await;
+                    let (a, b) = read;
+                    let output = b - (a * b) / (a + swap_in);
+                    Ok(pool
+                        .compare_and_set(read, (a + swap_in, b - output))
+                        .then_some(output))
+                },
+            )
+            .await
+        };
+
+        let outputs = futures::future::join_all((0..16).map(|_| swap())).await;
+        let total_output: Decimal = outputs.into_iter().map(|o| o.unwrap()).sum();
+
+        let (a, b) = *pool.reserves.lock().unwrap();
+        assert_eq!(a, Decimal::from(1000) + swap_in * Decimal::from(16));
+        // Every token paid out left the reserve exactly once
+        assert_eq!(b + total_output, Decimal::from(1000));
+    }
+
+    #[tokio::test]
+    async fn test_optimistic_gives_up_after_retry_budget() {
+        let mut attempts = 0;
+        let result: Result<(), ApiError> = retry_on_conflict(
+            PoolConcurrency::Optimistic { max_retries: 2 },
+            Uuid::nil(),
+            |_lock| {
+                attempts += 1;
+                async { Ok(None) }
+            },
+        )
+        .await;
+
+        assert!(matches!(result, Err(ApiError::Conflict(_))));
+        assert_eq!(attempts, 3);
+    }
+
+    #[test]
+    fn test_swap_quote_keeps_constant_product() {
+        let now = Utc::now();
+        let pool = LiquidityPool {
+            id: Uuid::nil(),
+            name: "KWH-USDC".to_string(),
+            token_a: "KWH".to_string(),
+            token_b: "USDC".to_string(),
+            reserve_a: Decimal::from(1000),
+            reserve_b: Decimal::from(4000),
+            total_supply: Decimal::from(2000),
+            fee_rate: Decimal::from_str("0.003").unwrap(),
+            created_at: now,
+            updated_at: now,
+        };
+
+        let quote = pool.calculate_swap("KWH", Decimal::from(100)).unwrap();
+        assert_eq!(quote.output_token, "USDC");
+        assert_eq!(quote.fee_amount, Decimal::from_str("0.3").unwrap());
+        // 4000 * 99.7 / 1099.7, rounded down
+        assert_eq!(quote.output_amount, Decimal::from_str("362.644357552").unwrap());
+        let k = pool.reserve_a * pool.reserve_b;
+        assert!((pool.reserve_a + Decimal::from(100) - quote.fee_amount) * (pool.reserve_b - quote.output_amount) >= k);
+
+        assert!(pool.calculate_swap("SOL", Decimal::ONE).is_err());
+        assert!(pool.calculate_swap("KWH", Decimal::ZERO).is_err());
+    }
+
+    #[test]
+    fn test_fee_growth_delta_empty_pool() {
+        assert_eq!(
+            AmmService::fee_growth_delta(Decimal::from(5), Decimal::ZERO),
+            Decimal::ZERO
+        );
+    }
+
+    #[test]
+    fn test_twap_weights_prices_by_time() {
+        // 1:2 pool for 300s, then a swap moves it to 1:4 for 100s
+        let start = (Decimal::ZERO, Decimal::ZERO);
+        let after_first = AmmService::accumulate_prices(
+            start,
+            Decimal::from(100),
+            Decimal::from(200),
+            300,
+        );
+        let end = AmmService::accumulate_prices(
+            after_first,
+            Decimal::from(50),
+            Decimal::from(200),
+            100,
+        );
+
+        // (2 * 300 + 4 * 100) / 400
+        assert_eq!(
+            AmmService::time_weighted_price(start.0, end.0, 400),
+            Some(Decimal::from_str("2.5").unwrap())
+        );
+        // (0.5 * 300 + 0.25 * 100) / 400
+        assert_eq!(
+            AmmService::time_weighted_price(start.1, end.1, 400),
+            Some(Decimal::from_str("0.4375").unwrap())
+        );
+    }
+
+    #[test]
+    fn test_twap_edge_cases() {
+        let cumulative = (Decimal::from(7), Decimal::from(3));
+        // Empty pools and zero elapsed time leave the accumulators alone
+        assert_eq!(
+            AmmService::accumulate_prices(cumulative, Decimal::ZERO, Decimal::from(10), 60),
+            cumulative
+        );
+        assert_eq!(
+            AmmService::accumulate_prices(cumulative, Decimal::ONE, Decimal::ONE, 0),
+            cumulative
+        );
+        assert_eq!(
+            AmmService::time_weighted_price(Decimal::ZERO, Decimal::ONE, 0),
+            None
+        );
+    }
+}
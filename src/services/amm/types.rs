@@ -0,0 +1,86 @@
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow, ToSchema)]
+pub struct SwapTransaction {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub pool_id: Uuid,
+    pub input_token: String,
+    #[schema(value_type = String)]
+    pub input_amount: Decimal,
+    pub output_token: String,
+    #[schema(value_type = String)]
+    pub output_amount: Decimal,
+    #[schema(value_type = String)]
+    pub fee_amount: Decimal,
+    #[schema(value_type = Option<String>)]
+    pub slippage_tolerance: Option<Decimal>,
+    pub status: String,
+    pub tx_hash: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// A user's share of a liquidity pool and the reserves it currently represents
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct LpPosition {
+    pub pool_id: Uuid,
+    pub pool_name: String,
+    pub token_a: String,
+    pub token_b: String,
+    pub shares: Decimal,
+    pub pool_total_supply: Decimal,
+    pub underlying_a: Decimal,
+    pub underlying_b: Decimal,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Swap fees accrued to an LP position and not yet claimed
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AccruedFees {
+    pub pool_id: Uuid,
+    pub user_id: Uuid,
+    pub token_a: String,
+    pub token_b: String,
+    pub amount_a: Decimal,
+    pub amount_b: Decimal,
+}
+
+/// A recorded fee payout to a liquidity provider
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct LpFeeClaim {
+    pub id: Uuid,
+    pub pool_id: Uuid,
+    pub user_id: Uuid,
+    pub amount_a: Decimal,
+    pub amount_b: Decimal,
+    pub claimed_at: DateTime<Utc>,
+}
+
+/// Snapshot of a pool's price accumulators
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct PoolPriceObservation {
+    pub price_cumulative_a: Decimal,
+    pub price_cumulative_b: Decimal,
+    pub observed_at: DateTime<Utc>,
+}
+
+/// Time-weighted average prices of a pool over `[from, to]`
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct PoolTwap {
+    pub pool_id: Uuid,
+    pub token_a: String,
+    pub token_b: String,
+    /// Token A priced in token B
+    #[schema(value_type = String)]
+    pub price_a_in_b: Decimal,
+    /// Token B priced in token A
+    #[schema(value_type = String)]
+    pub price_b_in_a: Decimal,
+    pub window_secs: i64,
+    pub from: DateTime<Utc>,
+    pub to: DateTime<Utc>,
+}
@@ -4,7 +4,7 @@ use sqlx::PgPool;
 use uuid::Uuid;
 
 pub mod types;
-pub use types::{AuditEvent, AuditEventRecord};
+pub use types::{AuditEvent, AuditEventFilter, AuditEventRecord};
 
 /// Audit logger service
 #[derive(Debug, Clone)]
@@ -22,6 +22,7 @@ impl AuditLogger {
     pub async fn log(&self, event: AuditEvent) -> Result<(), sqlx::Error> {
         let event_type = event.event_type();
         let user_id = event.user_id();
+        let correlation_id = event.correlation_id();
         let ip_address_str = event.ip_address().map(|s| s.to_string());
         let ip_address = ip_address_str
             .as_deref()
@@ -61,6 +62,7 @@ impl AuditLogger {
             event_type = event_type,
             user_id = ?user_id,
             ip = ?ip_address,
+            correlation_id = ?correlation_id,
             "Audit event logged"
         );
 
@@ -125,6 +127,111 @@ impl AuditLogger {
         Ok(records)
     }
 
+    /// Full lifecycle trail for one settlement, oldest first
+    pub async fn get_settlement_events(
+        &self,
+        settlement_id: Uuid,
+    ) -> Result<Vec<AuditEventRecord>, sqlx::Error> {
+        let records = sqlx::query_as::<_, AuditEventRecord>(
+            r#"
+            SELECT id, activity_type as event_type, user_id, ip_address, metadata as event_data, created_at
+            FROM user_activities
+            WHERE activity_type IN ('settlement_started', 'settlement_completed', 'settlement_failed', 'escrow_finalized')
+              AND metadata->>'settlement_id' = $1
+            ORDER BY created_at ASC
+            "#,
+        )
+        .bind(settlement_id.to_string())
+        .fetch_all(&self.db)
+        .await?;
+
+        Ok(records)
+    }
+
+    /// Audit events carrying a reading correlation id, oldest first
+    pub async fn get_correlation_events(
+        &self,
+        correlation_id: Uuid,
+    ) -> Result<Vec<AuditEventRecord>, sqlx::Error> {
+        let records = sqlx::query_as::<_, AuditEventRecord>(
+            r#"
+            SELECT id, activity_type as event_type, user_id, ip_address, metadata as event_data, created_at
+            FROM user_activities
+            WHERE metadata->>'correlation_id' = $1
+            ORDER BY created_at ASC
+            "#,
+        )
+        .bind(correlation_id.to_string())
+        .fetch_all(&self.db)
+        .await?;
+
+        Ok(records)
+    }
+
+    /// Search the audit log, newest first; returns the page and the total match count
+    pub async fn search_events(
+        &self,
+        filter: &AuditEventFilter,
+        limit: i64,
+        offset: i64,
+    ) -> Result<(Vec<AuditEventRecord>, i64), sqlx::Error> {
+        let (where_clause, bind_count) = filter.where_clause();
+        let entity_id = filter.entity_id.map(|id| id.to_string());
+
+        let count_query = format!("SELECT COUNT(*) FROM user_activities WHERE {}", where_clause);
+        let mut count_sqlx = sqlx::query_scalar::<_, i64>(&count_query);
+        if let Some(user_id) = filter.user_id {
+            count_sqlx = count_sqlx.bind(user_id);
+        }
+        if let Some(event_type) = &filter.event_type {
+            count_sqlx = count_sqlx.bind(event_type);
+        }
+        if let Some(from) = filter.from {
+            count_sqlx = count_sqlx.bind(from);
+        }
+        if let Some(to) = filter.to {
+            count_sqlx = count_sqlx.bind(to);
+        }
+        if let Some(entity_id) = &entity_id {
+            count_sqlx = count_sqlx.bind(entity_id);
+        }
+        let total = count_sqlx.fetch_one(&self.db).await?;
+
+        let query = format!(
+            "SELECT id, activity_type as event_type, user_id, ip_address, metadata as event_data, created_at
+             FROM user_activities
+             WHERE {}
+             ORDER BY created_at DESC, id DESC
+             LIMIT ${} OFFSET ${}",
+            where_clause,
+            bind_count,
+            bind_count + 1
+        );
+        let mut sqlx_query = sqlx::query_as::<_, AuditEventRecord>(&query);
+        if let Some(user_id) = filter.user_id {
+            sqlx_query = sqlx_query.bind(user_id);
+        }
+        if let Some(event_type) = &filter.event_type {
+            sqlx_query = sqlx_query.bind(event_type);
+        }
+        if let Some(from) = filter.from {
+            sqlx_query = sqlx_query.bind(from);
+        }
+        if let Some(to) = filter.to {
+            sqlx_query = sqlx_query.bind(to);
+        }
+        if let Some(entity_id) = &entity_id {
+            sqlx_query = sqlx_query.bind(entity_id);
+        }
+        let records = sqlx_query
+            .bind(limit)
+            .bind(offset)
+            .fetch_all(&self.db)
+            .await?;
+
+        Ok((records, total))
+    }
+
     /// Get recent security events (unauthorized access, failed logins, rate limits)
     pub async fn get_security_events(
         &self,
@@ -221,6 +328,74 @@ mod tests {
         assert_eq!(event.ip_address(), None);
     }
 
+    #[test]
+    fn test_settlement_event_ids() {
+        let settlement_id = Uuid::new_v4();
+        let buyer_id = Uuid::new_v4();
+        let event = AuditEvent::SettlementStarted {
+            settlement_id,
+            buyer_id,
+            seller_id: Uuid::new_v4(),
+            energy_amount: "10".to_string(),
+            total_amount: "1.5".to_string(),
+        };
+        assert_eq!(event.event_type(), "settlement_started");
+        assert_eq!(event.settlement_id(), Some(settlement_id));
+        assert_eq!(event.user_id(), Some(buyer_id));
+
+        let event = AuditEvent::SettlementFailed {
+            settlement_id,
+            reason: "blockhash expired".to_string(),
+            permanent: false,
+        };
+        let json = serde_json::to_value(&event).unwrap();
+        assert_eq!(json["type"], "settlement_failed");
+        assert_eq!(json["settlement_id"], settlement_id.to_string());
+        assert_eq!(event.user_id(), None);
+    }
+
+    #[test]
+    fn test_energy_minted_correlation_id() {
+        let correlation_id = Uuid::new_v4();
+        let event = AuditEvent::EnergyMinted {
+            user_id: Uuid::new_v4(),
+            reading_id: Uuid::new_v4(),
+            correlation_id,
+            meter_serial: "METER-001".to_string(),
+            reading_kwh: "12.5".to_string(),
+            signature: None,
+        };
+        assert_eq!(event.correlation_id(), Some(correlation_id));
+
+        // get_correlation_events matches on this top-level key
+        let json = serde_json::to_value(&event).unwrap();
+        assert_eq!(json["correlation_id"], correlation_id.to_string());
+
+        let event = AuditEvent::EmailVerified {
+            user_id: Uuid::new_v4(),
+        };
+        assert_eq!(event.correlation_id(), None);
+    }
+
+    #[test]
+    fn test_filter_where_clause_numbers_placeholders_in_bind_order() {
+        let (clause, next) = AuditEventFilter::default().where_clause();
+        assert_eq!(clause, "1 = 1");
+        assert_eq!(next, 1);
+
+        let filter = AuditEventFilter {
+            event_type: Some("order_created".to_string()),
+            to: Some(Utc::now()),
+            entity_id: Some(Uuid::new_v4()),
+            ..Default::default()
+        };
+        let (clause, next) = filter.where_clause();
+        assert!(clause.contains("activity_type = $1"));
+        assert!(clause.contains("created_at < $2"));
+        assert!(clause.contains("metadata->>'order_id' = $3 OR metadata->>'settlement_id' = $3"));
+        assert_eq!(next, 4);
+    }
+
     #[test]
     fn test_event_serialization() {
         let event = AuditEvent::OrderCreated {
@@ -44,6 +44,15 @@ pub enum AuditEvent {
     },
     /// Trading order cancelled
     OrderCancelled { user_id: Uuid, order_id: Uuid },
+    /// Open order cancelled by an admin on the owner's behalf
+    OrderForceCancelled {
+        order_id: Uuid,
+        owner_id: Uuid,
+        admin_id: Uuid,
+        reason: String,
+        /// Funds (buy) or energy (sell) returned to the owner
+        refunded: String,
+    },
     /// Trading order matched
     OrderMatched {
         buyer_id: Uuid,
@@ -51,6 +60,50 @@ pub enum AuditEvent {
         order_id: Uuid,
         amount: String,
     },
+    /// Settlement picked up for on-chain execution
+    SettlementStarted {
+        settlement_id: Uuid,
+        buyer_id: Uuid,
+        seller_id: Uuid,
+        energy_amount: String,
+        total_amount: String,
+    },
+    /// Settlement confirmed on-chain
+    SettlementCompleted {
+        settlement_id: Uuid,
+        signature: String,
+    },
+    /// Settlement execution failed; `permanent` failures are not retried
+    SettlementFailed {
+        settlement_id: Uuid,
+        reason: String,
+        permanent: bool,
+    },
+    /// Permanently failed settlement requeued by an admin
+    SettlementReplayed {
+        settlement_id: Uuid,
+        admin_id: Uuid,
+        previous_error: String,
+    },
+    /// Escrowed funds and energy released to the counterparties
+    EscrowFinalized {
+        settlement_id: Uuid,
+        buyer_id: Uuid,
+        seller_id: Uuid,
+        net_amount: String,
+    },
+    /// Energy tokens minted for a meter reading
+    ///
+    /// With mint aggregation the mint may also cover earlier readings of the
+    /// same meter, so the minted amount can exceed `reading_kwh`.
+    EnergyMinted {
+        user_id: Uuid,
+        reading_id: Uuid,
+        correlation_id: Uuid,
+        meter_serial: String,
+        reading_kwh: String,
+        signature: Option<String>,
+    },
     /// Unauthorized access attempt
     UnauthorizedAccess {
         ip: String,
@@ -88,7 +141,14 @@ impl AuditEvent {
             AuditEvent::BlockchainRegistration { .. } => "blockchain_registration",
             AuditEvent::OrderCreated { .. } => "order_created",
             AuditEvent::OrderCancelled { .. } => "order_cancelled",
+            AuditEvent::OrderForceCancelled { .. } => "order_force_cancelled",
             AuditEvent::OrderMatched { .. } => "order_matched",
+            AuditEvent::SettlementStarted { .. } => "settlement_started",
+            AuditEvent::SettlementCompleted { .. } => "settlement_completed",
+            AuditEvent::SettlementFailed { .. } => "settlement_failed",
+            AuditEvent::SettlementReplayed { .. } => "settlement_replayed",
+            AuditEvent::EscrowFinalized { .. } => "escrow_finalized",
+            AuditEvent::EnergyMinted { .. } => "energy_minted",
             AuditEvent::UnauthorizedAccess { .. } => "unauthorized_access",
             AuditEvent::RateLimitExceeded { .. } => "rate_limit_exceeded",
             AuditEvent::DataAccess { .. } => "data_access",
@@ -107,11 +167,40 @@ impl AuditEvent {
             | AuditEvent::BlockchainRegistration { user_id, .. }
             | AuditEvent::OrderCreated { user_id, .. }
             | AuditEvent::OrderCancelled { user_id, .. }
+            | AuditEvent::EnergyMinted { user_id, .. }
             | AuditEvent::DataAccess { user_id, .. }
             | AuditEvent::AdminAction {
                 admin_id: user_id, ..
+            }
+            | AuditEvent::SettlementReplayed {
+                admin_id: user_id, ..
+            }
+            | AuditEvent::OrderForceCancelled {
+                admin_id: user_id, ..
             } => Some(*user_id),
-            AuditEvent::OrderMatched { buyer_id, .. } => Some(*buyer_id), // Prioritize buyer for indexing
+            AuditEvent::OrderMatched { buyer_id, .. }
+            | AuditEvent::SettlementStarted { buyer_id, .. }
+            | AuditEvent::EscrowFinalized { buyer_id, .. } => Some(*buyer_id), // Prioritize buyer for indexing
+            _ => None,
+        }
+    }
+
+    /// Extract settlement_id for settlement lifecycle events
+    pub fn settlement_id(&self) -> Option<Uuid> {
+        match self {
+            AuditEvent::SettlementStarted { settlement_id, .. }
+            | AuditEvent::SettlementCompleted { settlement_id, .. }
+            | AuditEvent::SettlementFailed { settlement_id, .. }
+            | AuditEvent::SettlementReplayed { settlement_id, .. }
+            | AuditEvent::EscrowFinalized { settlement_id, .. } => Some(*settlement_id),
+            _ => None,
+        }
+    }
+
+    /// Extract the reading correlation id for energy lifecycle events
+    pub fn correlation_id(&self) -> Option<Uuid> {
+        match self {
+            AuditEvent::EnergyMinted { correlation_id, .. } => Some(*correlation_id),
             _ => None,
         }
     }
@@ -140,3 +229,50 @@ pub struct AuditEventRecord {
     pub event_data: serde_json::Value,
     pub created_at: Option<chrono::DateTime<Utc>>,
 }
+
+/// Filters for searching the audit log; unset fields match everything
+#[derive(Debug, Clone, Default)]
+pub struct AuditEventFilter {
+    pub user_id: Option<Uuid>,
+    pub event_type: Option<String>,
+    pub from: Option<chrono::DateTime<Utc>>,
+    pub to: Option<chrono::DateTime<Utc>>,
+    /// Order or settlement the event refers to
+    pub entity_id: Option<Uuid>,
+}
+
+impl AuditEventFilter {
+    /// SQL `WHERE` clause for the set filters, with placeholders numbered from `$1`
+    ///
+    /// Values must be bound in field order: user_id, event_type, from, to, entity_id.
+    pub(crate) fn where_clause(&self) -> (String, usize) {
+        let mut conditions = vec!["1 = 1".to_string()];
+        let mut bind_count = 1;
+
+        if self.user_id.is_some() {
+            conditions.push(format!("user_id = ${}", bind_count));
+            bind_count += 1;
+        }
+        if self.event_type.is_some() {
+            conditions.push(format!("activity_type = ${}", bind_count));
+            bind_count += 1;
+        }
+        if self.from.is_some() {
+            conditions.push(format!("created_at >= ${}", bind_count));
+            bind_count += 1;
+        }
+        if self.to.is_some() {
+            conditions.push(format!("created_at < ${}", bind_count));
+            bind_count += 1;
+        }
+        if self.entity_id.is_some() {
+            conditions.push(format!(
+                "(metadata->>'order_id' = ${0} OR metadata->>'settlement_id' = ${0})",
+                bind_count
+            ));
+            bind_count += 1;
+        }
+
+        (conditions.join(" AND "), bind_count)
+    }
+}
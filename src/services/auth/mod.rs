@@ -3,9 +3,38 @@
 use crate::{
     auth::jwt::JwtService,
     config::Config,
+    error::ApiError,
     services::EmailService,
 };
+use base64::{engine::general_purpose, Engine as _};
+use sha2::{Digest, Sha256};
 use sqlx::PgPool;
+use tracing::warn;
+use uuid::Uuid;
+
+/// Refresh tokens expire after this many days unless `REFRESH_TOKEN_TTL_DAYS` is set
+const DEFAULT_REFRESH_TOKEN_TTL_DAYS: i64 = 30;
+
+/// User behind a refresh token that was exchanged, with its replacement
+#[derive(Debug, Clone)]
+pub struct RefreshedSession {
+    pub user_id: Uuid,
+    pub username: String,
+    pub role: String,
+    pub refresh_token: String,
+}
+
+#[derive(sqlx::FromRow)]
+struct RefreshTokenRow {
+    id: Uuid,
+    user_id: Uuid,
+    device_id: Option<String>,
+    expired: bool,
+    revoked: bool,
+    username: String,
+    role: String,
+    is_active: bool,
+}
 
 /// Service for handling authentication-related logic (minimal version)
 #[derive(Clone)]
@@ -45,4 +74,148 @@ impl AuthService {
     pub fn jwt_service(&self) -> &JwtService {
         &self.jwt_service
     }
+
+    /// Issue a refresh token for `user_id`, bound to `device_id` if given
+    pub async fn issue_refresh_token(
+        &self,
+        user_id: Uuid,
+        device_id: Option<&str>,
+    ) -> Result<String, ApiError> {
+        let token = generate_refresh_token();
+
+        sqlx::query(
+            r#"
+            INSERT INTO refresh_tokens (user_id, token_hash, device_id, expires_at)
+            VALUES ($1, $2, $3, NOW() + make_interval(days => $4))
+            "#,
+        )
+        .bind(user_id)
+        .bind(hash_refresh_token(&token))
+        .bind(device_id)
+        .bind(refresh_token_ttl_days() as i32)
+        .execute(&self.db)
+        .await?;
+
+        Ok(token)
+    }
+
+    /// Exchange a refresh token for a new one on the same device
+    ///
+    /// The presented token is revoked. Presenting an already revoked token
+    /// means it leaked, so every active refresh token of the user is revoked.
+    pub async fn rotate_refresh_token(&self, token: &str) -> Result<RefreshedSession, ApiError> {
+        let mut tx = self.db.begin().await?;
+
+        let row = sqlx::query_as::<_, RefreshTokenRow>(
+            r#"
+            SELECT rt.id, rt.user_id, rt.device_id,
+                   rt.expires_at <= NOW() AS expired,
+                   rt.revoked_at IS NOT NULL AS revoked,
+                   u.username, u.role::text AS role, u.is_active
+            FROM refresh_tokens rt
+            JOIN users u ON u.id = rt.user_id
+            WHERE rt.token_hash = $1
+            FOR UPDATE OF rt
+            "#,
+        )
+        .bind(hash_refresh_token(token))
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or_else(|| ApiError::Unauthorized("Invalid refresh token".to_string()))?;
+
+        if row.revoked {
+            warn!(
+                "🚨 Revoked refresh token reused for user {}, revoking all sessions",
+                row.user_id
+            );
+            sqlx::query("UPDATE refresh_tokens SET revoked_at = NOW() WHERE user_id = $1 AND revoked_at IS NULL")
+                .bind(row.user_id)
+                .execute(&mut *tx)
+                .await?;
+            tx.commit().await?;
+            return Err(ApiError::Unauthorized("Refresh token has been revoked".to_string()));
+        }
+        if row.expired {
+            return Err(ApiError::Unauthorized("Refresh token has expired".to_string()));
+        }
+        if !row.is_active {
+            return Err(ApiError::Unauthorized("Account is disabled".to_string()));
+        }
+
+        let refresh_token = generate_refresh_token();
+        let new_id: Uuid = sqlx::query_scalar(
+            r#"
+            INSERT INTO refresh_tokens (user_id, token_hash, device_id, expires_at)
+            VALUES ($1, $2, $3, NOW() + make_interval(days => $4))
+            RETURNING id
+            "#,
+        )
+        .bind(row.user_id)
+        .bind(hash_refresh_token(&refresh_token))
+        .bind(&row.device_id)
+        .bind(refresh_token_ttl_days() as i32)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            "UPDATE refresh_tokens SET revoked_at = NOW(), last_used_at = NOW(), replaced_by = $2 WHERE id = $1",
+        )
+        .bind(row.id)
+        .bind(new_id)
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(RefreshedSession {
+            user_id: row.user_id,
+            username: row.username,
+            role: row.role,
+            refresh_token,
+        })
+    }
+
+    /// Revoke a refresh token; returns `false` if it was unknown or already revoked
+    pub async fn revoke_refresh_token(&self, token: &str) -> Result<bool, ApiError> {
+        let result = sqlx::query(
+            "UPDATE refresh_tokens SET revoked_at = NOW() WHERE token_hash = $1 AND revoked_at IS NULL",
+        )
+        .bind(hash_refresh_token(token))
+        .execute(&self.db)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+}
+
+fn refresh_token_ttl_days() -> i64 {
+    std::env::var("REFRESH_TOKEN_TTL_DAYS")
+        .ok()
+        .and_then(|v| v.parse::<i64>().ok())
+        .filter(|days| *days > 0)
+        .unwrap_or(DEFAULT_REFRESH_TOKEN_TTL_DAYS)
+}
+
+/// 256 random bits, URL-safe base64
+fn generate_refresh_token() -> String {
+    general_purpose::URL_SAFE_NO_PAD.encode(rand::random::<[u8; 32]>())
+}
+
+fn hash_refresh_token(token: &str) -> String {
+    format!("{:x}", Sha256::digest(token.as_bytes()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_refresh_tokens_are_unique_and_hashed() {
+        let (a, b) = (generate_refresh_token(), generate_refresh_token());
+        assert_ne!(a, b);
+        assert_eq!(a.len(), 43);
+        assert_eq!(hash_refresh_token(&a), hash_refresh_token(&a));
+        assert_ne!(hash_refresh_token(&a), a);
+        assert_eq!(hash_refresh_token(&a).len(), 64);
+    }
 }
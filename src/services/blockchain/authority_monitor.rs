@@ -0,0 +1,292 @@
+//! Authority wallet funding checks
+//!
+//! The payer keypair signs and pays for settlement transfers and the token
+//! accounts they create, and the `AUTHORITY_WALLET_PATH` keypair pays for
+//! minting and token account creation. When either is missing or out of SOL
+//! those operations fail with an unrelated-looking RPC error, so both balances
+//! are checked at startup and then periodically, and a shortfall fails
+//! readiness. A keypair configured for both roles is checked once.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use solana_sdk::signature::{Keypair, Signer};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+use tracing::{error, info, warn};
+use utoipa::ToSchema;
+
+use super::BlockchainService;
+use crate::config::AuthorityFundingConfig;
+use crate::middleware::metrics::track_authority_balance;
+
+/// Whether the authority can pay for settlements
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum AuthorityFunding {
+    Funded,
+    /// Above the minimum but below the low-balance mark
+    Low,
+    /// Below the minimum; settlements will start failing
+    Unfunded,
+    /// No authority keypair is configured
+    Missing,
+    /// The balance could not be read
+    Unknown,
+}
+
+impl AuthorityFunding {
+    fn classify(balance_sol: f64, config: &AuthorityFundingConfig) -> Self {
+        if balance_sol < config.min_balance_sol {
+            Self::Unfunded
+        } else if balance_sol < config.low_balance_sol {
+            Self::Low
+        } else {
+            Self::Funded
+        }
+    }
+
+    /// Whether the gateway can still settle trades
+    pub fn is_operational(self) -> bool {
+        matches!(self, Self::Funded | Self::Low | Self::Unknown)
+    }
+
+    /// Rank used to report the worst state across wallets
+    fn severity(self) -> u8 {
+        match self {
+            Self::Funded => 0,
+            Self::Low => 1,
+            Self::Unknown => 2,
+            Self::Unfunded => 3,
+            Self::Missing => 4,
+        }
+    }
+}
+
+/// What a checked keypair pays for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum AuthorityRole {
+    /// Settlement transfers and escrow, from `PAYER_PRIVATE_KEY` or secure storage
+    Payer,
+    /// Minting and token account creation, from `AUTHORITY_WALLET_PATH`
+    MintAuthority,
+}
+
+impl AuthorityRole {
+    fn label(self) -> &'static str {
+        match self {
+            Self::Payer => "payer",
+            Self::MintAuthority => "mint_authority",
+        }
+    }
+}
+
+/// Funding of one authority keypair
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct WalletStatus {
+    /// Roles served by this keypair; both when they share one key
+    pub roles: Vec<AuthorityRole>,
+    pub funding: AuthorityFunding,
+    pub pubkey: Option<String>,
+    pub balance_sol: Option<f64>,
+    pub error: Option<String>,
+}
+
+/// Outcome of the last authority check
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct AuthorityStatus {
+    /// Worst funding state across the checked wallets
+    pub funding: AuthorityFunding,
+    pub wallets: Vec<WalletStatus>,
+    pub min_balance_sol: f64,
+    pub low_balance_sol: f64,
+    pub checked_at: DateTime<Utc>,
+}
+
+/// Periodically checks that the authority keypairs are present and funded
+#[derive(Clone)]
+pub struct AuthorityMonitor {
+    blockchain: BlockchainService,
+    config: AuthorityFundingConfig,
+    last_status: Arc<RwLock<Option<AuthorityStatus>>>,
+}
+
+impl AuthorityMonitor {
+    pub fn new(blockchain: BlockchainService, config: AuthorityFundingConfig) -> Self {
+        Self {
+            blockchain,
+            config,
+            last_status: Arc::new(RwLock::new(None)),
+        }
+    }
+
+    /// Result of the most recent check, if one has run
+    pub async fn latest(&self) -> Option<AuthorityStatus> {
+        self.last_status.read().await.clone()
+    }
+
+    /// Load the keypairs, read their balances and record the outcome
+    ///
+    /// Alerts are logged when a role's funding state changes, so a wallet
+    /// left unfunded does not flood the logs.
+    pub async fn check(&self) -> AuthorityStatus {
+        let wallets = self.evaluate().await;
+        let funding = wallets
+            .iter()
+            .map(|wallet| wallet.funding)
+            .max_by_key(|funding| funding.severity())
+            .unwrap_or(AuthorityFunding::Missing);
+        let status = AuthorityStatus {
+            funding,
+            wallets,
+            min_balance_sol: self.config.min_balance_sol,
+            low_balance_sol: self.config.low_balance_sol,
+            checked_at: Utc::now(),
+        };
+
+        for wallet in &status.wallets {
+            if let Some(balance) = wallet.balance_sol {
+                for role in &wallet.roles {
+                    track_authority_balance(role.label(), balance, wallet.funding != AuthorityFunding::Funded);
+                }
+            }
+        }
+
+        let previous: HashMap<AuthorityRole, AuthorityFunding> = self
+            .last_status
+            .write()
+            .await
+            .replace(status.clone())
+            .map(|previous| {
+                previous
+                    .wallets
+                    .iter()
+                    .flat_map(|wallet| wallet.roles.iter().map(|role| (*role, wallet.funding)))
+                    .collect()
+            })
+            .unwrap_or_default();
+        for wallet in &status.wallets {
+            if wallet.roles.iter().any(|role| previous.get(role) != Some(&wallet.funding)) {
+                self.alert(wallet);
+            }
+        }
+
+        status
+    }
+
+    async fn evaluate(&self) -> Vec<WalletStatus> {
+        let payer = self.blockchain.load_configured_authority_keypair().await;
+        let mint_authority = self.blockchain.load_mint_authority_keypair();
+
+        match (payer, mint_authority) {
+            (Ok(payer), Ok(mint_authority)) if payer.pubkey() == mint_authority.pubkey() => {
+                vec![self.evaluate_keypair(&[AuthorityRole::Payer, AuthorityRole::MintAuthority], Ok(payer)).await]
+            }
+            (payer, mint_authority) => vec![
+                self.evaluate_keypair(&[AuthorityRole::Payer], payer).await,
+                self.evaluate_keypair(&[AuthorityRole::MintAuthority], mint_authority).await,
+            ],
+        }
+    }
+
+    async fn evaluate_keypair(&self, roles: &[AuthorityRole], keypair: anyhow::Result<Keypair>) -> WalletStatus {
+        let status = |funding, pubkey, balance_sol, error| WalletStatus {
+            roles: roles.to_vec(),
+            funding,
+            pubkey,
+            balance_sol,
+            error,
+        };
+
+        let keypair = match keypair {
+            Ok(keypair) => keypair,
+            Err(e) => return status(AuthorityFunding::Missing, None, None, Some(e.to_string())),
+        };
+        let pubkey = keypair.pubkey();
+
+        match self.blockchain.get_balance_sol(&pubkey).await {
+            Ok(balance) => status(
+                AuthorityFunding::classify(balance, &self.config),
+                Some(pubkey.to_string()),
+                Some(balance),
+                None,
+            ),
+            Err(e) => status(AuthorityFunding::Unknown, Some(pubkey.to_string()), None, Some(e.to_string())),
+        }
+    }
+
+    fn alert(&self, wallet: &WalletStatus) {
+        let pubkey = wallet.pubkey.as_deref().unwrap_or("<none>");
+        let roles = wallet
+            .roles
+            .iter()
+            .map(|role| role.label())
+            .collect::<Vec<_>>()
+            .join(", ");
+        match wallet.funding {
+            AuthorityFunding::Funded => info!(
+                "🔑 Authority wallet {} ({}) funded: {} SOL",
+                pubkey,
+                roles,
+                wallet.balance_sol.unwrap_or_default()
+            ),
+            AuthorityFunding::Low => warn!(
+                "⚠️ Authority wallet {} ({}) balance low: {} SOL (warning below {} SOL); top it up before settlements stall",
+                pubkey,
+                roles,
+                wallet.balance_sol.unwrap_or_default(),
+                self.config.low_balance_sol
+            ),
+            AuthorityFunding::Unfunded => error!(
+                "🚨 Authority wallet {} ({}) has {} SOL, below the {} SOL minimum; transactions it pays for will fail",
+                pubkey,
+                roles,
+                wallet.balance_sol.unwrap_or_default(),
+                self.config.min_balance_sol
+            ),
+            AuthorityFunding::Missing => error!(
+                "🚨 Authority keypair ({}) not configured; its transactions cannot be signed: {}",
+                roles,
+                wallet.error.as_deref().unwrap_or_default()
+            ),
+            AuthorityFunding::Unknown => warn!(
+                "⚠️ Could not read authority wallet {} ({}) balance: {}",
+                pubkey,
+                roles,
+                wallet.error.as_deref().unwrap_or_default()
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_funding_thresholds() {
+        let config = AuthorityFundingConfig {
+            min_balance_sol: 0.05,
+            low_balance_sol: 1.0,
+            check_interval_secs: 60,
+        };
+
+        assert_eq!(AuthorityFunding::classify(0.0, &config), AuthorityFunding::Unfunded);
+        assert_eq!(AuthorityFunding::classify(0.049, &config), AuthorityFunding::Unfunded);
+        assert_eq!(AuthorityFunding::classify(0.05, &config), AuthorityFunding::Low);
+        assert_eq!(AuthorityFunding::classify(1.0, &config), AuthorityFunding::Funded);
+
+        assert!(AuthorityFunding::Low.is_operational());
+        assert!(!AuthorityFunding::Unfunded.is_operational());
+        assert!(!AuthorityFunding::Missing.is_operational());
+    }
+
+    #[test]
+    fn test_worst_funding_wins() {
+        let worst = [AuthorityFunding::Funded, AuthorityFunding::Unfunded, AuthorityFunding::Low]
+            .into_iter()
+            .max_by_key(|funding| funding.severity());
+        assert_eq!(worst, Some(AuthorityFunding::Unfunded));
+    }
+}
@@ -0,0 +1,457 @@
+//! Blockchain client abstraction
+//!
+//! Services that move tokens depend on [`BlockchainClient`] rather than the
+//! concrete [`BlockchainService`], so their settlement and matching paths can
+//! be unit tested against [`MockBlockchainClient`] without a validator.
+
+use std::sync::Mutex;
+
+use anyhow::Result;
+use async_trait::async_trait;
+use solana_sdk::instruction::Instruction;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{Keypair, Signature, Signer};
+
+use super::errors::SettlementError;
+use super::token_management::TokenTransfer;
+use super::transactions::TransactionStatus;
+use super::BlockchainService;
+
+/// On-chain operations used by the settlement and trading services
+#[async_trait]
+pub trait BlockchainClient: Send + Sync + std::fmt::Debug {
+    async fn get_authority_keypair(&self) -> Result<Keypair>;
+
+    /// Token account of `wallet` for `mint`, created (paid by `authority`) if missing
+    async fn ensure_token_account_exists(
+        &self,
+        authority: &Keypair,
+        wallet: &Pubkey,
+        mint: &Pubkey,
+    ) -> Result<Pubkey>;
+
+    async fn transfer_tokens(
+        &self,
+        authority: &Keypair,
+        from: &Pubkey,
+        to: &Pubkey,
+        mint: &Pubkey,
+        amount: u64,
+        decimals: u8,
+    ) -> Result<Signature>;
+
+    async fn transfer_tokens_batch(
+        &self,
+        transfers: &[TokenTransfer],
+        signers: &[&Keypair],
+    ) -> Result<Signature>;
+
+    async fn get_slot(&self) -> Result<u64>;
+
+    /// Where a submitted transaction stands; `Pending` when the cluster has not seen it
+    async fn transaction_status(&self, signature: &Signature) -> Result<TransactionStatus>;
+
+    async fn get_token_balance(&self, owner: &Pubkey, mint: &Pubkey) -> Result<u64>;
+
+    async fn account_exists(&self, pubkey: &Pubkey) -> Result<bool>;
+
+    /// SOL balance of `pubkey` in lamports
+    async fn get_balance(&self, pubkey: &Pubkey) -> Result<u64>;
+
+    fn trading_program_id(&self) -> Pubkey;
+
+    fn calculate_ata_address(&self, wallet: &Pubkey, mint: &Pubkey) -> Result<Pubkey>;
+
+    /// Decimals of `mint`, used to scale amounts into atomic units
+    async fn decimals_for(&self, mint: &Pubkey) -> Result<u8>;
+
+    /// Create an order on the trading program, returning the order PDA
+    async fn execute_create_order(
+        &self,
+        authority: &Keypair,
+        market_address: &Pubkey,
+        side: u8,
+        quantity: u64,
+        price: u64,
+    ) -> Result<(Signature, String)>;
+
+    /// Submit a prebuilt atomic settlement instruction signed by the market authority
+    async fn execute_atomic_settlement(&self, market_authority: &Keypair, settlement_ix: Instruction) -> Result<Signature>;
+
+    async fn initiate_bridge_transfer(
+        &self,
+        authority: &Keypair,
+        market: &Pubkey,
+        sell_order: &Pubkey,
+        buyer_wallet: &Pubkey,
+        amount: u64,
+        target_chain: &str,
+    ) -> Result<Signature>;
+
+    async fn complete_bridge_transfer(
+        &self,
+        authority: &Keypair,
+        bridge_id: &str,
+        recipient: &Pubkey,
+        amount: u64,
+    ) -> Result<Signature>;
+}
+
+#[async_trait]
+impl BlockchainClient for BlockchainService {
+    async fn get_authority_keypair(&self) -> Result<Keypair> {
+        BlockchainService::get_authority_keypair(self).await
+    }
+
+    async fn ensure_token_account_exists(
+        &self,
+        authority: &Keypair,
+        wallet: &Pubkey,
+        mint: &Pubkey,
+    ) -> Result<Pubkey> {
+        BlockchainService::ensure_token_account_exists(self, authority, wallet, mint).await
+    }
+
+    async fn transfer_tokens(
+        &self,
+        authority: &Keypair,
+        from: &Pubkey,
+        to: &Pubkey,
+        mint: &Pubkey,
+        amount: u64,
+        decimals: u8,
+    ) -> Result<Signature> {
+        BlockchainService::transfer_tokens(self, authority, from, to, mint, amount, decimals).await
+    }
+
+    async fn transfer_tokens_batch(
+        &self,
+        transfers: &[TokenTransfer],
+        signers: &[&Keypair],
+    ) -> Result<Signature> {
+        BlockchainService::transfer_tokens_batch(self, transfers, signers).await
+    }
+
+    async fn get_slot(&self) -> Result<u64> {
+        BlockchainService::get_slot(self).await
+    }
+
+    async fn transaction_status(&self, signature: &Signature) -> Result<TransactionStatus> {
+        BlockchainService::get_transaction_status(self, signature).await
+    }
+
+    async fn get_token_balance(&self, owner: &Pubkey, mint: &Pubkey) -> Result<u64> {
+        BlockchainService::get_token_balance(self, owner, mint).await
+    }
+
+    async fn account_exists(&self, pubkey: &Pubkey) -> Result<bool> {
+        BlockchainService::account_exists(self, pubkey).await
+    }
+
+    async fn get_balance(&self, pubkey: &Pubkey) -> Result<u64> {
+        BlockchainService::get_balance(self, pubkey).await
+    }
+
+    fn trading_program_id(&self) -> Pubkey {
+        BlockchainService::trading_program_id(self)
+    }
+
+    fn calculate_ata_address(&self, wallet: &Pubkey, mint: &Pubkey) -> Result<Pubkey> {
+        BlockchainService::calculate_ata_address(self, wallet, mint)
+    }
+
+    async fn decimals_for(&self, mint: &Pubkey) -> Result<u8> {
+        BlockchainService::decimals_for(self, mint).await
+    }
+
+    async fn execute_create_order(
+        &self,
+        authority: &Keypair,
+        market_address: &Pubkey,
+        side: u8,
+        quantity: u64,
+        price: u64,
+    ) -> Result<(Signature, String)> {
+        BlockchainService::execute_create_order(self, authority, market_address, side, quantity, price).await
+    }
+
+    async fn execute_atomic_settlement(&self, market_authority: &Keypair, settlement_ix: Instruction) -> Result<Signature> {
+        BlockchainService::execute_atomic_settlement(self, market_authority, settlement_ix).await
+    }
+
+    async fn initiate_bridge_transfer(
+        &self,
+        authority: &Keypair,
+        market: &Pubkey,
+        sell_order: &Pubkey,
+        buyer_wallet: &Pubkey,
+        amount: u64,
+        target_chain: &str,
+    ) -> Result<Signature> {
+        BlockchainService::initiate_bridge_transfer(
+            self, authority, market, sell_order, buyer_wallet, amount, target_chain,
+        )
+        .await
+    }
+
+    async fn complete_bridge_transfer(
+        &self,
+        authority: &Keypair,
+        bridge_id: &str,
+        recipient: &Pubkey,
+        amount: u64,
+    ) -> Result<Signature> {
+        BlockchainService::complete_bridge_transfer(self, authority, bridge_id, recipient, amount).await
+    }
+}
+
+/// A call made against [`MockBlockchainClient`]
+#[derive(Debug, Clone, PartialEq)]
+pub enum BlockchainCall {
+    EnsureTokenAccount { wallet: Pubkey, mint: Pubkey },
+    Transfer { owner: Pubkey, from: Pubkey, to: Pubkey, mint: Pubkey, amount: u64, decimals: u8 },
+    TransferBatch { transfers: usize, signers: usize },
+    CreateOrder { market: Pubkey, side: u8, quantity: u64, price: u64 },
+    AtomicSettlement { program_id: Pubkey, accounts: usize },
+    InitiateBridge { buyer_wallet: Pubkey, amount: u64, target_chain: String },
+    CompleteBridge { bridge_id: String, recipient: Pubkey, amount: u64 },
+}
+
+/// In-memory [`BlockchainClient`] that records every call
+///
+/// Token accounts are derived like the real associated token accounts,
+/// every account exists, balances come from [`Self::with_balance`], mints
+/// have 9 decimals unless set with [`Self::with_decimals`] and each
+/// submission returns a fresh signature. Set [`Self::fail_transfers`] to make
+/// transfers error.
+#[derive(Debug)]
+pub struct MockBlockchainClient {
+    authority: Keypair,
+    slot: u64,
+    balances: Mutex<Vec<(Pubkey, Pubkey, u64)>>,
+    decimals: Mutex<Vec<(Pubkey, u8)>>,
+    fail_transfers: Mutex<Option<SettlementError>>,
+    calls: Mutex<Vec<BlockchainCall>>,
+}
+
+impl Default for MockBlockchainClient {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MockBlockchainClient {
+    pub fn new() -> Self {
+        Self {
+            authority: Keypair::new(),
+            slot: 1,
+            balances: Mutex::new(Vec::new()),
+            decimals: Mutex::new(Vec::new()),
+            fail_transfers: Mutex::new(None),
+            calls: Mutex::new(Vec::new()),
+        }
+    }
+
+    pub fn with_slot(mut self, slot: u64) -> Self {
+        self.slot = slot;
+        self
+    }
+
+    pub fn with_balance(self, owner: Pubkey, mint: Pubkey, amount: u64) -> Self {
+        self.balances.lock().unwrap().push((owner, mint, amount));
+        self
+    }
+
+    pub fn with_decimals(self, mint: Pubkey, decimals: u8) -> Self {
+        self.decimals.lock().unwrap().push((mint, decimals));
+        self
+    }
+
+    /// Make every later transfer fail with `error`
+    pub fn fail_transfers(&self, error: SettlementError) {
+        *self.fail_transfers.lock().unwrap() = Some(error);
+    }
+
+    pub fn authority_pubkey(&self) -> Pubkey {
+        self.authority.pubkey()
+    }
+
+    /// Calls recorded so far, in order
+    pub fn calls(&self) -> Vec<BlockchainCall> {
+        self.calls.lock().unwrap().clone()
+    }
+
+    /// Token transfers recorded so far, in order
+    pub fn transfers(&self) -> Vec<BlockchainCall> {
+        self.calls()
+            .into_iter()
+            .filter(|call| matches!(call, BlockchainCall::Transfer { .. }))
+            .collect()
+    }
+
+    fn record(&self, call: BlockchainCall) {
+        self.calls.lock().unwrap().push(call);
+    }
+
+    fn check_transfers(&self) -> Result<()> {
+        match self.fail_transfers.lock().unwrap().as_ref() {
+            Some(error) => Err(error.clone().into()),
+            None => Ok(()),
+        }
+    }
+}
+
+#[async_trait]
+impl BlockchainClient for MockBlockchainClient {
+    async fn get_authority_keypair(&self) -> Result<Keypair> {
+        Ok(self.authority.insecure_clone())
+    }
+
+    async fn ensure_token_account_exists(
+        &self,
+        _authority: &Keypair,
+        wallet: &Pubkey,
+        mint: &Pubkey,
+    ) -> Result<Pubkey> {
+        self.record(BlockchainCall::EnsureTokenAccount { wallet: *wallet, mint: *mint });
+        self.calculate_ata_address(wallet, mint)
+    }
+
+    async fn transfer_tokens(
+        &self,
+        authority: &Keypair,
+        from: &Pubkey,
+        to: &Pubkey,
+        mint: &Pubkey,
+        amount: u64,
+        decimals: u8,
+    ) -> Result<Signature> {
+        self.record(BlockchainCall::Transfer {
+            owner: authority.pubkey(),
+            from: *from,
+            to: *to,
+            mint: *mint,
+            amount,
+            decimals,
+        });
+        self.check_transfers()?;
+        Ok(Signature::new_unique())
+    }
+
+    async fn transfer_tokens_batch(
+        &self,
+        transfers: &[TokenTransfer],
+        signers: &[&Keypair],
+    ) -> Result<Signature> {
+        self.record(BlockchainCall::TransferBatch {
+            transfers: transfers.len(),
+            signers: signers.len(),
+        });
+        self.check_transfers()?;
+        Ok(Signature::new_unique())
+    }
+
+    async fn get_slot(&self) -> Result<u64> {
+        Ok(self.slot)
+    }
+
+    async fn transaction_status(&self, _signature: &Signature) -> Result<TransactionStatus> {
+        Ok(TransactionStatus::Pending)
+    }
+
+    async fn get_token_balance(&self, owner: &Pubkey, mint: &Pubkey) -> Result<u64> {
+        Ok(self
+            .balances
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|(o, m, _)| o == owner && m == mint)
+            .map(|(_, _, amount)| *amount)
+            .unwrap_or(0))
+    }
+
+    async fn account_exists(&self, _pubkey: &Pubkey) -> Result<bool> {
+        Ok(true)
+    }
+
+    async fn get_balance(&self, _pubkey: &Pubkey) -> Result<u64> {
+        Ok(0)
+    }
+
+    fn trading_program_id(&self) -> Pubkey {
+        Pubkey::default()
+    }
+
+    fn calculate_ata_address(&self, wallet: &Pubkey, mint: &Pubkey) -> Result<Pubkey> {
+        Ok(spl_associated_token_account::get_associated_token_address(wallet, mint))
+    }
+
+    async fn decimals_for(&self, mint: &Pubkey) -> Result<u8> {
+        Ok(self
+            .decimals
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|(m, _)| m == mint)
+            .map(|(_, decimals)| *decimals)
+            .unwrap_or(9))
+    }
+
+    async fn execute_create_order(
+        &self,
+        _authority: &Keypair,
+        market_address: &Pubkey,
+        side: u8,
+        quantity: u64,
+        price: u64,
+    ) -> Result<(Signature, String)> {
+        self.record(BlockchainCall::CreateOrder {
+            market: *market_address,
+            side,
+            quantity,
+            price,
+        });
+        Ok((Signature::new_unique(), Pubkey::new_unique().to_string()))
+    }
+
+    async fn execute_atomic_settlement(&self, _market_authority: &Keypair, settlement_ix: Instruction) -> Result<Signature> {
+        self.record(BlockchainCall::AtomicSettlement {
+            program_id: settlement_ix.program_id,
+            accounts: settlement_ix.accounts.len(),
+        });
+        self.check_transfers()?;
+        Ok(Signature::new_unique())
+    }
+
+    async fn initiate_bridge_transfer(
+        &self,
+        _authority: &Keypair,
+        _market: &Pubkey,
+        _sell_order: &Pubkey,
+        buyer_wallet: &Pubkey,
+        amount: u64,
+        target_chain: &str,
+    ) -> Result<Signature> {
+        self.record(BlockchainCall::InitiateBridge {
+            buyer_wallet: *buyer_wallet,
+            amount,
+            target_chain: target_chain.to_string(),
+        });
+        Ok(Signature::new_unique())
+    }
+
+    async fn complete_bridge_transfer(
+        &self,
+        _authority: &Keypair,
+        bridge_id: &str,
+        recipient: &Pubkey,
+        amount: u64,
+    ) -> Result<Signature> {
+        self.record(BlockchainCall::CompleteBridge {
+            bridge_id: bridge_id.to_string(),
+            recipient: *recipient,
+            amount,
+        });
+        Ok(Signature::new_unique())
+    }
+}
@@ -0,0 +1,329 @@
+//! Structured on-chain submission errors
+//!
+//! Solana client and RPC failures are mapped into [`SettlementError`] where
+//! they are produced, so callers decide whether to retry from the variant
+//! rather than from the error text.
+
+use solana_client::client_error::{ClientError, ClientErrorKind};
+use solana_client::rpc_request::RpcError;
+use solana_sdk::instruction::InstructionError;
+use solana_sdk::signature::Signature;
+use solana_sdk::transaction::TransactionError;
+
+/// JSON-RPC error code some providers use for throttled requests
+const RPC_TOO_MANY_REQUESTS: i64 = 429;
+
+/// JSON-RPC server errors for a node that is behind or missing the block
+const RPC_NODE_UNAVAILABLE: [i64; 3] = [-32004, -32005, -32007];
+
+/// Reported when the last valid block height for the transaction's blockhash
+/// passes before it is confirmed; the blockhash has expired
+const BLOCK_HEIGHT_EXCEEDED: &str = "block height exceeded";
+
+/// Why a transaction could not be landed on-chain
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum SettlementError {
+    /// Fee payer or token owner cannot cover the transfer
+    #[error("insufficient funds: {0}")]
+    InsufficientFunds(String),
+
+    /// The blockhash expired before the transaction landed
+    #[error("blockhash expired")]
+    BlockhashExpired,
+
+    /// The RPC provider is throttling requests
+    #[error("rate limited by RPC provider")]
+    RateLimited,
+
+    /// An account the transaction needs does not exist
+    #[error("account not found: {0}")]
+    AccountNotFound(String),
+
+    /// A program returned a custom error code
+    #[error("custom program error: {0:#x}")]
+    ProgramError(u32),
+
+    /// The transaction was rejected for a reason that will not change on retry
+    #[error("transaction rejected: {0}")]
+    Rejected(String),
+
+    /// The RPC node could not be reached or could not serve the request
+    #[error("network error: {0}")]
+    Network(String),
+
+    /// Any failure not classified above
+    #[error("{0}")]
+    Other(String),
+}
+
+impl SettlementError {
+    /// Whether submitting again may succeed
+    ///
+    /// Unclassified failures are retried, keeping the automatic retry
+    /// conservative.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            Self::BlockhashExpired | Self::RateLimited | Self::Network(_) | Self::Other(_) => true,
+            Self::InsufficientFunds(_)
+            | Self::AccountNotFound(_)
+            | Self::ProgramError(_)
+            | Self::Rejected(_) => false,
+        }
+    }
+
+    /// Whether the transaction may have landed even though submitting it failed
+    ///
+    /// A simulation or execution failure, or an expired blockhash, means an
+    /// atomic transaction changed nothing. Network, throttling and
+    /// unclassified errors can arrive after the cluster already accepted it.
+    pub fn may_have_landed(&self) -> bool {
+        matches!(self, Self::RateLimited | Self::Network(_) | Self::Other(_))
+    }
+
+    /// The classified error inside an `anyhow` chain, or [`Self::Other`]
+    /// carrying its message when none was attached at the source
+    pub fn from_anyhow(error: &anyhow::Error) -> Self {
+        error
+            .downcast_ref::<SettlementError>()
+            .cloned()
+            .unwrap_or_else(|| Self::Other(error.to_string()))
+    }
+
+    /// Classify the stderr of a failed `spl-token` CLI invocation
+    ///
+    /// The CLI only reports the client error as text, so this is the one
+    /// place classification falls back to matching its output.
+    pub fn from_cli_output(stderr: &str) -> Self {
+        let lower = stderr.to_lowercase();
+
+        if let Some(code) = lower
+            .split("custom program error: 0x")
+            .nth(1)
+            .and_then(|rest| {
+                let hex: String = rest.chars().take_while(|c| c.is_ascii_hexdigit()).collect();
+                u32::from_str_radix(&hex, 16).ok()
+            })
+        {
+            return Self::ProgramError(code);
+        }
+
+        let message = stderr.trim().to_string();
+        if lower.contains("insufficient funds") || lower.contains("insufficientfunds") {
+            Self::InsufficientFunds(message)
+        } else if lower.contains("blockhash not found")
+            || lower.contains("blockhashnotfound")
+            || lower.contains(BLOCK_HEIGHT_EXCEEDED)
+        {
+            Self::BlockhashExpired
+        } else if lower.contains("429") || lower.contains("too many requests") {
+            Self::RateLimited
+        } else if lower.contains("accountnotfound") || lower.contains("account not found") {
+            Self::AccountNotFound(message)
+        } else if lower.contains("error sending request") || lower.contains("connection refused") {
+            Self::Network(message)
+        } else {
+            Self::Other(message)
+        }
+    }
+}
+
+/// Signatures of every attempt at a transaction that was given up on
+///
+/// Carried beneath the [`SettlementError`] of a failed submission so the
+/// caller can check whether one of the attempts landed after all.
+#[derive(Debug, Clone, Default, PartialEq, Eq, thiserror::Error)]
+#[error("attempted signatures: {0:?}")]
+pub struct AttemptedSignatures(pub Vec<Signature>);
+
+impl AttemptedSignatures {
+    /// The attempts recorded in an `anyhow` chain; empty when none were
+    pub fn from_anyhow(error: &anyhow::Error) -> Vec<Signature> {
+        error
+            .downcast_ref::<Self>()
+            .map(|attempts| attempts.0.clone())
+            .unwrap_or_default()
+    }
+
+    /// `error` with these attempts attached; its message is unchanged
+    pub fn attach(self, error: SettlementError) -> anyhow::Error {
+        anyhow::Error::new(self).context(error)
+    }
+}
+
+impl From<&TransactionError> for SettlementError {
+    fn from(error: &TransactionError) -> Self {
+        match error {
+            TransactionError::InsufficientFundsForFee
+            | TransactionError::InsufficientFundsForRent { .. } => {
+                Self::InsufficientFunds(error.to_string())
+            }
+            TransactionError::BlockhashNotFound => Self::BlockhashExpired,
+            TransactionError::AccountNotFound
+            | TransactionError::ProgramAccountNotFound
+            | TransactionError::InvalidAccountForFee => Self::AccountNotFound(error.to_string()),
+            TransactionError::InstructionError(_, InstructionError::Custom(code)) => {
+                Self::ProgramError(*code)
+            }
+            TransactionError::InstructionError(_, InstructionError::InsufficientFunds) => {
+                Self::InsufficientFunds(error.to_string())
+            }
+            TransactionError::InstructionError(..)
+            | TransactionError::AlreadyProcessed
+            | TransactionError::SignatureFailure => Self::Rejected(error.to_string()),
+            _ => Self::Other(error.to_string()),
+        }
+    }
+}
+
+impl From<&ClientError> for SettlementError {
+    fn from(error: &ClientError) -> Self {
+        // Covers both executed failures and preflight simulation failures
+        if let Some(tx_error) = error.get_transaction_error() {
+            return Self::from(&tx_error);
+        }
+        // Expiry during confirmation only surfaces in the message
+        if error.to_string().to_lowercase().contains(BLOCK_HEIGHT_EXCEEDED) {
+            return Self::BlockhashExpired;
+        }
+
+        match error.kind() {
+            ClientErrorKind::Reqwest(e)
+                if e.status().map(|status| status.as_u16()) == Some(RPC_TOO_MANY_REQUESTS as u16) =>
+            {
+                Self::RateLimited
+            }
+            ClientErrorKind::Io(_) | ClientErrorKind::Reqwest(_) => Self::Network(error.to_string()),
+            ClientErrorKind::RpcError(RpcError::RpcResponseError { code, .. })
+                if *code == RPC_TOO_MANY_REQUESTS =>
+            {
+                Self::RateLimited
+            }
+            ClientErrorKind::RpcError(RpcError::RpcResponseError { code, .. })
+                if RPC_NODE_UNAVAILABLE.contains(code) =>
+            {
+                Self::Network(error.to_string())
+            }
+            // Raised when a sent transaction could not be confirmed in time
+            ClientErrorKind::RpcError(RpcError::ForUser(_)) => Self::Network(error.to_string()),
+            _ => Self::Other(error.to_string()),
+        }
+    }
+}
+
+impl From<ClientError> for SettlementError {
+    fn from(error: ClientError) -> Self {
+        Self::from(&error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_transient_variants_are_retryable() {
+        assert!(SettlementError::BlockhashExpired.is_retryable());
+        assert!(SettlementError::RateLimited.is_retryable());
+        assert!(SettlementError::Network("connection reset".to_string()).is_retryable());
+        assert!(SettlementError::Other("unknown".to_string()).is_retryable());
+    }
+
+    #[test]
+    fn test_permanent_variants_are_not_retryable() {
+        assert!(!SettlementError::InsufficientFunds("fee payer".to_string()).is_retryable());
+        assert!(!SettlementError::AccountNotFound("seller ata".to_string()).is_retryable());
+        assert!(!SettlementError::ProgramError(1).is_retryable());
+        assert!(!SettlementError::Rejected("already processed".to_string()).is_retryable());
+    }
+
+    #[test]
+    fn test_transaction_errors_map_to_variants() {
+        assert_eq!(
+            SettlementError::from(&TransactionError::BlockhashNotFound),
+            SettlementError::BlockhashExpired
+        );
+        assert!(matches!(
+            SettlementError::from(&TransactionError::InsufficientFundsForFee),
+            SettlementError::InsufficientFunds(_)
+        ));
+        assert!(matches!(
+            SettlementError::from(&TransactionError::AccountNotFound),
+            SettlementError::AccountNotFound(_)
+        ));
+        assert_eq!(
+            SettlementError::from(&TransactionError::InstructionError(2, InstructionError::Custom(0x1))),
+            SettlementError::ProgramError(1)
+        );
+        assert!(matches!(
+            SettlementError::from(&TransactionError::AlreadyProcessed),
+            SettlementError::Rejected(_)
+        ));
+    }
+
+    #[test]
+    fn test_client_transaction_error_is_unwrapped() {
+        let error = ClientError::from(TransactionError::BlockhashNotFound);
+        assert_eq!(SettlementError::from(&error), SettlementError::BlockhashExpired);
+    }
+
+    #[test]
+    fn test_block_height_exceeded_is_an_expired_blockhash() {
+        let error = ClientError::from(RpcError::ForUser(
+            "Signature 5h6x... has expired: block height exceeded".to_string(),
+        ));
+        assert_eq!(SettlementError::from(&error), SettlementError::BlockhashExpired);
+    }
+
+    #[test]
+    fn test_only_ambiguous_failures_may_have_landed() {
+        assert!(SettlementError::Network("timed out".to_string()).may_have_landed());
+        assert!(SettlementError::Other("unknown".to_string()).may_have_landed());
+        assert!(!SettlementError::BlockhashExpired.may_have_landed());
+        assert!(!SettlementError::ProgramError(1).may_have_landed());
+    }
+
+    #[test]
+    fn test_attempted_signatures_survive_in_the_chain() {
+        let signature = Signature::new_unique();
+        let error = AttemptedSignatures(vec![signature]).attach(SettlementError::RateLimited);
+
+        assert_eq!(error.to_string(), "rate limited by RPC provider");
+        assert_eq!(SettlementError::from_anyhow(&error), SettlementError::RateLimited);
+        assert_eq!(AttemptedSignatures::from_anyhow(&error), vec![signature]);
+        assert!(AttemptedSignatures::from_anyhow(&anyhow::anyhow!("plain")).is_empty());
+    }
+
+    #[test]
+    fn test_anyhow_keeps_the_classified_variant() {
+        let error = anyhow::Error::new(SettlementError::RateLimited);
+        assert_eq!(SettlementError::from_anyhow(&error), SettlementError::RateLimited);
+
+        let unclassified = anyhow::anyhow!("something else");
+        assert_eq!(
+            SettlementError::from_anyhow(&unclassified),
+            SettlementError::Other("something else".to_string())
+        );
+    }
+
+    #[test]
+    fn test_cli_output_classification() {
+        assert_eq!(
+            SettlementError::from_cli_output(
+                "Error: Client(Error { kind: RpcError(RpcResponseError { code: -32002, message: \"Transaction simulation failed: Error processing Instruction 0: custom program error: 0x1\" }) })"
+            ),
+            SettlementError::ProgramError(1)
+        );
+        assert_eq!(
+            SettlementError::from_cli_output("Error: Client(Error { kind: TransactionError(BlockhashNotFound) })"),
+            SettlementError::BlockhashExpired
+        );
+        assert_eq!(
+            SettlementError::from_cli_output("Error: transaction expired: block height exceeded"),
+            SettlementError::BlockhashExpired
+        );
+        assert_eq!(
+            SettlementError::from_cli_output("HTTP status client error (429 Too Many Requests)"),
+            SettlementError::RateLimited
+        );
+    }
+}
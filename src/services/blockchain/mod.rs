@@ -1,6 +1,9 @@
 //! Blockchain services module
 
 pub mod account_management;
+pub mod authority_monitor;
+pub mod client;
+pub mod errors;
 pub mod instructions;
 pub mod on_chain;
 pub mod priority_fee;
@@ -10,8 +13,12 @@ pub mod transactions;
 pub mod utils;
 
 // Re-exports
+pub use authority_monitor::{AuthorityFunding, AuthorityMonitor, AuthorityRole, AuthorityStatus, WalletStatus};
+pub use client::{BlockchainCall, BlockchainClient, MockBlockchainClient};
+pub use errors::SettlementError;
 pub use instructions::InstructionBuilder;
 pub use priority_fee::{PriorityFeeService, TransactionType};
 pub use service::BlockchainService;
+pub use token_management::TokenTransfer;
 pub use transactions::{TransactionHandler, TransactionStatus, FeeEstimate, SolBalanceCheck};
 pub use utils::BlockchainUtils;
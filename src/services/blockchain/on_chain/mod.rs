@@ -8,13 +8,12 @@ pub use trading::TradingManager;
 pub use tokens::TokenManager;
 pub use governance::GovernanceManager;
 
-use anyhow::{anyhow, Result};
+use anyhow::Result;
 use solana_sdk::instruction::Instruction;
 use solana_sdk::pubkey::Pubkey;
 use solana_sdk::signature::{Keypair, Signature};
 use solana_sdk::transaction::Transaction;
-use std::str::FromStr;
-use crate::config::SolanaProgramsConfig;
+use crate::config::ValidatedSolanaPrograms;
 use crate::services::blockchain::instructions::InstructionBuilder;
 use crate::services::blockchain::transactions::TransactionHandler;
 
@@ -27,14 +26,14 @@ pub struct OnChainManager {
     pub governance: GovernanceManager,
     transaction_handler: TransactionHandler,
     instruction_builder: InstructionBuilder,
-    program_ids: SolanaProgramsConfig,
+    program_ids: ValidatedSolanaPrograms,
 }
 
 impl OnChainManager {
     pub fn new(
         transaction_handler: TransactionHandler,
         instruction_builder: InstructionBuilder,
-        program_ids: SolanaProgramsConfig,
+        program_ids: ValidatedSolanaPrograms,
     ) -> Self {
         Self {
             registry: RegistryManager::new(transaction_handler.clone(), instruction_builder.clone()),
@@ -55,9 +54,8 @@ impl OnChainManager {
         self.transaction_handler.confirm_transaction(signature).await
     }
 
-    pub fn trading_program_id(&self) -> Result<Pubkey> {
-        Pubkey::from_str(&self.program_ids.trading_program_id)
-            .map_err(|e| anyhow!("Invalid Trading ID: {}", e))
+    pub fn trading_program_id(&self) -> Pubkey {
+        self.program_ids.trading
     }
 
     pub fn instruction_builder(&self) -> &InstructionBuilder {
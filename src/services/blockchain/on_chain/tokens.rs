@@ -4,6 +4,7 @@ use solana_sdk::{
     signature::{Keypair, Signature, Signer},
 };
 use crate::services::blockchain::instructions::InstructionBuilder;
+use crate::services::blockchain::priority_fee::TransactionType;
 use crate::services::blockchain::transactions::TransactionHandler;
 use tracing::info;
 
@@ -30,7 +31,7 @@ impl TokenManager {
     pub async fn mint_tokens(&self, authority: &Keypair, recipient: &str, amount: u64) -> Result<Signature> {
         info!("Minting {} tokens to {}...", amount, recipient);
         let instruction = self.instruction_builder.build_mint_instruction(recipient, amount)?;
-        self.transaction_handler.submit_transaction(solana_sdk::transaction::Transaction::new_with_payer(&[instruction], Some(&authority.pubkey()))).await
+        self.transaction_handler.submit_instructions(&[instruction], &authority.pubkey(), TransactionType::Minting).await
     }
 
     pub async fn transfer_tokens(
@@ -43,6 +44,6 @@ impl TokenManager {
     ) -> Result<Signature> {
         info!("Transferring {} tokens from {} to {}...", amount, from, to);
         let instruction = self.instruction_builder.build_transfer_instruction(from, to, amount, mint)?;
-        self.transaction_handler.submit_transaction(solana_sdk::transaction::Transaction::new_with_payer(&[instruction], Some(&authority.pubkey()))).await
+        self.transaction_handler.submit_instructions(&[instruction], &authority.pubkey(), TransactionType::TokenTransfer).await
     }
 }
@@ -4,6 +4,7 @@ use solana_sdk::{
     signature::{Keypair, Signature, Signer},
 };
 use crate::services::blockchain::instructions::InstructionBuilder;
+use crate::services::blockchain::priority_fee::TransactionType;
 use crate::services::blockchain::transactions::TransactionHandler;
 use tracing::info;
 
@@ -33,7 +34,7 @@ impl TradingManager {
         settlement_ix: solana_sdk::instruction::Instruction,
     ) -> Result<Signature> {
         info!("Executing atomic settlement on-chain...");
-        self.transaction_handler.submit_transaction(solana_sdk::transaction::Transaction::new_with_payer(&[settlement_ix], Some(&market_authority.pubkey()))).await
+        self.transaction_handler.submit_instructions(&[settlement_ix], &market_authority.pubkey(), TransactionType::Settlement).await
     }
 
     pub async fn swap_energy(
@@ -42,7 +43,7 @@ impl TradingManager {
         swap_ix: solana_sdk::instruction::Instruction,
     ) -> Result<Signature> {
         info!("Executing AMM swap on-chain...");
-        self.transaction_handler.submit_transaction(solana_sdk::transaction::Transaction::new_with_payer(&[swap_ix], Some(&user.pubkey()))).await
+        self.transaction_handler.submit_instructions(&[swap_ix], &user.pubkey(), TransactionType::Trading).await
     }
 
     pub async fn derive_order_pda(
@@ -83,7 +84,7 @@ impl TradingManager {
         let instruction = self.instruction_builder.build_create_order_instruction(
             market_address, &order_pda, side, quantity, price
         )?;
-        let sig = self.transaction_handler.submit_transaction(solana_sdk::transaction::Transaction::new_with_payer(&[instruction], Some(&authority.pubkey()))).await?;
+        let sig = self.transaction_handler.submit_instructions(&[instruction], &authority.pubkey(), TransactionType::Trading).await?;
         Ok((sig, order_pda.to_string()))
     }
 }
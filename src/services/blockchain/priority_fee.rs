@@ -4,6 +4,8 @@
 
 use anyhow::Result;
 use solana_client::rpc_client::RpcClient;
+use solana_sdk::compute_budget::ComputeBudgetInstruction;
+use solana_sdk::instruction::Instruction;
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 use tokio::sync::RwLock;
@@ -30,6 +32,78 @@ impl TransactionType {
             TransactionType::Other => 1.0,
         }
     }
+
+    /// Map the transaction labels used by callers onto a fee category
+    pub fn from_label(label: &str) -> Self {
+        match label {
+            "token_transaction" | "token_transfer" => TransactionType::TokenTransfer,
+            "minting" | "mint" => TransactionType::Minting,
+            "trading" | "order" => TransactionType::Trading,
+            "settlement" => TransactionType::Settlement,
+            _ => TransactionType::Other,
+        }
+    }
+}
+
+/// Compute budget settings applied to submitted transactions
+#[derive(Debug, Clone, PartialEq)]
+pub struct PriorityFeeConfig {
+    /// Base compute unit price in micro-lamports; 0 disables priority fees
+    pub compute_unit_price: u64,
+    /// Compute units requested per transaction; 0 keeps the runtime default
+    pub compute_unit_limit: u32,
+    /// Derive the base price from recent prioritization fees on the cluster,
+    /// falling back to `compute_unit_price` when none are reported
+    pub dynamic: bool,
+    /// Highest compute unit price we are willing to pay, in micro-lamports
+    pub max_compute_unit_price: u64,
+}
+
+impl Default for PriorityFeeConfig {
+    fn default() -> Self {
+        Self {
+            compute_unit_price: 10_000,
+            compute_unit_limit: 200_000,
+            dynamic: false,
+            max_compute_unit_price: 1_000_000,
+        }
+    }
+}
+
+impl PriorityFeeConfig {
+    /// Load from `SOLANA_COMPUTE_UNIT_PRICE`, `SOLANA_COMPUTE_UNIT_LIMIT`,
+    /// `SOLANA_PRIORITY_FEE_DYNAMIC` and `SOLANA_MAX_COMPUTE_UNIT_PRICE`
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        Self {
+            compute_unit_price: std::env::var("SOLANA_COMPUTE_UNIT_PRICE")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(defaults.compute_unit_price),
+            compute_unit_limit: std::env::var("SOLANA_COMPUTE_UNIT_LIMIT")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(defaults.compute_unit_limit),
+            dynamic: std::env::var("SOLANA_PRIORITY_FEE_DYNAMIC")
+                .map(|v| v == "true" || v == "1")
+                .unwrap_or(defaults.dynamic),
+            max_compute_unit_price: std::env::var("SOLANA_MAX_COMPUTE_UNIT_PRICE")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(defaults.max_compute_unit_price),
+        }
+    }
+
+    /// Whether transactions get a compute unit price at all
+    pub fn enabled(&self) -> bool {
+        self.compute_unit_price > 0
+    }
+
+    /// Price for `tx_type` given a base price, capped at the configured maximum
+    pub fn price_for(&self, base_price: u64, tx_type: TransactionType) -> u64 {
+        let adjusted = (base_price as f64 * tx_type.multiplier()) as u64;
+        adjusted.min(self.max_compute_unit_price)
+    }
 }
 
 /// Cached priority fee data
@@ -45,15 +119,13 @@ pub struct PriorityFeeService {
     rpc_client: Arc<RpcClient>,
     cache: Arc<RwLock<Option<CachedFee>>>,
     cache_ttl: Duration,
-    default_fee: u64,
-    min_fee: u64,
-    max_fee: u64,
+    config: PriorityFeeConfig,
 }
 
 impl std::fmt::Debug for PriorityFeeService {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("PriorityFeeService")
-            .field("default_fee", &self.default_fee)
+            .field("config", &self.config)
             .field("cache_ttl", &self.cache_ttl)
             .finish()
     }
@@ -62,33 +134,60 @@ impl std::fmt::Debug for PriorityFeeService {
 impl PriorityFeeService {
     /// Create a new priority fee service
     pub fn new(rpc_client: Arc<RpcClient>) -> Self {
+        Self::with_config(rpc_client, PriorityFeeConfig::default())
+    }
+
+    pub fn with_config(rpc_client: Arc<RpcClient>, config: PriorityFeeConfig) -> Self {
         Self {
             rpc_client,
             cache: Arc::new(RwLock::new(None)),
             cache_ttl: Duration::from_secs(10),
-            default_fee: 10_000,       // 0.00001 SOL = 10,000 micro-lamports
-            min_fee: 1_000,            // Minimum fee
-            max_fee: 1_000_000,        // Maximum fee (1 SOL in micro-lamports)
+            config,
         }
     }
 
-    /// Get the recommended priority fee for a transaction type
+    pub fn config(&self) -> &PriorityFeeConfig {
+        &self.config
+    }
+
+    /// Get the recommended priority fee for a transaction type, in micro-lamports
+    /// per compute unit
     pub async fn get_priority_fee(&self, tx_type: TransactionType) -> Result<u64> {
-        let base_fee = self.get_base_priority_fee().await?;
-        let multiplier = tx_type.multiplier();
-        let adjusted_fee = (base_fee as f64 * multiplier) as u64;
-        
-        // Clamp to min/max bounds
-        let final_fee = adjusted_fee.clamp(self.min_fee, self.max_fee);
-        
+        if !self.config.enabled() {
+            return Ok(0);
+        }
+
+        let base_fee = if self.config.dynamic {
+            self.get_base_priority_fee().await?
+        } else {
+            self.config.compute_unit_price
+        };
+        let final_fee = self.config.price_for(base_fee, tx_type);
+
         debug!(
-            "Priority fee for {:?}: base={}, multiplier={:.1}x, adjusted={}, final={}",
-            tx_type, base_fee, multiplier, adjusted_fee, final_fee
+            "Priority fee for {:?}: base={}, multiplier={:.1}x, final={}",
+            tx_type, base_fee, tx_type.multiplier(), final_fee
         );
-        
+
         Ok(final_fee)
     }
 
+    /// Compute budget instructions to prepend to a transaction of `tx_type`
+    pub async fn compute_budget_instructions(&self, tx_type: TransactionType) -> Vec<Instruction> {
+        let mut instructions = Vec::new();
+        if self.config.compute_unit_limit > 0 {
+            instructions.push(ComputeBudgetInstruction::set_compute_unit_limit(
+                self.config.compute_unit_limit,
+            ));
+        }
+
+        let price = self.get_priority_fee(tx_type).await.unwrap_or(0);
+        if price > 0 {
+            instructions.push(ComputeBudgetInstruction::set_compute_unit_price(price));
+        }
+        instructions
+    }
+
     /// Get base priority fee from network or cache
     async fn get_base_priority_fee(&self) -> Result<u64> {
         // Check cache first
@@ -122,8 +221,8 @@ impl PriorityFeeService {
         match self.rpc_client.get_recent_prioritization_fees(&[]) {
             Ok(fees) => {
                 if fees.is_empty() {
-                    info!("No recent priority fees found, using default: {}", self.default_fee);
-                    return Ok(self.default_fee);
+                    info!("No recent priority fees found, using default: {}", self.config.compute_unit_price);
+                    return Ok(self.config.compute_unit_price);
                 }
 
                 // Collect non-zero fees
@@ -134,14 +233,14 @@ impl PriorityFeeService {
                     .collect();
 
                 if fee_values.is_empty() {
-                    return Ok(self.default_fee);
+                    return Ok(self.config.compute_unit_price);
                 }
 
                 fee_values.sort();
 
                 // Calculate P75 (75th percentile) for reliability
                 let p75_index = (fee_values.len() * 75) / 100;
-                let p75_fee = fee_values.get(p75_index).copied().unwrap_or(self.default_fee);
+                let p75_fee = fee_values.get(p75_index).copied().unwrap_or(self.config.compute_unit_price);
 
                 // Add 20% buffer for reliability
                 let buffered_fee = p75_fee.saturating_mul(120) / 100;
@@ -158,7 +257,7 @@ impl PriorityFeeService {
             }
             Err(e) => {
                 warn!("Failed to fetch priority fees: {}, using default", e);
-                Ok(self.default_fee)
+                Ok(self.config.compute_unit_price)
             }
         }
     }
@@ -170,3 +269,29 @@ impl PriorityFeeService {
         debug!("Priority fee cache cleared");
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_price_scales_by_type_and_respects_cap() {
+        let config = PriorityFeeConfig {
+            max_compute_unit_price: 40_000,
+            ..PriorityFeeConfig::default()
+        };
+        assert_eq!(config.price_for(10_000, TransactionType::TokenTransfer), 10_000);
+        assert_eq!(config.price_for(10_000, TransactionType::Trading), 20_000);
+        assert_eq!(config.price_for(100_000, TransactionType::Settlement), 40_000);
+    }
+
+    #[test]
+    fn test_zero_price_disables_priority_fees() {
+        let config = PriorityFeeConfig {
+            compute_unit_price: 0,
+            ..PriorityFeeConfig::default()
+        };
+        assert!(!config.enabled());
+        assert_eq!(TransactionType::from_label("token_transaction"), TransactionType::TokenTransfer);
+    }
+}
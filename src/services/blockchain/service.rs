@@ -1,19 +1,21 @@
 use super::account_management::AccountManager;
 use super::instructions::InstructionBuilder;
 use super::on_chain::OnChainManager;
-use super::token_management::TokenManager as LegacyTokenManager;
-use super::transactions::TransactionHandler;
+use super::token_management::{TokenManager as LegacyTokenManager, TokenTransfer};
+use super::transactions::{TransactionHandler, TransactionStatus};
 use super::utils::BlockchainUtils;
-use crate::config::SolanaProgramsConfig;
+use crate::config::{format_config_errors, SolanaProgramsConfig, ValidatedSolanaPrograms};
 use anyhow::{anyhow, Result};
 use solana_client::rpc_client::RpcClient;
 use solana_sdk::{
+    program_pack::Pack,
     pubkey::Pubkey,
     signature::{Keypair, Signature, Signer},
     transaction::Transaction,
 };
+use std::collections::HashMap;
 use std::str::FromStr;
-use std::sync::Arc;
+use std::sync::{Arc, RwLock};
 use tracing::info;
 
 /// Blockchain service for interacting with Solana programs
@@ -21,12 +23,14 @@ use tracing::info;
 pub struct BlockchainService {
     rpc_client: Arc<RpcClient>,
     cluster: String,
-    program_ids: SolanaProgramsConfig,
+    program_ids: ValidatedSolanaPrograms,
     pub account_manager: AccountManager,
     on_chain_manager: OnChainManager,
     token_manager: LegacyTokenManager,
     transaction_handler: TransactionHandler,
     instruction_builder: InstructionBuilder,
+    /// Decimals per mint, seeded from config and filled from chain on first use
+    mint_decimals: Arc<RwLock<HashMap<Pubkey, u8>>>,
 }
 
 impl std::fmt::Debug for BlockchainService {
@@ -45,6 +49,10 @@ impl BlockchainService {
         program_ids: SolanaProgramsConfig,
     ) -> Result<Self> {
         info!("Initializing BlockchainService for cluster: {}", cluster);
+
+        let program_ids = program_ids.validated().map_err(|errors| {
+            anyhow!("Invalid Solana program IDs:\n{}", format_config_errors(&errors))
+        })?;
         
         let rpc_client = Arc::new(RpcClient::new(rpc_url));
         let transaction_handler = TransactionHandler::new(rpc_client.clone());
@@ -64,7 +72,7 @@ impl BlockchainService {
         let on_chain_manager = OnChainManager::new(
             transaction_handler.clone(),
             instruction_builder.clone(),
-            program_ids.clone(),
+            program_ids,
         );
 
         Ok(Self {
@@ -76,9 +84,42 @@ impl BlockchainService {
             token_manager,
             transaction_handler,
             instruction_builder,
+            mint_decimals: Arc::new(RwLock::new(HashMap::new())),
         })
     }
 
+    /// Register known decimals per mint so they are not read from chain
+    pub fn with_mint_decimals(self, decimals: impl IntoIterator<Item = (Pubkey, u8)>) -> Self {
+        self.mint_decimals
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .extend(decimals);
+        self
+    }
+
+    /// Decimals of `mint`, from the registry or read once from the mint account
+    pub async fn decimals_for(&self, mint: &Pubkey) -> Result<u8> {
+        if let Some(decimals) = self
+            .mint_decimals
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(mint)
+        {
+            return Ok(*decimals);
+        }
+
+        let data = self.get_account_data(mint).await?;
+        let decimals = mint_decimals_from_account_data(&data)
+            .map_err(|e| anyhow!("Account {} is not a token mint: {}", mint, e))?;
+        info!("Read {} decimals for mint {} from chain", decimals, mint);
+
+        self.mint_decimals
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(*mint, decimals);
+        Ok(decimals)
+    }
+
     pub fn client(&self) -> &RpcClient {
         &self.rpc_client
     }
@@ -95,6 +136,11 @@ impl BlockchainService {
         self.transaction_handler.confirm_transaction(signature).await
     }
 
+    /// Where `signature` stands on-chain; `Pending` when the cluster has not seen it
+    pub async fn get_transaction_status(&self, signature: &Signature) -> Result<TransactionStatus> {
+        self.transaction_handler.get_transaction_status(signature).await
+    }
+
     // --- Core On-Chain Delegation ---
 
     pub async fn initialize_registry(&self, authority: &Keypair) -> Result<Signature> {
@@ -191,11 +237,24 @@ impl BlockchainService {
         SigningManager::get_payer_keypair().await
     }
 
-    pub async fn mint_tokens_direct(&self, user_wallet: &Pubkey, amount_kwh: f64) -> Result<Signature> {
+    /// The configured authority keypair; unlike [`Self::get_authority_keypair`]
+    /// this fails instead of substituting an unfunded throwaway key
+    pub async fn load_configured_authority_keypair(&self) -> Result<Keypair> {
+        use super::transactions::SigningManager;
+        SigningManager::load_configured_payer_keypair().await
+    }
+
+    /// The `AUTHORITY_WALLET_PATH` keypair, which pays for minting and token
+    /// account creation
+    pub fn load_mint_authority_keypair(&self) -> Result<Keypair> {
         let authority_path = std::env::var("AUTHORITY_WALLET_PATH").unwrap_or_else(|_| "dev-wallet.json".to_string());
-        let authority = BlockchainUtils::load_keypair_from_file(&authority_path)?;
-        let mint = Pubkey::from_str(&std::env::var("ENERGY_TOKEN_MINT")?)?;
-        self.token_manager.mint_energy_tokens(&authority, user_wallet, user_wallet, &mint, amount_kwh).await
+        BlockchainUtils::load_keypair_from_file(&authority_path)
+    }
+
+    /// Mint `amount_atomic` energy tokens, already scaled to the mint's decimals
+    pub async fn mint_tokens_direct(&self, user_wallet: &Pubkey, amount_atomic: u64) -> Result<Signature> {
+        let authority = self.load_mint_authority_keypair()?;
+        self.token_manager.mint_energy_tokens_atomic(&authority, user_wallet, amount_atomic).await
     }
 
     /// Handles signature expected by minting handlers: authority, user_token_account, wallet_pubkey, token_mint, amount_kwh
@@ -249,12 +308,33 @@ impl BlockchainService {
         self.account_manager.account_exists(pubkey).await
     }
 
-    pub fn registry_program_id(&self) -> Result<Pubkey> {
-        Pubkey::from_str(&self.program_ids.registry_program_id).map_err(|e| anyhow!("Invalid Registry ID: {}", e))
+    pub fn calculate_ata_address(&self, wallet: &Pubkey, mint: &Pubkey) -> Result<Pubkey> {
+        self.account_manager.calculate_ata_address(wallet, mint)
+    }
+
+    /// Program IDs validated at construction
+    pub fn program_ids(&self) -> &ValidatedSolanaPrograms {
+        &self.program_ids
     }
 
-    pub fn trading_program_id(&self) -> Result<Pubkey> {
-        Pubkey::from_str(&self.program_ids.trading_program_id).map_err(|e| anyhow!("Invalid Trading ID: {}", e))
+    pub fn registry_program_id(&self) -> Pubkey {
+        self.program_ids.registry
+    }
+
+    pub fn oracle_program_id(&self) -> Pubkey {
+        self.program_ids.oracle
+    }
+
+    pub fn governance_program_id(&self) -> Pubkey {
+        self.program_ids.governance
+    }
+
+    pub fn energy_token_program_id(&self) -> Pubkey {
+        self.program_ids.energy_token
+    }
+
+    pub fn trading_program_id(&self) -> Pubkey {
+        self.program_ids.trading
     }
 
     pub async fn get_balance(&self, pubkey: &Pubkey) -> Result<u64> {
@@ -282,6 +362,15 @@ impl BlockchainService {
         self.token_manager.transfer_tokens(authority, from, to, mint, amount, decimals).await
     }
 
+    /// Submit several token transfers in a single transaction
+    pub async fn transfer_tokens_batch(
+        &self,
+        transfers: &[TokenTransfer],
+        signers: &[&Keypair],
+    ) -> Result<Signature> {
+        self.token_manager.transfer_tokens_batch(transfers, signers).await
+    }
+
     pub async fn request_airdrop(&self, pubkey: &Pubkey, lamports: u64) -> Result<Signature> {
         self.rpc_client.request_airdrop(pubkey, lamports).map_err(|e| anyhow!("Airdrop failed: {}", e))
     }
@@ -294,3 +383,45 @@ impl BlockchainService {
         self.rpc_client.get_latest_blockhash().map_err(|e| anyhow!("Failed to get blockhash: {}", e))
     }
 }
+
+/// Decimals of an SPL Token or Token-2022 mint; both share the base mint
+/// layout, with Token-2022 extensions appended after it
+fn mint_decimals_from_account_data(data: &[u8]) -> Result<u8> {
+    let base = data
+        .get(..spl_token::state::Mint::LEN)
+        .ok_or_else(|| anyhow!("account data is {} bytes", data.len()))?;
+    let mint = spl_token::state::Mint::unpack_from_slice(base)?;
+    if !mint.is_initialized {
+        return Err(anyhow!("mint is not initialized"));
+    }
+    Ok(mint.decimals)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_sdk::program_option::COption;
+
+    fn mint_data(decimals: u8, extension_bytes: usize) -> Vec<u8> {
+        let mint = spl_token::state::Mint {
+            mint_authority: COption::Some(Pubkey::new_unique()),
+            supply: 1_000,
+            decimals,
+            is_initialized: true,
+            freeze_authority: COption::None,
+        };
+        let mut data = vec![0u8; spl_token::state::Mint::LEN];
+        spl_token::state::Mint::pack(mint, &mut data).unwrap();
+        data.resize(data.len() + extension_bytes, 0);
+        data
+    }
+
+    #[test]
+    fn test_mint_decimals_read_from_base_layout() {
+        assert_eq!(mint_decimals_from_account_data(&mint_data(6, 0)).unwrap(), 6);
+        // Token-2022 mints carry extensions after the base layout
+        assert_eq!(mint_decimals_from_account_data(&mint_data(9, 83)).unwrap(), 9);
+        assert!(mint_decimals_from_account_data(&[0u8; 10]).is_err());
+        assert!(mint_decimals_from_account_data(&[0u8; 82]).is_err());
+    }
+}
@@ -4,10 +4,25 @@ use solana_sdk::signature::{Keypair, Signature};
 use std::str::FromStr;
 use std::time::Duration; // Added Duration
 
+use crate::services::blockchain::errors::SettlementError;
 use crate::services::blockchain::account_management::AccountManager; // Dependency
+use crate::services::blockchain::instructions::tokens::TokenInstructions;
 use crate::services::blockchain::transactions::TransactionHandler;
+use crate::services::blockchain::priority_fee::TransactionType;
 use crate::services::blockchain::utils::BlockchainUtils;
 
+/// One `transfer_checked` leg of a multi-transfer transaction
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TokenTransfer {
+    /// Owner of `from`; must be among the transaction signers
+    pub owner: Pubkey,
+    pub from: Pubkey,
+    pub to: Pubkey,
+    pub mint: Pubkey,
+    pub amount: u64,
+    pub decimals: u8,
+}
+
 /// Manages Token operations (mint, burn, transfer)
 #[derive(Clone, Debug)]
 pub struct TokenManager {
@@ -120,11 +135,21 @@ impl TokenManager {
         _mint: &Pubkey, // Not used directly - we derive from program
         amount_kwh: f64,
     ) -> Result<Signature> {
-        use solana_sdk::instruction::Instruction;
-        use solana_sdk::signature::Signer;
-
         // Convert kWh to token amount (with 9 decimals)
         let amount_lamports = (amount_kwh * 1_000_000_000.0) as u64;
+        self.mint_energy_tokens_atomic(authority, user_wallet, amount_lamports).await
+    }
+
+    /// Mint an amount already in the mint's atomic units to a user's token
+    /// account via the Anchor program
+    pub async fn mint_energy_tokens_atomic(
+        &self,
+        authority: &Keypair,
+        user_wallet: &Pubkey,
+        amount_lamports: u64,
+    ) -> Result<Signature> {
+        use solana_sdk::instruction::Instruction;
+        use solana_sdk::signature::Signer;
 
         // Get energy token program ID from environment (with fallback to deployed program ID)
         let energy_token_program_id = std::env::var("SOLANA_ENERGY_TOKEN_PROGRAM_ID")
@@ -366,6 +391,34 @@ impl TokenManager {
             .await
     }
 
+    /// Execute several token transfers atomically in one transaction
+    ///
+    /// `signers[0]` pays the fee; every transfer owner must also be in
+    /// `signers`. The caller is responsible for keeping the transaction
+    /// within Solana's size limit.
+    pub async fn transfer_tokens_batch(
+        &self,
+        transfers: &[TokenTransfer],
+        signers: &[&Keypair],
+    ) -> Result<Signature> {
+        if transfers.is_empty() {
+            return Err(anyhow!("No transfers to submit"));
+        }
+
+        let instructions = transfers
+            .iter()
+            .map(|t| {
+                TokenInstructions::build_spl_transfer_instruction(
+                    t.owner, t.from, t.to, t.mint, t.amount, t.decimals,
+                )
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        self.transaction_handler
+            .build_and_send_transaction_with_priority(instructions, signers, "token_transaction")
+            .await
+    }
+
     /// Transfer energy tokens between accounts
     pub async fn transfer_energy_tokens(
         &self,
@@ -436,7 +489,22 @@ impl TokenManager {
         // The settlement should be updated to pass owner, not ATA. 
         // For now, let's just use a different approach - transfer using from account directly.
         
-        let output = std::process::Command::new("spl-token")
+        let priority_fees = self.transaction_handler.priority_fees();
+        let compute_unit_price = priority_fees
+            .get_priority_fee(TransactionType::Settlement)
+            .await
+            .unwrap_or(0);
+        let compute_unit_limit = priority_fees.config().compute_unit_limit;
+
+        let mut command = std::process::Command::new("spl-token");
+        if compute_unit_price > 0 {
+            command.arg("--with-compute-unit-price").arg(compute_unit_price.to_string());
+        }
+        if compute_unit_limit > 0 {
+            command.arg("--with-compute-unit-limit").arg(compute_unit_limit.to_string());
+        }
+
+        let output = command
             .arg("transfer")
             .arg(mint.to_string())
             .arg(ui_amount.to_string())
@@ -462,7 +530,8 @@ impl TokenManager {
         let stderr_str = String::from_utf8_lossy(&output.stderr);
         
         if !output.status.success() {
-            return Err(anyhow!("spl-token transfer failed: {}", stderr_str));
+            tracing::error!("spl-token transfer failed: {}", stderr_str);
+            return Err(SettlementError::from_cli_output(&stderr_str).into());
         }
         
         // Extract signature from output
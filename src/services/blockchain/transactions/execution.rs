@@ -1,51 +1,101 @@
 use anyhow::{anyhow, Result};
-use solana_sdk::signature::Signature;
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::hash::Hash;
+use solana_sdk::signature::{Keypair, Signature};
 use solana_sdk::transaction::Transaction;
 use std::time::Duration;
-use tracing::{debug, error, info, warn};
+use tracing::{error, info, warn};
 use super::pool::ConnectionPool;
-use super::signing::SigningManager;
+use crate::services::blockchain::errors::{AttemptedSignatures, SettlementError};
+
+/// Attempts made before a submission is reported as failed
+const MAX_ATTEMPTS: u32 = 5;
+const BASE_DELAY_MS: u64 = 500;
+const MAX_DELAY_MS: u64 = 30_000;
+
+/// The RPC calls a submission needs, abstracted so retries can be exercised
+/// without a validator
+///
+/// Failures should carry a [`SettlementError`] so retries are decided on
+/// the classified error.
+pub trait TransactionSender {
+    fn latest_blockhash(&self) -> Result<Hash>;
+    fn send_and_confirm(&self, transaction: &Transaction) -> Result<Signature>;
+}
+
+impl TransactionSender for RpcClient {
+    fn latest_blockhash(&self) -> Result<Hash> {
+        self.get_latest_blockhash()
+            .map_err(|e| anyhow::Error::new(SettlementError::from(e)))
+    }
+
+    fn send_and_confirm(&self, transaction: &Transaction) -> Result<Signature> {
+        self.send_and_confirm_transaction(transaction)
+            .map_err(|e| anyhow::Error::new(SettlementError::from(e)))
+    }
+}
 
 pub struct ExecutionManager;
 
 impl ExecutionManager {
+    /// Submit `transaction` signed by `signers` (fee payer first), retrying
+    /// transient failures
     pub async fn submit_with_retry(
         pool: &ConnectionPool,
+        transaction: Transaction,
+        signers: &[&Keypair],
+    ) -> Result<Signature> {
+        let conn = pool.get_connection().await;
+        let result = Self::send_with_retry(conn.as_ref(), transaction, signers).await;
+        pool.return_connection(conn).await;
+        result
+    }
+
+    /// Every attempt fetches a fresh blockhash and re-signs with all
+    /// `signers`, so a retry never reuses the blockhash that expired under
+    /// the previous attempt. Expired-blockhash failures are retried
+    /// immediately, other retryable failures back off exponentially and
+    /// permanent ones are returned at once as a [`SettlementError`]. A
+    /// returned error carries the [`AttemptedSignatures`] of every attempt.
+    pub async fn send_with_retry<S: TransactionSender + ?Sized>(
+        sender: &S,
         mut transaction: Transaction,
+        signers: &[&Keypair],
     ) -> Result<Signature> {
         let mut attempts = 0;
-        let max_retries = 5;
-        let base_delay_ms = 500u64;
-        let max_delay_ms = 30_000u64;
+        let mut attempted = Vec::new();
 
         loop {
             attempts += 1;
-            
-            let conn = pool.get_connection().await;
-            let recent_blockhash = conn.get_latest_blockhash().map_err(|e| anyhow!("Retry blockhash failed: {}", e))?;
-            transaction.message.recent_blockhash = recent_blockhash;
-            
-            let payer = SigningManager::get_payer_keypair().await?;
-            transaction.try_sign(&[&payer], recent_blockhash).map_err(|e| anyhow!("Retry sign failed: {}", e))?;
-
-            match conn.send_and_confirm_transaction(&transaction) {
+
+            let recent_blockhash = match sender.latest_blockhash() {
+                Ok(hash) => hash,
+                Err(e) => return Err(AttemptedSignatures(attempted).attach(SettlementError::from_anyhow(&e))),
+            };
+            transaction
+                .try_sign(signers, recent_blockhash)
+                .map_err(|e| anyhow!("Retry sign failed: {}", e))?;
+            attempted.push(transaction.signatures[0]);
+
+            let err = match sender.send_and_confirm(&transaction) {
                 Ok(sig) => {
                     info!("Transaction submitted successfully on attempt {}", attempts);
-                    pool.return_connection(conn).await;
                     return Ok(sig);
                 }
-                Err(e) => {
-                    pool.return_connection(conn).await;
-                    let err_str = e.to_string();
-                    error!("Attempt {} failed: {}", attempts, err_str);
-
-                    if err_str.contains("insufficient funds") || attempts >= max_retries {
-                        return Err(anyhow!("Transaction failed: {}", e));
-                    }
-                }
+                Err(e) => SettlementError::from_anyhow(&e),
+            };
+            error!("Attempt {} failed: {}", attempts, err);
+
+            if !err.is_retryable() || attempts >= MAX_ATTEMPTS {
+                return Err(AttemptedSignatures(attempted).attach(err));
+            }
+
+            if err == SettlementError::BlockhashExpired {
+                warn!("Blockhash expired before the transaction landed, re-signing with a fresh one");
+                continue;
             }
 
-            let delay = Duration::from_millis(capped_backoff(attempts, base_delay_ms, max_delay_ms));
+            let delay = Duration::from_millis(capped_backoff(attempts, BASE_DELAY_MS, MAX_DELAY_MS));
             tokio::time::sleep(delay).await;
         }
     }
@@ -56,3 +106,104 @@ fn capped_backoff(attempts: u32, base: u64, max: u64) -> u64 {
     let jitter = rand::random::<u64>() % (exp / 4 + 1);
     exp.min(max) + jitter
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_sdk::instruction::{AccountMeta, Instruction};
+    use solana_sdk::pubkey::Pubkey;
+    use solana_sdk::signature::Signer;
+    use std::sync::Mutex;
+
+    /// Hands out a stale blockhash first and rejects transactions signed with it
+    struct StaleBlockhashSender {
+        stale: Hash,
+        fresh: Hash,
+        fetched: Mutex<u32>,
+        sent: Mutex<Vec<Hash>>,
+    }
+
+    impl TransactionSender for StaleBlockhashSender {
+        fn latest_blockhash(&self) -> Result<Hash> {
+            let mut fetched = self.fetched.lock().unwrap();
+            *fetched += 1;
+            Ok(if *fetched == 1 { self.stale } else { self.fresh })
+        }
+
+        fn send_and_confirm(&self, transaction: &Transaction) -> Result<Signature> {
+            self.sent.lock().unwrap().push(transaction.message.recent_blockhash);
+            if transaction.message.recent_blockhash != self.fresh {
+                return Err(SettlementError::BlockhashExpired.into());
+            }
+            transaction.verify().map_err(|e| anyhow!("{}", e))?;
+            Ok(transaction.signatures[0])
+        }
+    }
+
+    #[tokio::test]
+    async fn test_stale_blockhash_is_resigned_and_retried() {
+        let payer = Keypair::new();
+        let seller = Keypair::new();
+        let instruction = Instruction::new_with_bytes(
+            Pubkey::new_unique(),
+            &[1],
+            vec![
+                AccountMeta::new(payer.pubkey(), true),
+                AccountMeta::new(seller.pubkey(), true),
+            ],
+        );
+        let transaction = Transaction::new_with_payer(&[instruction], Some(&payer.pubkey()));
+        let sender = StaleBlockhashSender {
+            stale: Hash::new_unique(),
+            fresh: Hash::new_unique(),
+            fetched: Mutex::new(0),
+            sent: Mutex::new(Vec::new()),
+        };
+
+        let signature = ExecutionManager::send_with_retry(&sender, transaction, &[&payer, &seller])
+            .await
+            .expect("retry with a fresh blockhash should land");
+
+        assert_ne!(signature, Signature::default());
+        assert_eq!(*sender.sent.lock().unwrap(), vec![sender.stale, sender.fresh]);
+    }
+
+    /// Rejects every transaction as unaffordable
+    struct BrokeSender {
+        sent: Mutex<u32>,
+    }
+
+    impl TransactionSender for BrokeSender {
+        fn latest_blockhash(&self) -> Result<Hash> {
+            Ok(Hash::new_unique())
+        }
+
+        fn send_and_confirm(&self, _transaction: &Transaction) -> Result<Signature> {
+            *self.sent.lock().unwrap() += 1;
+            Err(SettlementError::InsufficientFunds("fee payer".to_string()).into())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_permanent_failure_is_not_retried() {
+        let payer = Keypair::new();
+        let instruction = Instruction::new_with_bytes(
+            Pubkey::new_unique(),
+            &[1],
+            vec![AccountMeta::new(payer.pubkey(), true)],
+        );
+        let transaction = Transaction::new_with_payer(&[instruction], Some(&payer.pubkey()));
+        let sender = BrokeSender { sent: Mutex::new(0) };
+
+        let err = ExecutionManager::send_with_retry(&sender, transaction, &[&payer])
+            .await
+            .unwrap_err();
+
+        assert!(matches!(
+            SettlementError::from_anyhow(&err),
+            SettlementError::InsufficientFunds(_)
+        ));
+        assert_eq!(AttemptedSignatures::from_anyhow(&err).len(), 1);
+        assert_eq!(*sender.sent.lock().unwrap(), 1);
+    }
+}
@@ -1,6 +1,7 @@
 use anyhow::Result;
 use solana_client::rpc_client::RpcClient;
 use solana_sdk::{
+    instruction::Instruction,
     pubkey::Pubkey,
     signature::{Keypair, Signature, Signer},
     transaction::Transaction,
@@ -8,6 +9,8 @@ use solana_sdk::{
 use std::sync::Arc;
 use tracing::info;
 
+use super::priority_fee::{PriorityFeeConfig, PriorityFeeService, TransactionType};
+
 pub mod pool;
 pub mod signing;
 pub mod validation;
@@ -18,7 +21,7 @@ pub mod confirmation;
 pub use pool::ConnectionPool;
 pub use signing::SigningManager;
 pub use validation::ValidationManager;
-pub use execution::ExecutionManager;
+pub use execution::{ExecutionManager, TransactionSender};
 pub use queries::QueryManager;
 pub use confirmation::{ConfirmationManager, TransactionStatus};
 
@@ -31,6 +34,7 @@ pub use confirmation::TransactionStatus as FeeEstimate; // Fake alias
 pub struct TransactionHandler {
     pool: Arc<ConnectionPool>,
     queries: Arc<QueryManager>,
+    priority_fees: PriorityFeeService,
 }
 
 impl std::fmt::Debug for TransactionHandler {
@@ -44,12 +48,31 @@ impl std::fmt::Debug for TransactionHandler {
 impl TransactionHandler {
     pub fn new(rpc_client: Arc<RpcClient>) -> Self {
         info!("Initializing modularized transaction handler");
+        let priority_fees = PriorityFeeService::with_config(rpc_client.clone(), PriorityFeeConfig::from_env());
         Self {
             pool: Arc::new(ConnectionPool::new(rpc_client)),
             queries: Arc::new(QueryManager::new()),
+            priority_fees,
         }
     }
 
+    pub fn priority_fees(&self) -> &PriorityFeeService {
+        &self.priority_fees
+    }
+
+    /// Build a transaction from `instructions` with compute budget
+    /// instructions for `tx_type` prepended, and submit it
+    pub async fn submit_instructions(
+        &self,
+        instructions: &[Instruction],
+        payer: &Pubkey,
+        tx_type: TransactionType,
+    ) -> Result<Signature> {
+        let mut all = self.priority_fees.compute_budget_instructions(tx_type).await;
+        all.extend_from_slice(instructions);
+        self.submit_transaction(Transaction::new_with_payer(&all, Some(payer))).await
+    }
+
     pub async fn submit_transaction(&self, mut transaction: Transaction) -> Result<Signature> {
         let conn = self.pool.get_connection().await;
         let recent_blockhash = self.queries.get_recent_blockhash(conn.clone()).await?;
@@ -62,7 +85,7 @@ impl TransactionHandler {
         let payer = SigningManager::get_payer_keypair().await?;
         let signature = SigningManager::sign_transaction(&mut transaction, &payer, recent_blockhash).await?;
 
-        ExecutionManager::submit_with_retry(&self.pool, transaction).await
+        ExecutionManager::submit_with_retry(&self.pool, transaction, &[&payer]).await
     }
 
     pub async fn get_balance(&self, pubkey: &Pubkey) -> Result<u64> {
@@ -83,6 +106,10 @@ impl TransactionHandler {
         ConfirmationManager::confirm_transaction(self.pool.arc_client(), signature).await
     }
 
+    pub async fn get_transaction_status(&self, signature: &Signature) -> Result<TransactionStatus> {
+        ConfirmationManager::get_transaction_status(self.pool.arc_client(), signature).await
+    }
+
     pub async fn confirm_transaction_with_polling(&self, signature: &Signature, timeout: u64, interval: u64) -> Result<TransactionStatus> {
         ConfirmationManager::confirm_transaction_with_polling(self.pool.arc_client(), signature, timeout, interval).await
     }
@@ -106,24 +133,24 @@ impl TransactionHandler {
         instructions: Vec<solana_sdk::instruction::Instruction>,
         signers: &[&Keypair],
     ) -> Result<Signature> {
-        let conn = self.pool.get_connection().await;
-        let recent_blockhash = self.queries.get_recent_blockhash(conn.clone()).await?;
-        self.pool.return_connection(conn).await;
-
-        let mut transaction = Transaction::new_with_payer(&instructions, Some(&signers[0].pubkey()));
-        transaction.sign(signers, recent_blockhash);
+        // Signed with a fresh blockhash on every submission attempt
+        let transaction = Transaction::new_with_payer(&instructions, Some(&signers[0].pubkey()));
 
-        ExecutionManager::submit_with_retry(&self.pool, transaction).await
+        ExecutionManager::submit_with_retry(&self.pool, transaction, signers).await
     }
 
     pub async fn build_and_send_transaction_with_priority(
         &self,
         instructions: Vec<solana_sdk::instruction::Instruction>,
         signers: &[&Keypair],
-        _transaction_type: &'static str,
+        transaction_type: &'static str,
     ) -> Result<Signature> {
-        // For now, just call the regular method
-        self.build_and_send_transaction(instructions, signers).await
+        let mut all = self
+            .priority_fees
+            .compute_budget_instructions(TransactionType::from_label(transaction_type))
+            .await;
+        all.extend(instructions);
+        self.build_and_send_transaction(all, signers).await
     }
 
     pub fn client(&self) -> &RpcClient {
@@ -23,6 +23,16 @@ impl SigningManager {
     }
 
     pub async fn get_payer_keypair() -> Result<Keypair> {
+        if let Ok(keypair) = Self::load_configured_payer_keypair().await {
+            return Ok(keypair);
+        }
+
+        warn!("Using fallback keypair - set PAYER_PRIVATE_KEY for production");
+        Ok(Keypair::new())
+    }
+
+    /// The configured payer keypair, without falling back to a throwaway key
+    pub async fn load_configured_payer_keypair() -> Result<Keypair> {
         // Try loading from secure storage first
         if let Ok(keypair) = Self::load_payer_keypair().await {
             return Ok(keypair);
@@ -43,8 +53,9 @@ impl SigningManager {
             }
         }
 
-        warn!("Using fallback keypair - set PAYER_PRIVATE_KEY for production");
-        Ok(Keypair::new())
+        Err(anyhow!(
+            "No payer keypair configured: provide payer.json in secure storage or set PAYER_PRIVATE_KEY"
+        ))
     }
 
     async fn load_payer_keypair() -> Result<Keypair> {
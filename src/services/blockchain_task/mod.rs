@@ -143,11 +143,13 @@ impl BlockchainTaskService {
                 // However, `MarketClearingService` methods are currently private/super-only for some parts.
                 // We might need to make `execute_escrow_refund` public or `pub(crate)`.
                 
-                let sig = self.market_clearing_service
-                    .execute_escrow_refund_retry(&data.user_id, data.amount, &data.asset_type)
-                    .await?;
-                
-                info!("Escrow refund executed via retry queue: {}", sig);
+                match self.market_clearing_service
+                    .execute_escrow_refund_retry(&data.user_id, data.order_id, data.amount, &data.asset_type)
+                    .await?
+                {
+                    Some(sig) => info!("Escrow refund executed via retry queue: {}", sig),
+                    None => info!("Escrow for order {} is held off-chain, nothing to refund", data.order_id),
+                }
                 Ok(())
             }
             _ => Err(anyhow::anyhow!("Unsupported task type or payload mismatch")),
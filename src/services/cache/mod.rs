@@ -1,3 +1,5 @@
+use std::future::Future;
+
 use anyhow::Result;
 use redis::aio::ConnectionManager;
 use redis::{AsyncCommands, Client, RedisResult};
@@ -14,6 +16,14 @@ pub struct CacheService {
     default_ttl: u64, // Default TTL in seconds
 }
 
+impl std::fmt::Debug for CacheService {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CacheService")
+            .field("default_ttl", &self.default_ttl)
+            .finish_non_exhaustive()
+    }
+}
+
 impl CacheService {
     /// Create new cache service instance
     pub async fn new(redis_url: &str) -> Result<Self> {
@@ -174,6 +184,108 @@ impl CacheService {
         Ok(value)
     }
 
+    /// Return the cached value for `key`, or run `load` and cache its result
+    ///
+    /// Redis errors count as a miss and a failed write is only logged, so an
+    /// outage degrades to querying the database on every call.
+    pub async fn get_or_load<T, E, F, Fut>(
+        &self,
+        key: &str,
+        ttl_seconds: u64,
+        load: F,
+    ) -> std::result::Result<T, E>
+    where
+        T: Serialize + for<'de> Deserialize<'de>,
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = std::result::Result<T, E>>,
+    {
+        if let Ok(Some(cached)) = self.get::<T>(key).await {
+            return Ok(cached);
+        }
+
+        let value = load().await?;
+        if let Err(e) = self.set_with_ttl(key, &value, ttl_seconds).await {
+            warn!("Failed to cache {}: {}", key, e);
+        }
+        Ok(value)
+    }
+
+    /// Current generation of a cache namespace, 0 if unset or unreadable
+    ///
+    /// Keys embedding the generation are invalidated together by
+    /// [`Self::bump_generation`]; the orphaned entries expire with their TTL.
+    pub async fn generation(&self, namespace: &str) -> u64 {
+        self.get::<u64>(&CacheKeys::generation(namespace))
+            .await
+            .ok()
+            .flatten()
+            .unwrap_or(0)
+    }
+
+    /// Invalidate every key built from the namespace's current generation
+    pub async fn bump_generation(&self, namespace: &str) {
+        if let Err(e) = self.increment(&CacheKeys::generation(namespace)).await {
+            warn!("Failed to invalidate cache namespace {}: {}", namespace, e);
+        }
+    }
+
+    /// Take the lock at `key` for `ttl_ms` unless someone else holds it;
+    /// `token` identifies the holder for renewal and release
+    pub async fn try_acquire_lock(&self, key: &str, token: &str, ttl_ms: u64) -> Result<bool> {
+        let mut conn = self.connection_manager.clone();
+
+        let result: RedisResult<Option<String>> = redis::cmd("SET")
+            .arg(key)
+            .arg(token)
+            .arg("NX")
+            .arg("PX")
+            .arg(ttl_ms)
+            .query_async(&mut conn)
+            .await;
+
+        result
+            .map(|reply| reply.is_some())
+            .map_err(|e| anyhow::anyhow!("Redis SET NX failed for {}: {}", key, e))
+    }
+
+    /// Extend the lock at `key` by `ttl_ms` if `token` still holds it
+    pub async fn renew_lock(&self, key: &str, token: &str, ttl_ms: u64) -> Result<bool> {
+        let mut conn = self.connection_manager.clone();
+
+        let script = redis::Script::new(
+            r#"
+            if redis.call("GET", KEYS[1]) == ARGV[1] then
+                return redis.call("PEXPIRE", KEYS[1], ARGV[2])
+            end
+            return 0
+            "#,
+        );
+        let result: RedisResult<i32> = script.key(key).arg(token).arg(ttl_ms).invoke_async(&mut conn).await;
+
+        result
+            .map(|renewed| renewed == 1)
+            .map_err(|e| anyhow::anyhow!("Redis lock renewal failed for {}: {}", key, e))
+    }
+
+    /// Release the lock at `key` if `token` still holds it
+    pub async fn release_lock(&self, key: &str, token: &str) -> Result<bool> {
+        let mut conn = self.connection_manager.clone();
+
+        let script = redis::Script::new(
+            r#"
+            if redis.call("GET", KEYS[1]) == ARGV[1] then
+                return redis.call("DEL", KEYS[1])
+            end
+            return 0
+            "#,
+        );
+        let result: RedisResult<i32> = script.key(key).arg(token).invoke_async(&mut conn).await;
+
+        result
+            .map(|released| released == 1)
+            .map_err(|e| anyhow::anyhow!("Redis lock release failed for {}: {}", key, e))
+    }
+
     /// Clear all cache (DANGEROUS - use with caution)
     pub async fn flush_all(&self) -> Result<()> {
         warn!("⚠️  Flushing all cache data!");
@@ -279,6 +391,16 @@ impl CacheService {
 pub struct CacheKeys;
 
 impl CacheKeys {
+    /// Namespace for order book depth snapshots
+    pub const ORDER_BOOK: &'static str = "orderbook";
+    /// Namespace for cleared/settled market epoch listings
+    pub const MARKET_EPOCHS: &'static str = "market:epochs";
+
+    /// Generation counter of a cache namespace
+    pub fn generation(namespace: &str) -> String {
+        format!("cache:gen:{}", namespace)
+    }
+
     /// Market epoch cache key
     pub fn market_epoch() -> String {
         "market:current_epoch".to_string()
@@ -299,6 +421,24 @@ impl CacheKeys {
         format!("orderbook:{}", market_id)
     }
 
+    /// Order book depth cache key
+    pub fn order_book_depth(generation: u64, levels: usize, zone_id: Option<i32>) -> String {
+        match zone_id {
+            Some(zone) => format!("orderbook:depth:{}:{}:{}", generation, levels, zone),
+            None => format!("orderbook:depth:{}:{}:all", generation, levels),
+        }
+    }
+
+    /// Recent market epochs cache key
+    pub fn market_epochs(generation: u64, limit: i64) -> String {
+        format!("market:epochs:{}:{}", generation, limit)
+    }
+
+    /// Settlement statistics cache key
+    pub fn settlement_stats() -> String {
+        "settlement:stats".to_string()
+    }
+
     /// Market statistics cache key
     pub fn market_stats(epoch_id: &str) -> String {
         format!("market:stats:{}", epoch_id)
@@ -314,6 +454,16 @@ impl CacheKeys {
         format!("settlement:{}", settlement_id)
     }
 
+    /// Lease held by the instance running the singleton background jobs
+    pub fn background_jobs_leader() -> String {
+        "leader:background-jobs".to_string()
+    }
+
+    /// Denylist entry for a revoked access token
+    pub fn revoked_token(jti: &Uuid) -> String {
+        format!("auth:revoked:{}", jti)
+    }
+
     /// ERC certificate cache key
     pub fn erc_certificate(certificate_id: &str) -> String {
         format!("erc:certificate:{}", certificate_id)
@@ -346,4 +496,17 @@ mod tests {
         assert!(wallet_key.contains("user:wallet"));
         assert!(wallet_key.contains(&user_id.to_string()));
     }
+
+    #[test]
+    fn test_depth_keys_change_with_generation() {
+        assert_ne!(
+            CacheKeys::order_book_depth(1, 20, None),
+            CacheKeys::order_book_depth(2, 20, None)
+        );
+        assert_ne!(
+            CacheKeys::order_book_depth(1, 20, None),
+            CacheKeys::order_book_depth(1, 20, Some(3))
+        );
+        assert_eq!(CacheKeys::generation(CacheKeys::ORDER_BOOK), "cache:gen:orderbook");
+    }
 }
@@ -3,13 +3,17 @@ pub mod types;
 use tracing::info;
 use std::sync::Arc;
 use tokio::sync::RwLock;
+use tokio_util::sync::CancellationToken;
+use tokio_util::task::TaskTracker;
 use chrono::Utc;
 use crate::services::websocket::WebSocketService;
 use crate::services::event_processor::EventProcessorService;
+use crate::services::sustainability::SustainabilityService;
 use crate::services::health_check::HealthChecker;
+use crate::services::job_coordinator::BackgroundJobCoordinator;
 use crate::services::transaction::metrics::MetricsExporter;
 use std::collections::HashMap;
-pub use types::{DashboardMetrics, GridStatus, ZoneGridStatus};
+pub use types::{DashboardDelta, DashboardMetrics, GridStatus, ZoneGridStatus};
 use crate::services::websocket::types::ZoneStatus as WsZoneStatus;
 
 #[derive(Clone)]
@@ -19,6 +23,12 @@ pub struct DashboardService {
     event_processor: EventProcessorService,
     websocket_service: WebSocketService,
     metrics: Arc<RwLock<GridStatus>>,
+    /// Last result of the background metrics refresh
+    cached_metrics: Arc<RwLock<Option<DashboardMetrics>>>,
+    /// Source of the CO2-avoided figure; without it the figure stays at zero
+    sustainability: Option<SustainabilityService>,
+    /// Only the lead replica writes history snapshots
+    job_coordinator: Option<BackgroundJobCoordinator>,
 }
 
 impl DashboardService {
@@ -46,9 +56,24 @@ impl DashboardService {
                 meter_generation: HashMap::new(),
                 meter_consumption: HashMap::new(),
             })),
+            cached_metrics: Arc::new(RwLock::new(None)),
+            sustainability: None,
+            job_coordinator: None,
         }
     }
 
+    /// Report CO2 avoided by renewable generation in the grid status
+    pub fn with_sustainability(mut self, sustainability: SustainabilityService) -> Self {
+        self.sustainability = Some(sustainability);
+        self
+    }
+
+    /// Record grid history only while this replica leads
+    pub fn with_job_coordinator(mut self, job_coordinator: BackgroundJobCoordinator) -> Self {
+        self.job_coordinator = Some(job_coordinator);
+        self
+    }
+
     /// Handle a new meter reading to update aggregate grid status and broadcast
     pub async fn handle_meter_reading(
         &self, 
@@ -70,10 +95,7 @@ impl DashboardService {
         metrics.total_generation = metrics.total_generation - old_gen + power_gen;
         metrics.total_consumption = metrics.total_consumption - old_cons + power_cons;
 
-        // Keep CO2 saved as cumulative based on Energy (kWh)
-        if kwh > 0.0 {
-            metrics.co2_saved_kg += kwh * 0.431;
-        }
+        // co2_saved_kg is refreshed from renewable readings by the history recorder
 
         // 2. Update Zone-specific Metrics
         if let Some(zid) = zone_id {
@@ -109,7 +131,6 @@ impl DashboardService {
         metrics.active_meters = metrics.active_meter_ids.len() as i64;
 
         metrics.net_balance = metrics.total_generation - metrics.total_consumption;
-        // metrics.co2_saved_kg updated above cumulatively
         metrics.timestamp = Utc::now();
 
         // Broadcast to all connected clients
@@ -183,7 +204,19 @@ impl DashboardService {
             
             loop {
                 interval.tick().await;
+
+                if let Some(sustainability) = &self_clone.sustainability {
+                    match sustainability.total_co2_avoided_kg().await {
+                        Ok(co2) => self_clone.metrics.write().await.co2_saved_kg = co2,
+                        Err(e) => tracing::warn!("⚠️ Failed to refresh CO2 avoided: {}", e),
+                    }
+                }
                 
+                // Every replica would otherwise insert the same snapshot
+                if self_clone.job_coordinator.as_ref().is_some_and(|c| !c.is_leader()) {
+                    continue;
+                }
+
                 let current = self_clone.get_grid_status().await;
                 let snapshot_time = Utc::now();
                 let zones_json = serde_json::to_value(&current.zones).unwrap_or(serde_json::Value::Null);
@@ -210,7 +243,64 @@ impl DashboardService {
         });
     }
 
+    /// Dashboard metrics as of the last background refresh, computing them
+    /// only if no refresh has completed yet
     pub async fn get_metrics(&self) -> anyhow::Result<DashboardMetrics> {
+        if let Some(metrics) = self.cached_metrics.read().await.clone() {
+            return Ok(metrics);
+        }
+
+        let metrics = self.compute_metrics().await?;
+        *self.cached_metrics.write().await = Some(metrics.clone());
+        Ok(metrics)
+    }
+
+    /// Start a task on `tracker` that recomputes the dashboard metrics every
+    /// `interval_secs` and pushes material changes to WebSocket clients,
+    /// until `shutdown` is cancelled
+    pub async fn start_metrics_refresher(
+        &self,
+        interval_secs: u64,
+        shutdown: CancellationToken,
+        tracker: &TaskTracker,
+    ) {
+        let self_clone = self.clone();
+        let threshold = std::env::var("DASHBOARD_DELTA_THRESHOLD")
+            .ok()
+            .and_then(|v| v.parse::<f64>().ok())
+            .unwrap_or(0.01); // 1%
+
+        tracker.spawn(async move {
+            tracing::info!("🚀 Starting Dashboard Metrics Refresher (interval: {}s)", interval_secs);
+            let mut interval = tokio::time::interval(std::time::Duration::from_secs(interval_secs));
+
+            loop {
+                tokio::select! {
+                    _ = shutdown.cancelled() => break,
+                    _ = interval.tick() => {}
+                }
+
+                let next = match self_clone.compute_metrics().await {
+                    Ok(metrics) => metrics,
+                    Err(e) => {
+                        tracing::error!("❌ Failed to refresh dashboard metrics: {}", e);
+                        continue;
+                    }
+                };
+
+                let previous = self_clone.cached_metrics.write().await.replace(next.clone());
+                let delta = previous.and_then(|prev| DashboardDelta::between(&prev, &next, threshold));
+                if let Some(delta) = delta {
+                    self_clone
+                        .websocket_service
+                        .broadcast_dashboard_metrics_updated(delta, next.computed_at)
+                        .await;
+                }
+            }
+        });
+    }
+
+    async fn compute_metrics(&self) -> anyhow::Result<DashboardMetrics> {
         // Fetch metrics in parallel where possible
         let (health_status, event_stats) = tokio::join!(
             self.health_checker.perform_health_check(),
@@ -224,6 +314,7 @@ impl DashboardService {
             event_processor: event_stats?,
             pending_transactions,
             grid_status: self.get_grid_status().await,
+            computed_at: Utc::now(),
         })
     }
 }
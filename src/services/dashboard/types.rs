@@ -49,4 +49,75 @@ pub struct DashboardMetrics {
     pub event_processor: EventProcessorStats,
     pub pending_transactions: HashMap<String, i64>,
     pub grid_status: GridStatus,
+    /// When these values were computed; the HTTP endpoint serves a cached copy
+    pub computed_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// The dashboard values that changed materially between two computations;
+/// unchanged values are left out
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct DashboardDelta {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub system_status: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub event_processor: Option<EventProcessorStats>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pending_transactions: Option<HashMap<String, i64>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub total_generation: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub total_consumption: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub net_balance: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub active_meters: Option<i64>,
+}
+
+/// Whether `next` differs from `prev` by at least `threshold` relative to `prev`
+fn changed_materially(prev: f64, next: f64, threshold: f64) -> bool {
+    if prev == 0.0 {
+        next != 0.0
+    } else {
+        ((next - prev) / prev).abs() >= threshold
+    }
+}
+
+impl DashboardDelta {
+    /// Changes from `prev` to `next`, or `None` if nothing changed materially.
+    /// Grid power figures count as changed once they move by `threshold`
+    /// (0.01 = 1%); counts and statuses on any change.
+    pub fn between(prev: &DashboardMetrics, next: &DashboardMetrics, threshold: f64) -> Option<Self> {
+        let (prev_grid, next_grid) = (&prev.grid_status, &next.grid_status);
+        let power = |prev: f64, next: f64| changed_materially(prev, next, threshold).then_some(next);
+
+        let delta = Self {
+            system_status: (prev.system_health.status != next.system_health.status)
+                .then(|| next.system_health.status.clone()),
+            event_processor: (prev.event_processor != next.event_processor)
+                .then(|| next.event_processor.clone()),
+            pending_transactions: (prev.pending_transactions != next.pending_transactions)
+                .then(|| next.pending_transactions.clone()),
+            total_generation: power(prev_grid.total_generation, next_grid.total_generation),
+            total_consumption: power(prev_grid.total_consumption, next_grid.total_consumption),
+            net_balance: power(prev_grid.net_balance, next_grid.net_balance),
+            active_meters: (prev_grid.active_meters != next_grid.active_meters)
+                .then_some(next_grid.active_meters),
+        };
+
+        (delta != Self::default()).then_some(delta)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_power_changes_below_threshold_are_not_material() {
+        assert!(!changed_materially(100.0, 100.5, 0.01));
+        assert!(changed_materially(100.0, 101.0, 0.01));
+        assert!(changed_materially(100.0, 98.0, 0.01));
+        assert!(changed_materially(0.0, 0.1, 0.01));
+        assert!(!changed_materially(0.0, 0.0, 0.01));
+    }
 }
@@ -1,20 +1,35 @@
 pub mod templates;
+pub mod transport;
 
 use anyhow::{Context, Result};
-use lettre::{
-    message::{header::ContentType, Mailbox, MultiPart, SinglePart},
-    transport::smtp::authentication::Credentials,
-    Message, SmtpTransport, Transport,
-};
-use tracing::{error, info};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tracing::{error, info, warn};
 
 use crate::config::EmailConfig;
+use crate::services::meter_analyzer::MeterAlertSummary;
 use templates::EmailTemplates;
+pub use transport::{EmailMessage, EmailTransport, PermanentEmailError};
+
+/// Maximum number of emails waiting for delivery
+const EMAIL_QUEUE_CAPACITY: usize = 1000;
+
+/// Delivery attempts per email before it is dropped
+const EMAIL_MAX_ATTEMPTS: u32 = 3;
+
+/// Delay before the first retry; doubles on each further attempt
+const EMAIL_RETRY_BASE_DELAY: Duration = Duration::from_secs(2);
 
 /// Email service for sending transactional emails
+///
+/// Public send methods enqueue the rendered message and return immediately;
+/// delivery happens on the worker started by [`EmailService::start_queue_worker`].
 #[derive(Clone)]
 pub struct EmailService {
-    mailer: SmtpTransport,
+    transport: Arc<dyn EmailTransport>,
+    queue_tx: mpsc::Sender<EmailMessage>,
+    queue_rx: Arc<Mutex<Option<mpsc::Receiver<EmailMessage>>>>,
     from_email: String,
     from_name: String,
     base_url: String,
@@ -24,40 +39,51 @@ pub struct EmailService {
 impl EmailService {
     /// Create a new email service from configuration
     pub fn new(config: &EmailConfig) -> Result<Self> {
-        // Determine if we should use TLS based on port
-        // Port 1025 is typically used for MailHog/local testing (no TLS)
-        // Ports 587, 465 are typically used for production SMTP (with TLS)
-        let use_tls = config.smtp_port != 1025;
-
-        let mailer = if use_tls {
-            // Production SMTP with TLS
-            let creds =
-                Credentials::new(config.smtp_username.clone(), config.smtp_password.clone());
-
-            SmtpTransport::starttls_relay(&config.smtp_host)
-                .context("Failed to create SMTP transport with TLS")?
-                .port(config.smtp_port)
-                .credentials(creds)
-                .build()
-        } else {
-            // Development SMTP without TLS (e.g., MailHog)
-            SmtpTransport::builder_dangerous(&config.smtp_host)
-                .port(config.smtp_port)
-                .build()
-        };
+        let transport = transport::from_config(config)?;
+        Ok(Self::with_transport(config, transport))
+    }
 
+    /// Create an email service that delivers through the given transport
+    pub fn with_transport(config: &EmailConfig, transport: Box<dyn EmailTransport>) -> Self {
         info!(
-            "Email service initialized: {}:{} (TLS: {}, enabled: {})",
-            config.smtp_host, config.smtp_port, use_tls, config.verification_enabled
+            "Email service initialized (transport: {}, enabled: {})",
+            transport.name(),
+            config.verification_enabled
         );
 
-        Ok(Self {
-            mailer,
+        let (queue_tx, queue_rx) = mpsc::channel(EMAIL_QUEUE_CAPACITY);
+
+        Self {
+            transport: Arc::from(transport),
+            queue_tx,
+            queue_rx: Arc::new(Mutex::new(Some(queue_rx))),
             from_email: config.from_address.clone(),
             from_name: config.from_name.clone(),
             base_url: config.verification_base_url.clone(),
             enabled: config.verification_enabled,
-        })
+        }
+    }
+
+    /// Spawn the background task that delivers queued emails. Returns false
+    /// if the worker was already started.
+    pub fn start_queue_worker(&self) -> bool {
+        let receiver = match self.queue_rx.lock() {
+            Ok(mut guard) => guard.take(),
+            Err(poisoned) => poisoned.into_inner().take(),
+        };
+        let Some(mut receiver) = receiver else {
+            return false;
+        };
+
+        let transport = self.transport.clone();
+        tokio::spawn(async move {
+            while let Some(message) = receiver.recv().await {
+                // Failures are logged inside; a queued email has no caller to report to
+                let _ = deliver_with_retry(transport.as_ref(), &message, EMAIL_RETRY_BASE_DELAY).await;
+            }
+        });
+
+        true
     }
 
     /// Send email verification message to user
@@ -163,7 +189,7 @@ impl EmailService {
         Ok(())
     }
 
-    /// Internal method to send email with HTML and text parts
+    /// Internal method to queue an email with HTML and text parts
     async fn send_email(
         &self,
         to_email: &str,
@@ -171,42 +197,31 @@ impl EmailService {
         html_body: &str,
         text_body: &str,
     ) -> Result<()> {
-        // Parse mailboxes
-        let from: Mailbox = format!("{} <{}>", self.from_name, self.from_email)
-            .parse()
-            .context("Failed to parse from address")?;
-
-        let to: Mailbox = to_email
-            .parse()
-            .context("Failed to parse recipient address")?;
-
-        // Build multipart email with HTML and plain text alternatives
-        let email = Message::builder()
-            .from(from)
-            .to(to)
-            .subject(subject)
-            .multipart(
-                MultiPart::alternative()
-                    .singlepart(
-                        SinglePart::builder()
-                            .header(ContentType::TEXT_PLAIN)
-                            .body(text_body.to_string()),
-                    )
-                    .singlepart(
-                        SinglePart::builder()
-                            .header(ContentType::TEXT_HTML)
-                            .body(html_body.to_string()),
-                    ),
-            )
-            .context("Failed to build email message")?;
-
-        // Send email via SMTP
-        match self.mailer.send(&email) {
-            Ok(_) => Ok(()),
-            Err(e) => {
-                error!("Failed to send email to {}: {}", to_email, e);
-                Err(anyhow::anyhow!("Failed to send email: {}", e))
+        let message = self.build_message(to_email, subject, html_body, text_body);
+
+        self.queue_tx.try_send(message).map_err(|e| match e {
+            mpsc::error::TrySendError::Full(_) => {
+                error!("Email queue full, dropping email to {}", to_email);
+                anyhow::anyhow!("Email queue is full")
             }
+            mpsc::error::TrySendError::Closed(_) => anyhow::anyhow!("Email queue is closed"),
+        })
+    }
+
+    fn build_message(
+        &self,
+        to_email: &str,
+        subject: &str,
+        html_body: &str,
+        text_body: &str,
+    ) -> EmailMessage {
+        EmailMessage {
+            from_name: self.from_name.clone(),
+            from_email: self.from_email.clone(),
+            to: to_email.to_string(),
+            subject: subject.to_string(),
+            html_body: html_body.to_string(),
+            text_body: text_body.to_string(),
         }
     }
 
@@ -248,14 +263,17 @@ If you received this email, your email configuration is working correctly!
 GridTokenX Platform - Automated Test Email
 "#;
 
-        self.send_email(
+        // Sent directly so configuration errors surface to the caller
+        let message = self.build_message(
             to_email,
             "GridTokenX Email Configuration Test",
             html_body,
             text_body,
-        )
-        .await
-        .context("Failed to send test email")?;
+        );
+        self.transport
+            .send(&message)
+            .await
+            .context("Failed to send test email")?;
 
         info!("Test email sent to {}", to_email);
         Ok(())
@@ -282,6 +300,70 @@ GridTokenX Platform - Automated Test Email
 
         self.send_email(to_email, title, &html_body, &text_body).await
     }
+
+    /// Send the daily digest of a user's unresolved meter alerts
+    ///
+    /// Delivered before returning rather than queued, so an `Ok` means the
+    /// digest was handed to the transport and its alerts can be marked sent.
+    pub async fn send_meter_alert_digest(
+        &self,
+        to_email: &str,
+        username: &str,
+        alerts: &[MeterAlertSummary],
+    ) -> Result<()> {
+        if !self.enabled {
+            info!(
+                "Email service disabled, skipping meter alert digest to {}",
+                to_email
+            );
+            return Ok(());
+        }
+
+        let html_body = templates::EmailTemplates::meter_alert_digest(username, alerts);
+        let text_body = templates::EmailTemplates::meter_alert_digest_text(username, alerts);
+        let subject = format!("GridTokenX Meter Alert Digest ({} alerts)", alerts.len());
+
+        let message = self.build_message(to_email, &subject, &html_body, &text_body);
+        deliver_with_retry(self.transport.as_ref(), &message, EMAIL_RETRY_BASE_DELAY).await
+    }
+}
+
+/// Deliver one email, retrying transient failures with exponential backoff
+///
+/// Returns the last error once the email is given up on.
+async fn deliver_with_retry(
+    transport: &dyn EmailTransport,
+    message: &EmailMessage,
+    base_delay: Duration,
+) -> Result<()> {
+    let mut delay = base_delay;
+    let mut attempt = 1;
+
+    loop {
+        match transport.send(message).await {
+            Ok(()) => return Ok(()),
+            Err(e) if e.downcast_ref::<PermanentEmailError>().is_some() => {
+                error!("Failed to send email to {}: {}", message.to, e);
+                return Err(e);
+            }
+            Err(e) if attempt >= EMAIL_MAX_ATTEMPTS => {
+                error!(
+                    "Failed to send email to {} after {} attempts: {}",
+                    message.to, attempt, e
+                );
+                return Err(e);
+            }
+            Err(e) => {
+                warn!(
+                    "Email to {} failed (attempt {}/{}), retrying in {:?}: {}",
+                    message.to, attempt, EMAIL_MAX_ATTEMPTS, delay, e
+                );
+                tokio::time::sleep(delay).await;
+                delay *= 2;
+                attempt += 1;
+            }
+        }
+    }
 }
 
 #[cfg(test)]
@@ -302,6 +384,9 @@ mod tests {
             verification_required: true,
             verification_enabled: false, // Disabled for tests
             auto_login_after_verification: false,
+            transport: "smtp".to_string(),
+            http_api_url: None,
+            http_api_key: None,
         };
 
         let service = EmailService::new(&config);
@@ -322,9 +407,154 @@ mod tests {
             verification_required: true,
             verification_enabled: false,
             auto_login_after_verification: false,
+            transport: "smtp".to_string(),
+            http_api_url: None,
+            http_api_key: None,
         };
 
         let service = EmailService::new(&config).unwrap();
         assert!(!service.is_enabled());
     }
+
+    struct RecordingTransport {
+        sent: Arc<std::sync::Mutex<Vec<EmailMessage>>>,
+    }
+
+    #[async_trait::async_trait]
+    impl EmailTransport for RecordingTransport {
+        async fn send(&self, message: &EmailMessage) -> Result<()> {
+            self.sent.lock().unwrap().push(message.clone());
+            Ok(())
+        }
+
+        fn name(&self) -> &'static str {
+            "recording"
+        }
+    }
+
+    struct FlakyTransport {
+        failures_left: std::sync::Mutex<u32>,
+        permanent: bool,
+        attempts: Arc<std::sync::Mutex<u32>>,
+    }
+
+    #[async_trait::async_trait]
+    impl EmailTransport for FlakyTransport {
+        async fn send(&self, _message: &EmailMessage) -> Result<()> {
+            *self.attempts.lock().unwrap() += 1;
+            let mut left = self.failures_left.lock().unwrap();
+            if *left == 0 {
+                return Ok(());
+            }
+            *left -= 1;
+            if self.permanent {
+                Err(anyhow::Error::new(PermanentEmailError("rejected".to_string())))
+            } else {
+                Err(anyhow::anyhow!("connection reset"))
+            }
+        }
+
+        fn name(&self) -> &'static str {
+            "flaky"
+        }
+    }
+
+    fn test_message() -> EmailMessage {
+        EmailMessage {
+            from_name: "Test".to_string(),
+            from_email: "test@example.com".to_string(),
+            to: "bob@example.com".to_string(),
+            subject: "Hi".to_string(),
+            html_body: String::new(),
+            text_body: String::new(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_delivery_retries_transient_failures() {
+        let attempts = Arc::new(std::sync::Mutex::new(0));
+        let transport = FlakyTransport {
+            failures_left: std::sync::Mutex::new(2),
+            permanent: false,
+            attempts: attempts.clone(),
+        };
+
+        assert!(deliver_with_retry(&transport, &test_message(), Duration::ZERO).await.is_ok());
+
+        assert_eq!(*attempts.lock().unwrap(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_delivery_does_not_retry_permanent_failures() {
+        let attempts = Arc::new(std::sync::Mutex::new(0));
+        let transport = FlakyTransport {
+            failures_left: std::sync::Mutex::new(5),
+            permanent: true,
+            attempts: attempts.clone(),
+        };
+
+        assert!(deliver_with_retry(&transport, &test_message(), Duration::ZERO).await.is_err());
+
+        assert_eq!(*attempts.lock().unwrap(), 1);
+    }
+
+    fn test_config(transport: &str) -> EmailConfig {
+        EmailConfig {
+            smtp_host: "smtp.example.com".to_string(),
+            smtp_port: 587,
+            smtp_username: "test@example.com".to_string(),
+            smtp_password: "password".to_string(),
+            from_name: "Test".to_string(),
+            from_address: "test@example.com".to_string(),
+            verification_expiry_hours: 24,
+            verification_base_url: "http://localhost:3000".to_string(),
+            verification_required: true,
+            verification_enabled: true,
+            auto_login_after_verification: false,
+            transport: transport.to_string(),
+            http_api_url: None,
+            http_api_key: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_send_uses_configured_transport() {
+        let sent = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let service = EmailService::with_transport(
+            &test_config("smtp"),
+            Box::new(RecordingTransport { sent: sent.clone() }),
+        );
+        assert!(service.start_queue_worker());
+        assert!(!service.start_queue_worker());
+
+        service
+            .send_welcome_email("alice@example.com", "alice")
+            .await
+            .unwrap();
+
+        // Delivery happens on the background worker
+        for _ in 0..50 {
+            if !sent.lock().unwrap().is_empty() {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+
+        let sent = sent.lock().unwrap();
+        assert_eq!(sent.len(), 1);
+        assert_eq!(sent[0].to, "alice@example.com");
+        assert_eq!(sent[0].from_email, "test@example.com");
+        assert!(sent[0].html_body.contains("alice"));
+    }
+
+    #[test]
+    fn test_transport_selection() {
+        assert!(EmailService::new(&test_config("carrier-pigeon")).is_err());
+        // HTTP transport needs an API URL
+        assert!(EmailService::new(&test_config("http")).is_err());
+
+        let mut config = test_config("http");
+        config.http_api_url = Some("https://api.mail.example.com/v1/send".to_string());
+        assert!(EmailService::new(&config).is_ok());
+    }
 }
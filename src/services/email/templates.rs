@@ -1,6 +1,14 @@
+use crate::services::meter_analyzer::{AlertSeverity, MeterAlertSummary};
+
+/// Severity sections of the meter alert digest, most urgent first
+const DIGEST_SEVERITY_ORDER: [(AlertSeverity, &str, &str); 3] = [
+    (AlertSeverity::Critical, "Critical", "#dc2626"),
+    (AlertSeverity::Warning, "Warning", "#d97706"),
+    (AlertSeverity::Info, "Info", "#2563eb"),
+];
+
 /// Email templates for the GridTokenX Platform
 /// Provides HTML email templates for verification, welcome, and other notifications
-
 pub struct EmailTemplates;
 
 impl EmailTemplates {
@@ -456,6 +464,154 @@ You are receiving this email because you enabled specific notifications in your
             title, username, message
         )
     }
+
+    /// HTML email template for the daily meter alert digest, grouped by severity
+    pub fn meter_alert_digest(username: &str, alerts: &[MeterAlertSummary]) -> String {
+        let mut sections = String::new();
+        for (severity, label, color) in DIGEST_SEVERITY_ORDER {
+            let group: Vec<&MeterAlertSummary> =
+                alerts.iter().filter(|a| a.severity == severity).collect();
+            if group.is_empty() {
+                continue;
+            }
+
+            sections.push_str(&format!(
+                r#"<h3 style="color: {}; margin: 20px 0 10px 0; font-size: 16px; font-weight: 600;">{} ({})</h3>
+              <table role="presentation" style="width: 100%; border-collapse: collapse; margin: 0 0 10px 0; font-size: 14px;">"#,
+                color,
+                label,
+                group.len()
+            ));
+            for alert in group {
+                sections.push_str(&format!(
+                    r#"
+                <tr>
+                  <td style="padding: 8px; border-bottom: 1px solid #e5e7eb; color: #1f2937;"><strong>{}</strong><br><span style="color: #6b7280;">{}</span></td>
+                  <td style="padding: 8px; border-bottom: 1px solid #e5e7eb; color: #4b5563;">{}</td>
+                  <td style="padding: 8px; border-bottom: 1px solid #e5e7eb; color: #6b7280; text-align: right; white-space: nowrap;">{}× · last {}</td>
+                </tr>"#,
+                    alert.meter_id,
+                    alert.alert_type,
+                    alert.message,
+                    alert.occurrences,
+                    alert.last_seen.format("%H:%M UTC")
+                ));
+            }
+            sections.push_str("\n              </table>");
+        }
+
+        format!(
+            r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+  <meta charset="UTF-8">
+  <meta name="viewport" content="width=device-width, initial-scale=1.0">
+  <title>Meter Alert Digest - GridTokenX</title>
+</head>
+<body style="margin: 0; padding: 0; font-family: -apple-system, BlinkMacSystemFont, 'Segoe UI', Roboto, 'Helvetica Neue', Arial, sans-serif; background-color: #f5f5f5;">
+  <table role="presentation" style="width: 100%; border-collapse: collapse; background-color: #f5f5f5;">
+    <tr>
+      <td align="center" style="padding: 40px 0;">
+        <table role="presentation" style="width: 600px; max-width: 100%; border-collapse: collapse; background-color: #ffffff; box-shadow: 0 4px 6px rgba(0, 0, 0, 0.1);">
+          <!-- Header -->
+          <tr>
+            <td style="background: linear-gradient(135deg, #10b981 0%, #059669 100%); padding: 30px; text-align: center; border-radius: 8px 8px 0 0;">
+              <h1 style="color: #ffffff; margin: 0; font-size: 24px; font-weight: 600;">GridTokenX</h1>
+            </td>
+          </tr>
+          
+          <!-- Body -->
+          <tr>
+            <td style="padding: 40px 30px; background-color: #ffffff;">
+              <h2 style="color: #1f2937; margin: 0 0 20px 0; font-size: 20px; font-weight: 600;">Meter Alert Digest</h2>
+              
+              <p style="color: #4b5563; line-height: 1.6; margin: 0 0 20px 0; font-size: 16px;">
+                Hello <strong>{}</strong>,
+              </p>
+              
+              <p style="color: #4b5563; line-height: 1.6; margin: 0 0 10px 0; font-size: 16px;">
+                Your meters raised the following unresolved alerts in the last 24 hours.
+              </p>
+              
+              {}
+              
+              <table role="presentation" style="width: 100%; border-collapse: collapse;">
+                <tr>
+                  <td align="center" style="padding: 30px 0;">
+                    <a href="https://gridtokenx.com/dashboard" 
+                      style="display: inline-block; background-color: #10b981; 
+                          color: #ffffff; padding: 12px 30px; text-decoration: none; 
+                          border-radius: 5px; font-weight: 600; font-size: 16px;">
+                      View Dashboard
+                    </a>
+                  </td>
+                </tr>
+              </table>
+              
+              <p style="color: #6b7280; margin: 0; font-size: 14px; line-height: 1.5;">
+                You are receiving this email because one or more of your registered meters reported abnormal readings.
+              </p>
+            </td>
+          </tr>
+          
+          <!-- Footer -->
+          <tr>
+            <td style="background-color: #f9fafb; padding: 20px; text-align: center; border-top: 1px solid #e5e7eb;">
+              <p style="color: #9ca3af; margin: 0; font-size: 12px;">
+                © 2026 GridTokenX Platform. All rights reserved.
+              </p>
+            </td>
+          </tr>
+        </table>
+      </td>
+    </tr>
+  </table>
+</body>
+</html>"#,
+            username, sections
+        )
+    }
+
+    /// Plain text email template for the daily meter alert digest
+    pub fn meter_alert_digest_text(username: &str, alerts: &[MeterAlertSummary]) -> String {
+        let mut sections = String::new();
+        for (severity, label, _) in DIGEST_SEVERITY_ORDER {
+            let group: Vec<&MeterAlertSummary> =
+                alerts.iter().filter(|a| a.severity == severity).collect();
+            if group.is_empty() {
+                continue;
+            }
+
+            sections.push_str(&format!("{} ({})\n", label.to_uppercase(), group.len()));
+            for alert in group {
+                sections.push_str(&format!(
+                    "- [{}] {}: {} ({}x, last {})\n",
+                    alert.meter_id,
+                    alert.alert_type,
+                    alert.message,
+                    alert.occurrences,
+                    alert.last_seen.format("%H:%M UTC")
+                ));
+            }
+            sections.push('\n');
+        }
+
+        format!(
+            r#"GridTokenX Meter Alert Digest
+
+Hello {},
+
+Your meters raised the following unresolved alerts in the last 24 hours.
+
+{}View your dashboard: https://gridtokenx.com/dashboard
+
+---
+You are receiving this email because one or more of your registered meters reported abnormal readings.
+© 2026 GridTokenX Platform. All rights reserved.
+"#,
+            username, sections
+        )
+    }
 }
 
 #[cfg(test)]
@@ -493,4 +649,45 @@ mod tests {
         assert!(verification_text.contains("testuser"));
         assert!(welcome_text.contains("testuser"));
     }
+
+    fn digest_alert(meter_id: &str, severity: AlertSeverity) -> MeterAlertSummary {
+        MeterAlertSummary {
+            meter_id: meter_id.to_string(),
+            alert_type: "low_voltage".to_string(),
+            severity,
+            message: "Low voltage detected: 195.0V (threshold: 200V)".to_string(),
+            occurrences: 3,
+            last_seen: chrono::Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_meter_alert_digest_groups_by_severity() {
+        let alerts = vec![
+            digest_alert("METER-INFO", AlertSeverity::Info),
+            digest_alert("METER-CRIT", AlertSeverity::Critical),
+        ];
+        let email = EmailTemplates::meter_alert_digest("testuser", &alerts);
+
+        assert!(email.contains("testuser"));
+        let critical = email.find("Critical (1)").expect("critical section");
+        let info = email.find("Info (1)").expect("info section");
+        assert!(critical < info);
+        assert!(!email.contains("Warning ("));
+        assert!(email.find("METER-CRIT").unwrap() < email.find("METER-INFO").unwrap());
+    }
+
+    #[test]
+    fn test_meter_alert_digest_text_groups_by_severity() {
+        let alerts = vec![
+            digest_alert("METER-A", AlertSeverity::Warning),
+            digest_alert("METER-B", AlertSeverity::Warning),
+        ];
+        let text = EmailTemplates::meter_alert_digest_text("testuser", &alerts);
+
+        assert!(text.contains("WARNING (2)"));
+        assert!(text.contains("[METER-A] low_voltage"));
+        assert!(text.contains("3x"));
+        assert!(!text.contains("CRITICAL"));
+    }
 }
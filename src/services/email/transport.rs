@@ -0,0 +1,216 @@
+//! Email delivery backends.
+//!
+//! `EmailService` renders messages and hands them to an [`EmailTransport`].
+//! The backend is chosen by `EMAIL_TRANSPORT`: `smtp` (default) or `http`
+//! for providers that expose an HTTP API instead of SMTP.
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use lettre::{
+    message::{header::ContentType, Mailbox, MultiPart, SinglePart},
+    transport::smtp::authentication::Credentials,
+    Message, SmtpTransport, Transport,
+};
+use reqwest::Client;
+use serde::Serialize;
+use std::time::Duration;
+use tracing::info;
+
+use crate::config::EmailConfig;
+
+/// A delivery failure that retrying will not fix (e.g. a rejected recipient)
+#[derive(Debug)]
+pub struct PermanentEmailError(pub String);
+
+impl std::fmt::Display for PermanentEmailError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl std::error::Error for PermanentEmailError {}
+
+/// A rendered email ready for delivery
+#[derive(Debug, Clone, Serialize)]
+pub struct EmailMessage {
+    pub from_name: String,
+    pub from_email: String,
+    pub to: String,
+    pub subject: String,
+    pub html_body: String,
+    pub text_body: String,
+}
+
+/// Delivers rendered emails
+#[async_trait]
+pub trait EmailTransport: Send + Sync {
+    async fn send(&self, message: &EmailMessage) -> Result<()>;
+
+    /// Short name used in logs
+    fn name(&self) -> &'static str;
+}
+
+/// Build the transport selected by `config.transport`
+pub fn from_config(config: &EmailConfig) -> Result<Box<dyn EmailTransport>> {
+    match config.transport.as_str() {
+        "smtp" => Ok(Box::new(SmtpEmailTransport::new(config)?)),
+        "http" => Ok(Box::new(HttpEmailTransport::new(config)?)),
+        other => Err(anyhow::anyhow!(
+            "Unknown EMAIL_TRANSPORT '{}', expected 'smtp' or 'http'",
+            other
+        )),
+    }
+}
+
+/// SMTP delivery via lettre
+pub struct SmtpEmailTransport {
+    mailer: SmtpTransport,
+}
+
+impl SmtpEmailTransport {
+    pub fn new(config: &EmailConfig) -> Result<Self> {
+        // Determine if we should use TLS based on port
+        // Port 1025 is typically used for MailHog/local testing (no TLS)
+        // Ports 587, 465 are typically used for production SMTP (with TLS)
+        let use_tls = config.smtp_port != 1025;
+
+        let mailer = if use_tls {
+            // Production SMTP with TLS
+            let creds =
+                Credentials::new(config.smtp_username.clone(), config.smtp_password.clone());
+
+            SmtpTransport::starttls_relay(&config.smtp_host)
+                .context("Failed to create SMTP transport with TLS")?
+                .port(config.smtp_port)
+                .credentials(creds)
+                .build()
+        } else {
+            // Development SMTP without TLS (e.g., MailHog)
+            SmtpTransport::builder_dangerous(&config.smtp_host)
+                .port(config.smtp_port)
+                .build()
+        };
+
+        info!(
+            "SMTP email transport: {}:{} (TLS: {})",
+            config.smtp_host, config.smtp_port, use_tls
+        );
+
+        Ok(Self { mailer })
+    }
+}
+
+#[async_trait]
+impl EmailTransport for SmtpEmailTransport {
+    async fn send(&self, message: &EmailMessage) -> Result<()> {
+        let from: Mailbox = format!("{} <{}>", message.from_name, message.from_email)
+            .parse()
+            .context("Failed to parse from address")?;
+
+        let to: Mailbox = message
+            .to
+            .parse()
+            .context("Failed to parse recipient address")?;
+
+        // Build multipart email with HTML and plain text alternatives
+        let email = Message::builder()
+            .from(from)
+            .to(to)
+            .subject(message.subject.clone())
+            .multipart(
+                MultiPart::alternative()
+                    .singlepart(
+                        SinglePart::builder()
+                            .header(ContentType::TEXT_PLAIN)
+                            .body(message.text_body.clone()),
+                    )
+                    .singlepart(
+                        SinglePart::builder()
+                            .header(ContentType::TEXT_HTML)
+                            .body(message.html_body.clone()),
+                    ),
+            )
+            .context("Failed to build email message")?;
+
+        // lettre's SMTP transport is blocking; keep it off the async workers
+        let mailer = self.mailer.clone();
+        tokio::task::spawn_blocking(move || mailer.send(&email))
+            .await
+            .context("SMTP send task panicked")?
+            .map(|_| ())
+            .map_err(|e| {
+                if e.is_permanent() {
+                    anyhow::Error::new(PermanentEmailError(format!("Failed to send email: {}", e)))
+                } else {
+                    anyhow::anyhow!("Failed to send email: {}", e)
+                }
+            })
+    }
+
+    fn name(&self) -> &'static str {
+        "smtp"
+    }
+}
+
+/// Generic HTTP API delivery: POSTs the message as JSON to
+/// `EMAIL_HTTP_API_URL` with `EMAIL_HTTP_API_KEY` as a bearer token.
+/// Provider-specific payload shapes can be added as further transports.
+pub struct HttpEmailTransport {
+    client: Client,
+    api_url: String,
+    api_key: Option<String>,
+}
+
+impl HttpEmailTransport {
+    pub fn new(config: &EmailConfig) -> Result<Self> {
+        let api_url = config
+            .http_api_url
+            .clone()
+            .ok_or_else(|| anyhow::anyhow!("EMAIL_HTTP_API_URL is required for the http email transport"))?;
+
+        let client = Client::builder()
+            .timeout(Duration::from_secs(10))
+            .build()
+            .context("Failed to create HTTP email client")?;
+
+        info!("HTTP email transport: {}", api_url);
+
+        Ok(Self {
+            client,
+            api_url,
+            api_key: config.http_api_key.clone(),
+        })
+    }
+}
+
+#[async_trait]
+impl EmailTransport for HttpEmailTransport {
+    async fn send(&self, message: &EmailMessage) -> Result<()> {
+        let mut request = self.client.post(&self.api_url).json(message);
+        if let Some(key) = &self.api_key {
+            request = request.bearer_auth(key);
+        }
+
+        let res = request
+            .send()
+            .await
+            .map_err(|e| anyhow::anyhow!("Email API request failed: {}", e))?;
+
+        if !res.status().is_success() {
+            let status = res.status();
+            let body = res.text().await.unwrap_or_default();
+            let message = format!("Email API returned {}: {}", status, body);
+            // 4xx other than rate limiting won't succeed on retry
+            if status.is_client_error() && status != reqwest::StatusCode::TOO_MANY_REQUESTS {
+                return Err(anyhow::Error::new(PermanentEmailError(message)));
+            }
+            return Err(anyhow::anyhow!(message));
+        }
+
+        Ok(())
+    }
+
+    fn name(&self) -> &'static str {
+        "http"
+    }
+}
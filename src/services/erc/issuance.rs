@@ -87,7 +87,7 @@ impl AggregatedIssuance {
         let amount_u64 = (energy_amount * 1000.0) as u64;
 
         // Get meter PDA from registry
-        let registry_program_id = self.blockchain_service.registry_program_id()?;
+        let registry_program_id = self.blockchain_service.registry_program_id();
         let (meter_pda, _) = Pubkey::find_program_address(
             &[b"meter", meter_id.as_bytes()],
             &registry_program_id,
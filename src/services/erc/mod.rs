@@ -6,18 +6,22 @@ pub mod types;
 
 pub use types::*;
 
+use std::collections::{BTreeMap, HashSet};
+
 use anyhow::{anyhow, Result};
 use chrono::Utc;
 use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
 use solana_sdk::signature::Keypair;
-use sqlx::PgPool;
-use tracing::{info, instrument};
+use sqlx::{PgConnection, PgPool};
+use tracing::{info, instrument, warn};
 use uuid::Uuid;
 
 use self::issuance::AggregatedIssuance;
 use self::queries::ErcQueryManager;
 use self::retiring::CertificateRetiring;
 use self::transfer::CertificateTransferManager;
+use crate::handlers::websocket::broadcaster::broadcast_certificate_expired;
 use crate::services::BlockchainService;
 
 /// Service for managing Energy Renewable Certificates
@@ -60,6 +64,26 @@ impl ErcService {
         issuer_wallet: &str,
         request: IssueErcRequest,
         settlement_id: Option<Uuid>,
+    ) -> Result<ErcCertificate> {
+        let mut conn = self
+            .db_pool
+            .acquire()
+            .await
+            .map_err(|e| anyhow!("Failed to acquire connection: {}", e))?;
+
+        self.insert_certificate(&mut *conn, user_id, issuer_wallet, request, settlement_id)
+            .await
+    }
+
+    /// Create the certificate record on the given connection so callers can
+    /// issue inside their own transaction
+    async fn insert_certificate(
+        &self,
+        conn: &mut PgConnection,
+        user_id: Uuid,
+        issuer_wallet: &str,
+        request: IssueErcRequest,
+        settlement_id: Option<Uuid>,
     ) -> Result<ErcCertificate> {
         info!("Issuing certificate for user {}", user_id);
 
@@ -135,7 +159,7 @@ impl ErcService {
             metadata_json,
             settlement_id
         )
-        .fetch_one(&self.db_pool)
+        .fetch_one(&mut *conn)
         .await
         .map_err(|e| anyhow!("Failed to create certificate record: {}", e))?;
 
@@ -144,6 +168,114 @@ impl ErcService {
         Ok(certificate)
     }
 
+    /// Aggregate confirmed meter readings into certificates, one per
+    /// renewable source. Readings that are missing, not owned by the user,
+    /// unconfirmed, empty or already certified are skipped and reported.
+    #[instrument(skip(self, issuer_wallet, reading_ids))]
+    pub async fn issue_batch(
+        &self,
+        user_id: Uuid,
+        issuer_wallet: &str,
+        reading_ids: Vec<Uuid>,
+    ) -> Result<BatchIssuanceReport> {
+        if reading_ids.is_empty() {
+            return Err(anyhow!("No readings provided"));
+        }
+
+        let wallet_address = sqlx::query_scalar::<_, Option<String>>(
+            "SELECT wallet_address FROM users WHERE id = $1",
+        )
+        .bind(user_id)
+        .fetch_optional(&self.db_pool)
+        .await
+        .map_err(|e| anyhow!("Failed to fetch user: {}", e))?
+        .flatten()
+        .ok_or_else(|| anyhow!("User {} has no wallet address", user_id))?;
+
+        let mut tx = self
+            .db_pool
+            .begin()
+            .await
+            .map_err(|e| anyhow!("Failed to start transaction: {}", e))?;
+
+        let rows: Vec<BatchReadingRow> = sqlx::query_as(
+            r#"
+            SELECT id, user_id, kwh_amount,
+                   COALESCE(meter_type, 'Unknown') AS renewable_source,
+                   on_chain_confirmed, erc_certificate_id
+            FROM meter_readings
+            WHERE id = ANY($1)
+            FOR UPDATE
+            "#,
+        )
+        .bind(&reading_ids)
+        .fetch_all(&mut *tx)
+        .await
+        .map_err(|e| anyhow!("Failed to fetch readings: {}", e))?;
+
+        let (groups, mut results) = group_batch_readings(user_id, &reading_ids, &rows);
+
+        let mut certificates = Vec::with_capacity(groups.len());
+        for (renewable_source, (kwh_amount, ids)) in groups {
+            let request = IssueErcRequest {
+                wallet_address: wallet_address.clone(),
+                meter_id: None,
+                kwh_amount,
+                expiry_date: None,
+                metadata: Some(serde_json::json!({
+                    "renewable_source": renewable_source,
+                    "reading_ids": ids,
+                })),
+            };
+
+            let certificate = self
+                .insert_certificate(&mut *tx, user_id, issuer_wallet, request, None)
+                .await?;
+
+            sqlx::query("UPDATE meter_readings SET erc_certificate_id = $1 WHERE id = ANY($2)")
+                .bind(certificate.id)
+                .bind(&ids)
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| anyhow!("Failed to link readings to certificate: {}", e))?;
+
+            for id in &ids {
+                results.push(BatchReadingResult {
+                    reading_id: *id,
+                    status: BatchReadingStatus::Issued,
+                    certificate_id: Some(certificate.certificate_id.clone()),
+                    reason: None,
+                });
+            }
+
+            certificates.push(certificate);
+        }
+
+        tx.commit()
+            .await
+            .map_err(|e| anyhow!("Failed to commit batch issuance: {}", e))?;
+
+        // Report in the order the readings were requested
+        results.sort_by_key(|r| {
+            reading_ids
+                .iter()
+                .position(|id| *id == r.reading_id)
+                .unwrap_or(usize::MAX)
+        });
+
+        info!(
+            "Batch issued {} certificates from {} readings for user {}",
+            certificates.len(),
+            reading_ids.len(),
+            user_id
+        );
+
+        Ok(BatchIssuanceReport {
+            certificates,
+            readings: results,
+        })
+    }
+
     /// Issue ERC certificate on-chain (calls governance program)
     #[instrument(skip(self, authority))]
     pub async fn issue_certificate_on_chain(
@@ -266,6 +398,41 @@ impl ErcService {
             .await
     }
 
+    // --- Expiry ---
+
+    /// Mark every active certificate past its expiry date as expired and
+    /// notify the owners. Returns the number of certificates expired.
+    #[instrument(skip(self))]
+    pub async fn expire_certificates(&self) -> Result<u64> {
+        let expired: Vec<(String, Option<Uuid>, chrono::DateTime<Utc>)> = sqlx::query_as(
+            r#"
+            UPDATE erc_certificates
+            SET status = 'expired', updated_at = NOW()
+            WHERE status = 'active'
+              AND expiry_date IS NOT NULL
+              AND expiry_date <= NOW()
+            RETURNING certificate_id, user_id, expiry_date
+            "#,
+        )
+        .fetch_all(&self.db_pool)
+        .await
+        .map_err(|e| anyhow!("Failed to expire certificates: {}", e))?;
+
+        for (certificate_id, user_id, expiry_date) in &expired {
+            let Some(user_id) = user_id else { continue };
+            if let Err(e) =
+                broadcast_certificate_expired(certificate_id.clone(), *user_id, *expiry_date).await
+            {
+                warn!(
+                    "Failed to notify user {} of expired certificate {}: {}",
+                    user_id, certificate_id, e
+                );
+            }
+        }
+
+        Ok(expired.len() as u64)
+    }
+
     // --- Statistics & Queries (Keep in main service or move if large) ---
 
     #[instrument(skip(self))]
@@ -325,3 +492,129 @@ impl ErcService {
             .await
     }
 }
+
+/// Split batch readings into per-source (total kWh, reading ids) groups and
+/// skip results for readings that cannot be certified
+fn group_batch_readings(
+    user_id: Uuid,
+    reading_ids: &[Uuid],
+    rows: &[BatchReadingRow],
+) -> (
+    BTreeMap<String, (Decimal, Vec<Uuid>)>,
+    Vec<BatchReadingResult>,
+) {
+    let mut groups: BTreeMap<String, (Decimal, Vec<Uuid>)> = BTreeMap::new();
+    let mut skipped = Vec::new();
+    let mut seen = HashSet::new();
+
+    let skip = |reading_id: Uuid, reason: &str| BatchReadingResult {
+        reading_id,
+        status: BatchReadingStatus::Skipped,
+        certificate_id: None,
+        reason: Some(reason.to_string()),
+    };
+
+    for reading_id in reading_ids {
+        if !seen.insert(*reading_id) {
+            continue;
+        }
+
+        let Some(row) = rows.iter().find(|r| r.id == *reading_id) else {
+            skipped.push(skip(*reading_id, "reading not found"));
+            continue;
+        };
+
+        if row.user_id != Some(user_id) {
+            skipped.push(skip(*reading_id, "reading not owned by user"));
+        } else if row.erc_certificate_id.is_some() {
+            skipped.push(skip(*reading_id, "already linked to a certificate"));
+        } else if row.on_chain_confirmed != Some(true) {
+            skipped.push(skip(*reading_id, "reading not confirmed"));
+        } else {
+            match row.kwh_amount {
+                Some(kwh) if kwh > Decimal::ZERO => {
+                    let entry = groups
+                        .entry(row.renewable_source.clone())
+                        .or_insert((Decimal::ZERO, Vec::new()));
+                    entry.0 += kwh;
+                    entry.1.push(row.id);
+                }
+                _ => skipped.push(skip(*reading_id, "reading has no energy")),
+            }
+        }
+    }
+
+    (groups, skipped)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn reading(user_id: Uuid, kwh: Decimal, source: &str) -> BatchReadingRow {
+        BatchReadingRow {
+            id: Uuid::new_v4(),
+            user_id: Some(user_id),
+            kwh_amount: Some(kwh),
+            renewable_source: source.to_string(),
+            on_chain_confirmed: Some(true),
+            erc_certificate_id: None,
+        }
+    }
+
+    #[test]
+    fn test_group_batch_readings_by_source() {
+        let user_id = Uuid::new_v4();
+        let rows = vec![
+            reading(user_id, Decimal::from(10), "Solar"),
+            reading(user_id, Decimal::new(55, 1), "Solar"),
+            reading(user_id, Decimal::from(7), "Wind"),
+        ];
+        let ids: Vec<Uuid> = rows.iter().map(|r| r.id).collect();
+
+        let (groups, skipped) = group_batch_readings(user_id, &ids, &rows);
+
+        assert!(skipped.is_empty());
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups["Solar"].0, Decimal::new(155, 1));
+        assert_eq!(groups["Solar"].1, vec![ids[0], ids[1]]);
+        assert_eq!(groups["Wind"].0, Decimal::from(7));
+    }
+
+    #[test]
+    fn test_group_batch_readings_skips_ineligible() {
+        let user_id = Uuid::new_v4();
+        let mut linked = reading(user_id, Decimal::from(10), "Solar");
+        linked.erc_certificate_id = Some(Uuid::new_v4());
+        let mut unconfirmed = reading(user_id, Decimal::from(10), "Solar");
+        unconfirmed.on_chain_confirmed = Some(false);
+        let foreign = reading(Uuid::new_v4(), Decimal::from(10), "Solar");
+        let empty = reading(user_id, Decimal::ZERO, "Solar");
+        let missing = Uuid::new_v4();
+
+        let rows = vec![linked, unconfirmed, foreign, empty];
+        let mut ids: Vec<Uuid> = rows.iter().map(|r| r.id).collect();
+        ids.push(missing);
+
+        let (groups, skipped) = group_batch_readings(user_id, &ids, &rows);
+
+        assert!(groups.is_empty());
+        let reasons: Vec<&str> = skipped
+            .iter()
+            .map(|r| r.reason.as_deref().unwrap())
+            .collect();
+        assert_eq!(
+            reasons,
+            vec![
+                "already linked to a certificate",
+                "reading not confirmed",
+                "reading not owned by user",
+                "reading has no energy",
+                "reading not found",
+            ]
+        );
+        assert!(skipped
+            .iter()
+            .all(|r| r.status == BatchReadingStatus::Skipped));
+    }
+}
@@ -50,6 +50,7 @@ impl CertificateRetiring {
             UPDATE erc_certificates
             SET status = 'retired'
             WHERE id = $1 AND status IN ('active', 'transferred')
+              AND (expiry_date IS NULL OR expiry_date > NOW())
             RETURNING
                 id, certificate_id,
                 user_id as "user_id?",
@@ -70,7 +71,7 @@ impl CertificateRetiring {
         .fetch_optional(&self.db_pool)
         .await
         .map_err(|e| anyhow!("Failed to retire certificate: {}", e))?
-        .ok_or_else(|| anyhow!("Certificate not found or already retired"))?;
+        .ok_or_else(|| anyhow!("Certificate not found, already retired or expired"))?;
 
         info!("Certificate {} retired", certificate.certificate_id);
 
@@ -66,11 +66,26 @@ impl CertificateTransferManager {
         let to_user_id = new_user_id.ok_or_else(|| anyhow!("Recipient user not found for wallet: {}", to_wallet))?;
 
         // Get current owner (from_user_id)
-        let current_cert = sqlx::query!("SELECT user_id FROM erc_certificates WHERE id = $1", certificate_uuid)
+        let current_cert = sqlx::query!(
+            "SELECT user_id, status, expiry_date FROM erc_certificates WHERE id = $1 FOR UPDATE",
+            certificate_uuid
+        )
             .fetch_optional(&mut *tx)
             .await
             .map_err(|e| anyhow!("Failed to fetch certificate: {}", e))?
             .ok_or_else(|| anyhow!("Certificate not found"))?;
+
+        // Expired and retired certificates are no longer transferable
+        let is_expired = current_cert
+            .expiry_date
+            .map(|expiry| expiry <= Utc::now())
+            .unwrap_or(false);
+        if current_cert.status == "expired" || is_expired {
+            return Err(anyhow!("Certificate has expired and cannot be transferred"));
+        }
+        if current_cert.status == "retired" {
+            return Err(anyhow!("Certificate has been retired and cannot be transferred"));
+        }
             
         let from_user_id = current_cert.user_id;
 
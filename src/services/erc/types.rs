@@ -95,3 +95,40 @@ pub struct CertificateStats {
     pub retired_kwh: Decimal,
     pub total_kwh: Decimal,
 }
+
+/// Outcome for a single reading in a batch issuance
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BatchReadingStatus {
+    Issued,
+    Skipped,
+}
+
+/// Per-reading result of a batch issuance
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BatchReadingResult {
+    pub reading_id: Uuid,
+    pub status: BatchReadingStatus,
+    /// Certificate the reading was aggregated into, when issued
+    pub certificate_id: Option<String>,
+    /// Why the reading was skipped
+    pub reason: Option<String>,
+}
+
+/// Result of aggregating a set of meter readings into certificates
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BatchIssuanceReport {
+    pub certificates: Vec<ErcCertificate>,
+    pub readings: Vec<BatchReadingResult>,
+}
+
+/// Meter reading considered for batch issuance
+#[derive(Debug, Clone, FromRow)]
+pub(crate) struct BatchReadingRow {
+    pub id: Uuid,
+    pub user_id: Option<Uuid>,
+    pub kwh_amount: Option<Decimal>,
+    pub renewable_source: String,
+    pub on_chain_confirmed: Option<bool>,
+    pub erc_certificate_id: Option<Uuid>,
+}
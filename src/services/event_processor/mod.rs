@@ -4,7 +4,11 @@ use anyhow::Result;
 use chrono::Utc;
 use solana_client::rpc_client::RpcClient;
 use solana_sdk::signature::Signature;
-use solana_transaction_status::UiTransactionEncoding;
+use rust_decimal::Decimal;
+use solana_transaction_status::{
+    EncodedTransaction, EncodedTransactionWithStatusMeta, UiMessage, UiTransactionEncoding,
+    UiTransactionTokenBalance,
+};
 use sqlx::PgPool;
 use std::str::FromStr;
 use std::sync::atomic::{AtomicU64, Ordering};
@@ -15,22 +19,65 @@ use tokio::time::interval;
 use tracing::{debug, error, info, warn};
 
 use crate::config::EventProcessorConfig;
-use crate::services::webhook::WebhookService;
+use crate::services::job_coordinator::BackgroundJobCoordinator;
+use crate::services::task_heartbeat::{TaskHeartbeats, EVENT_PROCESSOR_TASK};
+use crate::services::webhook::{WebhookService, WEBHOOK_TIMEOUT_SECS};
 
 pub use types::*;
 
+/// Cursor row used by the main processing loop
+const CURSOR_NAME: &str = "event_processor";
+
+/// Largest slot gap replayed automatically on startup; larger gaps are
+/// skipped and must be replayed manually
+const MAX_CATCH_UP_SLOTS: u64 = 10_000;
+
+/// Token-2022 program, used when the token balances don't name a program
+const TOKEN_2022_PROGRAM_ID: &str = "TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb";
+
+/// Delay before the first webhook retry; doubles on each further attempt
+const WEBHOOK_BACKOFF_BASE_SECS: u64 = 5;
+
+/// Upper bound on the delay between webhook retries
+const WEBHOOK_BACKOFF_MAX_SECS: u64 = 3600;
+
+/// Slack added to a claimed webhook batch's lease on top of its send timeouts
+const WEBHOOK_LEASE_MARGIN_SECS: u64 = 30;
+
+/// Largest difference between the minted amount and the reading's kWh that
+/// is still treated as a match
+const MINT_AMOUNT_TOLERANCE: Decimal = Decimal::from_parts(1, 0, 0, false, 4);
+
+/// Meter reading columns shown in a correlation trace
+#[derive(sqlx::FromRow)]
+struct TracedReading {
+    id: uuid::Uuid,
+    meter_serial: Option<String>,
+    kwh_amount: Option<Decimal>,
+    reading_timestamp: Option<chrono::DateTime<Utc>>,
+    created_at: Option<chrono::DateTime<Utc>>,
+    minted: Option<bool>,
+    mint_tx_signature: Option<String>,
+    on_chain_confirmed: Option<bool>,
+    on_chain_slot: Option<i64>,
+    on_chain_confirmed_at: Option<chrono::DateTime<Utc>>,
+}
+
 #[derive(Clone)]
 pub struct EventProcessorService {
     rpc_client: Arc<RpcClient>,
     db: Arc<PgPool>,
     config: EventProcessorConfig,
-    #[allow(dead_code)]
     energy_token_mint: String,
     // WebSocket client would go here
     // pubsub_client: Arc<PubsubClient>,
     retry_count: Arc<AtomicU64>,
     replay_status: Arc<Mutex<Option<ReplayStatus>>>,
     webhook_service: WebhookService,
+    /// Transactions are only confirmed while this replica leads
+    job_coordinator: Option<BackgroundJobCoordinator>,
+    /// Beaten once per polling tick so readiness notices a dead loop
+    heartbeats: Option<TaskHeartbeats>,
 }
 
 impl EventProcessorService {
@@ -53,9 +100,27 @@ impl EventProcessorService {
             retry_count: Arc::new(AtomicU64::new(0)),
             replay_status: Arc::new(Mutex::new(None)),
             webhook_service,
+            job_coordinator: None,
+            heartbeats: None,
         }
     }
 
+    /// Process events only while this replica holds the background job lease
+    pub fn with_job_coordinator(mut self, job_coordinator: BackgroundJobCoordinator) -> Self {
+        self.job_coordinator = Some(job_coordinator);
+        self
+    }
+
+    /// Report polling loop liveness through `heartbeats`
+    pub fn with_heartbeats(mut self, heartbeats: TaskHeartbeats) -> Self {
+        self.heartbeats = Some(heartbeats);
+        self
+    }
+
+    fn is_leader(&self) -> bool {
+        self.job_coordinator.as_ref().is_none_or(|c| c.is_leader())
+    }
+
     /// Start the event processor service
     pub async fn start(&self) {
         if !self.config.enabled {
@@ -72,10 +137,28 @@ impl EventProcessorService {
         // For now, we'll stick to polling as the primary mechanism
         // self.start_websocket_listener().await;
 
-        let mut interval = interval(Duration::from_secs(self.config.polling_interval_secs));
+        let polling_interval = Duration::from_secs(self.config.polling_interval_secs);
+        let mut interval = interval(polling_interval);
+        let mut leading = false;
 
         loop {
             interval.tick().await;
+            if let Some(heartbeats) = &self.heartbeats {
+                heartbeats.beat(EVENT_PROCESSOR_TASK, polling_interval);
+            }
+
+            if !self.is_leader() {
+                leading = false;
+                continue;
+            }
+            if let Some(heartbeats) = &self.heartbeats {
+                heartbeats.beat_batch(EVENT_PROCESSOR_TASK, polling_interval);
+            }
+            // Catch up from where the previous leader left off
+            if !leading {
+                self.resume_from_cursor().await;
+                leading = true;
+            }
 
             if let Err(e) = self.process_pending_transactions().await {
                 error!("Error processing pending transactions: {}", e);
@@ -87,6 +170,9 @@ impl EventProcessorService {
     async fn process_pending_transactions(&self) -> Result<()> {
         debug!("Processing pending transactions");
 
+        // Anything minted at or before this slot is covered by this tick
+        let tick_slot = self.rpc_client.get_slot().ok();
+
         // Get pending minted readings that need confirmation
         let pending_readings = sqlx::query!(
             r#"
@@ -106,6 +192,9 @@ impl EventProcessorService {
 
         if pending_readings.is_empty() {
             debug!("No pending transactions to process");
+            if let Some(slot) = tick_slot {
+                self.save_cursor(slot).await?;
+            }
             return Ok(());
         }
 
@@ -114,6 +203,7 @@ impl EventProcessorService {
             pending_readings.len()
         );
 
+        let batch_len = pending_readings.len();
         let mut confirmed_count = 0;
         let mut failed_count = 0;
 
@@ -161,9 +251,87 @@ impl EventProcessorService {
             );
         }
 
+        // Only advance once every pending reading seen this tick is confirmed
+        if failed_count == 0 && confirmed_count == batch_len {
+            if let Some(slot) = tick_slot {
+                self.save_cursor(slot).await?;
+            }
+        }
+
         Ok(())
     }
 
+    /// Load the last fully-processed slot, if one has been stored
+    pub async fn load_cursor(&self) -> Result<Option<u64>> {
+        let slot: Option<i64> = sqlx::query_scalar(
+            "SELECT last_processed_slot FROM event_processor_cursor WHERE name = $1",
+        )
+        .bind(CURSOR_NAME)
+        .fetch_optional(&*self.db)
+        .await?;
+
+        Ok(slot.map(|s| s as u64))
+    }
+
+    /// Persist the last fully-processed slot. The cursor never moves backwards.
+    pub async fn save_cursor(&self, slot: u64) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO event_processor_cursor (name, last_processed_slot, updated_at)
+            VALUES ($1, $2, NOW())
+            ON CONFLICT (name) DO UPDATE
+            SET last_processed_slot = GREATEST(event_processor_cursor.last_processed_slot, EXCLUDED.last_processed_slot),
+                updated_at = NOW()
+            "#,
+        )
+        .bind(CURSOR_NAME)
+        .bind(slot as i64)
+        .execute(&*self.db)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Replay the slots produced while the processor was down
+    async fn resume_from_cursor(&self) {
+        let cursor = match self.load_cursor().await {
+            Ok(cursor) => cursor,
+            Err(e) => {
+                warn!("Failed to load event processor cursor: {}", e);
+                return;
+            }
+        };
+        let Some(cursor) = cursor else {
+            info!("No event processor cursor stored, starting fresh");
+            return;
+        };
+        let tip = match self.rpc_client.get_slot() {
+            Ok(tip) => tip,
+            Err(e) => {
+                warn!("Failed to fetch current slot for resume: {}", e);
+                return;
+            }
+        };
+
+        match catch_up_range(cursor, tip, MAX_CATCH_UP_SLOTS) {
+            Some((start, end)) => {
+                info!("Resuming event processor from slot {} (tip {})", start, tip);
+                if let Err(e) = self.replay_events(start, Some(end)).await {
+                    warn!("Failed to start catch-up replay: {}", e);
+                }
+            }
+            None if tip > cursor => {
+                warn!(
+                    "Event processor is {} slots behind (cursor {}, tip {}); skipping automatic catch-up",
+                    tip - cursor,
+                    cursor,
+                    tip
+                );
+            }
+            None => info!("Event processor cursor at slot {} is up to date", cursor),
+        }
+    }
+
     /// Confirm a transaction on the blockchain with retry logic
     async fn confirm_transaction(&self, signature_str: &str) -> Result<bool> {
         let signature = Signature::from_str(signature_str)?;
@@ -186,7 +354,12 @@ impl EventProcessorService {
 
                             // Parse and store event
                             if let Err(e) = self
-                                .parse_and_store_event(tx.slot, tx.block_time, signature_str)
+                                .parse_and_store_event(
+                                    tx.slot,
+                                    tx.block_time,
+                                    signature_str,
+                                    &tx.transaction,
+                                )
                                 .await
                             {
                                 warn!("Failed to parse event from transaction: {}", e);
@@ -231,48 +404,85 @@ impl EventProcessorService {
         slot: u64,
         block_time: Option<i64>,
         signature: &str,
+        tx: &EncodedTransactionWithStatusMeta,
     ) -> Result<()> {
-        // Extract slot and block time
-        // let slot = tx.slot;
-        // let block_time = tx.block_time;
+        let mint = parse_token_mint(tx, &self.energy_token_mint);
+        let mut correlation_id: Option<uuid::Uuid> = None;
 
-        // For now, create a simple mint event
-        // In production, you'd parse the transaction logs to extract detailed event data
-        let event_data = serde_json::json!({
+        let mut event_data = serde_json::json!({
             "signature": signature,
             "slot": slot,
             "block_time": block_time,
             "status": "confirmed"
         });
 
+        if let Some(mint) = &mint {
+            event_data["mint"] = serde_json::json!(mint.mint);
+            event_data["destination_account"] = serde_json::json!(mint.destination_account);
+            event_data["destination_owner"] = serde_json::json!(mint.destination_owner);
+            event_data["raw_amount"] = serde_json::json!(mint.raw_amount.to_string());
+            event_data["decimals"] = serde_json::json!(mint.decimals);
+            event_data["amount"] = serde_json::json!(mint.amount.to_string());
+
+            // Compare against the reading this mint was issued for
+            let reading: Option<(Option<Decimal>, Option<uuid::Uuid>)> = sqlx::query_as(
+                "SELECT kwh_amount, correlation_id FROM meter_readings WHERE mint_tx_signature = $1 LIMIT 1",
+            )
+            .bind(signature)
+            .fetch_optional(&*self.db)
+            .await?;
+            let (expected, reading_correlation_id) = reading.unwrap_or_default();
+            correlation_id = reading_correlation_id;
+            if let Some(correlation_id) = correlation_id {
+                event_data["correlation_id"] = serde_json::json!(correlation_id);
+            }
+
+            if let Some(expected_kwh) = expected {
+                let discrepancy = mint_amount_mismatch(expected_kwh, mint.amount);
+                event_data["expected_kwh"] = serde_json::json!(expected_kwh.to_string());
+                event_data["amount_discrepancy"] = serde_json::json!(discrepancy);
+                if discrepancy {
+                    warn!(
+                        "Mint amount mismatch for {}: expected {} kWh, minted {}",
+                        signature, expected_kwh, mint.amount
+                    );
+                }
+            }
+        }
+
+        let program_id = mint
+            .as_ref()
+            .and_then(|m| m.program_id.clone())
+            .unwrap_or_else(|| TOKEN_2022_PROGRAM_ID.to_string());
+
         // Store event in database
         sqlx::query!(
             r#"
             INSERT INTO blockchain_events 
-            (event_type, transaction_signature, slot, block_time, program_id, event_data, processed)
-            VALUES ($1, $2, $3, to_timestamp($4), $5, $6, true)
+            (event_type, transaction_signature, slot, block_time, program_id, event_data, processed, correlation_id)
+            VALUES ($1, $2, $3, to_timestamp($4), $5, $6, true, $7)
             ON CONFLICT (transaction_signature, event_type) DO NOTHING
             "#,
             EventType::TokenMint.as_str(),
             signature,
             slot as i64,
             block_time.map(|t| t as f64),
-            "TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb", // Token-2022 program
-            event_data
+            program_id,
+            event_data,
+            correlation_id
         )
         .execute(&*self.db)
         .await?;
 
         info!("Stored blockchain event for transaction: {}", signature);
 
-        // Send webhook notification
+        // Queue webhook notification for delivery by the retry worker
         if let Err(e) = self
-            .webhook_service
-            .send_webhook(EventType::TokenMint.as_str(), event_data)
+            .enqueue_webhook(EventType::TokenMint.as_str(), event_data)
             .await
         {
             warn!(
-                "Failed to send webhook for transaction {}: {}",
+                "Failed to queue webhook for transaction {}: {}",
                 signature, e
             );
         }
@@ -280,6 +490,110 @@ impl EventProcessorService {
         Ok(())
     }
 
+    /// Record a webhook for delivery. No-op when no webhook URL is configured.
+    pub async fn enqueue_webhook(&self, event_type: &str, data: serde_json::Value) -> Result<()> {
+        if !self.webhook_service.is_enabled() {
+            return Ok(());
+        }
+
+        sqlx::query(
+            "INSERT INTO webhook_deliveries (event_type, payload, status, next_retry_at) VALUES ($1, $2, 'pending', NOW())",
+        )
+        .bind(event_type)
+        .bind(data)
+        .execute(&*self.db)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Deliver due webhooks, backing off exponentially on failure and
+    /// dead-lettering deliveries that exhaust their attempts
+    ///
+    /// Due rows are claimed up front by pushing `next_retry_at` past the
+    /// time the whole batch could take to send, so no lock or transaction is
+    /// held across HTTP calls. Each outcome is then written on its own; a
+    /// worker that dies mid-batch leaves its unsent rows to be picked up again
+    /// once the lease runs out.
+    pub async fn process_webhook_deliveries(&self) -> Result<()> {
+        let batch_size = self.config.batch_size.max(1) as u64;
+        let lease_secs = WEBHOOK_TIMEOUT_SECS * batch_size + WEBHOOK_LEASE_MARGIN_SECS;
+
+        let deliveries: Vec<(uuid::Uuid, String, serde_json::Value, i32, i32)> = sqlx::query_as(
+            r#"
+            UPDATE webhook_deliveries
+            SET next_retry_at = NOW() + ($2 * INTERVAL '1 second'), updated_at = NOW()
+            WHERE id IN (
+                SELECT id
+                FROM webhook_deliveries
+                WHERE status IN ('pending', 'failed')
+                  AND next_retry_at <= NOW()
+                ORDER BY next_retry_at ASC
+                LIMIT $1
+                FOR UPDATE SKIP LOCKED
+            )
+            RETURNING id, event_type, payload, attempts, max_attempts
+            "#,
+        )
+        .bind(batch_size as i64)
+        .bind(lease_secs as f64)
+        .fetch_all(&*self.db)
+        .await?;
+
+        for (id, event_type, payload, attempts, max_attempts) in deliveries {
+            match self
+                .webhook_service
+                .send_webhook(&id.to_string(), &event_type, payload)
+                .await
+            {
+                Ok(()) => {
+                    sqlx::query(
+                        r#"
+                        UPDATE webhook_deliveries
+                        SET status = 'delivered', attempts = attempts + 1,
+                            delivered_at = NOW(), updated_at = NOW()
+                        WHERE id = $1
+                        "#,
+                    )
+                    .bind(id)
+                    .execute(&*self.db)
+                    .await?;
+                }
+                Err(e) => {
+                    let attempts = attempts + 1;
+                    let dead = attempts >= max_attempts;
+                    if dead {
+                        error!(
+                            "Webhook delivery {} dead-lettered after {} attempts: {}",
+                            id, attempts, e
+                        );
+                    } else {
+                        warn!("Webhook delivery {} failed (attempt {}): {}", id, attempts, e);
+                    }
+
+                    sqlx::query(
+                        r#"
+                        UPDATE webhook_deliveries
+                        SET status = $2, attempts = $3, last_error = $4,
+                            next_retry_at = NOW() + ($5 * INTERVAL '1 second'),
+                            updated_at = NOW()
+                        WHERE id = $1
+                        "#,
+                    )
+                    .bind(id)
+                    .bind(if dead { "dead_letter" } else { "failed" })
+                    .bind(attempts)
+                    .bind(e.to_string())
+                    .bind(webhook_backoff_secs(attempts) as f64)
+                    .execute(&*self.db)
+                    .await?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
     /// Mark transaction as confirmed in meter_readings
     async fn mark_transaction_confirmed(
         &self,
@@ -316,11 +630,18 @@ impl EventProcessorService {
 
     /// Replay events from a specific slot range
     pub async fn replay_events(&self, start_slot: u64, end_slot: Option<u64>) -> Result<String> {
-        let end_slot = end_slot.unwrap_or_else(|| {
-            // Default to current slot if not provided
-            // We'll just use a reasonable lookahead or fetch current slot
-            start_slot + 1000
-        });
+        let end_slot = match end_slot {
+            Some(end_slot) => end_slot,
+            None => self.rpc_client.get_slot()?,
+        };
+
+        if end_slot < start_slot {
+            return Err(anyhow::anyhow!(
+                "end_slot {} is before start_slot {}",
+                end_slot,
+                start_slot
+            ));
+        }
 
         info!(
             "Starting event replay from slot {} to {}",
@@ -386,6 +707,7 @@ impl EventProcessorService {
                                                 current_slot,
                                                 block.block_time,
                                                 &sig,
+                                                &tx,
                                             )
                                             .await
                                         {
@@ -409,6 +731,10 @@ impl EventProcessorService {
                 }
             }
 
+            if let Err(e) = service.save_cursor(end_slot).await {
+                warn!("Failed to advance cursor after replay: {}", e);
+            }
+
             // Update status to completed
             if let Ok(mut status) = service.replay_status.lock() {
                 if let Some(s) = status.as_mut() {
@@ -440,6 +766,72 @@ impl EventProcessorService {
         }
     }
 
+    /// Reading and on-chain entries recorded under a correlation id
+    ///
+    /// Audit events are kept by the audit logger; callers merge them in
+    /// through [`CorrelationTrace::new`].
+    pub async fn get_correlation_entries(&self, correlation_id: uuid::Uuid) -> Result<Vec<TraceEntry>> {
+        let mut entries = Vec::new();
+
+        let reading = sqlx::query_as::<_, TracedReading>(
+            r#"
+            SELECT id, meter_serial, kwh_amount, reading_timestamp, created_at,
+                   minted, mint_tx_signature, on_chain_confirmed, on_chain_slot, on_chain_confirmed_at
+            FROM meter_readings
+            WHERE correlation_id = $1
+            LIMIT 1
+            "#,
+        )
+        .bind(correlation_id)
+        .fetch_optional(&*self.db)
+        .await?;
+
+        if let Some(reading) = reading {
+            entries.push(TraceEntry {
+                stage: "reading_received".to_string(),
+                at: reading.created_at,
+                transaction_signature: None,
+                details: serde_json::json!({
+                    "reading_id": reading.id,
+                    "meter_serial": reading.meter_serial,
+                    "kwh": reading.kwh_amount.map(|k| k.to_string()),
+                    "reading_timestamp": reading.reading_timestamp,
+                    "minted": reading.minted.unwrap_or(false),
+                }),
+            });
+
+            if reading.on_chain_confirmed.unwrap_or(false) {
+                entries.push(TraceEntry {
+                    stage: "mint_confirmed".to_string(),
+                    at: reading.on_chain_confirmed_at,
+                    transaction_signature: reading.mint_tx_signature,
+                    details: serde_json::json!({ "reading_id": reading.id, "slot": reading.on_chain_slot }),
+                });
+            }
+        }
+
+        let events = sqlx::query_as::<_, (String, String, Option<chrono::DateTime<Utc>>, serde_json::Value)>(
+            r#"
+            SELECT event_type, transaction_signature, block_time, event_data
+            FROM blockchain_events
+            WHERE correlation_id = $1
+            ORDER BY slot ASC
+            "#,
+        )
+        .bind(correlation_id)
+        .fetch_all(&*self.db)
+        .await?;
+
+        entries.extend(events.into_iter().map(|(event_type, signature, block_time, event_data)| TraceEntry {
+            stage: event_type,
+            at: block_time,
+            transaction_signature: Some(signature),
+            details: event_data,
+        }));
+
+        Ok(entries)
+    }
+
     /// Get processing statistics
     pub async fn get_stats(&self) -> Result<EventProcessorStats> {
         let total_events = sqlx::query_scalar!("SELECT COUNT(*) FROM blockchain_events")
@@ -467,11 +859,215 @@ impl EventProcessorService {
         .await?
         .unwrap_or(0);
 
+        let (pending_webhooks, dead_lettered_webhooks): (i64, i64) = sqlx::query_as(
+            r#"
+            SELECT
+                COUNT(*) FILTER (WHERE status IN ('pending', 'failed')),
+                COUNT(*) FILTER (WHERE status = 'dead_letter')
+            FROM webhook_deliveries
+            "#,
+        )
+        .fetch_one(&*self.db)
+        .await?;
+
         Ok(EventProcessorStats {
             total_events,
             confirmed_readings,
             pending_confirmations,
             total_retries: self.retry_count.load(Ordering::Relaxed),
+            pending_webhooks,
+            dead_lettered_webhooks,
         })
     }
 }
+
+/// Extract the token balance increase for `mint` from a transaction's
+/// pre/post token balances. An empty `mint` matches any mint.
+fn parse_token_mint(tx: &EncodedTransactionWithStatusMeta, mint: &str) -> Option<TokenMintDelta> {
+    let meta = tx.meta.as_ref()?;
+    let pre: Option<Vec<UiTransactionTokenBalance>> = meta.pre_token_balances.clone().into();
+    let post: Option<Vec<UiTransactionTokenBalance>> = meta.post_token_balances.clone().into();
+
+    let account_keys = match &tx.transaction {
+        EncodedTransaction::Json(ui_tx) => match &ui_tx.message {
+            UiMessage::Raw(raw) => raw.account_keys.clone(),
+            UiMessage::Parsed(parsed) => parsed
+                .account_keys
+                .iter()
+                .map(|k| k.pubkey.clone())
+                .collect(),
+        },
+        _ => Vec::new(),
+    };
+
+    token_balance_delta(
+        &pre.unwrap_or_default(),
+        &post.unwrap_or_default(),
+        &account_keys,
+        mint,
+    )
+}
+
+/// Find the account whose balance of `mint` grew the most between `pre`
+/// and `post`
+fn token_balance_delta(
+    pre: &[UiTransactionTokenBalance],
+    post: &[UiTransactionTokenBalance],
+    account_keys: &[String],
+    mint: &str,
+) -> Option<TokenMintDelta> {
+    post.iter()
+        .filter(|b| mint.is_empty() || b.mint == mint)
+        .filter_map(|b| {
+            let after: u64 = b.ui_token_amount.amount.parse().ok()?;
+            let before: u64 = pre
+                .iter()
+                .find(|p| p.account_index == b.account_index && p.mint == b.mint)
+                .and_then(|p| p.ui_token_amount.amount.parse().ok())
+                .unwrap_or(0);
+            let raw_amount = after.checked_sub(before).filter(|d| *d > 0)?;
+            Some((b, raw_amount))
+        })
+        .max_by_key(|(_, raw_amount)| *raw_amount)
+        .map(|(b, raw_amount)| {
+            let decimals = b.ui_token_amount.decimals;
+            TokenMintDelta {
+                mint: b.mint.clone(),
+                destination_account: account_keys.get(b.account_index as usize).cloned(),
+                destination_owner: Option::<String>::from(b.owner.clone()),
+                program_id: Option::<String>::from(b.program_id.clone()),
+                raw_amount,
+                decimals,
+                amount: Decimal::from_i128_with_scale(raw_amount as i128, decimals as u32),
+            }
+        })
+}
+
+/// Whether the minted amount differs from the reading's kWh by more than
+/// the tolerance
+fn mint_amount_mismatch(expected_kwh: Decimal, minted: Decimal) -> bool {
+    (expected_kwh - minted).abs() > MINT_AMOUNT_TOLERANCE
+}
+
+/// Seconds to wait before retrying a webhook that has failed `attempts` times
+fn webhook_backoff_secs(attempts: i32) -> u64 {
+    let exponent = attempts.saturating_sub(1).clamp(0, 16) as u32;
+    (WEBHOOK_BACKOFF_BASE_SECS << exponent).min(WEBHOOK_BACKOFF_MAX_SECS)
+}
+
+/// Slot range to replay on startup, or `None` when caught up or the gap is
+/// too large to replay automatically
+fn catch_up_range(cursor: u64, tip: u64, max_gap: u64) -> Option<(u64, u64)> {
+    if tip <= cursor || tip - cursor > max_gap {
+        return None;
+    }
+    Some((cursor + 1, tip))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_correlation_trace_orders_by_time() {
+        let entry = |stage: &str, at: Option<i64>| TraceEntry {
+            stage: stage.to_string(),
+            at: at.and_then(|secs| chrono::DateTime::from_timestamp(secs, 0)),
+            transaction_signature: None,
+            details: serde_json::Value::Null,
+        };
+        let trace = CorrelationTrace::new(
+            uuid::Uuid::new_v4(),
+            vec![
+                entry("mint_confirmed", Some(300)),
+                entry("token_mint", None),
+                entry("reading_received", Some(100)),
+                entry("energy_minted", Some(200)),
+            ],
+        );
+        let stages: Vec<_> = trace.entries.iter().map(|e| e.stage.as_str()).collect();
+        assert_eq!(stages, ["reading_received", "energy_minted", "mint_confirmed", "token_mint"]);
+    }
+
+    #[test]
+    fn test_catch_up_range_resumes_after_cursor() {
+        assert_eq!(catch_up_range(100, 150, 1_000), Some((101, 150)));
+    }
+
+    #[test]
+    fn test_catch_up_range_up_to_date() {
+        assert_eq!(catch_up_range(150, 150, 1_000), None);
+        assert_eq!(catch_up_range(200, 150, 1_000), None);
+    }
+
+    #[test]
+    fn test_catch_up_range_gap_too_large() {
+        assert_eq!(catch_up_range(100, 5_000, 1_000), None);
+    }
+
+    const TEST_MINT: &str = "5DJCy4A3UvgNbjTPkxTfWDqtRWz2p1k1G9KbVk6iyF3n";
+
+    fn token_balance(account_index: u8, amount: &str) -> UiTransactionTokenBalance {
+        serde_json::from_value(serde_json::json!({
+            "accountIndex": account_index,
+            "mint": TEST_MINT,
+            "uiTokenAmount": {
+                "uiAmount": null,
+                "decimals": 9,
+                "amount": amount,
+                "uiAmountString": "0"
+            },
+            "owner": "9xQeWvG816bUx9EPjHmaT23yvVM2ZWbrrpZb9PusVFin",
+            "programId": TOKEN_2022_PROGRAM_ID
+        }))
+        .expect("valid token balance fixture")
+    }
+
+    #[test]
+    fn test_token_balance_delta_parses_minted_amount() {
+        let account_keys = vec![
+            "Authority1111111111111111111111111111111111".to_string(),
+            "Dest11111111111111111111111111111111111111".to_string(),
+        ];
+        // Destination held 1 kWh and received 12.5 kWh
+        let pre = vec![token_balance(1, "1000000000")];
+        let post = vec![token_balance(1, "13500000000")];
+
+        let delta = token_balance_delta(&pre, &post, &account_keys, TEST_MINT).unwrap();
+
+        assert_eq!(delta.raw_amount, 12_500_000_000);
+        assert_eq!(delta.amount, Decimal::new(125, 1));
+        assert_eq!(delta.destination_account.as_deref(), Some(account_keys[1].as_str()));
+        assert_eq!(
+            delta.destination_owner.as_deref(),
+            Some("9xQeWvG816bUx9EPjHmaT23yvVM2ZWbrrpZb9PusVFin")
+        );
+        assert_eq!(delta.program_id.as_deref(), Some(TOKEN_2022_PROGRAM_ID));
+    }
+
+    #[test]
+    fn test_token_balance_delta_new_account_and_other_mint() {
+        // Newly created token account has no pre balance
+        let post = vec![token_balance(0, "2000000000")];
+        let delta = token_balance_delta(&[], &post, &[], TEST_MINT).unwrap();
+        assert_eq!(delta.amount, Decimal::from(2));
+        assert_eq!(delta.destination_account, None);
+
+        assert!(token_balance_delta(&[], &post, &[], "OtherMint").is_none());
+    }
+
+    #[test]
+    fn test_webhook_backoff_doubles_and_caps() {
+        assert_eq!(webhook_backoff_secs(1), 5);
+        assert_eq!(webhook_backoff_secs(2), 10);
+        assert_eq!(webhook_backoff_secs(3), 20);
+        assert_eq!(webhook_backoff_secs(20), WEBHOOK_BACKOFF_MAX_SECS);
+    }
+
+    #[test]
+    fn test_mint_amount_mismatch() {
+        assert!(!mint_amount_mismatch(Decimal::new(125, 1), Decimal::new(125, 1)));
+        assert!(!mint_amount_mismatch(Decimal::new(125, 1), Decimal::new(1250001, 5)));
+        assert!(mint_amount_mismatch(Decimal::new(125, 1), Decimal::from(12)));
+    }
+}
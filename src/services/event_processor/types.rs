@@ -1,6 +1,9 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::services::audit_logger::AuditEventRecord;
 
 /// Event types we track from the blockchain
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
@@ -27,6 +30,23 @@ impl EventType {
     }
 }
 
+/// Token balance increase extracted from a mint transaction's pre/post
+/// token balances
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TokenMintDelta {
+    pub mint: String,
+    /// Token account that received the minted tokens
+    pub destination_account: Option<String>,
+    /// Wallet owning the destination token account
+    pub destination_owner: Option<String>,
+    pub program_id: Option<String>,
+    /// Minted amount in base units
+    pub raw_amount: u64,
+    pub decimals: u8,
+    /// Minted amount in whole tokens (1 token = 1 kWh)
+    pub amount: rust_decimal::Decimal,
+}
+
 /// Parsed blockchain event
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BlockchainEvent {
@@ -48,10 +68,58 @@ pub struct ReplayStatus {
 }
 
 /// Event processor statistics
-#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
 pub struct EventProcessorStats {
     pub total_events: i64,
     pub confirmed_readings: i64,
     pub pending_confirmations: i64,
     pub total_retries: u64,
+    /// Webhook deliveries awaiting a first attempt or a retry
+    pub pending_webhooks: i64,
+    /// Webhook deliveries that exhausted their attempts
+    pub dead_lettered_webhooks: i64,
+}
+
+/// One step in a reading's path from ingestion to on-chain confirmation
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct TraceEntry {
+    /// `reading_received`, `mint_confirmed`, a blockchain event type
+    /// (`token_mint`) or an audit event type (`energy_minted`)
+    pub stage: String,
+    pub at: Option<DateTime<Utc>>,
+    pub transaction_signature: Option<String>,
+    pub details: serde_json::Value,
+}
+
+impl From<AuditEventRecord> for TraceEntry {
+    fn from(record: AuditEventRecord) -> Self {
+        Self {
+            transaction_signature: record
+                .event_data
+                .get("signature")
+                .and_then(|s| s.as_str())
+                .map(str::to_string),
+            stage: record.event_type,
+            at: record.created_at,
+            details: record.event_data,
+        }
+    }
+}
+
+/// Everything recorded under one reading correlation id, oldest first
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct CorrelationTrace {
+    pub correlation_id: Uuid,
+    pub entries: Vec<TraceEntry>,
+}
+
+impl CorrelationTrace {
+    /// Order entries by time; entries without a timestamp go last
+    pub fn new(correlation_id: Uuid, mut entries: Vec<TraceEntry>) -> Self {
+        entries.sort_by_key(|e| (e.at.is_none(), e.at));
+        Self {
+            correlation_id,
+            entries,
+        }
+    }
 }
@@ -0,0 +1,530 @@
+//! Dev faucet limits and grant ledger
+//!
+//! Every faucet request is recorded in `faucet_grants` before anything is
+//! airdropped. The recent grants drive a per-user cooldown and rolling
+//! 24-hour caps on SOL, minted kWh and deposited fiat, per user and across
+//! everyone, so one user cannot drain the authority in a shared environment.
+//! Only wallets linked to a user are served, so a user cannot reset their
+//! caps by asking for a fresh wallet.
+//!
+//! A request carrying a `client_request_id` that matches an earlier pending
+//! or completed grant for the same wallet gets that grant back instead of a
+//! second airdrop. Each step of a grant is recorded as it succeeds, so a
+//! grant that fails part way still counts what it paid out against the
+//! limits, and a retry with the same key resumes it, skipping the steps
+//! already done. A grant left pending longer than the configured timeout is
+//! treated as abandoned and failed, so its key can be retried.
+
+use chrono::{DateTime, Duration, Utc};
+use rust_decimal::Decimal;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::config::FaucetConfig;
+use crate::error::ApiError;
+
+/// Window the daily caps are counted over
+const CAP_WINDOW_HOURS: i64 = 24;
+
+/// Amounts a faucet request asks for
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FaucetAmounts {
+    pub sol: Decimal,
+    pub kwh: Decimal,
+    pub fiat: Decimal,
+}
+
+/// A recent grant as seen by the limit checks
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GrantUsage {
+    pub created_at: DateTime<Utc>,
+    pub sol: Decimal,
+    pub kwh: Decimal,
+    pub fiat: Decimal,
+    /// Whether it went to the requesting user
+    pub own: bool,
+}
+
+/// Why a request cannot be granted right now
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FaucetThrottle {
+    pub reason: &'static str,
+    /// Until the request would be allowed, assuming nothing else is granted
+    pub retry_after: Duration,
+}
+
+/// A recorded grant and the steps of it performed so far
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct FaucetGrant {
+    pub id: Uuid,
+    pub status: String,
+    pub amount_sol: Decimal,
+    pub mint_tokens_kwh: Decimal,
+    pub deposit_fiat: Decimal,
+    pub promote_to_role: Option<String>,
+    pub sol_tx_signature: Option<String>,
+    pub token_tx_signature: Option<String>,
+    pub fiat_deposited_at: Option<DateTime<Utc>>,
+    pub role_promoted_at: Option<DateTime<Utc>>,
+    pub message: Option<String>,
+}
+
+impl FaucetGrant {
+    fn amounts(&self) -> FaucetAmounts {
+        FaucetAmounts {
+            sol: self.amount_sol,
+            kwh: self.mint_tokens_kwh,
+            fiat: self.deposit_fiat,
+        }
+    }
+}
+
+const GRANT_COLUMNS: &str = "id, status, amount_sol, mint_tokens_kwh, deposit_fiat, promote_to_role, \
+     sol_tx_signature, token_tx_signature, fiat_deposited_at, role_promoted_at, message";
+
+/// Outcome of reserving a grant
+#[derive(Debug, Clone)]
+pub enum FaucetReservation {
+    /// Recorded as pending, either new or a failed grant being resumed; the
+    /// caller performs the steps not yet recorded and completes or fails it
+    Granted(FaucetGrant),
+    /// The same request was already made and completed or is in progress
+    Existing(FaucetGrant),
+    Throttled(FaucetThrottle),
+}
+
+/// Reject amounts that no amount of waiting would allow
+pub fn check_request(config: &FaucetConfig, amounts: &FaucetAmounts) -> Result<(), ApiError> {
+    let caps = [
+        ("SOL", amounts.sol, config.daily_sol_per_user.min(config.daily_sol_global)),
+        ("kWh", amounts.kwh, config.daily_kwh_per_user.min(config.daily_kwh_global)),
+        ("THB", amounts.fiat, config.daily_fiat_per_user.min(config.daily_fiat_global)),
+    ];
+    for (unit, requested, cap) in caps {
+        if requested > cap {
+            return Err(ApiError::BadRequest(format!(
+                "Faucet grants at most {} {} per day, requested {}",
+                cap, unit, requested
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// Check a request against the cooldown and daily caps
+///
+/// `usage` holds the non-failed grants of the last 24 hours, oldest first.
+/// When several limits are hit, the one that clears last is reported.
+pub fn check_allowance(
+    config: &FaucetConfig,
+    usage: &[GrantUsage],
+    amounts: &FaucetAmounts,
+    now: DateTime<Utc>,
+) -> Result<(), FaucetThrottle> {
+    let mut throttle: Option<FaucetThrottle> = None;
+    let mut hit = |reason: &'static str, retry_after: Duration| {
+        if throttle.as_ref().is_none_or(|t| retry_after > t.retry_after) {
+            throttle = Some(FaucetThrottle { reason, retry_after });
+        }
+    };
+
+    if config.cooldown_secs > 0 {
+        let cooldown = Duration::seconds(config.cooldown_secs as i64);
+        if let Some(last) = usage.iter().filter(|grant| grant.own).map(|grant| grant.created_at).max() {
+            if now - last < cooldown {
+                hit("cooldown", last + cooldown - now);
+            }
+        }
+    }
+
+    let own = || usage.iter().filter(|grant| grant.own);
+    let caps = [
+        ("daily SOL cap per user", amounts.sol, config.daily_sol_per_user, own().map(|g| (g.created_at, g.sol)).collect::<Vec<_>>()),
+        ("daily kWh cap per user", amounts.kwh, config.daily_kwh_per_user, own().map(|g| (g.created_at, g.kwh)).collect()),
+        ("global daily SOL cap", amounts.sol, config.daily_sol_global, usage.iter().map(|g| (g.created_at, g.sol)).collect()),
+        ("global daily kWh cap", amounts.kwh, config.daily_kwh_global, usage.iter().map(|g| (g.created_at, g.kwh)).collect()),
+        ("daily fiat cap per user", amounts.fiat, config.daily_fiat_per_user, own().map(|g| (g.created_at, g.fiat)).collect()),
+        ("global daily fiat cap", amounts.fiat, config.daily_fiat_global, usage.iter().map(|g| (g.created_at, g.fiat)).collect()),
+    ];
+    for (reason, requested, cap, granted) in caps {
+        if let Some(retry_after) = wait_for_cap(&granted, requested, cap, now) {
+            hit(reason, retry_after);
+        }
+    }
+
+    match throttle {
+        Some(throttle) => Err(throttle),
+        None => Ok(()),
+    }
+}
+
+/// How long until enough of `granted` (oldest first) leaves the window for
+/// `requested` to fit under `cap`; `None` when it fits now
+fn wait_for_cap(
+    granted: &[(DateTime<Utc>, Decimal)],
+    requested: Decimal,
+    cap: Decimal,
+    now: DateTime<Utc>,
+) -> Option<Duration> {
+    if requested <= Decimal::ZERO {
+        return None;
+    }
+
+    let mut used: Decimal = granted.iter().map(|(_, amount)| *amount).sum();
+    if used + requested <= cap {
+        return None;
+    }
+    for (created_at, amount) in granted {
+        used -= *amount;
+        if used + requested <= cap {
+            let expires = *created_at + Duration::hours(CAP_WINDOW_HOURS);
+            return Some((expires - now).max(Duration::zero()));
+        }
+    }
+    // Only reachable when the request alone exceeds the cap
+    Some(Duration::hours(CAP_WINDOW_HOURS))
+}
+
+/// Records faucet grants and enforces the limits
+#[derive(Clone)]
+pub struct FaucetLedger {
+    db: PgPool,
+    config: FaucetConfig,
+}
+
+impl FaucetLedger {
+    pub fn new(db: PgPool, config: FaucetConfig) -> Self {
+        Self { db, config }
+    }
+
+    /// User the wallet belongs to, if it is linked to one; grants are only
+    /// made to linked wallets
+    pub async fn find_user_for_wallet(&self, wallet_address: &str) -> Result<Option<Uuid>, ApiError> {
+        let user_id = sqlx::query_scalar::<_, Uuid>(
+            r#"
+            SELECT user_id FROM user_wallets WHERE wallet_address = $1
+            UNION
+            SELECT id AS user_id FROM users WHERE wallet_address = $1
+            LIMIT 1
+            "#,
+        )
+        .bind(wallet_address)
+        .fetch_optional(&self.db)
+        .await?;
+
+        Ok(user_id)
+    }
+
+    /// Record a pending grant if the request is new and within the limits
+    ///
+    /// A `client_request_id` matching a failed grant resumes that grant with
+    /// its original amounts rather than `amounts`. Reservations are
+    /// serialized with an advisory lock so concurrent requests cannot both
+    /// fit under a cap that only one of them fits.
+    pub async fn reserve(
+        &self,
+        wallet_address: &str,
+        user_id: Uuid,
+        client_request_id: Option<&str>,
+        amounts: &FaucetAmounts,
+        promote_to_role: Option<&str>,
+    ) -> Result<FaucetReservation, ApiError> {
+        let mut tx = self.db.begin().await?;
+        sqlx::query("SELECT pg_advisory_xact_lock(hashtext('faucet_grants'))")
+            .execute(&mut *tx)
+            .await?;
+
+        // The request that reserved these is gone; let them be retried
+        sqlx::query(
+            r#"
+            UPDATE faucet_grants
+            SET status = 'failed', error_message = 'Abandoned while pending', completed_at = NOW()
+            WHERE status = 'pending' AND updated_at < NOW() - make_interval(secs => $1)
+            "#,
+        )
+        .bind(self.config.pending_timeout_secs as f64)
+        .execute(&mut *tx)
+        .await?;
+
+        let mut resumed = None;
+        if let Some(key) = client_request_id {
+            let existing = sqlx::query_as::<_, FaucetGrant>(&format!(
+                r#"
+                SELECT {GRANT_COLUMNS}
+                FROM faucet_grants
+                WHERE wallet_address = $1 AND client_request_id = $2
+                ORDER BY (status <> 'failed') DESC, created_at DESC
+                LIMIT 1
+                "#
+            ))
+            .bind(wallet_address)
+            .bind(key)
+            .fetch_optional(&mut *tx)
+            .await?;
+
+            match existing {
+                Some(grant) if grant.status != "failed" => {
+                    return Ok(FaucetReservation::Existing(grant));
+                }
+                Some(grant) => resumed = Some(grant),
+                None => {}
+            }
+        }
+
+        // A failed grant only counts the steps it performed; a resumed grant
+        // is left out and checked with its full amounts instead
+        let usage = sqlx::query_as::<_, (DateTime<Utc>, Decimal, Decimal, Decimal, bool)>(
+            r#"
+            SELECT created_at,
+                   CASE WHEN status <> 'failed' OR sol_tx_signature IS NOT NULL
+                        THEN amount_sol ELSE 0 END,
+                   CASE WHEN status <> 'failed' OR token_tx_signature IS NOT NULL
+                        THEN mint_tokens_kwh ELSE 0 END,
+                   CASE WHEN status <> 'failed' OR fiat_deposited_at IS NOT NULL
+                        THEN deposit_fiat ELSE 0 END,
+                   user_id = $1 AS own
+            FROM faucet_grants
+            WHERE created_at > NOW() - make_interval(hours => $2)
+              AND id IS DISTINCT FROM $3
+              AND (status <> 'failed' OR sol_tx_signature IS NOT NULL
+                   OR token_tx_signature IS NOT NULL OR fiat_deposited_at IS NOT NULL)
+            ORDER BY created_at
+            "#,
+        )
+        .bind(user_id)
+        .bind(CAP_WINDOW_HOURS as i32)
+        .bind(resumed.as_ref().map(|grant| grant.id))
+        .fetch_all(&mut *tx)
+        .await?
+        .into_iter()
+        .map(|(created_at, sol, kwh, fiat, own)| GrantUsage { created_at, sol, kwh, fiat, own })
+        .collect::<Vec<_>>();
+
+        let checked = resumed.as_ref().map(FaucetGrant::amounts);
+        if let Err(throttle) = check_allowance(
+            &self.config,
+            &usage,
+            checked.as_ref().unwrap_or(amounts),
+            Utc::now(),
+        ) {
+            return Ok(FaucetReservation::Throttled(throttle));
+        }
+
+        let grant = match resumed {
+            Some(grant) => {
+                sqlx::query_as::<_, FaucetGrant>(&format!(
+                    r#"
+                    UPDATE faucet_grants
+                    SET status = 'pending', error_message = NULL, completed_at = NULL, updated_at = NOW()
+                    WHERE id = $1
+                    RETURNING {GRANT_COLUMNS}
+                    "#
+                ))
+                .bind(grant.id)
+                .fetch_one(&mut *tx)
+                .await?
+            }
+            None => {
+                sqlx::query_as::<_, FaucetGrant>(&format!(
+                    r#"
+                    INSERT INTO faucet_grants (
+                        wallet_address, user_id, client_request_id, amount_sol,
+                        mint_tokens_kwh, deposit_fiat, promote_to_role
+                    )
+                    VALUES ($1, $2, $3, $4, $5, $6, $7)
+                    RETURNING {GRANT_COLUMNS}
+                    "#
+                ))
+                .bind(wallet_address)
+                .bind(user_id)
+                .bind(client_request_id)
+                .bind(amounts.sol)
+                .bind(amounts.kwh)
+                .bind(amounts.fiat)
+                .bind(promote_to_role)
+                .fetch_one(&mut *tx)
+                .await?
+            }
+        };
+
+        tx.commit().await?;
+        Ok(FaucetReservation::Granted(grant))
+    }
+
+    /// Record the SOL airdrop of a grant as soon as it is sent
+    pub async fn record_sol_airdrop(&self, grant_id: Uuid, signature: &str) -> Result<(), ApiError> {
+        sqlx::query("UPDATE faucet_grants SET sol_tx_signature = $2 WHERE id = $1")
+            .bind(grant_id)
+            .bind(signature)
+            .execute(&self.db)
+            .await?;
+        Ok(())
+    }
+
+    /// Record the token mint of a grant as soon as it is sent
+    pub async fn record_token_mint(&self, grant_id: Uuid, signature: &str) -> Result<(), ApiError> {
+        sqlx::query("UPDATE faucet_grants SET token_tx_signature = $2 WHERE id = $1")
+            .bind(grant_id)
+            .bind(signature)
+            .execute(&self.db)
+            .await?;
+        Ok(())
+    }
+
+    /// Credit the grant's fiat deposit to `user_id`, at most once per grant
+    pub async fn deposit_fiat(&self, grant_id: Uuid, user_id: Uuid, amount: Decimal) -> Result<(), ApiError> {
+        let mut tx = self.db.begin().await?;
+        let claimed = sqlx::query(
+            "UPDATE faucet_grants SET fiat_deposited_at = NOW() WHERE id = $1 AND fiat_deposited_at IS NULL",
+        )
+        .bind(grant_id)
+        .execute(&mut *tx)
+        .await?;
+        if claimed.rows_affected() == 1 {
+            sqlx::query("UPDATE users SET balance = balance + $1 WHERE id = $2")
+                .bind(amount)
+                .bind(user_id)
+                .execute(&mut *tx)
+                .await?;
+        }
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// Give `user_id` the grant's role and record that it was done
+    pub async fn promote_role(&self, grant_id: Uuid, user_id: Uuid, role: &str) -> Result<(), ApiError> {
+        let mut tx = self.db.begin().await?;
+        sqlx::query("UPDATE users SET role = $1::text::user_role WHERE id = $2")
+            .bind(role)
+            .bind(user_id)
+            .execute(&mut *tx)
+            .await?;
+        sqlx::query("UPDATE faucet_grants SET role_promoted_at = NOW() WHERE id = $1")
+            .bind(grant_id)
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await?;
+        Ok(())
+    }
+
+    pub async fn complete(&self, grant_id: Uuid, message: &str) -> Result<(), ApiError> {
+        sqlx::query(
+            r#"
+            UPDATE faucet_grants
+            SET status = 'completed', message = $2, completed_at = NOW()
+            WHERE id = $1
+            "#,
+        )
+        .bind(grant_id)
+        .bind(message)
+        .execute(&self.db)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn fail(&self, grant_id: Uuid, error: &str) -> Result<(), ApiError> {
+        sqlx::query(
+            "UPDATE faucet_grants SET status = 'failed', error_message = $2, completed_at = NOW() WHERE id = $1",
+        )
+        .bind(grant_id)
+        .bind(error)
+        .execute(&self.db)
+        .await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> FaucetConfig {
+        FaucetConfig {
+            cooldown_secs: 60,
+            daily_sol_per_user: Decimal::new(5, 0),
+            daily_kwh_per_user: Decimal::new(1000, 0),
+            daily_sol_global: Decimal::new(8, 0),
+            daily_kwh_global: Decimal::new(100_000, 0),
+            daily_fiat_per_user: Decimal::new(500, 0),
+            daily_fiat_global: Decimal::new(10_000, 0),
+            pending_timeout_secs: 300,
+        }
+    }
+
+    fn grant(hours_ago: i64, sol: i64, own: bool, now: DateTime<Utc>) -> GrantUsage {
+        GrantUsage {
+            created_at: now - Duration::hours(hours_ago),
+            sol: Decimal::new(sol, 0),
+            kwh: Decimal::ZERO,
+            fiat: Decimal::ZERO,
+            own,
+        }
+    }
+
+    fn sol(amount: i64) -> FaucetAmounts {
+        FaucetAmounts {
+            sol: Decimal::new(amount, 0),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_cooldown_reports_time_left() {
+        let now = Utc::now();
+        let usage = vec![GrantUsage {
+            created_at: now - Duration::seconds(20),
+            sol: Decimal::ONE,
+            kwh: Decimal::ZERO,
+            fiat: Decimal::ZERO,
+            own: true,
+        }];
+
+        let throttle = check_allowance(&config(), &usage, &sol(1), now).unwrap_err();
+        assert_eq!(throttle.reason, "cooldown");
+        assert_eq!(throttle.retry_after, Duration::seconds(40));
+
+        // Someone else's recent grant does not start our cooldown
+        let others = vec![GrantUsage { own: false, ..usage[0].clone() }];
+        assert!(check_allowance(&config(), &others, &sol(1), now).is_ok());
+    }
+
+    #[test]
+    fn test_caps_wait_for_old_grants_to_expire() {
+        let now = Utc::now();
+
+        // 2 + 2 own SOL in the window; 2 more needs the 10h-old grant to expire
+        let usage = vec![grant(10, 2, true, now), grant(3, 2, true, now)];
+        let throttle = check_allowance(&config(), &usage, &sol(2), now).unwrap_err();
+        assert_eq!(throttle.reason, "daily SOL cap per user");
+        assert_eq!(throttle.retry_after, Duration::hours(14));
+        assert!(check_allowance(&config(), &usage, &sol(1), now).is_ok());
+
+        // Other users' grants fill the global cap
+        let usage = vec![grant(20, 4, false, now), grant(2, 3, false, now)];
+        let throttle = check_allowance(&config(), &usage, &sol(2), now).unwrap_err();
+        assert_eq!(throttle.reason, "global daily SOL cap");
+        assert_eq!(throttle.retry_after, Duration::hours(4));
+
+        assert!(check_request(&config(), &sol(6)).is_err());
+        assert!(check_request(&config(), &sol(5)).is_ok());
+    }
+
+    #[test]
+    fn test_fiat_deposits_are_capped() {
+        let now = Utc::now();
+        let fiat = |amount: i64| FaucetAmounts {
+            fiat: Decimal::new(amount, 0),
+            ..Default::default()
+        };
+        assert!(check_request(&config(), &fiat(501)).is_err());
+
+        let usage = vec![GrantUsage {
+            fiat: Decimal::new(400, 0),
+            ..grant(5, 0, true, now)
+        }];
+        let throttle = check_allowance(&config(), &usage, &fiat(200), now).unwrap_err();
+        assert_eq!(throttle.reason, "daily fiat cap per user");
+        assert_eq!(throttle.retry_after, Duration::hours(19));
+        assert!(check_allowance(&config(), &usage, &fiat(100), now).is_ok());
+    }
+}
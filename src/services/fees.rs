@@ -0,0 +1,267 @@
+//! Platform Fee Schedule
+//!
+//! Single source of the platform fee charged on settlements
+
+use std::collections::HashMap;
+use std::str::FromStr;
+
+use rust_decimal::Decimal;
+use sqlx::PgPool;
+use tracing::warn;
+use uuid::Uuid;
+
+/// Window over which a user's traded volume is counted for tier discounts
+pub const FEE_VOLUME_WINDOW_DAYS: i32 = 30;
+
+/// Rate applied once a user's trailing volume reaches `min_volume_kwh`
+#[derive(Debug, Clone, PartialEq)]
+pub struct VolumeTier {
+    pub min_volume_kwh: Decimal,
+    pub rate: Decimal,
+}
+
+/// Platform fee rates
+///
+/// The effective rate starts at `base_rate`, or the role's rate when one is
+/// configured, and drops to the best volume tier the user qualifies for if
+/// that tier is cheaper. With no tiers and no role rates this is a flat fee.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FeeSchedule {
+    pub base_rate: Decimal,
+    /// Sorted by `min_volume_kwh`, ascending
+    pub volume_tiers: Vec<VolumeTier>,
+    /// Keyed by lowercase `users.role`
+    pub role_rates: HashMap<String, Decimal>,
+}
+
+impl Default for FeeSchedule {
+    fn default() -> Self {
+        Self::flat(Decimal::new(1, 2)) // 1% platform fee
+    }
+}
+
+impl FeeSchedule {
+    pub fn flat(rate: Decimal) -> Self {
+        Self {
+            base_rate: rate,
+            volume_tiers: Vec::new(),
+            role_rates: HashMap::new(),
+        }
+    }
+
+    /// Load from `SETTLEMENT_FEE_RATE`, `SETTLEMENT_FEE_TIERS` and
+    /// `SETTLEMENT_FEE_ROLE_RATES`
+    ///
+    /// Tiers are `min_volume_kwh:rate` pairs and role rates are `role:rate`
+    /// pairs, both comma separated. Malformed entries are skipped with a warning.
+    pub fn from_env() -> Self {
+        let mut schedule = Self::default();
+
+        if let Some(rate) = std::env::var("SETTLEMENT_FEE_RATE")
+            .ok()
+            .and_then(|v| Decimal::from_str(&v).ok())
+        {
+            schedule.base_rate = rate;
+        }
+
+        if let Ok(value) = std::env::var("SETTLEMENT_FEE_TIERS") {
+            schedule = schedule.with_volume_tiers(
+                parse_rate_pairs("SETTLEMENT_FEE_TIERS", &value)
+                    .into_iter()
+                    .filter_map(|(volume, rate)| {
+                        Decimal::from_str(&volume).ok().map(|min_volume_kwh| VolumeTier {
+                            min_volume_kwh,
+                            rate,
+                        })
+                    })
+                    .collect(),
+            );
+        }
+
+        if let Ok(value) = std::env::var("SETTLEMENT_FEE_ROLE_RATES") {
+            schedule.role_rates = parse_rate_pairs("SETTLEMENT_FEE_ROLE_RATES", &value)
+                .into_iter()
+                .map(|(role, rate)| (role.to_lowercase(), rate))
+                .collect();
+        }
+
+        schedule
+    }
+
+    pub fn with_volume_tiers(mut self, mut tiers: Vec<VolumeTier>) -> Self {
+        tiers.sort_by(|a, b| a.min_volume_kwh.cmp(&b.min_volume_kwh));
+        self.volume_tiers = tiers;
+        self
+    }
+
+    pub fn with_role_rate(mut self, role: &str, rate: Decimal) -> Self {
+        self.role_rates.insert(role.to_lowercase(), rate);
+        self
+    }
+
+    /// Fee rate for a user with `role` and `volume_30d_kwh` of trailing volume
+    pub fn rate_for(&self, user_role: Option<&str>, volume_30d_kwh: Decimal) -> Decimal {
+        let role_rate = user_role
+            .and_then(|role| self.role_rates.get(&role.to_lowercase()))
+            .copied()
+            .unwrap_or(self.base_rate);
+
+        self.volume_tiers
+            .iter()
+            .rev()
+            .find(|tier| volume_30d_kwh >= tier.min_volume_kwh)
+            .map(|tier| tier.rate.min(role_rate))
+            .unwrap_or(role_rate)
+    }
+}
+
+/// Computes platform fees from a [`FeeSchedule`]
+#[derive(Debug, Clone, Default)]
+pub struct FeeCalculator {
+    schedule: FeeSchedule,
+}
+
+impl FeeCalculator {
+    pub fn new(schedule: FeeSchedule) -> Self {
+        Self { schedule }
+    }
+
+    pub fn from_env() -> Self {
+        Self::new(FeeSchedule::from_env())
+    }
+
+    pub fn schedule(&self) -> &FeeSchedule {
+        &self.schedule
+    }
+
+    /// Same schedule with a different base rate (used for hot reloads)
+    pub fn with_base_rate(&self, base_rate: Decimal) -> Self {
+        let mut schedule = self.schedule.clone();
+        schedule.base_rate = base_rate;
+        Self { schedule }
+    }
+
+    /// Fee charged on `trade_value` for a user with the given role and volume
+    pub fn fee_for(
+        &self,
+        trade_value: Decimal,
+        user_role: Option<&str>,
+        volume_30d_kwh: Decimal,
+    ) -> Decimal {
+        trade_value * self.schedule.rate_for(user_role, volume_30d_kwh)
+    }
+
+    /// Fee charged to `user_id` on `trade_value`, looking up their role and
+    /// trailing volume of completed settlements
+    ///
+    /// Falls back to the base rate if the lookup fails, so a database hiccup
+    /// never blocks a settlement.
+    pub async fn fee_for_user(&self, db: &PgPool, user_id: Uuid, trade_value: Decimal) -> Decimal {
+        let profile = sqlx::query_as::<_, (Option<String>, Decimal)>(
+            r#"
+            SELECT u.role::text,
+                   (SELECT COALESCE(SUM(s.energy_amount), 0) FROM settlements s
+                    WHERE (s.buyer_id = u.id OR s.seller_id = u.id)
+                      AND s.status = 'completed'
+                      AND s.created_at >= NOW() - make_interval(days => $2))
+            FROM users u
+            WHERE u.id = $1
+            "#,
+        )
+        .bind(user_id)
+        .bind(FEE_VOLUME_WINDOW_DAYS)
+        .fetch_optional(db)
+        .await;
+
+        match profile {
+            Ok(Some((role, volume))) => self.fee_for(trade_value, role.as_deref(), volume),
+            Ok(None) => self.fee_for(trade_value, None, Decimal::ZERO),
+            Err(e) => {
+                warn!("Fee profile lookup failed for user {}, using base rate: {}", user_id, e);
+                self.fee_for(trade_value, None, Decimal::ZERO)
+            }
+        }
+    }
+}
+
+fn parse_rate_pairs(var: &str, value: &str) -> Vec<(String, Decimal)> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+        .filter_map(|entry| {
+            let parsed = entry
+                .split_once(':')
+                .and_then(|(key, rate)| Some((key.trim().to_string(), Decimal::from_str(rate.trim()).ok()?)));
+            if parsed.is_none() {
+                warn!("Ignoring malformed {} entry: {}", var, entry);
+            }
+            parsed
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rate(s: &str) -> Decimal {
+        Decimal::from_str(s).unwrap()
+    }
+
+    fn tiered() -> FeeSchedule {
+        FeeSchedule::flat(rate("0.01")).with_volume_tiers(vec![
+            VolumeTier {
+                min_volume_kwh: rate("10000"),
+                rate: rate("0.005"),
+            },
+            VolumeTier {
+                min_volume_kwh: rate("1000"),
+                rate: rate("0.008"),
+            },
+        ])
+    }
+
+    #[test]
+    fn test_flat_fee() {
+        let calculator = FeeCalculator::default();
+        assert_eq!(
+            calculator.fee_for(Decimal::from(100), Some("prosumer"), rate("50000")),
+            rate("1.00")
+        );
+    }
+
+    #[test]
+    fn test_volume_tier_boundaries() {
+        let schedule = tiered();
+        assert_eq!(schedule.rate_for(None, rate("999.99")), rate("0.01"));
+        assert_eq!(schedule.rate_for(None, rate("1000")), rate("0.008"));
+        assert_eq!(schedule.rate_for(None, rate("9999.99")), rate("0.008"));
+        assert_eq!(schedule.rate_for(None, rate("10000")), rate("0.005"));
+    }
+
+    #[test]
+    fn test_role_rate_combines_with_tiers() {
+        let schedule = tiered().with_role_rate("Prosumer", rate("0.006"));
+
+        // Role rate replaces the base rate
+        assert_eq!(schedule.rate_for(Some("prosumer"), Decimal::ZERO), rate("0.006"));
+        // A tier only applies when it is cheaper than the role rate
+        assert_eq!(schedule.rate_for(Some("prosumer"), rate("1000")), rate("0.006"));
+        assert_eq!(schedule.rate_for(Some("prosumer"), rate("10000")), rate("0.005"));
+        // Roles without a rate use the base schedule
+        assert_eq!(schedule.rate_for(Some("consumer"), rate("1000")), rate("0.008"));
+    }
+
+    #[test]
+    fn test_parse_rate_pairs_skips_malformed_entries() {
+        let pairs = parse_rate_pairs("TEST", "1000:0.008, bad, 5000:x ,prosumer:0.005");
+        assert_eq!(
+            pairs,
+            vec![
+                ("1000".to_string(), rate("0.008")),
+                ("prosumer".to_string(), rate("0.005")),
+            ]
+        );
+    }
+}
@@ -3,6 +3,7 @@ use rust_decimal::Decimal;
 use uuid::Uuid;
 use crate::error::{ApiError, Result};
 use utoipa::ToSchema;
+use tracing::{error, warn};
 // Removed AppState
 
 #[derive(Debug, Clone)]
@@ -11,11 +12,48 @@ pub struct FuturesService {
     db: sqlx::PgPool,
 }
 
+/// Maintenance margin rate used when computing liquidation prices (0.5%)
+const MAINTENANCE_MARGIN_RATE: Decimal = Decimal::from_parts(5, 0, 0, false, 3);
+
+/// Highest leverage accepted on a futures order
+const MAX_LEVERAGE: i32 = 100;
+
+/// Number of candles returned when no limit is given
+const DEFAULT_CANDLE_LIMIT: i64 = 100;
+
+/// Upper bound on candles returned per request
+const MAX_CANDLE_LIMIT: i64 = 1000;
+
+/// A single filled order used as input to candle aggregation
+#[derive(Debug, Clone)]
+struct CandleFill {
+    time: chrono::DateTime<Utc>,
+    price: Decimal,
+    quantity: Decimal,
+}
+
 impl FuturesService {
     pub fn new(db: sqlx::PgPool) -> Self {
         Self { db }
     }
 
+    /// Price at which a position's remaining margin falls to the maintenance margin.
+    ///
+    /// Long:  entry * (1 - 1/leverage + mmr)
+    /// Short: entry * (1 + 1/leverage - mmr)
+    pub fn calculate_liquidation_price(side: &str, entry_price: Decimal, leverage: i32) -> Result<Decimal> {
+        if leverage < 1 {
+            return Err(ApiError::BadRequest("Leverage must be at least 1".to_string()));
+        }
+        let initial_margin_rate = Decimal::ONE / Decimal::from(leverage);
+        let factor = match side {
+            "long" => Decimal::ONE - initial_margin_rate + MAINTENANCE_MARGIN_RATE,
+            "short" => Decimal::ONE + initial_margin_rate - MAINTENANCE_MARGIN_RATE,
+            other => return Err(ApiError::BadRequest(format!("Invalid side: {}", other))),
+        };
+        Ok((entry_price * factor).max(Decimal::ZERO).round_dp(8))
+    }
+
     pub async fn get_products(&self) -> Result<Vec<FuturesProduct>> {
         sqlx::query_as!(
             FuturesProduct,
@@ -52,10 +90,41 @@ impl FuturesService {
         if quantity <= Decimal::ZERO {
             return Err(ApiError::BadRequest("Quantity must be positive".to_string()));
         }
+        if price <= Decimal::ZERO {
+            return Err(ApiError::BadRequest("Price must be positive".to_string()));
+        }
+        if !(1..=MAX_LEVERAGE).contains(&leverage) {
+            return Err(ApiError::BadRequest(format!(
+                "Leverage must be between 1 and {}",
+                MAX_LEVERAGE
+            )));
+        }
 
-        // TODO: Check margin requirements (mock check for now)
         let margin_required = (quantity * price) / Decimal::from(leverage);
-        
+        let liquidation_price = Self::calculate_liquidation_price(&side, price, leverage)?;
+
+        let mut tx = self.db.begin().await.map_err(ApiError::Database)?;
+
+        // Check margin against the user's available balance. Escrowed funds are
+        // already moved out of `balance` into `locked_amount`, so `balance` is
+        // what remains spendable.
+        let available = sqlx::query_scalar!(
+            "SELECT balance FROM users WHERE id = $1 FOR UPDATE",
+            user_id
+        )
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(ApiError::Database)?
+        .ok_or_else(|| ApiError::NotFound("User not found".to_string()))?
+        .unwrap_or(Decimal::ZERO);
+
+        if margin_required > available {
+            return Err(ApiError::BadRequest(format!(
+                "Insufficient margin. Required: {}, Available: {}",
+                margin_required, available
+            )));
+        }
+
         // Insert order
         let order_id = sqlx::query!(
             r#"
@@ -71,27 +140,42 @@ impl FuturesService {
             price,
             leverage
         )
-        .fetch_one(&self.db)
+        .fetch_one(&mut *tx)
         .await
         .map_err(|e| ApiError::Internal(e.to_string()))?
         .id;
 
+        // Lock the margin for every order as it is placed; a fill carries the
+        // lock over to the position it opens
+        sqlx::query!(
+            "UPDATE users SET balance = balance - $1, locked_amount = locked_amount + $1 WHERE id = $2",
+            margin_required,
+            user_id
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(ApiError::Database)?;
+
         // Auto-fill for MVP if market order
         if order_type == "market" {
-             sqlx::query!(
+            sqlx::query(
                 r#"
-                INSERT INTO futures_positions (user_id, product_id, side, quantity, entry_price, current_price, leverage, margin_used, unrealized_pnl)
-                VALUES ($1, $2, $3::futures_order_side, $4, $5, $5, $6, $7, 0)
+                INSERT INTO futures_positions (
+                    user_id, product_id, side, quantity, entry_price, current_price, leverage,
+                    margin_used, unrealized_pnl, liquidation_price, margin_locked
+                )
+                VALUES ($1, $2, $3::futures_order_side, $4, $5, $5, $6, $7, 0, $8, TRUE)
                 "#,
-                user_id,
-                product_id,
-                side as _,
-                quantity,
-                price, // Using price as execution price for simplicity
-                leverage,
-                margin_required
             )
-            .execute(&self.db)
+            .bind(user_id)
+            .bind(product_id)
+            .bind(&side)
+            .bind(quantity)
+            .bind(price) // Using price as execution price for simplicity
+            .bind(leverage)
+            .bind(margin_required)
+            .bind(liquidation_price)
+            .execute(&mut *tx)
             .await
             .map_err(|e| ApiError::Internal(e.to_string()))?;
 
@@ -102,11 +186,13 @@ impl FuturesService {
                 price,
                 order_id
             )
-            .execute(&self.db)
+            .execute(&mut *tx)
             .await
             .map_err(|e| ApiError::Internal(e.to_string()))?;
         }
 
+        tx.commit().await.map_err(ApiError::Database)?;
+
         Ok(order_id)
     }
 
@@ -231,25 +317,275 @@ pub struct FuturesOrder {
 impl FuturesService {
     // ... existing methods ...
 
-    pub async fn get_candles(&self, _product_id: Uuid, _interval: String) -> Result<Vec<Candle>> {
-        // ... existing mock candle generation ...
-        // Keeping as is for brevity in this replace block, but need to be careful not to delete it if I can't match it exactly. 
-        // Actually, to be safe, I should append the new methods after get_candles.
-        // Let's assume the previous content is there and just append.
-        // But replace_file_content needs target content.
-        // I will target the end of the file or after get_candles implementation.
-        // This tool is tricky if I don't see the exact lines.
-        // I'll assume get_candles is correct and just add new methods before the end of impl FuturesService.
-        
-        // RE-READING FILE CONTENT FROM STEP 35/36...
-        // The previous replace added get_candles.
-        // I will target the implementation of get_candles closing brace and add new methods.
-        
-        let candles = Vec::new();
-        // ... (lines 178-212 in my mental model, or previous step output) ...
-        // simulating the end of get_candles
-        
-        Ok(candles)
+    /// Unrealized PnL of a position at `mark_price`.
+    ///
+    /// `quantity` is the full (leveraged) position size, so leverage is already
+    /// reflected in the notional and does not scale the result again.
+    pub fn calculate_unrealized_pnl(side: &str, quantity: Decimal, entry_price: Decimal, mark_price: Decimal) -> Decimal {
+        match side {
+            "short" => (entry_price - mark_price) * quantity,
+            _ => (mark_price - entry_price) * quantity,
+        }
+    }
+
+    /// Whether `mark_price` has crossed the position's liquidation price
+    pub fn is_liquidatable(side: &str, mark_price: Decimal, liquidation_price: Option<Decimal>) -> bool {
+        match (side, liquidation_price) {
+            ("long", Some(liq)) => mark_price <= liq,
+            ("short", Some(liq)) => mark_price >= liq,
+            _ => false,
+        }
+    }
+
+    /// Mark all open positions to their product's current price, recompute
+    /// unrealized PnL and liquidate positions whose mark crossed the liquidation price.
+    ///
+    /// Returns `(positions_updated, positions_liquidated)`.
+    pub async fn refresh_mark_prices(&self) -> Result<(usize, usize)> {
+        let positions = sqlx::query_as::<_, (Uuid, Uuid, Uuid, String, Decimal, Decimal, Decimal, Option<Decimal>, Decimal)>(
+            r#"
+            SELECT p.id, p.user_id, p.product_id, p.side::text, p.quantity, p.entry_price,
+                   p.margin_used, p.liquidation_price, prod.current_price
+            FROM futures_positions p
+            JOIN futures_products prod ON p.product_id = prod.id
+            "#,
+        )
+        .fetch_all(&self.db)
+        .await
+        .map_err(ApiError::Database)?;
+
+        let mut updated = 0;
+        let mut liquidated = 0;
+
+        for (id, user_id, product_id, side, quantity, entry_price, margin_used, liquidation_price, mark_price) in positions {
+            if Self::is_liquidatable(&side, mark_price, liquidation_price) {
+                match self.liquidate_position(id, user_id, product_id, &side, quantity, margin_used, mark_price).await {
+                    Ok(()) => liquidated += 1,
+                    Err(e) => error!("Failed to liquidate futures position {}: {}", id, e),
+                }
+                continue;
+            }
+
+            let pnl = Self::calculate_unrealized_pnl(&side, quantity, entry_price, mark_price);
+            sqlx::query(
+                "UPDATE futures_positions SET current_price = $1, unrealized_pnl = $2, updated_at = NOW() WHERE id = $3",
+            )
+            .bind(mark_price)
+            .bind(pnl)
+            .bind(id)
+            .execute(&self.db)
+            .await
+            .map_err(ApiError::Database)?;
+            updated += 1;
+        }
+
+        Ok((updated, liquidated))
+    }
+
+    /// Close a position at the mark price, forfeiting its locked margin
+    async fn liquidate_position(
+        &self,
+        position_id: Uuid,
+        user_id: Uuid,
+        product_id: Uuid,
+        side: &str,
+        quantity: Decimal,
+        margin_used: Decimal,
+        mark_price: Decimal,
+    ) -> Result<()> {
+        let close_side = if side == "long" { "short" } else { "long" };
+        let mut tx = self.db.begin().await.map_err(ApiError::Database)?;
+
+        // Record the forced close in order history
+        sqlx::query(
+            r#"
+            INSERT INTO futures_orders (
+                user_id, product_id, side, order_type, quantity, price, leverage,
+                status, filled_quantity, average_fill_price
+            )
+            VALUES ($1, $2, $3::futures_order_side, 'market', $4, $5, 1, 'filled', $4, $5)
+            "#,
+        )
+        .bind(user_id)
+        .bind(product_id)
+        .bind(close_side)
+        .bind(quantity)
+        .bind(mark_price)
+        .execute(&mut *tx)
+        .await
+        .map_err(ApiError::Database)?;
+
+        let margin_locked: Option<bool> = sqlx::query_scalar(
+            "DELETE FROM futures_positions WHERE id = $1 RETURNING margin_locked",
+        )
+        .bind(position_id)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(ApiError::Database)?;
+
+        let Some(margin_locked) = margin_locked else {
+            // Closed concurrently; nothing left to liquidate
+            return Ok(());
+        };
+
+        // Margin is consumed by the loss: release the lock without crediting balance
+        if margin_locked {
+            sqlx::query("UPDATE users SET locked_amount = locked_amount - $1 WHERE id = $2")
+                .bind(margin_used)
+                .bind(user_id)
+                .execute(&mut *tx)
+                .await
+                .map_err(ApiError::Database)?;
+        }
+
+        tx.commit().await.map_err(ApiError::Database)?;
+
+        warn!(
+            "💥 Liquidated futures position {} for user {} at mark {} (margin forfeited: {})",
+            position_id, user_id, mark_price, margin_used
+        );
+
+        Ok(())
+    }
+
+    /// Aggregate filled orders into OHLCV candles for a product.
+    ///
+    /// Returns the most recent `limit` buckets of `interval` (1m/5m/1h/1d), oldest
+    /// first. Buckets without trades are filled with flat candles at the previous
+    /// close; leading buckets before any known trade are omitted.
+    pub async fn get_candles(&self, product_id: Uuid, interval: String, limit: Option<i64>) -> Result<Vec<Candle>> {
+        let interval_secs = Self::interval_seconds(&interval)?;
+        let limit = limit.unwrap_or(DEFAULT_CANDLE_LIMIT).clamp(1, MAX_CANDLE_LIMIT);
+
+        let now = Utc::now();
+        let window_end = Self::bucket_start(now, interval_secs) + chrono::Duration::seconds(interval_secs);
+        let window_start = window_end - chrono::Duration::seconds(interval_secs * limit);
+
+        let fills: Vec<CandleFill> = sqlx::query_as::<_, (chrono::DateTime<Utc>, Decimal, Decimal)>(
+            r#"
+            SELECT created_at, average_fill_price, COALESCE(filled_quantity, quantity)
+            FROM futures_orders
+            WHERE product_id = $1
+              AND status = 'filled'
+              AND average_fill_price IS NOT NULL
+              AND created_at >= $2 AND created_at < $3
+            ORDER BY created_at ASC
+            "#,
+        )
+        .bind(product_id)
+        .bind(window_start)
+        .bind(window_end)
+        .fetch_all(&self.db)
+        .await
+        .map_err(ApiError::Database)?
+        .into_iter()
+        .map(|(time, price, quantity)| CandleFill { time, price, quantity })
+        .collect();
+
+        // Last trade before the window seeds flat candles for leading gaps
+        let previous_close: Option<Decimal> = sqlx::query_scalar(
+            r#"
+            SELECT average_fill_price
+            FROM futures_orders
+            WHERE product_id = $1
+              AND status = 'filled'
+              AND average_fill_price IS NOT NULL
+              AND created_at < $2
+            ORDER BY created_at DESC
+            LIMIT 1
+            "#,
+        )
+        .bind(product_id)
+        .bind(window_start)
+        .fetch_optional(&self.db)
+        .await
+        .map_err(ApiError::Database)?;
+
+        Ok(Self::aggregate_candles(&fills, previous_close, window_start, interval_secs, limit))
+    }
+
+    /// Bucket size in seconds for a candle interval
+    fn interval_seconds(interval: &str) -> Result<i64> {
+        match interval {
+            "1m" => Ok(60),
+            "5m" => Ok(300),
+            "1h" => Ok(3_600),
+            "1d" => Ok(86_400),
+            other => Err(ApiError::BadRequest(format!(
+                "Unsupported candle interval '{}'. Use 1m, 5m, 1h or 1d",
+                other
+            ))),
+        }
+    }
+
+    fn bucket_start(time: chrono::DateTime<Utc>, interval_secs: i64) -> chrono::DateTime<Utc> {
+        let ts = time.timestamp();
+        chrono::DateTime::from_timestamp(ts - ts.rem_euclid(interval_secs), 0).unwrap_or(time)
+    }
+
+    /// Build `buckets` consecutive candles starting at `window_start` from fills
+    /// sorted by time.
+    fn aggregate_candles(
+        fills: &[CandleFill],
+        previous_close: Option<Decimal>,
+        window_start: chrono::DateTime<Utc>,
+        interval_secs: i64,
+        buckets: i64,
+    ) -> Vec<Candle> {
+        let mut candles = Vec::with_capacity(buckets as usize);
+        let mut last_close = previous_close;
+        let mut fills = fills.iter().peekable();
+
+        for i in 0..buckets {
+            let bucket_start = window_start + chrono::Duration::seconds(interval_secs * i);
+            let bucket_end = bucket_start + chrono::Duration::seconds(interval_secs);
+
+            let mut candle: Option<Candle> = None;
+            while let Some(fill) = fills.next_if(|f| f.time < bucket_end) {
+                if fill.time < bucket_start {
+                    continue;
+                }
+                match candle.as_mut() {
+                    Some(c) => {
+                        c.high = c.high.max(fill.price);
+                        c.low = c.low.min(fill.price);
+                        c.close = fill.price;
+                        c.volume += fill.quantity;
+                    }
+                    None => {
+                        candle = Some(Candle {
+                            time: bucket_start.to_rfc3339(),
+                            open: fill.price,
+                            high: fill.price,
+                            low: fill.price,
+                            close: fill.price,
+                            volume: fill.quantity,
+                        });
+                    }
+                }
+            }
+
+            match candle {
+                Some(c) => {
+                    last_close = Some(c.close);
+                    candles.push(c);
+                }
+                None => {
+                    if let Some(close) = last_close {
+                        candles.push(Candle {
+                            time: bucket_start.to_rfc3339(),
+                            open: close,
+                            high: close,
+                            low: close,
+                            close,
+                            volume: Decimal::ZERO,
+                        });
+                    }
+                }
+            }
+        }
+
+        candles
     }
 
     pub async fn get_order_book(&self, _product_id: Uuid) -> Result<OrderBook> {
@@ -311,24 +647,29 @@ impl FuturesService {
     }
 
     pub async fn close_position(&self, user_id: Uuid, position_id: Uuid) -> Result<Uuid> {
+        let mut tx = self.db.begin().await.map_err(ApiError::Database)?;
+
         // 1. Get position details
-        let position = sqlx::query!(
+        let position = sqlx::query_as::<_, (Uuid, String, Decimal, Decimal, Decimal, bool)>(
             r#"
-            SELECT product_id, COALESCE(side::text, 'unknown') as side, quantity, current_price 
-            FROM futures_positions 
+            SELECT product_id, COALESCE(side::text, 'unknown'), quantity, current_price,
+                   margin_used, margin_locked
+            FROM futures_positions
             WHERE id = $1 AND user_id = $2
+            FOR UPDATE
             "#,
-            position_id,
-            user_id
         )
-        .fetch_optional(&self.db)
+        .bind(position_id)
+        .bind(user_id)
+        .fetch_optional(&mut *tx)
         .await
         .map_err(|e| ApiError::Internal(e.to_string()))?
         .ok_or(ApiError::BadRequest("Position not found".to_string()))?;
+        let (product_id, side, quantity, price, margin_used, margin_locked) = position;
 
         // 2. Calculate closing side
-        let close_side = if position.side.as_deref() == Some("long") { "short" } else { "long" };
-        let price = position.current_price; // executing at current mark price for simplicity
+        let close_side = if side == "long" { "short" } else { "long" };
+        // executing at current mark price for simplicity
 
         // 3. Create closing order record (History)
         let order_id = sqlx::query!(
@@ -341,12 +682,12 @@ impl FuturesService {
             RETURNING id
             "#,
             user_id,
-            position.product_id,
+            product_id,
             close_side as _,
-            position.quantity,
+            quantity,
             price
         )
-        .fetch_one(&self.db)
+        .fetch_one(&mut *tx)
         .await
         .map_err(|e| ApiError::Internal(e.to_string()))?
         .id;
@@ -356,10 +697,157 @@ impl FuturesService {
             "DELETE FROM futures_positions WHERE id = $1",
             position_id
         )
-        .execute(&self.db)
+        .execute(&mut *tx)
         .await
         .map_err(|e| ApiError::Internal(e.to_string()))?;
 
+        // 5. Release the margin locked when the position was opened
+        if margin_locked {
+            sqlx::query!(
+                "UPDATE users SET balance = balance + $1, locked_amount = locked_amount - $1 WHERE id = $2",
+                margin_used,
+                user_id
+            )
+            .execute(&mut *tx)
+            .await
+            .map_err(ApiError::Database)?;
+        }
+
+        tx.commit().await.map_err(ApiError::Database)?;
+
         Ok(order_id)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    #[test]
+    fn test_liquidation_price_long() {
+        // 10x long at 100: 100 * (1 - 0.1 + 0.005) = 90.5
+        let price = FuturesService::calculate_liquidation_price("long", Decimal::from(100), 10).unwrap();
+        assert_eq!(price, Decimal::from_str("90.5").unwrap());
+    }
+
+    #[test]
+    fn test_liquidation_price_short() {
+        // 10x short at 100: 100 * (1 + 0.1 - 0.005) = 109.5
+        let price = FuturesService::calculate_liquidation_price("short", Decimal::from(100), 10).unwrap();
+        assert_eq!(price, Decimal::from_str("109.5").unwrap());
+    }
+
+    #[test]
+    fn test_liquidation_price_unleveraged_long_floors_at_zero() {
+        // 1x long only liquidates near zero: 100 * 0.005
+        let price = FuturesService::calculate_liquidation_price("long", Decimal::from(100), 1).unwrap();
+        assert_eq!(price, Decimal::from_str("0.5").unwrap());
+    }
+
+    fn fill(minute: u32, second: u32, price: &str, quantity: &str) -> CandleFill {
+        CandleFill {
+            time: chrono::TimeZone::with_ymd_and_hms(&Utc, 2026, 1, 1, 12, minute, second).unwrap(),
+            price: Decimal::from_str(price).unwrap(),
+            quantity: Decimal::from_str(quantity).unwrap(),
+        }
+    }
+
+    #[test]
+    fn test_aggregate_candles_two_buckets() {
+        let window_start = chrono::TimeZone::with_ymd_and_hms(&Utc, 2026, 1, 1, 12, 0, 0).unwrap();
+        let fills = vec![
+            fill(0, 5, "100", "1"),
+            fill(0, 20, "104", "2"),
+            fill(0, 40, "98", "0.5"),
+            fill(0, 55, "101", "1.5"),
+            fill(1, 10, "102", "3"),
+            fill(1, 50, "99", "1"),
+        ];
+
+        let candles = FuturesService::aggregate_candles(&fills, None, window_start, 60, 2);
+        assert_eq!(candles.len(), 2);
+
+        let first = &candles[0];
+        assert_eq!(first.time, window_start.to_rfc3339());
+        assert_eq!(first.open, Decimal::from(100));
+        assert_eq!(first.high, Decimal::from(104));
+        assert_eq!(first.low, Decimal::from(98));
+        assert_eq!(first.close, Decimal::from(101));
+        assert_eq!(first.volume, Decimal::from(5));
+
+        let second = &candles[1];
+        assert_eq!(second.open, Decimal::from(102));
+        assert_eq!(second.high, Decimal::from(102));
+        assert_eq!(second.low, Decimal::from(99));
+        assert_eq!(second.close, Decimal::from(99));
+        assert_eq!(second.volume, Decimal::from(4));
+    }
+
+    #[test]
+    fn test_aggregate_candles_fills_gaps_with_flat_candles() {
+        let window_start = chrono::TimeZone::with_ymd_and_hms(&Utc, 2026, 1, 1, 12, 0, 0).unwrap();
+        let fills = vec![fill(1, 0, "50", "2")];
+
+        // Leading gap is seeded from the previous close, trailing gap from the last fill
+        let candles =
+            FuturesService::aggregate_candles(&fills, Some(Decimal::from(48)), window_start, 60, 3);
+        assert_eq!(candles.len(), 3);
+        assert_eq!(candles[0].close, Decimal::from(48));
+        assert_eq!(candles[0].volume, Decimal::ZERO);
+        assert_eq!(candles[1].close, Decimal::from(50));
+        assert_eq!(candles[2].open, Decimal::from(50));
+        assert_eq!(candles[2].high, Decimal::from(50));
+        assert_eq!(candles[2].volume, Decimal::ZERO);
+
+        // Without a previous close, leading empty buckets are omitted
+        let candles = FuturesService::aggregate_candles(&fills, None, window_start, 60, 3);
+        assert_eq!(candles.len(), 2);
+    }
+
+    #[test]
+    fn test_interval_seconds() {
+        assert_eq!(FuturesService::interval_seconds("5m").unwrap(), 300);
+        assert_eq!(FuturesService::interval_seconds("1d").unwrap(), 86_400);
+        assert!(FuturesService::interval_seconds("3w").is_err());
+    }
+
+    #[test]
+    fn test_unrealized_pnl_after_price_move() {
+        let quantity = Decimal::from(2);
+        let entry = Decimal::from(100);
+        let mark = Decimal::from(110);
+
+        // Long gains when price rises
+        let long_pnl = FuturesService::calculate_unrealized_pnl("long", quantity, entry, mark);
+        assert_eq!(long_pnl, Decimal::from(20));
+
+        // Short loses the same amount
+        let short_pnl = FuturesService::calculate_unrealized_pnl("short", quantity, entry, mark);
+        assert_eq!(short_pnl, Decimal::from(-20));
+
+        // And the reverse on a drop
+        let mark = Decimal::from(95);
+        assert_eq!(FuturesService::calculate_unrealized_pnl("long", quantity, entry, mark), Decimal::from(-10));
+        assert_eq!(FuturesService::calculate_unrealized_pnl("short", quantity, entry, mark), Decimal::from(10));
+    }
+
+    #[test]
+    fn test_is_liquidatable() {
+        let liq = Some(Decimal::from(90));
+        assert!(FuturesService::is_liquidatable("long", Decimal::from(89), liq));
+        assert!(!FuturesService::is_liquidatable("long", Decimal::from(91), liq));
+
+        let liq = Some(Decimal::from(110));
+        assert!(FuturesService::is_liquidatable("short", Decimal::from(110), liq));
+        assert!(!FuturesService::is_liquidatable("short", Decimal::from(105), liq));
+
+        assert!(!FuturesService::is_liquidatable("long", Decimal::from(1), None));
+    }
+
+    #[test]
+    fn test_liquidation_price_rejects_invalid_input() {
+        assert!(FuturesService::calculate_liquidation_price("long", Decimal::from(100), 0).is_err());
+        assert!(FuturesService::calculate_liquidation_price("sideways", Decimal::from(100), 5).is_err());
+    }
+}
@@ -1,11 +1,16 @@
 use rust_decimal::Decimal;
 use rust_decimal::prelude::FromPrimitive;
+use serde::Serialize;
 use sqlx::PgPool;
 use std::collections::HashMap;
-use std::sync::Arc;
+use std::sync::{Arc, PoisonError, RwLock};
 use std::time::Duration;
-use tokio::sync::RwLock;
+use tokio_util::sync::CancellationToken;
+use tokio_util::task::TaskTracker;
 use tracing::{debug, warn, info, error};
+use utoipa::ToSchema;
+
+use crate::config::GridLossConfig;
 
 /// Zone rate configuration from database
 #[derive(Clone, Debug)]
@@ -16,11 +21,91 @@ pub struct ZoneRate {
     pub loss_factor: Decimal,
 }
 
+/// How a zone's feeder is built
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LineType {
+    Overhead,
+    Underground,
+}
+
+impl LineType {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "overhead" => Some(Self::Overhead),
+            "underground" => Some(Self::Underground),
+            _ => None,
+        }
+    }
+}
+
+/// Physical attributes of a zone's feeder, from `grid_zone_attributes`
+#[derive(Clone, Debug)]
+pub struct ZoneAttributes {
+    pub zone_id: i32,
+    /// Zone centroid
+    pub latitude: f64,
+    pub longitude: f64,
+    pub voltage_kv: Decimal,
+    pub line_type: LineType,
+}
+
+/// Delivery costs of moving energy between two zones
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ZoneRouteCost {
+    pub from_zone: i32,
+    pub to_zone: i32,
+    /// Wheeling charge in THB per kWh
+    #[schema(value_type = String)]
+    pub wheeling_charge: Decimal,
+    /// Share of energy lost in transit (0.03 = 3%)
+    #[schema(value_type = String)]
+    pub loss_factor: Decimal,
+}
+
+/// Landed cost of a trade, priced the way the matching engine prices it
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct LandedCostBreakdown {
+    pub seller_zone: Option<i32>,
+    pub buyer_zone: Option<i32>,
+    #[schema(value_type = String)]
+    pub energy_amount: Decimal,
+    #[schema(value_type = String)]
+    pub price_per_kwh: Decimal,
+    #[schema(value_type = String)]
+    pub wheeling_charge_per_kwh: Decimal,
+    #[schema(value_type = String)]
+    pub loss_factor: Decimal,
+    /// `energy_amount * price_per_kwh`
+    #[schema(value_type = String)]
+    pub energy_cost: Decimal,
+    #[schema(value_type = String)]
+    pub wheeling_cost: Decimal,
+    #[schema(value_type = String)]
+    pub loss_cost: Decimal,
+    #[schema(value_type = String)]
+    pub total_cost: Decimal,
+    /// Price plus wheeling and loss cost per kWh, compared against buy limits
+    #[schema(value_type = String)]
+    pub landed_cost_per_kwh: Decimal,
+    /// Energy the buyer receives after losses
+    #[schema(value_type = String)]
+    pub delivered_energy: Decimal,
+}
+
 /// Service to manage grid topology and calculate transmission costs
+///
+/// Rates configured in `zone_rates` take precedence over the built-in
+/// distance-based defaults, so tariffs can change without a redeploy. Loss
+/// factors for pairs without a configured rate are modelled from distance,
+/// voltage and line type when both zones have recorded attributes (see
+/// [`GridLossConfig`]); an admin-set rate always wins over the model.
 #[derive(Clone)]
 pub struct GridTopologyService {
     /// Cached zone rates: (from_zone, to_zone) -> ZoneRate
     rates_cache: Arc<RwLock<HashMap<(i32, i32), ZoneRate>>>,
+    /// Cached feeder attributes: zone_id -> ZoneAttributes
+    zone_attributes: Arc<RwLock<HashMap<i32, ZoneAttributes>>>,
+    loss_model: GridLossConfig,
     /// Database pool for loading rates
     pool: Option<PgPool>,
     /// Last cache refresh timestamp
@@ -31,6 +116,8 @@ impl GridTopologyService {
     pub fn new() -> Self {
         Self {
             rates_cache: Arc::new(RwLock::new(HashMap::new())),
+            zone_attributes: Arc::new(RwLock::new(HashMap::new())),
+            loss_model: GridLossConfig::default(),
             pool: None,
             last_refresh: Arc::new(RwLock::new(None)),
         }
@@ -39,19 +126,36 @@ impl GridTopologyService {
     /// Create service with database connection for dynamic rates
     pub fn with_pool(pool: PgPool) -> Self {
         Self {
-            rates_cache: Arc::new(RwLock::new(HashMap::new())),
             pool: Some(pool),
-            last_refresh: Arc::new(RwLock::new(None)),
+            ..Self::new()
         }
     }
 
-    /// Spawn a background task to refresh cache periodically
-    /// Returns the JoinHandle for the spawned task
-    pub fn spawn_refresh_task(self: Arc<Self>, refresh_interval_secs: u64) -> tokio::task::JoinHandle<()> {
+    /// Use `loss_model` for zones with recorded attributes
+    pub fn with_loss_model(mut self, loss_model: GridLossConfig) -> Self {
+        self.loss_model = loss_model;
+        self
+    }
+
+    /// Create service with database connection and load the active rates
+    pub async fn from_db(pool: PgPool) -> Result<Self, sqlx::Error> {
+        let service = Self::with_pool(pool);
+        service.load_rates().await?;
+        Ok(service)
+    }
+
+    /// Spawn a task on `tracker` that refreshes the cache every
+    /// `refresh_interval_secs` until `shutdown` is cancelled
+    pub fn spawn_refresh_task(
+        self: Arc<Self>,
+        refresh_interval_secs: u64,
+        shutdown: CancellationToken,
+        tracker: &TaskTracker,
+    ) -> tokio::task::JoinHandle<()> {
         let interval = Duration::from_secs(refresh_interval_secs);
         info!("🔄 Starting zone rates cache refresh task (interval: {}s)", refresh_interval_secs);
         
-        tokio::spawn(async move {
+        tracker.spawn(async move {
             // Initial load
             if let Err(e) = self.load_rates().await {
                 error!("Failed initial zone rates load: {}", e);
@@ -61,7 +165,10 @@ impl GridTopologyService {
             interval_timer.tick().await; // Skip first immediate tick
 
             loop {
-                interval_timer.tick().await;
+                tokio::select! {
+                    _ = shutdown.cancelled() => break,
+                    _ = interval_timer.tick() => {}
+                }
                 match self.load_rates().await {
                     Ok(count) => {
                         info!("🔄 Refreshed zone rates cache: {} rates loaded", count);
@@ -74,7 +181,8 @@ impl GridTopologyService {
         })
     }
 
-    /// Load zone rates from database into cache
+    /// Load zone rates and zone attributes from database into cache.
+    /// Returns the number of zone rates loaded.
     pub async fn load_rates(&self) -> Result<usize, sqlx::Error> {
         let Some(pool) = &self.pool else {
             warn!("No database pool configured, using default rates");
@@ -88,61 +196,103 @@ impl GridTopologyService {
             WHERE is_active = TRUE
               AND (effective_until IS NULL OR effective_until > NOW())
               AND effective_from <= NOW()
+            ORDER BY effective_from ASC
             "#
         )
         .fetch_all(pool)
         .await?;
 
-        let count = rows.len();
-        let mut cache = self.rates_cache.write().await;
-        cache.clear();
-        for row in rows {
-            use sqlx::Row;
-            let rate = ZoneRate {
-                from_zone_id: row.get("from_zone_id"),
-                to_zone_id: row.get("to_zone_id"),
-                wheeling_charge: row.get("wheeling_charge"),
-                loss_factor: row.get("loss_factor"),
-            };
-            cache.insert((rate.from_zone_id, rate.to_zone_id), rate);
-        }
-        
-        // Update last refresh timestamp
-        *self.last_refresh.write().await = Some(std::time::Instant::now());
+        use sqlx::Row;
+        let rates = rows.into_iter().map(|row| ZoneRate {
+            from_zone_id: row.get("from_zone_id"),
+            to_zone_id: row.get("to_zone_id"),
+            wheeling_charge: row.get("wheeling_charge"),
+            loss_factor: row.get("loss_factor"),
+        });
+        let count = self.replace_rates(rates);
         debug!("Loaded {} zone rates from database", count);
+
+        let rows = sqlx::query(
+            "SELECT zone_id, latitude, longitude, voltage_kv, line_type FROM grid_zone_attributes"
+        )
+        .fetch_all(pool)
+        .await?;
+
+        let attributes = rows.into_iter().filter_map(|row| {
+            let zone_id: i32 = row.get("zone_id");
+            let line_type: String = row.get("line_type");
+            let Some(line_type) = LineType::parse(&line_type) else {
+                warn!("Zone {} has unknown line type '{}', using flat loss factors", zone_id, line_type);
+                return None;
+            };
+            Some(ZoneAttributes {
+                zone_id,
+                latitude: row.get("latitude"),
+                longitude: row.get("longitude"),
+                voltage_kv: row.get("voltage_kv"),
+                line_type,
+            })
+        });
+        let zones = self.replace_zone_attributes(attributes);
+        debug!("Loaded attributes for {} zones from database", zones);
+
         Ok(count)
     }
 
+    /// Replace the cached zone attributes. Returns the number of zones cached.
+    pub fn replace_zone_attributes(&self, attributes: impl IntoIterator<Item = ZoneAttributes>) -> usize {
+        let attributes: HashMap<i32, ZoneAttributes> = attributes
+            .into_iter()
+            .map(|zone| (zone.zone_id, zone))
+            .collect();
+        let count = attributes.len();
+
+        *self.zone_attributes.write().unwrap_or_else(PoisonError::into_inner) = attributes;
+        count
+    }
+
+    /// Replace the cached rates; a later rate for the same zone pair wins.
+    /// Returns the number of zone pairs cached.
+    pub fn replace_rates(&self, rates: impl IntoIterator<Item = ZoneRate>) -> usize {
+        let rates: HashMap<(i32, i32), ZoneRate> = rates
+            .into_iter()
+            .map(|rate| ((rate.from_zone_id, rate.to_zone_id), rate))
+            .collect();
+        let count = rates.len();
+
+        // A writer that panicked cannot leave the map half-replaced; keep serving it
+        *self.rates_cache.write().unwrap_or_else(PoisonError::into_inner) = rates;
+        *self.last_refresh.write().unwrap_or_else(PoisonError::into_inner) = Some(std::time::Instant::now());
+        count
+    }
+
     /// Get cache age in seconds (None if never refreshed)
-    pub async fn cache_age_secs(&self) -> Option<u64> {
-        self.last_refresh.read().await.map(|t| t.elapsed().as_secs())
+    pub fn cache_age_secs(&self) -> Option<u64> {
+        self.last_refresh
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .map(|t| t.elapsed().as_secs())
     }
 
-    /// Get rate from cache or use default
-    async fn get_rate(&self, from_zone: i32, to_zone: i32) -> Option<ZoneRate> {
-        let cache = self.rates_cache.read().await;
+    /// Configured rate for a zone pair, if any
+    fn get_rate(&self, from_zone: Option<i32>, to_zone: Option<i32>) -> Option<ZoneRate> {
+        let (from_zone, to_zone) = (from_zone?, to_zone?);
+        let cache = self.rates_cache.read().unwrap_or_else(PoisonError::into_inner);
         cache.get(&(from_zone, to_zone)).cloned()
     }
 
     /// Calculate wheeling charge (transmission fee) in THB per kWh
     /// returns: Fee in THB
     pub fn calculate_wheeling_charge(&self, from_zone: Option<i32>, to_zone: Option<i32>) -> Decimal {
-        // Try to get from cache synchronously for backward compatibility
-        // For async version, use calculate_wheeling_charge_async
-        self.calculate_wheeling_charge_default(from_zone, to_zone)
+        match self.get_rate(from_zone, to_zone) {
+            Some(rate) => rate.wheeling_charge,
+            None => self.calculate_wheeling_charge_default(from_zone, to_zone),
+        }
     }
 
-    /// Async version that checks database cache first
+    /// Async version kept for existing callers; same as [`Self::calculate_wheeling_charge`]
     pub async fn calculate_wheeling_charge_async(&self, from_zone: Option<i32>, to_zone: Option<i32>) -> Decimal {
-        match (from_zone, to_zone) {
-            (Some(fz), Some(tz)) => {
-                if let Some(rate) = self.get_rate(fz, tz).await {
-                    return rate.wheeling_charge;
-                }
-                self.calculate_wheeling_charge_default(from_zone, to_zone)
-            }
-            _ => self.calculate_wheeling_charge_default(from_zone, to_zone),
-        }
+        self.calculate_wheeling_charge(from_zone, to_zone)
     }
 
     /// Default wheeling charge calculation (fallback)
@@ -172,21 +322,32 @@ impl GridTopologyService {
 
     /// Calculate technical loss (%)
     /// returns: Percentage as Decimal (e.g., 0.03 for 3%)
+    ///
+    /// A configured `zone_rates` loss wins, then the loss model, then the
+    /// flat defaults.
     pub fn calculate_loss_factor(&self, from_zone: Option<i32>, to_zone: Option<i32>) -> Decimal {
-        self.calculate_loss_factor_default(from_zone, to_zone)
+        if let Some(rate) = self.get_rate(from_zone, to_zone) {
+            return rate.loss_factor;
+        }
+        self.modelled_loss_factor(from_zone, to_zone)
+            .unwrap_or_else(|| self.calculate_loss_factor_default(from_zone, to_zone))
     }
 
-    /// Async version that checks database cache first
+    /// Async version kept for existing callers; same as [`Self::calculate_loss_factor`]
     pub async fn calculate_loss_factor_async(&self, from_zone: Option<i32>, to_zone: Option<i32>) -> Decimal {
-        match (from_zone, to_zone) {
-            (Some(fz), Some(tz)) => {
-                if let Some(rate) = self.get_rate(fz, tz).await {
-                    return rate.loss_factor;
-                }
-                self.calculate_loss_factor_default(from_zone, to_zone)
-            }
-            _ => self.calculate_loss_factor_default(from_zone, to_zone),
+        self.calculate_loss_factor(from_zone, to_zone)
+    }
+
+    /// Loss factor from the zones' attributes, if the model is enabled and
+    /// both zones have them
+    fn modelled_loss_factor(&self, from_zone: Option<i32>, to_zone: Option<i32>) -> Option<Decimal> {
+        if !self.loss_model.enabled {
+            return None;
         }
+        let (from_zone, to_zone) = (from_zone?, to_zone?);
+        let attributes = self.zone_attributes.read().unwrap_or_else(PoisonError::into_inner);
+        let (from, to) = (attributes.get(&from_zone)?, attributes.get(&to_zone)?);
+        Some(model_loss_factor(&self.loss_model, from, to))
     }
 
     /// Default loss factor calculation (fallback)
@@ -219,6 +380,84 @@ impl GridTopologyService {
     pub fn calculate_loss_cost(&self, energy_amount: Decimal, price: Decimal, loss_factor: Decimal) -> Decimal {
         energy_amount * price * loss_factor
     }
+
+    /// Wheeling charge and loss factor for every ordered pair of `zones`
+    pub fn route_costs(&self, zones: &[i32]) -> Vec<ZoneRouteCost> {
+        zones
+            .iter()
+            .flat_map(|&from_zone| {
+                zones.iter().map(move |&to_zone| ZoneRouteCost {
+                    from_zone,
+                    to_zone,
+                    wheeling_charge: self.calculate_wheeling_charge(Some(from_zone), Some(to_zone)),
+                    loss_factor: self.calculate_loss_factor(Some(from_zone), Some(to_zone)),
+                })
+            })
+            .collect()
+    }
+
+    /// Cost breakdown of buying `energy_amount` kWh at `price_per_kwh` from a
+    /// seller in `seller_zone`
+    pub fn landed_cost(
+        &self,
+        seller_zone: Option<i32>,
+        buyer_zone: Option<i32>,
+        energy_amount: Decimal,
+        price_per_kwh: Decimal,
+    ) -> LandedCostBreakdown {
+        let wheeling_charge_per_kwh = self.calculate_wheeling_charge(seller_zone, buyer_zone);
+        let loss_factor = self.calculate_loss_factor(seller_zone, buyer_zone);
+
+        let energy_cost = energy_amount * price_per_kwh;
+        let wheeling_cost = energy_amount * wheeling_charge_per_kwh;
+        let loss_cost = self.calculate_loss_cost(energy_amount, price_per_kwh, loss_factor);
+
+        LandedCostBreakdown {
+            seller_zone,
+            buyer_zone,
+            energy_amount,
+            price_per_kwh,
+            wheeling_charge_per_kwh,
+            loss_factor,
+            energy_cost,
+            wheeling_cost,
+            loss_cost,
+            total_cost: energy_cost + wheeling_cost + loss_cost,
+            landed_cost_per_kwh: price_per_kwh + wheeling_charge_per_kwh + price_per_kwh * loss_factor,
+            delivered_energy: energy_amount * (Decimal::ONE - loss_factor),
+        }
+    }
+}
+
+/// Mean Earth radius in km
+const EARTH_RADIUS_KM: f64 = 6371.0;
+
+/// Great-circle distance between two zone centroids in km
+fn distance_km(from: &ZoneAttributes, to: &ZoneAttributes) -> f64 {
+    let (lat1, lat2) = (from.latitude.to_radians(), to.latitude.to_radians());
+    let d_lat = lat2 - lat1;
+    let d_lon = (to.longitude - from.longitude).to_radians();
+    let a = (d_lat / 2.0).sin().powi(2) + lat1.cos() * lat2.cos() * (d_lon / 2.0).sin().powi(2);
+    2.0 * EARTH_RADIUS_KM * a.sqrt().asin()
+}
+
+/// Loss factor between two zones under `model`
+fn model_loss_factor(model: &GridLossConfig, from: &ZoneAttributes, to: &ZoneAttributes) -> Decimal {
+    let km = if from.zone_id == to.zone_id {
+        Decimal::ZERO
+    } else {
+        Decimal::from_f64(distance_km(from, to)).unwrap_or_default().round_dp(3)
+    };
+
+    // The lower-voltage side carries the higher current and dominates I²R loss
+    let voltage_kv = from.voltage_kv.min(to.voltage_kv);
+    let voltage_ratio = model.reference_voltage_kv / voltage_kv;
+    let mut line_loss = model.loss_per_km * km * voltage_ratio * voltage_ratio;
+    if from.line_type == LineType::Underground || to.line_type == LineType::Underground {
+        line_loss *= model.underground_factor;
+    }
+
+    (model.base_loss + line_loss).min(model.max_loss).round_dp(6)
 }
 
 impl std::fmt::Debug for GridTopologyService {
@@ -229,4 +468,101 @@ impl std::fmt::Debug for GridTopologyService {
     }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    fn dec(s: &str) -> Decimal {
+        Decimal::from_str(s).unwrap()
+    }
+
+    #[test]
+    fn test_route_costs_cover_every_zone_pair() {
+        let routes = GridTopologyService::new().route_costs(&[1, 2, 4]);
+        assert_eq!(routes.len(), 9);
+
+        let local = routes.iter().find(|r| r.from_zone == 2 && r.to_zone == 2).unwrap();
+        assert_eq!(local.wheeling_charge, dec("0.50"));
+        let far = routes.iter().find(|r| r.from_zone == 1 && r.to_zone == 4).unwrap();
+        assert_eq!(far.wheeling_charge, dec("1.80"));
+    }
+
+    #[test]
+    fn test_landed_cost_matches_engine_pricing() {
+        // Adjacent zones: 1.00 THB/kWh wheeling, 3% loss
+        let breakdown = GridTopologyService::new().landed_cost(Some(1), Some(2), dec("10"), dec("4.00"));
+
+        assert_eq!(breakdown.energy_cost, dec("40"));
+        assert_eq!(breakdown.wheeling_cost, dec("10"));
+        assert_eq!(breakdown.loss_cost, dec("1.2"));
+        assert_eq!(breakdown.total_cost, dec("51.2"));
+        assert_eq!(breakdown.landed_cost_per_kwh, dec("5.12"));
+        assert_eq!(breakdown.delivered_energy, dec("9.7"));
+    }
+
+    #[test]
+    fn test_configured_rates_override_defaults() {
+        let topology = GridTopologyService::new();
+        topology.replace_rates([
+            ZoneRate { from_zone_id: 1, to_zone_id: 2, wheeling_charge: dec("0.75"), loss_factor: dec("0.02") },
+            ZoneRate { from_zone_id: 1, to_zone_id: 2, wheeling_charge: dec("0.80"), loss_factor: dec("0.025") },
+        ]);
+
+        assert_eq!(topology.calculate_wheeling_charge(Some(1), Some(2)), dec("0.80"));
+        assert_eq!(topology.calculate_loss_factor(Some(1), Some(2)), dec("0.025"));
+        // Unconfigured pairs and directions keep the defaults
+        assert_eq!(topology.calculate_wheeling_charge(Some(2), Some(1)), dec("1.00"));
+        assert_eq!(topology.calculate_wheeling_charge(None, Some(2)), dec("2.00"));
+    }
+
+    fn zone(zone_id: i32, latitude: f64, voltage_kv: &str, line_type: LineType) -> ZoneAttributes {
+        ZoneAttributes { zone_id, latitude, longitude: 100.5, voltage_kv: dec(voltage_kv), line_type }
+    }
+
+    #[test]
+    fn test_loss_model_scales_with_distance_and_voltage() {
+        let model = GridLossConfig::default();
+        let a = zone(1, 13.7, "22", LineType::Overhead);
+        // 0.1° of latitude is about 11.12 km
+        let b = zone(2, 13.8, "22", LineType::Overhead);
 
+        assert_eq!(model_loss_factor(&model, &a, &a), model.base_loss);
+
+        let near = model_loss_factor(&model, &a, &b);
+        assert!(near > dec("0.0144") && near < dec("0.0145"), "{}", near);
+
+        let high_voltage = model_loss_factor(&model, &zone(1, 13.7, "115", LineType::Overhead), &zone(2, 13.8, "115", LineType::Overhead));
+        assert!(high_voltage > model.base_loss && high_voltage < near);
+
+        let cable = model_loss_factor(&model, &a, &zone(2, 13.8, "22", LineType::Underground));
+        assert!(cable < near);
+
+        let far = model_loss_factor(&model, &a, &zone(3, 20.0, "22", LineType::Overhead));
+        assert_eq!(far, model.max_loss);
+    }
+
+    #[test]
+    fn test_loss_model_falls_back_without_attributes() {
+        let topology = GridTopologyService::new();
+        topology.replace_zone_attributes([
+            zone(1, 13.7, "22", LineType::Overhead),
+            zone(2, 13.8, "22", LineType::Overhead),
+        ]);
+
+        assert!(topology.calculate_loss_factor(Some(1), Some(2)) < dec("0.0145"));
+        // Zone 3 has no attributes: flat adjacent-zone default
+        assert_eq!(topology.calculate_loss_factor(Some(2), Some(3)), dec("0.03"));
+
+        // An admin-set rate wins over the model
+        topology.replace_rates([
+            ZoneRate { from_zone_id: 1, to_zone_id: 2, wheeling_charge: dec("0.75"), loss_factor: dec("0.02") },
+        ]);
+        assert_eq!(topology.calculate_loss_factor(Some(1), Some(2)), dec("0.02"));
+        assert!(topology.calculate_loss_factor(Some(2), Some(1)) < dec("0.0145"));
+
+        let disabled = GridTopologyService::new().with_loss_model(GridLossConfig { enabled: false, ..GridLossConfig::default() });
+        disabled.replace_zone_attributes([zone(1, 13.7, "22", LineType::Overhead), zone(2, 13.8, "22", LineType::Overhead)]);
+        assert_eq!(disabled.calculate_loss_factor(Some(1), Some(2)), dec("0.03"));
+    }
+}
@@ -3,8 +3,52 @@ use std::sync::Arc;
 use std::time::{Duration, Instant};
 use tokio::sync::RwLock;
 
+use crate::services::blockchain::{AuthorityFunding, AuthorityMonitor};
+use crate::services::task_heartbeat::TaskHeartbeats;
+
 pub mod types;
-pub use types::{DependencyHealth, DetailedHealthStatus, HealthCheckStatus, SystemMetrics};
+pub use types::{
+    DatabasePoolStats, DependencyHealth, DetailedHealthStatus, HealthCheckStatus, SystemMetrics,
+};
+
+/// Dependency names used in `DetailedHealthStatus::dependencies`
+pub const SOLANA_RPC_DEPENDENCY: &str = "Solana RPC";
+pub const SOLANA_WS_DEPENDENCY: &str = "Solana WebSocket";
+pub const AUTHORITY_WALLET_DEPENDENCY: &str = "Authority Wallet";
+pub const BACKGROUND_TASKS_DEPENDENCY: &str = "Background Tasks";
+
+/// Timeout for each RPC / WebSocket probe
+const PROBE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Pool utilization above which the database is reported as degraded
+const DB_POOL_DEGRADED_UTILIZATION_PERCENT: f64 = 90.0;
+
+/// Default seconds the slot may stay unchanged before the RPC counts as stale
+const DEFAULT_SLOT_STALE_SECS: u64 = 30;
+
+/// Last slot seen by the RPC probe and when it last moved
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct SlotProgress {
+    slot: u64,
+    advanced_at: Instant,
+}
+
+impl SlotProgress {
+    /// Record a new observation; returns the updated progress and how long
+    /// the slot has been stuck
+    fn observe(previous: Option<SlotProgress>, slot: u64, now: Instant) -> (SlotProgress, Duration) {
+        match previous {
+            Some(prev) if slot <= prev.slot => (prev, now.saturating_duration_since(prev.advanced_at)),
+            _ => (
+                SlotProgress {
+                    slot,
+                    advanced_at: now,
+                },
+                Duration::ZERO,
+            ),
+        }
+    }
+}
 
 /// Health checker service
 #[derive(Clone)]
@@ -13,8 +57,13 @@ pub struct HealthChecker {
     db_pool: sqlx::PgPool,
     redis_client: redis::Client,
     blockchain_url: String,
+    blockchain_ws_url: Option<String>,
+    slot_progress: Arc<RwLock<Option<SlotProgress>>>,
+    slot_stale_after: Duration,
     last_check: Arc<RwLock<Option<DetailedHealthStatus>>>,
     email_service_enabled: bool,
+    authority_monitor: Option<AuthorityMonitor>,
+    task_heartbeats: Option<TaskHeartbeats>,
 }
 
 impl HealthChecker {
@@ -29,11 +78,39 @@ impl HealthChecker {
             db_pool,
             redis_client,
             blockchain_url,
+            blockchain_ws_url: None,
+            slot_progress: Arc::new(RwLock::new(None)),
+            slot_stale_after: Duration::from_secs(
+                std::env::var("SOLANA_SLOT_STALE_SECS")
+                    .ok()
+                    .and_then(|v| v.parse::<u64>().ok())
+                    .unwrap_or(DEFAULT_SLOT_STALE_SECS),
+            ),
             last_check: Arc::new(RwLock::new(None)),
             email_service_enabled,
+            authority_monitor: None,
+            task_heartbeats: None,
         }
     }
 
+    /// Also probe the Solana WebSocket endpoint
+    pub fn with_blockchain_ws_url(mut self, ws_url: String) -> Self {
+        self.blockchain_ws_url = Some(ws_url);
+        self
+    }
+
+    /// Also report the authority wallet's funding, as of its last check
+    pub fn with_authority_monitor(mut self, authority_monitor: AuthorityMonitor) -> Self {
+        self.authority_monitor = Some(authority_monitor);
+        self
+    }
+
+    /// Also report whether the background loops are still beating
+    pub fn with_task_heartbeats(mut self, task_heartbeats: TaskHeartbeats) -> Self {
+        self.task_heartbeats = Some(task_heartbeats);
+        self
+    }
+
     /// Get uptime in seconds
     pub fn get_uptime(&self) -> u64 {
         self.start_time.elapsed().as_secs()
@@ -44,14 +121,30 @@ impl HealthChecker {
         let start = Instant::now();
 
         match sqlx::query("SELECT 1").fetch_one(&self.db_pool).await {
-            Ok(_) => DependencyHealth {
-                name: "PostgreSQL".to_string(),
-                status: HealthCheckStatus::Healthy,
-                response_time_ms: Some(start.elapsed().as_millis() as u64),
-                last_check: Utc::now(),
-                error_message: None,
-                details: Some("Database connection successful".to_string()),
-            },
+            Ok(_) => {
+                let pool = DatabasePoolStats::from_pool(&self.db_pool);
+                let saturated = pool.utilization_percent >= DB_POOL_DEGRADED_UTILIZATION_PERCENT;
+                DependencyHealth {
+                    name: "PostgreSQL".to_string(),
+                    status: if saturated {
+                        HealthCheckStatus::Degraded
+                    } else {
+                        HealthCheckStatus::Healthy
+                    },
+                    response_time_ms: Some(start.elapsed().as_millis() as u64),
+                    last_check: Utc::now(),
+                    error_message: saturated.then(|| {
+                        format!(
+                            "Connection pool {:.0}% utilized ({}/{})",
+                            pool.utilization_percent, pool.active, pool.max_connections
+                        )
+                    }),
+                    details: Some(format!(
+                        "Database connection successful; pool {}/{} active, {} idle",
+                        pool.active, pool.max_connections, pool.idle
+                    )),
+                }
+            }
             Err(e) => DependencyHealth {
                 name: "PostgreSQL".to_string(),
                 status: HealthCheckStatus::Unhealthy,
@@ -100,64 +193,217 @@ impl HealthChecker {
         }
     }
 
-    /// Check blockchain RPC health
+    /// Check blockchain RPC health: reachability, latency and slot progress
     async fn check_blockchain(&self) -> DependencyHealth {
         let start = Instant::now();
+        let unhealthy = |error: String, response_time_ms: Option<u64>| DependencyHealth {
+            name: SOLANA_RPC_DEPENDENCY.to_string(),
+            status: HealthCheckStatus::Unhealthy,
+            response_time_ms,
+            last_check: Utc::now(),
+            error_message: Some(error),
+            details: None,
+        };
+
+        let client = match reqwest::Client::builder().timeout(PROBE_TIMEOUT).build() {
+            Ok(client) => client,
+            Err(e) => return unhealthy(e.to_string(), None),
+        };
 
-        match reqwest::Client::builder()
-            .timeout(Duration::from_secs(5))
-            .build()
+        let response = match client
+            .post(&self.blockchain_url)
+            .json(&serde_json::json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "method": "getSlot",
+                "params": [{ "commitment": "confirmed" }]
+            }))
+            .send()
+            .await
         {
-            Ok(client) => {
-                match client
-                    .post(&self.blockchain_url)
-                    .json(&serde_json::json!({
-                        "jsonrpc": "2.0",
-                        "id": 1,
-                        "method": "getHealth"
-                    }))
-                    .send()
-                    .await
-                {
-                    Ok(response) => {
-                        if response.status().is_success() {
-                            DependencyHealth {
-                                name: "Solana RPC".to_string(),
-                                status: HealthCheckStatus::Healthy,
-                                response_time_ms: Some(start.elapsed().as_millis() as u64),
-                                last_check: Utc::now(),
-                                error_message: None,
-                                details: Some("RPC endpoint responding".to_string()),
-                            }
-                        } else {
-                            DependencyHealth {
-                                name: "Solana RPC".to_string(),
-                                status: HealthCheckStatus::Degraded,
-                                response_time_ms: Some(start.elapsed().as_millis() as u64),
-                                last_check: Utc::now(),
-                                error_message: Some(format!("HTTP {}", response.status())),
-                                details: None,
-                            }
-                        }
-                    }
-                    Err(e) => DependencyHealth {
-                        name: "Solana RPC".to_string(),
-                        status: HealthCheckStatus::Unhealthy,
-                        response_time_ms: Some(start.elapsed().as_millis() as u64),
-                        last_check: Utc::now(),
-                        error_message: Some(e.to_string()),
-                        details: None,
-                    },
-                }
+            Ok(response) => response,
+            Err(e) => return unhealthy(e.to_string(), Some(start.elapsed().as_millis() as u64)),
+        };
+        let response_time_ms = Some(start.elapsed().as_millis() as u64);
+
+        if !response.status().is_success() {
+            return DependencyHealth {
+                name: SOLANA_RPC_DEPENDENCY.to_string(),
+                status: HealthCheckStatus::Degraded,
+                response_time_ms,
+                last_check: Utc::now(),
+                error_message: Some(format!("HTTP {}", response.status())),
+                details: None,
+            };
+        }
+
+        let body: serde_json::Value = match response.json().await {
+            Ok(body) => body,
+            Err(e) => return unhealthy(format!("Invalid RPC response: {}", e), response_time_ms),
+        };
+        let Some(slot) = body.get("result").and_then(|r| r.as_u64()) else {
+            let error = body
+                .get("error")
+                .map(|e| e.to_string())
+                .unwrap_or_else(|| "getSlot returned no result".to_string());
+            return DependencyHealth {
+                name: SOLANA_RPC_DEPENDENCY.to_string(),
+                status: HealthCheckStatus::Degraded,
+                response_time_ms,
+                last_check: Utc::now(),
+                error_message: Some(error),
+                details: None,
+            };
+        };
+
+        let stalled_for = {
+            let mut progress = self.slot_progress.write().await;
+            let (updated, stalled_for) = SlotProgress::observe(*progress, slot, Instant::now());
+            *progress = Some(updated);
+            stalled_for
+        };
+
+        if stalled_for > self.slot_stale_after {
+            DependencyHealth {
+                name: SOLANA_RPC_DEPENDENCY.to_string(),
+                status: HealthCheckStatus::Degraded,
+                response_time_ms,
+                last_check: Utc::now(),
+                error_message: Some(format!(
+                    "Slot {} has not advanced for {}s",
+                    slot,
+                    stalled_for.as_secs()
+                )),
+                details: None,
             }
-            Err(e) => DependencyHealth {
-                name: "Solana RPC".to_string(),
+        } else {
+            DependencyHealth {
+                name: SOLANA_RPC_DEPENDENCY.to_string(),
+                status: HealthCheckStatus::Healthy,
+                response_time_ms,
+                last_check: Utc::now(),
+                error_message: None,
+                details: Some(format!("Slot {}", slot)),
+            }
+        }
+    }
+
+    /// Check the Solana WebSocket endpoint accepts TCP connections
+    async fn check_blockchain_ws(&self, ws_url: &str) -> DependencyHealth {
+        let start = Instant::now();
+
+        let address = reqwest::Url::parse(ws_url).ok().and_then(|url| {
+            let host = url.host_str()?.to_string();
+            let port = url.port_or_known_default()?;
+            Some((host, port))
+        });
+        let Some((host, port)) = address else {
+            return DependencyHealth {
+                name: SOLANA_WS_DEPENDENCY.to_string(),
                 status: HealthCheckStatus::Unhealthy,
                 response_time_ms: None,
                 last_check: Utc::now(),
+                error_message: Some(format!("Invalid WebSocket URL: {}", ws_url)),
+                details: None,
+            };
+        };
+
+        let result = tokio::time::timeout(
+            PROBE_TIMEOUT,
+            tokio::net::TcpStream::connect((host.as_str(), port)),
+        )
+        .await;
+        let response_time_ms = Some(start.elapsed().as_millis() as u64);
+
+        match result {
+            Ok(Ok(_)) => DependencyHealth {
+                name: SOLANA_WS_DEPENDENCY.to_string(),
+                status: HealthCheckStatus::Healthy,
+                response_time_ms,
+                last_check: Utc::now(),
+                error_message: None,
+                details: Some(format!("{}:{} reachable", host, port)),
+            },
+            Ok(Err(e)) => DependencyHealth {
+                name: SOLANA_WS_DEPENDENCY.to_string(),
+                // Event subscriptions degrade, but settlements still go over HTTP RPC
+                status: HealthCheckStatus::Degraded,
+                response_time_ms,
+                last_check: Utc::now(),
                 error_message: Some(e.to_string()),
                 details: None,
             },
+            Err(_) => DependencyHealth {
+                name: SOLANA_WS_DEPENDENCY.to_string(),
+                status: HealthCheckStatus::Degraded,
+                response_time_ms,
+                last_check: Utc::now(),
+                error_message: Some(format!("Connection timed out after {}s", PROBE_TIMEOUT.as_secs())),
+                details: None,
+            },
+        }
+    }
+
+    /// Report the authority wallet from the monitor's last check rather than
+    /// querying the balance on every probe
+    async fn check_authority(&self, monitor: &AuthorityMonitor) -> DependencyHealth {
+        let Some(status) = monitor.latest().await else {
+            return DependencyHealth {
+                name: AUTHORITY_WALLET_DEPENDENCY.to_string(),
+                status: HealthCheckStatus::Unknown,
+                response_time_ms: None,
+                last_check: Utc::now(),
+                error_message: None,
+                details: Some("Not checked yet".to_string()),
+            };
+        };
+
+        // Error of the wallet that determined the overall state
+        let wallet_error = status
+            .wallets
+            .iter()
+            .find(|wallet| wallet.funding == status.funding)
+            .and_then(|wallet| wallet.error.clone());
+        let (health, error_message) = match status.funding {
+            AuthorityFunding::Funded => (HealthCheckStatus::Healthy, None),
+            AuthorityFunding::Low => (
+                HealthCheckStatus::Degraded,
+                Some(format!("Balance below {} SOL", status.low_balance_sol)),
+            ),
+            AuthorityFunding::Unfunded => (
+                HealthCheckStatus::Unhealthy,
+                Some(format!("Balance below the {} SOL minimum", status.min_balance_sol)),
+            ),
+            AuthorityFunding::Missing => (HealthCheckStatus::Unhealthy, wallet_error),
+            AuthorityFunding::Unknown => (HealthCheckStatus::Unknown, wallet_error),
+        };
+
+        let details = status
+            .wallets
+            .iter()
+            .map(|wallet| {
+                let roles = wallet
+                    .roles
+                    .iter()
+                    .map(|role| format!("{:?}", role))
+                    .collect::<Vec<_>>()
+                    .join("+");
+                match (&wallet.pubkey, wallet.balance_sol) {
+                    (Some(pubkey), Some(balance)) => format!("{} {}: {} SOL", roles, pubkey, balance),
+                    (Some(pubkey), None) => format!("{} {}", roles, pubkey),
+                    (None, _) => format!("{}: not configured", roles),
+                }
+            })
+            .collect::<Vec<_>>()
+            .join("; ");
+
+        DependencyHealth {
+            name: AUTHORITY_WALLET_DEPENDENCY.to_string(),
+            status: health,
+            response_time_ms: None,
+            last_check: status.checked_at,
+            error_message,
+            details: Some(details),
         }
     }
 
@@ -204,14 +450,36 @@ impl HealthChecker {
     /// Perform full health check
     pub async fn perform_health_check(&self) -> DetailedHealthStatus {
         // Check all dependencies in parallel
-        let (db_health, redis_health, blockchain_health) = tokio::join!(
+        let ws_check = async {
+            match &self.blockchain_ws_url {
+                Some(ws_url) => Some(self.check_blockchain_ws(ws_url).await),
+                None => None,
+            }
+        };
+        let authority_check = async {
+            match &self.authority_monitor {
+                Some(monitor) => Some(self.check_authority(monitor).await),
+                None => None,
+            }
+        };
+        let (db_health, redis_health, blockchain_health, ws_health, authority_health) = tokio::join!(
             self.check_database(),
             self.check_redis(),
-            self.check_blockchain()
+            self.check_blockchain(),
+            ws_check,
+            authority_check
         );
 
         let email_health = self.check_email();
-        let dependencies = vec![db_health, redis_health, blockchain_health, email_health];
+        let mut dependencies = vec![db_health, redis_health, blockchain_health];
+        dependencies.extend(ws_health);
+        dependencies.extend(authority_health);
+        dependencies.extend(
+            self.task_heartbeats
+                .as_ref()
+                .and_then(|heartbeats| self.check_background_tasks(heartbeats)),
+        );
+        dependencies.push(email_health);
 
         // Determine overall status
         let overall_status = if dependencies
@@ -236,6 +504,7 @@ impl HealthChecker {
             uptime_seconds: self.get_uptime(),
             dependencies,
             metrics: self.get_system_metrics(),
+            database_pool: DatabasePoolStats::from_pool(&self.db_pool),
         };
 
         // Cache the result
@@ -260,6 +529,24 @@ mod tests {
         assert_ne!(HealthCheckStatus::Healthy, HealthCheckStatus::Unhealthy);
     }
 
+    #[test]
+    fn test_slot_progress_tracks_stall_duration() {
+        let start = Instant::now();
+        let (progress, stalled) = SlotProgress::observe(None, 100, start);
+        assert_eq!(progress.slot, 100);
+        assert_eq!(stalled, Duration::ZERO);
+
+        let later = start + Duration::from_secs(40);
+        let (progress, stalled) = SlotProgress::observe(Some(progress), 100, later);
+        assert_eq!(progress.advanced_at, start);
+        assert_eq!(stalled, Duration::from_secs(40));
+
+        let (progress, stalled) = SlotProgress::observe(Some(progress), 101, later);
+        assert_eq!(progress.slot, 101);
+        assert_eq!(progress.advanced_at, later);
+        assert_eq!(stalled, Duration::ZERO);
+    }
+
     #[test]
     fn test_system_metrics_serialization() {
         let metrics = SystemMetrics {
@@ -23,6 +23,40 @@ pub struct DetailedHealthStatus {
     pub uptime_seconds: u64,
     pub dependencies: Vec<DependencyHealth>,
     pub metrics: SystemMetrics,
+    pub database_pool: DatabasePoolStats,
+}
+
+/// Database connection pool occupancy
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct DatabasePoolStats {
+    pub size: u32,
+    pub idle: u32,
+    pub active: u32,
+    pub max_connections: u32,
+    /// Active connections as a percentage of `max_connections`
+    pub utilization_percent: f64,
+}
+
+impl DatabasePoolStats {
+    pub fn from_pool(pool: &sqlx::PgPool) -> Self {
+        let size = pool.size();
+        let idle = pool.num_idle() as u32;
+        let active = size.saturating_sub(idle);
+        let max_connections = pool.options().get_max_connections();
+        let utilization_percent = if max_connections == 0 {
+            0.0
+        } else {
+            active as f64 / max_connections as f64 * 100.0
+        };
+
+        Self {
+            size,
+            idle,
+            active,
+            max_connections,
+            utilization_percent,
+        }
+    }
 }
 
 /// Dependency health information
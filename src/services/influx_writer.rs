@@ -0,0 +1,293 @@
+//! Time-series export of meter readings to InfluxDB
+//!
+//! Readings are queued as they are ingested and written in batches using the
+//! v2 line protocol, so a slow or unavailable InfluxDB never holds up the
+//! reading pipeline. Failed writes are retried with backoff and then dropped;
+//! PostgreSQL remains the system of record.
+
+use chrono::{DateTime, Utc};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tokio_util::sync::CancellationToken;
+use tokio_util::task::TaskTracker;
+use tracing::{debug, error, info, warn};
+
+use crate::config::InfluxConfig;
+use crate::middleware::metrics::track_influx_points;
+
+/// Maximum number of points waiting to be written
+const INFLUX_QUEUE_CAPACITY: usize = 10_000;
+
+/// Delay before the first retry; doubles on each further attempt
+const INFLUX_RETRY_BASE_DELAY: Duration = Duration::from_millis(500);
+
+const MEASUREMENT: &str = "meter_reading";
+
+/// One ingested meter reading
+#[derive(Debug, Clone, Default)]
+pub struct ReadingPoint {
+    pub meter_serial: String,
+    pub zone_id: Option<i32>,
+    pub meter_type: Option<String>,
+    pub kwh: f64,
+    pub power: Option<f64>,
+    pub voltage: Option<f64>,
+    pub current: Option<f64>,
+    pub energy_generated: Option<f64>,
+    pub energy_consumed: Option<f64>,
+    pub timestamp: DateTime<Utc>,
+}
+
+impl ReadingPoint {
+    /// Render as one line of InfluxDB line protocol with a nanosecond timestamp
+    ///
+    /// Missing and non-finite fields are left out; `kwh` is always written.
+    pub fn to_line_protocol(&self) -> String {
+        let mut line = String::from(MEASUREMENT);
+
+        line.push_str(",meter_serial=");
+        line.push_str(&escape_tag(&self.meter_serial));
+        if let Some(meter_type) = self.meter_type.as_deref().filter(|t| !t.is_empty()) {
+            line.push_str(",meter_type=");
+            line.push_str(&escape_tag(meter_type));
+        }
+        if let Some(zone_id) = self.zone_id {
+            line.push_str(&format!(",zone_id={}", zone_id));
+        }
+
+        let fields: Vec<String> = [
+            ("kwh", Some(self.kwh)),
+            ("power", self.power),
+            ("voltage", self.voltage),
+            ("current", self.current),
+            ("energy_generated", self.energy_generated),
+            ("energy_consumed", self.energy_consumed),
+        ]
+        .into_iter()
+        .filter_map(|(name, value)| value.filter(|v| v.is_finite()).map(|v| format!("{}={:?}", name, v)))
+        .collect();
+
+        line.push(' ');
+        line.push_str(&fields.join(","));
+
+        if let Some(nanos) = self.timestamp.timestamp_nanos_opt() {
+            line.push_str(&format!(" {}", nanos));
+        }
+        line
+    }
+}
+
+/// Escape a tag value: commas, equals signs and spaces are significant
+fn escape_tag(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            ',' | '=' | ' ' | '\\' => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            '\n' | '\r' => escaped.push(' '),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Batching writer for meter reading points
+///
+/// [`InfluxWriter::record`] enqueues and returns immediately; points are
+/// written by the worker started with [`InfluxWriter::start_worker`].
+#[derive(Clone)]
+pub struct InfluxWriter {
+    config: InfluxConfig,
+    client: reqwest::Client,
+    queue_tx: mpsc::Sender<ReadingPoint>,
+    queue_rx: Arc<Mutex<Option<mpsc::Receiver<ReadingPoint>>>>,
+}
+
+impl InfluxWriter {
+    /// Create a writer, or `None` if the export is disabled
+    pub fn new(config: &InfluxConfig, client: reqwest::Client) -> Option<Self> {
+        if !config.enabled {
+            info!("InfluxDB export disabled (set INFLUXDB_TOKEN to enable)");
+            return None;
+        }
+
+        let (queue_tx, queue_rx) = mpsc::channel(INFLUX_QUEUE_CAPACITY);
+        info!(
+            "InfluxDB export enabled ({} bucket {}, batches of {})",
+            config.url, config.bucket, config.batch_size
+        );
+
+        Some(Self {
+            config: config.clone(),
+            client,
+            queue_tx,
+            queue_rx: Arc::new(Mutex::new(Some(queue_rx))),
+        })
+    }
+
+    /// Queue a reading for export; dropped with a warning if the queue is full
+    pub fn record(&self, point: ReadingPoint) {
+        if let Err(e) = self.queue_tx.try_send(point) {
+            let point = match e {
+                mpsc::error::TrySendError::Full(point) | mpsc::error::TrySendError::Closed(point) => point,
+            };
+            warn!("InfluxDB queue unavailable, dropping reading for {}", point.meter_serial);
+            track_influx_points("dropped", 1);
+        }
+    }
+
+    /// Spawn the background task that batches and writes queued points.
+    /// Returns false if the worker was already started.
+    ///
+    /// On shutdown the points already queued are written before the task ends.
+    pub fn start_worker(&self, shutdown: CancellationToken, tracker: &TaskTracker) -> bool {
+        let receiver = match self.queue_rx.lock() {
+            Ok(mut guard) => guard.take(),
+            Err(poisoned) => poisoned.into_inner().take(),
+        };
+        let Some(mut receiver) = receiver else {
+            return false;
+        };
+
+        let writer = self.clone();
+        tracker.spawn(async move {
+            let batch_size = writer.config.batch_size;
+            let mut batch: Vec<String> = Vec::with_capacity(batch_size);
+            let mut ticker = tokio::time::interval(Duration::from_millis(writer.config.flush_interval_ms));
+
+            loop {
+                tokio::select! {
+                    point = receiver.recv() => match point {
+                        Some(point) => {
+                            batch.push(point.to_line_protocol());
+                            if batch.len() >= batch_size {
+                                writer.flush(&mut batch).await;
+                            }
+                        }
+                        None => break,
+                    },
+                    _ = ticker.tick() => {
+                        if !batch.is_empty() {
+                            writer.flush(&mut batch).await;
+                        }
+                    }
+                    _ = shutdown.cancelled() => break,
+                }
+            }
+
+            while let Ok(point) = receiver.try_recv() {
+                batch.push(point.to_line_protocol());
+                if batch.len() >= batch_size {
+                    writer.flush(&mut batch).await;
+                }
+            }
+            if !batch.is_empty() {
+                writer.flush(&mut batch).await;
+            }
+            info!("InfluxDB writer stopped");
+        });
+
+        true
+    }
+
+    /// Write and clear `batch`, retrying transient failures
+    async fn flush(&self, batch: &mut Vec<String>) {
+        let count = batch.len();
+        let body = batch.join("\n");
+        batch.clear();
+
+        let mut delay = INFLUX_RETRY_BASE_DELAY;
+        for attempt in 0..=self.config.max_retries {
+            match self.write(body.clone()).await {
+                Ok(()) => {
+                    debug!("Wrote {} points to InfluxDB", count);
+                    track_influx_points("written", count);
+                    return;
+                }
+                Err(WriteError::Permanent(e)) => {
+                    error!("InfluxDB rejected {} points: {}", count, e);
+                    break;
+                }
+                Err(WriteError::Transient(e)) if attempt == self.config.max_retries => {
+                    error!("Failed to write {} points to InfluxDB after {} attempts: {}", count, attempt + 1, e);
+                }
+                Err(WriteError::Transient(e)) => {
+                    warn!(
+                        "InfluxDB write failed (attempt {}/{}), retrying in {:?}: {}",
+                        attempt + 1,
+                        self.config.max_retries + 1,
+                        delay,
+                        e
+                    );
+                    tokio::time::sleep(delay).await;
+                    delay *= 2;
+                }
+            }
+        }
+        track_influx_points("dropped", count);
+    }
+
+    async fn write(&self, body: String) -> Result<(), WriteError> {
+        let response = self
+            .client
+            .post(format!("{}/api/v2/write", self.config.url))
+            .query(&[
+                ("org", self.config.org.as_str()),
+                ("bucket", self.config.bucket.as_str()),
+                ("precision", "ns"),
+            ])
+            .header(reqwest::header::AUTHORIZATION, format!("Token {}", self.config.token))
+            .header(reqwest::header::CONTENT_TYPE, "text/plain; charset=utf-8")
+            .body(body)
+            .send()
+            .await
+            .map_err(|e| WriteError::Transient(e.to_string()))?;
+
+        let status = response.status();
+        if status.is_success() {
+            return Ok(());
+        }
+
+        let detail = response.text().await.unwrap_or_default();
+        let message = format!("{}: {}", status, detail);
+        // Malformed points or bad credentials will not succeed on retry
+        if status.is_client_error() && status != reqwest::StatusCode::TOO_MANY_REQUESTS {
+            Err(WriteError::Permanent(message))
+        } else {
+            Err(WriteError::Transient(message))
+        }
+    }
+}
+
+enum WriteError {
+    Transient(String),
+    Permanent(String),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_line_protocol() {
+        let point = ReadingPoint {
+            meter_serial: "MTR 001,a=b".to_string(),
+            zone_id: Some(3),
+            meter_type: Some("solar".to_string()),
+            kwh: 1.5,
+            power: Some(2.0),
+            voltage: Some(f64::NAN),
+            timestamp: Utc.timestamp_opt(1_700_000_000, 0).unwrap(),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            point.to_line_protocol(),
+            "meter_reading,meter_serial=MTR\\ 001\\,a\\=b,meter_type=solar,zone_id=3 kwh=1.5,power=2.0 1700000000000000000"
+        );
+    }
+}
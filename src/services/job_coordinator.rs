@@ -0,0 +1,229 @@
+//! Leader election for the singleton background jobs
+//!
+//! Any number of gateway replicas can serve HTTP, but order matching,
+//! settlement, event processing and the other periodic jobs must run on
+//! exactly one of them: two settlement loops would transfer the same trade
+//! twice on-chain. Replicas compete for a lease in Redis; the holder renews it
+//! and runs the jobs, the others retry and take over once it lapses.
+//!
+//! A holder that cannot renew stops running jobs when its lease would have
+//! expired, measured from before the request that granted it, so it never
+//! believes it leads after Redis has handed the lease to someone else.
+//!
+//! A holder whose background loops have stopped beating gives the lease up
+//! and does not campaign again until they recover, so a replica with a dead
+//! matching or settlement loop does not keep the jobs from running elsewhere.
+
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio_util::sync::CancellationToken;
+use tokio_util::task::TaskTracker;
+use tracing::{info, warn};
+
+use crate::config::LeaderElectionConfig;
+use crate::middleware::metrics::track_job_leadership;
+use crate::services::cache::{CacheKeys, CacheService};
+use crate::services::task_heartbeat::TaskHeartbeats;
+
+/// This instance's view of the lease
+#[derive(Debug, Default)]
+struct LeaseState {
+    /// When the lease lapses unless renewed; `None` when not held
+    held_until: Option<Instant>,
+}
+
+impl LeaseState {
+    fn is_held(&self, now: Instant) -> bool {
+        self.held_until.is_some_and(|deadline| now < deadline)
+    }
+
+    /// Record a grant or renewal requested at `requested_at`
+    fn granted(&mut self, requested_at: Instant, ttl: Duration) {
+        self.held_until = Some(requested_at + ttl);
+    }
+
+    fn lost(&mut self) {
+        self.held_until = None;
+    }
+}
+
+/// Decides whether this instance runs the singleton background jobs
+///
+/// Cheap to clone; clones share the lease. With election disabled every
+/// instance is the leader.
+#[derive(Clone)]
+pub struct BackgroundJobCoordinator {
+    cache: CacheService,
+    config: LeaderElectionConfig,
+    lease: Arc<Mutex<LeaseState>>,
+    heartbeats: Option<TaskHeartbeats>,
+}
+
+impl BackgroundJobCoordinator {
+    pub fn new(cache: CacheService, config: LeaderElectionConfig) -> Self {
+        Self {
+            cache,
+            config,
+            lease: Arc::new(Mutex::new(LeaseState::default())),
+            heartbeats: None,
+        }
+    }
+
+    /// Stop leading while any loop reporting to `heartbeats` is stale
+    pub fn with_heartbeats(mut self, heartbeats: TaskHeartbeats) -> Self {
+        self.heartbeats = Some(heartbeats);
+        self
+    }
+
+    pub fn instance_id(&self) -> &str {
+        &self.config.instance_id
+    }
+
+    /// Whether singleton jobs should run on this instance right now
+    pub fn is_leader(&self) -> bool {
+        !self.config.enabled || self.lock_lease().is_held(Instant::now())
+    }
+
+    fn lock_lease(&self) -> std::sync::MutexGuard<'_, LeaseState> {
+        match self.lease.lock() {
+            Ok(guard) => guard,
+            Err(poisoned) => poisoned.into_inner(),
+        }
+    }
+
+    fn ttl(&self) -> Duration {
+        Duration::from_millis(self.config.lease_ttl_ms)
+    }
+
+    /// Take or renew the lease once; returns whether this instance leads
+    pub async fn campaign(&self) -> bool {
+        if !self.config.enabled {
+            return true;
+        }
+
+        let stale = self
+            .heartbeats
+            .as_ref()
+            .map(|heartbeats| heartbeats.stale_tasks())
+            .unwrap_or_default();
+        if !stale.is_empty() {
+            if self.is_leader() {
+                warn!(
+                    "⚠️ {} resigning the background job lease; stale loops: {}",
+                    self.instance_id(),
+                    stale.join(", ")
+                );
+                self.resign().await;
+            }
+            track_job_leadership(false);
+            return false;
+        }
+
+        let key = CacheKeys::background_jobs_leader();
+        let token = self.instance_id();
+        let was_leader = self.is_leader();
+        let requested_at = Instant::now();
+
+        let outcome = if was_leader {
+            self.cache.renew_lock(&key, token, self.config.lease_ttl_ms).await
+        } else {
+            self.cache.try_acquire_lock(&key, token, self.config.lease_ttl_ms).await
+        };
+
+        let is_leader = match outcome {
+            Ok(true) => {
+                self.lock_lease().granted(requested_at, self.ttl());
+                true
+            }
+            Ok(false) => {
+                self.lock_lease().lost();
+                false
+            }
+            // Keep the current deadline; the lease may still be ours
+            Err(e) => {
+                warn!("⚠️ Background job lease check failed on {}: {}", token, e);
+                self.is_leader()
+            }
+        };
+
+        if is_leader != was_leader {
+            if is_leader {
+                info!("👑 {} acquired the background job lease", token);
+            } else {
+                warn!("⚠️ {} lost the background job lease; singleton jobs paused", token);
+            }
+        }
+        track_job_leadership(is_leader);
+        is_leader
+    }
+
+    /// Spawn the task that keeps campaigning for the lease, releasing it on
+    /// shutdown so another instance can take over without waiting for it to
+    /// lapse
+    pub fn start(&self, shutdown: CancellationToken, tracker: &TaskTracker) {
+        if !self.config.enabled {
+            info!("⏸️ Leader election disabled; this instance runs all background jobs");
+            track_job_leadership(true);
+            return;
+        }
+
+        let coordinator = self.clone();
+        let interval = Duration::from_millis(self.config.renew_interval_ms);
+        tracker.spawn(async move {
+            info!(
+                "🚀 Starting background job leader election as {} (lease {}ms, renew every {}ms)",
+                coordinator.instance_id(),
+                coordinator.config.lease_ttl_ms,
+                coordinator.config.renew_interval_ms
+            );
+            loop {
+                tokio::select! {
+                    _ = shutdown.cancelled() => break,
+                    _ = tokio::time::sleep(interval) => {}
+                }
+                coordinator.campaign().await;
+            }
+            coordinator.resign().await;
+        });
+    }
+
+    /// Give up the lease if this instance holds it
+    async fn resign(&self) {
+        if !self.is_leader() {
+            return;
+        }
+        self.lock_lease().lost();
+        track_job_leadership(false);
+
+        let key = CacheKeys::background_jobs_leader();
+        match self.cache.release_lock(&key, self.instance_id()).await {
+            Ok(_) => info!("👋 {} released the background job lease", self.instance_id()),
+            Err(e) => warn!("⚠️ Failed to release background job lease: {}", e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lease_lapses_without_renewal() {
+        let ttl = Duration::from_millis(15_000);
+        let start = Instant::now();
+        let mut lease = LeaseState::default();
+        assert!(!lease.is_held(start));
+
+        lease.granted(start, ttl);
+        assert!(lease.is_held(start + Duration::from_millis(14_999)));
+        assert!(!lease.is_held(start + ttl));
+
+        // A renewal counts from when it was requested
+        lease.granted(start + Duration::from_secs(5), ttl);
+        assert!(lease.is_held(start + Duration::from_secs(19)));
+        assert!(!lease.is_held(start + Duration::from_secs(20)));
+
+        lease.lost();
+        assert!(!lease.is_held(start));
+    }
+}
@@ -228,6 +228,7 @@ impl KafkaConsumerService {
             params,
             request,
             retry_count: 0,
+            correlation_id: uuid::Uuid::new_v4(),
         })
     }
 }
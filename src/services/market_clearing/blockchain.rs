@@ -1,5 +1,4 @@
 use anyhow::Result;
-use rust_decimal::prelude::ToPrimitive;
 use rust_decimal::Decimal;
 use sqlx::Row;
 use solana_sdk::pubkey::Pubkey;
@@ -9,7 +8,9 @@ use tracing::{error, info};
 
 use solana_sdk::signature::Signer;
 use crate::database::schema::types::OrderSide;
+use crate::services::blockchain::instructions::{TokenInstructions, TradingInstructions};
 use crate::services::WalletService;
+use crate::utils::units::to_atomic;
 use super::MarketClearingService;
 
 impl MarketClearingService {
@@ -90,31 +91,34 @@ impl MarketClearingService {
 
         // On-chain tx
         let (signature, order_pda) = if self.config.tokenization.enable_real_blockchain {
-            let trading_program_id = self.blockchain_service.trading_program_id()?;
+            let trading_program_id = self.blockchain.trading_program_id();
             let (market_pda, _) = Pubkey::find_program_address(&[b"market"], &trading_program_id);
 
-            let multiplier = Decimal::from(1_000_000_000);
-            let amount_u64 = (energy_amount * multiplier).to_u64().unwrap_or(0);
-            let price_u64 = (price_per_kwh * multiplier).to_u64().unwrap_or(0);
+            let energy_mint = Pubkey::from_str(&self.config.energy_token_mint)
+                .map_err(|e| anyhow::anyhow!("Invalid energy mint config: {}", e))?;
+            let energy_decimals = self.blockchain.decimals_for(&energy_mint).await?;
+
+            let amount_u64 = to_atomic(energy_amount, energy_decimals)?;
+            let price_u64 = to_atomic(price_per_kwh, 9)?; // Price is matched scale
 
             info!("Creating order on-chain with Payer: {}", keypair.pubkey());
             info!("Market PDA: {}", market_pda);
             
             // Check balance
-            if let Ok(bal) = self.blockchain_service.account_manager.get_balance(&keypair.pubkey()).await {
+            if let Ok(bal) = self.blockchain.get_balance(&keypair.pubkey()).await {
                 info!("Payer Balance: {} lamports", bal);
             }
             
-            let (sig, pda_str) = match self.blockchain_service.execute_create_order(
+            let side_code = match side {
+                OrderSide::Buy => 0,
+                OrderSide::Sell => 1,
+            };
+            let (sig, pda_str) = match self.blockchain.execute_create_order(
                 &keypair,
-                &market_pda.to_string(),
+                &market_pda,
+                side_code,
                 amount_u64,
                 price_u64,
-                match side {
-                    OrderSide::Buy => "buy",
-                    OrderSide::Sell => "sell",
-                },
-                None,
             ).await {
                 Ok(res) => res,
                 Err(e) => {
@@ -162,7 +166,20 @@ impl MarketClearingService {
             match self.execute_escrow_lock(user_id, order_id, lock_amount, asset_type, session_token).await {
                 Ok(sig) => {
                     info!("On-chain escrow lock executed for order {}: {}", order_id, sig);
-                     // Optionally update DB with lock signature?
+                    // Marks the escrow as held on-chain, so unlocks refund up to this amount there
+                    if self.config.tokenization.enable_real_blockchain {
+                        if let Err(e) = sqlx::query(
+                            "UPDATE trading_orders SET escrow_tx_signature = $1, onchain_escrow_amount = $2 WHERE id = $3",
+                        )
+                            .bind(&sig)
+                            .bind(lock_amount)
+                            .bind(order_id)
+                            .execute(&self.db)
+                            .await
+                        {
+                            error!("Failed to record escrow lock signature for order {}: {}", order_id, e);
+                        }
+                    }
                 }
                 Err(e) => {
                     error!("Failed to execute escrow lock for order {}: {}", order_id, e);
@@ -246,29 +263,26 @@ impl MarketClearingService {
         let mint = Pubkey::from_str(&mint_str)?;
 
         // 3. User ATA
-        let user_ata = self.blockchain_service.calculate_ata_address(&keypair.pubkey(), &mint)?;
+        let user_ata = self.blockchain.calculate_ata_address(&keypair.pubkey(), &mint)?;
 
         // 4. Escrow Owner (API Authority)
-        let api_authority = self.blockchain_service.get_authority_keypair().await?;
+        let api_authority = self.blockchain.get_authority_keypair().await?;
         let escrow_owner = api_authority.pubkey();
 
         // 5. Ensure Escrow ATA exists
-        let escrow_ata = self.blockchain_service.ensure_token_account_exists(
+        let escrow_ata = self.blockchain.ensure_token_account_exists(
             &api_authority,
             &escrow_owner,
             &mint
         ).await?;
 
         // 6. Lock Tokens
-        // Determine decimals - USDC is 6, Energy is 9?
-        // Ideally fetch from chain, but for now hardcode or config?
-        let decimals = if asset_type == "energy" { 9 } else { 6 };
-        let multiplier = Decimal::from(10_u64.pow(decimals as u32));
-        let amount_u64 = (amount * multiplier).to_u64().unwrap_or(0);
+        let decimals = self.blockchain.decimals_for(&mint).await?;
+        let amount_u64 = to_atomic(amount, decimals)?;
 
         info!("Locking {} {} tokens ({} raw) from {} to API escrow {}", amount, asset_type, amount_u64, keypair.pubkey(), escrow_owner);
 
-        let signature = self.blockchain_service.lock_tokens_to_escrow(
+        let signature = self.blockchain.transfer_tokens(
             &keypair,
             &user_ata,
             &escrow_ata,
@@ -343,26 +357,25 @@ impl MarketClearingService {
         let mint = Pubkey::from_str(&mint_str)?;
 
         // 3. API Authority (Escrow Owner)
-        let api_authority = self.blockchain_service.get_authority_keypair().await?;
+        let api_authority = self.blockchain.get_authority_keypair().await?;
         let escrow_owner = api_authority.pubkey();
         
-        let escrow_ata = self.blockchain_service.calculate_ata_address(&escrow_owner, &mint)?;
+        let escrow_ata = self.blockchain.calculate_ata_address(&escrow_owner, &mint)?;
 
         // 4. Ensure Receiver ATA exists
-        let receiver_ata = self.blockchain_service.ensure_token_account_exists(
+        let receiver_ata = self.blockchain.ensure_token_account_exists(
             &api_authority,
             &receiver_wallet,
             &mint
         ).await?;
 
         // 5. Release Tokens
-        let decimals = if asset_type == "energy" { 9 } else { 6 };
-        let multiplier = Decimal::from(10_u64.pow(decimals as u32));
-        let amount_u64 = (amount * multiplier).to_u64().unwrap_or(0);
+        let decimals = self.blockchain.decimals_for(&mint).await?;
+        let amount_u64 = to_atomic(amount, decimals)?;
 
         info!("Releasing {} {} tokens from API escrow to receiver {}", amount, asset_type, receiver_wallet);
 
-        let signature = self.blockchain_service.release_escrow_to_seller(
+        let signature = self.blockchain.transfer_tokens(
             &api_authority,
             &escrow_ata,
             &receiver_ata,
@@ -417,26 +430,25 @@ impl MarketClearingService {
         let mint = Pubkey::from_str(&mint_str)?;
 
         // 3. API Authority (Escrow Owner)
-        let api_authority = self.blockchain_service.get_authority_keypair().await?;
+        let api_authority = self.blockchain.get_authority_keypair().await?;
         let escrow_owner = api_authority.pubkey();
         
-        let escrow_ata = self.blockchain_service.calculate_ata_address(&escrow_owner, &mint)?;
+        let escrow_ata = self.blockchain.calculate_ata_address(&escrow_owner, &mint)?;
 
         // 4. Ensure User ATA exists
-        let user_ata = self.blockchain_service.ensure_token_account_exists(
+        let user_ata = self.blockchain.ensure_token_account_exists(
              &api_authority,
              &user_wallet,
              &mint
         ).await?;
 
         // 5. Refund Tokens
-        let decimals = if asset_type == "energy" { 9 } else { 6 };
-        let multiplier = Decimal::from(10_u64.pow(decimals as u32));
-        let amount_u64 = (amount * multiplier).to_u64().unwrap_or(0);
+        let decimals = self.blockchain.decimals_for(&mint).await?;
+        let amount_u64 = to_atomic(amount, decimals)?;
 
         info!("Refunding {} {} tokens from API escrow to user {}", amount, asset_type, user_wallet);
 
-        let signature = self.blockchain_service.refund_escrow_to_buyer(
+        let signature = self.blockchain.transfer_tokens(
             &api_authority,
             &escrow_ata,
             &user_ata,
@@ -480,64 +492,79 @@ impl MarketClearingService {
         let currency_mint = Pubkey::from_str(&currency_mint_str)?;
 
         // 3. API Authority (Escrow & Market Authority)
-        let api_authority = self.blockchain_service.get_authority_keypair().await?;
+        let api_authority = self.blockchain.get_authority_keypair().await?;
         
         // 4. ATAs
         // Escrows (Owned by API Authority)
-        let buyer_currency_escrow = self.blockchain_service.calculate_ata_address(&api_authority.pubkey(), &currency_mint)?;
-        let seller_energy_escrow = self.blockchain_service.calculate_ata_address(&api_authority.pubkey(), &energy_mint)?;
+        let buyer_currency_escrow = self.blockchain.calculate_ata_address(&api_authority.pubkey(), &currency_mint)?;
+        let seller_energy_escrow = self.blockchain.calculate_ata_address(&api_authority.pubkey(), &energy_mint)?;
         
         // Destinations (Owned by Users)
-        let seller_currency_account = self.blockchain_service.calculate_ata_address(&seller_wallet, &currency_mint)?;
-        let buyer_energy_account = self.blockchain_service.calculate_ata_address(&buyer_wallet, &energy_mint)?;
+        let seller_currency_account = self.blockchain.calculate_ata_address(&seller_wallet, &currency_mint)?;
+        let buyer_energy_account = self.blockchain.calculate_ata_address(&buyer_wallet, &energy_mint)?;
 
         // Collectors (Owned by API Authority or dedicated Revenue wallet)
         // For simplicity, using API Authority ATA as collector
-        let fee_collector = self.blockchain_service.calculate_ata_address(&api_authority.pubkey(), &currency_mint)?;
-        let wheeling_collector = self.blockchain_service.calculate_ata_address(&api_authority.pubkey(), &currency_mint)?;
+        let fee_collector = self.blockchain.calculate_ata_address(&api_authority.pubkey(), &currency_mint)?;
+        let wheeling_collector = self.blockchain.calculate_ata_address(&api_authority.pubkey(), &currency_mint)?;
 
         // 5. Market PDA
-        let trading_program_id = self.blockchain_service.trading_program_id()?;
+        let trading_program_id = self.blockchain.trading_program_id();
         let (market_pda, _) = Pubkey::find_program_address(&[b"market"], &trading_program_id);
 
         // 6. Scale Amounts
-        let currency_decimals = 6; // USDC
-        let energy_decimals = 9;   // GRX
+        let currency_decimals = self.blockchain.decimals_for(&currency_mint).await?;
+        let energy_decimals = self.blockchain.decimals_for(&energy_mint).await?;
         
-        let amount_raw = (amount * Decimal::from(10_u64.pow(energy_decimals))).to_u64().unwrap_or(0);
-        let price_raw = (price * Decimal::from(10_u64.pow(9))).to_u64().unwrap_or(0); // Price is matched scale
-        let wheeling_raw = (wheeling_charge * Decimal::from(10_u64.pow(currency_decimals))).to_u64().unwrap_or(0);
+        let amount_raw = to_atomic(amount, energy_decimals)?;
+        let price_raw = to_atomic(price, 9)?; // Price is matched scale
+        let wheeling_raw = to_atomic(wheeling_charge, currency_decimals)?;
 
         // 7. Execute
-        let signature = self.blockchain_service.execute_atomic_settlement(
-            &api_authority,
-            &market_pda,
-            &buy_order_pda,
-            &sell_order_pda,
-            &buyer_currency_escrow,
-            &seller_energy_escrow,
-            &seller_currency_account,
-            &buyer_energy_account,
-            &fee_collector,
-            &wheeling_collector,
-            &energy_mint,
-            &currency_mint,
+        let token_program_id = TokenInstructions::get_token_program_id()?;
+        let settlement_ix = TradingInstructions::build_execute_atomic_settlement_instruction(
+            market_pda,
+            buy_order_pda,
+            sell_order_pda,
+            buyer_currency_escrow,
+            seller_energy_escrow,
+            seller_currency_account,
+            buyer_energy_account,
+            fee_collector,
+            wheeling_collector,
+            energy_mint,
+            currency_mint,
+            api_authority.pubkey(),
+            api_authority.pubkey(),
             amount_raw,
             price_raw,
             wheeling_raw,
-        ).await?;
+            token_program_id,
+            token_program_id,
+        )?;
+        let signature = self.blockchain.execute_atomic_settlement(&api_authority, settlement_ix).await?;
 
         Ok(signature.to_string())
     }
 
     /// Retry wrapper for escrow refund
+    ///
+    /// Returns `None` without a transfer if the order's escrow is held
+    /// off-chain. `amount` was already deducted from the order's on-chain
+    /// escrow when the original refund was attempted.
     pub async fn execute_escrow_refund_retry(
         &self,
         buyer_id: &Uuid,
+        order_id: Uuid,
         amount: Decimal,
         asset_type: &str,
-    ) -> Result<String> {
-        self.execute_escrow_refund(*buyer_id, amount, asset_type).await
+    ) -> Result<Option<String>> {
+        if !self.escrow_locked_on_chain(order_id).await? {
+            return Ok(None);
+        }
+        let signature = self.execute_escrow_refund(*buyer_id, amount, asset_type).await?;
+        self.record_refund(order_id, *buyer_id, amount, asset_type, &signature).await?;
+        Ok(Some(signature))
     }
 
     /// Helper to fetch user wallet pubkey
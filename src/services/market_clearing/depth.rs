@@ -0,0 +1,192 @@
+//! Aggregated order book depth
+//!
+//! Shared by the REST depth endpoint and the WebSocket order book snapshot so
+//! both report the same levels.
+
+use anyhow::Result;
+use chrono::Utc;
+use rust_decimal::Decimal;
+
+use super::types::{DepthLevel, OrderBookDepth};
+use super::MarketClearingService;
+use crate::database::schema::types::OrderSide;
+use crate::services::cache::CacheKeys;
+
+/// How long a cached depth snapshot is served before it is rebuilt
+const DEPTH_CACHE_TTL_SECS: u64 = 5;
+
+/// Resting volume at one price on one side of the book
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct DepthRow {
+    pub side: OrderSide,
+    pub price: Decimal,
+    pub volume: Decimal,
+    pub order_count: i64,
+}
+
+impl MarketClearingService {
+    /// Aggregate open limit orders into at most `levels` price levels per side
+    ///
+    /// Served from Redis for a few seconds when a cache is configured; order
+    /// writes drop the cached snapshots through [`Self::invalidate_order_book`].
+    pub async fn get_order_book_depth(
+        &self,
+        levels: usize,
+        zone_id: Option<i32>,
+    ) -> Result<OrderBookDepth> {
+        let Some(cache) = &self.cache else {
+            return self.load_order_book_depth(levels, zone_id).await;
+        };
+
+        let generation = cache.generation(CacheKeys::ORDER_BOOK).await;
+        cache
+            .get_or_load(
+                &CacheKeys::order_book_depth(generation, levels, zone_id),
+                DEPTH_CACHE_TTL_SECS,
+                || self.load_order_book_depth(levels, zone_id),
+            )
+            .await
+    }
+
+    /// Drop cached order book snapshots after the resting book changed
+    pub async fn invalidate_order_book(&self) {
+        if let Some(cache) = &self.cache {
+            cache.bump_generation(CacheKeys::ORDER_BOOK).await;
+            let _ = cache.delete(&CacheKeys::order_book("default")).await;
+        }
+    }
+
+    async fn load_order_book_depth(
+        &self,
+        levels: usize,
+        zone_id: Option<i32>,
+    ) -> Result<OrderBookDepth> {
+        let rows = sqlx::query_as::<_, DepthRow>(
+            r#"
+            SELECT
+                side,
+                price_per_kwh AS price,
+                -- Iceberg orders count only their shown slice
+                SUM(CASE
+                    WHEN display_quantity IS NULL THEN energy_amount - COALESCE(filled_amount, 0)
+                    ELSE LEAST(
+                        energy_amount - COALESCE(filled_amount, 0),
+                        display_quantity - MOD(COALESCE(filled_amount, 0), display_quantity)
+                    )
+                END) AS volume,
+                COUNT(*) AS order_count
+            FROM trading_orders
+            WHERE status IN ('pending', 'active', 'partially_filled')
+              AND order_type = 'limit'
+              AND price_per_kwh > 0
+              AND energy_amount > COALESCE(filled_amount, 0)
+              AND (expires_at IS NULL OR expires_at > NOW())
+              AND ($1::INT IS NULL OR zone_id = $1)
+            GROUP BY side, price_per_kwh
+            "#,
+        )
+        .bind(zone_id)
+        .fetch_all(&self.db)
+        .await?;
+
+        Ok(aggregate_depth(rows, levels))
+    }
+}
+
+/// Build bid/ask levels (best price first) with cumulative volume, plus the
+/// top-of-book summary
+pub fn aggregate_depth(rows: Vec<DepthRow>, levels: usize) -> OrderBookDepth {
+    let (mut bids, mut asks): (Vec<DepthRow>, Vec<DepthRow>) =
+        rows.into_iter().partition(|row| row.side == OrderSide::Buy);
+
+    bids.sort_by(|a, b| b.price.cmp(&a.price));
+    asks.sort_by(|a, b| a.price.cmp(&b.price));
+
+    let bids = to_levels(bids, levels);
+    let asks = to_levels(asks, levels);
+
+    let best_bid = bids.first().map(|level| level.price);
+    let best_ask = asks.first().map(|level| level.price);
+    let (mid_price, spread) = match (best_bid, best_ask) {
+        (Some(bid), Some(ask)) => (Some((bid + ask) / Decimal::TWO), Some(ask - bid)),
+        _ => (None, None),
+    };
+
+    OrderBookDepth {
+        bids,
+        asks,
+        best_bid,
+        best_ask,
+        mid_price,
+        spread,
+        timestamp: Utc::now(),
+    }
+}
+
+fn to_levels(rows: Vec<DepthRow>, levels: usize) -> Vec<DepthLevel> {
+    let mut cumulative_volume = Decimal::ZERO;
+    rows.into_iter()
+        .take(levels)
+        .map(|row| {
+            cumulative_volume += row.volume;
+            DepthLevel {
+                price: row.price,
+                volume: row.volume,
+                cumulative_volume,
+                order_count: row.order_count,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row(side: OrderSide, price: i64, volume: i64) -> DepthRow {
+        DepthRow {
+            side,
+            price: Decimal::new(price, 2),
+            volume: Decimal::from(volume),
+            order_count: 1,
+        }
+    }
+
+    #[test]
+    fn test_levels_are_sorted_best_first_with_cumulative_volume() {
+        let depth = aggregate_depth(
+            vec![
+                row(OrderSide::Buy, 40, 10),
+                row(OrderSide::Sell, 60, 5),
+                row(OrderSide::Buy, 45, 20),
+                row(OrderSide::Sell, 55, 8),
+            ],
+            10,
+        );
+
+        assert_eq!(depth.bids[0].price, Decimal::new(45, 2));
+        assert_eq!(depth.bids[1].cumulative_volume, Decimal::from(30));
+        assert_eq!(depth.asks[0].price, Decimal::new(55, 2));
+        assert_eq!(depth.asks[1].cumulative_volume, Decimal::from(13));
+        assert_eq!(depth.mid_price, Some(Decimal::new(50, 2)));
+        assert_eq!(depth.spread, Some(Decimal::new(10, 2)));
+    }
+
+    #[test]
+    fn test_levels_are_truncated() {
+        let depth = aggregate_depth(
+            vec![
+                row(OrderSide::Sell, 50, 1),
+                row(OrderSide::Sell, 51, 1),
+                row(OrderSide::Sell, 52, 1),
+            ],
+            2,
+        );
+
+        assert_eq!(depth.asks.len(), 2);
+        assert_eq!(depth.asks[1].price, Decimal::new(51, 2));
+        assert!(depth.bids.is_empty());
+        assert_eq!(depth.best_bid, None);
+        assert_eq!(depth.mid_price, None);
+    }
+}
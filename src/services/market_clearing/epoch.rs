@@ -5,9 +5,13 @@ use uuid::Uuid;
 use tracing::info;
 
 use crate::database::schema::types::EpochStatus;
+use crate::services::cache::CacheKeys;
 use super::MarketClearingService;
 use super::types::MarketEpoch;
 
+/// Upper bound on how long cached epoch listings are served
+const EPOCH_STATS_CACHE_TTL_SECS: u64 = 300;
+
 impl MarketClearingService {
     /// Get current market epoch (15-minute intervals)
     pub async fn get_current_epoch(&self) -> Result<Option<MarketEpoch>> {
@@ -75,6 +79,10 @@ impl MarketClearingService {
                     .execute(&self.db)
                     .await?;
 
+                if let Some(cache) = &self.cache {
+                    cache.bump_generation(CacheKeys::MARKET_EPOCHS).await;
+                }
+
                 // Update the existing epoch status for return
                 existing.status = new_status;
             }
@@ -138,6 +146,24 @@ impl MarketClearingService {
         Ok(epoch)
     }
 
+    /// Get epoch by id
+    pub async fn get_epoch_by_id(&self, epoch_id: Uuid) -> Result<Option<MarketEpoch>> {
+        let epoch = sqlx::query_as::<_, MarketEpoch>(
+            r#"
+            SELECT 
+                id, epoch_number, start_time, end_time, status,
+                clearing_price, total_volume, total_orders, matched_orders
+            FROM market_epochs 
+            WHERE id = $1
+            "#,
+        )
+        .bind(epoch_id)
+        .fetch_optional(&self.db)
+        .await?;
+
+        Ok(epoch)
+    }
+
     /// Update epoch statistics
     pub(super) async fn update_epoch_statistics(
         &self,
@@ -169,10 +195,32 @@ impl MarketClearingService {
         .execute(&self.db)
         .await?;
 
+        if let Some(cache) = &self.cache {
+            cache.bump_generation(CacheKeys::MARKET_EPOCHS).await;
+        }
+
         Ok(())
     }
 
+    /// Most recent cleared or settled epochs, newest first
+    ///
+    /// Cached until the next epoch clears when a cache is configured.
     pub async fn get_market_statistics(&self, epochs: i64) -> Result<Vec<MarketEpoch>> {
+        let Some(cache) = &self.cache else {
+            return self.load_market_statistics(epochs).await;
+        };
+
+        let generation = cache.generation(CacheKeys::MARKET_EPOCHS).await;
+        cache
+            .get_or_load(
+                &CacheKeys::market_epochs(generation, epochs),
+                EPOCH_STATS_CACHE_TTL_SECS,
+                || self.load_market_statistics(epochs),
+            )
+            .await
+    }
+
+    async fn load_market_statistics(&self, epochs: i64) -> Result<Vec<MarketEpoch>> {
         let stats = sqlx::query_as!(
             MarketEpoch,
             r#"
@@ -1,19 +1,45 @@
 use anyhow::Result;
 use rust_decimal::Decimal;
+use sqlx::PgConnection;
+use tracing::{debug, error, info};
 use uuid::Uuid;
-use super::MarketClearingService;
+use super::{MarketClearingService, OrderRejected};
+use crate::models::trading::OrderCloseReason;
 
 impl MarketClearingService {
     pub async fn lock_funds(&self, user_id: Uuid, order_id: Uuid, amount: Decimal) -> Result<()> {
         let mut tx = self.db.begin().await?;
+        Self::lock_funds_in_tx(&mut tx, user_id, order_id, amount).await?;
+        tx.commit().await?;
+        Ok(())
+    }
 
+    pub async fn lock_energy(&self, user_id: Uuid, order_id: Uuid, amount: Decimal) -> Result<()> {
+        let mut tx = self.db.begin().await?;
+        Self::lock_energy_in_tx(&mut tx, user_id, order_id, amount).await?;
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// Move `amount` from the user's balance into `locked_amount` and record a
+    /// `buy_lock` escrow row, failing if the balance does not cover it
+    pub(super) async fn lock_funds_in_tx(
+        conn: &mut PgConnection,
+        user_id: Uuid,
+        order_id: Uuid,
+        amount: Decimal,
+    ) -> Result<()> {
         // Check balance
         let user = sqlx::query!("SELECT balance FROM users WHERE id = $1 FOR UPDATE", user_id)
-            .fetch_one(&mut *tx)
+            .fetch_one(&mut *conn)
             .await?;
 
         if user.balance.unwrap_or(Decimal::ZERO) < amount {
-            return Err(anyhow::anyhow!("Insufficient balance for escrow. Required: {}, Available: {}", amount, user.balance.unwrap_or(Decimal::ZERO)));
+            return Err(OrderRejected::new(
+                OrderCloseReason::InsufficientFunds,
+                format!("Insufficient balance for escrow. Required: {}, Available: {}", amount, user.balance.unwrap_or(Decimal::ZERO)),
+            )
+            .into());
         }
 
         // Update user balance and locked_amount
@@ -22,7 +48,7 @@ impl MarketClearingService {
             amount,
             user_id
         )
-        .execute(&mut *tx)
+        .execute(&mut *conn)
         .await?;
 
         // Create escrow record
@@ -37,22 +63,33 @@ impl MarketClearingService {
             amount,
             format!("Buy order {} escrow", order_id)
         )
-        .execute(&mut *tx)
+        .execute(&mut *conn)
         .await?;
 
-        tx.commit().await?;
         Ok(())
     }
 
-    pub async fn lock_energy(&self, user_id: Uuid, order_id: Uuid, amount: Decimal) -> Result<()> {
-        let mut tx = self.db.begin().await?;
-        
+    /// Add `amount` to the user's `locked_energy` and record a `sell_lock`
+    /// escrow row
+    ///
+    /// Energy is held on-chain, so callers that need a balance check compare
+    /// against the wallet before locking (see `create_order`).
+    pub(super) async fn lock_energy_in_tx(
+        conn: &mut PgConnection,
+        user_id: Uuid,
+        order_id: Uuid,
+        amount: Decimal,
+    ) -> Result<()> {
+        if amount <= Decimal::ZERO {
+            return Err(anyhow::anyhow!("Energy lock amount must be positive"));
+        }
+
         sqlx::query!(
             "UPDATE users SET locked_energy = locked_energy + $1 WHERE id = $2",
             amount,
             user_id
         )
-        .execute(&mut *tx)
+        .execute(&mut *conn)
         .await?;
 
         sqlx::query!(
@@ -66,13 +103,18 @@ impl MarketClearingService {
             amount,
             format!("Sell order {} energy lock", order_id)
         )
-        .execute(&mut *tx)
+        .execute(&mut *conn)
         .await?;
 
-        tx.commit().await?;
         Ok(())
     }
 
+    /// Return `amount` of the order's locked funds to the user's balance
+    ///
+    /// If the order's escrow was locked on-chain the tokens are transferred
+    /// back as well, capped at what is still held on-chain for the order, and
+    /// the refund is recorded in `escrow_refunds`; a failed transfer is queued
+    /// for retry and does not fail the unlock.
     pub async fn unlock_funds(&self, user_id: Uuid, order_id: Uuid, amount: Decimal, reason: &str) -> Result<()> {
         let mut tx = self.db.begin().await?;
 
@@ -94,6 +136,10 @@ impl MarketClearingService {
         .await?;
 
         tx.commit().await?;
+
+        if amount > Decimal::ZERO {
+            self.refund_escrow_on_chain(order_id, user_id, amount, "currency").await;
+        }
         Ok(())
     }
 
@@ -120,4 +166,132 @@ impl MarketClearingService {
         tx.commit().await?;
         Ok(())
     }
+
+    /// Deduct up to `amount` from the escrow the order still holds on-chain
+    ///
+    /// Returns the deducted amount, or `None` if the order's escrow is held
+    /// only in the database. Escrow added by later edits exists only in the
+    /// database, so refunds never exceed what the on-chain lock transferred.
+    async fn claim_onchain_refund(&self, order_id: Uuid, amount: Decimal) -> Result<Option<Decimal>> {
+        let claimed: Option<Decimal> = sqlx::query_scalar(
+            r#"
+            WITH held AS (
+                SELECT id, onchain_escrow_amount AS amount
+                FROM trading_orders
+                WHERE id = $1 AND escrow_tx_signature IS NOT NULL
+                FOR UPDATE
+            )
+            UPDATE trading_orders o
+            SET onchain_escrow_amount = held.amount - LEAST(held.amount, $2)
+            FROM held
+            WHERE o.id = held.id
+            RETURNING LEAST(held.amount, $2)
+            "#,
+        )
+        .bind(order_id)
+        .bind(amount)
+        .fetch_optional(&self.db)
+        .await?;
+        Ok(claimed)
+    }
+
+    /// Whether the order's escrow was locked on-chain; escrow held only in the
+    /// database needs no on-chain refund
+    pub(super) async fn escrow_locked_on_chain(&self, order_id: Uuid) -> Result<bool> {
+        let signature: Option<String> =
+            sqlx::query_scalar("SELECT escrow_tx_signature FROM trading_orders WHERE id = $1")
+                .bind(order_id)
+                .fetch_optional(&self.db)
+                .await?
+                .flatten();
+        Ok(signature.is_some())
+    }
+
+    /// Record a transfer that returned part of an order's escrow
+    ///
+    /// Every refund keeps its own `escrow_refunds` row; the order's
+    /// `refund_tx_signature` shows the latest one.
+    pub(super) async fn record_refund(
+        &self,
+        order_id: Uuid,
+        user_id: Uuid,
+        amount: Decimal,
+        asset_type: &str,
+        signature: &str,
+    ) -> Result<()> {
+        let mut tx = self.db.begin().await?;
+        sqlx::query(
+            "INSERT INTO escrow_refunds (order_id, user_id, amount, asset_type, signature) VALUES ($1, $2, $3, $4, $5)",
+        )
+        .bind(order_id)
+        .bind(user_id)
+        .bind(amount)
+        .bind(asset_type)
+        .bind(signature)
+        .execute(&mut *tx)
+        .await?;
+        sqlx::query("UPDATE trading_orders SET refund_tx_signature = $1, updated_at = NOW() WHERE id = $2")
+            .bind(signature)
+            .bind(order_id)
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// Execute an on-chain escrow refund for an order whose escrow is held
+    /// on-chain, recording its signature; queues a retry task if it fails
+    pub(super) async fn refund_escrow_on_chain(
+        &self,
+        order_id: Uuid,
+        user_id: Uuid,
+        refund_amount: Decimal,
+        asset_type: &str,
+    ) -> Option<String> {
+        let refund_amount = match self.claim_onchain_refund(order_id, refund_amount).await {
+            Ok(None) => {
+                debug!("Escrow for order {} is held off-chain, no on-chain refund needed", order_id);
+                return None;
+            }
+            Ok(Some(claimed)) if claimed <= Decimal::ZERO => {
+                debug!("No on-chain escrow left for order {}, nothing to refund", order_id);
+                return None;
+            }
+            Ok(Some(claimed)) => claimed,
+            Err(e) => {
+                error!("Failed to claim on-chain refund for order {}: {}", order_id, e);
+                return None;
+            }
+        };
+
+        match self.execute_escrow_refund(user_id, refund_amount, asset_type).await {
+            Ok(sig) => {
+                info!("On-chain escrow refund executed for order {}: {}", order_id, sig);
+                if let Err(e) = self.record_refund(order_id, user_id, refund_amount, asset_type, &sig).await {
+                    error!("Failed to record refund signature {} for order {}: {}", sig, order_id, e);
+                }
+                Some(sig)
+            }
+            Err(e) => {
+                error!("Failed to execute on-chain refund for order {}: {}. Queueing for retry.", order_id, e);
+
+                // Queue for manual retry
+                let payload = serde_json::json!({
+                    "type": "EscrowRefund",
+                    "data": {
+                        "user_id": user_id,
+                        "amount": refund_amount,
+                        "asset_type": asset_type,
+                        "order_id": order_id
+                    }
+                });
+
+                let _ = self.queue_blockchain_task("escrow_refund", payload).await.map_err(|qe| {
+                    error!("CRITICAL: Failed to queue blockchain task: {}", qe);
+                    qe
+                });
+                None
+            }
+        }
+    }
 }
@@ -5,7 +5,6 @@ use rust_decimal::Decimal;
 
 use sqlx::Row;
 use uuid::Uuid;
-use std::str::FromStr;
 use tracing::{error, info, warn};
 use reqwest::Client;
 
@@ -13,7 +12,8 @@ use crate::database::schema::types::OrderStatus;
 use crate::error::ApiError;
 use crate::handlers::websocket::broadcaster::broadcast_p2p_order_update;
 use super::MarketClearingService;
-use super::types::{OrderMatch, Settlement};
+use super::types::OrderMatch;
+use crate::services::settlement::{insert_settlement, Settlement, SettlementStatus};
 use crate::middleware::metrics;
 
 impl MarketClearingService {
@@ -22,6 +22,11 @@ impl MarketClearingService {
         let start_time = std::time::Instant::now();
         info!("Starting order matching for epoch: {}", epoch_id);
 
+        if self.is_epoch_halted(epoch_id).await? {
+            warn!("Clearing for epoch {} is halted by the price band, skipping matching", epoch_id);
+            return Ok(vec![]);
+        }
+
         // Get current order book
         let (mut buy_orders, mut sell_orders) = self.get_order_book(epoch_id).await?;
 
@@ -30,6 +35,8 @@ impl MarketClearingService {
             return Ok(vec![]);
         }
 
+        let price_band = self.price_band(epoch_id).await?;
+
         let mut matches = Vec::new();
         let mut total_volume = Decimal::ZERO;
         let mut total_match_count = 0;
@@ -64,6 +71,13 @@ impl MarketClearingService {
             }
 
             if let Some(sell_idx) = best_sell_idx {
+                // Circuit breaker: the match is rejected and the rest of the
+                // epoch is not cleared; matches made so far still settle
+                if let Some(band) = price_band.filter(|band| !band.contains(match_price)) {
+                    self.halt_epoch_for_price_band(epoch_id, match_price, &band).await?;
+                    break;
+                }
+
                 let sell_order = &mut sell_orders[sell_idx];
                 let buy_order = &mut buy_orders[0];
 
@@ -181,8 +195,8 @@ impl MarketClearingService {
                         settlement.buyer_id.to_string(),
                         settlement.seller_id.to_string(),
                         settlement.energy_amount.to_string(),
-                        settlement.price_per_kwh.to_string(),
-                        settlement.total_amount.to_string(),
+                        settlement.price.to_string(),
+                        settlement.total_value.to_string(),
                         Utc::now().to_rfc3339(),
                     ).await;
                 },
@@ -294,8 +308,10 @@ impl MarketClearingService {
 
         // Calculate settlement amounts
         let total_amount = order_match.matched_amount * order_match.match_price;
-        let fee_rate = Decimal::from_str("0.01").expect("Invalid fee rate constant"); // 1% fee
-        let fee_amount = total_amount * fee_rate;
+        let fee_amount = self
+            .fee_calculator()
+            .fee_for_user(&self.db, sell_order.get("user_id"), total_amount)
+            .await;
         // Total settlement value includes fees and wheeling charges
         let net_amount = total_amount - fee_amount - wheeling_charge;
 
@@ -349,55 +365,32 @@ impl MarketClearingService {
         let settlement = Settlement {
             id: Uuid::new_v4(),
             epoch_id: order_match.epoch_id,
+            match_id: Some(order_match.id),
             buyer_id: buy_order.get("user_id"),
             seller_id: sell_order.get("user_id"),
-            energy_amount: order_match.matched_amount.clone(),
-            price_per_kwh: order_match.match_price.clone(),
-            total_amount: total_amount.clone(),
-            fee_amount: fee_amount.clone(),
-            wheeling_charge: wheeling_charge.clone(),
-            loss_factor: loss_factor.clone(),
-            loss_cost: loss_cost.clone(),
-            effective_energy: effective_energy.clone(),
+            buy_order_id: order_match.buy_order_id,
+            sell_order_id: order_match.sell_order_id,
+            energy_amount: order_match.matched_amount,
+            price: order_match.match_price,
+            total_value: total_amount,
+            fee_amount,
+            net_amount,
+            status: SettlementStatus::Pending,
+            blockchain_tx: None,
+            batch_signature: None,
+            created_at: Utc::now(),
+            confirmed_at: None,
             buyer_zone_id: buy_order.get("zone_id"),
             seller_zone_id: sell_order.get("zone_id"),
-            net_amount: net_amount.clone(),
-            status: "pending".to_string(),
+            wheeling_charge: Some(wheeling_charge),
+            loss_cost: Some(loss_cost),
+            loss_factor: Some(loss_factor),
+            effective_energy: Some(effective_energy),
             buyer_session_token: buy_order.get("session_token"),
             seller_session_token: sell_order.get("session_token"),
         };
 
-        // Save settlement
-        sqlx::query(
-            r#"
-            INSERT INTO settlements (
-                id, epoch_id, buyer_id, seller_id, energy_amount, 
-                price_per_kwh, total_amount, fee_amount, wheeling_charge,
-                loss_factor, loss_cost, effective_energy, buyer_zone_id,
-                seller_zone_id, net_amount, status, buyer_session_token, seller_session_token
-            ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18)
-            "#,
-        )
-        .bind(&settlement.id)
-        .bind(&settlement.epoch_id)
-        .bind(&settlement.buyer_id)
-        .bind(&settlement.seller_id)
-        .bind(&settlement.energy_amount)
-        .bind(&settlement.price_per_kwh)
-        .bind(&settlement.total_amount)
-        .bind(&settlement.fee_amount)
-        .bind(&settlement.wheeling_charge)
-        .bind(&settlement.loss_factor)
-        .bind(&settlement.loss_cost)
-        .bind(&settlement.effective_energy)
-        .bind(settlement.buyer_zone_id)
-        .bind(settlement.seller_zone_id)
-        .bind(&settlement.net_amount)
-        .bind(&settlement.status)
-        .bind(&settlement.buyer_session_token)
-        .bind(&settlement.seller_session_token)
-        .execute(&self.db)
-        .await?;
+        insert_settlement(&self.db, &settlement).await?;
 
         // Update order match with settlement ID
         sqlx::query(
@@ -4,31 +4,41 @@ pub mod orders;
 pub mod matching;
 pub mod blockchain;
 pub mod escrow;
+pub mod depth;
 pub mod revenue;
+pub mod price_band;
 
 use sqlx::PgPool;
 use rust_decimal::Decimal;
+use std::sync::Arc;
 
 pub use types::*;
+pub use price_band::{PriceBand, PriceBandStatus, PriceReferenceSource, HaltedEpoch, HaltResolution, HaltResolutionResult};
 
-use crate::config::Config;
-use crate::services::{AuditLogger, BlockchainService, WalletService, WebSocketService, ErcService};
+use crate::config::{Config, ReloadableConfig};
+use crate::services::blockchain::BlockchainClient;
+use crate::services::{AuditLogger, WalletService, WebSocketService, ErcService, FeeCalculator, CacheService};
 
 #[derive(Clone, Debug)]
 pub struct MarketClearingService {
     db: PgPool,
-    blockchain_service: BlockchainService,
+    blockchain: Arc<dyn BlockchainClient>,
     config: Config,
     _wallet_service: WalletService,
     audit_logger: AuditLogger,
     websocket_service: WebSocketService,
     erc_service: ErcService,
+    fee_calculator: FeeCalculator,
+    /// Caches order book depth and epoch listings; reads go to the DB when unset
+    cache: Option<CacheService>,
+    /// Source of the hot-reloadable base fee rate; the environment rate when unset
+    runtime_config: Option<ReloadableConfig>,
 }
 
 impl MarketClearingService {
     pub fn new(
         db: PgPool,
-        blockchain_service: BlockchainService,
+        blockchain: Arc<dyn BlockchainClient>,
         config: Config,
         _wallet_service: WalletService,
         audit_logger: AuditLogger,
@@ -37,12 +47,34 @@ impl MarketClearingService {
     ) -> Self {
         Self {
             db,
-            blockchain_service,
+            blockchain,
             config,
             _wallet_service,
             audit_logger,
             websocket_service,
             erc_service,
+            fee_calculator: FeeCalculator::from_env(),
+            cache: None,
+            runtime_config: None,
+        }
+    }
+
+    pub fn with_cache(mut self, cache: CacheService) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+
+    /// Read the fee rate through a hot-reloadable config handle
+    pub fn with_runtime_config(mut self, runtime_config: ReloadableConfig) -> Self {
+        self.runtime_config = Some(runtime_config);
+        self
+    }
+
+    /// Fee calculator for the rate currently in effect
+    fn fee_calculator(&self) -> FeeCalculator {
+        match &self.runtime_config {
+            Some(runtime) => self.fee_calculator.with_base_rate(runtime.settlement_fee_rate()),
+            None => self.fee_calculator.clone(),
         }
     }
 
@@ -2,16 +2,75 @@ use anyhow::Result;
 use chrono::{DateTime, Duration, Utc};
 use rust_decimal::prelude::ToPrimitive;
 use rust_decimal::Decimal;
-use sqlx::Row;
 use uuid::Uuid;
-use tracing::{info, error};
+use tracing::{info, error, warn};
 
 use crate::database::schema::types::{OrderSide, OrderStatus, OrderType};
 use crate::error::ApiError;
+use crate::services::AuditEvent;
+use crate::models::trading::{OrderCloseReason, TimeInForce};
+use crate::utils::units::to_atomic;
 use super::MarketClearingService;
-use super::types::{OrderBookEntry, Settlement};
+use super::types::{BulkCancelFailure, BulkCancelResult, CancelFilter, ForceCancelResult, OrderBookEntry, OrderOptions, OrderRejected};
+use crate::services::settlement::{settlement_from_row, Settlement, SETTLEMENT_SELECT};
+
+/// Open order being cancelled
+struct CancellableOrder {
+    id: Uuid,
+    side: OrderSide,
+    energy_amount: Decimal,
+    filled_amount: Decimal,
+    price_per_kwh: Decimal,
+}
+
+impl CancellableOrder {
+    fn unfilled(&self) -> Decimal {
+        self.energy_amount - self.filled_amount
+    }
+}
+
+/// `hours` after `now`, saturating at the latest representable time
+fn hours_after(now: DateTime<Utc>, hours: i64) -> DateTime<Utc> {
+    Duration::try_hours(hours)
+        .and_then(|window| now.checked_add_signed(window))
+        .unwrap_or(DateTime::<Utc>::MAX_UTC)
+}
+
+/// Expiry for an order placed at `now`: `requested` clamped to `max_hours`
+/// ahead, or `default_hours` ahead when none was given. Expiries at or
+/// before `now` are rejected.
+pub fn resolve_order_expiry(
+    requested: Option<DateTime<Utc>>,
+    now: DateTime<Utc>,
+    default_hours: i64,
+    max_hours: i64,
+) -> std::result::Result<DateTime<Utc>, OrderRejected> {
+    let latest = hours_after(now, max_hours);
+    match requested {
+        Some(expiry) if expiry <= now => Err(OrderRejected::new(
+            OrderCloseReason::InvalidExpiry,
+            format!("Expiry time {} is in the past", expiry),
+        )),
+        Some(expiry) => Ok(expiry.min(latest)),
+        None => Ok(hours_after(now, default_hours).min(latest)),
+    }
+}
 
 impl MarketClearingService {
+    /// Expiry for a new order under the configured default and maximum windows
+    pub fn order_expiry(
+        &self,
+        requested: Option<DateTime<Utc>>,
+        now: DateTime<Utc>,
+    ) -> std::result::Result<DateTime<Utc>, OrderRejected> {
+        resolve_order_expiry(
+            requested,
+            now,
+            self.config.order_default_expiry_hours,
+            self.config.order_max_expiry_hours,
+        )
+    }
+
     /// Get current order book for an epoch
     pub async fn get_order_book(
         &self,
@@ -71,7 +130,28 @@ impl MarketClearingService {
         Ok((buy_orders, sell_orders))
     }
 
+    /// Look up an order previously created with the given idempotency key
+    pub async fn find_order_by_client_id(
+        &self,
+        user_id: Uuid,
+        client_order_id: &str,
+    ) -> Result<Option<(Uuid, OrderStatus, DateTime<Utc>)>> {
+        let existing = sqlx::query_as::<_, (Uuid, OrderStatus, DateTime<Utc>)>(
+            "SELECT id, status, created_at FROM trading_orders WHERE user_id = $1 AND client_order_id = $2",
+        )
+        .bind(user_id)
+        .bind(client_order_id)
+        .fetch_optional(&self.db)
+        .await?;
+
+        Ok(existing)
+    }
+
     /// Create a new trading order (DB and On-Chain)
+    ///
+    /// When `client_order_id` is given and an order with that key already
+    /// exists for the user, the existing order id is returned and nothing is
+    /// escrowed or submitted on-chain.
     pub async fn create_order(
         &self,
         user_id: Uuid,
@@ -83,29 +163,98 @@ impl MarketClearingService {
         zone_id: Option<i32>,
         meter_id: Option<Uuid>,
         session_token: Option<&str>,
+        client_order_id: Option<&str>,
+    ) -> Result<Uuid> {
+        self.create_order_with_options(
+            user_id,
+            side,
+            order_type,
+            energy_amount,
+            price_per_kwh,
+            expiry_time,
+            zone_id,
+            meter_id,
+            session_token,
+            client_order_id,
+            OrderOptions::default(),
+        )
+        .await
+    }
+
+    /// [`create_order`](Self::create_order) with the extra behaviour in `options`
+    pub async fn create_order_with_options(
+        &self,
+        user_id: Uuid,
+        side: OrderSide,
+        order_type: OrderType,
+        energy_amount: Decimal,
+        price_per_kwh: Option<Decimal>,
+        expiry_time: Option<DateTime<Utc>>,
+        zone_id: Option<i32>,
+        meter_id: Option<Uuid>,
+        session_token: Option<&str>,
+        client_order_id: Option<&str>,
+        options: OrderOptions,
     ) -> Result<Uuid> {
         info!("Creating order in MarketClearingService for user: {}, meter: {:?}", user_id, meter_id);
 
+        if let Some(key) = client_order_id {
+            if let Some((existing_id, _, _)) = self.find_order_by_client_id(user_id, key).await? {
+                info!("Duplicate order request {} for user {}, returning order {}", key, user_id, existing_id);
+                return Ok(existing_id);
+            }
+        }
+
         if energy_amount <= Decimal::ZERO {
-            return Err(anyhow::anyhow!("Energy amount must be positive"));
+            return Err(OrderRejected::new(OrderCloseReason::InvalidAmount, "Energy amount must be positive").into());
+        }
+
+        if let Some(display_quantity) = options.display_quantity {
+            if order_type != OrderType::Limit {
+                return Err(OrderRejected::new(OrderCloseReason::InvalidAmount, "Display quantity is only supported for Limit orders").into());
+            }
+            if options.time_in_force != TimeInForce::Gtc {
+                return Err(OrderRejected::new(
+                    OrderCloseReason::InvalidAmount,
+                    "Display quantity is only supported for GTC orders",
+                ).into());
+            }
+            if display_quantity <= Decimal::ZERO || display_quantity >= energy_amount {
+                return Err(OrderRejected::new(
+                    OrderCloseReason::InvalidAmount,
+                    "Display quantity must be positive and less than the energy amount",
+                ).into());
+            }
         }
 
         let price_per_kwh_val = match order_type {
             OrderType::Limit => {
                 let price = price_per_kwh.ok_or_else(|| {
-                    anyhow::anyhow!("Price per kWh is required for Limit orders")
+                    OrderRejected::new(OrderCloseReason::MissingLimitPrice, "Price per kWh is required for Limit orders")
                 })?;
                 if price <= Decimal::ZERO {
-                    return Err(anyhow::anyhow!("Price per kWh must be positive"));
+                    return Err(OrderRejected::new(OrderCloseReason::InvalidPrice, "Price per kWh must be positive").into());
                 }
                 price
             }
-            OrderType::Market => Decimal::ZERO,
+            OrderType::Market => match (side, price_per_kwh) {
+                // Slippage guard: the highest landed cost per kWh the buyer accepts,
+                // escrowed up front like a limit price
+                (OrderSide::Buy, Some(max_price)) => {
+                    if max_price <= Decimal::ZERO {
+                        return Err(OrderRejected::new(OrderCloseReason::InvalidPrice, "Maximum price per kWh must be positive").into());
+                    }
+                    max_price
+                }
+                // Unguarded market buys escrow per match from the available
+                // balance; market sells take the best bid
+                _ => Decimal::ZERO,
+            },
         };
 
         let order_id = Uuid::new_v4();
         let now = Utc::now();
-        let expires_at = expiry_time.unwrap_or_else(|| now + Duration::days(1));
+        let expires_at = self.order_expiry(expiry_time, now)?;
 
         // Get or create current epoch
         let epoch = self.get_or_create_epoch(now).await?;
@@ -114,12 +263,15 @@ impl MarketClearingService {
         let mut tx = self.db.begin().await?;
 
         // 2. Insert order into DB (Must process first to satisfy FK for escrow_records)
-        sqlx::query!(
+        // A concurrent retry with the same client_order_id inserts nothing here.
+        let inserted = sqlx::query!(
             r#"
             INSERT INTO trading_orders (
                 id, user_id, order_type, side, energy_amount, price_per_kwh,
-                filled_amount, status, expires_at, created_at, epoch_id, zone_id, meter_id
-            ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13)
+                filled_amount, status, expires_at, created_at, epoch_id, zone_id, meter_id,
+                client_order_id, display_quantity, time_in_force
+            ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16)
+            ON CONFLICT (user_id, client_order_id) WHERE client_order_id IS NOT NULL DO NOTHING
             "#,
             order_id,
             user_id,
@@ -133,15 +285,29 @@ impl MarketClearingService {
             now,
             epoch.id,
             zone_id,
-            meter_id
+            meter_id,
+            client_order_id,
+            options.display_quantity,
+            options.time_in_force.as_str()
         )
         .execute(&mut *tx)
         .await?;
 
+        if inserted.rows_affected() == 0 {
+            tx.rollback().await?;
+            let key = client_order_id.unwrap_or_default();
+            let (existing_id, _, _) = self
+                .find_order_by_client_id(user_id, key)
+                .await?
+                .ok_or_else(|| anyhow::anyhow!("Order with client_order_id {} vanished", key))?;
+            info!("Duplicate order request {} for user {}, returning order {}", key, user_id, existing_id);
+            return Ok(existing_id);
+        }
+
         // 3. Fetch user (for balance/wallet check)
         // Must happen inside transaction for lock stability if we are checking DB balance
         let user = sqlx::query!(
-            "SELECT balance, wallet_address FROM users WHERE id = $1 FOR UPDATE", 
+            "SELECT locked_energy, wallet_address FROM users WHERE id = $1 FOR UPDATE",
             user_id
         )
         .fetch_one(&mut *tx)
@@ -172,48 +338,25 @@ impl MarketClearingService {
                         .map_err(|e| anyhow::anyhow!("Invalid currency mint config: {}", e))?;
 
                     // Convert required amount to token units (e.g. 6 decimals for USDC)
-                    let decimals = self.config.currency_decimals;
-                    let required_tokens = (total_escrow_amount * Decimal::from(10u64.pow(decimals as u32)))
-                        .to_u64()
-                        .ok_or_else(|| anyhow::anyhow!("Amount too large"))?;
+                    let required_tokens = to_atomic(total_escrow_amount, self.config.currency_decimals)?;
 
-                    let balance = self.blockchain_service.get_token_balance(&user_wallet, &currency_mint).await?;
+                    let balance = self.blockchain.get_token_balance(&user_wallet, &currency_mint).await?;
                     
                     info!("On-chain balance check for user {}: has {} tokens, needs {}", user_id, balance, required_tokens);
 
                     if balance < required_tokens {
-                         return Err(anyhow::anyhow!("Insufficient on-chain balance. Required: {}, Available: {}", required_tokens, balance));
+                         return Err(OrderRejected::new(
+                             OrderCloseReason::InsufficientFunds,
+                             format!("Insufficient on-chain balance. Required: {}, Available: {}", required_tokens, balance),
+                         ).into());
                     }
                 }
 
-                // 3. Database Balance Check (Always perform for internal consistency)
-                if user.balance.unwrap_or(Decimal::ZERO) < total_escrow_amount {
-                    return Err(anyhow::anyhow!("Insufficient DB balance for escrow. Required: {}, Available: {}", total_escrow_amount, user.balance.unwrap_or(Decimal::ZERO)));
+                // 3. Lock funds in DB (checks the DB balance, always performed for internal consistency).
+                // Unguarded market buys have nothing to lock until they match.
+                if total_escrow_amount > Decimal::ZERO {
+                    Self::lock_funds_in_tx(&mut tx, user_id, order_id, total_escrow_amount).await?;
                 }
-
-                // Update user balance and locked_amount
-                sqlx::query!(
-                    "UPDATE users SET balance = balance - $1, locked_amount = locked_amount + $1 WHERE id = $2",
-                    total_escrow_amount,
-                    user_id
-                )
-                .execute(&mut *tx)
-                .await?;
-
-                // Create escrow record
-                sqlx::query!(
-                    r#"
-                    INSERT INTO escrow_records (
-                        user_id, order_id, amount, asset_type, escrow_type, status, description
-                    ) VALUES ($1, $2, $3, 'currency', 'buy_lock', 'locked', $4)
-                    "#,
-                    user_id,
-                    order_id,
-                    total_escrow_amount,
-                    format!("Buy order {} escrow", order_id)
-                )
-                .execute(&mut *tx)
-                .await?;
             }
             OrderSide::Sell => {
                 // 1. On-Chain Energy Balance Check (Optional/Configurable)
@@ -235,44 +378,25 @@ impl MarketClearingService {
                     let energy_mint = Pubkey::from_str(&self.config.energy_token_mint)
                         .map_err(|e| anyhow::anyhow!("Invalid energy mint config: {}", e))?;
                     
-                    // Energy tokens usually have 9 decimals (same as SOL)
-                    // TODO: Move energy decimals to config if variable
-                    let decimals = 9; 
-                    let required_tokens = (energy_amount * Decimal::from(10u64.pow(decimals)))
-                        .to_u64()
-                        .ok_or_else(|| anyhow::anyhow!("Energy amount too large"))?;
-
-                    let balance = self.blockchain_service.get_token_balance(&user_wallet, &energy_mint).await?;
+                    let decimals = self.blockchain.decimals_for(&energy_mint).await?;
+                    // Energy already locked by other open sell orders is not available again
+                    let already_locked = user.locked_energy.unwrap_or(Decimal::ZERO);
+                    let required_tokens = to_atomic(energy_amount + already_locked, decimals)?;
+
+                    let balance = self.blockchain.get_token_balance(&user_wallet, &energy_mint).await?;
                     
-                    info!("On-chain energy check for user {}: has {} tokens, needs {}", user_id, balance, required_tokens);
+                    info!("On-chain energy check for user {}: has {} tokens, needs {} ({} kWh already locked)", user_id, balance, required_tokens, already_locked);
 
                     if balance < required_tokens {
-                        return Err(anyhow::anyhow!("Insufficient on-chain energy balance. Required: {}, Available: {}", required_tokens, balance));
+                        return Err(OrderRejected::new(
+                            OrderCloseReason::InsufficientEnergy,
+                            format!("Insufficient on-chain energy balance. Required: {}, Available: {}", required_tokens, balance),
+                        ).into());
                     }
                 }
 
                 // Lock energy in DB
-                sqlx::query!(
-                    "UPDATE users SET locked_energy = locked_energy + $1 WHERE id = $2",
-                    energy_amount,
-                    user_id
-                )
-                .execute(&mut *tx)
-                .await?;
-
-                sqlx::query!(
-                    r#"
-                    INSERT INTO escrow_records (
-                        user_id, order_id, amount, asset_type, escrow_type, status, description
-                    ) VALUES ($1, $2, $3, 'energy', 'sell_lock', 'locked', $4)
-                    "#,
-                    user_id,
-                    order_id,
-                    energy_amount,
-                    format!("Sell order {} energy lock", order_id)
-                )
-                .execute(&mut *tx)
-                .await?;
+                Self::lock_energy_in_tx(&mut tx, user_id, order_id, energy_amount).await?;
             }
         }
 
@@ -296,10 +420,10 @@ impl MarketClearingService {
 
         info!("Created order {} for user {} with assets escrowed", order_id, user_id);
 
-        // Broadcast order created event
+        // Broadcast order created event; an iceberg order shows its first slice
         self.websocket_service.broadcast_order_created(
             order_id.to_string(),
-            energy_amount.to_f64().unwrap_or(0.0),
+            options.display_quantity.unwrap_or(energy_amount).to_f64().unwrap_or(0.0),
             price_per_kwh_val.to_f64().unwrap_or(0.0),
             match side {
                 OrderSide::Buy => None,
@@ -317,6 +441,8 @@ impl MarketClearingService {
             price: price_per_kwh_val.to_string(),
         });
 
+        self.invalidate_order_book().await;
+
         // 3. On-Chain Order Creation
         self.execute_on_chain_order_creation(user_id, order_id, side, energy_amount, price_per_kwh_val, session_token).await?;
 
@@ -368,8 +494,6 @@ impl MarketClearingService {
 
     /// Cancel an order and refund the unfilled escrow amount
     pub async fn cancel_order(&self, order_id: Uuid, user_id: Uuid) -> Result<()> {
-        use crate::handlers::websocket::broadcaster::broadcast_p2p_order_update;
-        
         // Get full order details including filled amount
         let order = sqlx::query!(
             r#"
@@ -397,134 +521,434 @@ impl MarketClearingService {
                 )).into());
             }
 
-            // Calculate unfilled amount that needs to be refunded
-            let filled = order.filled_amount.unwrap_or(Decimal::ZERO);
-            let original = order.energy_amount;
-            let unfilled = original - filled;
-
-            if unfilled <= Decimal::ZERO {
+            let order = CancellableOrder {
+                id: order_id,
+                side: order.side,
+                energy_amount: order.energy_amount,
+                filled_amount: order.filled_amount.unwrap_or(Decimal::ZERO),
+                price_per_kwh: order.price_per_kwh,
+            };
+            if order.unfilled() <= Decimal::ZERO {
                 return Err(ApiError::BadRequest(
                     "Order is fully filled and cannot be cancelled".to_string()
                 ).into());
             }
 
-            // price_per_kwh is Decimal (not null in trading_orders)
-            let price = order.price_per_kwh;
-
             // Start transaction for atomicity
             let mut tx = self.db.begin().await?;
+            self.release_cancelled_order(&mut tx, user_id, &order, OrderCloseReason::UserCancelled).await?;
+            tx.commit().await?;
 
-            // Refund based on order side
-            match order.side {
-                OrderSide::Buy => {
-                    // Return locked funds for unfilled portion
-                    let refund_amount = unfilled * price;
-                    sqlx::query!(
-                        "UPDATE users SET balance = balance + $1, locked_amount = locked_amount - $1 WHERE id = $2",
-                        refund_amount,
-                        user_id
-                    )
-                    .execute(&mut *tx)
-                    .await?;
-
-                    info!(
-                        "Refunded {} to user {} for cancelled buy order {} (unfilled: {} kWh @ {})",
-                        refund_amount, user_id, order_id, unfilled, price
-                    );
+            self.announce_cancelled_order(user_id, &order, OrderCloseReason::UserCancelled).await;
+        } else {
+            return Err(ApiError::NotFound("Order not found".to_string()).into());
+        }
+
+        self.invalidate_order_book().await;
+        Ok(())
+    }
+
+    /// Cancel every open order of `user_id` matching `filter`
+    ///
+    /// All cancellations run in one transaction, each order under its own
+    /// savepoint: an order that fails to cancel is rolled back on its own
+    /// and reported, without aborting the rest of the batch.
+    pub async fn cancel_all_orders(&self, user_id: Uuid, filter: CancelFilter) -> Result<BulkCancelResult> {
+        let mut tx = self.db.begin().await?;
+
+        // Lock the orders so matching cannot fill them mid-cancel
+        let orders = sqlx::query_as::<_, (Uuid, OrderSide, Decimal, Option<Decimal>, Decimal)>(
+            r#"
+            SELECT id, side, energy_amount, filled_amount, price_per_kwh
+            FROM trading_orders
+            WHERE user_id = $1
+              AND status IN ('pending', 'active', 'partially_filled')
+              AND ($2::order_side IS NULL OR side = $2)
+              AND ($3::int IS NULL OR zone_id = $3)
+            ORDER BY created_at ASC
+            FOR UPDATE
+            "#,
+        )
+        .bind(user_id)
+        .bind(filter.side)
+        .bind(filter.zone_id)
+        .fetch_all(&mut *tx)
+        .await?;
+
+        let mut result = BulkCancelResult::default();
+        let mut cancelled = Vec::with_capacity(orders.len());
+        for (id, side, energy_amount, filled_amount, price_per_kwh) in orders {
+            let order = CancellableOrder {
+                id,
+                side,
+                energy_amount,
+                filled_amount: filled_amount.unwrap_or(Decimal::ZERO),
+                price_per_kwh,
+            };
+            if order.unfilled() <= Decimal::ZERO {
+                continue;
+            }
+
+            let mut savepoint = tx.begin().await?;
+            match self.release_cancelled_order(&mut savepoint, user_id, &order, OrderCloseReason::UserCancelled).await {
+                Ok(()) => {
+                    savepoint.commit().await?;
+                    match order.side {
+                        OrderSide::Buy => result.refunded_funds += order.unfilled() * order.price_per_kwh,
+                        OrderSide::Sell => result.released_energy += order.unfilled(),
+                    }
+                    result.cancelled += 1;
+                    cancelled.push(order);
                 }
-                OrderSide::Sell => {
-                    // Return locked energy for unfilled portion
-                    sqlx::query!(
-                        "UPDATE users SET locked_energy = locked_energy - $1 WHERE id = $2",
-                        unfilled,
-                        user_id
-                    )
-                    .execute(&mut *tx)
-                    .await?;
-
-                    info!(
-                        "Unlocked {} kWh energy for user {} from cancelled sell order {}",
-                        unfilled, user_id, order_id
-                    );
+                Err(e) => {
+                    savepoint.rollback().await?;
+                    error!("Failed to cancel order {} for user {}: {}", order.id, user_id, e);
+                    result.failed.push(BulkCancelFailure {
+                        order_id: order.id,
+                        error: e.to_string(),
+                    });
                 }
             }
+        }
 
-            // Update escrow record status
-            sqlx::query!(
-                "UPDATE escrow_records SET status = 'released', description = $1, updated_at = NOW() WHERE order_id = $2 AND status = 'locked'",
-                format!("Order cancelled - refunded unfilled portion: {}", unfilled),
-                order_id
-            )
-            .execute(&mut *tx)
-            .await?;
+        tx.commit().await?;
+
+        for order in &cancelled {
+            self.announce_cancelled_order(user_id, order, OrderCloseReason::UserCancelled).await;
+        }
+        if result.cancelled > 0 {
+            self.invalidate_order_book().await;
+        }
+
+        info!(
+            "Bulk cancel for user {}: {} cancelled, {} failed (refunded {}, released {} kWh)",
+            user_id, result.cancelled, result.failed.len(), result.refunded_funds, result.released_energy
+        );
+        Ok(result)
+    }
+
+    /// Cancel an order on behalf of an admin, whoever owns it
+    ///
+    /// For orders left wedged by an inconsistency between the on-chain and
+    /// DB settlement paths. The unfilled portion is refunded as for a user
+    /// cancel; filled, settled and already closed orders are refused.
+    pub async fn force_cancel_order(&self, order_id: Uuid, admin_id: Uuid, reason: &str) -> Result<ForceCancelResult> {
+        let mut tx = self.db.begin().await?;
 
-            // Update order status to cancelled
-            sqlx::query(
-                "UPDATE trading_orders SET status = 'cancelled'::order_status, updated_at = NOW() WHERE id = $1"
+        let (owner_id, side, status, energy_amount, filled_amount, price_per_kwh) =
+            sqlx::query_as::<_, (Uuid, OrderSide, OrderStatus, Decimal, Option<Decimal>, Decimal)>(
+                r#"
+                SELECT user_id, side, status, energy_amount, filled_amount, price_per_kwh
+                FROM trading_orders
+                WHERE id = $1
+                FOR UPDATE
+                "#,
             )
             .bind(order_id)
-            .execute(&mut *tx)
-            .await?;
+            .fetch_optional(&mut *tx)
+            .await?
+            .ok_or_else(|| ApiError::NotFound(format!("Order {} not found", order_id)))?;
+
+        if !matches!(status, OrderStatus::Pending | OrderStatus::Active | OrderStatus::PartiallyFilled) {
+            return Err(ApiError::Conflict(format!(
+                "Order {} is {} and cannot be force-cancelled",
+                order_id, status
+            ))
+            .into());
+        }
 
-            tx.commit().await?;
+        let order = CancellableOrder {
+            id: order_id,
+            side,
+            energy_amount,
+            filled_amount: filled_amount.unwrap_or(Decimal::ZERO),
+            price_per_kwh,
+        };
+        let unfilled = order.unfilled();
+        if unfilled <= Decimal::ZERO {
+            return Err(ApiError::Conflict(format!(
+                "Order {} is fully filled; its settlements must be resolved instead",
+                order_id
+            ))
+            .into());
+        }
+        self.release_cancelled_order(&mut tx, owner_id, &order, OrderCloseReason::AdminCancelled).await?;
+        tx.commit().await?;
 
-            // Broadcast cancellation via WebSocket
-            let _ = broadcast_p2p_order_update(
-                order_id,
-                user_id,
-                match order.side {
-                    OrderSide::Buy => "buy".to_string(),
-                    OrderSide::Sell => "sell".to_string(),
-                },
-                "cancelled".to_string(),
-                original.to_string(),
-                filled.to_string(),
-                "0".to_string(), // remaining is 0 after cancel
-                price.to_string(),
-            ).await;
-
-            info!("Order {} cancelled by user {} (filled: {}, refunded: {})", 
-                order_id, user_id, filled, unfilled);
-
-            // Execute On-Chain Refund
-            // Buy Order -> Refund Currency (unfilled * price)
-            // Sell Order -> Refund Energy (unfilled)
-            let (asset_type, refund_amount) = match order.side {
-                OrderSide::Buy => ("currency", unfilled * price),
-                OrderSide::Sell => ("energy", unfilled),
-            };
+        let (refunded_funds, released_energy) = match side {
+            OrderSide::Buy => (unfilled * price_per_kwh, Decimal::ZERO),
+            OrderSide::Sell => (Decimal::ZERO, unfilled),
+        };
+        warn!(
+            "Admin {} force-cancelled order {} of user {} ({}): {}",
+            admin_id, order_id, owner_id, status, reason
+        );
+        self.audit_logger.log_async(AuditEvent::OrderForceCancelled {
+            order_id,
+            owner_id,
+            admin_id,
+            reason: reason.to_string(),
+            refunded: match side {
+                OrderSide::Buy => refunded_funds.to_string(),
+                OrderSide::Sell => released_energy.to_string(),
+            },
+        });
 
-            if refund_amount > Decimal::ZERO {
-                match self.execute_escrow_refund(user_id, refund_amount, asset_type).await {
-                    Ok(sig) => {
-                        info!("On-chain escrow refund executed for order {}: {}", order_id, sig);
-                    }
-                    Err(e) => {
-                         error!("Failed to execute on-chain refund for order {}: {}. Queueing for retry.", order_id, e);
-                         
-                         // Queue for manual retry
-                         let payload = serde_json::json!({
-                             "type": "EscrowRefund", 
-                             "data": {
-                                 "user_id": user_id,
-                                 "amount": refund_amount,
-                                 "asset_type": asset_type,
-                                 "order_id": order_id
-                             }
-                         });
-                         
-                         let _ = self.queue_blockchain_task("escrow_refund", payload).await.map_err(|qe| {
-                             error!("CRITICAL: Failed to queue blockchain task: {}", qe);
-                             qe
-                         });
-                    }
-                }
+        self.announce_cancelled_order(owner_id, &order, OrderCloseReason::AdminCancelled).await;
+        self.invalidate_order_book().await;
+
+        Ok(ForceCancelResult {
+            order_id,
+            owner_id,
+            previous_status: status.to_string(),
+            refunded_funds,
+            released_energy,
+        })
+    }
+
+    /// Refund the unfilled portion of `order` and mark it cancelled
+    async fn release_cancelled_order(
+        &self,
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        user_id: Uuid,
+        order: &CancellableOrder,
+        reason: OrderCloseReason,
+    ) -> Result<()> {
+        let order_id = order.id;
+        let unfilled = order.unfilled();
+        let price = order.price_per_kwh;
+
+        // Refund based on order side
+        match order.side {
+            OrderSide::Buy => {
+                // Return locked funds for unfilled portion
+                let refund_amount = unfilled * price;
+                sqlx::query!(
+                    "UPDATE users SET balance = balance + $1, locked_amount = locked_amount - $1 WHERE id = $2",
+                    refund_amount,
+                    user_id
+                )
+                .execute(&mut **tx)
+                .await?;
+
+                info!(
+                    "Refunded {} to user {} for cancelled buy order {} (unfilled: {} kWh @ {})",
+                    refund_amount, user_id, order_id, unfilled, price
+                );
+            }
+            OrderSide::Sell => {
+                // Return locked energy for unfilled portion
+                sqlx::query!(
+                    "UPDATE users SET locked_energy = locked_energy - $1 WHERE id = $2",
+                    unfilled,
+                    user_id
+                )
+                .execute(&mut **tx)
+                .await?;
+
+                info!(
+                    "Unlocked {} kWh energy for user {} from cancelled sell order {}",
+                    unfilled, user_id, order_id
+                );
             }
+        }
 
-        } else {
-            return Err(ApiError::NotFound("Order not found".to_string()).into());
+        // Update escrow record status
+        sqlx::query!(
+            "UPDATE escrow_records SET status = 'released', description = $1, updated_at = NOW() WHERE order_id = $2 AND status = 'locked'",
+            format!("Order cancelled - refunded unfilled portion: {}", unfilled),
+            order_id
+        )
+        .execute(&mut **tx)
+        .await?;
+
+        // Update order status to cancelled
+        sqlx::query(
+            "UPDATE trading_orders SET status = 'cancelled'::order_status, cancellation_reason = $2, updated_at = NOW() WHERE id = $1"
+        )
+        .bind(order_id)
+        .bind(reason.as_str())
+        .execute(&mut **tx)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Broadcast a committed cancellation and return the escrow on-chain
+    async fn announce_cancelled_order(&self, user_id: Uuid, order: &CancellableOrder, reason: OrderCloseReason) {
+        use crate::handlers::websocket::broadcaster::broadcast_p2p_order_update;
+
+        let unfilled = order.unfilled();
+
+        // Broadcast cancellation via WebSocket
+        let _ = broadcast_p2p_order_update(
+            order.id,
+            user_id,
+            match order.side {
+                OrderSide::Buy => "buy".to_string(),
+                OrderSide::Sell => "sell".to_string(),
+            },
+            "cancelled".to_string(),
+            order.energy_amount.to_string(),
+            order.filled_amount.to_string(),
+            "0".to_string(), // remaining is 0 after cancel
+            order.price_per_kwh.to_string(),
+        ).await;
+
+        info!("Order {} of user {} cancelled ({}; filled: {}, refunded: {})", 
+            order.id, user_id, reason, order.filled_amount, unfilled);
+
+        // Execute On-Chain Refund
+        // Buy Order -> Refund Currency (unfilled * price)
+        // Sell Order -> Refund Energy (unfilled)
+        let (asset_type, refund_amount) = match order.side {
+            OrderSide::Buy => ("currency", unfilled * order.price_per_kwh),
+            OrderSide::Sell => ("energy", unfilled),
+        };
+
+        self.websocket_service
+            .broadcast_order_cancelled(order.id, user_id, order.side, reason, refund_amount)
+            .await;
+
+        if refund_amount > Decimal::ZERO {
+            self.refund_escrow_on_chain(order.id, user_id, refund_amount, asset_type).await;
+        }
+    }
+
+    /// Reduce the size of a resting order, releasing escrow for the removed portion.
+    ///
+    /// The order can be reduced down to its already-filled amount; an order reduced
+    /// to exactly its filled amount is marked filled.
+    pub async fn reduce_order(&self, order_id: Uuid, user_id: Uuid, reduce_by: Decimal) -> Result<()> {
+        use crate::handlers::websocket::broadcaster::broadcast_p2p_order_update;
+
+        if reduce_by <= Decimal::ZERO {
+            return Err(ApiError::BadRequest("Reduction amount must be positive".to_string()).into());
+        }
+
+        let mut tx = self.db.begin().await?;
+
+        let order = sqlx::query!(
+            r#"
+            SELECT user_id, side as "side!: OrderSide", status as "status: OrderStatus",
+                   energy_amount, filled_amount, price_per_kwh as "price_per_kwh"
+            FROM trading_orders
+            WHERE id = $1
+            FOR UPDATE
+            "#,
+            order_id
+        )
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or_else(|| ApiError::NotFound("Order not found".to_string()))?;
+
+        if order.user_id != user_id {
+            return Err(ApiError::Forbidden("Order does not belong to user".to_string()).into());
+        }
+
+        if !matches!(order.status, OrderStatus::Pending | OrderStatus::Active | OrderStatus::PartiallyFilled) {
+            return Err(ApiError::BadRequest(format!(
+                "Order cannot be reduced (status: {:?})", order.status
+            )).into());
         }
 
+        let filled = order.filled_amount.unwrap_or(Decimal::ZERO);
+        let original = order.energy_amount;
+        let unfilled = original - filled;
+        let new_amount = original - reduce_by;
+
+        if reduce_by > unfilled {
+            return Err(ApiError::BadRequest(format!(
+                "Cannot reduce by {} kWh: only {} kWh unfilled (filled: {})",
+                reduce_by, unfilled, filled
+            )).into());
+        }
+
+        if new_amount <= Decimal::ZERO {
+            return Err(ApiError::BadRequest(
+                "Reduction would remove the entire order; cancel it instead".to_string()
+            ).into());
+        }
+
+        let price = order.price_per_kwh;
+
+        // Release escrow for the removed portion
+        let (asset_type, release_amount) = match order.side {
+            OrderSide::Buy => {
+                let refund_amount = reduce_by * price;
+                sqlx::query!(
+                    "UPDATE users SET balance = balance + $1, locked_amount = locked_amount - $1 WHERE id = $2",
+                    refund_amount,
+                    user_id
+                )
+                .execute(&mut *tx)
+                .await?;
+                ("currency", refund_amount)
+            }
+            OrderSide::Sell => {
+                sqlx::query!(
+                    "UPDATE users SET locked_energy = locked_energy - $1 WHERE id = $2",
+                    reduce_by,
+                    user_id
+                )
+                .execute(&mut *tx)
+                .await?;
+                ("energy", reduce_by)
+            }
+        };
+
+        sqlx::query(
+            r#"
+            INSERT INTO escrow_records (
+                user_id, order_id, amount, asset_type, escrow_type, status, description
+            ) VALUES ($1, $2, $3, $4, 'partial_release', 'released', $5)
+            "#,
+        )
+        .bind(user_id)
+        .bind(order_id)
+        .bind(release_amount)
+        .bind(asset_type)
+        .bind(format!("Order reduced by {} kWh", reduce_by))
+        .execute(&mut *tx)
+        .await?;
+
+        let new_status = if new_amount <= filled {
+            OrderStatus::Filled
+        } else {
+            order.status
+        };
+
+        sqlx::query(
+            "UPDATE trading_orders SET energy_amount = $1, status = $2, updated_at = NOW() WHERE id = $3"
+        )
+        .bind(new_amount)
+        .bind(&new_status)
+        .bind(order_id)
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        let _ = broadcast_p2p_order_update(
+            order_id,
+            user_id,
+            match order.side {
+                OrderSide::Buy => "buy".to_string(),
+                OrderSide::Sell => "sell".to_string(),
+            },
+            new_status.as_str().to_string(),
+            new_amount.to_string(),
+            filled.to_string(),
+            (new_amount - filled).to_string(),
+            price.to_string(),
+        ).await;
+
+        info!("Order {} reduced by {} kWh by user {} ({} -> {}, released {} {})",
+            order_id, reduce_by, user_id, original, new_amount, release_amount, asset_type);
+
+        self.invalidate_order_book().await;
+        self.refund_escrow_on_chain(order_id, user_id, release_amount, asset_type).await;
+
         Ok(())
     }
 
@@ -535,55 +959,26 @@ impl MarketClearingService {
         limit: i64,
         offset: i64,
     ) -> Result<Vec<Settlement>> {
-        let settlements = sqlx::query(
-            r#"
-            SELECT 
-                id, epoch_id, buyer_id, seller_id, 
-                energy_amount, price_per_kwh, 
-                total_amount, fee_amount, 
-                wheeling_charge, loss_factor, 
-                loss_cost, effective_energy, 
-                buyer_zone_id, seller_zone_id, 
-                net_amount, status,
-                buyer_session_token, seller_session_token
-            FROM settlements 
-            WHERE buyer_id = $1 OR seller_id = $1
-            ORDER BY created_at DESC
-            LIMIT $2 OFFSET $3
-            "#
-        )
+        let rows = sqlx::query(&format!(
+            "{} WHERE s.buyer_id = $1 OR s.seller_id = $1 ORDER BY s.created_at DESC LIMIT $2 OFFSET $3",
+            SETTLEMENT_SELECT
+        ))
         .bind(user_id)
         .bind(limit)
         .bind(offset)
         .fetch_all(&self.db)
         .await?;
 
-        let result = settlements.into_iter().map(|row| Settlement {
-            id: row.get("id"),
-            epoch_id: row.get("epoch_id"),
-            buyer_id: row.get("buyer_id"),
-            seller_id: row.get("seller_id"),
-            energy_amount: row.get("energy_amount"),
-            price_per_kwh: row.get("price_per_kwh"),
-            total_amount: row.get("total_amount"),
-            fee_amount: row.get("fee_amount"),
-            wheeling_charge: row.get("wheeling_charge"),
-            loss_factor: row.get("loss_factor"),
-            loss_cost: row.get("loss_cost"),
-            effective_energy: row.get("effective_energy"),
-            buyer_zone_id: row.get("buyer_zone_id"),
-            seller_zone_id: row.get("seller_zone_id"),
-            net_amount: row.get("net_amount"),
-            status: row.get("status"),
-            buyer_session_token: row.get("buyer_session_token"),
-            seller_session_token: row.get("seller_session_token"),
-        }).collect();
+        let settlements = rows
+            .iter()
+            .map(settlement_from_row)
+            .collect::<std::result::Result<Vec<_>, _>>()?;
 
-        Ok(result)
+        Ok(settlements)
     }
 
     /// Queue a blockchain task for retry
-    async fn queue_blockchain_task(&self, task_type: &str, payload: serde_json::Value) -> Result<Uuid> {
+    pub(super) async fn queue_blockchain_task(&self, task_type: &str, payload: serde_json::Value) -> Result<Uuid> {
         let id = sqlx::query!(
             r#"
             INSERT INTO blockchain_tasks (task_type, payload, status, next_retry_at)
@@ -601,3 +996,44 @@ impl MarketClearingService {
         Ok(id)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_expiry_defaults_when_not_requested() {
+        let now = Utc::now();
+        let expiry = resolve_order_expiry(None, now, 24, 720).unwrap();
+        assert_eq!(expiry, now + Duration::hours(24));
+    }
+
+    #[test]
+    fn test_far_expiry_is_clamped_to_max() {
+        let now = Utc::now();
+        let requested = now + Duration::days(3650);
+        let expiry = resolve_order_expiry(Some(requested), now, 24, 720).unwrap();
+        assert_eq!(expiry, now + Duration::hours(720));
+
+        let within = now + Duration::hours(48);
+        assert_eq!(resolve_order_expiry(Some(within), now, 24, 720).unwrap(), within);
+    }
+
+    #[test]
+    fn test_huge_expiry_windows_saturate() {
+        let now = Utc::now();
+        let expiry = resolve_order_expiry(None, now, i64::MAX, i64::MAX).unwrap();
+        assert_eq!(expiry, DateTime::<Utc>::MAX_UTC);
+
+        let requested = now + Duration::days(3650);
+        assert_eq!(resolve_order_expiry(Some(requested), now, 24, i64::MAX).unwrap(), requested);
+    }
+
+    #[test]
+    fn test_past_expiry_is_rejected() {
+        let now = Utc::now();
+        let rejected = resolve_order_expiry(Some(now - Duration::minutes(1)), now, 24, 720).unwrap_err();
+        assert_eq!(rejected.reason, OrderCloseReason::InvalidExpiry);
+        assert!(resolve_order_expiry(Some(now), now, 24, 720).is_err());
+    }
+}
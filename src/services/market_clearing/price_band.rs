@@ -0,0 +1,333 @@
+//! Per-epoch price band (circuit breaker) for epoch clearing
+//!
+//! Matches are checked against a band around a reference price before they
+//! are saved. The first match outside the band is rejected and halts clearing
+//! for the rest of its epoch, so a single fat-fingered or manipulative order
+//! cannot clear the market at an off-market price. An admin then either
+//! resumes clearing without the band or cancels the epoch's open orders,
+//! releasing their escrow.
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use tracing::{error, info, warn};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use super::{ForceCancelResult, MarketClearingService};
+use crate::middleware::metrics::track_price_band_breach;
+use crate::services::cache::CacheKeys;
+
+/// Prices a match may clear at
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PriceBand {
+    pub reference_price: Decimal,
+    pub lower_bound: Decimal,
+    pub upper_bound: Decimal,
+}
+
+impl PriceBand {
+    /// Band of `max_deviation_pct` percent either side of `reference_price`;
+    /// `None` when the band is disabled or there is no usable reference
+    pub fn around(reference_price: Decimal, max_deviation_pct: Decimal) -> Option<Self> {
+        if max_deviation_pct <= Decimal::ZERO || reference_price <= Decimal::ZERO {
+            return None;
+        }
+        let deviation = reference_price * max_deviation_pct / Decimal::ONE_HUNDRED;
+        Some(Self {
+            reference_price,
+            lower_bound: (reference_price - deviation).max(Decimal::ZERO),
+            upper_bound: reference_price + deviation,
+        })
+    }
+
+    pub fn contains(&self, price: Decimal) -> bool {
+        price >= self.lower_bound && price <= self.upper_bound
+    }
+}
+
+/// Where the reference price of the band comes from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum PriceReferenceSource {
+    /// `PRICE_BAND_REFERENCE_PRICE`
+    Configured,
+    /// Clearing price of the last epoch that cleared
+    PreviousEpoch,
+}
+
+/// Band configuration and the band the next clearing run would apply
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct PriceBandStatus {
+    pub enabled: bool,
+    /// Allowed deviation from the reference price in percent
+    #[schema(value_type = String)]
+    pub max_deviation_pct: Decimal,
+    #[schema(value_type = Option<String>)]
+    pub reference_price: Option<Decimal>,
+    pub reference_source: Option<PriceReferenceSource>,
+    #[schema(value_type = Option<String>)]
+    pub lower_bound: Option<Decimal>,
+    #[schema(value_type = Option<String>)]
+    pub upper_bound: Option<Decimal>,
+    /// Epochs whose clearing was halted in the last 24 hours
+    pub halted_epochs: Vec<HaltedEpoch>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema, sqlx::FromRow)]
+pub struct HaltedEpoch {
+    pub epoch_id: Uuid,
+    pub epoch_number: i64,
+    pub halted_at: DateTime<Utc>,
+    pub halt_reason: Option<String>,
+}
+
+/// How an admin resolves a price band halt
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum HaltResolution {
+    /// Lift the halt and clear the rest of the epoch without the band
+    Resume,
+    /// Keep the epoch halted and cancel its open orders, releasing their escrow
+    CancelOrders,
+}
+
+/// Outcome of resolving a price band halt
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct HaltResolutionResult {
+    pub epoch_id: Uuid,
+    pub resolution: HaltResolution,
+    /// Orders cancelled and refunded; empty when clearing was resumed
+    pub cancelled_orders: Vec<ForceCancelResult>,
+}
+
+impl MarketClearingService {
+    /// Reference price for clearing `epoch_id`, or for the next epoch to
+    /// clear when `None`
+    pub async fn price_band_reference(
+        &self,
+        epoch_id: Option<Uuid>,
+    ) -> Result<Option<(Decimal, PriceReferenceSource)>> {
+        if let Some(price) = self.config.price_band.reference_price {
+            return Ok(Some((price, PriceReferenceSource::Configured)));
+        }
+
+        let previous = sqlx::query_scalar::<_, Decimal>(
+            r#"
+            SELECT clearing_price
+            FROM market_epochs
+            WHERE clearing_price IS NOT NULL
+              AND ($1::uuid IS NULL OR start_time < (SELECT start_time FROM market_epochs WHERE id = $1))
+            ORDER BY start_time DESC
+            LIMIT 1
+            "#,
+        )
+        .bind(epoch_id)
+        .fetch_optional(&self.db)
+        .await?;
+
+        Ok(previous.map(|price| (price, PriceReferenceSource::PreviousEpoch)))
+    }
+
+    /// Band applied when clearing `epoch_id`, if one is active; none once an
+    /// admin has overridden the band for the epoch
+    pub async fn price_band(&self, epoch_id: Uuid) -> Result<Option<PriceBand>> {
+        if !self.config.price_band.enabled() {
+            return Ok(None);
+        }
+        let overridden = sqlx::query_scalar::<_, bool>(
+            "SELECT price_band_overridden_at IS NOT NULL FROM market_epochs WHERE id = $1",
+        )
+        .bind(epoch_id)
+        .fetch_optional(&self.db)
+        .await?
+        .unwrap_or(false);
+        if overridden {
+            return Ok(None);
+        }
+        Ok(self
+            .price_band_reference(Some(epoch_id))
+            .await?
+            .and_then(|(price, _)| PriceBand::around(price, self.config.price_band.max_deviation_pct)))
+    }
+
+    pub async fn price_band_status(&self) -> Result<PriceBandStatus> {
+        let config = &self.config.price_band;
+        let reference = self.price_band_reference(None).await?;
+        let band = reference
+            .filter(|_| config.enabled())
+            .and_then(|(price, _)| PriceBand::around(price, config.max_deviation_pct));
+
+        let halted_epochs = sqlx::query_as::<_, HaltedEpoch>(
+            r#"
+            SELECT id AS epoch_id, epoch_number, halted_at, halt_reason
+            FROM market_epochs
+            WHERE halted_at > NOW() - INTERVAL '24 hours'
+            ORDER BY halted_at DESC
+            "#,
+        )
+        .fetch_all(&self.db)
+        .await?;
+
+        Ok(PriceBandStatus {
+            enabled: config.enabled(),
+            max_deviation_pct: config.max_deviation_pct,
+            reference_price: reference.map(|(price, _)| price),
+            reference_source: reference.map(|(_, source)| source),
+            lower_bound: band.map(|band| band.lower_bound),
+            upper_bound: band.map(|band| band.upper_bound),
+            halted_epochs,
+        })
+    }
+
+    /// Whether clearing for `epoch_id` was halted by the price band
+    pub async fn is_epoch_halted(&self, epoch_id: Uuid) -> Result<bool> {
+        let halted = sqlx::query_scalar::<_, bool>(
+            "SELECT halted_at IS NOT NULL FROM market_epochs WHERE id = $1",
+        )
+        .bind(epoch_id)
+        .fetch_optional(&self.db)
+        .await?;
+        Ok(halted.unwrap_or(false))
+    }
+
+    /// Halt clearing for `epoch_id` after `match_price` breached `band`, and
+    /// alert admins
+    pub(super) async fn halt_epoch_for_price_band(
+        &self,
+        epoch_id: Uuid,
+        match_price: Decimal,
+        band: &PriceBand,
+    ) -> Result<()> {
+        let reason = format!(
+            "Match price {} outside band {} - {} (reference {})",
+            match_price, band.lower_bound, band.upper_bound, band.reference_price
+        );
+        error!("🚨 Price band breached in epoch {}: {}; clearing halted", epoch_id, reason);
+        track_price_band_breach();
+
+        sqlx::query("UPDATE market_epochs SET halted_at = NOW(), halt_reason = $2 WHERE id = $1")
+            .bind(epoch_id)
+            .bind(&reason)
+            .execute(&self.db)
+            .await?;
+        if let Some(cache) = &self.cache {
+            cache.bump_generation(CacheKeys::MARKET_EPOCHS).await;
+        }
+
+        // Operational alert: admins only, not the public market feed
+        let admin_ids = sqlx::query_scalar::<_, Uuid>("SELECT id FROM users WHERE role::text = 'admin'")
+            .fetch_all(&self.db)
+            .await?;
+        self.websocket_service
+            .notify_price_band_breached(
+                &admin_ids,
+                epoch_id,
+                match_price,
+                band.reference_price,
+                band.lower_bound,
+                band.upper_bound,
+            )
+            .await;
+        Ok(())
+    }
+
+    /// Resolve the price band halt on `epoch_id`
+    ///
+    /// `Resume` lifts the halt and disables the band for the epoch, so the
+    /// next clearing run matches the remaining orders. `CancelOrders` leaves
+    /// the epoch halted and force-cancels its open orders, refunding their
+    /// unfilled escrow; orders that cannot be cancelled are skipped.
+    pub async fn resolve_price_band_halt(
+        &self,
+        epoch_id: Uuid,
+        admin_id: Uuid,
+        resolution: HaltResolution,
+        reason: &str,
+    ) -> Result<HaltResolutionResult> {
+        if !self.is_epoch_halted(epoch_id).await? {
+            return Err(crate::error::ApiError::Conflict(format!(
+                "Clearing for epoch {} is not halted",
+                epoch_id
+            ))
+            .into());
+        }
+
+        let mut cancelled_orders = Vec::new();
+        match resolution {
+            HaltResolution::Resume => {
+                sqlx::query(
+                    r#"
+                    UPDATE market_epochs
+                    SET halted_at = NULL, halt_reason = NULL,
+                        price_band_overridden_at = NOW(), price_band_overridden_by = $2
+                    WHERE id = $1
+                    "#,
+                )
+                .bind(epoch_id)
+                .bind(admin_id)
+                .execute(&self.db)
+                .await?;
+                warn!("Admin {} resumed clearing for epoch {} without the price band: {}", admin_id, epoch_id, reason);
+            }
+            HaltResolution::CancelOrders => {
+                let order_ids = sqlx::query_scalar::<_, Uuid>(
+                    r#"
+                    SELECT id FROM trading_orders
+                    WHERE epoch_id = $1 AND status IN ('pending', 'active', 'partially_filled')
+                    ORDER BY created_at
+                    "#,
+                )
+                .bind(epoch_id)
+                .fetch_all(&self.db)
+                .await?;
+
+                for order_id in order_ids {
+                    match self.force_cancel_order(order_id, admin_id, reason).await {
+                        Ok(result) => cancelled_orders.push(result),
+                        Err(e) => warn!("Skipping order {} of halted epoch {}: {}", order_id, epoch_id, e),
+                    }
+                }
+                info!(
+                    "Admin {} cancelled {} open orders of halted epoch {}: {}",
+                    admin_id,
+                    cancelled_orders.len(),
+                    epoch_id,
+                    reason
+                );
+            }
+        }
+
+        if let Some(cache) = &self.cache {
+            cache.bump_generation(CacheKeys::MARKET_EPOCHS).await;
+        }
+
+        Ok(HaltResolutionResult {
+            epoch_id,
+            resolution,
+            cancelled_orders,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_band_around_reference() {
+        let band = PriceBand::around(Decimal::new(400, 2), Decimal::new(20, 0)).unwrap();
+        assert_eq!(band.lower_bound, Decimal::new(320, 2));
+        assert_eq!(band.upper_bound, Decimal::new(480, 2));
+
+        assert!(band.contains(Decimal::new(320, 2)));
+        assert!(band.contains(Decimal::new(480, 2)));
+        assert!(!band.contains(Decimal::new(481, 2)));
+        // A fat-fingered ask far below the market
+        assert!(!band.contains(Decimal::new(4, 2)));
+
+        assert!(PriceBand::around(Decimal::new(400, 2), Decimal::ZERO).is_none());
+        assert!(PriceBand::around(Decimal::ZERO, Decimal::new(20, 0)).is_none());
+    }
+}
@@ -1,10 +1,13 @@
 use chrono::{DateTime, Utc};
 use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 use uuid::Uuid;
 
 use crate::database::schema::types::{EpochStatus, OrderSide};
+use crate::models::trading::{OrderCloseReason, TimeInForce};
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
 pub struct MarketEpoch {
     pub id: Uuid,
     pub epoch_number: i64,
@@ -51,28 +54,6 @@ pub struct TradeMatch {
     pub seller_session_token: Option<String>,
 }
 
-#[derive(Debug, Clone)]
-pub struct Settlement {
-    pub id: Uuid,
-    pub epoch_id: Uuid,
-    pub buyer_id: Uuid,
-    pub seller_id: Uuid,
-    pub energy_amount: Decimal,
-    pub price_per_kwh: Decimal,
-    pub total_amount: Decimal,
-    pub fee_amount: Decimal,
-    pub wheeling_charge: Decimal,
-    pub loss_factor: Decimal,
-    pub loss_cost: Decimal,
-    pub effective_energy: Decimal,
-    pub buyer_zone_id: Option<i32>,
-    pub seller_zone_id: Option<i32>,
-    pub net_amount: Decimal,
-    pub status: String,
-    pub buyer_session_token: Option<String>,
-    pub seller_session_token: Option<String>,
-}
-
 #[derive(Debug)]
 pub struct OrderBookEntry {
     pub order_id: Uuid,
@@ -101,3 +82,103 @@ pub struct ClearingPrice {
     /// Best ask price
     pub best_ask: Decimal,
 }
+
+/// An order refused by `create_order` before anything was persisted
+///
+/// Returned inside `anyhow::Error`; handlers downcast to surface the reason.
+#[derive(Debug, Clone, thiserror::Error)]
+#[error("{message}")]
+pub struct OrderRejected {
+    pub reason: OrderCloseReason,
+    pub message: String,
+}
+
+impl OrderRejected {
+    pub fn new(reason: OrderCloseReason, message: impl Into<String>) -> Self {
+        Self {
+            reason,
+            message: message.into(),
+        }
+    }
+}
+
+/// Optional order behaviour beyond the basic order parameters
+#[derive(Debug, Clone, Default)]
+pub struct OrderOptions {
+    /// Iceberg order: show only this much on the public book at a time
+    pub display_quantity: Option<Decimal>,
+    pub time_in_force: TimeInForce,
+}
+
+/// Which open orders a bulk cancel applies to; unset fields match everything
+#[derive(Debug, Clone, Default)]
+pub struct CancelFilter {
+    pub side: Option<OrderSide>,
+    pub zone_id: Option<i32>,
+}
+
+/// Outcome of cancelling a user's open orders in bulk
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+pub struct BulkCancelResult {
+    /// Number of orders cancelled
+    pub cancelled: usize,
+    /// Funds returned from cancelled buy orders
+    #[schema(value_type = String)]
+    pub refunded_funds: Decimal,
+    /// Energy unlocked from cancelled sell orders (kWh)
+    #[schema(value_type = String)]
+    pub released_energy: Decimal,
+    /// Orders that could not be cancelled; they are left as they were
+    pub failed: Vec<BulkCancelFailure>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct BulkCancelFailure {
+    pub order_id: Uuid,
+    pub error: String,
+}
+
+/// Outcome of an admin force-cancel
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ForceCancelResult {
+    pub order_id: Uuid,
+    pub owner_id: Uuid,
+    /// Status the order was in before it was cancelled
+    pub previous_status: String,
+    /// Funds returned to the owner (buy orders)
+    #[schema(value_type = String)]
+    pub refunded_funds: Decimal,
+    /// Energy unlocked for the owner (sell orders, kWh)
+    #[schema(value_type = String)]
+    pub released_energy: Decimal,
+}
+
+/// One aggregated price level of the order book
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct DepthLevel {
+    #[schema(value_type = String)]
+    pub price: Decimal,
+    /// Remaining volume resting at this price (kWh)
+    #[schema(value_type = String)]
+    pub volume: Decimal,
+    /// Volume at this price and all better prices (kWh)
+    #[schema(value_type = String)]
+    pub cumulative_volume: Decimal,
+    pub order_count: i64,
+}
+
+/// Aggregated order book depth, best prices first
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct OrderBookDepth {
+    pub bids: Vec<DepthLevel>,
+    pub asks: Vec<DepthLevel>,
+    #[schema(value_type = Option<String>)]
+    pub best_bid: Option<Decimal>,
+    #[schema(value_type = Option<String>)]
+    pub best_ask: Option<Decimal>,
+    #[schema(value_type = Option<String>)]
+    pub mid_price: Option<Decimal>,
+    #[schema(value_type = Option<String>)]
+    pub spread: Option<Decimal>,
+    pub timestamp: DateTime<Utc>,
+}
@@ -0,0 +1,196 @@
+//! Meter Alert Digest Service
+//!
+//! Scheduled job that batches each owner's unresolved meter alerts from the
+//! last day into a single digest email.
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use tracing::{error, info};
+use uuid::Uuid;
+
+use crate::services::meter_analyzer::{AlertSeverity, MeterAlertSummary};
+use crate::services::EmailService;
+
+/// Alerts older than this are not included in a digest
+const DIGEST_WINDOW_HOURS: i32 = 24;
+
+/// One aggregated alert row, ordered by owner
+#[derive(Debug, sqlx::FromRow)]
+struct DigestRow {
+    user_id: Uuid,
+    email: String,
+    username: String,
+    meter_serial: String,
+    alert_type: String,
+    severity: String,
+    message: String,
+    occurrences: i64,
+    last_seen: DateTime<Utc>,
+}
+
+/// A single owner's digest
+#[derive(Debug)]
+struct OwnerDigest {
+    user_id: Uuid,
+    email: String,
+    username: String,
+    alerts: Vec<MeterAlertSummary>,
+}
+
+/// Sends daily meter alert digests
+#[derive(Clone)]
+pub struct MeterAlertDigestService {
+    db: PgPool,
+    email_service: EmailService,
+}
+
+impl MeterAlertDigestService {
+    pub fn new(db: PgPool, email_service: EmailService) -> Self {
+        Self { db, email_service }
+    }
+
+    /// Send one digest per owner with pending alerts; returns the number of digests sent
+    ///
+    /// Alerts are stamped with `digested_at` only once their digest has been
+    /// delivered, so an owner whose email fails gets them again next run.
+    pub async fn send_daily_digests(&self) -> Result<usize> {
+        if !self.email_service.is_enabled() {
+            return Ok(0);
+        }
+        let cutoff = Utc::now();
+
+        let rows = sqlx::query_as::<_, DigestRow>(
+            r#"
+            SELECT
+                a.user_id,
+                u.email,
+                u.username,
+                a.meter_serial,
+                a.alert_type,
+                a.severity,
+                (ARRAY_AGG(a.message ORDER BY a.created_at DESC))[1] AS message,
+                COUNT(*) AS occurrences,
+                MAX(a.created_at) AS last_seen
+            FROM meter_alerts a
+            JOIN users u ON u.id = a.user_id
+            WHERE a.resolved_at IS NULL
+              AND a.digested_at IS NULL
+              AND a.created_at > $1 - make_interval(hours => $2)
+              AND a.created_at <= $1
+            GROUP BY a.user_id, u.email, u.username, a.meter_serial, a.alert_type, a.severity
+            ORDER BY a.user_id, last_seen DESC
+            "#,
+        )
+        .bind(cutoff)
+        .bind(DIGEST_WINDOW_HOURS)
+        .fetch_all(&self.db)
+        .await?;
+
+        let mut sent = 0;
+        for digest in group_by_owner(rows) {
+            if let Err(e) = self
+                .email_service
+                .send_meter_alert_digest(&digest.email, &digest.username, &digest.alerts)
+                .await
+            {
+                error!("❌ Failed to send meter alert digest to {}: {}", digest.user_id, e);
+                continue;
+            }
+
+            sqlx::query(
+                r#"
+                UPDATE meter_alerts
+                SET digested_at = NOW()
+                WHERE user_id = $1
+                  AND resolved_at IS NULL
+                  AND digested_at IS NULL
+                  AND created_at > $2 - make_interval(hours => $3)
+                  AND created_at <= $2
+                "#,
+            )
+            .bind(digest.user_id)
+            .bind(cutoff)
+            .bind(DIGEST_WINDOW_HOURS)
+            .execute(&self.db)
+            .await?;
+
+            sent += 1;
+        }
+
+        if sent > 0 {
+            info!("📧 Sent {} meter alert digests", sent);
+        }
+        Ok(sent)
+    }
+}
+
+/// Collapse owner-ordered rows into one digest per owner
+fn group_by_owner(rows: Vec<DigestRow>) -> Vec<OwnerDigest> {
+    let mut digests: Vec<OwnerDigest> = Vec::new();
+
+    for row in rows {
+        let summary = MeterAlertSummary {
+            meter_id: row.meter_serial,
+            alert_type: row.alert_type,
+            severity: AlertSeverity::from_db(&row.severity),
+            message: row.message,
+            occurrences: row.occurrences,
+            last_seen: row.last_seen,
+        };
+
+        match digests.last_mut() {
+            Some(digest) if digest.user_id == row.user_id => digest.alerts.push(summary),
+            _ => digests.push(OwnerDigest {
+                user_id: row.user_id,
+                email: row.email,
+                username: row.username,
+                alerts: vec![summary],
+            }),
+        }
+    }
+
+    digests
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row(user_id: Uuid, meter: &str, severity: &str) -> DigestRow {
+        DigestRow {
+            user_id,
+            email: format!("{}@example.com", user_id),
+            username: "owner".to_string(),
+            meter_serial: meter.to_string(),
+            alert_type: "low_voltage".to_string(),
+            severity: severity.to_string(),
+            message: "Low voltage".to_string(),
+            occurrences: 2,
+            last_seen: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_group_by_owner_batches_consecutive_rows() {
+        let alice = Uuid::new_v4();
+        let bob = Uuid::new_v4();
+        let digests = group_by_owner(vec![
+            row(alice, "M-1", "critical"),
+            row(alice, "M-2", "warning"),
+            row(bob, "M-3", "info"),
+        ]);
+
+        assert_eq!(digests.len(), 2);
+        assert_eq!(digests[0].user_id, alice);
+        assert_eq!(digests[0].alerts.len(), 2);
+        assert_eq!(digests[0].alerts[0].severity, AlertSeverity::Critical);
+        assert_eq!(digests[1].user_id, bob);
+        assert_eq!(digests[1].alerts[0].meter_id, "M-3");
+    }
+
+    #[test]
+    fn test_group_by_owner_empty() {
+        assert!(group_by_owner(Vec::new()).is_empty());
+    }
+}
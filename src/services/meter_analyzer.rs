@@ -1,9 +1,11 @@
 use chrono::{DateTime, Utc};
 use serde::Serialize;
+use sqlx::PgPool;
+use uuid::Uuid;
 use crate::handlers::meter::types::ReadingData;
 
 /// Alert severity levels
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
 #[serde(rename_all = "lowercase")]
 pub enum AlertSeverity {
     Info,
@@ -11,6 +13,26 @@ pub enum AlertSeverity {
     Critical,
 }
 
+impl AlertSeverity {
+    /// Lowercase name as stored in `meter_alerts.severity`
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AlertSeverity::Info => "info",
+            AlertSeverity::Warning => "warning",
+            AlertSeverity::Critical => "critical",
+        }
+    }
+
+    /// Parse a stored severity; unknown values are treated as informational
+    pub fn from_db(value: &str) -> Self {
+        match value {
+            "critical" => AlertSeverity::Critical,
+            "warning" => AlertSeverity::Warning,
+            _ => AlertSeverity::Info,
+        }
+    }
+}
+
 /// Meter alert for abnormal readings
 #[derive(Debug, Clone, Serialize)]
 pub struct MeterAlert {
@@ -23,6 +45,46 @@ pub struct MeterAlert {
     pub timestamp: DateTime<Utc>,
 }
 
+/// Aggregated view of repeated alerts of one type on one meter, used in digests
+#[derive(Debug, Clone, Serialize)]
+pub struct MeterAlertSummary {
+    pub meter_id: String,
+    pub alert_type: String,
+    pub severity: AlertSeverity,
+    /// Message of the most recent occurrence
+    pub message: String,
+    pub occurrences: i64,
+    pub last_seen: DateTime<Utc>,
+}
+
+/// Persist alerts raised for a meter owned by `user_id`
+pub async fn record_alerts(
+    db: &PgPool,
+    user_id: Uuid,
+    alerts: &[MeterAlert],
+) -> Result<(), sqlx::Error> {
+    for alert in alerts {
+        sqlx::query(
+            r#"
+            INSERT INTO meter_alerts
+                (user_id, meter_serial, alert_type, severity, message, value, threshold, created_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+            "#,
+        )
+        .bind(user_id)
+        .bind(&alert.meter_id)
+        .bind(&alert.alert_type)
+        .bind(alert.severity.as_str())
+        .bind(&alert.message)
+        .bind(alert.value)
+        .bind(alert.threshold)
+        .bind(alert.timestamp)
+        .execute(db)
+        .await?;
+    }
+    Ok(())
+}
+
 /// Check for abnormal readings and generate alerts
 pub fn check_alerts<T: ReadingData>(
     meter_id: &str,
@@ -10,6 +10,7 @@ pub mod wallet;
 pub mod websocket;
 
 // Enabled Services for P2P Trading
+pub mod amm;
 pub mod audit_logger;
 pub mod market_clearing;
 pub mod settlement;
@@ -21,15 +22,26 @@ pub mod transaction;
 pub mod validation;
 pub mod webhook;
 pub mod erc;
+pub mod faucet;
+pub mod fees;
 pub mod grid_topology;
+pub mod influx_writer;
+pub mod job_coordinator;
 pub mod notification;
 pub mod price_monitor;
 pub mod reading_processor;
+pub mod reconciliation;
 pub mod recurring_scheduler;
+pub mod sustainability;
+pub mod task_heartbeat;
+pub mod task_supervisor;
+pub mod trading_account;
+pub mod treasury;
 pub mod notification_dispatcher;
 pub mod kafka;
 
 pub mod meter_analyzer;
+pub mod meter_alert_digest;
 pub mod meter;
 pub mod blockchain_task;
 
@@ -42,6 +54,7 @@ pub use health_check::HealthChecker;
 pub use wallet::WalletService;
 pub use websocket::WebSocketService;
 
+pub use amm::AmmService;
 pub use audit_logger::{AuditLogger, AuditEvent};
 pub use market_clearing::MarketClearingService;
 pub use settlement::SettlementService;
@@ -51,10 +64,18 @@ pub use dashboard::DashboardService;
 pub use event_processor::EventProcessorService;
 pub use webhook::WebhookService;
 pub use erc::ErcService;
+pub use fees::{FeeCalculator, FeeSchedule};
 pub use grid_topology::GridTopologyService;
+pub use influx_writer::InfluxWriter;
+pub use job_coordinator::BackgroundJobCoordinator;
 pub use notification::NotificationService;
 pub use price_monitor::{PriceMonitor, PriceMonitorConfig};
+pub use reconciliation::ReconciliationService;
 pub use recurring_scheduler::{RecurringScheduler, RecurringSchedulerConfig};
+pub use sustainability::SustainabilityService;
+pub use task_heartbeat::TaskHeartbeats;
+pub use trading_account::TradingAccountService;
+pub use treasury::TreasuryService;
 pub use notification_dispatcher::{NotificationDispatcher, NotificationDispatcherConfig};
 pub use kafka::KafkaConsumerService;
 pub use blockchain_task::{BlockchainTaskService, BlockchainTaskType, TaskPayload, EscrowRefundPayload};
@@ -9,27 +9,76 @@ use uuid::Uuid;
 use std::str::FromStr;
 use solana_sdk::pubkey::Pubkey;
 
-use std::sync::Arc;
-use std::time::Duration;
-use tokio::sync::RwLock;
-
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, OnceLock};
+use std::time::{Duration, Instant};
+use tokio::sync::{Mutex, Notify, RwLock};
+use tokio_util::sync::CancellationToken;
+use tokio_util::task::TaskTracker;
+
+use self::types::{
+    buy_priority_cmp, fillable_amount, match_terms, requeue_candidate, BuyLimit, ClearingPass, MatchCandidate, MatchScope, MatchedFill,
+    MatchingCycleSummary, MatchingEngineStatus, SelfTradeAction, SelfTradePrevention, ZoneClearingStrategy,
+};
 use crate::{
+    config::ReloadableConfig,
     database::schema::types::{OrderStatus, OrderSide},
-    services::{market_clearing::{TradeMatch, MarketClearingService}, SettlementService, WebSocketService, GridTopologyService, BlockchainService},
-    middleware::metrics::{track_order_matched, track_trading_operation},
+    models::trading::{slice_refreshed, OrderCloseReason, TimeInForce, TradingOrderDb},
+    services::{market_clearing::{TradeMatch, MarketClearingService}, BackgroundJobCoordinator, SettlementService, WebSocketService, GridTopologyService, BlockchainService},
+    services::task_heartbeat::{TaskHeartbeats, MATCHING_ENGINE_TASK},
+    services::task_supervisor::{supervise, RestartPolicy},
+    utils::units::to_atomic,
+    middleware::metrics::{
+        track_dust_cancellation, track_match_candidates, track_matching_cycle, track_open_orders,
+        track_order_matched, track_self_trade_prevented, track_trading_operation, track_zone_match,
+    },
 };
 
+/// Price levels per side in the order book snapshot broadcast after a cycle
+const DEPTH_SNAPSHOT_LEVELS: usize = 20;
+
 /// Background service that automatically matches orders with offers
 #[derive(Clone)]
 pub struct OrderMatchingEngine {
     db: PgPool,
     running: Arc<RwLock<bool>>,
+    /// Held for the duration of a matching cycle so `stop()` can wait for it
+    cycle_lock: Arc<Mutex<()>>,
+    /// Incremented on each `start()`; a loop exits once its generation is stale
+    generation: Arc<AtomicU64>,
+    /// Wakes a sleeping loop when the engine is stopped
+    wake: Arc<Notify>,
+    last_cycle: Arc<RwLock<Option<MatchingCycleSummary>>>,
     match_interval_secs: u64,
+    /// Period after the first `start()` during which cycles only simulate matches
+    warmup: Duration,
+    /// Rank same-zone sellers ahead of cheaper sellers in other zones
+    prefer_same_zone: bool,
+    /// What happens when a user's buy order meets their own sell order
+    self_trade_prevention: SelfTradePrevention,
+    /// Whether zones clear on their own before matching across them
+    zone_clearing: ZoneClearingStrategy,
+    /// Set by the first `start()`; the warm-up counts from here
+    started_at: Arc<OnceLock<Instant>>,
     websocket_service: Option<WebSocketService>,
     settlement: Option<SettlementService>,
     market_clearing: Option<MarketClearingService>,
     blockchain_service: Option<BlockchainService>,
     grid_topology: GridTopologyService,
+    /// Hot-reloadable settings; overrides `match_interval_secs` when set
+    runtime_config: Option<ReloadableConfig>,
+    /// Cycles are skipped while another replica holds the job lease
+    job_coordinator: Option<BackgroundJobCoordinator>,
+    /// Beaten once per loop iteration so readiness notices a dead loop
+    heartbeats: Option<TaskHeartbeats>,
+    /// Cancelled on process shutdown; checked between matching cycles
+    shutdown: CancellationToken,
+    /// Tracks the matching loop so shutdown can wait for the current cycle
+    task_tracker: TaskTracker,
+    /// (side, zone) series of the open orders gauge reported last cycle, so
+    /// emptied series drop to zero instead of keeping a stale count
+    open_order_series: Arc<std::sync::Mutex<BTreeSet<(&'static str, Option<i32>)>>>,
 }
 
 impl OrderMatchingEngine {
@@ -44,15 +93,151 @@ impl OrderMatchingEngine {
             info!("Order matching interval set to {} seconds", match_interval_secs);
         }
 
+        // Warm-up window before live matching, default disabled
+        let warmup_secs = std::env::var("MATCHING_WARMUP_SECS")
+            .ok()
+            .and_then(|v| v.parse::<u64>().ok())
+            .unwrap_or(0);
+
+        if warmup_secs > 0 {
+            info!("Order matching warm-up period set to {} seconds", warmup_secs);
+        }
+
+        // Optional same-zone tiebreak ahead of landed cost, default off
+        let prefer_same_zone = std::env::var("MATCHING_PREFER_SAME_ZONE")
+            .ok()
+            .and_then(|v| v.parse::<bool>().ok())
+            .unwrap_or(false);
+
+        if prefer_same_zone {
+            info!("Order matching prefers same-zone sellers");
+        }
+
+        let self_trade_prevention = match std::env::var("MATCHING_SELF_TRADE_PREVENTION") {
+            Ok(value) => SelfTradePrevention::parse(&value).unwrap_or_else(|| {
+                warn!("Unknown MATCHING_SELF_TRADE_PREVENTION '{}', skipping self-matches", value);
+                SelfTradePrevention::default()
+            }),
+            Err(_) => SelfTradePrevention::default(),
+        };
+
+        if self_trade_prevention != SelfTradePrevention::default() {
+            info!("Order matching self-trade prevention: {}", self_trade_prevention.as_str());
+        }
+
+        let zone_clearing = match std::env::var("MATCHING_ZONE_CLEARING") {
+            Ok(value) => ZoneClearingStrategy::parse(&value).unwrap_or_else(|| {
+                warn!("Unknown MATCHING_ZONE_CLEARING '{}', matching the merged book", value);
+                ZoneClearingStrategy::default()
+            }),
+            Err(_) => ZoneClearingStrategy::default(),
+        };
+
+        if zone_clearing != ZoneClearingStrategy::default() {
+            info!("Order matching zone clearing: {}", zone_clearing.as_str());
+        }
+
         Self {
             db,
             running: Arc::new(RwLock::new(false)),
+            cycle_lock: Arc::new(Mutex::new(())),
+            generation: Arc::new(AtomicU64::new(0)),
+            wake: Arc::new(Notify::new()),
+            last_cycle: Arc::new(RwLock::new(None)),
             match_interval_secs,
+            warmup: Duration::from_secs(warmup_secs),
+            prefer_same_zone,
+            self_trade_prevention,
+            zone_clearing,
+            started_at: Arc::new(OnceLock::new()),
             websocket_service: None,
             settlement: None,
             market_clearing: None,
             blockchain_service: None,
             grid_topology: GridTopologyService::new(),
+            runtime_config: None,
+            job_coordinator: None,
+            heartbeats: None,
+            shutdown: CancellationToken::new(),
+            task_tracker: TaskTracker::new(),
+            open_order_series: Arc::new(std::sync::Mutex::new(BTreeSet::new())),
+        }
+    }
+
+    /// Stop the matching loop when `shutdown` is cancelled, tracking it on `task_tracker`
+    pub fn with_shutdown(mut self, shutdown: CancellationToken, task_tracker: TaskTracker) -> Self {
+        self.shutdown = shutdown;
+        self.task_tracker = task_tracker;
+        self
+    }
+
+    /// Read the matching interval through a hot-reloadable config handle
+    pub fn with_runtime_config(mut self, runtime_config: ReloadableConfig) -> Self {
+        self.runtime_config = Some(runtime_config);
+        self
+    }
+
+    /// Only match while this replica holds the background job lease
+    pub fn with_job_coordinator(mut self, job_coordinator: BackgroundJobCoordinator) -> Self {
+        self.job_coordinator = Some(job_coordinator);
+        self
+    }
+
+    /// Report loop liveness through `heartbeats`
+    pub fn with_heartbeats(mut self, heartbeats: TaskHeartbeats) -> Self {
+        self.heartbeats = Some(heartbeats);
+        self
+    }
+
+    /// Matching interval currently in effect
+    fn match_interval_secs(&self) -> u64 {
+        self.runtime_config
+            .as_ref()
+            .map(|runtime| runtime.matching_interval_secs())
+            .unwrap_or(self.match_interval_secs)
+    }
+
+    /// Override the warm-up period (measured from the first `start()`)
+    pub fn with_warmup(mut self, warmup: Duration) -> Self {
+        self.warmup = warmup;
+        self
+    }
+
+    /// Price delivery between zones with a shared topology service
+    pub fn with_grid_topology(mut self, grid_topology: GridTopologyService) -> Self {
+        self.grid_topology = grid_topology;
+        self
+    }
+
+    /// Rank same-zone sellers ahead of landed cost when choosing a match
+    pub fn with_same_zone_priority(mut self, prefer_same_zone: bool) -> Self {
+        self.prefer_same_zone = prefer_same_zone;
+        self
+    }
+
+    /// Override the self-trade prevention policy
+    pub fn with_self_trade_prevention(mut self, policy: SelfTradePrevention) -> Self {
+        self.self_trade_prevention = policy;
+        self
+    }
+
+    /// Override the zone clearing strategy
+    pub fn with_zone_clearing(mut self, strategy: ZoneClearingStrategy) -> Self {
+        self.zone_clearing = strategy;
+        self
+    }
+
+    /// Whether the engine is still inside its post-startup warm-up window
+    ///
+    /// An engine that was never started has not begun its warm-up.
+    pub fn is_warming_up(&self) -> bool {
+        !self.warmup_remaining().is_zero()
+    }
+
+    fn warmup_remaining(&self) -> Duration {
+        match self.started_at.get() {
+            Some(started_at) => self.warmup.saturating_sub(started_at.elapsed()),
+            None => self.warmup,
         }
     }
 
@@ -81,31 +266,71 @@ impl OrderMatchingEngine {
     }
 
     /// Start the background matching engine
-    pub async fn start(&self) {
+    ///
+    /// Returns `false` if the engine was already running.
+    pub async fn start(&self) -> bool {
         let mut running = self.running.write().await;
         if *running {
             warn!("Order matching engine is already running");
-            return;
+            return false;
         }
         *running = true;
+        let generation = self.generation.fetch_add(1, Ordering::SeqCst) + 1;
         drop(running);
+        self.started_at.get_or_init(Instant::now);
 
         info!(
             "🚀 Starting automated order matching engine (interval: {}s)",
-            self.match_interval_secs
+            self.match_interval_secs()
         );
 
+        // Restarted after a panic; a stop or restart ends it for good
         let engine = self.clone();
-        tokio::spawn(async move {
-            engine.run_matching_loop().await;
-        });
+        self.task_tracker.spawn(supervise(
+            MATCHING_ENGINE_TASK,
+            RestartPolicy::from_env(),
+            self.shutdown.clone(),
+            move || {
+                let engine = engine.clone();
+                async move { engine.run_matching_loop(generation).await }
+            },
+        ));
+        true
     }
 
     /// Stop the background matching engine
-    pub async fn stop(&self) {
+    ///
+    /// Waits for an in-progress cycle to finish rather than aborting it
+    /// mid-match. Returns `false` if the engine was not running.
+    pub async fn stop(&self) -> bool {
         let mut running = self.running.write().await;
+        let was_running = *running;
         *running = false;
+        drop(running);
+
+        if !was_running {
+            return false;
+        }
+
+        self.wake.notify_waiters();
+        let _cycle = self.cycle_lock.lock().await;
         info!("⏹️  Stopped automated order matching engine");
+        true
+    }
+
+    /// Whether the matching loop is currently enabled
+    pub async fn is_running(&self) -> bool {
+        *self.running.read().await
+    }
+
+    /// Running state, interval and last-cycle outcome
+    pub async fn status(&self) -> MatchingEngineStatus {
+        MatchingEngineStatus {
+            running: self.is_running().await,
+            warming_up: self.is_warming_up(),
+            interval_secs: self.match_interval_secs(),
+            last_cycle: self.last_cycle.read().await.clone(),
+        }
     }
 
     /// Minimum trade amount in kWh to avoid dust
@@ -156,6 +381,10 @@ impl OrderMatchingEngine {
                 trigger_status: row.get("trigger_status"),
                 trailing_offset: row.get("trailing_offset"),
                 triggered_at: row.get("triggered_at"),
+                cancellation_reason: None,
+                display_quantity: None,
+                display_refreshed_at: None,
+                time_in_force: None,
              }
         }).collect();
 
@@ -166,13 +395,15 @@ impl OrderMatchingEngine {
 
             // 1. Update status to expired
             sqlx::query(
-                "UPDATE trading_orders SET status = 'expired', updated_at = NOW() WHERE id = $1"
+                "UPDATE trading_orders SET status = 'expired', cancellation_reason = $2, updated_at = NOW() WHERE id = $1"
             )
             .bind(order.id)
+            .bind(OrderCloseReason::Expired.as_str())
             .execute(&self.db)
             .await?;
 
             // 2. Process Refund/Unlock
+            let mut released = Decimal::ZERO;
             if let Some(market_clearing) = &self.market_clearing {
                 let remaining_amount = order.energy_amount - order.filled_amount.unwrap_or(Decimal::ZERO);
                 
@@ -186,6 +417,7 @@ impl OrderMatchingEngine {
                                 error!("Failed to refund funds for expired order {}: {}", order.id, e);
                             } else {
                                 info!("💰 Refunded {} for expired buy order {}", refund_value, order.id);
+                                released = refund_value;
                             }
                         }
                         OrderSide::Sell => {
@@ -193,12 +425,20 @@ impl OrderMatchingEngine {
                                 error!("Failed to unlock energy for expired order {}: {}", order.id, e);
                             } else {
                                 info!("⚡ Unlocked {} energy for expired sell order {}", remaining_amount, order.id);
+                                released = remaining_amount;
                             }
                         }
                     }
                 }
             }
 
+            // 3. Notify subscribers so the order drops off live order lists
+            if let Some(ws_service) = &self.websocket_service {
+                ws_service
+                    .broadcast_order_cancelled(order.id, order.user_id, order.side, OrderCloseReason::Expired, released)
+                    .await;
+            }
+
             expired_count += 1;
         }
 
@@ -210,29 +450,60 @@ impl OrderMatchingEngine {
     }
 
     /// Main matching loop
-    async fn run_matching_loop(&self) {
+    async fn run_matching_loop(&self, generation: u64) {
         loop {
             // Check if we should continue running
             {
                 let running = self.running.read().await;
-                if !*running {
+                if !*running
+                    || self.shutdown.is_cancelled()
+                    || self.generation.load(Ordering::SeqCst) != generation
+                {
                     break;
                 }
             }
 
+            if let Some(heartbeats) = &self.heartbeats {
+                heartbeats.beat(MATCHING_ENGINE_TASK, Duration::from_secs(self.match_interval_secs()));
+            }
+
+            // A standby replica keeps the loop alive to take over on failover
+            if self.job_coordinator.as_ref().is_some_and(|c| !c.is_leader()) {
+                tokio::select! {
+                    _ = self.shutdown.cancelled() => break,
+                    _ = self.wake.notified() => {}
+                    _ = tokio::time::sleep(Duration::from_secs(self.match_interval_secs())) => {}
+                }
+                continue;
+            }
+
+            if let Some(heartbeats) = &self.heartbeats {
+                heartbeats.beat_batch(MATCHING_ENGINE_TASK, Duration::from_secs(self.match_interval_secs()));
+            }
+            let cycle = self.cycle_lock.lock().await;
+
             // Cleanup expired orders first
             if let Err(e) = self.expire_stale_orders().await {
                 error!("❌ Error expiring stale orders: {}", e);
             }
 
             // Run one matching cycle
-            match self.match_orders_cycle().await {
+            let cycle_start = Instant::now();
+            let outcome = self.match_orders_cycle().await;
+            track_matching_cycle(cycle_start.elapsed().as_secs_f64(), outcome.is_ok());
+            match outcome {
                 Ok((matches, volume)) => {
+                    *self.last_cycle.write().await = Some(MatchingCycleSummary {
+                        matches,
+                        volume,
+                        completed_at: chrono::Utc::now(),
+                    });
                     if matches > 0 {
                         info!(
                             "✅ Matching cycle completed: {} new transactions created, volume: {} kWh",
                             matches, volume
                         );
+                        self.broadcast_depth_snapshot().await;
                     } else {
                         debug!("Matching cycle completed: no new matches");
                     }
@@ -241,17 +512,56 @@ impl OrderMatchingEngine {
                     error!("❌ Error in matching cycle: {}", e);
                 }
             }
+            drop(cycle);
 
-            // Sleep before next cycle
-            tokio::time::sleep(Duration::from_secs(self.match_interval_secs)).await;
+            // Sleep before next cycle, waking early on stop or shutdown
+            tokio::select! {
+                _ = self.shutdown.cancelled() => break,
+                _ = self.wake.notified() => {}
+                _ = tokio::time::sleep(Duration::from_secs(self.match_interval_secs())) => {}
+            }
         }
 
+        // A stopped engine is not a dead one; a newer loop reports for itself
+        if let Some(heartbeats) = &self.heartbeats {
+            if self.generation.load(Ordering::SeqCst) == generation {
+                heartbeats.retire(MATCHING_ENGINE_TASK);
+            }
+        }
         info!("Order matching loop terminated");
     }
 
+    /// Drop cached depth and push the post-cycle order book to WebSocket subscribers
+    async fn broadcast_depth_snapshot(&self) {
+        let Some(market_clearing) = &self.market_clearing else {
+            return;
+        };
+        // Matches changed the book; don't serve a pre-cycle snapshot
+        market_clearing.invalidate_order_book().await;
+
+        let Some(ws_service) = &self.websocket_service else {
+            return;
+        };
+        match market_clearing.get_order_book_depth(DEPTH_SNAPSHOT_LEVELS, None).await {
+            Ok(depth) => ws_service.broadcast_order_book_depth(&depth).await,
+            Err(e) => warn!("Failed to build order book snapshot: {}", e),
+        }
+    }
+
     /// Run one matching cycle
+    ///
+    /// During the warm-up window the cycle runs in simulate-only mode: candidate
+    /// matches are computed and logged, but no matches, settlements or order
+    /// updates are written.
     async fn match_orders_cycle(&self) -> Result<(usize, Decimal)> {
-        use crate::models::trading::TradingOrderDb;
+        let simulate = self.is_warming_up();
+        if simulate {
+            let remaining = self.warmup_remaining();
+            info!(
+                "🔥 Matching engine warming up ({}s remaining): simulate-only cycle",
+                remaining.as_secs()
+            );
+        }
 
         // Get all pending buy orders
         let buy_orders_rows = sqlx::query(
@@ -262,10 +572,12 @@ impl OrderMatchingEngine {
                 expires_at, created_at, filled_at, meter_id,
                 refund_tx_signature, order_pda, session_token,
                 trigger_price, trigger_type, trigger_status,
-                trailing_offset, triggered_at
+                trailing_offset, triggered_at,
+                display_quantity, display_refreshed_at, time_in_force
             FROM trading_orders
             WHERE side = 'buy'::order_side AND status IN ('pending', 'active', 'partially_filled')
-            ORDER BY created_at ASC
+            ORDER BY (order_type = 'market'::order_type) DESC, price_per_kwh DESC,
+                COALESCE(display_refreshed_at, created_at) ASC, id ASC
             "#,
         )
         .fetch_all(&self.db)
@@ -295,6 +607,10 @@ impl OrderMatchingEngine {
                 trigger_status: row.get("trigger_status"),
                 trailing_offset: row.get("trailing_offset"),
                 triggered_at: row.get("triggered_at"),
+                cancellation_reason: None,
+                display_quantity: row.get("display_quantity"),
+                display_refreshed_at: row.get("display_refreshed_at"),
+                time_in_force: row.get("time_in_force"),
             }
         }).collect();
 
@@ -310,10 +626,11 @@ impl OrderMatchingEngine {
                 expires_at, created_at, filled_at, meter_id,
                 refund_tx_signature, order_pda, session_token,
                 trigger_price, trigger_type, trigger_status,
-                trailing_offset, triggered_at
+                trailing_offset, triggered_at,
+                display_quantity, display_refreshed_at, time_in_force
             FROM trading_orders
             WHERE side = 'sell'::order_side AND status IN ('pending', 'active', 'partially_filled')
-            ORDER BY price_per_kwh ASC, created_at ASC
+            ORDER BY price_per_kwh ASC, COALESCE(display_refreshed_at, created_at) ASC, id ASC
             "#,
         )
         .fetch_all(&self.db)
@@ -343,218 +660,581 @@ impl OrderMatchingEngine {
                 trigger_status: row.get("trigger_status"),
                 trailing_offset: row.get("trailing_offset"),
                 triggered_at: row.get("triggered_at"),
+                cancellation_reason: None,
+                display_quantity: row.get("display_quantity"),
+                display_refreshed_at: row.get("display_refreshed_at"),
+                time_in_force: row.get("time_in_force"),
             }
         }).collect();
 
         info!("Fetched {} sell orders", sell_orders_db.len());
 
+        self.report_open_orders(&buy_orders_db, &sell_orders_db);
+
         if buy_orders_db.is_empty() || sell_orders_db.is_empty() {
+            // Nothing to match against, which is the pass IOC and FOK orders get
+            if !simulate {
+                self.cancel_unfilled_remainders(&buy_orders_db).await;
+                self.cancel_unfilled_remainders(&sell_orders_db).await;
+            }
             return Ok((0, Decimal::ZERO));
         }
 
         let mut matches_created = 0;
         let mut total_matched_volume = Decimal::ZERO;
 
-        // Try to match each buy order
-        for buy_order in &buy_orders_db {
-            let mut buy_filled_amount = buy_order.filled_amount.unwrap_or(Decimal::ZERO);
-            let buy_energy_amount = buy_order.energy_amount;
+        // Buy orders cancelled during an earlier pass of this cycle
+        let mut closed_buys = vec![false; buy_orders_db.len()];
+        // Fills a fill-or-kill buy carries into the next pass
+        let mut held_fills: Vec<Vec<MatchedFill>> = vec![Vec::new(); buy_orders_db.len()];
+        let passes = self.zone_clearing.passes();
+
+        for (pass_number, &pass) in passes.iter().enumerate() {
+            let final_pass = pass_number + 1 == passes.len();
+
+            // Try to match each buy order. An iceberg buy whose shown slice fills
+            // is queued again behind the buy orders already resting at its price.
+            let mut buy_queue: VecDeque<usize> = (0..buy_orders_db.len()).filter(|&index| !closed_buys[index]).collect();
+            buy_queue
+                .make_contiguous()
+                .sort_by(|&a, &b| buy_priority_cmp(&buy_orders_db[a], &buy_orders_db[b]));
+            while let Some(buy_index) = buy_queue.pop_front() {
+                let buy_order = &buy_orders_db[buy_index];
+                let mut buy_filled_amount = buy_order.filled_amount.unwrap_or(Decimal::ZERO);
+                let mut buy_energy_amount = buy_order.energy_amount;
             
-            // Calculate remaining amount needed
-            let mut remaining_buy_amount = buy_energy_amount - buy_filled_amount;
+                // Calculate remaining amount needed
+                let mut remaining_buy_amount = buy_energy_amount - buy_filled_amount;
             
-            // Dust protection: If remaining amount is too small, mark as filled/cancelled to stop matching
-            if remaining_buy_amount < Self::MIN_TRADE_AMOUNT {
-                if remaining_buy_amount > Decimal::ZERO {
-                    // Start a new logical block to avoid borrowing issues if we were scanning orders
-                    // But here we are just deciding to skip/close this buy order
-                    let _ = sqlx::query("UPDATE trading_orders SET status = 'cancelled', updated_at = NOW() WHERE id = $1")
-                        .bind(buy_order.id)
-                        .execute(&self.db).await;
-                    info!("Cancelled dust buy order {} (rem: {})", buy_order.id, remaining_buy_amount);
+                // Dust protection: If remaining amount is too small, mark as filled/cancelled to stop matching
+                if remaining_buy_amount < Self::MIN_TRADE_AMOUNT {
+                    if remaining_buy_amount > Decimal::ZERO && !simulate {
+                        // Start a new logical block to avoid borrowing issues if we were scanning orders
+                        // But here we are just deciding to skip/close this buy order
+                        let _ = sqlx::query("UPDATE trading_orders SET status = 'cancelled', cancellation_reason = $2, updated_at = NOW() WHERE id = $1")
+                            .bind(buy_order.id)
+                            .bind(OrderCloseReason::Dust.as_str())
+                            .execute(&self.db).await;
+                        info!("Cancelled dust buy order {} (rem: {})", buy_order.id, remaining_buy_amount);
+                        track_dust_cancellation("buy");
+
+                        // Release the escrow still held for the dust remainder
+                        let mut released = Decimal::ZERO;
+                        if let Some(market_clearing) = &self.market_clearing {
+                            let refund_value = remaining_buy_amount * buy_order.price_per_kwh;
+                            match market_clearing.unlock_funds(buy_order.user_id, buy_order.id, refund_value, "Dust Remainder").await {
+                                Ok(()) => released = refund_value,
+                                Err(e) => error!("Failed to refund dust remainder for order {}: {}", buy_order.id, e),
+                            }
+                        }
+
+                        if let Some(ws_service) = &self.websocket_service {
+                            ws_service
+                                .broadcast_order_cancelled(buy_order.id, buy_order.user_id, OrderSide::Buy, OrderCloseReason::Dust, released)
+                                .await;
+                        }
+                    }
+                    for fill in std::mem::take(&mut held_fills[buy_index]) {
+                        self.complete_match(buy_order, &fill).await;
+                    }
+                    closed_buys[buy_index] = true;
+                    continue; 
                 }
-                continue; 
-            }
 
-            // 1. Calculate Landed Cost for all available sellers relative to THIS buyer
-            // 2. Filter eligible sellers
-            // 3. Sort by Landed Cost ASC
+                // 1. Calculate Landed Cost for all available sellers relative to THIS buyer
+                // 2. Filter eligible sellers
+                // 3. Sort by price-time priority (see `MatchCandidate::priority_cmp`)
             
-            // We create a list of indices to sell_orders_db to avoid cloning the whole structs
-            struct Candidate {
-                index: usize,
-                landed_cost: Decimal,
-                match_price: Decimal, // The base price (sell price)
-                wheeling_charge_per_kwh: Decimal,
-                loss_factor: Decimal,
-                loss_cost_per_kwh: Decimal,
-            }
+                // We create a list of indices to sell_orders_db to avoid cloning the whole structs
+                let buy_limit = BuyLimit::of(buy_order.order_type, buy_order.price_per_kwh);
+                let mut buy_slice_left = buy_order.visible_quantity();
+                let mut candidates = self.match_candidates(buy_order, buy_limit, pass, &sell_orders_db);
+
+                track_match_candidates(candidates.len());
+                candidates.sort_by(|a, b| a.priority_cmp(b, self.prefer_same_zone));
+
+                // A fill-or-kill buy trades only if this and the passes after it
+                // can fill all of it between them
+                let buy_tif = buy_order.time_in_force();
+                let mut killed = false;
+                if buy_tif == TimeInForce::Fok {
+                    let mut reachable = candidates.clone();
+                    for &later in &passes[pass_number + 1..] {
+                        reachable.extend(self.match_candidates(buy_order, buy_limit, later, &sell_orders_db));
+                    }
+                    let fillable = fillable_amount(
+                        remaining_buy_amount,
+                        reachable.iter().filter_map(|candidate| {
+                            let sell_order = &sell_orders_db[candidate.index];
+                            if sell_order.user_id == buy_order.user_id
+                                && self.self_trade_prevention != SelfTradePrevention::Allow
+                            {
+                                return None;
+                            }
+                            let remaining = sell_order.energy_amount - sell_order.filled_amount.unwrap_or(Decimal::ZERO);
+                            Some((remaining, sell_order.time_in_force() == TimeInForce::Fok))
+                        }),
+                    );
+                    if fillable < remaining_buy_amount {
+                        info!(
+                            "Fill-or-kill buy order {} cannot be filled in full ({} of {} kWh available)",
+                            buy_order.id, fillable, remaining_buy_amount
+                        );
+                        killed = true;
+                        candidates.clear();
+                    }
+                }
 
-            let mut candidates: Vec<Candidate> = Vec::new();
+                // Execute matches against candidates. A fill-or-kill buy holds
+                // its fills back until it is known to fill in full.
+                let mut candidates = VecDeque::from(candidates);
+                let mut buy_cancelled = false;
+                let mut held = std::mem::take(&mut held_fills[buy_index]);
+                while let Some(candidate) = candidates.pop_front() {
+                    if remaining_buy_amount <= Decimal::ZERO || buy_slice_left <= Decimal::ZERO {
+                        break;
+                    }
 
-            for (idx, sell_order) in sell_orders_db.iter().enumerate() {
-                let sell_filled = sell_order.filled_amount.unwrap_or(Decimal::ZERO);
-                let sell_energy = sell_order.energy_amount;
-                let remaining_sell = sell_energy - sell_filled;
+                    // Access the mutable sell order via index
+                    let sell_order = &mut sell_orders_db[candidate.index];
                 
-                if remaining_sell < Self::MIN_TRADE_AMOUNT {
-                    continue; // Skip dust entries
-                }
+                    let sell_filled = sell_order.filled_amount.unwrap_or(Decimal::ZERO);
+                    let remaining_sell = sell_order.energy_amount - sell_filled;
 
-                // Calculate Costs
-                // If zone_id is missing, we use None which results in higher default fees
-                let wheeling_charge = self.grid_topology.calculate_wheeling_charge(sell_order.zone_id, buy_order.zone_id);
-                let loss_factor = self.grid_topology.calculate_loss_factor(sell_order.zone_id, buy_order.zone_id);
-                
-                let sell_price = sell_order.price_per_kwh;
-                let loss_cost_unit = sell_price * loss_factor;
-                let landed_price = sell_price + wheeling_charge + loss_cost_unit;
-
-                // Check compatibility
-                if landed_price <= buy_order.price_per_kwh {
-                    candidates.push(Candidate {
-                        index: idx,
-                        landed_cost: landed_price,
-                        match_price: sell_price,
-                        wheeling_charge_per_kwh: wheeling_charge,
-                        loss_factor,
-                        loss_cost_per_kwh: loss_cost_unit,
-                    });
-                }
-            }
+                    if remaining_sell <= Decimal::ZERO {
+                        continue;
+                    }
+
+                    if sell_order.user_id == buy_order.user_id {
+                        let action = self.self_trade_prevention.action(
+                            buy_order.created_at.unwrap_or(chrono::DateTime::<chrono::Utc>::MAX_UTC),
+                            sell_order.created_at.unwrap_or(chrono::DateTime::<chrono::Utc>::MAX_UTC),
+                        );
+                        if action != SelfTradeAction::Match {
+                            track_self_trade_prevented(self.self_trade_prevention.as_str());
+                            info!(
+                                "Self-trade prevented between buy order {} and sell order {} ({})",
+                                buy_order.id, sell_order.id, self.self_trade_prevention.as_str()
+                            );
+                            if simulate {
+                                continue;
+                            }
+                        }
 
-            // Sort by Landed Cost ASC
-            candidates.sort_by(|a, b| a.landed_cost.cmp(&b.landed_cost));
+                        match action {
+                            SelfTradeAction::Match => {}
+                            SelfTradeAction::Skip => continue,
+                            SelfTradeAction::CancelBuy => {
+                                // Held fills are reverted below, so their volume is not kept
+                                let held_amount: Decimal = held.iter().map(|fill| fill.amount).sum();
+                                self.cancel_remainder(
+                                    buy_order,
+                                    buy_filled_amount - held_amount,
+                                    remaining_buy_amount + held_amount,
+                                    OrderCloseReason::SelfTrade,
+                                ).await;
+                                buy_cancelled = true;
+                                break;
+                            }
+                            SelfTradeAction::CancelSell => {
+                                self.cancel_remainder(sell_order, sell_filled, remaining_sell, OrderCloseReason::SelfTrade).await;
+                                sell_order.energy_amount = sell_filled;
+                                continue;
+                            }
+                            SelfTradeAction::DecrementBoth => {
+                                let overlap = remaining_buy_amount.min(remaining_sell);
+                                self.decrement_for_self_trade(sell_order, overlap).await;
+                                sell_order.energy_amount -= overlap;
+                                self.decrement_for_self_trade(buy_order, overlap).await;
+                                buy_energy_amount -= overlap;
+                                remaining_buy_amount -= overlap;
+                                continue;
+                            }
+                        }
+                    }
 
-            // Execute matches against candidates
-            for candidate in candidates {
-                if remaining_buy_amount <= Decimal::ZERO {
-                    break;
+                    // Match amount, up to the shown slice of either iceberg order
+                    let mut match_amount = remaining_buy_amount
+                        .min(buy_slice_left)
+                        .min(sell_order.visible_quantity());
+
+                    // An unguarded market buy has nothing escrowed, so it fills
+                    // only as far as the buyer's balance covers at this price
+                    if buy_limit == BuyLimit::Any && !simulate {
+                        match self.affordable_energy(buy_order.user_id, candidate.match_price).await {
+                            Ok(affordable) => match_amount = match_amount.min(affordable),
+                            Err(e) => {
+                                error!("Failed to read balance for market buy order {}: {}", buy_order.id, e);
+                                break;
+                            }
+                        }
+                        if match_amount < Self::MIN_TRADE_AMOUNT {
+                            info!("Market buy order {} exhausted the buyer's balance", buy_order.id);
+                            break;
+                        }
+                    }
+
+                    // A fill-or-kill sell only trades in one piece
+                    if sell_order.time_in_force() == TimeInForce::Fok && match_amount < remaining_sell {
+                        continue;
+                    }
+
+                    let total_energy_cost = match_amount * candidate.match_price;
+                    let total_wheeling = match_amount * candidate.wheeling_charge_per_kwh;
+                    let total_loss_cost = match_amount * candidate.loss_cost_per_kwh;
+
+                    info!(
+                        "Matching buy order {} with sell order {}: {} kWh at ${}/kWh base (Landed: ${})",
+                        buy_order.id, sell_order.id, match_amount, candidate.match_price, candidate.landed_cost
+                    );
+
+                    let epoch_id = buy_order.epoch_id.or(sell_order.epoch_id)
+                        .ok_or_else(|| anyhow::anyhow!("Epoch ID required"))?;
+
+                    if simulate {
+                        info!(
+                            "[warm-up] Would match buy order {} with sell order {}: {} kWh at ${}/kWh (epoch {})",
+                            buy_order.id, sell_order.id, match_amount, candidate.match_price, epoch_id
+                        );
+                        sell_order.filled_amount = Some(sell_filled + match_amount);
+                        buy_filled_amount += match_amount;
+                        remaining_buy_amount -= match_amount;
+                        buy_slice_left -= match_amount;
+                        matches_created += 1;
+                        total_matched_volume += match_amount;
+                        if let Some(candidate) = Self::refresh_sell_slice(sell_order, sell_filled, candidate) {
+                            requeue_candidate(&mut candidates, candidate, self.prefer_same_zone);
+                        }
+                        continue;
+                    }
+
+                    if buy_limit == BuyLimit::Any {
+                        let Some(market_clearing) = &self.market_clearing else {
+                            warn!("Market buy order {} cannot be escrowed without market clearing", buy_order.id);
+                            break;
+                        };
+                        if let Err(e) = market_clearing.lock_funds(buy_order.user_id, buy_order.id, total_energy_cost).await {
+                            warn!("Failed to escrow funds for market buy order {}: {}", buy_order.id, e);
+                            break;
+                        }
+                    }
+
+                    // DB Actions
+                    match self.create_order_match(
+                        epoch_id,
+                        buy_order.id,
+                        sell_order.id,
+                        buy_order.user_id,
+                        sell_order.user_id,
+                        match_amount,
+                        candidate.match_price,
+                        total_energy_cost,
+                        MatchScope::of(candidate.same_zone),
+                    ).await {
+                        Ok(match_id) => {
+                             matches_created += 1;
+                             total_matched_volume += match_amount;
+                             // metrics...
+                             track_order_matched("p2p", match_amount.to_f64().unwrap_or(0.0));
+                             track_zone_match(MatchScope::of(candidate.same_zone).as_str(), match_amount.to_f64().unwrap_or(0.0));
+                             track_trading_operation("match", true);
+
+                             let fill = MatchedFill {
+                                 match_id,
+                                 epoch_id,
+                                 sell_index: candidate.index,
+                                 sell_order_id: sell_order.id,
+                                 seller_id: sell_order.user_id,
+                                 sell_order_pda: sell_order.order_pda.clone(),
+                                 seller_session_token: sell_order.session_token.clone(),
+                                 seller_zone_id: sell_order.zone_id,
+                                 sell_filled_before: sell_filled,
+                                 sell_refreshed_before: sell_order.display_refreshed_at,
+                                 amount: match_amount,
+                                 match_price: candidate.match_price,
+                                 total_energy_cost,
+                                 total_wheeling,
+                                 loss_factor: candidate.loss_factor,
+                                 total_loss_cost,
+                                 escrowed: if buy_limit == BuyLimit::Any { total_energy_cost } else { Decimal::ZERO },
+                             };
+
+                             // Update In-Memory State
+                             sell_order.filled_amount = Some(sell_filled + match_amount);
+                             buy_filled_amount += match_amount;
+                             remaining_buy_amount -= match_amount;
+                             buy_slice_left -= match_amount;
+                             let refreshed = Self::refresh_sell_slice(sell_order, sell_filled, candidate);
+
+                             // Update DB - Sell Order
+                             let new_sell_status = if sell_order.filled_amount.unwrap_or_default() >= sell_order.energy_amount {
+                                 OrderStatus::Filled
+                             } else {
+                                 OrderStatus::PartiallyFilled
+                             };
+                         
+                             let _ = sqlx::query("UPDATE trading_orders SET filled_amount = $1, status = $2, display_refreshed_at = $4, updated_at = NOW() WHERE id = $3")
+                                .bind(sell_order.filled_amount)
+                                .bind(new_sell_status)
+                                .bind(sell_order.id)
+                                .bind(sell_order.display_refreshed_at)
+                                .execute(&self.db).await;
+
+                             if let Some(candidate) = refreshed {
+                                 requeue_candidate(&mut candidates, candidate, self.prefer_same_zone);
+                             }
+
+                             if buy_tif == TimeInForce::Fok {
+                                 held.push(fill);
+                             } else {
+                                 self.complete_match(buy_order, &fill).await;
+                             }
+                        },
+                        Err(e) => {
+                            error!("Failed to create match: {}", e);
+                            if buy_limit == BuyLimit::Any {
+                                if let Some(market_clearing) = &self.market_clearing {
+                                    if let Err(e) = market_clearing.unlock_funds(buy_order.user_id, buy_order.id, total_energy_cost, "Match Failed").await {
+                                        error!("Failed to release market buy escrow for order {}: {}", buy_order.id, e);
+                                    }
+                                }
+                            }
+                        }
+                    }
                 }
 
-                // Access the mutable sell order via index
-                let sell_order = &mut sell_orders_db[candidate.index];
-                
-                let sell_filled = sell_order.filled_amount.unwrap_or(Decimal::ZERO);
-                let remaining_sell = sell_order.energy_amount - sell_filled;
+                // A fill-or-kill buy that cannot fill, or was left short by a
+                // failed match or a self-trade cancellation, gives back every
+                // fill it made this cycle. One still filling carries its fills
+                // into the next pass.
+                if buy_tif == TimeInForce::Fok {
+                    if killed || buy_cancelled || (final_pass && remaining_buy_amount > Decimal::ZERO) {
+                        if !held.is_empty() {
+                            let reverted = self.revert_fills(buy_order, &held, &mut sell_orders_db).await;
+                            warn!(
+                                "Fill-or-kill buy order {} was only partially filled; reverted {} matches ({} kWh)",
+                                buy_order.id, held.len(), reverted
+                            );
+                            matches_created -= held.len();
+                            total_matched_volume -= reverted;
+                            buy_filled_amount -= reverted;
+                            remaining_buy_amount += reverted;
+                        }
+                        if !buy_cancelled && !simulate {
+                            self.cancel_remainder(buy_order, buy_filled_amount, remaining_buy_amount, OrderCloseReason::FillOrKill).await;
+                        }
+                        closed_buys[buy_index] = true;
+                        continue;
+                    }
+                    if remaining_buy_amount > Decimal::ZERO {
+                        held_fills[buy_index] = held;
+                    } else {
+                        for fill in &held {
+                            self.complete_match(buy_order, fill).await;
+                        }
+                    }
+                }
 
-                if remaining_sell <= Decimal::ZERO {
+                if buy_cancelled {
+                    closed_buys[buy_index] = true;
                     continue;
                 }
 
-                // Match amount
-                let match_amount = if remaining_buy_amount < remaining_sell {
-                    remaining_buy_amount
+                // Filling an iceberg buy's shown slice reveals the next one
+                let display_refreshed_at = if buy_slice_left <= Decimal::ZERO
+                    && remaining_buy_amount >= Self::MIN_TRADE_AMOUNT
+                    && slice_refreshed(buy_energy_amount, buy_order.filled_amount.unwrap_or(Decimal::ZERO), buy_filled_amount, buy_order.display_quantity)
+                {
+                    Some(chrono::Utc::now())
                 } else {
-                    remaining_sell
+                    buy_order.display_refreshed_at
                 };
 
-                let total_energy_cost = match_amount * candidate.match_price;
-                let total_wheeling = match_amount * candidate.wheeling_charge_per_kwh;
-                let total_loss_cost = match_amount * candidate.loss_cost_per_kwh;
+                // Update DB - Buy Order (after processing all candidates)
+                let new_buy_status = if buy_filled_amount >= buy_energy_amount {
+                    OrderStatus::Filled
+                } else if buy_filled_amount > Decimal::ZERO {
+                    OrderStatus::PartiallyFilled
+                } else {
+                    OrderStatus::Active
+                };
 
-                info!(
-                    "Matching buy order {} with sell order {}: {} kWh at ${}/kWh base (Landed: ${})",
-                    buy_order.id, sell_order.id, match_amount, candidate.match_price, candidate.landed_cost
-                );
+                if !simulate {
+                    let _ = sqlx::query("UPDATE trading_orders SET filled_amount = $1, status = $2, display_refreshed_at = $4, updated_at = NOW() WHERE id = $3")
+                        .bind(buy_filled_amount)
+                        .bind(&new_buy_status)
+                        .bind(buy_order.id)
+                        .bind(display_refreshed_at)
+                        .execute(&self.db).await;
+                }
 
-                let epoch_id = buy_order.epoch_id.or(sell_order.epoch_id)
-                    .ok_or_else(|| anyhow::anyhow!("Epoch ID required"))?;
-
-                // DB Actions
-                match self.create_order_match(
-                    epoch_id,
-                    buy_order.id,
-                    sell_order.id,
-                    buy_order.user_id,
-                    sell_order.user_id,
-                    match_amount,
-                    candidate.match_price,
-                    total_energy_cost,
-                    buy_order.order_pda.as_deref(),
-                    sell_order.order_pda.as_deref(),
-                ).await {
-                    Ok(match_id) => {
-                         matches_created += 1;
-                         total_matched_volume += match_amount;
-                         // metrics...
-                         track_order_matched("p2p", match_amount.to_f64().unwrap_or(0.0));
-                         track_trading_operation("match", true);
-
-                         // Trigger settlement
-                         // Note: We need to pass the extra costs to settlement service eventually.
-                         // For now, we use the standard method.
-                         self.trigger_settlement(
-                            match_id, buy_order.id, sell_order.id, 
-                            buy_order.user_id, sell_order.user_id, 
-                            match_amount, candidate.match_price, total_energy_cost, epoch_id,
-                            (total_wheeling, candidate.loss_factor, total_loss_cost, buy_order.zone_id, sell_order.zone_id),
-                            buy_order.session_token.clone(), sell_order.session_token.clone()
-                         ).await;
-
-                         // Update In-Memory State
-                         sell_order.filled_amount = Some(sell_filled + match_amount);
-                         buy_filled_amount += match_amount;
-                         remaining_buy_amount -= match_amount;
-
-                         // Update DB - Sell Order
-                         let new_sell_status = if sell_order.filled_amount.unwrap_or_default() >= sell_order.energy_amount {
-                             OrderStatus::Filled
-                         } else {
-                             OrderStatus::PartiallyFilled
-                         };
-                         
-                         let _ = sqlx::query("UPDATE trading_orders SET filled_amount = $1, status = $2, updated_at = NOW() WHERE id = $3")
-                            .bind(sell_order.filled_amount)
-                            .bind(new_sell_status)
-                            .bind(sell_order.id)
-                            .execute(&self.db).await;
-                    },
-                    Err(e) => {
-                        error!("Failed to create match: {}", e);
-                    }
+                if display_refreshed_at != buy_order.display_refreshed_at {
+                    debug!("Iceberg buy order {} revealed its next slice", buy_order.id);
+                    let buy_order = &mut buy_orders_db[buy_index];
+                    buy_order.filled_amount = Some(buy_filled_amount);
+                    buy_order.energy_amount = buy_energy_amount;
+                    buy_order.display_refreshed_at = display_refreshed_at;
+
+                    let position = buy_queue.partition_point(|&index| {
+                        buy_priority_cmp(&buy_orders_db[index], &buy_orders_db[buy_index]) == std::cmp::Ordering::Less
+                    });
+                    buy_queue.insert(position, buy_index);
+                    continue;
                 }
-            }
 
-            // Update DB - Buy Order (after processing all candidates)
-            let new_buy_status = if buy_filled_amount >= buy_energy_amount {
-                OrderStatus::Filled
-            } else if buy_filled_amount > Decimal::ZERO {
-                OrderStatus::PartiallyFilled
-            } else {
-                OrderStatus::Active
-            };
+                // Carry this pass's fills into the next one; the remainder is
+                // only closed out or sent to the AMM after the last pass
+                if !final_pass {
+                    let buy_order = &mut buy_orders_db[buy_index];
+                    buy_order.filled_amount = Some(buy_filled_amount);
+                    buy_order.energy_amount = buy_energy_amount;
+                    continue;
+                }
 
-            let _ = sqlx::query("UPDATE trading_orders SET filled_amount = $1, status = $2, updated_at = NOW() WHERE id = $3")
-                .bind(buy_filled_amount)
-                .bind(&new_buy_status)
-                .bind(buy_order.id)
-                .execute(&self.db).await;
-
-            // --- AMM FALLBACK ---
-            if remaining_buy_amount > Self::MIN_TRADE_AMOUNT && new_buy_status != OrderStatus::Filled {
-                info!("💧 Buy order {} not fully filled, attempting AMM fallback for {} kWh", buy_order.id, remaining_buy_amount);
-                match self.attempt_amm_match(buy_order, remaining_buy_amount).await {
-                    Ok(filled) => {
-                        buy_filled_amount += filled;
-                        // Final status update after AMM
-                        let final_status = if buy_filled_amount >= buy_energy_amount { OrderStatus::Filled } else { OrderStatus::PartiallyFilled };
-                        let _ = sqlx::query("UPDATE trading_orders SET filled_amount = $1, status = $2, updated_at = NOW() WHERE id = $3")
-                            .bind(buy_filled_amount)
-                            .bind(final_status)
-                            .bind(buy_order.id)
-                            .execute(&self.db).await;
+                if simulate {
+                    continue;
+                }
+
+                // --- AMM FALLBACK ---
+                // Unguarded market buys have no price to cap the swap at
+                if remaining_buy_amount > Self::MIN_TRADE_AMOUNT
+                    && new_buy_status != OrderStatus::Filled
+                    && buy_limit != BuyLimit::Any
+                    && buy_tif != TimeInForce::Fok
+                {
+                    info!("💧 Buy order {} not fully filled, attempting AMM fallback for {} kWh", buy_order.id, remaining_buy_amount);
+                    match self.attempt_amm_match(buy_order, remaining_buy_amount).await {
+                        Ok(filled) => {
+                            buy_filled_amount += filled;
+                            // Final status update after AMM
+                            let final_status = if buy_filled_amount >= buy_energy_amount { OrderStatus::Filled } else { OrderStatus::PartiallyFilled };
+                            let _ = sqlx::query("UPDATE trading_orders SET filled_amount = $1, status = $2, updated_at = NOW() WHERE id = $3")
+                                .bind(buy_filled_amount)
+                                .bind(final_status)
+                                .bind(buy_order.id)
+                                .execute(&self.db).await;
+                        }
+                        Err(e) => {
+                            warn!("AMM fallback skipped or failed for {}: {}", buy_order.id, e);
+                        }
                     }
-                    Err(e) => {
-                        warn!("AMM fallback skipped or failed for {}: {}", buy_order.id, e);
+                }
+
+                // IOC and FOK buys get this one pass
+                if let Some(reason) = buy_tif.close_reason() {
+                    let unfilled = buy_energy_amount - buy_filled_amount;
+                    if unfilled > Decimal::ZERO {
+                        self.cancel_remainder(buy_order, buy_filled_amount, unfilled, reason).await;
                     }
                 }
             }
         }
 
+        if !simulate {
+            self.cancel_unfilled_remainders(&sell_orders_db).await;
+        }
+
+        if simulate {
+            if matches_created > 0 {
+                info!(
+                    "[warm-up] Simulated {} matches ({} kWh); nothing was persisted",
+                    matches_created, total_matched_volume
+                );
+            }
+            return Ok((0, Decimal::ZERO));
+        }
+
         Ok((matches_created, total_matched_volume))
     }
 
+    /// Sell orders able to fill `buy_order` in `pass`, in book order
+    fn match_candidates(
+        &self,
+        buy_order: &TradingOrderDb,
+        buy_limit: BuyLimit,
+        pass: ClearingPass,
+        sell_orders: &[TradingOrderDb],
+    ) -> Vec<MatchCandidate> {
+        let mut candidates = Vec::new();
+
+        for (idx, sell_order) in sell_orders.iter().enumerate() {
+            let sell_filled = sell_order.filled_amount.unwrap_or(Decimal::ZERO);
+            let sell_energy = sell_order.energy_amount;
+            let remaining_sell = sell_energy - sell_filled;
+        
+            if remaining_sell < Self::MIN_TRADE_AMOUNT {
+                continue; // Skip dust entries
+            }
+
+            let same_zone = sell_order.zone_id.is_some() && sell_order.zone_id == buy_order.zone_id;
+            if !pass.admits(same_zone) {
+                continue;
+            }
+
+            // Calculate Costs
+            // If zone_id is missing, we use None which results in higher default fees
+            let (wheeling_charge, loss_factor) = if pass.prices_delivery() {
+                (
+                    self.grid_topology.calculate_wheeling_charge(sell_order.zone_id, buy_order.zone_id),
+                    self.grid_topology.calculate_loss_factor(sell_order.zone_id, buy_order.zone_id),
+                )
+            } else {
+                (Decimal::ZERO, Decimal::ZERO)
+            };
+        
+            // Check compatibility
+            if let Some((match_price, landed_price)) = match_terms(
+                buy_limit,
+                sell_order.order_type,
+                sell_order.price_per_kwh,
+                wheeling_charge,
+                loss_factor,
+            ) {
+                candidates.push(MatchCandidate {
+                    index: idx,
+                    order_id: sell_order.id,
+                    queued_at: sell_order.priority_time().unwrap_or(chrono::DateTime::<chrono::Utc>::MAX_UTC),
+                    same_zone,
+                    landed_cost: landed_price,
+                    match_price,
+                    wheeling_charge_per_kwh: wheeling_charge,
+                    loss_factor,
+                    loss_cost_per_kwh: match_price * loss_factor,
+                    sell_order_type: sell_order.order_type,
+                });
+            }
+        }
+
+        candidates
+    }
+
+    /// Set the open orders gauge per side and zone, zeroing series that
+    /// emptied since the last cycle
+    fn report_open_orders(
+        &self,
+        buy_orders: &[crate::models::trading::TradingOrderDb],
+        sell_orders: &[crate::models::trading::TradingOrderDb],
+    ) {
+        let mut counts: BTreeMap<(&'static str, Option<i32>), usize> = BTreeMap::new();
+        for order in buy_orders {
+            *counts.entry(("buy", order.zone_id)).or_default() += 1;
+        }
+        for order in sell_orders {
+            *counts.entry(("sell", order.zone_id)).or_default() += 1;
+        }
+
+        let mut series = self.open_order_series.lock().unwrap_or_else(|e| e.into_inner());
+        for &(side, zone_id) in series.iter() {
+            if !counts.contains_key(&(side, zone_id)) {
+                track_open_orders(side, zone_id, 0);
+            }
+        }
+        for (&(side, zone_id), &count) in &counts {
+            track_open_orders(side, zone_id, count);
+        }
+        *series = counts.into_keys().collect();
+    }
+
     /// Create an order match record
     async fn create_order_match(
         &self,
@@ -566,8 +1246,7 @@ impl OrderMatchingEngine {
         energy_amount: Decimal,
         price_per_kwh: Decimal,
         _total_price: Decimal,
-        buy_order_pda: Option<&str>,
-        sell_order_pda: Option<&str>,
+        scope: MatchScope,
     ) -> Result<Uuid> {
         let match_id = Uuid::new_v4();
 
@@ -582,9 +1261,10 @@ impl OrderMatchingEngine {
                 match_price,
                 match_time,
                 status,
+                clearing_scope,
                 created_at,
                 updated_at
-            ) VALUES ($1, $2, $3, $4, $5, $6, NOW(), $7, NOW(), NOW())
+            ) VALUES ($1, $2, $3, $4, $5, $6, NOW(), $7, $8, NOW(), NOW())
             "#,
         )
         .bind(match_id)
@@ -594,11 +1274,24 @@ impl OrderMatchingEngine {
         .bind(&energy_amount)
         .bind(&price_per_kwh)
         .bind(OrderStatus::Pending)
+        .bind(scope.as_str())
         .execute(&self.db)
         .await?;
 
+        Ok(match_id)
+    }
 
-        // 2. Execute On-Chain Match (if blockchain service is available)
+    /// Execute a recorded match on-chain, announce it and settle it
+    async fn complete_match(&self, buy_order: &TradingOrderDb, fill: &MatchedFill) {
+        let match_id = fill.match_id;
+        let buy_order_id = buy_order.id;
+        let sell_order_id = fill.sell_order_id;
+        let energy_amount = fill.amount;
+        let price_per_kwh = fill.match_price;
+        let buy_order_pda = buy_order.order_pda.as_deref();
+        let sell_order_pda = fill.sell_order_pda.as_deref();
+
+        // Execute On-Chain Match (if blockchain service is available)
         if let Some(blockchain) = &self.blockchain_service {
              // We need the authority keypair to sign the match
              match blockchain.get_authority_keypair().await {
@@ -611,24 +1304,34 @@ impl OrderMatchingEngine {
                      // But execute_match_orders takes string pubkeys.
                      
                      // Helper to derive market PDA
-                     let market_pda = Pubkey::find_program_address(&[b"market"], &blockchain.trading_program_id().unwrap_or_default()).0;
+                     let market_pda = Pubkey::find_program_address(&[b"market"], &blockchain.trading_program_id()).0;
                      
                                              
                      if let (Some(b_pda), Some(s_pda)) = (buy_order_pda, sell_order_pda) {
-                         let match_u64 = (energy_amount * Decimal::from(1_000_000_000)).to_u64().unwrap_or(0);
-                         
-                         info!("Executing on-chain match: Buyer {}, Seller {}, Amount {}", b_pda, s_pda, match_u64);
-                         
-                         match blockchain.execute_match_orders(&authority, &market_pda.to_string(), b_pda, s_pda, match_u64).await {
-                             Ok(sig) => {
-                                 info!("✅ On-chain match successful: {}", sig);
-                                 // Update order_matches with signature?
-                                 // Schema might not have it yet.
-                             },
-                             Err(e) => {
-                                 error!("❌ On-chain match failed: {}", e);
-                                 // We continue, as off-chain settlement is primary for now.
+                         let match_amount = async {
+                             let energy_mint_str = std::env::var("ENERGY_TOKEN_MINT").unwrap_or_else(|_| "Geq98m3Vw63AqrMEVoZsiW5DbNkScteZAdWDmm95ykYF".to_string());
+                             let energy_mint = Pubkey::from_str(&energy_mint_str)?;
+                             let decimals = blockchain.decimals_for(&energy_mint).await?;
+                             Ok::<_, anyhow::Error>(to_atomic(energy_amount, decimals)?)
+                         }
+                         .await;
+                         match match_amount {
+                             Ok(match_u64) => {
+                                 info!("Executing on-chain match: Buyer {}, Seller {}, Amount {}", b_pda, s_pda, match_u64);
+
+                                 match blockchain.execute_match_orders(&authority, &market_pda.to_string(), b_pda, s_pda, match_u64).await {
+                                     Ok(sig) => {
+                                         info!("✅ On-chain match successful: {}", sig);
+                                         // Update order_matches with signature?
+                                         // Schema might not have it yet.
+                                     },
+                                     Err(e) => {
+                                         error!("❌ On-chain match failed: {}", e);
+                                         // We continue, as off-chain settlement is primary for now.
+                                     }
+                                 }
                              }
+                             Err(e) => error!("❌ Skipping on-chain match {}: {}", match_id, e),
                          }
                      } else {
                          warn!("Skipping on-chain match: Missing Order PDAs for {} or {}", buy_order_id, sell_order_id);
@@ -656,7 +1359,59 @@ impl OrderMatchingEngine {
             });
         }
 
-        Ok(match_id)
+        // Trigger settlement
+        // Note: We need to pass the extra costs to settlement service eventually.
+        // For now, we use the standard method.
+        self.trigger_settlement(
+            match_id, buy_order_id, sell_order_id,
+            buy_order.user_id, fill.seller_id,
+            energy_amount, price_per_kwh, fill.total_energy_cost, fill.epoch_id,
+            (fill.total_wheeling, fill.loss_factor, fill.total_loss_cost, buy_order.zone_id, fill.seller_zone_id),
+            buy_order.session_token.clone(), fill.seller_session_token.clone()
+        ).await;
+    }
+
+    /// Undo the held fills of a fill-or-kill buy that did not fill in full:
+    /// delete the match records, put the sell orders back as they were and
+    /// release whatever a market buy escrowed. Returns the reverted volume.
+    async fn revert_fills(&self, buy_order: &TradingOrderDb, fills: &[MatchedFill], sell_orders: &mut [TradingOrderDb]) -> Decimal {
+        let match_ids: Vec<Uuid> = fills.iter().map(|fill| fill.match_id).collect();
+        if let Err(e) = sqlx::query("DELETE FROM order_matches WHERE id = ANY($1)")
+            .bind(&match_ids)
+            .execute(&self.db)
+            .await
+        {
+            error!("Failed to delete reverted matches of fill-or-kill order {}: {}", buy_order.id, e);
+        }
+
+        // Newest first, so a sell order matched twice ends up as it started
+        for fill in fills.iter().rev() {
+            let sell_order = &mut sell_orders[fill.sell_index];
+            sell_order.filled_amount = Some(fill.sell_filled_before);
+            sell_order.display_refreshed_at = fill.sell_refreshed_before;
+
+            if let Err(e) = sqlx::query("UPDATE trading_orders SET filled_amount = $1, status = $2, display_refreshed_at = $4, updated_at = NOW() WHERE id = $3")
+                .bind(sell_order.filled_amount)
+                .bind(&sell_order.status)
+                .bind(sell_order.id)
+                .bind(sell_order.display_refreshed_at)
+                .execute(&self.db)
+                .await
+            {
+                error!("Failed to restore sell order {} after reverting match {}: {}", sell_order.id, fill.match_id, e);
+            }
+        }
+
+        let escrowed: Decimal = fills.iter().map(|fill| fill.escrowed).sum();
+        if escrowed > Decimal::ZERO {
+            if let Some(market_clearing) = &self.market_clearing {
+                if let Err(e) = market_clearing.unlock_funds(buy_order.user_id, buy_order.id, escrowed, "Fill-or-Kill Reverted").await {
+                    error!("Failed to release market buy escrow for order {}: {}", buy_order.id, e);
+                }
+            }
+        }
+
+        fills.iter().map(|fill| fill.amount).sum()
     }
 
     /// Create settlement for the matched trade
@@ -753,6 +1508,152 @@ impl OrderMatchingEngine {
         self.match_orders_cycle().await
     }
 
+    /// Reveal the next slice of an iceberg sell order once a fill from
+    /// `filled_before` used up the shown one, returning its candidate with the
+    /// new queue time so it ranks behind sellers already at its price
+    fn refresh_sell_slice(
+        sell_order: &mut TradingOrderDb,
+        filled_before: Decimal,
+        candidate: MatchCandidate,
+    ) -> Option<MatchCandidate> {
+        let filled = sell_order.filled_amount.unwrap_or(Decimal::ZERO);
+        if sell_order.energy_amount - filled < Self::MIN_TRADE_AMOUNT
+            || !slice_refreshed(sell_order.energy_amount, filled_before, filled, sell_order.display_quantity)
+        {
+            return None;
+        }
+
+        let now = chrono::Utc::now();
+        sell_order.display_refreshed_at = Some(now);
+        debug!("Iceberg sell order {} revealed its next slice", sell_order.id);
+        Some(MatchCandidate { queued_at: now, ..candidate })
+    }
+
+    /// Cancel the unfilled `remaining` of `order` for `reason` and release
+    /// its escrow
+    async fn cancel_remainder(&self, order: &TradingOrderDb, filled: Decimal, remaining: Decimal, reason: OrderCloseReason) {
+        if let Err(e) = sqlx::query(
+            "UPDATE trading_orders SET filled_amount = $2, status = 'cancelled', cancellation_reason = $3, updated_at = NOW() WHERE id = $1",
+        )
+        .bind(order.id)
+        .bind(filled)
+        .bind(reason.as_str())
+        .execute(&self.db)
+        .await
+        {
+            error!("Failed to cancel order {} ({}): {}", order.id, reason, e);
+            return;
+        }
+        info!("Cancelled order {} ({}, rem: {})", order.id, reason, remaining);
+
+        let escrow_reason = match reason {
+            OrderCloseReason::SelfTrade => "Self-Trade Prevented",
+            OrderCloseReason::ImmediateOrCancel => "IOC Remainder",
+            OrderCloseReason::FillOrKill => "Fill-or-Kill Not Filled",
+            _ => "Order Cancelled",
+        };
+        let released = self.release_escrow(order, remaining, escrow_reason).await;
+        if let Some(ws_service) = &self.websocket_service {
+            ws_service
+                .broadcast_order_cancelled(order.id, order.user_id, order.side, reason, released)
+                .await;
+        }
+    }
+
+    /// Cancel whatever IOC and FOK orders still have unfilled after their
+    /// matching pass; GTC orders keep resting
+    async fn cancel_unfilled_remainders(&self, orders: &[TradingOrderDb]) {
+        for order in orders {
+            let Some(reason) = order.time_in_force().close_reason() else {
+                continue;
+            };
+            let filled = order.filled_amount.unwrap_or(Decimal::ZERO);
+            let unfilled = order.energy_amount - filled;
+            if unfilled > Decimal::ZERO {
+                self.cancel_remainder(order, filled, unfilled, reason).await;
+            }
+        }
+    }
+
+    /// Shrink `order` by `amount` after a prevented self-trade, closing it once
+    /// nothing tradable remains, and release that much escrow
+    async fn decrement_for_self_trade(&self, order: &TradingOrderDb, amount: Decimal) {
+        if let Err(e) = sqlx::query(
+            r#"
+            UPDATE trading_orders
+            SET energy_amount = energy_amount - $2,
+                status = CASE
+                    WHEN energy_amount - $2 - COALESCE(filled_amount, 0) < $3 THEN 'cancelled'::order_status
+                    ELSE status
+                END,
+                cancellation_reason = CASE
+                    WHEN energy_amount - $2 - COALESCE(filled_amount, 0) < $3 THEN $4
+                    ELSE cancellation_reason
+                END,
+                updated_at = NOW()
+            WHERE id = $1
+            "#,
+        )
+        .bind(order.id)
+        .bind(amount)
+        .bind(Self::MIN_TRADE_AMOUNT)
+        .bind(OrderCloseReason::SelfTrade.as_str())
+        .execute(&self.db)
+        .await
+        {
+            error!("Failed to decrement self-trading order {}: {}", order.id, e);
+            return;
+        }
+
+        self.release_escrow(order, amount, "Self-Trade Prevented").await;
+    }
+
+    /// Release the escrow backing `amount` kWh of `order`, returning the funds
+    /// (buy) or energy (sell) released
+    async fn release_escrow(&self, order: &TradingOrderDb, amount: Decimal, reason: &str) -> Decimal {
+        let Some(market_clearing) = &self.market_clearing else {
+            return Decimal::ZERO;
+        };
+
+        match order.side {
+            OrderSide::Buy => {
+                // Unguarded market buys hold no escrow until they match
+                let refund_value = amount * order.price_per_kwh;
+                if refund_value <= Decimal::ZERO {
+                    return Decimal::ZERO;
+                }
+                match market_clearing.unlock_funds(order.user_id, order.id, refund_value, reason).await {
+                    Ok(()) => refund_value,
+                    Err(e) => {
+                        error!("Failed to refund funds for order {}: {}", order.id, e);
+                        Decimal::ZERO
+                    }
+                }
+            }
+            OrderSide::Sell => match market_clearing.unlock_energy(order.user_id, order.id, amount, reason).await {
+                Ok(()) => amount,
+                Err(e) => {
+                    error!("Failed to unlock energy for order {}: {}", order.id, e);
+                    Decimal::ZERO
+                }
+            },
+        }
+    }
+
+    /// Energy the user's available balance buys at `price_per_kwh`, rounded down
+    async fn affordable_energy(&self, user_id: Uuid, price_per_kwh: Decimal) -> Result<Decimal> {
+        let balance: Option<Decimal> = sqlx::query_scalar("SELECT balance FROM users WHERE id = $1")
+            .bind(user_id)
+            .fetch_one(&self.db)
+            .await?;
+
+        if price_per_kwh <= Decimal::ZERO {
+            return Ok(Decimal::MAX);
+        }
+        Ok((balance.unwrap_or(Decimal::ZERO) / price_per_kwh)
+            .round_dp_with_strategy(9, rust_decimal::RoundingStrategy::ToZero))
+    }
+
     /// Attempt to match a remaining order amount with the AMM pool
     async fn attempt_amm_match(
         &self,
@@ -769,7 +1670,7 @@ impl OrderMatchingEngine {
         }
 
         // 1. Get Pool PDA and Mints based on Source Type
-        let program_id = blockchain.trading_program_id()?;
+        let program_id = blockchain.trading_program_id();
         let market_pda = Pubkey::find_program_address(&[b"market"], &program_id).0;
         
         // Map source string to curve type discriminator
@@ -807,8 +1708,9 @@ impl OrderMatchingEngine {
         let (pool_currency_vault, _) = Pubkey::find_program_address(&[b"currency_vault", pool_pda.as_ref()], &program_id);
 
         // 4. Execute Swap
-        let amount_milli_kwh = (remaining_amount * Decimal::from(1000)).to_u64().unwrap_or(0);
-        let max_currency = (remaining_amount * order.price_per_kwh * Decimal::from(1_000_000)).to_u64().unwrap_or(0);
+        // Energy in milli-kWh, the cap in micro-units of the payment token
+        let amount_milli_kwh = to_atomic(remaining_amount, 3)?;
+        let max_currency = to_atomic(remaining_amount * order.price_per_kwh, 6)?;
 
         if amount_milli_kwh == 0 { return Ok(Decimal::ZERO); }
 
@@ -1,2 +1,561 @@
 // Types for Order Matching Engine
-// Currently empty as main types are imported from other modules
+
+use std::cmp::Ordering;
+use std::collections::VecDeque;
+
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::Serialize;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::database::schema::types::OrderType;
+use crate::models::trading::TradingOrderDb;
+
+/// Outcome of the most recent completed matching cycle
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct MatchingCycleSummary {
+    /// Number of matches created in the cycle
+    pub matches: usize,
+    /// Matched volume in kWh
+    pub volume: Decimal,
+    pub completed_at: DateTime<Utc>,
+}
+
+/// Current state of the order matching engine
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct MatchingEngineStatus {
+    pub running: bool,
+    pub warming_up: bool,
+    /// Configured interval between cycles in seconds
+    pub interval_secs: u64,
+    /// Last completed cycle, if any has run since startup
+    pub last_cycle: Option<MatchingCycleSummary>,
+}
+
+/// A resting sell order able to fill the current buy order
+#[derive(Debug, Clone)]
+pub(crate) struct MatchCandidate {
+    /// Index into the cycle's sell order list
+    pub index: usize,
+    pub order_id: Uuid,
+    /// When the order joined the queue at its price: its creation, or the
+    /// reveal of its current iceberg slice
+    pub queued_at: DateTime<Utc>,
+    /// Seller and buyer are in the same grid zone
+    pub same_zone: bool,
+    /// Sell price plus wheeling and loss cost per kWh
+    pub landed_cost: Decimal,
+    /// The base price (sell price)
+    pub match_price: Decimal,
+    pub wheeling_charge_per_kwh: Decimal,
+    pub loss_factor: Decimal,
+    pub loss_cost_per_kwh: Decimal,
+    /// Market sells are priced off the buyer's limit
+    pub sell_order_type: OrderType,
+}
+
+/// A match recorded for the current buy order, not yet executed on-chain,
+/// announced or settled
+///
+/// A fill-or-kill buy holds its fills until all of them have been recorded,
+/// so they can be reverted if it does not fill in full.
+#[derive(Debug, Clone)]
+pub(crate) struct MatchedFill {
+    pub match_id: Uuid,
+    pub epoch_id: Uuid,
+    /// Index into the cycle's sell order list
+    pub sell_index: usize,
+    pub sell_order_id: Uuid,
+    pub seller_id: Uuid,
+    pub sell_order_pda: Option<String>,
+    pub seller_session_token: Option<String>,
+    pub seller_zone_id: Option<i32>,
+    /// The sell order's fill and slice reveal before this match
+    pub sell_filled_before: Decimal,
+    pub sell_refreshed_before: Option<DateTime<Utc>>,
+    pub amount: Decimal,
+    pub match_price: Decimal,
+    pub total_energy_cost: Decimal,
+    pub total_wheeling: Decimal,
+    pub loss_factor: Decimal,
+    pub total_loss_cost: Decimal,
+    /// Funds escrowed for an unguarded market buy at match time
+    pub escrowed: Decimal,
+}
+
+/// How the engine handles a buy order meeting a sell order from the same user
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SelfTradePrevention {
+    /// Pass over the user's own sell order and keep both orders resting
+    #[default]
+    Skip,
+    /// Cancel whichever of the two orders was placed later
+    CancelNewest,
+    /// Cancel whichever of the two orders was placed first
+    CancelOldest,
+    /// Shrink both orders by the overlapping amount without trading it
+    DecrementBoth,
+    /// Let the orders match
+    Allow,
+}
+
+/// What to do with one self-matching pair
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum SelfTradeAction {
+    Skip,
+    CancelBuy,
+    CancelSell,
+    DecrementBoth,
+    Match,
+}
+
+impl SelfTradePrevention {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Skip => "skip",
+            Self::CancelNewest => "cancel_newest",
+            Self::CancelOldest => "cancel_oldest",
+            Self::DecrementBoth => "decrement_both",
+            Self::Allow => "allow",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_lowercase().as_str() {
+            "skip" => Some(Self::Skip),
+            "cancel_newest" => Some(Self::CancelNewest),
+            "cancel_oldest" => Some(Self::CancelOldest),
+            "decrement_both" => Some(Self::DecrementBoth),
+            "allow" => Some(Self::Allow),
+            _ => None,
+        }
+    }
+
+    /// Resolve the policy for a buy and sell order of the same user; on equal
+    /// timestamps the buy order is treated as the newer one
+    pub(crate) fn action(
+        &self,
+        buy_created_at: DateTime<Utc>,
+        sell_created_at: DateTime<Utc>,
+    ) -> SelfTradeAction {
+        let buy_is_newer = buy_created_at >= sell_created_at;
+        match self {
+            Self::Skip => SelfTradeAction::Skip,
+            Self::CancelNewest if buy_is_newer => SelfTradeAction::CancelBuy,
+            Self::CancelNewest => SelfTradeAction::CancelSell,
+            Self::CancelOldest if buy_is_newer => SelfTradeAction::CancelSell,
+            Self::CancelOldest => SelfTradeAction::CancelBuy,
+            Self::DecrementBoth => SelfTradeAction::DecrementBoth,
+            Self::Allow => SelfTradeAction::Match,
+        }
+    }
+}
+
+/// How the book is split by grid zone before matching
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ZoneClearingStrategy {
+    /// One pass over the whole book, with wheeling and loss priced into
+    /// every cross-zone candidate
+    #[default]
+    Merged,
+    /// Clear each zone on its own at base prices first, then match what is
+    /// left across zones with wheeling and loss applied
+    ZonalFirst,
+}
+
+impl ZoneClearingStrategy {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Merged => "merged",
+            Self::ZonalFirst => "zonal_first",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_lowercase().as_str() {
+            "merged" => Some(Self::Merged),
+            "zonal_first" => Some(Self::ZonalFirst),
+            _ => None,
+        }
+    }
+
+    /// Passes a matching cycle runs, in order; the last one closes out
+    /// time-in-force remainders and tries the AMM
+    pub(crate) fn passes(&self) -> &'static [ClearingPass] {
+        match self {
+            Self::Merged => &[ClearingPass::All],
+            Self::ZonalFirst => &[ClearingPass::IntraZone, ClearingPass::CrossZone],
+        }
+    }
+}
+
+/// Which sellers one pass of a matching cycle offers each buyer
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ClearingPass {
+    All,
+    /// Sellers in the buyer's zone, at base price
+    IntraZone,
+    /// Sellers in other or unknown zones
+    CrossZone,
+}
+
+impl ClearingPass {
+    pub fn admits(&self, same_zone: bool) -> bool {
+        match self {
+            Self::All => true,
+            Self::IntraZone => same_zone,
+            Self::CrossZone => !same_zone,
+        }
+    }
+
+    /// Whether wheeling and loss are priced into candidates in this pass
+    pub fn prices_delivery(&self) -> bool {
+        *self != Self::IntraZone
+    }
+}
+
+/// Whether a match stayed inside one grid zone, as recorded on the match
+///
+/// Orders without a zone count as cross-zone, since delivery to them is
+/// priced with the default cross-zone tariffs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MatchScope {
+    IntraZone,
+    CrossZone,
+}
+
+impl MatchScope {
+    pub fn of(same_zone: bool) -> Self {
+        if same_zone {
+            Self::IntraZone
+        } else {
+            Self::CrossZone
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::IntraZone => "intra_zone",
+            Self::CrossZone => "cross_zone",
+        }
+    }
+}
+
+/// The landed cost per kWh a buy order accepts
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum BuyLimit {
+    /// Unguarded market buy: any landed cost, bounded by the buyer's balance
+    Any,
+    /// Limit price, or a market buy's slippage guard
+    AtMost(Decimal),
+}
+
+impl BuyLimit {
+    /// Read the limit off a stored buy order; unguarded market buys carry a zero price
+    pub fn of(order_type: OrderType, price_per_kwh: Decimal) -> Self {
+        match order_type {
+            OrderType::Market if price_per_kwh <= Decimal::ZERO => Self::Any,
+            _ => Self::AtMost(price_per_kwh),
+        }
+    }
+}
+
+/// Base price and landed cost per kWh at which a seller fills a buyer, if they cross
+///
+/// A limit sell trades at its own price. A market sell takes the buyer's
+/// limit: it is priced so that its landed cost equals that limit, and cannot
+/// trade against an unguarded market buy since neither side names a price.
+pub(crate) fn match_terms(
+    buy: BuyLimit,
+    sell_order_type: OrderType,
+    sell_price: Decimal,
+    wheeling_charge: Decimal,
+    loss_factor: Decimal,
+) -> Option<(Decimal, Decimal)> {
+    let landed = |price: Decimal| price + wheeling_charge + price * loss_factor;
+
+    match (sell_order_type, buy) {
+        (OrderType::Limit, BuyLimit::Any) => Some((sell_price, landed(sell_price))),
+        (OrderType::Limit, BuyLimit::AtMost(limit)) => {
+            let landed_cost = landed(sell_price);
+            (landed_cost <= limit).then_some((sell_price, landed_cost))
+        }
+        (OrderType::Market, BuyLimit::Any) => None,
+        (OrderType::Market, BuyLimit::AtMost(limit)) => {
+            let price = ((limit - wheeling_charge) / (Decimal::ONE + loss_factor)).round_dp(9);
+            (price > Decimal::ZERO).then(|| (price, landed(price)))
+        }
+    }
+}
+
+impl MatchCandidate {
+    /// Price-time priority for sellers competing for one buy order
+    ///
+    /// 1. same-zone sellers first, only when `prefer_same_zone` is set
+    /// 2. landed cost ascending
+    /// 3. `queued_at` ascending (older orders first)
+    /// 4. order id, so the order is total and repeatable
+    pub fn priority_cmp(&self, other: &Self, prefer_same_zone: bool) -> Ordering {
+        let zone = if prefer_same_zone {
+            other.same_zone.cmp(&self.same_zone)
+        } else {
+            Ordering::Equal
+        };
+
+        zone.then_with(|| self.landed_cost.cmp(&other.landed_cost))
+            .then_with(|| self.queued_at.cmp(&other.queued_at))
+            .then_with(|| self.order_id.cmp(&other.order_id))
+    }
+}
+
+/// Put a candidate back into a queue sorted by [`MatchCandidate::priority_cmp`],
+/// behind every candidate that ranks ahead of it
+pub(crate) fn requeue_candidate(
+    queue: &mut VecDeque<MatchCandidate>,
+    candidate: MatchCandidate,
+    prefer_same_zone: bool,
+) {
+    let position = queue.partition_point(|queued| queued.priority_cmp(&candidate, prefer_same_zone) == Ordering::Less);
+    queue.insert(position, candidate);
+}
+
+/// How much of `wanted` one pass over `candidates` would fill
+///
+/// Candidates are given in priority order as their unfilled amount and
+/// whether they are fill-or-kill, in which case they only trade in one piece.
+pub(crate) fn fillable_amount(wanted: Decimal, candidates: impl IntoIterator<Item = (Decimal, bool)>) -> Decimal {
+    let mut left = wanted;
+    for (available, all_or_none) in candidates {
+        if left <= Decimal::ZERO {
+            break;
+        }
+        let fill = left.min(available);
+        if all_or_none && fill < available {
+            continue;
+        }
+        left -= fill;
+    }
+    wanted - left
+}
+
+/// Priority among resting buy orders, as the matching cycle loads them:
+/// market orders first, then price descending, then queue time and id
+pub(crate) fn buy_priority_cmp(a: &TradingOrderDb, b: &TradingOrderDb) -> Ordering {
+    let is_market = |order: &TradingOrderDb| order.order_type == OrderType::Market;
+    let queued_at = |order: &TradingOrderDb| order.priority_time().unwrap_or(DateTime::<Utc>::MAX_UTC);
+
+    is_market(b)
+        .cmp(&is_market(a))
+        .then_with(|| b.price_per_kwh.cmp(&a.price_per_kwh))
+        .then_with(|| queued_at(a).cmp(&queued_at(b)))
+        .then_with(|| a.id.cmp(&b.id))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::trading::{slice_refreshed, visible_quantity, OrderCloseReason, TimeInForce};
+    use chrono::Duration;
+
+    fn candidate(landed_cost: i64, age_secs: i64, same_zone: bool) -> MatchCandidate {
+        MatchCandidate {
+            index: 0,
+            order_id: Uuid::new_v4(),
+            queued_at: Utc::now() - Duration::seconds(age_secs),
+            same_zone,
+            landed_cost: Decimal::new(landed_cost, 2),
+            match_price: Decimal::new(landed_cost, 2),
+            wheeling_charge_per_kwh: Decimal::ZERO,
+            loss_factor: Decimal::ZERO,
+            loss_cost_per_kwh: Decimal::ZERO,
+            sell_order_type: OrderType::Limit,
+        }
+    }
+
+    #[test]
+    fn test_equal_landed_cost_prefers_older_seller() {
+        let newer = candidate(50, 10, false);
+        let older = candidate(50, 60, false);
+
+        let mut candidates = vec![newer.clone(), older.clone()];
+        candidates.sort_by(|a, b| a.priority_cmp(b, false));
+
+        assert_eq!(candidates[0].order_id, older.order_id);
+        assert_eq!(candidates[1].order_id, newer.order_id);
+    }
+
+    #[test]
+    fn test_lower_landed_cost_beats_age() {
+        let cheap_new = candidate(40, 1, false);
+        let pricey_old = candidate(50, 600, false);
+
+        let mut candidates = vec![pricey_old, cheap_new.clone()];
+        candidates.sort_by(|a, b| a.priority_cmp(b, false));
+
+        assert_eq!(candidates[0].order_id, cheap_new.order_id);
+    }
+
+    #[test]
+    fn test_same_zone_preference_is_opt_in() {
+        let remote_cheap = candidate(40, 60, false);
+        let local_pricey = candidate(50, 60, true);
+
+        let mut default_order = vec![local_pricey.clone(), remote_cheap.clone()];
+        default_order.sort_by(|a, b| a.priority_cmp(b, false));
+        assert_eq!(default_order[0].order_id, remote_cheap.order_id);
+
+        let mut zone_first = vec![remote_cheap, local_pricey.clone()];
+        zone_first.sort_by(|a, b| a.priority_cmp(b, true));
+        assert_eq!(zone_first[0].order_id, local_pricey.order_id);
+    }
+
+    #[test]
+    fn test_self_trade_policy_resolves_by_age() {
+        let older = Utc::now() - Duration::seconds(60);
+        let newer = Utc::now();
+
+        assert_eq!(SelfTradePrevention::default().action(newer, older), SelfTradeAction::Skip);
+        assert_eq!(SelfTradePrevention::CancelNewest.action(newer, older), SelfTradeAction::CancelBuy);
+        assert_eq!(SelfTradePrevention::CancelNewest.action(older, newer), SelfTradeAction::CancelSell);
+        assert_eq!(SelfTradePrevention::CancelOldest.action(newer, older), SelfTradeAction::CancelSell);
+        assert_eq!(SelfTradePrevention::CancelOldest.action(older, newer), SelfTradeAction::CancelBuy);
+        assert_eq!(SelfTradePrevention::DecrementBoth.action(newer, older), SelfTradeAction::DecrementBoth);
+        assert_eq!(SelfTradePrevention::Allow.action(newer, older), SelfTradeAction::Match);
+    }
+
+    #[test]
+    fn test_self_trade_policy_parse_round_trip() {
+        for policy in [
+            SelfTradePrevention::Skip,
+            SelfTradePrevention::CancelNewest,
+            SelfTradePrevention::CancelOldest,
+            SelfTradePrevention::DecrementBoth,
+            SelfTradePrevention::Allow,
+        ] {
+            assert_eq!(SelfTradePrevention::parse(policy.as_str()), Some(policy));
+        }
+        assert_eq!(SelfTradePrevention::parse("bogus"), None);
+    }
+
+    #[test]
+    fn test_zonal_first_clears_each_zone_before_crossing() {
+        let passes = ZoneClearingStrategy::ZonalFirst.passes();
+        assert_eq!(passes, &[ClearingPass::IntraZone, ClearingPass::CrossZone]);
+
+        // Every seller is offered to a buyer in exactly one pass
+        for same_zone in [true, false] {
+            assert_eq!(passes.iter().filter(|pass| pass.admits(same_zone)).count(), 1);
+        }
+        assert!(!ClearingPass::IntraZone.prices_delivery());
+        assert!(ClearingPass::CrossZone.prices_delivery());
+
+        assert_eq!(ZoneClearingStrategy::Merged.passes(), &[ClearingPass::All]);
+        assert_eq!(ZoneClearingStrategy::parse(" Zonal_First "), Some(ZoneClearingStrategy::ZonalFirst));
+        assert_eq!(ZoneClearingStrategy::parse("nodal"), None);
+    }
+
+    #[test]
+    fn test_market_buy_fills_cheapest_sellers_regardless_of_price() {
+        let buy = BuyLimit::of(OrderType::Market, Decimal::ZERO);
+        assert_eq!(buy, BuyLimit::Any);
+
+        // Far above any sensible limit, yet all cross an unguarded market buy
+        let prices = [Decimal::new(900, 2), Decimal::new(250, 2), Decimal::new(5000, 2)];
+        let mut candidates: Vec<MatchCandidate> = prices
+            .iter()
+            .map(|&price| {
+                let (match_price, landed_cost) =
+                    match_terms(buy, OrderType::Limit, price, Decimal::new(1, 2), Decimal::new(5, 2))
+                        .expect("a market buy accepts any landed cost");
+                MatchCandidate {
+                    match_price,
+                    landed_cost,
+                    ..candidate(0, 60, false)
+                }
+            })
+            .collect();
+        candidates.sort_by(|a, b| a.priority_cmp(b, false));
+
+        let filled: Vec<Decimal> = candidates.iter().map(|c| c.match_price).collect();
+        assert_eq!(filled, vec![Decimal::new(250, 2), Decimal::new(900, 2), Decimal::new(5000, 2)]);
+    }
+
+    #[test]
+    fn test_market_buy_guard_caps_landed_cost() {
+        let buy = BuyLimit::of(OrderType::Market, Decimal::new(20, 2));
+        assert_eq!(buy, BuyLimit::AtMost(Decimal::new(20, 2)));
+
+        assert!(match_terms(buy, OrderType::Limit, Decimal::new(18, 2), Decimal::ZERO, Decimal::ZERO).is_some());
+        // 0.18 + 0.01 wheeling + 10% loss = 0.208 > 0.20
+        assert!(match_terms(buy, OrderType::Limit, Decimal::new(18, 2), Decimal::new(1, 2), Decimal::new(10, 2)).is_none());
+    }
+
+    #[test]
+    fn test_market_sell_takes_the_bid() {
+        let bid = BuyLimit::AtMost(Decimal::new(21, 2));
+        let (price, landed) =
+            match_terms(bid, OrderType::Market, Decimal::ZERO, Decimal::new(1, 2), Decimal::ZERO).unwrap();
+        assert_eq!(price, Decimal::new(20, 2));
+        assert_eq!(landed, Decimal::new(21, 2));
+
+        assert!(match_terms(BuyLimit::Any, OrderType::Market, Decimal::ZERO, Decimal::ZERO, Decimal::ZERO).is_none());
+    }
+
+    #[test]
+    fn test_iceberg_shows_one_slice_but_fills_in_full() {
+        let total = Decimal::new(100, 0);
+        let display = Some(Decimal::new(10, 0));
+        let mut filled = Decimal::ZERO;
+        let mut revealed = 0;
+
+        // Uneven buys each take at most the shown slice per match
+        for buy in [3, 7, 12, 25, 4, 60].map(Decimal::from) {
+            let mut remaining_buy = buy;
+            while remaining_buy > Decimal::ZERO && filled < total {
+                let visible = visible_quantity(total, filled, display);
+                assert!(visible > Decimal::ZERO && visible <= Decimal::new(10, 0));
+
+                let fill = remaining_buy.min(visible);
+                if slice_refreshed(total, filled, filled + fill, display) {
+                    revealed += 1;
+                }
+                filled += fill;
+                remaining_buy -= fill;
+            }
+        }
+
+        assert_eq!(filled, total);
+        assert_eq!(visible_quantity(total, filled, display), Decimal::ZERO);
+        // Ten slices; filling the last reveals nothing
+        assert_eq!(revealed, 9);
+        assert_eq!(visible_quantity(total, Decimal::new(35, 0), None), Decimal::new(65, 0));
+    }
+
+    #[test]
+    fn test_revealed_slice_queues_behind_resting_sellers() {
+        let iceberg = candidate(50, 600, false);
+        let same_price = candidate(50, 60, false);
+        let pricier = candidate(60, 600, false);
+        let mut queue = VecDeque::from(vec![same_price.clone(), pricier.clone()]);
+
+        requeue_candidate(&mut queue, MatchCandidate { queued_at: Utc::now(), ..iceberg.clone() }, false);
+
+        let order: Vec<Uuid> = queue.iter().map(|c| c.order_id).collect();
+        assert_eq!(order, vec![same_price.order_id, iceberg.order_id, pricier.order_id]);
+    }
+
+    #[test]
+    fn test_fill_or_kill_sell_trades_only_in_one_piece() {
+        // The 8 kWh FOK sell would be split, so the buy passes over it
+        let book = [(Decimal::from(4), false), (Decimal::from(8), true), (Decimal::from(5), false)];
+        assert_eq!(fillable_amount(Decimal::from(10), book), Decimal::from(9));
+
+        let book = [(Decimal::from(8), true), (Decimal::from(5), false)];
+        assert_eq!(fillable_amount(Decimal::from(10), book), Decimal::from(10));
+
+        for value in ["GTC", "IOC", "FOK"] {
+            assert_eq!(TimeInForce::from_db(value).map(|tif| tif.as_str()), Some(value));
+        }
+    }
+}
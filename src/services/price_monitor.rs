@@ -117,6 +117,10 @@ impl PriceMonitor {
                 trigger_status: row.get("trigger_status"),
                 trailing_offset: row.get("trailing_offset"),
                 triggered_at: row.get("triggered_at"),
+                cancellation_reason: None,
+                display_quantity: None,
+                display_refreshed_at: None,
+                time_in_force: None,
              }
         }).collect();
 
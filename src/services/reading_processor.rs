@@ -2,6 +2,7 @@ use std::sync::Arc;
 use tokio::time::{sleep, Duration};
 use tracing::{info, error, debug, warn};
 use serde::{Deserialize, Serialize};
+use uuid::Uuid;
 
 use crate::app_state::AppState;
 use crate::handlers::auth::types::{CreateReadingRequest, CreateReadingParams};
@@ -15,6 +16,9 @@ pub struct ReadingTask {
     pub request: CreateReadingRequest,
     #[serde(default)]
     pub retry_count: u32,
+    /// Assigned at ingestion and carried through minting
+    #[serde(default = "Uuid::new_v4")]
+    pub correlation_id: Uuid,
 }
 
 /// Service that processes meter readings from a Redis queue
@@ -0,0 +1,256 @@
+//! Ledger reconciliation
+//!
+//! Compares each user's on-chain energy token balance with the balance the
+//! database says they should hold: energy minted to their wallet, plus energy
+//! bought, minus energy sold in completed settlements. Drift points at
+//! partial settlement failures, double transfers or wallet identity mismatches.
+
+use std::str::FromStr;
+
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::Serialize;
+use sqlx::PgPool;
+use tracing::{info, warn};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::error::ApiError;
+use crate::services::BlockchainService;
+use crate::utils::units::from_atomic;
+
+/// Energy token decimals (1 token = 1 kWh)
+const ENERGY_TOKEN_DECIMALS: u8 = 9;
+
+/// Default drift tolerated before a user is reported, in kWh
+const DEFAULT_THRESHOLD_KWH: &str = "0.001";
+
+/// Default number of users checked per scheduled run
+const DEFAULT_SAMPLE_SIZE: i64 = 50;
+
+/// DB-derived energy flows for one user, in kWh
+#[derive(Debug, sqlx::FromRow)]
+struct ExpectedEnergy {
+    wallet_address: Option<String>,
+    minted: Decimal,
+    bought: Decimal,
+    sold: Decimal,
+}
+
+/// On-chain versus expected energy balance for one user
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct UserReconciliation {
+    pub user_id: Uuid,
+    pub wallet_address: String,
+    /// Energy token balance read from chain, in kWh
+    pub on_chain_balance: Decimal,
+    /// `minted + bought - sold`
+    pub expected_balance: Decimal,
+    pub minted: Decimal,
+    pub bought: Decimal,
+    pub sold: Decimal,
+    /// `on_chain_balance - expected_balance`
+    pub difference: Decimal,
+    pub threshold: Decimal,
+    pub is_balanced: bool,
+    pub checked_at: DateTime<Utc>,
+}
+
+/// Reconciles DB-derived balances with on-chain token balances
+#[derive(Clone, Debug)]
+pub struct ReconciliationService {
+    db: PgPool,
+    blockchain: BlockchainService,
+    energy_mint: String,
+    threshold: Decimal,
+    sample_size: i64,
+}
+
+impl ReconciliationService {
+    /// `RECONCILIATION_THRESHOLD_KWH` and `RECONCILIATION_SAMPLE_SIZE` tune
+    /// the reporting threshold and scheduled sample size
+    pub fn new(db: PgPool, blockchain: BlockchainService, energy_mint: String) -> Self {
+        let threshold = std::env::var("RECONCILIATION_THRESHOLD_KWH")
+            .ok()
+            .and_then(|v| Decimal::from_str(&v).ok())
+            .unwrap_or_else(|| Decimal::from_str(DEFAULT_THRESHOLD_KWH).unwrap_or_default());
+        let sample_size = std::env::var("RECONCILIATION_SAMPLE_SIZE")
+            .ok()
+            .and_then(|v| v.parse::<i64>().ok())
+            .filter(|n| *n > 0)
+            .unwrap_or(DEFAULT_SAMPLE_SIZE);
+
+        Self {
+            db,
+            blockchain,
+            energy_mint,
+            threshold,
+            sample_size,
+        }
+    }
+
+    /// Compare one user's on-chain energy balance with the DB-derived balance
+    pub async fn reconcile_user(&self, user_id: Uuid) -> Result<UserReconciliation, ApiError> {
+        let expected = sqlx::query_as::<_, ExpectedEnergy>(
+            r#"
+            SELECT u.wallet_address,
+                   (SELECT COALESCE(SUM(r.kwh_amount), 0) FROM meter_readings r
+                    LEFT JOIN meter_registry m ON m.meter_serial = r.meter_serial
+                    LEFT JOIN users owner ON owner.id = r.user_id
+                    WHERE r.minted = true
+                      AND COALESCE(m.mint_destination_wallet, owner.wallet_address) = u.wallet_address
+                   ) AS minted,
+                   (SELECT COALESCE(SUM(COALESCE(s.effective_energy, s.energy_amount)), 0) FROM settlements s
+                    WHERE s.buyer_id = u.id AND s.status = 'completed') AS bought,
+                   (SELECT COALESCE(SUM(s.energy_amount), 0) FROM settlements s
+                    WHERE s.seller_id = u.id AND s.status = 'completed') AS sold
+            FROM users u
+            WHERE u.id = $1
+            "#,
+        )
+        .bind(user_id)
+        .fetch_optional(&self.db)
+        .await
+        .map_err(ApiError::Database)?
+        .ok_or_else(|| ApiError::NotFound("User not found".to_string()))?;
+
+        let wallet_address = expected
+            .wallet_address
+            .ok_or_else(|| ApiError::BadRequest(format!("User {} has no wallet connected", user_id)))?;
+
+        let owner = BlockchainService::parse_pubkey(&wallet_address)
+            .map_err(|e| ApiError::Internal(format!("Invalid wallet address: {}", e)))?;
+        let mint = BlockchainService::parse_pubkey(&self.energy_mint)
+            .map_err(|e| ApiError::Internal(format!("Invalid energy mint: {}", e)))?;
+        let atomic = self
+            .blockchain
+            .get_token_balance(&owner, &mint)
+            .await
+            .map_err(|e| ApiError::Internal(format!("Failed to read token balance: {}", e)))?;
+        let on_chain_balance = from_atomic(atomic, ENERGY_TOKEN_DECIMALS)
+            .map_err(|e| ApiError::Internal(format!("Invalid token balance: {}", e)))?;
+
+        Ok(Self::compare(
+            user_id,
+            wallet_address,
+            on_chain_balance,
+            expected.minted,
+            expected.bought,
+            expected.sold,
+            self.threshold,
+        ))
+    }
+
+    /// Reconcile a random sample of users with wallets, returning those whose
+    /// drift exceeds the threshold
+    pub async fn reconcile_sample(&self) -> Result<Vec<UserReconciliation>, ApiError> {
+        let user_ids = sqlx::query_scalar::<_, Uuid>(
+            r#"
+            SELECT id FROM users
+            WHERE wallet_address IS NOT NULL
+            ORDER BY random()
+            LIMIT $1
+            "#,
+        )
+        .bind(self.sample_size)
+        .fetch_all(&self.db)
+        .await
+        .map_err(ApiError::Database)?;
+
+        let checked = user_ids.len();
+        let mut discrepancies = Vec::new();
+        for user_id in user_ids {
+            match self.reconcile_user(user_id).await {
+                Ok(report) if !report.is_balanced => {
+                    warn!(
+                        "⚖️ Balance drift for user {} ({}): on-chain {} kWh, expected {} kWh (diff {})",
+                        report.user_id,
+                        report.wallet_address,
+                        report.on_chain_balance,
+                        report.expected_balance,
+                        report.difference
+                    );
+                    metrics::counter!("reconciliation_discrepancies_total").increment(1);
+                    discrepancies.push(report);
+                }
+                Ok(_) => {}
+                Err(e) => warn!("Reconciliation skipped for user {}: {}", user_id, e),
+            }
+        }
+
+        info!(
+            "⚖️ Reconciled {} users, {} with drift above {} kWh",
+            checked,
+            discrepancies.len(),
+            self.threshold
+        );
+        Ok(discrepancies)
+    }
+
+    fn compare(
+        user_id: Uuid,
+        wallet_address: String,
+        on_chain_balance: Decimal,
+        minted: Decimal,
+        bought: Decimal,
+        sold: Decimal,
+        threshold: Decimal,
+    ) -> UserReconciliation {
+        let expected_balance = minted + bought - sold;
+        let difference = on_chain_balance - expected_balance;
+
+        UserReconciliation {
+            user_id,
+            wallet_address,
+            on_chain_balance,
+            expected_balance,
+            minted,
+            bought,
+            sold,
+            difference,
+            threshold,
+            is_balanced: difference.abs() <= threshold,
+            checked_at: Utc::now(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kwh(s: &str) -> Decimal {
+        Decimal::from_str(s).unwrap()
+    }
+
+    #[test]
+    fn test_balanced_within_threshold() {
+        let report = ReconciliationService::compare(
+            Uuid::new_v4(),
+            "wallet".to_string(),
+            kwh("12.0005"),
+            kwh("10"),
+            kwh("5"),
+            kwh("3"),
+            kwh("0.001"),
+        );
+        assert_eq!(report.expected_balance, kwh("12"));
+        assert!(report.is_balanced);
+    }
+
+    #[test]
+    fn test_double_transfer_shows_as_negative_drift() {
+        // The seller's tokens left twice for one settlement
+        let report = ReconciliationService::compare(
+            Uuid::new_v4(),
+            "wallet".to_string(),
+            kwh("4"),
+            kwh("10"),
+            kwh("0"),
+            kwh("3"),
+            kwh("0.001"),
+        );
+        assert_eq!(report.difference, kwh("-3"));
+        assert!(!report.is_balanced);
+    }
+}
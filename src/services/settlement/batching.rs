@@ -0,0 +1,444 @@
+//! Multi-transfer settlement transactions
+//!
+//! Packs the seller -> buyer transfers of several settlements into one Solana
+//! transaction, cutting RPC round-trips and fees when clearing a busy epoch.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{Keypair, Signature, Signer};
+use tracing::{error, info, warn};
+use uuid::Uuid;
+
+use super::{Settlement, SettlementBatchResult, SettlementService, SettlementStatus, SettlementTransaction};
+use crate::error::ApiError;
+use crate::services::blockchain::errors::AttemptedSignatures;
+use crate::services::blockchain::{SettlementError, TokenTransfer, TransactionStatus};
+use crate::services::AuditEvent;
+
+/// Largest serialized transaction Solana accepts, in bytes
+pub const MAX_TRANSACTION_SIZE: usize = 1232;
+
+/// Program id index, 4 account indexes and `transfer_checked` data
+/// (tag, u64 amount, u8 decimals), each with a one-byte length prefix
+const TRANSFER_INSTRUCTION_SIZE: usize = 1 + (1 + 4) + (1 + 10);
+
+/// Room kept for compute-budget instructions added at submission
+const COMPUTE_BUDGET_HEADROOM: usize = 64;
+
+/// Seller -> buyer transfer for one settlement, ready to sign
+pub(super) struct PreparedTransfer {
+    pub settlement_id: Uuid,
+    pub seller_keypair: Keypair,
+    pub transfer: TokenTransfer,
+    /// Grid loss sent to the loss sink, if any
+    pub loss_transfer: Option<TokenTransfer>,
+}
+
+impl PreparedTransfer {
+    fn transfers(&self) -> impl Iterator<Item = &TokenTransfer> {
+        std::iter::once(&self.transfer).chain(self.loss_transfer.as_ref())
+    }
+}
+
+/// Serialized size of a transaction carrying `transfers` with `fee_payer` paying
+///
+/// Counts and lengths are compact-u16 encoded, which is one byte at these sizes.
+pub fn estimated_transaction_size(fee_payer: &Pubkey, transfers: &[TokenTransfer]) -> usize {
+    let mut signers = HashSet::from([*fee_payer]);
+    let mut accounts = signers.clone();
+    for t in transfers {
+        signers.insert(t.owner);
+        accounts.extend([t.owner, t.from, t.to, t.mint]);
+    }
+    // The token program is an account key too
+    let account_keys = accounts.len() + 1;
+
+    1 + signers.len() * 64 // signatures
+        + 3 // message header
+        + 1 + account_keys * 32
+        + 32 // recent blockhash
+        + 1 + transfers.len() * TRANSFER_INSTRUCTION_SIZE
+        + COMPUTE_BUDGET_HEADROOM
+}
+
+/// Split prepared transfers into transactions of a single mint, each holding at
+/// most `max_settlements` settlements and fitting in [`MAX_TRANSACTION_SIZE`]
+fn plan_batches(
+    fee_payer: &Pubkey,
+    prepared: Vec<PreparedTransfer>,
+    max_settlements: usize,
+) -> Vec<Vec<PreparedTransfer>> {
+    let mut by_mint: BTreeMap<Pubkey, Vec<PreparedTransfer>> = BTreeMap::new();
+    for p in prepared {
+        by_mint.entry(p.transfer.mint).or_default().push(p);
+    }
+
+    let mut batches = Vec::new();
+    for group in by_mint.into_values() {
+        let mut current: Vec<PreparedTransfer> = Vec::new();
+        let mut transfers: Vec<TokenTransfer> = Vec::new();
+
+        for p in group {
+            let with_next: Vec<TokenTransfer> = transfers.iter().chain(p.transfers()).copied().collect();
+            let full = current.len() >= max_settlements
+                || estimated_transaction_size(fee_payer, &with_next) > MAX_TRANSACTION_SIZE;
+
+            if full && !current.is_empty() {
+                batches.push(std::mem::take(&mut current));
+                transfers = p.transfers().copied().collect();
+            } else {
+                transfers = with_next;
+            }
+            current.push(p);
+        }
+
+        if !current.is_empty() {
+            batches.push(current);
+        }
+    }
+
+    batches
+}
+
+/// Why a batch's settlements were not settled by its transaction
+#[derive(Debug, PartialEq, Eq)]
+enum UnsettledBatch {
+    /// Nothing was transferred; the settlements can be sent one by one
+    NotLanded,
+    /// An attempt may still have landed; resending could pay sellers twice
+    Unresolved,
+}
+
+/// Outcome of a failed batch from its error and the on-chain status of
+/// each attempted signature
+fn classify_failed_batch(
+    cause: &SettlementError,
+    statuses: &[(Signature, TransactionStatus)],
+) -> Result<Signature, UnsettledBatch> {
+    if !cause.may_have_landed() {
+        return Err(UnsettledBatch::NotLanded);
+    }
+    let landed = statuses.iter().find(|(_, status)| {
+        matches!(
+            status,
+            TransactionStatus::Processed | TransactionStatus::Confirmed(_) | TransactionStatus::Finalized
+        )
+    });
+    if let Some((signature, _)) = landed {
+        return Ok(*signature);
+    }
+    // Only attempts that executed and failed are known to have changed nothing
+    let all_failed = !statuses.is_empty()
+        && statuses.iter().all(|(_, status)| matches!(status, TransactionStatus::Failed(_)));
+    if all_failed {
+        Err(UnsettledBatch::NotLanded)
+    } else {
+        Err(UnsettledBatch::Unresolved)
+    }
+}
+
+impl SettlementService {
+    /// Settle several pending settlements with as few transactions as possible
+    ///
+    /// Transfers of the same mint are packed into multi-transfer transactions
+    /// signed by the platform authority (fee payer) and each seller. When a
+    /// batch transaction is known not to have landed, or a settlement cannot
+    /// be prepared for batching, those settlements are executed one by one
+    /// instead; a batch that may have landed stays processing and is listed
+    /// as unresolved. Bridge settlements and mock mode always take the
+    /// individual path.
+    pub async fn execute_batch(&self, ids: &[Uuid]) -> Result<SettlementBatchResult, ApiError> {
+        let mut result = SettlementBatchResult::default();
+        let mut settlements: HashMap<Uuid, Settlement> = HashMap::new();
+        let mut prepared = Vec::new();
+        let mut individual = Vec::new();
+
+        for &id in ids {
+            let settlement = match self.get_settlement(id).await {
+                Ok(settlement) => settlement,
+                Err(e) => {
+                    error!("❌ Cannot load settlement {} for batching: {}", id, e);
+                    result.failed.push(id);
+                    continue;
+                }
+            };
+
+            match settlement.status {
+                SettlementStatus::Pending if self.config.enable_real_blockchain => {}
+                SettlementStatus::Pending | SettlementStatus::PendingBridge => {
+                    individual.push(id);
+                    continue;
+                }
+                ref other => {
+                    warn!("Skipping settlement {} in batch: status is {}", id, other);
+                    continue;
+                }
+            }
+
+            match self.prepare_transfer(&settlement).await {
+                Ok(p) => {
+                    prepared.push(p);
+                    settlements.insert(id, settlement);
+                }
+                Err(e) => {
+                    warn!("Settlement {} cannot be batched, settling alone: {}", id, e);
+                    individual.push(id);
+                }
+            }
+        }
+
+        if !prepared.is_empty() {
+            let authority = self
+                .blockchain
+                .get_authority_keypair()
+                .await
+                .map_err(|e| ApiError::Internal(format!("Failed to get authority: {}", e)))?;
+
+            for batch in plan_batches(&authority.pubkey(), prepared, self.config.batch_max_settlements) {
+                if !self.may_start_settlement() {
+                    break;
+                }
+                tokio::time::sleep(self.throttle.next_delay()).await;
+                if !self.may_start_settlement() {
+                    break;
+                }
+                let batch_ids: Vec<Uuid> = batch.iter().map(|p| p.settlement_id).collect();
+
+                for id in &batch_ids {
+                    let s = &settlements[id];
+                    self.update_settlement_status(*id, SettlementStatus::Processing).await?;
+                    self.audit_logger.log_async(AuditEvent::SettlementStarted {
+                        settlement_id: *id,
+                        buyer_id: s.buyer_id,
+                        seller_id: s.seller_id,
+                        energy_amount: s.energy_amount.to_string(),
+                        total_amount: s.total_value.to_string(),
+                    });
+                }
+
+                let transfers: Vec<TokenTransfer> = batch.iter().flat_map(|p| p.transfers()).copied().collect();
+                let mut signers: Vec<&Keypair> = vec![&authority];
+                for p in &batch {
+                    if !signers.iter().any(|k| k.pubkey() == p.seller_keypair.pubkey()) {
+                        signers.push(&p.seller_keypair);
+                    }
+                }
+
+                let submitted = self.blockchain.transfer_tokens_batch(&transfers, &signers).await;
+                self.throttle
+                    .record_result(submitted.as_ref().err().map(SettlementError::from_anyhow).as_ref());
+                let submitted = match submitted {
+                    Ok(signature) => Ok(signature),
+                    Err(e) => self.resolve_failed_batch(&e).await,
+                };
+                match submitted {
+                    Ok(signature) => {
+                        let signature = signature.to_string();
+                        info!(
+                            "📦 Settled {} trades in one transaction ({} transfers): {}",
+                            batch_ids.len(),
+                            transfers.len(),
+                            signature
+                        );
+                        self.record_batch_signature(&batch_ids, &signature).await?;
+
+                        // On failure the batch stays processing with its
+                        // signature recorded, so nothing is transferred twice
+                        let slot = self
+                            .blockchain
+                            .get_slot()
+                            .await
+                            .map_err(|e| ApiError::Internal(format!("Failed to get slot: {}", e)))?;
+                        for id in &batch_ids {
+                            let tx_result = SettlementTransaction {
+                                settlement_id: *id,
+                                signature: signature.clone(),
+                                slot,
+                                confirmation_status: "confirmed".to_string(),
+                            };
+                            if let Err(e) = self.complete_settlement(&settlements[id], &tx_result).await {
+                                error!("⚠️ Post-settlement steps failed for {}: {}", id, e);
+                            }
+                        }
+
+                        result.batched.extend(batch_ids);
+                        result.batch_signatures.push(signature);
+                    }
+                    Err(UnsettledBatch::NotLanded) => {
+                        warn!(
+                            "Batch of {} settlements did not land, falling back to individual transfers",
+                            batch_ids.len()
+                        );
+                        individual.extend(batch_ids);
+                    }
+                    Err(UnsettledBatch::Unresolved) => {
+                        error!(
+                            "❌ Batch of {} settlements may have landed; left processing for reconciliation: {:?}",
+                            batch_ids.len(),
+                            batch_ids
+                        );
+                        result.unresolved.extend(batch_ids);
+                    }
+                }
+            }
+        }
+
+        for id in individual {
+            if !self.may_start_settlement() {
+                break;
+            }
+            tokio::time::sleep(self.throttle.next_delay()).await;
+            if !self.may_start_settlement() {
+                break;
+            }
+            match self.execute_settlement(id).await {
+                Ok(_) => {
+                    self.throttle.record_result(None);
+                    result.individually_settled.push(id);
+                }
+                Err(e) => {
+                    if let ApiError::Settlement(cause) = &e {
+                        self.throttle.record_result(Some(cause));
+                    }
+                    error!("❌ Failed to process settlement {}: {}", id, e);
+                    result.failed.push(id);
+                }
+            }
+        }
+
+        Ok(result)
+    }
+
+    /// Decide what a failed batch submission means for its settlements
+    ///
+    /// An ambiguous error may come back after the transaction landed, so
+    /// every attempted signature is looked up before anything is resent.
+    async fn resolve_failed_batch(&self, error: &anyhow::Error) -> Result<Signature, UnsettledBatch> {
+        let cause = SettlementError::from_anyhow(error);
+        warn!("Batch settlement transaction failed: {}", cause);
+
+        let mut statuses = Vec::new();
+        if cause.may_have_landed() {
+            for signature in AttemptedSignatures::from_anyhow(error) {
+                let status = match self.blockchain.transaction_status(&signature).await {
+                    Ok(status) => status,
+                    Err(e) => {
+                        warn!("Failed to look up batch transaction {}: {}", signature, e);
+                        TransactionStatus::Pending
+                    }
+                };
+                statuses.push((signature, status));
+            }
+        }
+        classify_failed_batch(&cause, &statuses)
+    }
+
+    /// Store the shared multi-transfer signature on every settlement in a batch
+    async fn record_batch_signature(&self, ids: &[Uuid], signature: &str) -> Result<(), ApiError> {
+        sqlx::query("UPDATE settlements SET batch_signature = $1, updated_at = NOW() WHERE id = ANY($2)")
+            .bind(signature)
+            .bind(ids)
+            .execute(&self.db)
+            .await
+            .map_err(ApiError::Database)?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn prepared(seller: &Keypair, mint: Pubkey, with_loss: bool) -> PreparedTransfer {
+        let transfer = TokenTransfer {
+            owner: seller.pubkey(),
+            from: Pubkey::new_unique(),
+            to: Pubkey::new_unique(),
+            mint,
+            amount: 1_000,
+            decimals: 9,
+        };
+        PreparedTransfer {
+            settlement_id: Uuid::new_v4(),
+            seller_keypair: seller.insecure_clone(),
+            transfer,
+            loss_transfer: with_loss.then(|| TokenTransfer {
+                to: Pubkey::new_unique(),
+                amount: 10,
+                ..transfer
+            }),
+        }
+    }
+
+    #[test]
+    fn test_single_transfer_fits_comfortably() {
+        let seller = Keypair::new();
+        let p = prepared(&seller, Pubkey::new_unique(), true);
+        let transfers: Vec<_> = p.transfers().copied().collect();
+        assert!(estimated_transaction_size(&Pubkey::new_unique(), &transfers) < MAX_TRANSACTION_SIZE / 2);
+    }
+
+    #[test]
+    fn test_batches_split_by_mint_and_size() {
+        let fee_payer = Pubkey::new_unique();
+        let (mint_a, mint_b) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let mut items: Vec<_> = (0..12).map(|_| prepared(&Keypair::new(), mint_a, false)).collect();
+        items.push(prepared(&Keypair::new(), mint_b, false));
+
+        let batches = plan_batches(&fee_payer, items, 50);
+
+        assert_eq!(batches.iter().map(Vec::len).sum::<usize>(), 13);
+        for batch in &batches {
+            let mint = batch[0].transfer.mint;
+            assert!(batch.iter().all(|p| p.transfer.mint == mint));
+            let transfers: Vec<_> = batch.iter().flat_map(|p| p.transfers()).copied().collect();
+            assert!(estimated_transaction_size(&fee_payer, &transfers) <= MAX_TRANSACTION_SIZE);
+        }
+        // Distinct sellers each add a signature, so 12 cannot share one transaction
+        assert!(batches.len() > 2);
+    }
+
+    #[test]
+    fn test_same_seller_shares_signature_and_respects_max() {
+        let fee_payer = Pubkey::new_unique();
+        let seller = Keypair::new();
+        let mint = Pubkey::new_unique();
+        let items: Vec<_> = (0..5).map(|_| prepared(&seller, mint, false)).collect();
+
+        let batches = plan_batches(&fee_payer, items, 2);
+        assert_eq!(batches.iter().map(Vec::len).collect::<Vec<_>>(), vec![2, 2, 1]);
+    }
+
+    #[test]
+    fn test_failed_batch_falls_back_only_when_nothing_landed() {
+        let (first, second) = (Signature::new_unique(), Signature::new_unique());
+        let network = SettlementError::Network("confirmation timed out".to_string());
+
+        // Rejected in simulation: nothing was transferred
+        assert_eq!(
+            classify_failed_batch(&SettlementError::ProgramError(1), &[]),
+            Err(UnsettledBatch::NotLanded)
+        );
+        // A retry after a timeout landed
+        assert_eq!(
+            classify_failed_batch(
+                &network,
+                &[(first, TransactionStatus::Pending), (second, TransactionStatus::Confirmed(1))]
+            ),
+            Ok(second)
+        );
+        // Every attempt executed and failed
+        assert_eq!(
+            classify_failed_batch(&network, &[(first, TransactionStatus::Failed("custom program error".to_string()))]),
+            Err(UnsettledBatch::NotLanded)
+        );
+        // Unseen attempts may still land
+        assert_eq!(
+            classify_failed_batch(&network, &[(first, TransactionStatus::Pending)]),
+            Err(UnsettledBatch::Unresolved)
+        );
+        assert_eq!(classify_failed_batch(&network, &[]), Err(UnsettledBatch::Unresolved));
+    }
+}
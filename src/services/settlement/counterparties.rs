@@ -0,0 +1,105 @@
+//! Checks on a settlement's buyer and seller before anything is transferred
+//!
+//! A settlement between a user and themselves, or with a counterparty that
+//! has no usable wallet, would otherwise fail deep inside the transfer with
+//! an error that reads like a transient chain problem and gets retried. These
+//! checks reject it up front with a reason that will not change on retry.
+
+use solana_sdk::pubkey::Pubkey;
+use std::str::FromStr;
+use uuid::Uuid;
+
+use crate::error::ApiError;
+
+/// Why a settlement cannot be executed
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum SettlementRejection {
+    #[error("buyer and seller are the same user {0}")]
+    SelfTrade(Uuid),
+
+    #[error("{role} {user_id} has no wallet connected")]
+    MissingWallet { role: &'static str, user_id: Uuid },
+
+    #[error("{role} {user_id} wallet '{wallet}' is not a valid address")]
+    InvalidWallet {
+        role: &'static str,
+        user_id: Uuid,
+        wallet: String,
+    },
+
+    #[error("seller wallet identity mismatch: DB={wallet} Decrypted={decrypted}")]
+    IdentityMismatch { wallet: String, decrypted: String },
+}
+
+/// Rejections are validation errors, which the retry job never retries
+impl From<SettlementRejection> for ApiError {
+    fn from(rejection: SettlementRejection) -> Self {
+        ApiError::Validation(format!("Settlement rejected: {}", rejection))
+    }
+}
+
+/// Buyer and seller must be different users
+pub fn check_distinct(buyer_id: Uuid, seller_id: Uuid) -> Result<(), SettlementRejection> {
+    if buyer_id == seller_id {
+        return Err(SettlementRejection::SelfTrade(buyer_id));
+    }
+    Ok(())
+}
+
+/// `wallet` of the `role` party as a public key
+pub fn check_wallet(role: &'static str, user_id: Uuid, wallet: Option<&str>) -> Result<Pubkey, SettlementRejection> {
+    let wallet = wallet
+        .map(str::trim)
+        .filter(|wallet| !wallet.is_empty())
+        .ok_or(SettlementRejection::MissingWallet { role, user_id })?;
+
+    Pubkey::from_str(wallet).map_err(|_| SettlementRejection::InvalidWallet {
+        role,
+        user_id,
+        wallet: wallet.to_string(),
+    })
+}
+
+/// The decrypted seller key must belong to the seller's recorded wallet
+pub fn check_seller_identity(wallet: &str, decrypted: &Pubkey) -> Result<(), SettlementRejection> {
+    if decrypted.to_string() != wallet {
+        return Err(SettlementRejection::IdentityMismatch {
+            wallet: wallet.to_string(),
+            decrypted: decrypted.to_string(),
+        });
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_counterparty_checks_give_specific_reasons() {
+        let user = Uuid::new_v4();
+        assert_eq!(check_distinct(user, user), Err(SettlementRejection::SelfTrade(user)));
+        assert!(check_distinct(user, Uuid::new_v4()).is_ok());
+
+        assert_eq!(
+            check_wallet("buyer", user, None),
+            Err(SettlementRejection::MissingWallet { role: "buyer", user_id: user })
+        );
+        assert_eq!(
+            check_wallet("seller", user, Some("  ")),
+            Err(SettlementRejection::MissingWallet { role: "seller", user_id: user })
+        );
+        assert!(matches!(
+            check_wallet("seller", user, Some("not-a-key")),
+            Err(SettlementRejection::InvalidWallet { role: "seller", .. })
+        ));
+        let key = Pubkey::new_unique();
+        assert_eq!(check_wallet("buyer", user, Some(&key.to_string())), Ok(key));
+
+        assert!(check_seller_identity(&key.to_string(), &key).is_ok());
+        assert!(check_seller_identity(&key.to_string(), &Pubkey::new_unique()).is_err());
+
+        let error: ApiError = SettlementRejection::SelfTrade(user).into();
+        assert!(matches!(error, ApiError::Validation(msg) if msg.contains("same user")));
+    }
+}
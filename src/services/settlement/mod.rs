@@ -1,33 +1,56 @@
+pub mod batching;
+pub mod counterparties;
+pub mod persistence;
+pub mod retry_policy;
+pub mod revenue_split;
+pub mod rounding;
+pub mod schedule;
+pub mod throttle;
 pub mod types;
 
 use anyhow::Result;
-use chrono::Utc;
+use chrono::{DateTime, Utc};
 use rust_decimal::Decimal;
 use rust_decimal::prelude::ToPrimitive;
 use sqlx::PgPool;
 use std::sync::Arc;
 use std::time::Duration;
 use tokio::sync::RwLock;
+use tokio_util::sync::CancellationToken;
 use tracing::{debug, error, info, warn};
 use uuid::Uuid;
 
+use crate::config::ReloadableConfig;
 use crate::error::ApiError;
 use crate::services::market_clearing::TradeMatch;
-use crate::services::BlockchainService;
+use crate::services::{AuditEvent, AuditLogger, BlockchainService, WebSocketService};
+use crate::services::blockchain::{BlockchainClient, SettlementError, TokenTransfer};
 use crate::services::erc::{ErcService, IssueErcRequest};
+use crate::services::fees::FeeCalculator;
+use crate::services::job_coordinator::BackgroundJobCoordinator;
 use crate::services::notification::{NotificationService, SettlementNotification};
 use crate::handlers::websocket::broadcaster::broadcast_settlement_complete;
 use crate::middleware::metrics;
+use crate::utils::units::{to_atomic, ConversionError};
 use futures::{stream, StreamExt};
-use solana_sdk::signature::{Signature, Signer};
-
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{Keypair, Signature, Signer};
+
+use batching::PreparedTransfer;
+pub use counterparties::SettlementRejection;
+pub use persistence::{insert_settlement, settlement_from_row, SETTLEMENT_SELECT};
+pub use retry_policy::{RetryDecision, RetryOverride, RetryOverrides, RetryRuleSource};
+pub use revenue_split::{RevenueRecipient, RevenueShare, RevenueSplit, RevenueSplits};
+pub use rounding::{EnergyRounding, EnergySplit};
+pub use schedule::{SettlementSchedule, SettlementScheduleStatus};
+pub use throttle::AdaptiveDelay;
 pub use types::*;
 
 /// Settlement service for blockchain transaction execution
 #[derive(Clone)]
 pub struct SettlementService {
     db: PgPool,
-    blockchain: BlockchainService,
+    blockchain: Arc<dyn BlockchainClient>,
     config: SettlementConfig,
     encryption_secret: String,
     #[allow(dead_code)]
@@ -36,6 +59,20 @@ pub struct SettlementService {
     erc_service: Option<ErcService>,
     /// Notification service for email alerts
     notification_service: NotificationService,
+    /// Records settlement lifecycle events for compliance reconstruction
+    audit_logger: AuditLogger,
+    /// Hot-reloadable settings; overrides the schedule's base fee rate when set
+    runtime_config: Option<ReloadableConfig>,
+    /// Cancelled on process shutdown; no new settlements start once set
+    shutdown: CancellationToken,
+    /// Background job lease; no new settlements start while another replica holds it
+    job_coordinator: Option<BackgroundJobCoordinator>,
+    /// Pacing between settlements; widens while the RPC provider rate limits
+    throttle: Arc<AdaptiveDelay>,
+    /// Tells buyers and sellers when their settlement fails
+    websocket_service: Option<WebSocketService>,
+    /// Environment and database retry overrides currently in effect
+    retry_overrides: Arc<std::sync::RwLock<RetryOverrides>>,
 }
 
 impl SettlementService {
@@ -51,18 +88,83 @@ impl SettlementService {
     ) -> Self {
         // Create ErcService with cloned db and blockchain
         let erc_service = Some(ErcService::new(db.clone(), blockchain.clone()));
-        
+
+        Self {
+            erc_service,
+            ..Self::with_client(db, Arc::new(blockchain), config, encryption_secret)
+        }
+    }
+
+    /// Settlement service over any [`BlockchainClient`]; RECs are not issued
+    pub fn with_client(
+        db: PgPool,
+        blockchain: Arc<dyn BlockchainClient>,
+        config: SettlementConfig,
+        encryption_secret: String,
+    ) -> Self {
         // Create NotificationService
         let notification_service = NotificationService::new(db.clone());
-        
+        let audit_logger = AuditLogger::new(db.clone());
+        let retry_overrides = Arc::new(std::sync::RwLock::new(config.retry_overrides.clone()));
+
         Self {
             db,
             blockchain,
             config,
             encryption_secret,
             pending_settlements: Arc::new(RwLock::new(Vec::new())),
-            erc_service,
+            erc_service: None,
             notification_service,
+            audit_logger,
+            runtime_config: None,
+            shutdown: CancellationToken::new(),
+            job_coordinator: None,
+            throttle: Arc::new(AdaptiveDelay::from_env()),
+            websocket_service: None,
+            retry_overrides,
+        }
+    }
+
+    /// Stop starting new settlements once `shutdown` is cancelled
+    pub fn with_shutdown(mut self, shutdown: CancellationToken) -> Self {
+        self.shutdown = shutdown;
+        self
+    }
+
+    /// Stop starting new settlements as soon as this replica loses the
+    /// background job lease, not just at the next loop iteration
+    pub fn with_job_coordinator(mut self, job_coordinator: BackgroundJobCoordinator) -> Self {
+        self.job_coordinator = Some(job_coordinator);
+        self
+    }
+
+    /// Whether another settlement may be started now
+    ///
+    /// Checked right before each transfer: a batch can outlast the lease,
+    /// and a replica that lost it must not race the new leader on-chain.
+    fn may_start_settlement(&self) -> bool {
+        !self.shutdown.is_cancelled()
+            && self.job_coordinator.as_ref().is_none_or(|c| c.is_leader())
+    }
+
+    /// Notify the counterparties of failed settlements over WebSocket
+    pub fn with_websocket(mut self, websocket_service: WebSocketService) -> Self {
+        self.websocket_service = Some(websocket_service);
+        self
+    }
+
+    /// Read the fee rate through a hot-reloadable config handle
+    pub fn with_runtime_config(mut self, runtime_config: ReloadableConfig) -> Self {
+        self.runtime_config = Some(runtime_config);
+        self
+    }
+
+    /// Fee calculator for the schedule currently in effect
+    fn fee_calculator(&self) -> FeeCalculator {
+        let calculator = FeeCalculator::new(self.config.fee_schedule.clone());
+        match &self.runtime_config {
+            Some(runtime) => calculator.with_base_rate(runtime.settlement_fee_rate()),
+            None => calculator,
         }
     }
 
@@ -134,11 +236,15 @@ impl SettlementService {
     /// Create a single settlement from a trade match
     pub async fn create_settlement(&self, trade: &TradeMatch) -> Result<Settlement, ApiError> {
         info!("Creating settlement for trade match: {}", trade.match_id);
+        counterparties::check_distinct(trade.buyer_id, trade.seller_id)?;
 
         // Calculate values using passed trade info
         let total_value = trade.total_value;
-        let fee_rate = self.config.fee_rate;
-        let fee_amount = total_value * fee_rate;
+        // The seller pays the platform fee out of their proceeds
+        let fee_amount = self
+            .fee_calculator()
+            .fee_for_user(&self.db, trade.seller_id, total_value)
+            .await;
         
         // Net Amount = Total Value - Fees - Wheeling Charges
         let wheeling_charge = trade.wheeling_charge;
@@ -227,7 +333,8 @@ impl SettlementService {
 
         let settlement = Settlement {
             id: Uuid::new_v4(),
-            trade_id: trade.id,
+            epoch_id: trade.epoch_id,
+            match_id: Some(trade.match_id),
             buyer_id: trade.buyer_id,
             seller_id: trade.seller_id,
             buy_order_id: trade.buy_order_id,
@@ -248,44 +355,12 @@ impl SettlementService {
             
             status,
             blockchain_tx: None,
+            batch_signature: None,
             created_at: Utc::now(),
             confirmed_at: None,
         };
 
-        sqlx::query(
-            r#"
-            INSERT INTO settlements (
-                id, buyer_id, seller_id, buy_order_id, sell_order_id,
-                energy_amount, price_per_kwh, total_amount, fee_amount, net_amount, status, created_at,
-                wheeling_charge, loss_factor, loss_cost, effective_energy, buyer_zone_id, seller_zone_id, epoch_id,
-                buyer_session_token, seller_session_token
-            )
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18, $19, $20, $21)
-            "#,
-        )
-        .bind(settlement.id)
-        .bind(settlement.buyer_id)
-        .bind(settlement.seller_id)
-        .bind(settlement.buy_order_id)
-        .bind(settlement.sell_order_id)
-        .bind(settlement.energy_amount)
-        .bind(settlement.price)
-        .bind(settlement.total_value)
-        .bind(settlement.fee_amount)
-        .bind(settlement.net_amount)
-        .bind(settlement.status.to_string())
-        .bind(settlement.created_at)
-        .bind(settlement.wheeling_charge)
-        .bind(settlement.loss_factor)
-        .bind(settlement.loss_cost)
-        .bind(settlement.effective_energy)
-        .bind(settlement.buyer_zone_id)
-        .bind(settlement.seller_zone_id)
-        .bind(trade.epoch_id)
-        .bind(&settlement.buyer_session_token)
-        .bind(&settlement.seller_session_token)
-        .execute(&self.db)
-        .await?;
+        insert_settlement(&self.db, &settlement).await?;
 
         info!(
             "📝 Created settlement {}: {} kWh at ${} (buyer: {}, seller: {})",
@@ -311,6 +386,14 @@ impl SettlementService {
         // Get settlement details
         let settlement = self.get_settlement(settlement_id).await?;
 
+        self.audit_logger.log_async(AuditEvent::SettlementStarted {
+            settlement_id,
+            buyer_id: settlement.buyer_id,
+            seller_id: settlement.seller_id,
+            energy_amount: settlement.energy_amount.to_string(),
+            total_amount: settlement.total_value.to_string(),
+        });
+
         // 1. Handle Briding Status
         if settlement.status == SettlementStatus::PendingBridge {
             match self.execute_bridge_initiation(&settlement).await {
@@ -326,62 +409,25 @@ impl SettlementService {
                 Err(e) => {
                     error!("❌ Bridge initiation failed for {}: {}", settlement_id, e);
                     self.update_settlement_status(settlement_id, SettlementStatus::Failed).await?;
+                    self.audit_logger.log_async(AuditEvent::SettlementFailed {
+                        settlement_id,
+                        reason: e.to_string(),
+                        permanent: false,
+                    });
+                    self.announce_failure(&settlement, &e).await;
                     return Err(e);
                 }
             }
         }
 
-        // 2. Execute normal blockchain transaction
-        match self.execute_blockchain_transfer(&settlement).await {
+        // 2. Execute normal blockchain transaction, once both parties can settle
+        let outcome = match self.validate_counterparties(&settlement).await {
+            Ok(()) => self.execute_blockchain_transfer(&settlement).await,
+            Err(e) => Err(e),
+        };
+        match outcome {
             Ok(tx_result) => {
-                // Update settlement with transaction signature
-                self.update_settlement_confirmed(
-                    settlement_id,
-                    &tx_result.signature,
-                    SettlementStatus::Completed,
-                )
-                .await?;
-
-                // Finalize Escrow (Move funds and unlock energy)
-                if let Err(e) = self.finalize_escrow(&settlement).await {
-                    error!("⚠️ Failed to finalize escrow for settlement {}: {}", settlement_id, e);
-                    // We don't fail the whole method if escrow finalization fails here, 
-                    // but it should be noted. In production, this should be retryable.
-                }
-
-                // Broadcast settlement completion via WebSocket
-                if let Err(e) = broadcast_settlement_complete(
-                    settlement.id,
-                    settlement.buyer_id,
-                    settlement.seller_id,
-                    settlement.energy_amount.to_string(),
-                    settlement.total_value.to_string(),
-                    Some(tx_result.signature.clone()),
-                ).await {
-                    error!("⚠️ Failed to broadcast settlement: {}", e);
-                }
-
-                // Send email notifications to buyer and seller
-                self.send_settlement_notifications(&settlement, &tx_result.signature).await;
-
-                // Issue REC (Renewable Energy Certificate) to seller
-                if let Err(e) = self.issue_rec_for_settlement(&settlement).await {
-                    error!("⚠️ Failed to issue REC for settlement {}: {}", settlement_id, e);
-                    // Non-blocking - settlement completed, REC issuance is secondary
-                }
-
-                info!(
-                    "✅ Settlement {} completed: tx {}",
-                    settlement_id, tx_result.signature
-                );
-
-                // Record success metrics
-                metrics::track_settlement(true);
-                metrics::track_revenue("fee", settlement.fee_amount.to_f64().unwrap_or(0.0));
-                if let Some(wheeling) = settlement.wheeling_charge {
-                    metrics::track_revenue("wheeling", wheeling.to_f64().unwrap_or(0.0));
-                }
-
+                self.complete_settlement(&settlement, &tx_result).await?;
                 Ok(tx_result)
             }
             Err(e) => {
@@ -391,17 +437,142 @@ impl SettlementService {
                 self.update_settlement_status(settlement_id, SettlementStatus::Failed)
                     .await?;
 
+                self.audit_logger.log_async(AuditEvent::SettlementFailed {
+                    settlement_id,
+                    reason: e.to_string(),
+                    permanent: false,
+                });
+
                 // Record failure metric
                 metrics::track_settlement(false);
 
-                Err(ApiError::Internal(format!(
-                    "Settlement execution failed: {}",
-                    e
-                )))
+                // Keep the classified transfer error and counterparty
+                // rejections for the retry job
+                let error = match e {
+                    ApiError::Settlement(_) | ApiError::Validation(_) => e,
+                    other => ApiError::Internal(format!("Settlement execution failed: {}", other)),
+                };
+                self.announce_failure(&settlement, &error).await;
+                Err(error)
             }
         }
     }
 
+    /// Reject a settlement whose parties cannot settle, before any transfer
+    ///
+    /// Wallets are only used, and so only checked, when transfers are real.
+    async fn validate_counterparties(&self, settlement: &Settlement) -> Result<(), ApiError> {
+        counterparties::check_distinct(settlement.buyer_id, settlement.seller_id)?;
+        if !self.config.enable_real_blockchain {
+            return Ok(());
+        }
+
+        let buyer_wallet = self.find_user_wallet(&settlement.buyer_id).await?;
+        counterparties::check_wallet("buyer", settlement.buyer_id, buyer_wallet.as_deref())?;
+        let seller_wallet = self.find_user_wallet(&settlement.seller_id).await?;
+        counterparties::check_wallet("seller", settlement.seller_id, seller_wallet.as_deref())?;
+        Ok(())
+    }
+
+    /// Tell the buyer and seller their settlement failed
+    ///
+    /// This is the only announcement per failed attempt: the retry job marks
+    /// non-retryable failures permanent using the same classification, so
+    /// `retryable` already tells clients whether it will be retried.
+    async fn announce_failure(&self, settlement: &Settlement, error: &ApiError) {
+        if let Some(ws) = &self.websocket_service {
+            let retryable = self.classify_failure(error).retryable;
+            ws.broadcast_settlement_failed(
+                settlement.id,
+                settlement.buyer_id,
+                settlement.seller_id,
+                Self::public_failure_reason(error).to_string(),
+                retryable,
+            )
+            .await;
+        }
+    }
+
+    /// Failure reason safe to show the counterparties
+    ///
+    /// Raw errors carry RPC responses, wallet addresses and internal
+    /// messages; clients only get the category.
+    fn public_failure_reason(error: &ApiError) -> &'static str {
+        match error {
+            ApiError::Settlement(SettlementError::InsufficientFunds(_)) => "insufficient funds for the transfer",
+            ApiError::Settlement(
+                SettlementError::BlockhashExpired | SettlementError::RateLimited | SettlementError::Network(_),
+            ) => "temporary blockchain network issue",
+            ApiError::Settlement(SettlementError::AccountNotFound(_)) => "a required token account does not exist",
+            ApiError::Settlement(_) => "transfer rejected on-chain",
+            ApiError::Validation(_) => "a counterparty cannot settle this trade",
+            _ => "internal settlement error",
+        }
+    }
+
+    /// Record a confirmed transfer and run the post-settlement steps: escrow
+    /// release, WebSocket broadcast, notifications, REC issuance and metrics
+    async fn complete_settlement(
+        &self,
+        settlement: &Settlement,
+        tx_result: &SettlementTransaction,
+    ) -> Result<(), ApiError> {
+        // Update settlement with transaction signature
+        self.update_settlement_confirmed(
+            settlement.id,
+            &tx_result.signature,
+            SettlementStatus::Completed,
+        )
+        .await?;
+
+        self.audit_logger.log_async(AuditEvent::SettlementCompleted {
+            settlement_id: settlement.id,
+            signature: tx_result.signature.clone(),
+        });
+
+        // Finalize Escrow (Move funds and unlock energy)
+        if let Err(e) = self.finalize_escrow(settlement).await {
+            error!("⚠️ Failed to finalize escrow for settlement {}: {}", settlement.id, e);
+            // We don't fail the whole method if escrow finalization fails here, 
+            // but it should be noted. In production, this should be retryable.
+        }
+
+        // Broadcast settlement completion via WebSocket
+        if let Err(e) = broadcast_settlement_complete(
+            settlement.id,
+            settlement.buyer_id,
+            settlement.seller_id,
+            settlement.energy_amount.to_string(),
+            settlement.total_value.to_string(),
+            Some(tx_result.signature.clone()),
+        ).await {
+            error!("⚠️ Failed to broadcast settlement: {}", e);
+        }
+
+        // Send email notifications to buyer and seller
+        self.send_settlement_notifications(settlement, &tx_result.signature).await;
+
+        // Issue REC (Renewable Energy Certificate) to seller
+        if let Err(e) = self.issue_rec_for_settlement(settlement).await {
+            error!("⚠️ Failed to issue REC for settlement {}: {}", settlement.id, e);
+            // Non-blocking - settlement completed, REC issuance is secondary
+        }
+
+        info!(
+            "✅ Settlement {} completed: tx {}",
+            settlement.id, tx_result.signature
+        );
+
+        // Record success metrics
+        metrics::track_settlement(true);
+        metrics::track_revenue("fee", settlement.fee_amount.to_f64().unwrap_or(0.0));
+        if let Some(wheeling) = settlement.wheeling_charge {
+            metrics::track_revenue("wheeling", wheeling.to_f64().unwrap_or(0.0));
+        }
+
+        Ok(())
+    }
+
     /// Execute actual blockchain transfer
     async fn execute_blockchain_transfer(
         &self,
@@ -422,47 +593,112 @@ impl SettlementService {
             });
         }
 
+        let prepared = self.prepare_transfer(settlement).await?;
+        self.send_transfer(prepared).await
+    }
+
+    /// Submit a prepared seller -> buyer transfer, plus its grid loss transfer
+    async fn send_transfer(&self, prepared: PreparedTransfer) -> Result<SettlementTransaction, ApiError> {
+        let transfer = prepared.transfer;
+
+        // Execute Token Transfer (Seller -> Buyer)
+        info!(
+            "Executing Direct Token Transfer: From {} to {}, Amount: {} (atomic), Decimals: {}",
+            transfer.from, transfer.to, transfer.amount, transfer.decimals
+        );
+
+        let signature = self
+            .blockchain
+            .transfer_tokens(
+                &prepared.seller_keypair, // Signer (Owner of From Account)
+                &transfer.from,           // From (Seller ATA)
+                &transfer.to,             // To (Buyer ATA)
+                &transfer.mint,
+                transfer.amount,
+                transfer.decimals,
+            )
+            .await
+            .map_err(|e| ApiError::Settlement(SettlementError::from_anyhow(&e)))?;
+
+        if let Some(loss) = prepared.loss_transfer {
+            info!("📉 Recording {} loss tokens to grid loss sink", loss.amount);
+            let _ = self
+                .blockchain
+                .transfer_tokens(&prepared.seller_keypair, &loss.from, &loss.to, &loss.mint, loss.amount, loss.decimals)
+                .await;
+        }
+
+        info!("Settlement transfer completed. Signature: {}", signature);
+
+        // Get current slot for confirmation
+        let slot = self
+            .blockchain
+            .get_slot()
+            .await
+            .map_err(|e| ApiError::Internal(format!("Failed to get slot: {}", e)))?;
+
+        // Create settlement transaction record
+        Ok(SettlementTransaction {
+            settlement_id: prepared.settlement_id,
+            signature: signature.to_string(),
+            slot,
+            confirmation_status: "confirmed".to_string(),
+        })
+    }
+
+    /// Resolve the seller's keypair, both token accounts and atomic amounts
+    /// for a settlement's seller -> buyer transfer
+    async fn prepare_transfer(&self, settlement: &Settlement) -> Result<PreparedTransfer, ApiError> {
         // 1. Get buyer and seller wallets from database
         let buyer_wallet = self.get_user_wallet(&settlement.buyer_id).await?;
         let seller_wallet = self.get_user_wallet(&settlement.seller_id).await?;
 
-        // 2. Parse wallet addresses
-        let buyer_pubkey = BlockchainService::parse_pubkey(&buyer_wallet)
-            .map_err(|e| ApiError::Internal(format!("Invalid buyer wallet: {}", e)))?;
-        let _seller_pubkey = BlockchainService::parse_pubkey(&seller_wallet)
-            .map_err(|e| ApiError::Internal(format!("Invalid seller wallet: {}", e)))?;
-
-        // 3. Get mint address from environment
+        // 2. Get mint address from environment
         let mint_str = std::env::var("ENERGY_TOKEN_MINT")
             .map_err(|e| ApiError::Internal(format!("ENERGY_TOKEN_MINT not set: {}", e)))?;
         let mint = BlockchainService::parse_pubkey(&mint_str)
             .map_err(|e| ApiError::Internal(format!("Invalid mint config: {}", e)))?;
 
-        // 4. Get authority keypair (Platform)
-        let _platform_authority = self
-            .blockchain
-            .get_authority_keypair()
+        // 3. Decrypt Seller Keypair (CRITICAL FIX: Seller must sign transfer)
+        let seller_keypair = self.get_user_keypair(&settlement.seller_id, settlement.seller_session_token.as_deref()).await?;
+
+        // Best effort: a missing or invalid sink leaves the loss with the seller
+        let loss_sink_wallet = std::env::var("GRID_LOSS_SINK_WALLET").unwrap_or_else(|_| "LoSsSiNk1111111111111111111111111111111111".to_string());
+        let loss_sink = BlockchainService::parse_pubkey(&loss_sink_wallet).ok();
+
+        self.build_transfer(settlement, &buyer_wallet, &seller_wallet, seller_keypair, mint, loss_sink)
             .await
-            .map_err(|e| ApiError::Internal(format!("Failed to get authority: {}", e)))?;
+    }
 
-        // 5. Decrypt Seller Keypair (CRITICAL FIX: Seller must sign transfer)
-        let seller_keypair = self.get_user_keypair(&settlement.seller_id, settlement.seller_session_token.as_deref()).await?;
+    /// Check the seller's identity, resolve token accounts and convert the
+    /// settlement's energy into atomic token amounts
+    async fn build_transfer(
+        &self,
+        settlement: &Settlement,
+        buyer_wallet: &str,
+        seller_wallet: &str,
+        seller_keypair: Keypair,
+        mint: Pubkey,
+        loss_sink: Option<Pubkey>,
+    ) -> Result<PreparedTransfer, ApiError> {
         let seller_decrypted_pubkey = seller_keypair.pubkey();
-        
-        // High visibility debug logs
-        println!("DEBUG: Settlement {} - Decrypted seller PK: {}", settlement.id, seller_decrypted_pubkey);
-        error!("DEBUG: Settlement {} - Decrypted seller PK: {}", settlement.id, seller_decrypted_pubkey);
 
         // CRITICAL CHECK: Does the decrypted key match the wallet we expect?
-        if seller_decrypted_pubkey.to_string() != seller_wallet {
-            println!("❌ IDENTITY MISMATCH! DB={} Decrypted={}", seller_wallet, seller_decrypted_pubkey);
-            error!("❌ IDENTITY MISMATCH! DB wallet is {}, but decrypted key is {}. Aborting settlement.", seller_wallet, seller_decrypted_pubkey);
-            return Err(ApiError::Internal(format!(
-                "Wallet identity mismatch: DB={} Decrypted={}",
-                seller_wallet, seller_decrypted_pubkey
-            )));
+        // Checked before anything else so a mismatch never touches the chain.
+        if let Err(rejection) = counterparties::check_seller_identity(seller_wallet, &seller_decrypted_pubkey) {
+            error!("❌ {} for settlement {}. Aborting settlement.", rejection, settlement.id);
+            return Err(rejection.into());
         }
 
+        let buyer_pubkey = counterparties::check_wallet("buyer", settlement.buyer_id, Some(buyer_wallet))?;
+
+        // Get authority keypair (Platform)
+        let _platform_authority = self
+            .blockchain
+            .get_authority_keypair()
+            .await
+            .map_err(|e| ApiError::Internal(format!("Failed to get authority: {}", e)))?;
+
         let seller_actual_pubkey = seller_decrypted_pubkey;
 
         let buyer_token_account = self
@@ -481,78 +717,59 @@ impl SettlementService {
                 ApiError::Internal(format!("Failed to create seller token account: {}", e))
             })?;
 
-        // 7. Calculate match amount (in Wh, same as order creation: kWh * 1000)
-        let match_amount_wh = {
-            let amount_wh = settlement.energy_amount * Decimal::from(1000i64);
-            // Use floor() to get integer part, then convert to u64
-            amount_wh.floor().to_string().parse::<u64>().unwrap_or(0)
-        };
+        // Calculate match amount (in Wh, same as order creation: kWh * 1000)
+        let match_amount_wh = to_atomic(settlement.energy_amount, 3)
+            .map_err(|e| ApiError::Validation(format!("Invalid settlement amount: {}", e)))?;
 
         info!(
             "🔍 Settlement energy_amount: {}, match_amount_wh: {}",
             settlement.energy_amount, match_amount_wh
         );
 
-        // 8. Execute Token Transfer (Seller -> Buyer)
-        // Only transfer the EFFECTIVE energy to the buyer.
-        let effective_energy = settlement.effective_energy.unwrap_or(settlement.energy_amount);
-        let amount_atomic = effective_energy * Decimal::from(1_000_000_000);
-        let transfer_amount = amount_atomic
-            .trunc()
-            .to_string()
-            .parse::<u64>()
-            .unwrap_or(0);
-
-        info!(
-            "Executing Direct Token Transfer: From {} to {}, Amount: {} (atomic), Decimals: 9 (Effective Energy: {})",
-            seller_token_account, buyer_token_account, transfer_amount, effective_energy
-        );
-
-        let signature = self
+        let decimals = self
             .blockchain
-            .transfer_tokens(
-                &seller_keypair,   // Signer (Owner of From Account)
-                &seller_token_account, // From (Seller ATA)
-                &buyer_token_account,  // To (Buyer ATA)
-                &mint,
-                transfer_amount,
-                9, // Decimals
-            )
+            .decimals_for(&mint)
             .await
-            .map_err(|e| ApiError::Internal(format!("Token transfer failed: {}", e)))?;
+            .map_err(|e| ApiError::Internal(format!("Failed to resolve energy mint decimals: {}", e)))?;
+
+        // Only the EFFECTIVE energy goes to the buyer; the rest of the seller's
+        // debit is grid loss
+        let effective_energy = settlement.effective_energy.unwrap_or(settlement.energy_amount);
+        let split = self
+            .config
+            .energy_rounding
+            .split_atomic(settlement.energy_amount, effective_energy, decimals)
+            .map_err(|e| ApiError::Validation(format!("Invalid transfer amount: {}", e)))?;
+
+        let transfer = TokenTransfer {
+            owner: seller_actual_pubkey,
+            from: seller_token_account,
+            to: buyer_token_account,
+            mint,
+            amount: split.buyer_atomic,
+            decimals,
+        };
 
         // Handle grid loss: the difference between energy_amount (gross) and effective_energy
-        // remain in the seller's account if we only transfer the effective amount.
-        // To properly account for it, we should 'burn' these tokens or transfer them to a loss sink.
-        let loss_energy = settlement.energy_amount - effective_energy;
-        if loss_energy > Decimal::ZERO {
-            let loss_atomic = (loss_energy * Decimal::from(1_000_000_000)).trunc().to_string().parse::<u64>().unwrap_or(0);
-            if loss_atomic > 0 {
-                let loss_sink_wallet = std::env::var("GRID_LOSS_SINK_WALLET").unwrap_or_else(|_| "LoSsSiNk1111111111111111111111111111111111".to_string());
-                if let Ok(sink_pubkey) = BlockchainService::parse_pubkey(&loss_sink_wallet) {
-                    if let Ok(sink_token_account) = self.blockchain.ensure_token_account_exists(&_platform_authority, &sink_pubkey, &mint).await {
-                        info!("📉 Recording {} loss tokens to grid loss sink", loss_atomic);
-                        let _ = self.blockchain.transfer_tokens(&seller_keypair, &seller_token_account, &sink_token_account, &mint, loss_atomic, 9).await;
-                    }
+        // would remain in the seller's account, so it is sent to a loss sink instead.
+        let mut loss_transfer = None;
+        if split.loss_atomic > 0 {
+            if let Some(sink_pubkey) = loss_sink {
+                if let Ok(sink_token_account) = self.blockchain.ensure_token_account_exists(&_platform_authority, &sink_pubkey, &mint).await {
+                    loss_transfer = Some(TokenTransfer {
+                        to: sink_token_account,
+                        amount: split.loss_atomic,
+                        ..transfer
+                    });
                 }
             }
         }
 
-        info!("Settlement transfer completed. Signature: {}", signature);
-
-        // 9. Get current slot for confirmation
-        let slot = self
-            .blockchain
-            .get_slot()
-            .await
-            .map_err(|e| ApiError::Internal(format!("Failed to get slot: {}", e)))?;
-
-        // 10. Create settlement transaction record
-        Ok(SettlementTransaction {
+        Ok(PreparedTransfer {
             settlement_id: settlement.id,
-            signature: signature.to_string(),
-            slot,
-            confirmation_status: "confirmed".to_string(),
+            seller_keypair,
+            transfer,
+            loss_transfer,
         })
     }
 
@@ -572,7 +789,9 @@ impl SettlementService {
         // For simulation, we use a placeholder or derive it
         let sell_order = BlockchainService::parse_pubkey("Fmk6vb74MjZpXVE9kAS5q4U5L8hr2AEJcDikfRSFTiyY").unwrap();
 
-        let amount_atomic = (settlement.energy_amount * Decimal::from(1_000_000_000)).trunc().to_string().parse::<u64>().unwrap_or(0);
+        let decimals = self.configured_mint_decimals("ENERGY_TOKEN_MINT").await?;
+        let amount_atomic = to_atomic(settlement.energy_amount, decimals)
+            .map_err(|e| ApiError::Validation(format!("Invalid bridge amount: {}", e)))?;
         
         let target_chain = 1; // Simulated target chain ID
         let target_address = [0u8; 32]; // Simulated target address
@@ -609,6 +828,17 @@ impl SettlementService {
             )))
     }
 
+    /// Helper: Decimals of the mint configured in the `var` environment variable
+    async fn configured_mint_decimals(&self, var: &str) -> Result<u8, ApiError> {
+        let mint_str = std::env::var(var).map_err(|e| ApiError::Internal(format!("{} not set: {}", var, e)))?;
+        let mint = BlockchainService::parse_pubkey(&mint_str)
+            .map_err(|e| ApiError::Internal(format!("Invalid {} config: {}", var, e)))?;
+        self.blockchain
+            .decimals_for(&mint)
+            .await
+            .map_err(|e| ApiError::Internal(format!("Failed to resolve {} decimals: {}", var, e)))
+    }
+
     /// Helper: Get user wallet address from database
     async fn get_user_wallet(&self, user_id: &Uuid) -> Result<String, ApiError> {
         let result = sqlx::query!("SELECT wallet_address FROM users WHERE id = $1", user_id)
@@ -621,6 +851,16 @@ impl SettlementService {
             .ok_or_else(|| ApiError::Internal(format!("User {} has no wallet connected", user_id)))
     }
 
+    /// Helper: Wallet address of a user, `None` if unset or the user is gone
+    async fn find_user_wallet(&self, user_id: &Uuid) -> Result<Option<String>, ApiError> {
+        sqlx::query_scalar::<_, Option<String>>("SELECT wallet_address FROM users WHERE id = $1")
+            .bind(user_id)
+            .fetch_optional(&self.db)
+            .await
+            .map(Option::flatten)
+            .map_err(ApiError::Database)
+    }
+
     /// Helper: Get Order PDA from database
     async fn _get_order_pda(&self, order_id: Uuid) -> Result<String, ApiError> {
         let result = sqlx::query!(
@@ -636,36 +876,142 @@ impl SettlementService {
             .ok_or_else(|| ApiError::Internal(format!("Order {} has no PDA stored", order_id)))
     }
 
-    /// Process all pending settlements in parallel
+    /// Process pending settlements in parallel, honouring the settlement windows
+    ///
+    /// Outside every window only urgent settlements are executed; the rest
+    /// stay pending until the next window opens.
     pub async fn process_pending_settlements(&self) -> Result<usize, ApiError> {
+        let window_open = self.config.schedule.is_open(Utc::now());
+        let pending_ids = self.get_pending_settlements_filtered(!window_open).await?;
+
+        if !window_open {
+            let deferred = self.count_deferred_settlements().await?;
+            if deferred > 0 {
+                debug!(
+                    "⏳ Outside settlement window: deferring {} settlements until {:?}",
+                    deferred,
+                    self.config.schedule.current_or_next_window(Utc::now()).map(|(start, _)| start)
+                );
+            }
+        }
+
+        self.process_settlements(pending_ids).await
+    }
+
+    /// Process all pending settlements now, whether or not a window is open
+    pub async fn drain_pending_settlements(&self) -> Result<usize, ApiError> {
         let pending_ids = self.get_pending_settlements().await?;
+        self.process_settlements(pending_ids).await
+    }
+
+    /// Whether `settlement_id` may be executed now: this instance may start
+    /// settlements, and a settlement window is open or the settlement is urgent
+    ///
+    /// Every path that executes an existing settlement outside the pending
+    /// loop, such as retries, checks this first so it honours the windows too.
+    pub async fn settlement_due(&self, settlement_id: Uuid) -> Result<bool, ApiError> {
+        if !self.may_start_settlement() {
+            return Ok(false);
+        }
+        if self.config.schedule.is_open(Utc::now()) {
+            return Ok(true);
+        }
+
+        let urgent: Option<bool> = sqlx::query_scalar("SELECT urgent FROM settlements WHERE id = $1")
+            .bind(settlement_id)
+            .fetch_optional(&self.db)
+            .await
+            .map_err(ApiError::Database)?;
+        Ok(urgent.unwrap_or(false))
+    }
+
+    /// Where the settlement schedule stands now
+    pub fn schedule_status(&self) -> SettlementScheduleStatus {
+        self.config.schedule.status(Utc::now())
+    }
+
+    /// Execute `settlement_id` as soon as possible, even outside the
+    /// settlement windows
+    pub async fn mark_urgent(&self, settlement_id: Uuid) -> Result<Settlement, ApiError> {
+        let updated = sqlx::query(
+            "UPDATE settlements SET urgent = TRUE, updated_at = NOW() WHERE id = $1 AND status = 'pending'",
+        )
+        .bind(settlement_id)
+        .execute(&self.db)
+        .await
+        .map_err(ApiError::Database)?;
 
+        if updated.rows_affected() == 0 {
+            let settlement = self.get_settlement(settlement_id).await?;
+            return Err(ApiError::BadRequest(format!(
+                "Settlement is {}, only pending settlements can be expedited",
+                settlement.status
+            )));
+        }
+
+        info!("⚡ Settlement {} marked urgent", settlement_id);
+        self.get_settlement(settlement_id).await
+    }
+
+    async fn process_settlements(&self, pending_ids: Vec<Uuid>) -> Result<usize, ApiError> {
         if pending_ids.is_empty() {
             debug!("No pending settlements to process");
             return Ok(0);
         }
 
-        info!("🚀 Processing {} pending settlements concurrently...", pending_ids.len());
         let total_count = pending_ids.len();
 
+        // Pack transfers into multi-transfer transactions when batching is on
+        if self.config.batch_max_settlements > 1 && self.config.enable_real_blockchain {
+            info!("🚀 Processing {} pending settlements in batches...", total_count);
+            let result = self.execute_batch(&pending_ids).await?;
+            info!(
+                "🏁 BATCH SETTLEMENT COMPLETE: {} in {} transactions, {} individually, {} failed, {} unresolved",
+                result.batched.len(),
+                result.batch_signatures.len(),
+                result.individually_settled.len(),
+                result.failed.len(),
+                result.unresolved.len()
+            );
+            return Ok(result.settled_count());
+        }
+
+        info!("🚀 Processing {} pending settlements concurrently...", total_count);
+
         // Use StreamExt to process settlements in parallel with a concurrency limit
         let concurrency = 10; // Process 10 settlements at a time
         
         // Use a counter for successful settlements
         let processed_count = Arc::new(tokio::sync::Mutex::new(0));
+        let skipped_count = Arc::new(std::sync::atomic::AtomicUsize::new(0));
         let this = Arc::new(self.clone());
 
         stream::iter(pending_ids)
             .for_each_concurrent(concurrency, |settlement_id| {
                 let this = this.clone();
                 let processed_count = processed_count.clone();
+                let skipped_count = skipped_count.clone();
                 async move {
+                    // Settlements already in flight finish; pending ones wait for the next run
+                    if !this.may_start_settlement() {
+                        skipped_count.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+                        return;
+                    }
+                    tokio::time::sleep(this.throttle.next_delay()).await;
+                    if !this.may_start_settlement() {
+                        skipped_count.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+                        return;
+                    }
                     match this.execute_settlement(settlement_id).await {
                         Ok(_) => {
+                            this.throttle.record_result(None);
                             let mut count = processed_count.lock().await;
                             *count += 1;
                         }
                         Err(e) => {
+                            if let ApiError::Settlement(cause) = &e {
+                                this.throttle.record_result(Some(cause));
+                            }
                             error!("❌ Failed to process settlement {}: {}", settlement_id, e);
                         }
                     }
@@ -673,6 +1019,14 @@ impl SettlementService {
             })
             .await;
 
+        let skipped = skipped_count.load(std::sync::atomic::Ordering::Relaxed);
+        if skipped > 0 {
+            warn!(
+                "⏸️ Shutting down or lost the job lease: left {} settlements pending for the next run",
+                skipped
+            );
+        }
+
         let processed = *processed_count.lock().await;
         let success_rate = (processed as f64 / total_count as f64) * 100.0;
         info!(
@@ -684,66 +1038,141 @@ impl SettlementService {
 
     /// Get settlement by ID
     pub async fn get_settlement(&self, id: Uuid) -> Result<Settlement, ApiError> {
+        let row = sqlx::query(&format!("{} WHERE s.id = $1", SETTLEMENT_SELECT))
+            .bind(id)
+            .fetch_optional(&self.db)
+            .await
+            .map_err(ApiError::Database)?
+            .ok_or(ApiError::NotFound("Settlement not found".into()))?;
+
+        settlement_from_row(&row).map_err(ApiError::Database)
+    }
+
+    /// Settlement with its revenue rows, escrow records and signatures, as
+    /// `viewer` may see it
+    ///
+    /// Only the buyer, the seller and admins may read a settlement; anyone
+    /// else gets `NotFound`. A party sees only their own escrow and refunds
+    /// and none of the treasury sweep details.
+    pub async fn get_settlement_detail(&self, id: Uuid, viewer: Uuid, is_admin: bool) -> Result<SettlementDetail, ApiError> {
         use sqlx::Row;
 
-        let row = sqlx::query(
+        let settlement = self.get_settlement(id).await?;
+        if !is_admin && viewer != settlement.buyer_id && viewer != settlement.seller_id {
+            return Err(ApiError::NotFound("Settlement not found".into()));
+        }
+        // Escrow and refunds are limited to the viewer's own unless an admin asks
+        let owner = (!is_admin).then_some(viewer);
+        let order_ids = vec![settlement.buy_order_id, settlement.sell_order_id];
+
+        let revenue_rows = sqlx::query(
             r#"
-            SELECT
-                id, buyer_id, seller_id, buy_order_id, sell_order_id, energy_amount,
-                price_per_kwh, total_amount, fee_amount, net_amount,
-                status, transaction_hash, created_at, processed_at,
-                wheeling_charge, loss_factor, loss_cost, effective_energy, buyer_zone_id, seller_zone_id,
-                buyer_session_token, seller_session_token
-            FROM settlements
-            WHERE id = $1
+            SELECT id, revenue_type, recipient, amount, description, created_at,
+                   sweep_id, swept_at, sweep_signature
+            FROM platform_revenue
+            WHERE settlement_id = $1
+            ORDER BY created_at ASC, revenue_type, recipient
             "#,
         )
         .bind(id)
-        .fetch_optional(&self.db)
+        .fetch_all(&self.db)
         .await
-        .map_err(ApiError::Database)?
-        .ok_or(ApiError::NotFound("Settlement not found".into()))?;
-
-        let status_str: String = row.get("status");
-        let status = match status_str.to_lowercase().as_str() {
-            "pending" => SettlementStatus::Pending,
-            "processing" => SettlementStatus::Processing,
-            "completed" | "confirmed" => SettlementStatus::Completed,
-            "failed" => SettlementStatus::Failed,
-            "pending_bridge" => SettlementStatus::PendingBridge,
-            "bridging_initiated" => SettlementStatus::BridgingInitiated,
-            _ => SettlementStatus::Pending,
-        };
+        .map_err(ApiError::Database)?;
 
-        Ok(Settlement {
+        let mut revenue = Vec::with_capacity(revenue_rows.len());
+        for row in &revenue_rows {
+            let recipient: String = row.get("recipient");
+            let mut entry = SettlementRevenueEntry {
+                id: row.get("id"),
+                revenue_type: row.get("revenue_type"),
+                recipient: recipient.parse::<RevenueRecipient>().map_err(ApiError::Internal)?,
+                amount: row.get("amount"),
+                description: row.get("description"),
+                created_at: row.get("created_at"),
+                sweep_id: row.get("sweep_id"),
+                swept_at: row.get("swept_at"),
+                sweep_signature: row.get("sweep_signature"),
+            };
+            if !is_admin {
+                entry.sweep_id = None;
+                entry.swept_at = None;
+                entry.sweep_signature = None;
+            }
+            revenue.push(entry);
+        }
+        let revenue_total = revenue.iter().map(|entry| entry.amount).sum();
+
+        let escrow = sqlx::query(
+            r#"
+            SELECT id, user_id, order_id, asset_type, escrow_type, status, amount,
+                   description, updated_at
+            FROM escrow_records
+            WHERE order_id = ANY($1)
+              AND ($2::uuid IS NULL OR user_id = $2)
+            ORDER BY created_at ASC
+            "#,
+        )
+        .bind(&order_ids)
+        .bind(owner)
+        .fetch_all(&self.db)
+        .await
+        .map_err(ApiError::Database)?
+        .iter()
+        .map(|row| SettlementEscrowEntry {
             id: row.get("id"),
-            trade_id: Uuid::new_v4(), // Not stored in this simplified version
-            buyer_id: row.get("buyer_id"),
-            seller_id: row.get("seller_id"),
-            buy_order_id: row.get("buy_order_id"),
-            sell_order_id: row.get("sell_order_id"),
-            energy_amount: row.get("energy_amount"),
-            price: row.get("price_per_kwh"),
-            total_value: row.get("total_amount"),
-            fee_amount: row.get("fee_amount"),
-            net_amount: row.get("net_amount"),
-            status,
-            blockchain_tx: row.get("transaction_hash"),
-            created_at: row.get("created_at"),
-            confirmed_at: row.get("processed_at"),
-            wheeling_charge: row.get("wheeling_charge"),
-            loss_factor: row.get("loss_factor"),
-            loss_cost: row.get("loss_cost"),
-            effective_energy: row.get("effective_energy"),
-            buyer_zone_id: row.get("buyer_zone_id"),
-            seller_zone_id: row.get("seller_zone_id"),
-            buyer_session_token: row.get("buyer_session_token"),
-            seller_session_token: row.get("seller_session_token"),
+            user_id: row.get("user_id"),
+            order_id: row.get("order_id"),
+            asset_type: row.get("asset_type"),
+            escrow_type: row.get("escrow_type"),
+            status: row.get("status"),
+            amount: row.get("amount"),
+            description: row.get("description"),
+            updated_at: row.get("updated_at"),
+        })
+        .collect();
+
+        let refund_signatures: Vec<String> = sqlx::query_scalar(
+            r#"
+            SELECT signature
+            FROM escrow_refunds
+            WHERE order_id = ANY($1)
+              AND ($2::uuid IS NULL OR user_id = $2)
+            ORDER BY created_at ASC
+            "#,
+        )
+        .bind(&order_ids)
+        .bind(owner)
+        .fetch_all(&self.db)
+        .await
+        .map_err(ApiError::Database)?;
+
+        let mut signatures: Vec<String> = Vec::new();
+        for signature in [&settlement.blockchain_tx, &settlement.batch_signature]
+            .into_iter()
+            .flatten()
+            .chain(&refund_signatures)
+        {
+            if !signatures.contains(signature) {
+                signatures.push(signature.clone());
+            }
+        }
+
+        Ok(SettlementDetail {
+            settlement,
+            revenue,
+            revenue_total,
+            escrow,
+            signatures,
         })
     }
 
     /// Get all pending settlements
     pub async fn get_pending_settlements(&self) -> Result<Vec<Uuid>, ApiError> {
+        self.get_pending_settlements_filtered(false).await
+    }
+
+    /// Pending settlements, urgent first; only urgent ones if `urgent_only`
+    async fn get_pending_settlements_filtered(&self, urgent_only: bool) -> Result<Vec<Uuid>, ApiError> {
         use sqlx::Row;
 
         let rows = sqlx::query(
@@ -751,10 +1180,12 @@ impl SettlementService {
             SELECT id
             FROM settlements
             WHERE status = 'pending'
-            ORDER BY created_at ASC
+              AND (urgent = TRUE OR NOT $1)
+            ORDER BY urgent DESC, created_at ASC
             LIMIT 100
             "#,
         )
+        .bind(urgent_only)
         .fetch_all(&self.db)
         .await
         .map_err(ApiError::Database)?;
@@ -762,6 +1193,14 @@ impl SettlementService {
         Ok(rows.into_iter().map(|row| row.get("id")).collect())
     }
 
+    /// Pending settlements waiting for the next settlement window
+    async fn count_deferred_settlements(&self) -> Result<i64, ApiError> {
+        sqlx::query_scalar("SELECT COUNT(*) FROM settlements WHERE status = 'pending' AND urgent = FALSE")
+            .fetch_one(&self.db)
+            .await
+            .map_err(ApiError::Database)
+    }
+
     /// Execute a batch of settlements in on-chain transactions with physical transfers
     pub async fn execute_batch_settlement(
         &self,
@@ -782,24 +1221,14 @@ impl SettlementService {
 
         use solana_sdk::instruction::AccountMeta;
 
+        let energy_decimals = self.configured_mint_decimals("ENERGY_TOKEN_MINT").await?;
+        let currency_decimals = self.configured_mint_decimals("CURRENCY_TOKEN_MINT").await?;
+
         for s in &settlements {
-            let amount_atomic = (s.energy_amount * Decimal::from(1_000_000_000))
-                .trunc()
-                .to_string()
-                .parse::<u64>()
-                .unwrap_or(0);
-            
-            let price_atomic = (s.price * Decimal::from(1_000_000_000))
-                .trunc()
-                .to_string()
-                .parse::<u64>()
-                .unwrap_or(0);
-            
-            let wheeling_atomic = (s.wheeling_charge.unwrap_or(Decimal::ZERO) * Decimal::from(1_000_000_000))
-                .trunc()
-                .to_string()
-                .parse::<u64>()
-                .unwrap_or(0);
+            let invalid = |e: ConversionError| ApiError::Validation(format!("Invalid amount in settlement {}: {}", s.id, e));
+            let amount_atomic = to_atomic(s.energy_amount, energy_decimals).map_err(invalid)?;
+            let price_atomic = to_atomic(s.price, currency_decimals).map_err(invalid)?;
+            let wheeling_atomic = to_atomic(s.wheeling_charge.unwrap_or(Decimal::ZERO), currency_decimals).map_err(invalid)?;
 
             amounts.push(amount_atomic);
             prices.push(price_atomic);
@@ -849,6 +1278,10 @@ impl SettlementService {
         // 3. Update database records
         for s in settlements {
             self.update_settlement_confirmed(s.id, &signature.to_string(), SettlementStatus::Completed).await?;
+            self.audit_logger.log_async(AuditEvent::SettlementCompleted {
+                settlement_id: s.id,
+                signature: signature.to_string(),
+            });
             if let Err(e) = self.finalize_escrow(&s).await {
                 error!("⚠️ Failed to finalize escrow for settlement {}: {}", s.id, e);
             }
@@ -912,7 +1345,12 @@ impl SettlementService {
         id: Uuid,
         tx_signature: &str,
     ) -> Result<(), ApiError> {
-        self.update_settlement_confirmed(id, tx_signature, SettlementStatus::Completed).await
+        self.update_settlement_confirmed(id, tx_signature, SettlementStatus::Completed).await?;
+        self.audit_logger.log_async(AuditEvent::SettlementCompleted {
+            settlement_id: id,
+            signature: tx_signature.to_string(),
+        });
+        Ok(())
     }
 
     /// Retry failed settlements with exponential backoff (called by background job)
@@ -942,6 +1380,12 @@ impl SettlementService {
             let delay_secs = base_delay_secs * (2_u64.pow(retry_count));
             let max_delay_secs = 300; // Cap at 5 minutes
             let actual_delay = delay_secs.min(max_delay_secs);
+
+            // Non-urgent retries wait for a settlement window like new settlements
+            if !self.settlement_due(settlement.id).await? {
+                debug!("⏳ Outside settlement window: deferring retry of settlement {}", settlement.id);
+                continue;
+            }
             
             info!(
                 "Retrying settlement {} (attempt {}/{}) with {}s delay",
@@ -950,6 +1394,14 @@ impl SettlementService {
             
             // Wait with exponential backoff
             tokio::time::sleep(Duration::from_secs(actual_delay)).await;
+            if !self.may_start_settlement() {
+                info!("⏸️ Stopping settlement retries: shutting down or lost the job lease");
+                break;
+            }
+            if !self.settlement_due(settlement.id).await? {
+                debug!("⏳ Outside settlement window: deferring retry of settlement {}", settlement.id);
+                continue;
+            }
             
             match self.execute_settlement(settlement.id).await {
                 Ok(_) => {
@@ -958,16 +1410,18 @@ impl SettlementService {
                 }
                 Err(e) => {
                     let error_str = e.to_string();
-                    
-                    // Classify error: determine if retryable
-                    let is_retryable = Self::is_retryable_error(&error_str);
-                    
-                    if is_retryable {
-                        error!("⚠️ Settlement {} retry failed (retryable): {}", settlement.id, e);
+                    let decision = self.classify_failure(&e);
+                    let rule = match &decision.pattern {
+                        Some(pattern) => format!("{:?} override '{}'", decision.source, pattern),
+                        None => "default".to_string(),
+                    };
+
+                    if decision.retryable {
+                        error!("⚠️ Settlement {} retry failed (retryable, {}): {}", settlement.id, rule, e);
                         self.increment_retry_count(&settlement.id).await?;
                     } else {
                         // Non-retryable error - mark as permanently failed
-                        error!("❌ Settlement {} permanently failed (non-retryable): {}", settlement.id, e);
+                        error!("❌ Settlement {} permanently failed (non-retryable, {}): {}", settlement.id, rule, e);
                         self.mark_settlement_permanent_failure(&settlement.id, &error_str).await?;
                     }
                 }
@@ -977,58 +1431,82 @@ impl SettlementService {
         Ok(retried)
     }
 
-    /// Classify if an error is retryable
-    fn is_retryable_error(error: &str) -> bool {
-        let retryable_patterns = [
-            "timeout",
-            "connection refused",
-            "network",
-            "rate limit",
-            "429",
-            "503",
-            "temporary",
-            "try again",
-            "blockhash",
-            "not found", // Transaction not yet confirmed
-        ];
-        
-        let non_retryable_patterns = [
-            "insufficient",
-            "invalid signature",
-            "invalid account",
-            "unauthorized",
-            "forbidden",
-            "already processed",
-            "account not found",  // Permanent missing account
-            "program failed",
-        ];
-        
-        let error_lower = error.to_lowercase();
-        
-        // If matches non-retryable, don't retry
-        for pattern in non_retryable_patterns.iter() {
-            if error_lower.contains(pattern) {
-                return false;
-            }
+    /// Classify if a settlement failure is retryable
+    ///
+    /// Transfer failures are judged by their [`SettlementError`] variant.
+    /// Rejected requests will fail the same way again; anything else, such
+    /// as a database hiccup, is retried.
+    fn is_retryable_error(error: &ApiError) -> bool {
+        match error {
+            ApiError::Settlement(e) => e.is_retryable(),
+            ApiError::BadRequest(_)
+            | ApiError::Validation(_)
+            | ApiError::NotFound(_)
+            | ApiError::Forbidden(_)
+            | ApiError::Conflict(_) => false,
+            _ => true,
         }
-        
-        // If matches retryable, retry
-        for pattern in retryable_patterns.iter() {
-            if error_lower.contains(pattern) {
-                return true;
-            }
+    }
+
+    /// Classify a settlement failure, letting an override pattern matching
+    /// its message take precedence over [`Self::is_retryable_error`]
+    fn classify_failure(&self, error: &ApiError) -> RetryDecision {
+        let overrides = match self.retry_overrides.read() {
+            Ok(guard) => guard,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+        overrides.decide(&error.to_string(), Self::is_retryable_error(error))
+    }
+
+    /// Seconds between periodic [`Self::reload_retry_overrides`] calls
+    pub fn retry_overrides_refresh_secs(&self) -> u64 {
+        self.config.retry_overrides_refresh_secs
+    }
+
+    /// Retry overrides currently in effect
+    pub fn retry_overrides(&self) -> RetryOverrides {
+        match self.retry_overrides.read() {
+            Ok(guard) => guard.clone(),
+            Err(poisoned) => poisoned.into_inner().clone(),
         }
-        
-        // Default: retry unknown errors (conservative)
-        true
     }
 
-    /// Mark settlement as permanently failed (non-retryable)
+    /// Re-read the `settlement_retry_overrides` table and merge it with the
+    /// environment patterns; the previous set stays in effect on error
+    ///
+    /// Only this replica is updated. Every replica also calls this every
+    /// `retry_overrides_refresh_secs`, so table changes reach the others
+    /// within that interval.
+    pub async fn reload_retry_overrides(&self) -> Result<RetryOverrides, ApiError> {
+        let from_db = RetryOverrides::load_from_db(&self.db)
+            .await
+            .map_err(ApiError::Database)?;
+        let merged = self.config.retry_overrides.clone().merge(from_db);
+
+        let mut guard = match self.retry_overrides.write() {
+            Ok(guard) => guard,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+        if *guard != merged {
+            info!(
+                "Loaded {} settlement retry overrides ({} from environment)",
+                merged.rules.len(),
+                self.config.retry_overrides.rules.len()
+            );
+            *guard = merged.clone();
+        }
+        Ok(merged)
+    }
+
+    /// Mark settlement as permanently failed (non-retryable) and park it in
+    /// the dead-letter queue
     async fn mark_settlement_permanent_failure(
         &self,
         settlement_id: &Uuid,
         error_message: &str,
     ) -> Result<(), ApiError> {
+        let mut tx = self.db.begin().await.map_err(ApiError::Database)?;
+
         sqlx::query(
             r#"
             UPDATE settlements
@@ -1040,14 +1518,113 @@ impl SettlementService {
         )
         .bind(error_message)
         .bind(settlement_id)
-        .execute(&self.db)
+        .execute(&mut *tx)
+        .await
+        .map_err(ApiError::Database)?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO settlement_dead_letters (settlement_id, error_message, retry_count)
+            SELECT id, $1, COALESCE(retry_count, 0) FROM settlements WHERE id = $2
+            ON CONFLICT (settlement_id) WHERE replayed_at IS NULL DO UPDATE
+            SET error_message = EXCLUDED.error_message, failed_at = NOW()
+            "#,
+        )
+        .bind(error_message)
+        .bind(settlement_id)
+        .execute(&mut *tx)
         .await
         .map_err(ApiError::Database)?;
+
+        tx.commit().await.map_err(ApiError::Database)?;
         
         info!("Settlement {} marked as permanently failed: {}", settlement_id, error_message);
+        self.audit_logger.log_async(AuditEvent::SettlementFailed {
+            settlement_id: *settlement_id,
+            reason: error_message.to_string(),
+            permanent: true,
+        });
         Ok(())
     }
 
+    /// Permanently failed settlements not yet replayed, oldest first
+    pub async fn list_permanent_failures(&self) -> Result<Vec<SettlementDeadLetter>, ApiError> {
+        sqlx::query_as::<_, SettlementDeadLetter>(
+            r#"
+            SELECT d.settlement_id, s.buyer_id, s.seller_id, s.energy_amount, s.total_amount,
+                   d.error_message, d.retry_count, d.failed_at
+            FROM settlement_dead_letters d
+            JOIN settlements s ON s.id = d.settlement_id
+            WHERE d.replayed_at IS NULL
+            ORDER BY d.failed_at ASC
+            "#,
+        )
+        .fetch_all(&self.db)
+        .await
+        .map_err(ApiError::Database)
+    }
+
+    /// Requeue a permanently failed settlement after its root cause is fixed
+    ///
+    /// The settlement goes back to `pending` with its retry count reset, so
+    /// the next settlement run picks it up; the dead letter is closed with
+    /// the admin who replayed it.
+    pub async fn force_retry(&self, settlement_id: Uuid, admin_id: Uuid) -> Result<Settlement, ApiError> {
+        let mut tx = self.db.begin().await.map_err(ApiError::Database)?;
+
+        let (status, previous_error): (String, Option<String>) = sqlx::query_as(
+            "SELECT status, error_message FROM settlements WHERE id = $1 FOR UPDATE",
+        )
+        .bind(settlement_id)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(ApiError::Database)?
+        .ok_or_else(|| ApiError::NotFound(format!("Settlement {} not found", settlement_id)))?;
+
+        if SettlementStatus::from_db(&status) != SettlementStatus::PermanentlyFailed {
+            return Err(ApiError::BadRequest(format!(
+                "Settlement {} is {}, only permanently failed settlements can be replayed",
+                settlement_id, status
+            )));
+        }
+
+        sqlx::query(
+            r#"
+            UPDATE settlements
+            SET status = 'pending', retry_count = 0, error_message = NULL, updated_at = NOW()
+            WHERE id = $1
+            "#,
+        )
+        .bind(settlement_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(ApiError::Database)?;
+
+        sqlx::query(
+            r#"
+            UPDATE settlement_dead_letters
+            SET replayed_at = NOW(), replayed_by = $2
+            WHERE settlement_id = $1 AND replayed_at IS NULL
+            "#,
+        )
+        .bind(settlement_id)
+        .bind(admin_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(ApiError::Database)?;
+
+        tx.commit().await.map_err(ApiError::Database)?;
+
+        info!("♻️ Settlement {} requeued by admin {}", settlement_id, admin_id);
+        self.audit_logger.log_async(AuditEvent::SettlementReplayed {
+            settlement_id,
+            admin_id,
+            previous_error: previous_error.unwrap_or_default(),
+        });
+
+        self.get_settlement(settlement_id).await
+    }
+
     /// Increment retry count for a settlement
     pub async fn increment_retry_count(&self, settlement_id: &Uuid) -> Result<(), ApiError> {
         sqlx::query(
@@ -1093,6 +1670,338 @@ impl SettlementService {
             total_settled_value: row.get("total_settled_value"),
         })
     }
+
+    /// Compare recorded `platform_revenue` against the fees, wheeling charges and
+    /// loss costs of settlements completed in `[from, to)`.
+    ///
+    /// Any settlement whose recorded revenue drifts from its expected revenue by
+    /// more than `reconciliation_tolerance` is reported as a discrepancy.
+    pub async fn reconcile_revenue(
+        &self,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<RevenueReconciliation, ApiError> {
+        use sqlx::Row;
+
+        if from >= to {
+            return Err(ApiError::BadRequest(
+                "Reconciliation window start must be before its end".to_string(),
+            ));
+        }
+
+        // Mirrors the revenue rows written by finalize_escrow: only positive
+        // components produce a platform_revenue record.
+        let rows = sqlx::query(
+            r#"
+            SELECT
+                s.id,
+                GREATEST(s.fee_amount, 0)
+                    + GREATEST(COALESCE(s.wheeling_charge, 0), 0)
+                    + GREATEST(COALESCE(s.loss_cost, 0), 0) as expected_revenue,
+                COALESCE((
+                    SELECT SUM(r.amount) FROM platform_revenue r WHERE r.settlement_id = s.id
+                ), 0) as recorded_revenue
+            FROM settlements s
+            WHERE s.status = 'completed'
+              AND s.processed_at >= $1
+              AND s.processed_at < $2
+            ORDER BY s.processed_at ASC
+            "#,
+        )
+        .bind(from)
+        .bind(to)
+        .fetch_all(&self.db)
+        .await
+        .map_err(ApiError::Database)?;
+
+        let tolerance = self.config.reconciliation_tolerance;
+        let mut expected_total = Decimal::ZERO;
+        let mut recorded_total = Decimal::ZERO;
+        let mut discrepancies = Vec::new();
+
+        for row in &rows {
+            let expected_revenue: Decimal = row.get("expected_revenue");
+            let recorded_revenue: Decimal = row.get("recorded_revenue");
+            expected_total += expected_revenue;
+            recorded_total += recorded_revenue;
+
+            let difference = recorded_revenue - expected_revenue;
+            if difference.abs() > tolerance {
+                discrepancies.push(RevenueDiscrepancy {
+                    settlement_id: row.get("id"),
+                    expected_revenue,
+                    recorded_revenue,
+                    difference,
+                });
+            }
+        }
+
+        let difference = recorded_total - expected_total;
+        if !discrepancies.is_empty() {
+            warn!(
+                "⚠️ Revenue reconciliation found {} discrepancies between {} and {} (drift: {})",
+                discrepancies.len(),
+                from,
+                to,
+                difference
+            );
+        }
+
+        Ok(RevenueReconciliation {
+            from,
+            to,
+            settlement_count: rows.len() as i64,
+            expected_total,
+            recorded_total,
+            difference,
+            tolerance,
+            is_balanced: discrepancies.is_empty(),
+            discrepancies,
+        })
+    }
+
+    /// Platform revenue recorded in `[from, to)`, totalled per recipient
+    pub async fn revenue_by_recipient(
+        &self,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<RevenueByRecipient, ApiError> {
+        use sqlx::Row;
+
+        if from >= to {
+            return Err(ApiError::BadRequest(
+                "Revenue window start must be before its end".to_string(),
+            ));
+        }
+
+        let rows = sqlx::query(
+            r#"
+            SELECT
+                recipient,
+                COALESCE(SUM(amount), 0) as total,
+                COALESCE(SUM(amount) FILTER (WHERE revenue_type = 'platform_fee'), 0) as platform_fees,
+                COALESCE(SUM(amount) FILTER (WHERE revenue_type = 'wheeling_charge'), 0) as wheeling_charges,
+                COALESCE(SUM(amount) FILTER (WHERE revenue_type = 'loss_cost'), 0) as loss_costs,
+                COUNT(DISTINCT settlement_id) as settlement_count
+            FROM platform_revenue
+            WHERE created_at >= $1
+              AND created_at < $2
+            GROUP BY recipient
+            ORDER BY recipient
+            "#,
+        )
+        .bind(from)
+        .bind(to)
+        .fetch_all(&self.db)
+        .await
+        .map_err(ApiError::Database)?;
+
+        let mut recipients = Vec::with_capacity(rows.len());
+        for row in &rows {
+            let recipient: String = row.get("recipient");
+            let recipient = recipient.parse::<RevenueRecipient>().map_err(ApiError::Internal)?;
+            recipients.push(RecipientRevenue {
+                recipient,
+                total: row.get("total"),
+                platform_fees: row.get("platform_fees"),
+                wheeling_charges: row.get("wheeling_charges"),
+                loss_costs: row.get("loss_costs"),
+                settlement_count: row.get("settlement_count"),
+            });
+        }
+
+        Ok(RevenueByRecipient {
+            from,
+            to,
+            recipients,
+            splits: self.config.revenue_splits.clone(),
+        })
+    }
+
+    /// Dry-run the on-chain settlement path for a settlement without moving tokens.
+    ///
+    /// Runs every step of `execute_blockchain_transfer` up to, but not including,
+    /// the transfer itself and reports pass/fail per step. Token accounts are only
+    /// derived and looked up, never created. Runs regardless of
+    /// `enable_real_blockchain` so a deployment can be validated before going live.
+    pub async fn validate_settlement_path(
+        &self,
+        settlement_id: Uuid,
+    ) -> Result<SettlementPathReport, ApiError> {
+        let settlement = self.get_settlement(settlement_id).await?;
+        let mut report = SettlementPathReport::new(settlement_id);
+
+        info!("🧪 Validating settlement path for {}", settlement_id);
+
+        // 1. Wallets
+        let buyer_pubkey = match self.get_user_wallet(&settlement.buyer_id).await.and_then(|w| {
+            BlockchainService::parse_pubkey(&w)
+                .map_err(|e| ApiError::Internal(format!("Invalid buyer wallet: {}", e)))
+        }) {
+            Ok(pk) => {
+                report.pass("buyer_wallet", pk.to_string());
+                Some(pk)
+            }
+            Err(e) => {
+                report.fail("buyer_wallet", e.to_string());
+                None
+            }
+        };
+
+        let seller_wallet = match self.get_user_wallet(&settlement.seller_id).await {
+            Ok(w) => match BlockchainService::parse_pubkey(&w) {
+                Ok(_) => {
+                    report.pass("seller_wallet", w.clone());
+                    Some(w)
+                }
+                Err(e) => {
+                    report.fail("seller_wallet", format!("Invalid seller wallet: {}", e));
+                    None
+                }
+            },
+            Err(e) => {
+                report.fail("seller_wallet", e.to_string());
+                None
+            }
+        };
+
+        // 2. Mint config
+        let mint = match std::env::var("ENERGY_TOKEN_MINT") {
+            Ok(mint_str) => match BlockchainService::parse_pubkey(&mint_str) {
+                Ok(mint) => {
+                    report.pass("mint_config", mint.to_string());
+                    Some(mint)
+                }
+                Err(e) => {
+                    report.fail("mint_config", format!("Invalid mint config: {}", e));
+                    None
+                }
+            },
+            Err(e) => {
+                report.fail("mint_config", format!("ENERGY_TOKEN_MINT not set: {}", e));
+                None
+            }
+        };
+
+        // 3. Platform authority
+        match self.blockchain.get_authority_keypair().await {
+            Ok(authority) => report.pass("authority_keypair", authority.pubkey().to_string()),
+            Err(e) => report.fail("authority_keypair", format!("Failed to get authority: {}", e)),
+        }
+
+        // 4. Seller key decryption
+        let seller_keypair = match self
+            .get_user_keypair(&settlement.seller_id, settlement.seller_session_token.as_deref())
+            .await
+        {
+            Ok(kp) => {
+                report.pass("seller_key_decryption", kp.pubkey().to_string());
+                Some(kp)
+            }
+            Err(e) => {
+                report.fail("seller_key_decryption", e.to_string());
+                None
+            }
+        };
+
+        // 5. Identity match between decrypted key and registered wallet
+        let seller_pubkey = match (&seller_keypair, &seller_wallet) {
+            (Some(kp), Some(wallet)) => {
+                let decrypted = kp.pubkey();
+                if decrypted.to_string() == *wallet {
+                    report.pass("seller_identity", "Decrypted key matches registered wallet");
+                    Some(decrypted)
+                } else {
+                    report.fail(
+                        "seller_identity",
+                        format!("Wallet identity mismatch: DB={} Decrypted={}", wallet, decrypted),
+                    );
+                    None
+                }
+            }
+            _ => {
+                report.skip("seller_identity", "Requires seller wallet and decrypted key");
+                None
+            }
+        };
+
+        // 6. Token account resolution
+        match (buyer_pubkey, mint) {
+            (Some(buyer), Some(mint)) => match self.blockchain.calculate_ata_address(&buyer, &mint) {
+                Ok(ata) => match self.blockchain.account_exists(&ata).await {
+                    Ok(true) => report.pass("buyer_token_account", ata.to_string()),
+                    Ok(false) => report.pass(
+                        "buyer_token_account",
+                        format!("{} (will be created at settlement)", ata),
+                    ),
+                    Err(e) => report.fail("buyer_token_account", format!("RPC lookup failed: {}", e)),
+                },
+                Err(e) => report.fail("buyer_token_account", e.to_string()),
+            },
+            _ => report.skip("buyer_token_account", "Requires buyer wallet and mint"),
+        }
+
+        let seller_ata_ready = match (seller_pubkey, mint) {
+            (Some(seller), Some(mint)) => match self.blockchain.calculate_ata_address(&seller, &mint) {
+                Ok(ata) => match self.blockchain.account_exists(&ata).await {
+                    Ok(true) => {
+                        report.pass("seller_token_account", ata.to_string());
+                        true
+                    }
+                    Ok(false) => {
+                        report.fail("seller_token_account", format!("{} does not exist", ata));
+                        false
+                    }
+                    Err(e) => {
+                        report.fail("seller_token_account", format!("RPC lookup failed: {}", e));
+                        false
+                    }
+                },
+                Err(e) => {
+                    report.fail("seller_token_account", e.to_string());
+                    false
+                }
+            },
+            _ => {
+                report.skip("seller_token_account", "Requires verified seller identity and mint");
+                false
+            }
+        };
+
+        // 7. Seller balance covers the gross energy amount (effective + loss)
+        match (seller_pubkey, mint) {
+            (Some(seller), Some(mint)) if seller_ata_ready => {
+                let required = match self.blockchain.decimals_for(&mint).await {
+                    Ok(decimals) => to_atomic(settlement.energy_amount, decimals).map_err(|e| e.to_string()),
+                    Err(e) => Err(format!("Failed to resolve mint decimals: {}", e)),
+                };
+                match required {
+                    Ok(required) => match self.blockchain.get_token_balance(&seller, &mint).await {
+                        Ok(balance) if balance >= required => report.pass(
+                            "seller_balance",
+                            format!("Balance {} >= required {}", balance, required),
+                        ),
+                        Ok(balance) => report.fail(
+                            "seller_balance",
+                            format!("Balance {} < required {}", balance, required),
+                        ),
+                        Err(e) => report.fail("seller_balance", format!("RPC lookup failed: {}", e)),
+                    },
+                    Err(e) => report.fail("seller_balance", e),
+                }
+            }
+            _ => report.skip("seller_balance", "Requires seller token account"),
+        }
+
+        if report.all_passed {
+            info!("✅ Settlement path for {} validated", settlement_id);
+        } else {
+            warn!("⚠️ Settlement path for {} failed validation", settlement_id);
+        }
+
+        Ok(report)
+    }
+
     /// Helper: Get user keypair from database
     async fn get_user_keypair(
         &self,
@@ -1248,38 +2157,25 @@ impl SettlementService {
         .execute(&mut *tx)
         .await.map_err(ApiError::Database)?;
 
-        // 4. Record Platform Revenue (Fees, Wheeling, Loss)
-        if settlement.fee_amount > Decimal::ZERO {
-            sqlx::query!(
-                "INSERT INTO platform_revenue (settlement_id, amount, revenue_type, description) VALUES ($1, $2, 'platform_fee', $3)",
-                settlement.id,
-                settlement.fee_amount,
-                format!("Platform fee for settlement {}", settlement.id)
-            )
-            .execute(&mut *tx)
-            .await.map_err(ApiError::Database)?;
-        }
-
-        if let Some(wheeling) = settlement.wheeling_charge {
-            if wheeling > Decimal::ZERO {
-                sqlx::query!(
-                    "INSERT INTO platform_revenue (settlement_id, amount, revenue_type, description) VALUES ($1, $2, 'wheeling_charge', $3)",
-                    settlement.id,
-                    wheeling,
-                    format!("Wheeling charge for settlement {}", settlement.id)
-                )
-                .execute(&mut *tx)
-                .await.map_err(ApiError::Database)?;
-            }
-        }
-
-        if let Some(loss_cost) = settlement.loss_cost {
-            if loss_cost > Decimal::ZERO {
+        // 4. Record Platform Revenue (Fees, Wheeling, Loss), one row per recipient share
+        let splits = &self.config.revenue_splits;
+        let components = [
+            ("platform_fee", Some(settlement.fee_amount), &splits.platform_fee, "Platform fee"),
+            ("wheeling_charge", settlement.wheeling_charge, &splits.wheeling_charge, "Wheeling charge"),
+            ("loss_cost", settlement.loss_cost, &splits.loss_cost, "Grid loss cost"),
+        ];
+        for (revenue_type, amount, split, label) in components {
+            let Some(amount) = amount.filter(|amount| *amount > Decimal::ZERO) else {
+                continue;
+            };
+            for (recipient, share) in split.allocate(amount) {
                 sqlx::query!(
-                    "INSERT INTO platform_revenue (settlement_id, amount, revenue_type, description) VALUES ($1, $2, 'loss_cost', $3)",
+                    "INSERT INTO platform_revenue (settlement_id, amount, revenue_type, recipient, description) VALUES ($1, $2, $3, $4, $5)",
                     settlement.id,
-                    loss_cost,
-                    format!("Grid loss cost for settlement {}", settlement.id)
+                    share,
+                    revenue_type,
+                    recipient.as_str(),
+                    format!("{} for settlement {}", label, settlement.id)
                 )
                 .execute(&mut *tx)
                 .await.map_err(ApiError::Database)?;
@@ -1298,6 +2194,12 @@ impl SettlementService {
         tx.commit().await.map_err(ApiError::Database)?;
         
         info!("🔐 Escrow finalized for settlement {}: funds transferred and energy unlocked", settlement.id);
+        self.audit_logger.log_async(AuditEvent::EscrowFinalized {
+            settlement_id: settlement.id,
+            buyer_id: settlement.buyer_id,
+            seller_id: settlement.seller_id,
+            net_amount: settlement.net_amount.to_string(),
+        });
         Ok(())
     }
 
@@ -1420,15 +2322,29 @@ impl SettlementService {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::services::blockchain::{BlockchainCall, MockBlockchainClient};
+    use crate::services::fees::FeeSchedule;
     use std::str::FromStr;
 
     #[test]
     fn test_settlement_config_default() {
         let config = SettlementConfig::default();
-        assert_eq!(config.fee_rate, Decimal::from_str("0.01").unwrap());
+        assert_eq!(config.fee_schedule.base_rate, Decimal::from_str("0.01").unwrap());
         assert_eq!(config.min_confirmation_blocks, 32);
     }
 
+    #[test]
+    fn test_public_failure_reason_hides_error_details() {
+        let error = ApiError::Settlement(SettlementError::Network("rpc https://node.internal:8899 refused".to_string()));
+        assert_eq!(SettlementService::public_failure_reason(&error), "temporary blockchain network issue");
+
+        let error = ApiError::from(counterparties::SettlementRejection::SelfTrade(Uuid::new_v4()));
+        assert_eq!(SettlementService::public_failure_reason(&error), "a counterparty cannot settle this trade");
+
+        let error = ApiError::Internal("connection reset by peer".to_string());
+        assert_eq!(SettlementService::public_failure_reason(&error), "internal settlement error");
+    }
+
     #[test]
     fn test_settlement_status_display() {
         assert_eq!(SettlementStatus::Pending.to_string(), "pending");
@@ -1439,7 +2355,8 @@ mod tests {
     fn test_settlement_creation() {
         let settlement = Settlement {
             id: Uuid::new_v4(),
-            trade_id: Uuid::new_v4(),
+            epoch_id: Uuid::new_v4(),
+            match_id: Some(Uuid::new_v4()),
             buyer_id: Uuid::new_v4(),
             seller_id: Uuid::new_v4(),
             buy_order_id: Uuid::new_v4(),
@@ -1451,6 +2368,7 @@ mod tests {
             net_amount: Decimal::from_str("14.85").unwrap(),
             status: SettlementStatus::Pending,
             blockchain_tx: None,
+            batch_signature: None,
             created_at: Utc::now(),
             buyer_zone_id: None,
             seller_zone_id: None,
@@ -1466,20 +2384,44 @@ mod tests {
         assert_eq!(settlement.status, SettlementStatus::Pending);
     }
 
+    #[test]
+    fn test_settlement_status_round_trips_through_db_string() {
+        for status in [
+            SettlementStatus::Pending,
+            SettlementStatus::Processing,
+            SettlementStatus::Completed,
+            SettlementStatus::Failed,
+            SettlementStatus::PendingBridge,
+            SettlementStatus::BridgingInitiated,
+            SettlementStatus::PermanentlyFailed,
+        ] {
+            assert_eq!(SettlementStatus::from_db(&status.to_string()), status);
+        }
+        assert_eq!(SettlementStatus::from_db("confirmed"), SettlementStatus::Completed);
+    }
+
     #[test]
     fn test_fee_calculation() {
         let config = SettlementConfig {
-            fee_rate: Decimal::from_str("0.01").unwrap(), // 1%
+            fee_schedule: FeeSchedule::flat(Decimal::from_str("0.01").unwrap()), // 1%
             min_confirmation_blocks: 32,
             retry_attempts: 3,
             retry_delay_secs: 5,
             enable_real_blockchain: true,
+            batch_max_settlements: 1,
+            reconciliation_tolerance: Decimal::ZERO,
+            schedule: SettlementSchedule::default(),
+            retry_overrides: RetryOverrides::default(),
+            retry_overrides_refresh_secs: 60,
+            revenue_splits: RevenueSplits::default(),
+            energy_rounding: EnergyRounding::default(),
         };
 
         let trade_amount = Decimal::from(100);
         let expected_fee = Decimal::from_str("1.00").unwrap();
 
-        assert_eq!(config.fee_rate * trade_amount, expected_fee);
+        let calculator = FeeCalculator::new(config.fee_schedule);
+        assert_eq!(calculator.fee_for(trade_amount, None, Decimal::ZERO), expected_fee);
     }
 
     #[test]
@@ -1519,13 +2461,138 @@ mod tests {
     #[test]
     fn test_custom_fee_rate() {
         let custom_config = SettlementConfig {
-            fee_rate: Decimal::from_str("0.005").unwrap(), // 0.5%
+            fee_schedule: FeeSchedule::flat(Decimal::from_str("0.005").unwrap()), // 0.5%
             min_confirmation_blocks: 64,
             retry_attempts: 5,
             retry_delay_secs: 10,
             enable_real_blockchain: true,
+            batch_max_settlements: 1,
+            reconciliation_tolerance: Decimal::ZERO,
+            schedule: SettlementSchedule::default(),
+            retry_overrides: RetryOverrides::default(),
+            retry_overrides_refresh_secs: 60,
+            revenue_splits: RevenueSplits::default(),
+            energy_rounding: EnergyRounding::default(),
         };
 
-        assert_eq!(custom_config.fee_rate, Decimal::from_str("0.005").unwrap());
+        assert_eq!(custom_config.fee_schedule.base_rate, Decimal::from_str("0.005").unwrap());
+    }
+
+    fn mock_service(chain: Arc<MockBlockchainClient>) -> SettlementService {
+        let db = PgPool::connect_lazy("postgres://localhost/gridtokenx_test").unwrap();
+        SettlementService::with_client(db, chain, SettlementConfig::default(), "secret".to_string())
+    }
+
+    fn settlement_with_loss(energy: &str, effective: &str) -> Settlement {
+        Settlement {
+            id: Uuid::new_v4(),
+            epoch_id: Uuid::new_v4(),
+            match_id: None,
+            buyer_id: Uuid::new_v4(),
+            seller_id: Uuid::new_v4(),
+            buy_order_id: Uuid::new_v4(),
+            sell_order_id: Uuid::new_v4(),
+            energy_amount: Decimal::from_str(energy).unwrap(),
+            price: Decimal::from_str("0.15").unwrap(),
+            total_value: Decimal::ZERO,
+            fee_amount: Decimal::ZERO,
+            net_amount: Decimal::ZERO,
+            status: SettlementStatus::Pending,
+            blockchain_tx: None,
+            batch_signature: None,
+            created_at: Utc::now(),
+            buyer_zone_id: None,
+            seller_zone_id: None,
+            wheeling_charge: None,
+            loss_factor: None,
+            loss_cost: None,
+            effective_energy: Some(Decimal::from_str(effective).unwrap()),
+            confirmed_at: None,
+            buyer_session_token: None,
+            seller_session_token: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_transfer_converts_effective_energy_and_routes_loss_to_sink() {
+        let chain = Arc::new(MockBlockchainClient::new().with_slot(42));
+        let service = mock_service(chain.clone());
+        let seller = Keypair::new();
+        let buyer = Pubkey::new_unique();
+        let (mint, sink) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let settlement = settlement_with_loss("1.5", "1.4");
+
+        let prepared = service
+            .build_transfer(&settlement, &buyer.to_string(), &seller.pubkey().to_string(), seller.insecure_clone(), mint, Some(sink))
+            .await
+            .unwrap();
+        let result = service.send_transfer(prepared).await.unwrap();
+
+        let seller_ata = spl_associated_token_account::get_associated_token_address(&seller.pubkey(), &mint);
+        assert_eq!(
+            chain.transfers(),
+            vec![
+                BlockchainCall::Transfer {
+                    owner: seller.pubkey(),
+                    from: seller_ata,
+                    to: spl_associated_token_account::get_associated_token_address(&buyer, &mint),
+                    mint,
+                    amount: 1_400_000_000,
+                    decimals: 9,
+                },
+                BlockchainCall::Transfer {
+                    owner: seller.pubkey(),
+                    from: seller_ata,
+                    to: spl_associated_token_account::get_associated_token_address(&sink, &mint),
+                    mint,
+                    amount: 100_000_000,
+                    decimals: 9,
+                },
+            ]
+        );
+        assert_eq!(result.settlement_id, settlement.id);
+        assert_eq!(result.slot, 42);
+    }
+
+    #[tokio::test]
+    async fn test_seller_identity_mismatch_aborts_before_any_transfer() {
+        let chain = Arc::new(MockBlockchainClient::new());
+        let service = mock_service(chain.clone());
+        let settlement = settlement_with_loss("1", "1");
+
+        let result = service
+            .build_transfer(
+                &settlement,
+                &Pubkey::new_unique().to_string(),
+                &Pubkey::new_unique().to_string(),
+                Keypair::new(),
+                Pubkey::new_unique(),
+                None,
+            )
+            .await;
+
+        assert!(matches!(result, Err(ApiError::Validation(msg)) if msg.contains("identity mismatch")));
+        assert!(chain.calls().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_failed_transfer_surfaces_error() {
+        let chain = Arc::new(MockBlockchainClient::new());
+        chain.fail_transfers(SettlementError::ProgramError(1));
+        let service = mock_service(chain.clone());
+        let seller = Keypair::new();
+        let settlement = settlement_with_loss("2", "2");
+
+        let prepared = service
+            .build_transfer(&settlement, &Pubkey::new_unique().to_string(), &seller.pubkey().to_string(), seller.insecure_clone(), Pubkey::new_unique(), None)
+            .await
+            .unwrap();
+        assert!(prepared.loss_transfer.is_none());
+
+        let err = service.send_transfer(prepared).await.unwrap_err();
+        assert!(err.to_string().contains("Token transfer failed"));
+        assert!(matches!(err, ApiError::Settlement(SettlementError::ProgramError(1))));
+        assert!(!SettlementService::is_retryable_error(&err));
+        assert_eq!(chain.transfers().len(), 1);
     }
 }
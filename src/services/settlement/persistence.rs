@@ -0,0 +1,108 @@
+//! Settlement persistence
+//!
+//! The only place that writes or maps rows of the `settlements` table, shared
+//! by the epoch auction (`MarketClearingService`) and the continuous matching
+//! flow (`SettlementService`) so both produce identical rows.
+
+use sqlx::postgres::PgRow;
+use sqlx::{PgExecutor, Row};
+
+use super::types::{Settlement, SettlementStatus};
+
+/// Column list for reading a [`Settlement`] with [`settlement_from_row`]
+///
+/// Expects the table aliased as `s`. Order ids and the match id fall back to
+/// `order_matches` for rows written before they were stored on the settlement.
+pub const SETTLEMENT_SELECT: &str = r#"
+    SELECT
+        s.id, s.epoch_id, om.id AS match_id, s.buyer_id, s.seller_id,
+        COALESCE(s.buy_order_id, om.buy_order_id) AS buy_order_id,
+        COALESCE(s.sell_order_id, om.sell_order_id) AS sell_order_id,
+        s.energy_amount, s.price_per_kwh, s.total_amount, s.fee_amount, s.net_amount,
+        s.status, s.transaction_hash, s.batch_signature, s.created_at, s.processed_at,
+        s.wheeling_charge, s.loss_factor, s.loss_cost, s.effective_energy,
+        s.buyer_zone_id, s.seller_zone_id,
+        s.buyer_session_token, s.seller_session_token
+    FROM settlements s
+    LEFT JOIN LATERAL (
+        SELECT id, buy_order_id, sell_order_id FROM order_matches
+        WHERE settlement_id = s.id
+        LIMIT 1
+    ) om ON TRUE
+"#;
+
+/// Insert a new settlement row
+pub async fn insert_settlement<'e, E: PgExecutor<'e>>(
+    executor: E,
+    settlement: &Settlement,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        INSERT INTO settlements (
+            id, epoch_id, buyer_id, seller_id, buy_order_id, sell_order_id,
+            energy_amount, price_per_kwh, total_amount, fee_amount, net_amount, status, created_at,
+            wheeling_charge, loss_factor, loss_cost, effective_energy, buyer_zone_id, seller_zone_id,
+            buyer_session_token, seller_session_token
+        )
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18, $19, $20, $21)
+        "#,
+    )
+    .bind(settlement.id)
+    .bind(settlement.epoch_id)
+    .bind(settlement.buyer_id)
+    .bind(settlement.seller_id)
+    .bind(settlement.buy_order_id)
+    .bind(settlement.sell_order_id)
+    .bind(settlement.energy_amount)
+    .bind(settlement.price)
+    .bind(settlement.total_value)
+    .bind(settlement.fee_amount)
+    .bind(settlement.net_amount)
+    .bind(settlement.status.to_string())
+    .bind(settlement.created_at)
+    .bind(settlement.wheeling_charge)
+    .bind(settlement.loss_factor)
+    .bind(settlement.loss_cost)
+    .bind(settlement.effective_energy)
+    .bind(settlement.buyer_zone_id)
+    .bind(settlement.seller_zone_id)
+    .bind(&settlement.buyer_session_token)
+    .bind(&settlement.seller_session_token)
+    .execute(executor)
+    .await?;
+
+    Ok(())
+}
+
+/// Map a row selected with [`SETTLEMENT_SELECT`]
+pub fn settlement_from_row(row: &PgRow) -> Result<Settlement, sqlx::Error> {
+    let status: String = row.try_get("status")?;
+
+    Ok(Settlement {
+        id: row.try_get("id")?,
+        epoch_id: row.try_get("epoch_id")?,
+        match_id: row.try_get("match_id")?,
+        buyer_id: row.try_get("buyer_id")?,
+        seller_id: row.try_get("seller_id")?,
+        buy_order_id: row.try_get("buy_order_id")?,
+        sell_order_id: row.try_get("sell_order_id")?,
+        energy_amount: row.try_get("energy_amount")?,
+        price: row.try_get("price_per_kwh")?,
+        total_value: row.try_get("total_amount")?,
+        fee_amount: row.try_get("fee_amount")?,
+        net_amount: row.try_get("net_amount")?,
+        status: SettlementStatus::from_db(&status),
+        blockchain_tx: row.try_get("transaction_hash")?,
+        batch_signature: row.try_get("batch_signature")?,
+        created_at: row.try_get("created_at")?,
+        confirmed_at: row.try_get("processed_at")?,
+        wheeling_charge: row.try_get("wheeling_charge")?,
+        loss_factor: row.try_get("loss_factor")?,
+        loss_cost: row.try_get("loss_cost")?,
+        effective_energy: row.try_get("effective_energy")?,
+        buyer_zone_id: row.try_get("buyer_zone_id")?,
+        seller_zone_id: row.try_get("seller_zone_id")?,
+        buyer_session_token: row.try_get("buyer_session_token")?,
+        seller_session_token: row.try_get("seller_session_token")?,
+    })
+}
@@ -0,0 +1,166 @@
+//! Operator overrides for settlement retry classification
+//!
+//! Failed settlements are classified as retryable or not from their
+//! [`SettlementError`](crate::services::blockchain::SettlementError) variant.
+//! When a provider starts returning an error the built-in rules misjudge,
+//! operators can add message patterns through `SETTLEMENT_RETRYABLE_PATTERNS`,
+//! `SETTLEMENT_NON_RETRYABLE_PATTERNS` or the `settlement_retry_overrides`
+//! table, and reload the table without a restart.
+
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use utoipa::ToSchema;
+
+/// Where a retry decision came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum RetryRuleSource {
+    /// Built-in classification by error variant
+    Default,
+    /// `SETTLEMENT_*_PATTERNS` environment variables
+    Env,
+    /// `settlement_retry_overrides` table
+    Database,
+}
+
+/// One override pattern
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct RetryOverride {
+    /// Matched case-insensitively as a substring of the error message
+    pub pattern: String,
+    pub retryable: bool,
+    pub source: RetryRuleSource,
+}
+
+/// Retry decision for one failure
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RetryDecision {
+    pub retryable: bool,
+    pub source: RetryRuleSource,
+    /// Override pattern that decided, if any
+    pub pattern: Option<String>,
+}
+
+/// Override patterns, checked before the built-in classification
+///
+/// A message matching both a retryable and a non-retryable pattern is not
+/// retried: a dead-lettered settlement can be replayed, a duplicate transfer
+/// cannot be undone.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct RetryOverrides {
+    pub rules: Vec<RetryOverride>,
+}
+
+impl RetryOverrides {
+    /// Patterns from `SETTLEMENT_RETRYABLE_PATTERNS` and
+    /// `SETTLEMENT_NON_RETRYABLE_PATTERNS`, each comma separated
+    pub fn from_env() -> Self {
+        let patterns = |var: &str, retryable: bool| {
+            std::env::var(var)
+                .unwrap_or_default()
+                .split(',')
+                .map(str::trim)
+                .filter(|pattern| !pattern.is_empty())
+                .map(|pattern| RetryOverride {
+                    pattern: pattern.to_string(),
+                    retryable,
+                    source: RetryRuleSource::Env,
+                })
+                .collect::<Vec<_>>()
+        };
+
+        let mut rules = patterns("SETTLEMENT_RETRYABLE_PATTERNS", true);
+        rules.extend(patterns("SETTLEMENT_NON_RETRYABLE_PATTERNS", false));
+        Self { rules }
+    }
+
+    /// Patterns stored in `settlement_retry_overrides`
+    pub async fn load_from_db(db: &PgPool) -> Result<Self, sqlx::Error> {
+        let rows = sqlx::query_as::<_, (String, bool)>(
+            "SELECT pattern, retryable FROM settlement_retry_overrides ORDER BY created_at",
+        )
+        .fetch_all(db)
+        .await?;
+
+        Ok(Self {
+            rules: rows
+                .into_iter()
+                .map(|(pattern, retryable)| RetryOverride {
+                    pattern: pattern.trim().to_string(),
+                    retryable,
+                    source: RetryRuleSource::Database,
+                })
+                .filter(|rule| !rule.pattern.is_empty())
+                .collect(),
+        })
+    }
+
+    /// These rules followed by `other`'s
+    pub fn merge(mut self, other: Self) -> Self {
+        self.rules.extend(other.rules);
+        self
+    }
+
+    /// The override deciding `message`, if any pattern matches
+    pub fn matching(&self, message: &str) -> Option<&RetryOverride> {
+        let message = message.to_lowercase();
+        let mut matches = self
+            .rules
+            .iter()
+            .filter(|rule| message.contains(&rule.pattern.to_lowercase()));
+
+        let first = matches.next()?;
+        if !first.retryable {
+            return Some(first);
+        }
+        Some(matches.find(|rule| !rule.retryable).unwrap_or(first))
+    }
+
+    /// Apply the overrides to a failure the built-in rules judged `default_retryable`
+    pub fn decide(&self, message: &str, default_retryable: bool) -> RetryDecision {
+        match self.matching(message) {
+            Some(rule) => RetryDecision {
+                retryable: rule.retryable,
+                source: rule.source,
+                pattern: Some(rule.pattern.clone()),
+            },
+            None => RetryDecision {
+                retryable: default_retryable,
+                source: RetryRuleSource::Default,
+                pattern: None,
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(pattern: &str, retryable: bool) -> RetryOverride {
+        RetryOverride {
+            pattern: pattern.to_string(),
+            retryable,
+            source: RetryRuleSource::Database,
+        }
+    }
+
+    #[test]
+    fn test_override_beats_default_and_non_retryable_wins_ties() {
+        let overrides = RetryOverrides {
+            rules: vec![rule("Node Is Behind", true), rule("slot", false)],
+        };
+
+        let decision = overrides.decide("RPC error: node is behind by 120 slots", false);
+        assert!(!decision.retryable);
+        assert_eq!(decision.pattern.as_deref(), Some("slot"));
+
+        let decision = overrides.decide("node is behind", false);
+        assert!(decision.retryable);
+        assert_eq!(decision.source, RetryRuleSource::Database);
+
+        let decision = overrides.decide("insufficient funds", false);
+        assert_eq!(decision.source, RetryRuleSource::Default);
+        assert!(!decision.retryable);
+    }
+}
@@ -0,0 +1,210 @@
+//! Allocation of settlement revenue between recipients
+//!
+//! Platform fees, wheeling charges and loss costs can each be shared between
+//! the platform treasury, the grid operator and the insurance fund. Every
+//! share is recorded as its own `platform_revenue` row tagged with its
+//! recipient, so the grid operator's wheeling revenue can be reported apart
+//! from the platform's.
+//!
+//! Splits are written as `recipient:percent` pairs, e.g.
+//! `REVENUE_SPLIT_WHEELING_CHARGE=grid_operator:90,platform_treasury:10`, and
+//! must add up to 100.
+
+use rust_decimal::{Decimal, RoundingStrategy};
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+use utoipa::ToSchema;
+
+/// Decimal places of `platform_revenue.amount`
+const AMOUNT_SCALE: u32 = 8;
+
+/// Party a share of settlement revenue is owed to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum RevenueRecipient {
+    PlatformTreasury,
+    GridOperator,
+    InsuranceFund,
+}
+
+impl RevenueRecipient {
+    /// Value of the `platform_revenue.recipient` column
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::PlatformTreasury => "platform_treasury",
+            Self::GridOperator => "grid_operator",
+            Self::InsuranceFund => "insurance_fund",
+        }
+    }
+}
+
+impl FromStr for RevenueRecipient {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.trim() {
+            "platform_treasury" => Ok(Self::PlatformTreasury),
+            "grid_operator" => Ok(Self::GridOperator),
+            "insurance_fund" => Ok(Self::InsuranceFund),
+            other => Err(format!(
+                "unknown recipient '{}', expected platform_treasury, grid_operator or insurance_fund",
+                other
+            )),
+        }
+    }
+}
+
+/// One recipient's percentage of a revenue component
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct RevenueShare {
+    pub recipient: RevenueRecipient,
+    pub percent: Decimal,
+}
+
+/// How one revenue component is divided; the percentages add up to 100
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct RevenueSplit {
+    pub shares: Vec<RevenueShare>,
+}
+
+impl RevenueSplit {
+    /// Everything to `recipient`
+    pub fn whole(recipient: RevenueRecipient) -> Self {
+        Self {
+            shares: vec![RevenueShare {
+                recipient,
+                percent: Decimal::ONE_HUNDRED,
+            }],
+        }
+    }
+
+    /// Divide `amount` between the recipients, dropping zero shares
+    ///
+    /// Shares are rounded down to the column's precision and the last share
+    /// takes the remainder, so the allocations always sum to `amount` and
+    /// revenue reconciliation is unaffected by the split.
+    pub fn allocate(&self, amount: Decimal) -> Vec<(RevenueRecipient, Decimal)> {
+        let mut remaining = amount;
+        let mut allocations = Vec::with_capacity(self.shares.len());
+
+        for (index, share) in self.shares.iter().enumerate() {
+            let portion = if index + 1 == self.shares.len() {
+                remaining
+            } else {
+                (amount * share.percent / Decimal::ONE_HUNDRED)
+                    .round_dp_with_strategy(AMOUNT_SCALE, RoundingStrategy::ToZero)
+            };
+            remaining -= portion;
+            if portion > Decimal::ZERO {
+                allocations.push((share.recipient, portion));
+            }
+        }
+        allocations
+    }
+}
+
+impl Default for RevenueSplit {
+    fn default() -> Self {
+        Self::whole(RevenueRecipient::PlatformTreasury)
+    }
+}
+
+impl FromStr for RevenueSplit {
+    type Err = String;
+
+    fn from_str(spec: &str) -> Result<Self, Self::Err> {
+        let mut shares: Vec<RevenueShare> = Vec::new();
+        for entry in spec.split(',').map(str::trim).filter(|entry| !entry.is_empty()) {
+            let (recipient, percent) = entry
+                .split_once(':')
+                .ok_or_else(|| format!("expected recipient:percent, got '{}'", entry))?;
+            let recipient = recipient.parse::<RevenueRecipient>()?;
+            let percent = Decimal::from_str(percent.trim())
+                .map_err(|e| format!("invalid percent for {}: {}", recipient.as_str(), e))?;
+
+            if percent <= Decimal::ZERO {
+                return Err(format!("share for {} must be greater than zero", recipient.as_str()));
+            }
+            if shares.iter().any(|share| share.recipient == recipient) {
+                return Err(format!("{} listed more than once", recipient.as_str()));
+            }
+            shares.push(RevenueShare { recipient, percent });
+        }
+
+        if shares.is_empty() {
+            return Err("no recipients given".to_string());
+        }
+        let total: Decimal = shares.iter().map(|share| share.percent).sum();
+        if total != Decimal::ONE_HUNDRED {
+            return Err(format!("shares add up to {}%, expected 100%", total));
+        }
+        Ok(Self { shares })
+    }
+}
+
+/// Splits for each revenue component recorded by escrow finalization
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct RevenueSplits {
+    pub platform_fee: RevenueSplit,
+    pub wheeling_charge: RevenueSplit,
+    pub loss_cost: RevenueSplit,
+}
+
+impl RevenueSplits {
+    /// Read `REVENUE_SPLIT_PLATFORM_FEE`, `REVENUE_SPLIT_WHEELING_CHARGE` and
+    /// `REVENUE_SPLIT_LOSS_COST`; unset or invalid ones keep everything in the
+    /// treasury
+    ///
+    /// Invalid splits are reported by config validation before this runs.
+    pub fn from_env() -> Self {
+        let split = |var: &str| match std::env::var(var) {
+            Ok(spec) => spec.parse::<RevenueSplit>().unwrap_or_else(|e| {
+                tracing::error!("Invalid {}, crediting the platform treasury: {}", var, e);
+                RevenueSplit::default()
+            }),
+            Err(_) => RevenueSplit::default(),
+        };
+
+        Self {
+            platform_fee: split("REVENUE_SPLIT_PLATFORM_FEE"),
+            wheeling_charge: split("REVENUE_SPLIT_WHEELING_CHARGE"),
+            loss_cost: split("REVENUE_SPLIT_LOSS_COST"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_must_total_one_hundred_percent() {
+        let split: RevenueSplit = "grid_operator:70, platform_treasury:20, insurance_fund:10".parse().unwrap();
+        assert_eq!(split.shares.len(), 3);
+
+        assert!("grid_operator:70,platform_treasury:20".parse::<RevenueSplit>().is_err());
+        assert!("grid_operator:50,grid_operator:50".parse::<RevenueSplit>().is_err());
+        assert!("landlord:100".parse::<RevenueSplit>().is_err());
+        assert!("grid_operator:110,platform_treasury:-10".parse::<RevenueSplit>().is_err());
+        assert!("".parse::<RevenueSplit>().is_err());
+    }
+
+    #[test]
+    fn test_allocations_sum_to_amount() {
+        let split: RevenueSplit = "platform_treasury:33.33,grid_operator:33.33,insurance_fund:33.34".parse().unwrap();
+        let amount = Decimal::new(1, 8);
+        let allocations = split.allocate(amount);
+        assert_eq!(allocations.iter().map(|(_, share)| *share).sum::<Decimal>(), amount);
+        // Shares that round to nothing are not recorded
+        assert_eq!(allocations, vec![(RevenueRecipient::InsuranceFund, amount)]);
+
+        let wheeling: RevenueSplit = "grid_operator:90,platform_treasury:10".parse().unwrap();
+        assert_eq!(
+            wheeling.allocate(Decimal::new(250, 2)),
+            vec![
+                (RevenueRecipient::GridOperator, Decimal::new(225, 2)),
+                (RevenueRecipient::PlatformTreasury, Decimal::new(25, 2)),
+            ]
+        );
+    }
+}
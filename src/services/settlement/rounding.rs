@@ -0,0 +1,149 @@
+//! Rounding of effective energy to atomic token units
+//!
+//! A settlement debits the seller the gross energy amount, credits the buyer
+//! the energy left after grid losses and sends the difference to the loss
+//! sink. Effective energy rarely lands on a whole atomic unit; always
+//! truncating it shorts every buyer by a fraction of a unit that accumulates
+//! in the loss sink. The policy picks how the buyer's share is rounded, and
+//! the loss is whatever remains, so the two always add up to the seller's
+//! debit.
+
+use rust_decimal::{Decimal, RoundingStrategy};
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+use crate::utils::units::{to_atomic, to_atomic_rounded, ConversionError};
+
+/// How the buyer's effective energy is rounded to atomic units
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EnergyRounding {
+    /// Drop any fraction of a unit; the loss sink keeps it
+    #[default]
+    Truncate,
+    /// Round to the nearest unit, halves up
+    RoundHalfUp,
+    /// Round to the nearest unit, halves to the even unit, so halves favour
+    /// neither party over many settlements
+    RoundToNearestUnit,
+}
+
+impl EnergyRounding {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Truncate => "truncate",
+            Self::RoundHalfUp => "round_half_up",
+            Self::RoundToNearestUnit => "round_to_nearest_unit",
+        }
+    }
+
+    fn strategy(&self) -> RoundingStrategy {
+        match self {
+            Self::Truncate => RoundingStrategy::ToZero,
+            Self::RoundHalfUp => RoundingStrategy::MidpointAwayFromZero,
+            Self::RoundToNearestUnit => RoundingStrategy::MidpointNearestEven,
+        }
+    }
+
+    /// Split `gross` energy into the buyer's `effective` share and the grid
+    /// loss, in atomic units of a token with `decimals` decimals
+    ///
+    /// The seller's debit is `gross` truncated to a whole unit; the buyer's
+    /// share is rounded by this policy and never exceeds it, and the loss is
+    /// the remainder.
+    pub fn split_atomic(
+        &self,
+        gross: Decimal,
+        effective: Decimal,
+        decimals: u8,
+    ) -> Result<EnergySplit, ConversionError> {
+        let gross_atomic = to_atomic(gross, decimals)?;
+        let buyer_atomic = to_atomic_rounded(effective, decimals, self.strategy())?.min(gross_atomic);
+
+        Ok(EnergySplit {
+            gross_atomic,
+            buyer_atomic,
+            loss_atomic: gross_atomic - buyer_atomic,
+        })
+    }
+}
+
+impl FromStr for EnergyRounding {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.trim().to_lowercase().as_str() {
+            "truncate" => Ok(Self::Truncate),
+            "round_half_up" => Ok(Self::RoundHalfUp),
+            "round_to_nearest_unit" => Ok(Self::RoundToNearestUnit),
+            other => Err(format!(
+                "unknown rounding '{}', expected truncate, round_half_up or round_to_nearest_unit",
+                other
+            )),
+        }
+    }
+}
+
+/// Atomic amounts moved by one settlement transfer
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EnergySplit {
+    /// Debited from the seller
+    pub gross_atomic: u64,
+    /// Credited to the buyer
+    pub buyer_atomic: u64,
+    /// Sent to the loss sink
+    pub loss_atomic: u64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_buyer_and_loss_always_sum_to_seller_debit() {
+        let policies = [
+            EnergyRounding::Truncate,
+            EnergyRounding::RoundHalfUp,
+            EnergyRounding::RoundToNearestUnit,
+        ];
+        let gross_amounts = ["1.5", "100", "0.333333333", "7.123456789"];
+        let loss_factors = ["0", "0.03", "0.0333333", "0.05", "0.125", "0.5", "1"];
+
+        for policy in policies {
+            for gross in gross_amounts {
+                for loss_factor in loss_factors {
+                    let gross = Decimal::from_str(gross).unwrap();
+                    let effective = gross * (Decimal::ONE - Decimal::from_str(loss_factor).unwrap());
+                    let split = policy.split_atomic(gross, effective, 9).unwrap();
+                    assert_eq!(
+                        split.buyer_atomic + split.loss_atomic,
+                        split.gross_atomic,
+                        "{} at loss {} with {}",
+                        gross,
+                        loss_factor,
+                        policy.as_str()
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_policy_decides_who_keeps_the_fraction() {
+        // 1 kWh at 3 decimals with a third lost: 666.666... Wh effective
+        let gross = Decimal::ONE;
+        let effective = gross * (Decimal::ONE - Decimal::ONE / Decimal::from(3));
+
+        let truncated = EnergyRounding::Truncate.split_atomic(gross, effective, 3).unwrap();
+        assert_eq!((truncated.buyer_atomic, truncated.loss_atomic), (666, 334));
+
+        let rounded = EnergyRounding::RoundHalfUp.split_atomic(gross, effective, 3).unwrap();
+        assert_eq!((rounded.buyer_atomic, rounded.loss_atomic), (667, 333));
+
+        // Exactly half a unit: half-up gives it to the buyer, nearest-even
+        // only when that lands on an even unit
+        let half = Decimal::from_str("0.0025").unwrap();
+        assert_eq!(EnergyRounding::RoundHalfUp.split_atomic(Decimal::ONE, half, 3).unwrap().buyer_atomic, 3);
+        assert_eq!(EnergyRounding::RoundToNearestUnit.split_atomic(Decimal::ONE, half, 3).unwrap().buyer_atomic, 2);
+    }
+}
@@ -0,0 +1,216 @@
+//! Settlement scheduling windows
+//!
+//! Operators can restrict on-chain settlement to off-peak hours, when RPC
+//! capacity is cheaper and priority fees lower. Outside every window pending
+//! settlements accumulate; the first run inside a window drains the backlog.
+//! Settlements flagged urgent go out regardless.
+
+use chrono::{DateTime, Datelike, Duration, FixedOffset, NaiveTime, Utc, Weekday};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// One recurring window, e.g. `mon-fri 22:00-06:00`
+///
+/// A window whose end is before its start runs past midnight, and one whose
+/// end equals its start lasts a whole day; the days are the days it opens on.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SettlementWindow {
+    /// Days the window opens on, Monday first
+    pub days: [bool; 7],
+    pub start: NaiveTime,
+    pub end: NaiveTime,
+}
+
+impl SettlementWindow {
+    fn length(&self) -> Duration {
+        let length = self.end - self.start;
+        if length <= Duration::zero() {
+            length + Duration::days(1)
+        } else {
+            length
+        }
+    }
+}
+
+/// When settlements may be executed
+///
+/// With no windows configured settlement runs continuously.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SettlementSchedule {
+    pub windows: Vec<SettlementWindow>,
+    /// Offset the window times are written in
+    pub utc_offset: FixedOffset,
+}
+
+impl Default for SettlementSchedule {
+    fn default() -> Self {
+        Self {
+            windows: Vec::new(),
+            utc_offset: FixedOffset::east_opt(0).expect("zero offset"),
+        }
+    }
+}
+
+/// Where the schedule stands at a point in time
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct SettlementScheduleStatus {
+    /// False when settlement is restricted to windows
+    pub continuous: bool,
+    /// Whether non-urgent settlements are being executed now
+    pub window_open: bool,
+    /// Start of the current window, or of the next one if none is open
+    pub next_window_start: Option<DateTime<Utc>>,
+    pub next_window_end: Option<DateTime<Utc>>,
+}
+
+impl SettlementSchedule {
+    /// Parse `;`-separated windows of the form `[days ]HH:MM-HH:MM`, where
+    /// days are a range (`mon-fri`) or a list (`sat,sun`) and default to
+    /// every day. `24:00` may be used as an end time.
+    pub fn parse(spec: &str, utc_offset: FixedOffset) -> Result<Self, String> {
+        let windows = spec
+            .split(';')
+            .map(str::trim)
+            .filter(|entry| !entry.is_empty())
+            .map(parse_window)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Self { windows, utc_offset })
+    }
+
+    pub fn is_continuous(&self) -> bool {
+        self.windows.is_empty()
+    }
+
+    /// The window containing `now`, or else the next one to open
+    pub fn current_or_next_window(&self, now: DateTime<Utc>) -> Option<(DateTime<Utc>, DateTime<Utc>)> {
+        let local_today = now.with_timezone(&self.utc_offset).date_naive();
+
+        // Yesterday's windows may still be open past midnight
+        (-1..=7)
+            .filter_map(|offset| local_today.checked_add_signed(Duration::days(offset)))
+            .flat_map(|date| {
+                self.windows.iter().filter_map(move |window| {
+                    if !window.days[date.weekday().num_days_from_monday() as usize] {
+                        return None;
+                    }
+                    let start = date
+                        .and_time(window.start)
+                        .and_local_timezone(self.utc_offset)
+                        .single()?
+                        .with_timezone(&Utc);
+                    Some((start, start + window.length()))
+                })
+            })
+            .filter(|(_, end)| *end > now)
+            .min_by_key(|(start, _)| *start)
+    }
+
+    /// Whether non-urgent settlements may be executed at `now`
+    pub fn is_open(&self, now: DateTime<Utc>) -> bool {
+        self.is_continuous()
+            || self
+                .current_or_next_window(now)
+                .is_some_and(|(start, _)| start <= now)
+    }
+
+    pub fn status(&self, now: DateTime<Utc>) -> SettlementScheduleStatus {
+        let window = self.current_or_next_window(now);
+        SettlementScheduleStatus {
+            continuous: self.is_continuous(),
+            window_open: self.is_open(now),
+            next_window_start: window.map(|(start, _)| start),
+            next_window_end: window.map(|(_, end)| end),
+        }
+    }
+}
+
+fn parse_window(entry: &str) -> Result<SettlementWindow, String> {
+    let (days, times) = match entry.rsplit_once(char::is_whitespace) {
+        Some((days, times)) => (parse_days(days.trim())?, times),
+        None => ([true; 7], entry),
+    };
+
+    let (start, end) = times
+        .split_once('-')
+        .ok_or_else(|| format!("expected HH:MM-HH:MM, got '{}'", times))?;
+    Ok(SettlementWindow {
+        days,
+        start: parse_time(start)?,
+        end: parse_time(end)?,
+    })
+}
+
+fn parse_time(value: &str) -> Result<NaiveTime, String> {
+    let value = value.trim();
+    if value == "24:00" {
+        return Ok(NaiveTime::MIN);
+    }
+    NaiveTime::parse_from_str(value, "%H:%M").map_err(|e| format!("invalid time '{}': {}", value, e))
+}
+
+fn parse_days(value: &str) -> Result<[bool; 7], String> {
+    let day = |name: &str| -> Result<usize, String> {
+        name.trim()
+            .parse::<Weekday>()
+            .map(|day| day.num_days_from_monday() as usize)
+            .map_err(|_| format!("invalid day '{}'", name.trim()))
+    };
+
+    let mut days = [false; 7];
+    for part in value.split(',') {
+        match part.split_once('-') {
+            Some((from, to)) => {
+                let (from, to) = (day(from)?, day(to)?);
+                let mut current = from;
+                loop {
+                    days[current] = true;
+                    if current == to {
+                        break;
+                    }
+                    current = (current + 1) % 7;
+                }
+            }
+            None => days[day(part)?] = true,
+        }
+    }
+    Ok(days)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_overnight_window() {
+        let bangkok = FixedOffset::east_opt(7 * 3600).unwrap();
+        let schedule = SettlementSchedule::parse("mon-fri 22:00-06:00; sat,sun 00:00-24:00", bangkok).unwrap();
+        assert_eq!(schedule.windows.len(), 2);
+
+        // Tuesday 12:00 Bangkok: closed until 22:00
+        let noon = bangkok.with_ymd_and_hms(2026, 1, 20, 12, 0, 0).unwrap().with_timezone(&Utc);
+        assert!(!schedule.is_open(noon));
+        let (start, end) = schedule.current_or_next_window(noon).unwrap();
+        assert_eq!(start, bangkok.with_ymd_and_hms(2026, 1, 20, 22, 0, 0).unwrap());
+        assert_eq!(end, bangkok.with_ymd_and_hms(2026, 1, 21, 6, 0, 0).unwrap());
+
+        // Wednesday 03:00 is inside Tuesday's window
+        let night = bangkok.with_ymd_and_hms(2026, 1, 21, 3, 0, 0).unwrap().with_timezone(&Utc);
+        assert!(schedule.is_open(night));
+
+        // Saturday afternoon is inside the weekend window
+        let weekend = bangkok.with_ymd_and_hms(2026, 1, 24, 15, 0, 0).unwrap().with_timezone(&Utc);
+        assert!(schedule.is_open(weekend));
+    }
+
+    #[test]
+    fn test_empty_schedule_is_continuous() {
+        let schedule = SettlementSchedule::default();
+        assert!(schedule.is_open(Utc::now()));
+        assert!(schedule.status(Utc::now()).next_window_start.is_none());
+
+        assert!(SettlementSchedule::parse("22:00", FixedOffset::east_opt(0).unwrap()).is_err());
+        assert!(SettlementSchedule::parse("funday 01:00-02:00", FixedOffset::east_opt(0).unwrap()).is_err());
+    }
+}
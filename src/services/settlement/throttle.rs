@@ -0,0 +1,156 @@
+//! Adaptive pacing between settlements
+//!
+//! The delay before each settlement widens when the RPC provider starts
+//! rate limiting us and narrows back once settlements go through again, so a
+//! throttled provider slows the run down instead of failing the whole batch.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+use crate::middleware::metrics;
+use crate::services::blockchain::SettlementError;
+
+/// Default floor for the inter-settlement delay
+const DEFAULT_MIN_DELAY_MS: u64 = 100;
+/// Default ceiling for the inter-settlement delay
+const DEFAULT_MAX_DELAY_MS: u64 = 10_000;
+
+/// Inter-settlement delay that backs off on rate-limit errors
+#[derive(Debug)]
+pub struct AdaptiveDelay {
+    min_ms: u64,
+    max_ms: u64,
+    current_ms: AtomicU64,
+}
+
+impl Default for AdaptiveDelay {
+    fn default() -> Self {
+        Self::new(
+            Duration::from_millis(DEFAULT_MIN_DELAY_MS),
+            Duration::from_millis(DEFAULT_MAX_DELAY_MS),
+        )
+    }
+}
+
+impl AdaptiveDelay {
+    pub fn new(min: Duration, max: Duration) -> Self {
+        let min_ms = min.as_millis() as u64;
+        let max_ms = (max.as_millis() as u64).max(min_ms);
+        Self {
+            min_ms,
+            max_ms,
+            current_ms: AtomicU64::new(min_ms),
+        }
+    }
+
+    /// Bounds from `SETTLEMENT_DELAY_MIN_MS` and `SETTLEMENT_DELAY_MAX_MS`
+    pub fn from_env() -> Self {
+        let min_ms = std::env::var("SETTLEMENT_DELAY_MIN_MS")
+            .ok()
+            .and_then(|v| v.parse::<u64>().ok())
+            .unwrap_or(DEFAULT_MIN_DELAY_MS);
+        let max_ms = std::env::var("SETTLEMENT_DELAY_MAX_MS")
+            .ok()
+            .and_then(|v| v.parse::<u64>().ok())
+            .unwrap_or(DEFAULT_MAX_DELAY_MS);
+
+        Self::new(Duration::from_millis(min_ms), Duration::from_millis(max_ms))
+    }
+
+    /// Current delay, without jitter
+    pub fn current(&self) -> Duration {
+        Duration::from_millis(self.current_ms.load(Ordering::Relaxed))
+    }
+
+    /// Delay to wait before the next settlement: the current delay plus up
+    /// to 25% jitter so concurrent workers do not retry in lockstep
+    pub fn next_delay(&self) -> Duration {
+        let current = self.current_ms.load(Ordering::Relaxed);
+        let jitter = rand::random::<u64>() % (current / 4 + 1);
+        Duration::from_millis(current + jitter)
+    }
+
+    /// Double the delay (up to the ceiling) after a rate-limit error
+    pub fn record_rate_limited(&self) {
+        self.update(|current| current.saturating_mul(2).max(self.min_ms.max(1)));
+    }
+
+    /// Shrink the delay by a quarter (down to the floor) after a success
+    pub fn record_success(&self) {
+        self.update(|current| current - current / 4);
+    }
+
+    /// Feed a settlement outcome back into the delay: `None` on success, or
+    /// the classified transfer failure
+    pub fn record_result(&self, error: Option<&SettlementError>) {
+        match error {
+            Some(SettlementError::RateLimited) => self.record_rate_limited(),
+            Some(_) => {}
+            None => self.record_success(),
+        }
+    }
+
+    fn update(&self, f: impl Fn(u64) -> u64) {
+        let (min, max) = (self.min_ms, self.max_ms);
+        let previous = self
+            .current_ms
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |current| {
+                Some(f(current).clamp(min, max))
+            })
+            .unwrap_or(min);
+        let now = f(previous).clamp(min, max);
+        metrics::track_settlement_delay(now as f64);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn delay() -> AdaptiveDelay {
+        AdaptiveDelay::new(Duration::from_millis(100), Duration::from_millis(1_000))
+    }
+
+    #[test]
+    fn test_widens_on_rate_limit_up_to_ceiling() {
+        let delay = delay();
+        delay.record_result(Some(&SettlementError::RateLimited));
+        assert_eq!(delay.current(), Duration::from_millis(200));
+        for _ in 0..10 {
+            delay.record_rate_limited();
+        }
+        assert_eq!(delay.current(), Duration::from_millis(1_000));
+    }
+
+    #[test]
+    fn test_narrows_back_to_floor_when_healthy() {
+        let delay = delay();
+        for _ in 0..4 {
+            delay.record_rate_limited();
+        }
+        delay.record_result(None);
+        assert_eq!(delay.current(), Duration::from_millis(1_000 - 250));
+        for _ in 0..20 {
+            delay.record_success();
+        }
+        assert_eq!(delay.current(), Duration::from_millis(100));
+    }
+
+    #[test]
+    fn test_other_errors_leave_delay_alone() {
+        let delay = delay();
+        delay.record_rate_limited();
+        delay.record_result(Some(&SettlementError::InsufficientFunds("transaction".to_string())));
+        assert_eq!(delay.current(), Duration::from_millis(200));
+    }
+
+    #[test]
+    fn test_jitter_stays_within_a_quarter() {
+        let delay = delay();
+        delay.record_rate_limited();
+        for _ in 0..100 {
+            let next = delay.next_delay();
+            assert!(next >= Duration::from_millis(200) && next <= Duration::from_millis(250));
+        }
+    }
+}
@@ -2,8 +2,15 @@ use chrono::{DateTime, Utc};
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 use std::str::FromStr;
+use utoipa::ToSchema;
 use uuid::Uuid;
 
+use super::retry_policy::RetryOverrides;
+use super::revenue_split::{RevenueRecipient, RevenueSplits};
+use super::rounding::EnergyRounding;
+use super::schedule::SettlementSchedule;
+use crate::services::fees::FeeSchedule;
+
 /// Settlement status
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub enum SettlementStatus {
@@ -13,6 +20,8 @@ pub enum SettlementStatus {
     Failed,
     PendingBridge,
     BridgingInitiated,
+    /// Failed with a non-retryable error; only an admin replay requeues it
+    PermanentlyFailed,
 }
 
 impl std::fmt::Display for SettlementStatus {
@@ -24,15 +33,37 @@ impl std::fmt::Display for SettlementStatus {
             Self::Failed => write!(f, "failed"),
             Self::PendingBridge => write!(f, "pending_bridge"),
             Self::BridgingInitiated => write!(f, "bridging_initiated"),
+            Self::PermanentlyFailed => write!(f, "permanently_failed"),
+        }
+    }
+}
+
+impl SettlementStatus {
+    /// Parse the `settlements.status` column; unknown values read as pending
+    pub fn from_db(status: &str) -> Self {
+        match status.to_lowercase().as_str() {
+            "pending" => Self::Pending,
+            "processing" => Self::Processing,
+            "completed" | "confirmed" => Self::Completed,
+            "failed" => Self::Failed,
+            "pending_bridge" => Self::PendingBridge,
+            "bridging_initiated" => Self::BridgingInitiated,
+            "permanently_failed" => Self::PermanentlyFailed,
+            _ => Self::Pending,
         }
     }
 }
 
 /// Settlement record
+///
+/// The one representation of a `settlements` row, used by both the epoch
+/// auction and continuous matching.
 #[derive(Debug, Clone, Serialize)]
 pub struct Settlement {
     pub id: Uuid,
-    pub trade_id: Uuid,
+    pub epoch_id: Uuid,
+    /// The `order_matches` row this settlement pays out
+    pub match_id: Option<Uuid>,
     pub buyer_id: Uuid,
     pub seller_id: Uuid,
     // Add missing fields for PDA lookup
@@ -45,6 +76,8 @@ pub struct Settlement {
     pub net_amount: Decimal,
     pub status: SettlementStatus,
     pub blockchain_tx: Option<String>,
+    /// Signature of the multi-transfer transaction this settlement was part of
+    pub batch_signature: Option<String>,
     pub created_at: DateTime<Utc>,
     pub confirmed_at: Option<DateTime<Utc>>,
     // Zone and Cost allocations
@@ -70,21 +103,35 @@ pub struct SettlementTransaction {
 /// Settlement service configuration
 #[derive(Debug, Clone)]
 pub struct SettlementConfig {
-    pub fee_rate: Decimal,            // Platform fee (e.g., 0.01 = 1%)
+    pub fee_schedule: FeeSchedule,    // Platform fee rates (flat 1% by default)
     pub min_confirmation_blocks: u64, // Minimum blocks for confirmation
     pub retry_attempts: u32,          // Number of retry attempts for failed transactions
     pub retry_delay_secs: u64,        // Delay between retries
     pub enable_real_blockchain: bool, // Enable/disable real blockchain interactions
+    pub batch_max_settlements: usize, // Settlements per multi-transfer transaction (1 disables batching)
+    pub reconciliation_tolerance: Decimal, // Max allowed revenue drift per settlement
+    pub schedule: SettlementSchedule, // Windows non-urgent settlements are executed in
+    pub retry_overrides: RetryOverrides, // Retry classification patterns from the environment
+    pub retry_overrides_refresh_secs: u64, // How often every replica re-reads the overrides table
+    pub revenue_splits: RevenueSplits, // How fees, wheeling and loss revenue are shared between recipients
+    pub energy_rounding: EnergyRounding, // How the buyer's effective energy is rounded to atomic units
 }
 
 impl Default for SettlementConfig {
     fn default() -> Self {
         Self {
-            fee_rate: Decimal::from_str("0.01").expect("valid hardcoded decimal 0.01"), // 1% platform fee
+            fee_schedule: FeeSchedule::default(),
             min_confirmation_blocks: 32,                  // ~13 seconds on Solana
             retry_attempts: 3,
             retry_delay_secs: 5,
             enable_real_blockchain: true, // Default to true for safety
+            batch_max_settlements: 8,
+            reconciliation_tolerance: Decimal::new(1, 8), // Smallest NUMERIC(20, 8) unit
+            schedule: SettlementSchedule::default(), // Continuous
+            retry_overrides: RetryOverrides::default(),
+            retry_overrides_refresh_secs: 60,
+            revenue_splits: RevenueSplits::default(), // Everything to the platform treasury
+            energy_rounding: EnergyRounding::default(), // Truncate
         }
     }
 }
@@ -94,13 +141,14 @@ impl SettlementConfig {
     pub fn from_env() -> Self {
         let mut config = Self::default();
 
-        // Read fee rate from environment
-        if let Ok(val) = std::env::var("SETTLEMENT_FEE_RATE") {
-            if let Ok(rate) = Decimal::from_str(&val) {
-                config.fee_rate = rate;
-                tracing::info!("Using custom settlement fee rate: {}", rate);
-            }
-        }
+        // Read fee schedule from environment
+        config.fee_schedule = FeeSchedule::from_env();
+        tracing::info!(
+            "Settlement fee schedule: base_rate={}, {} volume tiers, {} role rates",
+            config.fee_schedule.base_rate,
+            config.fee_schedule.volume_tiers.len(),
+            config.fee_schedule.role_rates.len()
+        );
 
         // Read blockchain mode from environment (use same env var as tokenization)
         if let Ok(val) = std::env::var("TOKENIZATION_ENABLE_REAL_BLOCKCHAIN") {
@@ -124,11 +172,96 @@ impl SettlementConfig {
             }
         }
 
+        // Read settlement batch size from environment
+        if let Ok(val) = std::env::var("SETTLEMENT_BATCH_MAX") {
+            if let Ok(max) = val.parse::<usize>() {
+                config.batch_max_settlements = max.max(1);
+            }
+        }
+
+        // Read revenue reconciliation tolerance from environment
+        if let Ok(val) = std::env::var("SETTLEMENT_RECONCILIATION_TOLERANCE") {
+            if let Ok(tolerance) = Decimal::from_str(&val) {
+                config.reconciliation_tolerance = tolerance;
+            }
+        }
+
+        // Read settlement windows from environment
+        if let Ok(spec) = std::env::var("SETTLEMENT_WINDOWS") {
+            let offset = std::env::var("SETTLEMENT_WINDOW_UTC_OFFSET")
+                .ok()
+                .and_then(|val| chrono::FixedOffset::from_str(val.trim()).ok())
+                .unwrap_or(config.schedule.utc_offset);
+            match SettlementSchedule::parse(&spec, offset) {
+                Ok(schedule) => {
+                    tracing::info!(
+                        "Settlement windows: {} (UTC{}), continuous={}",
+                        spec,
+                        offset,
+                        schedule.is_continuous()
+                    );
+                    config.schedule = schedule;
+                }
+                Err(e) => tracing::error!("Invalid SETTLEMENT_WINDOWS, settling continuously: {}", e),
+            }
+        }
+
+        // Read retry classification overrides from environment
+        config.retry_overrides = RetryOverrides::from_env();
+        if !config.retry_overrides.rules.is_empty() {
+            tracing::info!(
+                "Settlement retry overrides from environment: {} patterns",
+                config.retry_overrides.rules.len()
+            );
+        }
+
+        // Read retry override refresh interval from environment
+        if let Ok(val) = std::env::var("SETTLEMENT_RETRY_OVERRIDES_REFRESH_SECS") {
+            if let Ok(secs) = val.parse::<u64>() {
+                config.retry_overrides_refresh_secs = secs.max(1);
+            }
+        }
+
+        // Read revenue recipient splits from environment
+        config.revenue_splits = RevenueSplits::from_env();
+
+        // Read effective energy rounding from environment
+        if let Ok(val) = std::env::var("SETTLEMENT_ENERGY_ROUNDING") {
+            match val.parse::<EnergyRounding>() {
+                Ok(rounding) => {
+                    tracing::info!("Settlement energy rounding: {}", rounding.as_str());
+                    config.energy_rounding = rounding;
+                }
+                Err(e) => tracing::error!("Invalid SETTLEMENT_ENERGY_ROUNDING, truncating: {}", e),
+            }
+        }
+
         config
     }
 }
 
 
+/// Outcome of [`super::SettlementService::execute_batch`]
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct SettlementBatchResult {
+    /// Signatures of the multi-transfer transactions that landed
+    pub batch_signatures: Vec<String>,
+    /// Settled inside a multi-transfer transaction
+    pub batched: Vec<Uuid>,
+    /// Settled on their own, after their batch failed or could not be prepared
+    pub individually_settled: Vec<Uuid>,
+    pub failed: Vec<Uuid>,
+    /// Left processing because their batch may have landed; reconcile
+    /// against the chain before settling them again
+    pub unresolved: Vec<Uuid>,
+}
+
+impl SettlementBatchResult {
+    pub fn settled_count(&self) -> usize {
+        self.batched.len() + self.individually_settled.len()
+    }
+}
+
 /// Settlement statistics
 #[derive(Debug, Clone, Serialize)]
 pub struct SettlementStats {
@@ -138,3 +271,179 @@ pub struct SettlementStats {
     pub failed_count: i64,
     pub total_settled_value: Decimal,
 }
+
+/// A permanently failed settlement waiting in the dead-letter queue
+#[derive(Debug, Clone, Serialize, ToSchema, sqlx::FromRow)]
+pub struct SettlementDeadLetter {
+    pub settlement_id: Uuid,
+    pub buyer_id: Uuid,
+    pub seller_id: Uuid,
+    pub energy_amount: Decimal,
+    pub total_amount: Decimal,
+    /// Error that was classified as non-retryable
+    pub error_message: String,
+    /// Automatic retries made before the failure was recorded
+    pub retry_count: i32,
+    pub failed_at: DateTime<Utc>,
+}
+
+/// Per-settlement mismatch between expected and recorded platform revenue
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct RevenueDiscrepancy {
+    pub settlement_id: Uuid,
+    pub expected_revenue: Decimal,
+    pub recorded_revenue: Decimal,
+    pub difference: Decimal,
+}
+
+/// Reconciliation of `platform_revenue` against completed settlements in a window
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct RevenueReconciliation {
+    pub from: DateTime<Utc>,
+    pub to: DateTime<Utc>,
+    pub settlement_count: i64,
+    pub expected_total: Decimal,
+    pub recorded_total: Decimal,
+    pub difference: Decimal,
+    pub tolerance: Decimal,
+    pub is_balanced: bool,
+    pub discrepancies: Vec<RevenueDiscrepancy>,
+}
+
+/// Revenue credited to one recipient in a window
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct RecipientRevenue {
+    pub recipient: RevenueRecipient,
+    pub total: Decimal,
+    pub platform_fees: Decimal,
+    pub wheeling_charges: Decimal,
+    pub loss_costs: Decimal,
+    pub settlement_count: i64,
+}
+
+/// Platform revenue by recipient, with the splits currently applied
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct RevenueByRecipient {
+    pub from: DateTime<Utc>,
+    pub to: DateTime<Utc>,
+    pub recipients: Vec<RecipientRevenue>,
+    pub splits: RevenueSplits,
+}
+
+/// One `platform_revenue` row recorded for a settlement
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct SettlementRevenueEntry {
+    pub id: Uuid,
+    /// `platform_fee`, `wheeling_charge` or `loss_cost`
+    pub revenue_type: String,
+    pub recipient: RevenueRecipient,
+    pub amount: Decimal,
+    pub description: Option<String>,
+    pub created_at: Option<DateTime<Utc>>,
+    /// Treasury sweep that paid this row out, if any; admins only
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sweep_id: Option<Uuid>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub swept_at: Option<DateTime<Utc>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sweep_signature: Option<String>,
+}
+
+/// Escrow held for one of the settlement's orders
+///
+/// Escrow is locked per order, so an order filled by several matches shares
+/// its records across their settlements.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct SettlementEscrowEntry {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub order_id: Option<Uuid>,
+    /// `currency` or `energy`
+    pub asset_type: String,
+    /// `buy_lock` or `sell_lock`
+    pub escrow_type: String,
+    /// `locked`, `released` or `refunded`
+    pub status: String,
+    pub amount: Decimal,
+    pub description: Option<String>,
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+/// Receipt of a settlement: the settlement, what it paid to the platform and
+/// its recipients, the escrow behind it and the transactions that moved it
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct SettlementDetail {
+    #[schema(value_type = Object)]
+    pub settlement: Settlement,
+    pub revenue: Vec<SettlementRevenueEntry>,
+    /// Sum of `revenue`; fee, wheeling charge and loss cost together
+    pub revenue_total: Decimal,
+    pub escrow: Vec<SettlementEscrowEntry>,
+    /// Distinct on-chain signatures that moved the settlement: its own
+    /// transfer, the multi-transfer transaction if batched, and the escrow
+    /// refunds of its orders
+    pub signatures: Vec<String>,
+}
+
+/// Outcome of a single step in a settlement dry-run
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum SettlementPathStepStatus {
+    Passed,
+    Failed,
+    /// Not run because an earlier step it depends on failed
+    Skipped,
+}
+
+/// A single step of the settlement path checked during a dry-run
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct SettlementPathStep {
+    pub name: String,
+    pub status: SettlementPathStepStatus,
+    pub detail: String,
+}
+
+/// Dry-run report of the on-chain settlement path, without moving tokens
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct SettlementPathReport {
+    pub settlement_id: Uuid,
+    pub steps: Vec<SettlementPathStep>,
+    pub all_passed: bool,
+}
+
+impl SettlementPathReport {
+    pub fn new(settlement_id: Uuid) -> Self {
+        Self {
+            settlement_id,
+            steps: Vec::new(),
+            all_passed: true,
+        }
+    }
+
+    pub fn pass(&mut self, name: &str, detail: impl Into<String>) {
+        self.push(name, SettlementPathStepStatus::Passed, detail.into());
+    }
+
+    pub fn fail(&mut self, name: &str, detail: impl Into<String>) {
+        self.all_passed = false;
+        self.push(name, SettlementPathStepStatus::Failed, detail.into());
+    }
+
+    pub fn skip(&mut self, name: &str, reason: &str) {
+        self.all_passed = false;
+        self.push(name, SettlementPathStepStatus::Skipped, reason.to_string());
+    }
+
+    /// Status of the named step, if it was recorded
+    pub fn step_status(&self, name: &str) -> Option<SettlementPathStepStatus> {
+        self.steps.iter().find(|s| s.name == name).map(|s| s.status)
+    }
+
+    fn push(&mut self, name: &str, status: SettlementPathStepStatus, detail: String) {
+        self.steps.push(SettlementPathStep {
+            name: name.to_string(),
+            status,
+            detail,
+        });
+    }
+}
@@ -0,0 +1,294 @@
+//! Renewable mix and CO2-avoided accounting
+//!
+//! Renewable generation is read from meter readings, classified by the
+//! meter's type, and settled P2P trades are attributed to the source of the
+//! seller's meter. Each renewable kWh avoids the grid emission factor less
+//! the source's own lifecycle factor (see [`EmissionFactors`]).
+//!
+//! The all-time total shown on the grid status feed is kept as a running
+//! tally: each refresh adds only the readings stored since the previous one,
+//! up to a short settle lag so readings still being written are not skipped.
+
+use chrono::{DateTime, Duration, Utc};
+use serde::Serialize;
+use sqlx::{FromRow, PgPool};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::config::EmissionFactors;
+
+/// Energy from one source, zone and user
+#[derive(Debug, Clone, FromRow)]
+struct EnergyRow {
+    source: Option<String>,
+    zone_id: Option<i32>,
+    user_id: Option<Uuid>,
+    kwh: f64,
+}
+
+#[derive(Debug, Clone, Default, Serialize, ToSchema)]
+pub struct SourceSustainability {
+    pub source: String,
+    pub generation_kwh: f64,
+    pub traded_kwh: f64,
+    pub co2_avoided_kg: f64,
+    /// Share of all metered generation, renewable or not
+    pub share_of_generation: f64,
+}
+
+#[derive(Debug, Clone, Default, Serialize, ToSchema)]
+pub struct ZoneSustainability {
+    pub zone_id: Option<i32>,
+    pub renewable_generation_kwh: f64,
+    pub renewable_traded_kwh: f64,
+    pub co2_avoided_kg: f64,
+}
+
+#[derive(Debug, Clone, Default, Serialize, ToSchema)]
+pub struct UserSustainability {
+    pub user_id: Uuid,
+    pub renewable_generation_kwh: f64,
+    /// Renewable energy bought through settled trades
+    pub renewable_purchased_kwh: f64,
+    /// Avoided by the user's own generation
+    pub co2_avoided_kg: f64,
+    /// Avoided by the renewable energy the user bought
+    pub purchased_co2_avoided_kg: f64,
+}
+
+#[derive(Debug, Clone, Default, Serialize, ToSchema)]
+pub struct SustainabilityReport {
+    pub since: Option<DateTime<Utc>>,
+    pub generation_kwh: f64,
+    pub renewable_generation_kwh: f64,
+    /// Renewable share of metered generation (0.0 - 1.0)
+    pub renewable_share: f64,
+    pub renewable_traded_kwh: f64,
+    /// Avoided by renewable generation
+    pub co2_avoided_kg: f64,
+    /// Avoided by renewable energy delivered through P2P trades; a subset of
+    /// the generation above, not in addition to it
+    pub traded_co2_avoided_kg: f64,
+    pub by_source: Vec<SourceSustainability>,
+    pub by_zone: Vec<ZoneSustainability>,
+    pub by_user: Vec<UserSustainability>,
+}
+
+/// Readings younger than this are left for the next tally pass, so one
+/// committed late with an earlier `created_at` is still counted
+const TALLY_SETTLE_SECS: i64 = 120;
+
+/// Renewable generation counted so far by [`SustainabilityService::total_co2_avoided_kg`]
+#[derive(Debug, Default)]
+struct GenerationTally {
+    /// Readings created up to here are included
+    counted_until: Option<DateTime<Utc>>,
+    kwh_by_source: HashMap<String, f64>,
+}
+
+#[derive(Clone)]
+pub struct SustainabilityService {
+    db: PgPool,
+    factors: EmissionFactors,
+    tally: Arc<Mutex<GenerationTally>>,
+}
+
+impl SustainabilityService {
+    pub fn new(db: PgPool, factors: EmissionFactors) -> Self {
+        Self {
+            db,
+            factors,
+            tally: Arc::new(Mutex::new(GenerationTally::default())),
+        }
+    }
+
+    /// Renewable mix and CO2 avoided since `since` (all time if `None`)
+    pub async fn report(&self, since: Option<DateTime<Utc>>) -> anyhow::Result<SustainabilityReport> {
+        let generation = sqlx::query_as::<_, EnergyRow>(
+            r#"
+            SELECT LOWER(COALESCE(r.meter_type, reg.meter_type)) AS source,
+                   reg.zone_id,
+                   r.user_id,
+                   SUM(r.energy_generated)::float8 AS kwh
+            FROM meter_readings r
+            LEFT JOIN meter_registry reg ON reg.meter_serial = r.meter_serial
+            WHERE r.energy_generated > 0
+              AND ($1::timestamptz IS NULL OR r.reading_timestamp >= $1)
+            GROUP BY 1, 2, 3
+            "#,
+        )
+        .bind(since)
+        .fetch_all(&self.db)
+        .await?;
+
+        // Attributed to the source of the seller's first registered meter;
+        // the buyer is the user credited with the purchase
+        let trades = sqlx::query_as::<_, EnergyRow>(
+            r#"
+            SELECT (
+                       SELECT LOWER(reg.meter_type) FROM meter_registry reg
+                       WHERE reg.user_id = s.seller_id
+                       ORDER BY reg.created_at ASC
+                       LIMIT 1
+                   ) AS source,
+                   s.seller_zone_id AS zone_id,
+                   s.buyer_id AS user_id,
+                   SUM(CASE WHEN s.effective_energy > 0 THEN s.effective_energy ELSE s.energy_amount END)::float8 AS kwh
+            FROM settlements s
+            WHERE s.status = 'completed'
+              AND ($1::timestamptz IS NULL OR s.processed_at >= $1)
+            GROUP BY 1, 2, 3
+            "#,
+        )
+        .bind(since)
+        .fetch_all(&self.db)
+        .await?;
+
+        let mut report = aggregate(&self.factors, &generation, &trades);
+        report.since = since;
+        Ok(report)
+    }
+
+    /// CO2 avoided by all renewable generation to date, for the grid status feed
+    ///
+    /// Adds the readings stored since the previous call to a running tally;
+    /// only the first call reads the whole table.
+    pub async fn total_co2_avoided_kg(&self) -> anyhow::Result<f64> {
+        let mut tally = self.tally.lock().await;
+        let until = Utc::now() - Duration::seconds(TALLY_SETTLE_SECS);
+
+        let rows = sqlx::query_as::<_, (Option<String>, f64)>(
+            r#"
+            SELECT LOWER(COALESCE(r.meter_type, reg.meter_type)),
+                   SUM(r.energy_generated)::float8
+            FROM meter_readings r
+            LEFT JOIN meter_registry reg ON reg.meter_serial = r.meter_serial
+            WHERE r.energy_generated > 0
+              AND ($1::timestamptz IS NULL OR r.created_at > $1)
+              AND r.created_at <= $2
+            GROUP BY 1
+            "#,
+        )
+        .bind(tally.counted_until)
+        .bind(until)
+        .fetch_all(&self.db)
+        .await?;
+
+        for (source, kwh) in rows {
+            if let Some(source) = source {
+                *tally.kwh_by_source.entry(source).or_default() += kwh;
+            }
+        }
+        tally.counted_until = Some(until);
+
+        Ok(tally
+            .kwh_by_source
+            .iter()
+            .filter_map(|(source, kwh)| Some(kwh * self.factors.avoided_per_kwh(source)?))
+            .sum())
+    }
+}
+
+fn aggregate(factors: &EmissionFactors, generation: &[EnergyRow], trades: &[EnergyRow]) -> SustainabilityReport {
+    let mut report = SustainabilityReport::default();
+    let mut sources: HashMap<String, SourceSustainability> = HashMap::new();
+    let mut zones: HashMap<Option<i32>, ZoneSustainability> = HashMap::new();
+    let mut users: HashMap<Uuid, UserSustainability> = HashMap::new();
+
+    let renewable = |row: &EnergyRow| {
+        let source = row.source.as_deref()?;
+        factors.avoided_per_kwh(source).map(|avoided| (source.to_string(), avoided))
+    };
+
+    for row in generation {
+        report.generation_kwh += row.kwh;
+        let Some((source, avoided)) = renewable(row) else { continue };
+        let co2 = row.kwh * avoided;
+
+        report.renewable_generation_kwh += row.kwh;
+        report.co2_avoided_kg += co2;
+
+        let by_source = sources.entry(source.clone()).or_insert_with(|| SourceSustainability { source, ..Default::default() });
+        by_source.generation_kwh += row.kwh;
+        by_source.co2_avoided_kg += co2;
+
+        let by_zone = zones.entry(row.zone_id).or_insert_with(|| ZoneSustainability { zone_id: row.zone_id, ..Default::default() });
+        by_zone.renewable_generation_kwh += row.kwh;
+        by_zone.co2_avoided_kg += co2;
+
+        if let Some(user_id) = row.user_id {
+            let by_user = users.entry(user_id).or_insert_with(|| UserSustainability { user_id, ..Default::default() });
+            by_user.renewable_generation_kwh += row.kwh;
+            by_user.co2_avoided_kg += co2;
+        }
+    }
+
+    for row in trades {
+        let Some((source, avoided)) = renewable(row) else { continue };
+        let co2 = row.kwh * avoided;
+
+        report.renewable_traded_kwh += row.kwh;
+        report.traded_co2_avoided_kg += co2;
+
+        sources.entry(source.clone()).or_insert_with(|| SourceSustainability { source, ..Default::default() }).traded_kwh += row.kwh;
+        zones.entry(row.zone_id).or_insert_with(|| ZoneSustainability { zone_id: row.zone_id, ..Default::default() }).renewable_traded_kwh += row.kwh;
+
+        if let Some(user_id) = row.user_id {
+            let by_user = users.entry(user_id).or_insert_with(|| UserSustainability { user_id, ..Default::default() });
+            by_user.renewable_purchased_kwh += row.kwh;
+            by_user.purchased_co2_avoided_kg += co2;
+        }
+    }
+
+    if report.generation_kwh > 0.0 {
+        report.renewable_share = report.renewable_generation_kwh / report.generation_kwh;
+        for source in sources.values_mut() {
+            source.share_of_generation = source.generation_kwh / report.generation_kwh;
+        }
+    }
+
+    report.by_source = sources.into_values().collect();
+    report.by_source.sort_by(|a, b| b.generation_kwh.total_cmp(&a.generation_kwh));
+    report.by_zone = zones.into_values().collect();
+    report.by_zone.sort_by_key(|zone| zone.zone_id);
+    report.by_user = users.into_values().collect();
+    report.by_user.sort_by(|a, b| b.co2_avoided_kg.total_cmp(&a.co2_avoided_kg));
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row(source: &str, zone_id: i32, user_id: Uuid, kwh: f64) -> EnergyRow {
+        EnergyRow { source: Some(source.to_string()), zone_id: Some(zone_id), user_id: Some(user_id), kwh }
+    }
+
+    #[test]
+    fn test_only_renewable_sources_avoid_co2() {
+        let factors = EmissionFactors::default();
+        let (prosumer, buyer) = (Uuid::new_v4(), Uuid::new_v4());
+        let generation = [
+            row("solar", 1, prosumer, 100.0),
+            row("residential", 1, buyer, 300.0),
+        ];
+        let trades = [row("solar", 1, buyer, 40.0)];
+
+        let report = aggregate(&factors, &generation, &trades);
+
+        assert_eq!(report.generation_kwh, 400.0);
+        assert_eq!(report.renewable_generation_kwh, 100.0);
+        assert!((report.renewable_share - 0.25).abs() < 1e-9);
+        assert!((report.co2_avoided_kg - 39.0).abs() < 1e-9);
+        assert!((report.traded_co2_avoided_kg - 15.6).abs() < 1e-9);
+        assert_eq!(report.by_source.len(), 1);
+        assert_eq!(report.by_source[0].traded_kwh, 40.0);
+
+        let buyer_stats = report.by_user.iter().find(|u| u.user_id == buyer).unwrap();
+        assert_eq!(buyer_stats.renewable_generation_kwh, 0.0);
+        assert_eq!(buyer_stats.renewable_purchased_kwh, 40.0);
+    }
+}
@@ -0,0 +1,201 @@
+//! Liveness of the long-running background loops
+//!
+//! The matching engine, settlement processor and event processor each run in
+//! a loop that can end without taking the process down: a panic kills only
+//! its task, and the HTTP side keeps answering as if nothing happened. Every
+//! loop records a heartbeat per iteration together with the interval it
+//! sleeps for, and the readiness probe fails once one of them has gone quiet
+//! for several intervals.
+//!
+//! Standby replicas keep their loops turning while another replica leads, so
+//! they beat too; a loop only stops beating when it is dead or stuck.
+//!
+//! A loop about to run a batch that can outlast its usual allowance, such as
+//! a settlement backlog, marks the beat as batch work and gets
+//! [`BATCH_ALLOWANCE`] instead. The job coordinator gives up the lease while
+//! any loop is stale, so a replica with a dead loop hands its jobs over.
+
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+
+/// Heartbeat names of the monitored loops
+pub const MATCHING_ENGINE_TASK: &str = "matching_engine";
+pub const SETTLEMENT_TASK: &str = "settlement_processor";
+pub const EVENT_PROCESSOR_TASK: &str = "event_processor";
+
+/// Missed intervals after which a loop counts as dead
+const STALE_AFTER_INTERVALS: u32 = 3;
+
+/// Added to the allowance so one slow iteration is not mistaken for a dead loop
+const STALE_GRACE: Duration = Duration::from_secs(30);
+
+/// How long a loop may work through one batch without beating
+pub const BATCH_ALLOWANCE: Duration = Duration::from_secs(600);
+
+#[derive(Debug, Clone, Copy)]
+struct Heartbeat {
+    at: Instant,
+    interval: Duration,
+    /// Beaten before batch work rather than between iterations
+    batch: bool,
+}
+
+impl Heartbeat {
+    fn stale_after(&self) -> Duration {
+        let allowance = self.interval * STALE_AFTER_INTERVALS;
+        if self.batch {
+            allowance.max(BATCH_ALLOWANCE) + STALE_GRACE
+        } else {
+            allowance + STALE_GRACE
+        }
+    }
+}
+
+/// Liveness of one background loop
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TaskLiveness {
+    pub name: String,
+    /// Seconds since the loop last beat
+    pub last_beat_secs_ago: u64,
+    /// Seconds the loop sleeps between iterations
+    pub interval_secs: u64,
+    /// Whether it beat within its allowance
+    pub alive: bool,
+}
+
+/// Heartbeats of the background loops; cheap to clone, clones share state
+#[derive(Clone, Default)]
+pub struct TaskHeartbeats {
+    beats: Arc<Mutex<BTreeMap<&'static str, Heartbeat>>>,
+}
+
+impl TaskHeartbeats {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn lock(&self) -> MutexGuard<'_, BTreeMap<&'static str, Heartbeat>> {
+        match self.beats.lock() {
+            Ok(guard) => guard,
+            Err(poisoned) => poisoned.into_inner(),
+        }
+    }
+
+    /// Record an iteration of `task`, which next beats within `interval`
+    pub fn beat(&self, task: &'static str, interval: Duration) {
+        self.record(task, interval, false);
+    }
+
+    /// Record that `task` is starting batch work, which may keep it from
+    /// beating for up to [`BATCH_ALLOWANCE`]
+    pub fn beat_batch(&self, task: &'static str, interval: Duration) {
+        self.record(task, interval, true);
+    }
+
+    fn record(&self, task: &'static str, interval: Duration, batch: bool) {
+        self.lock().insert(
+            task,
+            Heartbeat {
+                at: Instant::now(),
+                interval,
+                batch,
+            },
+        );
+    }
+
+    /// Stop monitoring `task` after its loop exits on purpose, e.g. when the
+    /// matching engine is stopped by an admin
+    pub fn retire(&self, task: &'static str) {
+        self.lock().remove(task);
+    }
+
+    /// Liveness of every loop that has beaten and not retired
+    pub fn snapshot(&self) -> Vec<TaskLiveness> {
+        Self::liveness_at(&self.lock(), Instant::now())
+    }
+
+    /// Names of the loops that have gone quiet past their allowance
+    pub fn stale_tasks(&self) -> Vec<String> {
+        self.snapshot()
+            .into_iter()
+            .filter(|task| !task.alive)
+            .map(|task| task.name)
+            .collect()
+    }
+
+    fn liveness_at(beats: &BTreeMap<&'static str, Heartbeat>, now: Instant) -> Vec<TaskLiveness> {
+        beats
+            .iter()
+            .map(|(name, beat)| {
+                let silent_for = now.saturating_duration_since(beat.at);
+                TaskLiveness {
+                    name: name.to_string(),
+                    last_beat_secs_ago: silent_for.as_secs(),
+                    interval_secs: beat.interval.as_secs(),
+                    alive: silent_for < beat.stale_after(),
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_loop_is_dead_after_missing_several_intervals() {
+        let start = Instant::now();
+        let mut beats = BTreeMap::new();
+        beats.insert(
+            SETTLEMENT_TASK,
+            Heartbeat {
+                at: start,
+                interval: Duration::from_secs(5),
+                batch: false,
+            },
+        );
+        beats.insert(
+            EVENT_PROCESSOR_TASK,
+            Heartbeat {
+                at: start,
+                interval: Duration::from_secs(60),
+                batch: false,
+            },
+        );
+
+        // 5s × 3 + 30s grace
+        let liveness = TaskHeartbeats::liveness_at(&beats, start + Duration::from_secs(44));
+        assert!(liveness.iter().all(|task| task.alive));
+
+        let liveness = TaskHeartbeats::liveness_at(&beats, start + Duration::from_secs(45));
+        let settlement = liveness.iter().find(|task| task.name == SETTLEMENT_TASK).unwrap();
+        assert!(!settlement.alive);
+        assert_eq!(settlement.last_beat_secs_ago, 45);
+        let events = liveness.iter().find(|task| task.name == EVENT_PROCESSOR_TASK).unwrap();
+        assert!(events.alive);
+    }
+
+    #[test]
+    fn test_batch_work_gets_its_own_allowance() {
+        let start = Instant::now();
+        let mut beats = BTreeMap::new();
+        beats.insert(
+            SETTLEMENT_TASK,
+            Heartbeat {
+                at: start,
+                interval: Duration::from_secs(5),
+                batch: true,
+            },
+        );
+
+        let liveness = TaskHeartbeats::liveness_at(&beats, start + BATCH_ALLOWANCE);
+        assert!(liveness[0].alive);
+
+        let liveness = TaskHeartbeats::liveness_at(&beats, start + BATCH_ALLOWANCE + STALE_GRACE);
+        assert!(!liveness[0].alive);
+    }
+}
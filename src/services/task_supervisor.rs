@@ -0,0 +1,172 @@
+//! Restart of panicked background loops
+//!
+//! A panic inside a spawned loop kills only that task; matching or event
+//! confirmation would stop for good while the API keeps serving. Each
+//! singleton loop runs as a child task of a supervisor that watches its
+//! `JoinHandle`, logs the panic and starts it again after a backoff, giving
+//! up after too many failures in a row.
+//!
+//! A loop that returns normally (stopped, shutting down) is not restarted.
+
+use std::future::Future;
+use std::time::{Duration, Instant};
+
+use tokio_util::sync::CancellationToken;
+use tracing::{error, info, warn};
+
+use crate::middleware::metrics::track_task_restart;
+
+/// A run this long resets the consecutive failure count
+const STABLE_RUN: Duration = Duration::from_secs(600);
+
+/// How often and how quickly a panicked loop is restarted
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RestartPolicy {
+    /// Consecutive panics after which the loop stays down
+    pub max_restarts: u32,
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+}
+
+impl Default for RestartPolicy {
+    fn default() -> Self {
+        Self {
+            max_restarts: 5,
+            initial_backoff: Duration::from_secs(1),
+            max_backoff: Duration::from_secs(60),
+        }
+    }
+}
+
+impl RestartPolicy {
+    /// Read `TASK_RESTART_MAX_ATTEMPTS`, `TASK_RESTART_INITIAL_BACKOFF_MS` and
+    /// `TASK_RESTART_MAX_BACKOFF_MS`, keeping defaults for unset values
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        let millis = |var: &str, default: Duration| {
+            std::env::var(var)
+                .ok()
+                .and_then(|v| v.parse::<u64>().ok())
+                .map(Duration::from_millis)
+                .unwrap_or(default)
+        };
+
+        Self {
+            max_restarts: std::env::var("TASK_RESTART_MAX_ATTEMPTS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(defaults.max_restarts),
+            initial_backoff: millis("TASK_RESTART_INITIAL_BACKOFF_MS", defaults.initial_backoff),
+            max_backoff: millis("TASK_RESTART_MAX_BACKOFF_MS", defaults.max_backoff),
+        }
+    }
+
+    /// Delay before restart number `attempt` (1-based), doubling each time
+    fn backoff(&self, attempt: u32) -> Duration {
+        let factor = 2u32.saturating_pow(attempt.saturating_sub(1));
+        self.initial_backoff
+            .saturating_mul(factor)
+            .min(self.max_backoff)
+    }
+}
+
+/// Run the loop built by `task` until it returns, restarting it after panics
+///
+/// `task` is called again for each restart. Spawn the returned future where
+/// the loop itself would have been spawned, e.g. on a `TaskTracker` so
+/// shutdown waits for it.
+pub async fn supervise<F, Fut>(
+    name: &'static str,
+    policy: RestartPolicy,
+    shutdown: CancellationToken,
+    task: F,
+) where
+    F: Fn() -> Fut + Send + 'static,
+    Fut: Future<Output = ()> + Send + 'static,
+{
+    let mut failures = 0u32;
+
+    loop {
+        let started = Instant::now();
+        let outcome = tokio::spawn(task()).await;
+
+        let error = match outcome {
+            Ok(()) => return,
+            Err(e) if e.is_cancelled() => return,
+            Err(e) => e,
+        };
+
+        if started.elapsed() >= STABLE_RUN {
+            failures = 0;
+        }
+        failures += 1;
+
+        if failures > policy.max_restarts {
+            error!(
+                "💀 Background task {} panicked {} times in a row, not restarting: {}",
+                name, failures, error
+            );
+            return;
+        }
+
+        let delay = policy.backoff(failures);
+        error!(
+            "💥 Background task {} panicked, restarting in {:?} (attempt {}/{}): {}",
+            name, delay, failures, policy.max_restarts, error
+        );
+        track_task_restart(name);
+
+        tokio::select! {
+            _ = shutdown.cancelled() => {
+                warn!("⚠️ Not restarting {}: shutting down", name);
+                return;
+            }
+            _ = tokio::time::sleep(delay) => {}
+        }
+        info!("🔄 Restarting background task {}", name);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU32, Ordering};
+    use std::sync::Arc;
+
+    #[test]
+    fn test_backoff_doubles_up_to_the_cap() {
+        let policy = RestartPolicy {
+            max_restarts: 10,
+            initial_backoff: Duration::from_secs(1),
+            max_backoff: Duration::from_secs(10),
+        };
+        assert_eq!(policy.backoff(1), Duration::from_secs(1));
+        assert_eq!(policy.backoff(2), Duration::from_secs(2));
+        assert_eq!(policy.backoff(4), Duration::from_secs(8));
+        assert_eq!(policy.backoff(5), Duration::from_secs(10));
+        assert_eq!(policy.backoff(40), Duration::from_secs(10));
+    }
+
+    #[tokio::test]
+    async fn test_panicking_task_is_restarted_until_the_limit() {
+        let runs = Arc::new(AtomicU32::new(0));
+        let policy = RestartPolicy {
+            max_restarts: 2,
+            initial_backoff: Duration::from_millis(1),
+            max_backoff: Duration::from_millis(1),
+        };
+
+        let counter = runs.clone();
+        supervise("test_task", policy, CancellationToken::new(), move || {
+            let counter = counter.clone();
+            async move {
+                counter.fetch_add(1, Ordering::SeqCst);
+                panic!("boom");
+            }
+        })
+        .await;
+
+        // The first run plus two restarts
+        assert_eq!(runs.load(Ordering::SeqCst), 3);
+    }
+}
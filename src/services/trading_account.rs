@@ -0,0 +1,265 @@
+//! Per-user trading account view
+//!
+//! Combines the ledger columns on `users`, the user's open orders and their
+//! recently completed settlements into one exposure summary, so clients do
+//! not have to assemble it from the order, balance and settlement endpoints.
+
+use chrono::{DateTime, Duration, Utc};
+use rust_decimal::Decimal;
+use serde::Serialize;
+use sqlx::{FromRow, PgPool};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// Window the settled figures cover
+const SETTLED_WINDOW_HOURS: i64 = 24;
+
+#[derive(Debug, Default, FromRow)]
+struct LedgerRow {
+    balance: Option<Decimal>,
+    locked_amount: Option<Decimal>,
+    locked_energy: Option<Decimal>,
+}
+
+#[derive(Debug, Default, FromRow)]
+struct OpenOrdersRow {
+    buy_orders: i64,
+    buy_volume: Decimal,
+    buy_notional: Decimal,
+    sell_orders: i64,
+    sell_volume: Decimal,
+    sell_notional: Decimal,
+}
+
+#[derive(Debug, Default, FromRow)]
+struct SettledRow {
+    bought_kwh: Decimal,
+    paid: Decimal,
+    sold_kwh: Decimal,
+    received: Decimal,
+}
+
+/// Open orders on one side of the book
+#[derive(Debug, Clone, Default, Serialize, ToSchema)]
+pub struct OpenSideExposure {
+    pub orders: i64,
+    /// Unfilled volume (kWh)
+    #[schema(value_type = String)]
+    pub volume: Decimal,
+    /// Unfilled volume at the orders' limit prices
+    #[schema(value_type = String)]
+    pub notional: Decimal,
+}
+
+/// Energy and funds settled over the recent window
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct SettledSummary {
+    pub since: DateTime<Utc>,
+    #[schema(value_type = String)]
+    pub bought_kwh: Decimal,
+    #[schema(value_type = String)]
+    pub sold_kwh: Decimal,
+    /// `bought_kwh - sold_kwh`
+    #[schema(value_type = String)]
+    pub net_energy_kwh: Decimal,
+    /// Paid for purchases at the matched prices
+    #[schema(value_type = String)]
+    pub paid: Decimal,
+    /// Received for sales, after fees
+    #[schema(value_type = String)]
+    pub received: Decimal,
+    /// `received - paid`
+    #[schema(value_type = String)]
+    pub net_funds: Decimal,
+}
+
+/// A user's balances, open exposure and recent settled flows
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct TradingExposure {
+    pub user_id: Uuid,
+    /// Spendable balance
+    #[schema(value_type = String)]
+    pub balance: Decimal,
+    /// Funds held in escrow for open buy orders
+    #[schema(value_type = String)]
+    pub locked_amount: Decimal,
+    /// Energy held in escrow for open sell orders (kWh)
+    #[schema(value_type = String)]
+    pub locked_energy: Decimal,
+    /// `balance + locked_amount`
+    #[schema(value_type = String)]
+    pub total_funds: Decimal,
+    pub open_buy: OpenSideExposure,
+    pub open_sell: OpenSideExposure,
+    /// Clearing price of the last cleared epoch, if any
+    #[schema(value_type = Option<String>)]
+    pub mark_price: Option<Decimal>,
+    /// Open buy volume valued at the mark price, less its notional at the
+    /// limit prices
+    #[schema(value_type = Option<String>)]
+    pub unrealized_buy_pnl: Option<Decimal>,
+    /// Open sell volume's notional at the limit prices, less its value at the
+    /// mark price; positive when offered above the market
+    #[schema(value_type = Option<String>)]
+    pub unrealized_sell_pnl: Option<Decimal>,
+    pub settled_24h: SettledSummary,
+    pub as_of: DateTime<Utc>,
+}
+
+impl TradingExposure {
+    fn assemble(
+        user_id: Uuid,
+        ledger: LedgerRow,
+        open: OpenOrdersRow,
+        settled: SettledRow,
+        mark_price: Option<Decimal>,
+        now: DateTime<Utc>,
+    ) -> Self {
+        let balance = ledger.balance.unwrap_or_default();
+        let locked_amount = ledger.locked_amount.unwrap_or_default();
+
+        Self {
+            user_id,
+            balance,
+            locked_amount,
+            locked_energy: ledger.locked_energy.unwrap_or_default(),
+            total_funds: balance + locked_amount,
+            open_buy: OpenSideExposure {
+                orders: open.buy_orders,
+                volume: open.buy_volume,
+                notional: open.buy_notional,
+            },
+            open_sell: OpenSideExposure {
+                orders: open.sell_orders,
+                volume: open.sell_volume,
+                notional: open.sell_notional,
+            },
+            mark_price,
+            unrealized_buy_pnl: mark_price.map(|mark| open.buy_volume * mark - open.buy_notional),
+            unrealized_sell_pnl: mark_price.map(|mark| open.sell_notional - open.sell_volume * mark),
+            settled_24h: SettledSummary {
+                since: now - Duration::hours(SETTLED_WINDOW_HOURS),
+                bought_kwh: settled.bought_kwh,
+                sold_kwh: settled.sold_kwh,
+                net_energy_kwh: settled.bought_kwh - settled.sold_kwh,
+                paid: settled.paid,
+                received: settled.received,
+                net_funds: settled.received - settled.paid,
+            },
+            as_of: now,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct TradingAccountService {
+    db: PgPool,
+}
+
+impl TradingAccountService {
+    pub fn new(db: PgPool) -> Self {
+        Self { db }
+    }
+
+    /// Exposure summary for `user_id`; `None` if the user does not exist
+    pub async fn exposure(&self, user_id: Uuid) -> anyhow::Result<Option<TradingExposure>> {
+        let Some(ledger) = sqlx::query_as::<_, LedgerRow>(
+            "SELECT balance, locked_amount, locked_energy FROM users WHERE id = $1",
+        )
+        .bind(user_id)
+        .fetch_optional(&self.db)
+        .await?
+        else {
+            return Ok(None);
+        };
+
+        let open = sqlx::query_as::<_, OpenOrdersRow>(
+            r#"
+            SELECT COUNT(*) FILTER (WHERE side = 'buy') AS buy_orders,
+                   COALESCE(SUM(energy_amount - COALESCE(filled_amount, 0)) FILTER (WHERE side = 'buy'), 0) AS buy_volume,
+                   COALESCE(SUM((energy_amount - COALESCE(filled_amount, 0)) * price_per_kwh) FILTER (WHERE side = 'buy'), 0) AS buy_notional,
+                   COUNT(*) FILTER (WHERE side = 'sell') AS sell_orders,
+                   COALESCE(SUM(energy_amount - COALESCE(filled_amount, 0)) FILTER (WHERE side = 'sell'), 0) AS sell_volume,
+                   COALESCE(SUM((energy_amount - COALESCE(filled_amount, 0)) * price_per_kwh) FILTER (WHERE side = 'sell'), 0) AS sell_notional
+            FROM trading_orders
+            WHERE user_id = $1
+              AND status IN ('pending', 'partially_filled')
+            "#,
+        )
+        .bind(user_id)
+        .fetch_one(&self.db)
+        .await?;
+
+        let now = Utc::now();
+        let settled = sqlx::query_as::<_, SettledRow>(
+            r#"
+            SELECT COALESCE(SUM(energy_amount) FILTER (WHERE buyer_id = $1), 0) AS bought_kwh,
+                   COALESCE(SUM(total_amount) FILTER (WHERE buyer_id = $1), 0) AS paid,
+                   COALESCE(SUM(energy_amount) FILTER (WHERE seller_id = $1), 0) AS sold_kwh,
+                   COALESCE(SUM(net_amount) FILTER (WHERE seller_id = $1), 0) AS received
+            FROM settlements
+            WHERE (buyer_id = $1 OR seller_id = $1)
+              AND status = 'completed'
+              AND processed_at >= $2
+            "#,
+        )
+        .bind(user_id)
+        .bind(now - Duration::hours(SETTLED_WINDOW_HOURS))
+        .fetch_one(&self.db)
+        .await?;
+
+        let mark_price = sqlx::query_scalar::<_, Decimal>(
+            r#"
+            SELECT clearing_price
+            FROM market_epochs
+            WHERE clearing_price IS NOT NULL
+            ORDER BY start_time DESC
+            LIMIT 1
+            "#,
+        )
+        .fetch_optional(&self.db)
+        .await?;
+
+        Ok(Some(TradingExposure::assemble(user_id, ledger, open, settled, mark_price, now)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_exposure_nets_settled_flows_and_marks_open_orders() {
+        let ledger = LedgerRow {
+            balance: Some(Decimal::new(100, 0)),
+            locked_amount: Some(Decimal::new(40, 0)),
+            locked_energy: None,
+        };
+        let open = OpenOrdersRow {
+            buy_orders: 2,
+            buy_volume: Decimal::new(10, 0),
+            buy_notional: Decimal::new(40, 0),
+            sell_orders: 1,
+            sell_volume: Decimal::new(5, 0),
+            sell_notional: Decimal::new(25, 0),
+        };
+        let settled = SettledRow {
+            bought_kwh: Decimal::new(3, 0),
+            paid: Decimal::new(12, 0),
+            sold_kwh: Decimal::new(8, 0),
+            received: Decimal::new(30, 0),
+        };
+
+        let exposure =
+            TradingExposure::assemble(Uuid::new_v4(), ledger, open, settled, Some(Decimal::new(45, 1)), Utc::now());
+
+        assert_eq!(exposure.total_funds, Decimal::new(140, 0));
+        assert_eq!(exposure.locked_energy, Decimal::ZERO);
+        assert_eq!(exposure.settled_24h.net_energy_kwh, Decimal::new(-5, 0));
+        assert_eq!(exposure.settled_24h.net_funds, Decimal::new(18, 0));
+        // 5 kWh offered at 5.00 against a 4.50 market
+        assert_eq!(exposure.unrealized_sell_pnl, Some(Decimal::new(25, 1)));
+        // 10 kWh bid at 4.00 against a 4.50 market
+        assert_eq!(exposure.unrealized_buy_pnl, Some(Decimal::new(5, 0)));
+    }
+}
@@ -1,5 +1,7 @@
 use anyhow::Result;
+use chrono::Utc;
 use sqlx::{PgPool, Row};
+use std::collections::HashMap;
 use uuid::Uuid;
 
 use crate::error::ApiError;
@@ -270,6 +272,121 @@ impl TransactionQueryService {
         operations.map_err(ApiError::Database)
     }
 
+    /// Resolve several transaction ids at once, with one query per table
+    ///
+    /// Ids are looked up as P2P orders, then AMM swaps, then blockchain
+    /// transactions, the same precedence as the single-id lookup. Ids found in
+    /// none of them are absent from the result, as are blockchain transactions
+    /// with no owning user, which have no user id to report.
+    pub async fn get_transaction_statuses(
+        &self,
+        ids: &[Uuid],
+    ) -> Result<HashMap<Uuid, TransactionResponse>, ApiError> {
+        let mut found = HashMap::with_capacity(ids.len());
+        if ids.is_empty() {
+            return Ok(found);
+        }
+
+        let orders = sqlx::query(
+            r#"
+            SELECT id, user_id, status::text AS status, created_at, settled_at
+            FROM trading_orders
+            WHERE id = ANY($1)
+            "#,
+        )
+        .bind(ids)
+        .fetch_all(&self.db)
+        .await
+        .map_err(ApiError::Database)?;
+
+        for row in orders {
+            let created_at: Option<chrono::DateTime<Utc>> = row.try_get("created_at")?;
+            let settled_at: Option<chrono::DateTime<Utc>> = row.try_get("settled_at")?;
+            let id: Uuid = row.try_get("id")?;
+            found.insert(
+                id,
+                TransactionResponse {
+                    transaction_type: TransactionType::EnergyTrade,
+                    operation_id: id,
+                    user_id: row.try_get("user_id")?,
+                    status: TransactionStatus::from_order_status(&row.try_get::<String, _>("status")?),
+                    signature: None,
+                    attempts: 1,
+                    last_error: None,
+                    created_at: created_at.unwrap_or_else(Utc::now),
+                    submitted_at: created_at,
+                    confirmed_at: settled_at,
+                    settled_at,
+                },
+            );
+        }
+
+        let swaps = sqlx::query(
+            r#"
+            SELECT id, user_id, tx_hash, created_at
+            FROM swap_transactions
+            WHERE id = ANY($1)
+            "#,
+        )
+        .bind(ids)
+        .fetch_all(&self.db)
+        .await
+        .map_err(ApiError::Database)?;
+
+        for row in swaps {
+            let id: Uuid = row.try_get("id")?;
+            let created_at: chrono::DateTime<Utc> = row.try_get("created_at")?;
+            let response = TransactionResponse {
+                transaction_type: TransactionType::Swap,
+                operation_id: id,
+                user_id: row.try_get("user_id")?,
+                status: TransactionStatus::Settled,
+                signature: row.try_get("tx_hash")?,
+                attempts: 1,
+                last_error: None,
+                created_at,
+                submitted_at: Some(created_at),
+                confirmed_at: Some(created_at),
+                settled_at: Some(created_at),
+            };
+            found.entry(id).or_insert(response);
+        }
+
+        let chain_txs = sqlx::query(
+            r#"
+            SELECT id, signature, user_id, instruction_name, status, submitted_at, created_at
+            FROM blockchain_transactions
+            WHERE id = ANY($1) AND user_id IS NOT NULL
+            "#,
+        )
+        .bind(ids)
+        .fetch_all(&self.db)
+        .await
+        .map_err(ApiError::Database)?;
+
+        for row in chain_txs {
+            let id: Uuid = row.try_get("id")?;
+            let created_at: Option<chrono::DateTime<Utc>> = row.try_get("created_at")?;
+            let instruction: Option<String> = row.try_get("instruction_name")?;
+            let response = TransactionResponse {
+                transaction_type: TransactionType::from_instruction(instruction.as_deref()),
+                operation_id: id,
+                user_id: row.try_get("user_id")?,
+                status: TransactionStatus::from_chain_status(&row.try_get::<String, _>("status")?),
+                signature: Some(row.try_get("signature")?),
+                attempts: 1,
+                last_error: None,
+                created_at: created_at.unwrap_or_else(Utc::now),
+                submitted_at: row.try_get("submitted_at")?,
+                confirmed_at: None,
+                settled_at: None,
+            };
+            found.entry(id).or_insert(response);
+        }
+
+        Ok(found)
+    }
+
     /// Get transaction statistics
     pub async fn get_transaction_stats(&self) -> Result<TransactionStats, ApiError> {
         // Get total count
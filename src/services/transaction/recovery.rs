@@ -109,15 +109,19 @@ impl TransactionRecoveryService {
             // Route to appropriate service based on operation type
             match operation.operation_type.as_str() {
                 "settlement" => {
-                    if let Err(e) = self
+                    match self
                         .retry_settlement_transaction(operation.operation_id)
                         .await
                     {
-                        error!(
-                            "Failed to retry settlement transaction {}: {}",
-                            operation.operation_id, e
-                        );
-                        continue;
+                        Ok(true) => {}
+                        Ok(false) => continue,
+                        Err(e) => {
+                            error!(
+                                "Failed to retry settlement transaction {}: {}",
+                                operation.operation_id, e
+                            );
+                            continue;
+                        }
                     }
                 }
                 // Add retry logic for other operation types as needed
@@ -205,7 +209,20 @@ impl TransactionRecoveryService {
         // Route to appropriate service based on operation type
         match op_type_inner.as_str() {
             "settlement" => {
-                self.retry_settlement_transaction(op_id).await?;
+                if !self.retry_settlement_transaction(op_id).await? {
+                    return Ok(TransactionRetryResponse {
+                        operation_id: op_id,
+                        success: false,
+                        attempts: op_attempts,
+                        last_error: Some(
+                            "Settlement is not due: outside the settlement windows and not urgent, or this instance is not running settlements"
+                                .to_string(),
+                        ),
+                        signature: op_sig.clone(),
+                        status: op_status,
+                        new_attempts: op_attempts,
+                    });
+                }
             }
             // Add retry logic for other operation types as needed
             _ => {
@@ -247,8 +264,14 @@ impl TransactionRecoveryService {
         })
     }
 
-    /// Retry a settlement transaction
-    async fn retry_settlement_transaction(&self, settlement_id: Uuid) -> Result<(), ApiError> {
+    /// Retry a settlement transaction; returns whether it was attempted
+    async fn retry_settlement_transaction(&self, settlement_id: Uuid) -> Result<bool, ApiError> {
+        // Honour the settlement windows and the job lease like the settlement loop
+        if !self.settlement.settlement_due(settlement_id).await? {
+            info!("⏳ Settlement {} is not due yet; leaving it for a later retry", settlement_id);
+            return Ok(false);
+        }
+
         // Increment attempt count first
         let result: Option<bool> =
             sqlx::query_scalar("SELECT increment_blockchain_attempts($1, $2, $3)")
@@ -296,6 +319,6 @@ impl TransactionRecoveryService {
             }
         }
 
-        Ok(())
+        Ok(true)
     }
 }
@@ -0,0 +1,513 @@
+//! Payout of recorded platform revenue to treasury wallets
+//!
+//! Escrow finalization only records fees, wheeling charges and loss costs in
+//! `platform_revenue`; the sweep turns them into token movement. For each
+//! recipient with a configured wallet, the unswept rows are claimed under a
+//! `treasury_sweeps` record, their total is sent in currency tokens from the
+//! platform authority, and the rows are stamped with the transfer signature.
+//! Precision beyond the currency token's smallest unit is split off the
+//! claimed rows into a new unswept row, so it is carried to a later sweep
+//! rather than marked as paid.
+//!
+//! A transfer that was certainly not applied releases its rows for the next
+//! sweep. One whose outcome is unknown, such as a network error after
+//! submission, leaves them claimed by the failed sweep until an operator has
+//! checked the chain, since sending again could pay the recipient twice.
+
+use rust_decimal::{Decimal, RoundingStrategy};
+use serde::{Deserialize, Serialize};
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{Keypair, Signer};
+use sqlx::PgPool;
+use std::sync::Arc;
+use tracing::{error, info, warn};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::config::TreasuryConfig;
+use crate::error::ApiError;
+use crate::middleware::metrics::track_treasury_sweep;
+use crate::services::blockchain::{BlockchainClient, SettlementError};
+use crate::services::settlement::RevenueRecipient;
+use crate::services::BlockchainService;
+use crate::utils::units::to_atomic;
+
+/// Outcome of sweeping one recipient's revenue
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SweepStatus {
+    Completed,
+    Failed,
+    /// Nothing was claimed; the revenue waits for a later sweep
+    Skipped,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct RecipientSweep {
+    pub recipient: RevenueRecipient,
+    pub status: SweepStatus,
+    pub sweep_id: Option<Uuid>,
+    pub wallet_address: Option<String>,
+    pub amount: Decimal,
+    pub revenue_count: i64,
+    pub signature: Option<String>,
+    /// Why the sweep failed or was skipped
+    pub detail: Option<String>,
+}
+
+impl RecipientSweep {
+    /// A sweep that ended before claiming any revenue
+    fn unclaimed(
+        recipient: RevenueRecipient,
+        status: SweepStatus,
+        amount: Decimal,
+        revenue_count: i64,
+        detail: impl Into<String>,
+    ) -> Self {
+        Self {
+            recipient,
+            status,
+            sweep_id: None,
+            wallet_address: None,
+            amount,
+            revenue_count,
+            signature: None,
+            detail: Some(detail.into()),
+        }
+    }
+}
+
+/// Result of one sweep run, one entry per recipient with unswept revenue
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct TreasurySweepReport {
+    pub sweeps: Vec<RecipientSweep>,
+}
+
+/// Authority, token accounts and decimals for transfers to one wallet
+struct SweepTransfer {
+    authority: Keypair,
+    from: Pubkey,
+    to: Pubkey,
+    mint: Pubkey,
+    decimals: u8,
+}
+
+/// Rows claimed for a sweep, or why nothing was claimed
+enum Claim {
+    Claimed {
+        sweep_id: Uuid,
+        amount: Decimal,
+        revenue_count: i64,
+    },
+    /// Another sweep took the rows first
+    Empty,
+    /// The rows total less than the currency token's smallest unit
+    Dust(Decimal),
+}
+
+/// Whether a failed transfer might still have been applied on-chain
+///
+/// Errors raised before or by preflight mean nothing was sent; network and
+/// unclassified errors may come after the transaction was submitted.
+fn transfer_may_have_landed(error: &SettlementError) -> bool {
+    matches!(error, SettlementError::Network(_) | SettlementError::Other(_))
+}
+
+#[derive(Clone)]
+pub struct TreasuryService {
+    db: PgPool,
+    blockchain: Arc<dyn BlockchainClient>,
+    config: TreasuryConfig,
+    currency_mint: String,
+    /// When false, sweeps are recorded with mock signatures
+    real_blockchain: bool,
+}
+
+impl TreasuryService {
+    pub fn new(
+        db: PgPool,
+        blockchain: Arc<dyn BlockchainClient>,
+        config: TreasuryConfig,
+        currency_mint: String,
+    ) -> Self {
+        Self {
+            db,
+            blockchain,
+            config,
+            currency_mint,
+            real_blockchain: true,
+        }
+    }
+
+    /// Record sweeps without sending transfers, for deployments running
+    /// without a real blockchain
+    pub fn with_real_blockchain(mut self, enabled: bool) -> Self {
+        self.real_blockchain = enabled;
+        self
+    }
+
+    pub fn config(&self) -> &TreasuryConfig {
+        &self.config
+    }
+
+    fn wallet_for(&self, recipient: RevenueRecipient) -> Option<&str> {
+        match recipient {
+            RevenueRecipient::PlatformTreasury => self.config.platform_treasury_wallet.as_deref(),
+            RevenueRecipient::GridOperator => self.config.grid_operator_wallet.as_deref(),
+            RevenueRecipient::InsuranceFund => self.config.insurance_fund_wallet.as_deref(),
+        }
+    }
+
+    /// Pay every recipient's unswept revenue to its wallet
+    pub async fn sweep_revenue(&self) -> Result<TreasurySweepReport, ApiError> {
+        let unswept = sqlx::query_as::<_, (String, Decimal, i64)>(
+            r#"
+            SELECT recipient, COALESCE(SUM(amount), 0), COUNT(*)
+            FROM platform_revenue
+            WHERE sweep_id IS NULL
+            GROUP BY recipient
+            ORDER BY recipient
+            "#,
+        )
+        .fetch_all(&self.db)
+        .await
+        .map_err(ApiError::Database)?;
+
+        let mut sweeps = Vec::with_capacity(unswept.len());
+        for (recipient, total, count) in unswept {
+            let recipient = recipient.parse::<RevenueRecipient>().map_err(ApiError::Internal)?;
+
+            let Some(wallet) = self.wallet_for(recipient) else {
+                sweeps.push(RecipientSweep::unclaimed(
+                    recipient,
+                    SweepStatus::Skipped,
+                    total,
+                    count,
+                    "no wallet configured",
+                ));
+                continue;
+            };
+            if total <= Decimal::ZERO || total < self.config.min_sweep_amount {
+                sweeps.push(RecipientSweep::unclaimed(
+                    recipient,
+                    SweepStatus::Skipped,
+                    total,
+                    count,
+                    "below the minimum sweep amount",
+                ));
+                continue;
+            }
+
+            sweeps.push(self.sweep_recipient(recipient, wallet, total, count).await?);
+        }
+
+        Ok(TreasurySweepReport { sweeps })
+    }
+
+    async fn sweep_recipient(
+        &self,
+        recipient: RevenueRecipient,
+        wallet: &str,
+        unswept: Decimal,
+        unswept_count: i64,
+    ) -> Result<RecipientSweep, ApiError> {
+        let transfer = if self.real_blockchain {
+            match self.prepare_transfer(wallet).await {
+                Ok(transfer) => Some(transfer),
+                Err(e) => {
+                    error!("❌ Cannot sweep {} revenue to {}: {}", recipient.as_str(), wallet, e);
+                    return Ok(RecipientSweep {
+                        wallet_address: Some(wallet.to_string()),
+                        ..RecipientSweep::unclaimed(recipient, SweepStatus::Failed, unswept, unswept_count, e.to_string())
+                    });
+                }
+            }
+        } else {
+            None
+        };
+        let decimals = transfer.as_ref().map(|transfer| transfer.decimals);
+        let (sweep_id, amount, revenue_count) = match self.claim(recipient, wallet, decimals).await? {
+            Claim::Claimed {
+                sweep_id,
+                amount,
+                revenue_count,
+            } => (sweep_id, amount, revenue_count),
+            Claim::Empty => {
+                return Ok(RecipientSweep::unclaimed(
+                    recipient,
+                    SweepStatus::Skipped,
+                    Decimal::ZERO,
+                    0,
+                    "revenue was claimed by a concurrent sweep",
+                ));
+            }
+            Claim::Dust(amount) => {
+                return Ok(RecipientSweep::unclaimed(
+                    recipient,
+                    SweepStatus::Skipped,
+                    amount,
+                    unswept_count,
+                    "below the currency token's smallest unit",
+                ));
+            }
+        };
+        let mut sweep = RecipientSweep {
+            recipient,
+            status: SweepStatus::Completed,
+            sweep_id: Some(sweep_id),
+            wallet_address: Some(wallet.to_string()),
+            amount,
+            revenue_count,
+            signature: None,
+            detail: None,
+        };
+
+        let sent = match &transfer {
+            Some(transfer) => self.send(transfer, amount).await,
+            None => Ok(format!("mock_sweep_sig_{}", Uuid::new_v4())),
+        };
+
+        match sent {
+            Ok(signature) => {
+                self.complete(sweep_id, &signature).await?;
+                info!(
+                    "🏦 Swept {} of {} revenue ({} rows) to {}: {}",
+                    amount,
+                    recipient.as_str(),
+                    revenue_count,
+                    wallet,
+                    signature
+                );
+                track_treasury_sweep(recipient.as_str(), true);
+                sweep.signature = Some(signature);
+            }
+            Err(e) => {
+                let held = transfer_may_have_landed(&e);
+                self.fail(sweep_id, &e.to_string(), !held).await?;
+                if held {
+                    error!(
+                        "🚨 Treasury sweep {} to {} has an unknown outcome; its {} revenue rows stay claimed until checked on-chain: {}",
+                        sweep_id, wallet, revenue_count, e
+                    );
+                } else {
+                    warn!("⚠️ Treasury sweep {} to {} failed, revenue released: {}", sweep_id, wallet, e);
+                }
+                track_treasury_sweep(recipient.as_str(), false);
+                sweep.status = SweepStatus::Failed;
+                sweep.detail = Some(e.to_string());
+            }
+        }
+
+        Ok(sweep)
+    }
+
+    /// Resolve the authority, both token accounts and the currency decimals
+    async fn prepare_transfer(&self, wallet: &str) -> anyhow::Result<SweepTransfer> {
+        let wallet = BlockchainService::parse_pubkey(wallet)?;
+        let mint = BlockchainService::parse_pubkey(&self.currency_mint)?;
+        let authority = self.blockchain.get_authority_keypair().await?;
+        let decimals = self.blockchain.decimals_for(&mint).await?;
+        let from = self.blockchain.calculate_ata_address(&authority.pubkey(), &mint)?;
+        let to = self.blockchain.ensure_token_account_exists(&authority, &wallet, &mint).await?;
+
+        Ok(SweepTransfer {
+            authority,
+            from,
+            to,
+            mint,
+            decimals,
+        })
+    }
+
+    async fn send(&self, transfer: &SweepTransfer, amount: Decimal) -> Result<String, SettlementError> {
+        let atomic = to_atomic(amount, transfer.decimals).map_err(|e| SettlementError::Rejected(e.to_string()))?;
+        self.blockchain
+            .transfer_tokens(
+                &transfer.authority,
+                &transfer.from,
+                &transfer.to,
+                &transfer.mint,
+                atomic,
+                transfer.decimals,
+            )
+            .await
+            .map(|signature| signature.to_string())
+            .map_err(|e| SettlementError::from_anyhow(&e))
+    }
+
+    /// Open a sweep and claim the recipient's unswept revenue rows for it
+    ///
+    /// With `decimals`, the claimed total is truncated to what the currency
+    /// token can represent and the remainder is split off one claimed row
+    /// into a new unswept row. Nothing is recorded when no rows were claimed
+    /// or the truncated total is zero.
+    async fn claim(
+        &self,
+        recipient: RevenueRecipient,
+        wallet: &str,
+        decimals: Option<u8>,
+    ) -> Result<Claim, ApiError> {
+        let mut tx = self.db.begin().await.map_err(ApiError::Database)?;
+
+        let sweep_id = sqlx::query_scalar::<_, Uuid>(
+            "INSERT INTO treasury_sweeps (recipient, wallet_address) VALUES ($1, $2) RETURNING id",
+        )
+        .bind(recipient.as_str())
+        .bind(wallet)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(ApiError::Database)?;
+
+        let (amount, revenue_count) = sqlx::query_as::<_, (Decimal, i64)>(
+            r#"
+            WITH claimed AS (
+                UPDATE platform_revenue
+                SET sweep_id = $1
+                WHERE recipient = $2 AND sweep_id IS NULL
+                RETURNING amount
+            )
+            SELECT COALESCE(SUM(amount), 0), COUNT(*) FROM claimed
+            "#,
+        )
+        .bind(sweep_id)
+        .bind(recipient.as_str())
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(ApiError::Database)?;
+
+        if revenue_count == 0 {
+            return Ok(Claim::Empty);
+        }
+
+        let sendable = match decimals {
+            Some(decimals) => amount.round_dp_with_strategy(decimals as u32, RoundingStrategy::ToZero),
+            None => amount,
+        };
+        if sendable <= Decimal::ZERO {
+            return Ok(Claim::Dust(amount));
+        }
+
+        let remainder = amount - sendable;
+        if remainder > Decimal::ZERO && !self.carry_remainder(&mut tx, sweep_id, remainder).await? {
+            warn!(
+                "⚠️ No single {} revenue row covers the {} below the currency token's smallest unit; sweep deferred",
+                recipient.as_str(),
+                remainder
+            );
+            return Ok(Claim::Dust(amount));
+        }
+
+        sqlx::query("UPDATE treasury_sweeps SET amount = $2, revenue_count = $3 WHERE id = $1")
+            .bind(sweep_id)
+            .bind(sendable)
+            .bind(revenue_count as i32)
+            .execute(&mut *tx)
+            .await
+            .map_err(ApiError::Database)?;
+
+        tx.commit().await.map_err(ApiError::Database)?;
+        Ok(Claim::Claimed {
+            sweep_id,
+            amount: sendable,
+            revenue_count,
+        })
+    }
+
+    /// Move `remainder` out of the largest row claimed by `sweep_id` into a
+    /// new unswept row for the same settlement, keeping recorded revenue
+    /// unchanged. False when no claimed row is large enough to split.
+    async fn carry_remainder(
+        &self,
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        sweep_id: Uuid,
+        remainder: Decimal,
+    ) -> Result<bool, ApiError> {
+        let carried = sqlx::query(
+            r#"
+            WITH split AS (
+                UPDATE platform_revenue
+                SET amount = amount - $2
+                WHERE id = (
+                    SELECT id FROM platform_revenue
+                    WHERE sweep_id = $1 AND amount >= $2
+                    ORDER BY amount DESC
+                    LIMIT 1
+                )
+                RETURNING settlement_id, revenue_type, recipient, description, created_at
+            )
+            INSERT INTO platform_revenue (settlement_id, amount, revenue_type, recipient, description, created_at)
+            SELECT settlement_id, $2, revenue_type, recipient, description, created_at FROM split
+            "#,
+        )
+        .bind(sweep_id)
+        .bind(remainder)
+        .execute(&mut **tx)
+        .await
+        .map_err(ApiError::Database)?;
+
+        Ok(carried.rows_affected() > 0)
+    }
+
+    async fn complete(&self, sweep_id: Uuid, signature: &str) -> Result<(), ApiError> {
+        let mut tx = self.db.begin().await.map_err(ApiError::Database)?;
+
+        sqlx::query(
+            "UPDATE treasury_sweeps SET status = 'completed', signature = $2, completed_at = NOW() WHERE id = $1",
+        )
+        .bind(sweep_id)
+        .bind(signature)
+        .execute(&mut *tx)
+        .await
+        .map_err(ApiError::Database)?;
+
+        sqlx::query("UPDATE platform_revenue SET swept_at = NOW(), sweep_signature = $2 WHERE sweep_id = $1")
+            .bind(sweep_id)
+            .bind(signature)
+            .execute(&mut *tx)
+            .await
+            .map_err(ApiError::Database)?;
+
+        tx.commit().await.map_err(ApiError::Database)
+    }
+
+    /// Mark a sweep failed, handing its rows back to the next sweep if
+    /// `release` is set
+    async fn fail(&self, sweep_id: Uuid, error_message: &str, release: bool) -> Result<(), ApiError> {
+        let mut tx = self.db.begin().await.map_err(ApiError::Database)?;
+
+        sqlx::query(
+            "UPDATE treasury_sweeps SET status = 'failed', error_message = $2, completed_at = NOW() WHERE id = $1",
+        )
+        .bind(sweep_id)
+        .bind(error_message)
+        .execute(&mut *tx)
+        .await
+        .map_err(ApiError::Database)?;
+
+        if release {
+            sqlx::query("UPDATE platform_revenue SET sweep_id = NULL WHERE sweep_id = $1")
+                .bind(sweep_id)
+                .execute(&mut *tx)
+                .await
+                .map_err(ApiError::Database)?;
+        }
+
+        tx.commit().await.map_err(ApiError::Database)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_only_ambiguous_failures_keep_revenue_claimed() {
+        assert!(transfer_may_have_landed(&SettlementError::Network("connection reset".to_string())));
+        assert!(transfer_may_have_landed(&SettlementError::Other("timed out".to_string())));
+
+        assert!(!transfer_may_have_landed(&SettlementError::InsufficientFunds("authority".to_string())));
+        assert!(!transfer_may_have_landed(&SettlementError::BlockhashExpired));
+        assert!(!transfer_may_have_landed(&SettlementError::RateLimited));
+        assert!(!transfer_may_have_landed(&SettlementError::Rejected("preflight".to_string())));
+    }
+}
@@ -0,0 +1,362 @@
+// Meter Reading Validator
+// Screens readings for physically impossible values before they can be minted
+
+use anyhow::Result;
+use chrono::{DateTime, Duration, Utc};
+use sqlx::PgPool;
+use tracing::warn;
+use uuid::Uuid;
+
+use crate::handlers::auth::types::CreateReadingRequest;
+
+/// Nominal reading interval used to turn kWh into average power when the
+/// meter has no earlier reading
+const DEFAULT_READING_INTERVAL_MINUTES: i64 = 15;
+
+/// Headroom over the rated power before a reading counts as an anomaly
+const RATED_POWER_TOLERANCE: f64 = 1.2;
+
+/// Physical limits for one meter
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MeterBounds {
+    /// Rated power of the installation in kW
+    pub max_power_kw: f64,
+    /// Most energy a single reading may report
+    pub max_kwh_per_interval: f64,
+}
+
+impl MeterBounds {
+    /// Gateway-wide defaults from `METER_MAX_POWER_KW` and `METER_MAX_KWH_PER_INTERVAL`
+    pub fn from_env() -> Self {
+        let max_power_kw = std::env::var("METER_MAX_POWER_KW")
+            .ok()
+            .and_then(|v| v.parse::<f64>().ok())
+            .filter(|v| *v > 0.0)
+            .unwrap_or(100.0);
+        let max_kwh_per_interval = std::env::var("METER_MAX_KWH_PER_INTERVAL")
+            .ok()
+            .and_then(|v| v.parse::<f64>().ok())
+            .filter(|v| *v > 0.0)
+            .unwrap_or(50.0);
+
+        Self {
+            max_power_kw,
+            max_kwh_per_interval,
+        }
+    }
+}
+
+/// Why a reading was quarantined
+#[derive(Debug, Clone, PartialEq)]
+pub enum ReadingAnomaly {
+    NegativeEnergy { field: &'static str, value: f64 },
+    FutureTimestamp { timestamp: DateTime<Utc> },
+    ExceedsIntervalEnergy { kwh: f64, max_kwh: f64 },
+    ExceedsRatedPower { power_kw: f64, max_power_kw: f64 },
+}
+
+impl ReadingAnomaly {
+    /// Stable identifier stored in `quarantined_readings.anomaly_type`
+    pub fn code(&self) -> &'static str {
+        match self {
+            ReadingAnomaly::NegativeEnergy { .. } => "negative_energy",
+            ReadingAnomaly::FutureTimestamp { .. } => "future_timestamp",
+            ReadingAnomaly::ExceedsIntervalEnergy { .. } => "exceeds_interval_energy",
+            ReadingAnomaly::ExceedsRatedPower { .. } => "exceeds_rated_power",
+        }
+    }
+}
+
+impl std::fmt::Display for ReadingAnomaly {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ReadingAnomaly::NegativeEnergy { field, value } => {
+                write!(f, "Negative energy: {} = {} kWh", field, value)
+            }
+            ReadingAnomaly::FutureTimestamp { timestamp } => {
+                write!(f, "Reading timestamp {} is in the future", timestamp)
+            }
+            ReadingAnomaly::ExceedsIntervalEnergy { kwh, max_kwh } => {
+                write!(f, "{:.3} kWh exceeds the per-reading limit of {:.3} kWh", kwh, max_kwh)
+            }
+            ReadingAnomaly::ExceedsRatedPower {
+                power_kw,
+                max_power_kw,
+            } => write!(
+                f,
+                "Implied power {:.2} kW exceeds the meter rating of {:.2} kW",
+                power_kw, max_power_kw
+            ),
+        }
+    }
+}
+
+/// Result of screening a reading
+#[derive(Debug, Clone)]
+pub enum ScreeningOutcome {
+    Clean,
+    Quarantined {
+        quarantine_id: Uuid,
+        anomaly: ReadingAnomaly,
+        user_id: Uuid,
+        wallet_address: Option<String>,
+    },
+}
+
+/// Meter context needed to screen a reading
+#[derive(Debug, sqlx::FromRow)]
+struct MeterScreeningRow {
+    meter_id: Uuid,
+    user_id: Uuid,
+    wallet_address: Option<String>,
+    max_power_kw: Option<f64>,
+    max_kwh_per_interval: Option<f64>,
+    previous_reading_at: Option<DateTime<Utc>>,
+}
+
+/// Flags physically impossible readings and quarantines them for review
+#[derive(Debug, Clone)]
+pub struct MeterReadingValidator {
+    db: PgPool,
+    default_bounds: MeterBounds,
+    max_clock_skew: Duration,
+}
+
+impl MeterReadingValidator {
+    pub fn new(db: PgPool) -> Self {
+        let max_clock_skew_secs = std::env::var("METER_MAX_CLOCK_SKEW_SECS")
+            .ok()
+            .and_then(|v| v.parse::<i64>().ok())
+            .unwrap_or(300);
+
+        Self {
+            db,
+            default_bounds: MeterBounds::from_env(),
+            max_clock_skew: Duration::seconds(max_clock_skew_secs),
+        }
+    }
+
+    /// Check a reading against the meter's bounds
+    ///
+    /// `previous_reading_at` is the meter's last reading before this one; the
+    /// time since then is used to convert kWh into average power.
+    pub fn evaluate(
+        request: &CreateReadingRequest,
+        timestamp: DateTime<Utc>,
+        bounds: &MeterBounds,
+        previous_reading_at: Option<DateTime<Utc>>,
+        now: DateTime<Utc>,
+        max_clock_skew: Duration,
+    ) -> std::result::Result<(), ReadingAnomaly> {
+        let energy_fields = [
+            ("kwh", Some(request.kwh)),
+            ("energy_generated", request.energy_generated),
+            ("energy_consumed", request.energy_consumed),
+            ("surplus_energy", request.surplus_energy),
+            ("deficit_energy", request.deficit_energy),
+        ];
+        for (field, value) in energy_fields {
+            if let Some(value) = value.filter(|v| *v < 0.0) {
+                return Err(ReadingAnomaly::NegativeEnergy { field, value });
+            }
+        }
+
+        if timestamp > now + max_clock_skew {
+            return Err(ReadingAnomaly::FutureTimestamp { timestamp });
+        }
+
+        if request.kwh > bounds.max_kwh_per_interval {
+            return Err(ReadingAnomaly::ExceedsIntervalEnergy {
+                kwh: request.kwh,
+                max_kwh: bounds.max_kwh_per_interval,
+            });
+        }
+
+        let max_power_kw = bounds.max_power_kw * RATED_POWER_TOLERANCE;
+        let interval = previous_reading_at
+            .map(|previous| timestamp - previous)
+            .filter(|elapsed| *elapsed > Duration::zero())
+            .unwrap_or_else(|| Duration::minutes(DEFAULT_READING_INTERVAL_MINUTES));
+        let implied_power_kw = request.kwh / (interval.num_seconds().max(1) as f64 / 3600.0);
+
+        let reported_power_kw = [request.power, request.power_generated, request.power_consumed]
+            .into_iter()
+            .flatten()
+            .map(f64::abs)
+            .fold(implied_power_kw, f64::max);
+        if reported_power_kw > max_power_kw {
+            return Err(ReadingAnomaly::ExceedsRatedPower {
+                power_kw: reported_power_kw,
+                max_power_kw: bounds.max_power_kw,
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Screen a reading and store it in `quarantined_readings` if it is anomalous
+    ///
+    /// Readings for unknown meters pass through; they fail later when the
+    /// meter context cannot be resolved.
+    pub async fn screen(
+        &self,
+        serial: &str,
+        request: &CreateReadingRequest,
+        timestamp: DateTime<Utc>,
+    ) -> Result<ScreeningOutcome> {
+        let meter = sqlx::query_as::<_, MeterScreeningRow>(
+            r#"
+            SELECT m.id AS meter_id, m.user_id, u.wallet_address,
+                   m.max_power_kw, m.max_kwh_per_interval,
+                   (SELECT MAX(r.reading_timestamp) FROM meter_readings r
+                    WHERE r.meter_serial = m.meter_serial AND r.reading_timestamp < $2) AS previous_reading_at
+            FROM meter_registry m
+            JOIN users u ON u.id = m.user_id
+            WHERE m.meter_serial = $1
+            "#,
+        )
+        .bind(serial)
+        .bind(timestamp)
+        .fetch_optional(&self.db)
+        .await?;
+
+        let Some(meter) = meter else {
+            return Ok(ScreeningOutcome::Clean);
+        };
+
+        let bounds = MeterBounds {
+            max_power_kw: meter.max_power_kw.unwrap_or(self.default_bounds.max_power_kw),
+            max_kwh_per_interval: meter
+                .max_kwh_per_interval
+                .unwrap_or(self.default_bounds.max_kwh_per_interval),
+        };
+
+        let anomaly = match Self::evaluate(
+            request,
+            timestamp,
+            &bounds,
+            meter.previous_reading_at,
+            Utc::now(),
+            self.max_clock_skew,
+        ) {
+            Ok(()) => return Ok(ScreeningOutcome::Clean),
+            Err(anomaly) => anomaly,
+        };
+
+        warn!("🚨 Quarantining reading for {}: {}", serial, anomaly);
+
+        let quarantine_id = sqlx::query_scalar::<_, Uuid>(
+            r#"
+            INSERT INTO quarantined_readings
+                (meter_serial, meter_id, user_id, kwh_amount, reading_timestamp, anomaly_type, reason, payload)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+            RETURNING id
+            "#,
+        )
+        .bind(serial)
+        .bind(meter.meter_id)
+        .bind(meter.user_id)
+        .bind(request.kwh)
+        .bind(timestamp)
+        .bind(anomaly.code())
+        .bind(anomaly.to_string())
+        .bind(serde_json::to_value(request)?)
+        .fetch_one(&self.db)
+        .await?;
+
+        Ok(ScreeningOutcome::Quarantined {
+            quarantine_id,
+            anomaly,
+            user_id: meter.user_id,
+            wallet_address: meter.wallet_address,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BOUNDS: MeterBounds = MeterBounds {
+        max_power_kw: 10.0,
+        max_kwh_per_interval: 5.0,
+    };
+
+    fn reading(kwh: f64) -> CreateReadingRequest {
+        CreateReadingRequest {
+            kwh,
+            ..Default::default()
+        }
+    }
+
+    fn evaluate(
+        request: &CreateReadingRequest,
+        timestamp: DateTime<Utc>,
+        previous: Option<DateTime<Utc>>,
+    ) -> std::result::Result<(), ReadingAnomaly> {
+        MeterReadingValidator::evaluate(
+            request,
+            timestamp,
+            &BOUNDS,
+            previous,
+            timestamp,
+            Duration::seconds(300),
+        )
+    }
+
+    #[test]
+    fn test_plausible_reading_passes() {
+        // 2 kWh over 15 minutes = 8 kW on a 10 kW meter
+        assert!(evaluate(&reading(2.0), Utc::now(), None).is_ok());
+    }
+
+    #[test]
+    fn test_negative_energy_is_flagged() {
+        let mut request = reading(1.0);
+        request.energy_consumed = Some(-0.5);
+        assert_eq!(
+            evaluate(&request, Utc::now(), None).unwrap_err().code(),
+            "negative_energy"
+        );
+    }
+
+    #[test]
+    fn test_future_timestamp_is_flagged() {
+        let now = Utc::now();
+        let result = MeterReadingValidator::evaluate(
+            &reading(1.0),
+            now + Duration::hours(1),
+            &BOUNDS,
+            None,
+            now,
+            Duration::seconds(300),
+        );
+        assert_eq!(result.unwrap_err().code(), "future_timestamp");
+    }
+
+    #[test]
+    fn test_power_implied_by_short_interval_is_flagged() {
+        let now = Utc::now();
+        // 1 kWh in one minute = 60 kW
+        let result = evaluate(&reading(1.0), now, Some(now - Duration::minutes(1)));
+        assert!(matches!(result, Err(ReadingAnomaly::ExceedsRatedPower { .. })));
+
+        // Same energy over an hour is fine
+        assert!(evaluate(&reading(1.0), now, Some(now - Duration::hours(1))).is_ok());
+    }
+
+    #[test]
+    fn test_interval_energy_and_reported_power_limits() {
+        assert_eq!(
+            evaluate(&reading(6.0), Utc::now(), None).unwrap_err().code(),
+            "exceeds_interval_energy"
+        );
+
+        let mut request = reading(0.5);
+        request.power_generated = Some(50.0);
+        assert_eq!(
+            evaluate(&request, Utc::now(), None).unwrap_err().code(),
+            "exceeds_rated_power"
+        );
+    }
+}
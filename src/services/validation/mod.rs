@@ -1,6 +1,8 @@
 // Validation services
 pub mod transaction_validation_service;
 pub mod oracle_validator;
+pub mod meter_reading_validator;
 
 pub use transaction_validation_service::TransactionValidationService;
 pub use oracle_validator::*;
+pub use meter_reading_validator::{MeterBounds, MeterReadingValidator, ReadingAnomaly, ScreeningOutcome};
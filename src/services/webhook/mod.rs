@@ -3,11 +3,14 @@ use hmac::{Hmac, Mac};
 use reqwest::Client;
 use sha2::Sha256;
 use std::time::Duration;
-use tracing::{error, info, warn};
+use tracing::{info, warn};
 
 pub mod types;
 pub use types::WebhookPayload;
 
+/// Longest a single webhook request may take before it is abandoned
+pub const WEBHOOK_TIMEOUT_SECS: u64 = 10;
+
 /// Webhook Dispatcher Service
 #[derive(Clone)]
 pub struct WebhookService {
@@ -19,7 +22,7 @@ pub struct WebhookService {
 impl WebhookService {
     pub fn new(webhook_url: Option<String>, webhook_secret: Option<String>) -> Self {
         let client = Client::builder()
-            .timeout(Duration::from_secs(10))
+            .timeout(Duration::from_secs(WEBHOOK_TIMEOUT_SECS))
             .build()
             .unwrap_or_default();
 
@@ -30,18 +33,28 @@ impl WebhookService {
         }
     }
 
-    /// Send webhook notification
-    pub async fn send_webhook(&self, event_type: &str, data: serde_json::Value) -> Result<()> {
+    /// Whether a webhook endpoint is configured
+    pub fn is_enabled(&self) -> bool {
+        self.webhook_url.is_some()
+    }
+
+    /// Make a single delivery attempt. Retries are driven by the caller's
+    /// delivery queue, so `event_id` should stay the same across attempts.
+    pub async fn send_webhook(
+        &self,
+        event_id: &str,
+        event_type: &str,
+        data: serde_json::Value,
+    ) -> Result<()> {
         let url = match &self.webhook_url {
             Some(url) => url,
             None => return Ok(()), // Webhook disabled
         };
 
-        let event_id = uuid::Uuid::new_v4().to_string();
         let timestamp = chrono::Utc::now().to_rfc3339();
 
         let mut payload = WebhookPayload {
-            event_id,
+            event_id: event_id.to_string(),
             event_type: event_type.to_string(),
             timestamp,
             data,
@@ -54,43 +67,62 @@ impl WebhookService {
             payload.signature = Some(signature);
         }
 
-        // Send request with retries
-        let mut attempts = 0;
-        let max_retries = 3;
-        let mut backoff = Duration::from_millis(500);
-
-        loop {
-            attempts += 1;
-            match self.client.post(url).json(&payload).send().await {
-                Ok(res) => {
-                    if res.status().is_success() {
-                        info!("Webhook sent successfully for event {}", payload.event_type);
-                        return Ok(());
-                    } else {
-                        warn!(
-                            "Webhook failed with status {}: {}",
-                            res.status(),
-                            res.text().await.unwrap_or_default()
-                        );
-                    }
-                }
-                Err(e) => {
-                    warn!("Webhook request failed: {}", e);
-                }
-            }
-
-            if attempts >= max_retries {
-                error!("Failed to send webhook after {} attempts", max_retries);
-                break;
-            }
-
-            tokio::time::sleep(backoff).await;
-            backoff *= 2;
+        let res = self
+            .client
+            .post(url)
+            .json(&payload)
+            .send()
+            .await
+            .map_err(|e| anyhow::anyhow!("Webhook request failed: {}", e))?;
+
+        if !res.status().is_success() {
+            let status = res.status();
+            let body = res.text().await.unwrap_or_default();
+            warn!("Webhook failed with status {}: {}", status, body);
+            return Err(anyhow::anyhow!("Webhook returned status {}", status));
         }
 
+        info!("Webhook sent successfully for event {}", payload.event_type);
         Ok(())
     }
 
+    /// Verify an inbound webhook signature against the configured secret.
+    ///
+    /// `signature_header` is the hex-encoded HMAC-SHA256 of the raw request
+    /// body, optionally prefixed with `sha256=`. Returns false when no secret
+    /// is configured. The comparison is constant-time.
+    pub fn verify_signature(&self, payload: &[u8], signature_header: &str) -> bool {
+        let Some(secret) = &self.webhook_secret else {
+            return false;
+        };
+
+        let signature_hex = signature_header
+            .trim()
+            .strip_prefix("sha256=")
+            .unwrap_or(signature_header.trim());
+        let Ok(signature) = hex::decode(signature_hex) else {
+            return false;
+        };
+
+        type HmacSha256 = Hmac<Sha256>;
+        let Ok(mut mac) = HmacSha256::new_from_slice(secret.as_bytes()) else {
+            return false;
+        };
+        mac.update(payload);
+        mac.verify_slice(&signature).is_ok()
+    }
+
+    /// Compute the `sha256=<hex>` signature header for a raw body. This is
+    /// the counterpart of [`verify_signature`](Self::verify_signature) for
+    /// partners and tests producing signed requests.
+    pub fn sign_body(secret: &str, payload: &[u8]) -> Result<String> {
+        type HmacSha256 = Hmac<Sha256>;
+        let mut mac = HmacSha256::new_from_slice(secret.as_bytes())
+            .map_err(|e| anyhow::anyhow!("Invalid HMAC secret: {}", e))?;
+        mac.update(payload);
+        Ok(format!("sha256={}", hex::encode(mac.finalize().into_bytes())))
+    }
+
     /// Sign payload using HMAC-SHA256
     fn sign_payload(&self, payload: &WebhookPayload, secret: &str) -> Result<String> {
         // Create a canonical string representation for signing
@@ -112,3 +144,35 @@ impl WebhookService {
         Ok(hex::encode(code_bytes))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SECRET: &str = "partner-shared-secret";
+
+    fn service(secret: Option<&str>) -> WebhookService {
+        WebhookService::new(None, secret.map(str::to_string))
+    }
+
+    #[test]
+    fn test_verify_signature_round_trip() {
+        let body = br#"{"settlement_id":"abc","status":"completed"}"#;
+        let header = WebhookService::sign_body(SECRET, body).unwrap();
+
+        assert!(service(Some(SECRET)).verify_signature(body, &header));
+        // Bare hex without the prefix is accepted too
+        assert!(service(Some(SECRET)).verify_signature(body, header.trim_start_matches("sha256=")));
+    }
+
+    #[test]
+    fn test_verify_signature_rejects_mismatch() {
+        let body = br#"{"amount":100}"#;
+        let header = WebhookService::sign_body(SECRET, body).unwrap();
+
+        assert!(!service(Some(SECRET)).verify_signature(br#"{"amount":101}"#, &header));
+        assert!(!service(Some("other-secret")).verify_signature(body, &header));
+        assert!(!service(Some(SECRET)).verify_signature(body, "sha256=not-hex"));
+        assert!(!service(None).verify_signature(body, &header));
+    }
+}
@@ -1,26 +1,45 @@
 pub mod types;
 
 use axum::extract::ws::{Message, WebSocket};
-use futures::{stream::SplitSink, SinkExt, StreamExt};
+use futures::{future::BoxFuture, SinkExt, StreamExt};
+use rust_decimal::Decimal;
 use rustc_hash::FxHashMap;
 use std::sync::Arc;
 use tokio::sync::{mpsc, RwLock};
 use tracing::{error, info, warn};
 use uuid::Uuid;
 
+use crate::database::schema::types::OrderSide;
+use crate::models::trading::OrderCloseReason;
+use crate::services::market_clearing::{DepthLevel, OrderBookDepth};
+
 pub use types::*;
 
-/// WebSocket client connection
-#[allow(dead_code)]
-struct Client {
-    id: Uuid,
-    sender: SplitSink<WebSocket, Message>,
+/// Resolves a JWT sent in a client's `auth` message to the user it belongs to
+pub type TokenAuthenticator = Arc<dyn Fn(String) -> BoxFuture<'static, Option<Uuid>> + Send + Sync>;
+
+/// Frame queued for delivery to one client
+#[derive(Debug, Clone)]
+enum Outbound {
+    Event(MarketEvent),
+    Control(serde_json::Value),
+}
+
+/// A connected client and the user it authenticated as, if any
+#[derive(Debug)]
+struct ClientHandle {
+    tx: mpsc::UnboundedSender<Outbound>,
+    user_id: Option<Uuid>,
 }
 
 /// WebSocket broadcast service
+///
+/// Public events reach every client. Events addressed to users (see
+/// [`MarketEvent::audience`]) only reach sockets authenticated as one of
+/// those users, so anonymous clients see the public feeds alone.
 #[derive(Clone, Debug)]
 pub struct WebSocketService {
-    clients: Arc<RwLock<FxHashMap<Uuid, mpsc::UnboundedSender<MarketEvent>>>>,
+    clients: Arc<RwLock<FxHashMap<Uuid, ClientHandle>>>,
 }
 
 impl WebSocketService {
@@ -33,15 +52,33 @@ impl WebSocketService {
     }
 
     /// Register a new WebSocket client
-    pub async fn register_client(&self, socket: WebSocket) -> Uuid {
+    ///
+    /// `user_id` is set when the upgrade request already carried a valid
+    /// token. Otherwise the client may authenticate later by sending
+    /// `{"type": "auth", "token": "..."}`, checked with `authenticator`.
+    pub async fn register_client(
+        &self,
+        socket: WebSocket,
+        user_id: Option<Uuid>,
+        authenticator: Option<TokenAuthenticator>,
+    ) -> Uuid {
         let client_id = Uuid::new_v4();
         let (sender, mut receiver) = socket.split();
-        let (tx, mut rx) = mpsc::unbounded_channel::<MarketEvent>();
+        let (tx, mut rx) = mpsc::unbounded_channel::<Outbound>();
 
         // Store the client sender
-        self.clients.write().await.insert(client_id, tx);
+        self.clients.write().await.insert(
+            client_id,
+            ClientHandle {
+                tx: tx.clone(),
+                user_id,
+            },
+        );
 
-        info!("✅ WebSocket client connected: {}", client_id);
+        match user_id {
+            Some(user_id) => info!("✅ WebSocket client connected: {} (user {})", client_id, user_id),
+            None => info!("✅ WebSocket client connected: {} (anonymous)", client_id),
+        }
 
         // Spawn task to forward messages to this client
         let clients = self.clients.clone();
@@ -52,6 +89,7 @@ impl WebSocketService {
             let welcome = serde_json::json!({
                 "type": "connected",
                 "client_id": client_id.to_string(),
+                "authenticated": user_id.is_some(),
                 "message": "Connected to GridTokenX market feed"
             });
 
@@ -60,8 +98,12 @@ impl WebSocketService {
             }
 
             // Forward market events to this client
-            while let Some(event) = rx.recv().await {
-                match serde_json::to_string(&event) {
+            while let Some(frame) = rx.recv().await {
+                let json = match frame {
+                    Outbound::Event(event) => serde_json::to_string(&event),
+                    Outbound::Control(value) => serde_json::to_string(&value),
+                };
+                match json {
                     Ok(json) => {
                         if let Err(e) = sender.send(Message::Text(json.into())).await {
                             warn!("Failed to send message to client {}: {}", client_id, e);
@@ -79,14 +121,26 @@ impl WebSocketService {
             info!("❌ WebSocket client disconnected: {}", client_id);
         });
 
-        // Spawn task to handle incoming messages (ping/pong, subscriptions)
+        // Spawn task to handle incoming messages (auth, ping/pong, subscriptions)
+        let clients = self.clients.clone();
         tokio::spawn(async move {
             while let Some(Ok(msg)) = receiver.next().await {
                 match msg {
-                    Message::Text(text) => {
-                        // Handle subscription messages if needed
-                        info!("Received message from client: {}", text);
-                    }
+                    Message::Text(text) => match serde_json::from_str::<ClientMessage>(&text) {
+                        Ok(ClientMessage::Auth { token }) => {
+                            let reply = Self::authenticate_client(
+                                &clients,
+                                client_id,
+                                authenticator.as_ref(),
+                                token,
+                            )
+                            .await;
+                            let _ = tx.send(Outbound::Control(reply));
+                        }
+                        Err(_) => {
+                            info!("Received message from client: {}", text);
+                        }
+                    },
                     Message::Close(_) => {
                         info!("Client requested close");
                         break;
@@ -98,12 +152,56 @@ impl WebSocketService {
                     _ => {}
                 }
             }
+            // Drop the handle so the forwarding task ends too
+            clients.write().await.remove(&client_id);
         });
 
         client_id
     }
 
-    /// Broadcast a market event to all connected clients
+    /// Handle an `auth` message, binding the client to the token's user
+    async fn authenticate_client(
+        clients: &RwLock<FxHashMap<Uuid, ClientHandle>>,
+        client_id: Uuid,
+        authenticator: Option<&TokenAuthenticator>,
+        token: String,
+    ) -> serde_json::Value {
+        let Some(authenticator) = authenticator else {
+            return serde_json::json!({
+                "type": "auth_failed",
+                "message": "Authentication is not available on this endpoint"
+            });
+        };
+
+        if let Some(user_id) = clients.read().await.get(&client_id).and_then(|c| c.user_id) {
+            return serde_json::json!({
+                "type": "auth_failed",
+                "message": format!("Already authenticated as {}", user_id)
+            });
+        }
+
+        match authenticator(token).await {
+            Some(user_id) => {
+                if let Some(client) = clients.write().await.get_mut(&client_id) {
+                    client.user_id = Some(user_id);
+                }
+                info!("🔐 WebSocket client {} authenticated as user {}", client_id, user_id);
+                serde_json::json!({
+                    "type": "authenticated",
+                    "user_id": user_id.to_string()
+                })
+            }
+            None => serde_json::json!({
+                "type": "auth_failed",
+                "message": "Invalid or expired token"
+            }),
+        }
+    }
+
+    /// Deliver a market event to every client entitled to it
+    ///
+    /// Public events go to all clients; user-addressed events only to the
+    /// sockets of the users involved.
     pub async fn broadcast(&self, event: MarketEvent) {
         let clients = self.clients.read().await;
         let client_count = clients.len();
@@ -112,17 +210,23 @@ impl WebSocketService {
             return; // No clients connected, skip broadcasting
         }
 
-        info!(
-            "📢 Broadcasting event to {} clients: {:?}",
-            client_count, event
-        );
-
-        // Send to all clients
-        for (client_id, tx) in clients.iter() {
-            if let Err(e) = tx.send(event.clone()) {
+        let audience = event.audience();
+        let mut delivered = 0;
+        for (client_id, client) in clients.iter() {
+            if !audience.includes(client.user_id) {
+                continue;
+            }
+            if let Err(e) = client.tx.send(Outbound::Event(event.clone())) {
                 warn!("Failed to send event to client {}: {}", client_id, e);
+            } else {
+                delivered += 1;
             }
         }
+
+        info!(
+            "📢 Delivered event to {} of {} clients: {:?}",
+            delivered, client_count, event
+        );
     }
 
     /// Broadcast offer created event
@@ -180,6 +284,35 @@ impl WebSocketService {
         .await;
     }
 
+    /// Broadcast order cancelled/expired event
+    ///
+    /// `released` is the currency refunded for a buy order or the energy
+    /// unlocked for a sell order.
+    pub async fn broadcast_order_cancelled(
+        &self,
+        order_id: Uuid,
+        user_id: Uuid,
+        side: OrderSide,
+        reason: OrderCloseReason,
+        released: Decimal,
+    ) {
+        let (refunded_amount, unlocked_energy) = match side {
+            OrderSide::Buy => (Some(released.to_string()), None),
+            OrderSide::Sell => (None, Some(released.to_string())),
+        };
+
+        self.broadcast(MarketEvent::OrderCancelled {
+            order_id: order_id.to_string(),
+            user_id: user_id.to_string(),
+            side: side.to_string(),
+            reason: reason.to_string(),
+            refunded_amount,
+            unlocked_energy,
+            timestamp: chrono::Utc::now(),
+        })
+        .await;
+    }
+
     /// Broadcast order matched event
     pub async fn broadcast_order_matched(
         &self,
@@ -238,6 +371,16 @@ impl WebSocketService {
         self.clients.read().await.len()
     }
 
+    /// Get number of connected clients bound to a user
+    pub async fn authenticated_client_count(&self) -> usize {
+        self.clients
+            .read()
+            .await
+            .values()
+            .filter(|c| c.user_id.is_some())
+            .count()
+    }
+
     /// Broadcast order book snapshot
     pub async fn broadcast_order_book_snapshot(
         &self,
@@ -270,6 +413,26 @@ impl WebSocketService {
         .await;
     }
 
+    /// Broadcast an aggregated depth snapshot as an order book snapshot
+    pub async fn broadcast_order_book_depth(&self, depth: &OrderBookDepth) {
+        let to_pairs = |levels: &[DepthLevel]| -> Vec<(String, String)> {
+            levels
+                .iter()
+                .map(|level| (level.price.to_string(), level.volume.to_string()))
+                .collect()
+        };
+
+        self.broadcast_order_book_snapshot(
+            to_pairs(&depth.bids),
+            to_pairs(&depth.asks),
+            depth.best_bid.map(|p| p.to_string()),
+            depth.best_ask.map(|p| p.to_string()),
+            depth.mid_price.map(|p| p.to_string()),
+            depth.spread.map(|p| p.to_string()),
+        )
+        .await;
+    }
+
     /// Broadcast order book buy side update
     pub async fn broadcast_order_book_buy_update(
         &self,
@@ -336,6 +499,37 @@ impl WebSocketService {
         .await;
     }
 
+    /// Broadcast an AMM swap with the pool's reserves after the swap
+    pub async fn broadcast_swap_executed(
+        &self,
+        pool_id: Uuid,
+        input_token: &str,
+        output_token: &str,
+        input_amount: Decimal,
+        output_amount: Decimal,
+        input_reserve: Decimal,
+        output_reserve: Decimal,
+    ) {
+        let new_price = if input_reserve.is_zero() {
+            Decimal::ZERO
+        } else {
+            (output_reserve / input_reserve).round_dp(9)
+        };
+
+        self.broadcast(MarketEvent::SwapExecuted {
+            pool_id: pool_id.to_string(),
+            input_token: input_token.to_string(),
+            output_token: output_token.to_string(),
+            input_amount: input_amount.to_string(),
+            output_amount: output_amount.to_string(),
+            input_reserve: input_reserve.to_string(),
+            output_reserve: output_reserve.to_string(),
+            new_price: new_price.to_string(),
+            timestamp: chrono::Utc::now(),
+        })
+        .await;
+    }
+
     /// Broadcast market depth update
     pub async fn broadcast_market_depth_update(
         &self,
@@ -461,6 +655,74 @@ impl WebSocketService {
         .await;
     }
 
+    /// Broadcast the dashboard values that changed in the last refresh
+    pub async fn broadcast_dashboard_metrics_updated(
+        &self,
+        changes: crate::services::dashboard::DashboardDelta,
+        computed_at: chrono::DateTime<chrono::Utc>,
+    ) {
+        self.broadcast(MarketEvent::DashboardMetricsUpdated { changes, computed_at })
+            .await;
+    }
+
+    /// Deliver an event to the sockets of `users` only, whatever its audience
+    pub async fn send_to_users(&self, users: &[Uuid], event: MarketEvent) {
+        let clients = self.clients.read().await;
+        for (client_id, client) in clients.iter() {
+            if !client.user_id.is_some_and(|id| users.contains(&id)) {
+                continue;
+            }
+            if let Err(e) = client.tx.send(Outbound::Event(event.clone())) {
+                warn!("Failed to send event to client {}: {}", client_id, e);
+            }
+        }
+    }
+
+    /// Alert admins that a match breached the price band and its epoch's
+    /// clearing halted
+    pub async fn notify_price_band_breached(
+        &self,
+        admin_ids: &[Uuid],
+        epoch_id: Uuid,
+        match_price: Decimal,
+        reference_price: Decimal,
+        lower_bound: Decimal,
+        upper_bound: Decimal,
+    ) {
+        self.send_to_users(
+            admin_ids,
+            MarketEvent::PriceBandBreached {
+                epoch_id: epoch_id.to_string(),
+                match_price: match_price.to_string(),
+                reference_price: reference_price.to_string(),
+                lower_bound: lower_bound.to_string(),
+                upper_bound: upper_bound.to_string(),
+                timestamp: chrono::Utc::now(),
+            },
+        )
+        .await;
+    }
+
+    /// Tell a settlement's buyer and seller that it failed
+    pub async fn broadcast_settlement_failed(
+        &self,
+        settlement_id: Uuid,
+        buyer_id: Uuid,
+        seller_id: Uuid,
+        reason: String,
+        retryable: bool,
+    ) {
+        self.broadcast(MarketEvent::SettlementFailed {
+            settlement_id: settlement_id.to_string(),
+            buyer_id: buyer_id.to_string(),
+            seller_id: seller_id.to_string(),
+            reason,
+            retryable,
+            timestamp: chrono::Utc::now(),
+        })
+        .await;
+    }
+
     /// Broadcast a meter alert
     pub async fn broadcast_meter_alert(
         &self,
@@ -28,6 +28,20 @@ pub enum MarketEvent {
         energy_source: Option<String>,
         created_by: String,
     },
+    /// Order closed without filling (user cancel, expiry or dust)
+    OrderCancelled {
+        order_id: String,
+        user_id: String,
+        side: String,
+        reason: String,
+        /// Currency returned to the buyer's balance
+        #[serde(skip_serializing_if = "Option::is_none")]
+        refunded_amount: Option<String>,
+        /// Energy released back to the seller
+        #[serde(skip_serializing_if = "Option::is_none")]
+        unlocked_energy: Option<String>,
+        timestamp: chrono::DateTime<chrono::Utc>,
+    },
     /// Order matched with an offer
     OrderMatched {
         order_id: String,
@@ -84,6 +98,21 @@ pub enum MarketEvent {
         total_value: String,
         executed_at: String,
     },
+    /// AMM swap executed against a liquidity pool
+    SwapExecuted {
+        pool_id: String,
+        input_token: String,
+        output_token: String,
+        input_amount: String,
+        output_amount: String,
+        /// Pool reserve of the input token after the swap
+        input_reserve: String,
+        /// Pool reserve of the output token after the swap
+        output_reserve: String,
+        /// Input token priced in the output token after the swap
+        new_price: String,
+        timestamp: chrono::DateTime<chrono::Utc>,
+    },
     /// Market depth update
     MarketDepthUpdate {
         total_buy_volume: String,
@@ -150,6 +179,35 @@ pub enum MarketEvent {
         timestamp: chrono::DateTime<chrono::Utc>,
     },
 
+    /// Dashboard metrics changed materially since the last refresh
+    DashboardMetricsUpdated {
+        #[serde(flatten)]
+        changes: crate::services::dashboard::DashboardDelta,
+        computed_at: chrono::DateTime<chrono::Utc>,
+    },
+
+    /// Clearing for an epoch was halted by the price band circuit breaker;
+    /// sent to admins only
+    PriceBandBreached {
+        epoch_id: String,
+        match_price: String,
+        reference_price: String,
+        lower_bound: String,
+        upper_bound: String,
+        timestamp: chrono::DateTime<chrono::Utc>,
+    },
+
+    /// A settlement failed; `retryable` says whether it will be retried
+    /// automatically or waits for an admin replay
+    SettlementFailed {
+        settlement_id: String,
+        buyer_id: String,
+        seller_id: String,
+        reason: String,
+        retryable: bool,
+        timestamp: chrono::DateTime<chrono::Utc>,
+    },
+
     /// Meter alert event
     MeterAlert {
         meter_id: String,
@@ -175,3 +233,112 @@ pub struct PriceLevel {
     pub price: String,
     pub volume: String,
 }
+
+/// Message sent by a client over the socket
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ClientMessage {
+    /// Authenticate an anonymous connection with a JWT
+    Auth { token: String },
+}
+
+/// Who may receive a [`MarketEvent`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EventAudience {
+    /// Every connected client
+    Public,
+    /// Only sockets authenticated as one of these users
+    Users(Vec<Uuid>),
+}
+
+impl EventAudience {
+    /// Whether a client authenticated as `user_id` (or anonymous) receives the event
+    pub fn includes(&self, user_id: Option<Uuid>) -> bool {
+        match self {
+            EventAudience::Public => true,
+            EventAudience::Users(users) => user_id.is_some_and(|id| users.contains(&id)),
+        }
+    }
+}
+
+impl MarketEvent {
+    /// Trades, settlement updates and order cancellations concern their
+    /// counterparties only, and admin alerts reach no one by broadcast;
+    /// everything else is a public feed
+    pub fn audience(&self) -> EventAudience {
+        let users = |ids: &[&str]| {
+            EventAudience::Users(ids.iter().filter_map(|id| Uuid::parse_str(id).ok()).collect())
+        };
+
+        match self {
+            MarketEvent::TradeExecuted { buyer_id, seller_id, .. }
+            | MarketEvent::TransactionUpdated { buyer_id, seller_id, .. }
+            | MarketEvent::SettlementFailed { buyer_id, seller_id, .. } => {
+                users(&[buyer_id.as_str(), seller_id.as_str()])
+            }
+            MarketEvent::OrderCancelled { user_id, .. } => users(&[user_id.as_str()]),
+            // Addressed explicitly with `send_to_users`, never broadcast
+            MarketEvent::PriceBandBreached { .. } => EventAudience::Users(Vec::new()),
+            _ => EventAudience::Public,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_trade_reaches_only_counterparties() {
+        let (buyer, seller) = (Uuid::new_v4(), Uuid::new_v4());
+        let event = MarketEvent::TradeExecuted {
+            trade_id: Uuid::new_v4().to_string(),
+            buy_order_id: Uuid::new_v4().to_string(),
+            sell_order_id: Uuid::new_v4().to_string(),
+            buyer_id: buyer.to_string(),
+            seller_id: seller.to_string(),
+            quantity: "10".to_string(),
+            price: "0.15".to_string(),
+            total_value: "1.5".to_string(),
+            executed_at: chrono::Utc::now().to_rfc3339(),
+        };
+
+        let audience = event.audience();
+        assert!(audience.includes(Some(buyer)));
+        assert!(audience.includes(Some(seller)));
+        assert!(!audience.includes(Some(Uuid::new_v4())));
+        assert!(!audience.includes(None));
+    }
+
+    #[test]
+    fn test_market_feeds_are_public() {
+        let event = MarketEvent::MarketStats {
+            total_active_offers: 1,
+            total_pending_orders: 2,
+            average_price: 0.15,
+            total_volume_24h: 100.0,
+        };
+        assert_eq!(event.audience(), EventAudience::Public);
+        assert!(event.audience().includes(None));
+    }
+
+    #[test]
+    fn test_price_band_alert_is_not_broadcast() {
+        let event = MarketEvent::PriceBandBreached {
+            epoch_id: Uuid::new_v4().to_string(),
+            match_price: "0.04".to_string(),
+            reference_price: "4.00".to_string(),
+            lower_bound: "3.20".to_string(),
+            upper_bound: "4.80".to_string(),
+            timestamp: chrono::Utc::now(),
+        };
+        assert!(!event.audience().includes(None));
+        assert!(!event.audience().includes(Some(Uuid::new_v4())));
+    }
+
+    #[test]
+    fn test_auth_message_parses() {
+        let msg: ClientMessage = serde_json::from_str(r#"{"type":"auth","token":"abc"}"#).unwrap();
+        assert!(matches!(msg, ClientMessage::Auth { token } if token == "abc"));
+    }
+}
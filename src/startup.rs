@@ -7,18 +7,23 @@ use tracing::{error, info, warn};
 
 use crate::app_state::AppState;
 use crate::auth::jwt::{ApiKeyService, JwtService};
-use crate::config::Config;
+use crate::config::{Config, ReloadableConfig};
+use tokio_util::sync::CancellationToken;
+use tokio_util::task::TaskTracker;
 use crate::database;
 use crate::services;
+use crate::services::task_heartbeat;
+use crate::services::task_supervisor::{supervise, RestartPolicy};
 
 /// Initialize minimal application services and create the AppState.
 pub async fn initialize_app(config: &Config) -> Result<AppState> {
     info!("🚀 Starting minimal Gateway for Simulator → Anchor testing");
 
     // Initialize Prometheus metrics exporter
-    let metrics_handle = metrics_exporter_prometheus::PrometheusBuilder::new()
-        .install_recorder()
+    let metrics_handle = crate::middleware::metrics::prometheus_builder()
+        .and_then(|builder| builder.install_recorder())
         .map_err(|e| anyhow::anyhow!("Failed to install Prometheus recorder: {}", e))?;
+    crate::middleware::metrics::register_matching_metrics();
     info!("✅ Prometheus metrics initialized");
 
     // Setup database connections
@@ -33,8 +38,12 @@ pub async fn initialize_app(config: &Config) -> Result<AppState> {
     let redis_client = setup_redis(config).await?;
     info!("✅ Redis connection established");
 
-    // Initialize authentication services
-    let jwt_service = JwtService::new()?;
+    // Initialize cache service
+    let cache_service = services::CacheService::new(&config.redis_url).await?;
+    info!("✅ Cache service initialized");
+
+    // Initialize authentication services; revoked token ids live in the cache
+    let jwt_service = JwtService::new()?.with_revocations(cache_service.clone());
     let api_key_service = ApiKeyService::new()?;
     info!("✅ JWT and API key services initialized");
 
@@ -55,9 +64,19 @@ pub async fn initialize_app(config: &Config) -> Result<AppState> {
         config.solana_rpc_url.clone(),
         "localnet".to_string(),
         config.solana_programs.clone(),
-    )?;
+    )?
+    .with_mint_decimals(mint_decimals_registry(&config));
+    let solana_programs = *blockchain_service.program_ids();
     info!("✅ Blockchain service initialized (RPC: {})", config.solana_rpc_url);
 
+    // Check the authority can pay for settlements before serving traffic;
+    // a shortfall is alerted and fails readiness rather than startup
+    let authority_monitor = services::blockchain::AuthorityMonitor::new(
+        blockchain_service.clone(),
+        config.authority_funding.clone(),
+    );
+    authority_monitor.check().await;
+
     // Initialize wallet service
     let wallet_service = if let Ok(path) = std::env::var("AUTHORITY_WALLET_PATH") {
         info!("Loading authority wallet from: {}", path);
@@ -72,9 +91,17 @@ pub async fn initialize_app(config: &Config) -> Result<AppState> {
     let websocket_service = services::WebSocketService::new();
     info!("✅ WebSocket service initialized");
 
-    // Initialize cache service
-    let cache_service = services::CacheService::new(&config.redis_url).await?;
-    info!("✅ Cache service initialized");
+    // Background loops report here so readiness notices one that has died
+    let task_heartbeats = services::TaskHeartbeats::new();
+
+    // Only the lease holder runs matching, settlement and the other singleton jobs
+    let job_coordinator = services::BackgroundJobCoordinator::new(
+        cache_service.clone(),
+        config.leader_election.clone(),
+    )
+    .with_heartbeats(task_heartbeats.clone());
+    info!("✅ Job coordinator initialized (instance {})", job_coordinator.instance_id());
+
 
     // Initialize health checker
     let health_checker = services::HealthChecker::new(
@@ -82,7 +109,10 @@ pub async fn initialize_app(config: &Config) -> Result<AppState> {
         redis_client.clone(),
         config.solana_rpc_url.clone(),
         email_service.is_some(),
-    );
+    )
+    .with_blockchain_ws_url(config.solana_ws_url.clone())
+    .with_authority_monitor(authority_monitor.clone())
+    .with_task_heartbeats(task_heartbeats.clone());
     info!("✅ Health checker initialized");
 
     // Initialize audit logger
@@ -93,39 +123,67 @@ pub async fn initialize_app(config: &Config) -> Result<AppState> {
     let erc_service = services::ErcService::new(db_pool.clone(), blockchain_service.clone());
     info!("✅ ERC service initialized");
 
+    // Hot-reloadable settings shared by matching, clearing, settlement and CORS
+    let runtime_config = ReloadableConfig::from_env();
+
     // Initialize market clearing service
     let market_clearing = services::MarketClearingService::new(
         db_pool.clone(),
-        blockchain_service.clone(),
+        std::sync::Arc::new(blockchain_service.clone()),
         config.clone(),
         wallet_service.clone(),
         audit_logger.clone(),
         websocket_service.clone(),
         erc_service.clone(),
-    );
+    )
+    .with_cache(cache_service.clone())
+    .with_runtime_config(runtime_config.clone());
     info!("✅ Market clearing service initialized");
 
+    // Shutdown coordination for background tasks
+    let shutdown = CancellationToken::new();
+    let background_tasks = TaskTracker::new();
+
     // Initialize settlement service with environment-based config
     let settlement_config = services::settlement::SettlementConfig::from_env();
     info!(
         "✅ Settlement config: fee_rate={}, real_blockchain={}",
-        settlement_config.fee_rate, settlement_config.enable_real_blockchain
+        settlement_config.fee_schedule.base_rate, settlement_config.enable_real_blockchain
     );
     let settlement = services::SettlementService::with_config(
         db_pool.clone(),
         blockchain_service.clone(),
         settlement_config,
         config.encryption_secret.clone(),
-    );
+    )
+    .with_runtime_config(runtime_config.clone())
+    .with_websocket(websocket_service.clone())
+    .with_job_coordinator(job_coordinator.clone())
+    .with_shutdown(shutdown.clone());
+    if let Err(e) = settlement.reload_retry_overrides().await {
+        warn!("⚠️ Failed to load settlement retry overrides, using environment patterns only: {}", e);
+    }
     info!("✅ Settlement service initialized");
 
-
     // Initialize matching engine
+    let grid_topology = match services::GridTopologyService::from_db(db_pool.clone()).await {
+        Ok(grid_topology) => grid_topology,
+        Err(e) => {
+            warn!("⚠️ Failed to load zone rates, using default tariffs until the next refresh: {}", e);
+            services::GridTopologyService::with_pool(db_pool.clone())
+        }
+    }
+    .with_loss_model(config.grid_loss.clone());
     let market_clearing_engine = services::OrderMatchingEngine::new(db_pool.clone())
+        .with_grid_topology(grid_topology.clone())
         .with_websocket(websocket_service.clone())
         .with_settlement(settlement.clone())
         .with_market_clearing(market_clearing.clone())
-        .with_blockchain(blockchain_service.clone());
+        .with_blockchain(blockchain_service.clone())
+        .with_runtime_config(runtime_config.clone())
+        .with_job_coordinator(job_coordinator.clone())
+        .with_heartbeats(task_heartbeats.clone())
+        .with_shutdown(shutdown.clone(), background_tasks.clone());
     info!("✅ Order matching engine initialized");
 
     // Initialize futures service
@@ -158,20 +216,38 @@ pub async fn initialize_app(config: &Config) -> Result<AppState> {
         config.solana_rpc_url.clone(),
         config.event_processor.clone(),
         config.energy_token_mint.clone(),
-    );
+    )
+    .with_job_coordinator(job_coordinator.clone())
+    .with_heartbeats(task_heartbeats.clone());
     info!("✅ Event processor service initialized");
 
     // Initialize reading processor service (Asynchronous queue)
     let reading_processor = services::reading_processor::ReadingProcessorService::new();
     info!("✅ Reading processor service initialized");
 
+    // Initialize sustainability accounting
+    let sustainability = services::SustainabilityService::new(
+        db_pool.clone(),
+        config.emission_factors.clone(),
+    );
+
+    // Initialize AMM liquidity pools (AMM_POOL_CONCURRENCY selects lock or optimistic updates)
+    let amm_service = services::AmmService::new(db_pool.clone())
+        .with_websocket(websocket_service.clone());
+    info!("✅ AMM service initialized");
+
+    // Initialize per-user trading account view
+    let trading_account = services::TradingAccountService::new(db_pool.clone());
+
     // Initialize dashboard service
     let dashboard_service = services::DashboardService::new(
         db_pool.clone(),
         health_checker.clone(),
         event_processor.clone(),
         websocket_service.clone(),
-    );
+    )
+    .with_sustainability(sustainability.clone())
+    .with_job_coordinator(job_coordinator.clone());
     info!("✅ Dashboard service initialized");
 
     // Initialize notification dispatcher
@@ -189,6 +265,28 @@ pub async fn initialize_app(config: &Config) -> Result<AppState> {
     );
     info!("✅ Blockchain task service initialized");
 
+    // Initialize meter reading anomaly screening
+    let meter_reading_validator = services::validation::MeterReadingValidator::new(db_pool.clone());
+    info!("✅ Meter reading validator initialized");
+
+    // Initialize DB / on-chain balance reconciliation
+    let reconciliation = services::ReconciliationService::new(
+        db_pool.clone(),
+        blockchain_service.clone(),
+        config.energy_token_mint.clone(),
+    );
+    info!("✅ Reconciliation service initialized");
+
+    // Initialize platform revenue payouts
+    let treasury = services::TreasuryService::new(
+        db_pool.clone(),
+        std::sync::Arc::new(blockchain_service.clone()),
+        config.treasury.clone(),
+        config.currency_token_mint.clone(),
+    )
+    .with_real_blockchain(config.tokenization.enable_real_blockchain);
+    info!("✅ Treasury service initialized");
+
     // Initialize HTTP Client
     let http_client = reqwest::Client::builder()
         .timeout(std::time::Duration::from_secs(10))
@@ -196,13 +294,25 @@ pub async fn initialize_app(config: &Config) -> Result<AppState> {
         .map_err(|e| anyhow::anyhow!("Failed to create HTTP client: {}", e))?;
     info!("✅ HTTP client initialized");
 
+    // Initialize meter reading export to InfluxDB (optional)
+    let influx_writer = services::InfluxWriter::new(&config.influx, http_client.clone());
+
+    // Initialize rate limiter
+    let rate_limiter = crate::middleware::RateLimiter::new(
+        crate::middleware::RateLimitConfig::from_env(config.rate_limit_window),
+    );
+    info!("✅ Rate limiter initialized (window: {}s)", config.rate_limit_window);
+
     // Create minimal application state
     let app_state = AppState {
         db: db_pool,
         redis: redis_client,
         config: config.clone(),
+        runtime_config,
+        solana_programs,
         jwt_service,
         api_key_service,
+        rate_limiter,
         auth,
         email_service,
         blockchain_service,
@@ -215,6 +325,7 @@ pub async fn initialize_app(config: &Config) -> Result<AppState> {
         settlement,
         market_clearing_engine,
         futures_service,
+        amm_service,
         dashboard_service,
         event_processor: event_processor.clone(),
         price_monitor,
@@ -224,6 +335,18 @@ pub async fn initialize_app(config: &Config) -> Result<AppState> {
         erc_service,
         notification_dispatcher,
         blockchain_task_service: blockchain_task_service.clone(),
+        meter_reading_validator,
+        reconciliation,
+        grid_topology,
+        sustainability,
+        trading_account,
+        treasury,
+        task_heartbeats,
+        job_coordinator,
+        authority_monitor,
+        influx_writer,
+        shutdown,
+        background_tasks,
         metrics_handle,
         http_client,
     };
@@ -273,6 +396,19 @@ fn initialize_email_service(config: &Config) -> Option<services::EmailService> {
     }
 }
 
+/// Known token decimals: `MINT_DECIMALS` entries plus the currency mint.
+/// Unparseable mint addresses are left to the on-chain lookup.
+fn mint_decimals_registry(config: &Config) -> Vec<(solana_sdk::pubkey::Pubkey, u8)> {
+    use std::str::FromStr;
+
+    std::iter::once((config.currency_token_mint.as_str(), config.currency_decimals))
+        .chain(config.mint_decimals.iter().map(|(mint, decimals)| (mint.as_str(), *decimals)))
+        .filter_map(|(mint, decimals)| {
+            solana_sdk::pubkey::Pubkey::from_str(mint).ok().map(|mint| (mint, decimals))
+        })
+        .collect()
+}
+
 /// Initialize wallet service and load authority wallet.
 async fn initialize_wallet(wallet_service: &services::WalletService) {
     match wallet_service.initialize_authority().await {
@@ -291,43 +427,201 @@ async fn initialize_wallet(wallet_service: &services::WalletService) {
 }
 
 /// Spawn background tasks.
-pub async fn spawn_background_tasks(app_state: &AppState, _config: &Config) {
+pub async fn spawn_background_tasks(app_state: &AppState, config: &Config) {
     info!("📌 Spawning background tasks...");
+
+    // Reload hot-reloadable settings on SIGHUP
+    spawn_config_reload_listener(app_state.runtime_config.clone());
+
+    // Sample database pool occupancy and acquire latency for Prometheus
+    let db_pool = app_state.db.clone();
+    let pool_metrics_interval = std::env::var("DB_POOL_METRICS_INTERVAL_SECS")
+        .ok()
+        .and_then(|v| v.parse::<u64>().ok())
+        .unwrap_or(10);
+    tokio::spawn(async move {
+        loop {
+            crate::middleware::metrics::track_db_pool(
+                db_pool.size(),
+                db_pool.num_idle(),
+                db_pool.options().get_max_connections(),
+            );
+
+            let started = std::time::Instant::now();
+            match db_pool.acquire().await {
+                Ok(_conn) => crate::middleware::metrics::track_db_pool_acquire(
+                    started.elapsed().as_secs_f64(),
+                ),
+                Err(sqlx::Error::PoolTimedOut) => {
+                    warn!("⚠️ Database pool exhausted: acquire timed out");
+                    crate::middleware::metrics::track_db_pool_acquire_timeout();
+                }
+                Err(e) => warn!("⚠️ Database pool probe failed: {}", e),
+            }
+
+            tokio::time::sleep(tokio::time::Duration::from_secs(pool_metrics_interval)).await;
+        }
+    });
+
+    // Re-check the authority wallet's funding; read-only, so every replica runs it
+    let authority_monitor = app_state.authority_monitor.clone();
+    let authority_check_interval = config.authority_funding.check_interval_secs;
+    tokio::spawn(async move {
+        loop {
+            tokio::time::sleep(tokio::time::Duration::from_secs(authority_check_interval)).await;
+            authority_monitor.check().await;
+        }
+    });
+
+    // Re-read settlement retry overrides so a reload on one replica reaches
+    // every replica; read-only, so every replica runs it
+    let settlement = app_state.settlement.clone();
+    let shutdown = app_state.shutdown.clone();
+    let overrides_refresh = settlement.retry_overrides_refresh_secs();
+    app_state.background_tasks.spawn(async move {
+        loop {
+            tokio::select! {
+                _ = shutdown.cancelled() => break,
+                _ = tokio::time::sleep(tokio::time::Duration::from_secs(overrides_refresh)) => {}
+            }
+            if let Err(e) = settlement.reload_retry_overrides().await {
+                warn!("⚠️ Failed to refresh settlement retry overrides: {}", e);
+            }
+        }
+    });
+
+    // Periodically drop idle rate limiter counters
+    let rate_limiter = app_state.rate_limiter.clone();
+    let prune_interval = rate_limiter.config().window;
+    tokio::spawn(async move {
+        loop {
+            tokio::time::sleep(prune_interval).await;
+            rate_limiter.prune();
+        }
+    });
+
+    // Start Email Delivery Queue
+    if let Some(email_service) = &app_state.email_service {
+        email_service.start_queue_worker();
+        info!("✅ Email Delivery Queue started");
+    }
+
+    // Start InfluxDB Export
+    if let Some(influx_writer) = &app_state.influx_writer {
+        influx_writer.start_worker(app_state.shutdown.clone(), &app_state.background_tasks);
+        info!("✅ InfluxDB Writer started");
+    }
     
+    // Keep zone tariffs in step with the zone_rates table
+    std::sync::Arc::new(app_state.grid_topology.clone()).spawn_refresh_task(
+        config.zone_rates_refresh_interval_secs,
+        app_state.shutdown.clone(),
+        &app_state.background_tasks,
+    );
+
+    // Elect the replica that runs the singleton jobs below. Each job keeps
+    // running on every replica but only does work while this one leads, so
+    // a standby takes over within one lease TTL of the leader going away.
+    let job_coordinator = app_state.job_coordinator.clone();
+    job_coordinator.campaign().await;
+    job_coordinator.start(app_state.shutdown.clone(), &app_state.background_tasks);
+    info!("✅ Background Job Coordinator started");
+
     // Start the Order Matching Engine
     app_state.market_clearing_engine.start().await;
     info!("✅ Order Matching Engine started");
 
     // Start Settlement Service Loop
     let settlement = app_state.settlement.clone();
+    let coordinator = job_coordinator.clone();
+    let shutdown = app_state.shutdown.clone();
+    let heartbeats = app_state.task_heartbeats.clone();
     let settlement_interval = std::env::var("SETTLEMENT_INTERVAL_SECS")
         .ok()
         .and_then(|v| v.parse::<u64>().ok())
         .unwrap_or(5);
-    tokio::spawn(async move {
-        info!("🚀 Starting automated settlement processing (interval: {}s)", settlement_interval);
-        loop {
-            match settlement.process_pending_settlements().await {
-                Ok(count) => {
-                    if count > 0 {
-                        info!("✅ Processed {} settlements", count);
+    let settlement_loop = move || {
+        let settlement = settlement.clone();
+        let coordinator = coordinator.clone();
+        let shutdown = shutdown.clone();
+        let heartbeats = heartbeats.clone();
+        async move {
+            info!("🚀 Starting automated settlement processing (interval: {}s)", settlement_interval);
+            while !shutdown.is_cancelled() {
+                heartbeats.beat(
+                    task_heartbeat::SETTLEMENT_TASK,
+                    tokio::time::Duration::from_secs(settlement_interval),
+                );
+                if coordinator.is_leader() {
+                    heartbeats.beat_batch(
+                        task_heartbeat::SETTLEMENT_TASK,
+                        tokio::time::Duration::from_secs(settlement_interval),
+                    );
+                    match settlement.process_pending_settlements().await {
+                        Ok(count) => {
+                            if count > 0 {
+                                info!("✅ Processed {} settlements", count);
+                            }
+                        }
+                        Err(e) => {
+                            error!("❌ Error processing settlements: {}", e);
+                        }
                     }
                 }
-                Err(e) => {
-                    error!("❌ Error processing settlements: {}", e);
+                tokio::select! {
+                    _ = shutdown.cancelled() => break,
+                    _ = tokio::time::sleep(tokio::time::Duration::from_secs(settlement_interval)) => {}
                 }
             }
-            tokio::time::sleep(tokio::time::Duration::from_secs(settlement_interval)).await;
+            info!("⏹️ Settlement processing stopped");
         }
-    });
+    };
+    app_state.background_tasks.spawn(supervise(
+        task_heartbeat::SETTLEMENT_TASK,
+        RestartPolicy::from_env(),
+        app_state.shutdown.clone(),
+        settlement_loop,
+    ));
     info!("✅ Settlement Service started");
 
     // Start Event Processor Service
-    let event_processor = app_state.event_processor.clone();
-    tokio::spawn(async move {
-        event_processor.start().await;
-    });
-    info!("✅ Event Processor Service started");
+    if config.event_processor.enabled {
+        let event_processor = app_state.event_processor.clone();
+        tokio::spawn(supervise(
+            task_heartbeat::EVENT_PROCESSOR_TASK,
+            RestartPolicy::from_env(),
+            app_state.shutdown.clone(),
+            move || {
+                let event_processor = event_processor.clone();
+                async move { event_processor.start().await }
+            },
+        ));
+        info!("✅ Event Processor Service started");
+
+        // Start Webhook Delivery Worker (Retry Queue)
+        let event_processor = app_state.event_processor.clone();
+        let coordinator = job_coordinator.clone();
+        let shutdown = app_state.shutdown.clone();
+        let webhook_interval = config.event_processor.webhook_delivery_interval_secs;
+        app_state.background_tasks.spawn(async move {
+            info!("🚀 Starting webhook delivery worker (interval: {}s)", webhook_interval);
+            loop {
+                if coordinator.is_leader() {
+                    if let Err(e) = event_processor.process_webhook_deliveries().await {
+                        error!("❌ Error processing webhook deliveries: {}", e);
+                    }
+                }
+                tokio::select! {
+                    _ = shutdown.cancelled() => break,
+                    _ = tokio::time::sleep(tokio::time::Duration::from_secs(webhook_interval)) => {}
+                }
+            }
+            info!("⏹️ Webhook delivery worker stopped");
+        });
+        info!("✅ Webhook Delivery Worker started");
+    } else {
+        info!("⏸️ Event Processor Service disabled (EVENT_PROCESSOR_ENABLED=false)");
+    }
 
     // Start Reading Processor Service (Worker Scaling)
     let reading_processor = app_state.reading_processor.clone();
@@ -351,13 +645,27 @@ pub async fn spawn_background_tasks(app_state: &AppState, _config: &Config) {
     app_state.dashboard_service.start_history_recorder().await;
     info!("✅ Grid History Recorder started");
 
+    // Serve dashboard metrics from a periodically refreshed cache
+    app_state
+        .dashboard_service
+        .start_metrics_refresher(
+            config.dashboard_metrics_interval_secs,
+            app_state.shutdown.clone(),
+            &app_state.background_tasks,
+        )
+        .await;
+    info!("✅ Dashboard Metrics Refresher started");
+
     // Start Price Monitor Loop
     let price_monitor = app_state.price_monitor.clone();
+    let coordinator = job_coordinator.clone();
     tokio::spawn(async move {
         info!("🚀 Starting price monitor (interval: 10s)");
         loop {
-            if let Err(e) = price_monitor.check_and_trigger_orders().await {
-                error!("❌ Error in price monitor: {}", e);
+            if coordinator.is_leader() {
+                if let Err(e) = price_monitor.check_and_trigger_orders().await {
+                    error!("❌ Error in price monitor: {}", e);
+                }
             }
             tokio::time::sleep(tokio::time::Duration::from_secs(10)).await;
         }
@@ -366,11 +674,14 @@ pub async fn spawn_background_tasks(app_state: &AppState, _config: &Config) {
 
     // Start Recurring Scheduler Loop
     let recurring_scheduler = app_state.recurring_scheduler.clone();
+    let coordinator = job_coordinator.clone();
     tokio::spawn(async move {
         info!("🚀 Starting recurring scheduler (interval: 60s)");
         loop {
-            if let Err(e) = recurring_scheduler.process_due_orders().await {
-                error!("❌ Error in recurring scheduler: {}", e);
+            if coordinator.is_leader() {
+                if let Err(e) = recurring_scheduler.process_due_orders().await {
+                    error!("❌ Error in recurring scheduler: {}", e);
+                }
             }
             tokio::time::sleep(tokio::time::Duration::from_secs(60)).await;
         }
@@ -395,13 +706,137 @@ pub async fn spawn_background_tasks(app_state: &AppState, _config: &Config) {
         info!("⏸️ Kafka Consumer disabled (set KAFKA_ENABLED=true to enable)");
     }
 
+    // Start Futures Mark Price Refresher
+    let futures_service = app_state.futures_service.clone();
+    let mark_price_interval = config.futures_mark_price_interval_secs;
+    let coordinator = job_coordinator.clone();
+    let shutdown = app_state.shutdown.clone();
+    app_state.background_tasks.spawn(async move {
+        info!("🚀 Starting futures mark price refresher (interval: {}s)", mark_price_interval);
+        loop {
+            if coordinator.is_leader() {
+                match futures_service.refresh_mark_prices().await {
+                    Ok((_, liquidated)) if liquidated > 0 => {
+                        info!("💥 Liquidated {} futures positions", liquidated);
+                    }
+                    Ok(_) => {}
+                    Err(e) => {
+                        error!("❌ Error refreshing futures mark prices: {}", e);
+                    }
+                }
+            }
+            tokio::select! {
+                _ = shutdown.cancelled() => break,
+                _ = tokio::time::sleep(tokio::time::Duration::from_secs(mark_price_interval)) => {}
+            }
+        }
+    });
+    info!("✅ Futures Mark Price Refresher started");
+
+    // Start ERC Certificate Expiry Sweep
+    let erc_service = app_state.erc_service.clone();
+    let erc_expiry_interval = std::env::var("ERC_EXPIRY_SWEEP_INTERVAL_SECS")
+        .ok()
+        .and_then(|v| v.parse::<u64>().ok())
+        .unwrap_or(86400);
+    let coordinator = job_coordinator.clone();
+    tokio::spawn(async move {
+        info!("🚀 Starting ERC expiry sweep (interval: {}s)", erc_expiry_interval);
+        loop {
+            if coordinator.is_leader() {
+                match erc_service.expire_certificates().await {
+                    Ok(count) if count > 0 => {
+                        info!("⏳ Expired {} ERC certificates", count);
+                    }
+                    Ok(_) => {}
+                    Err(e) => {
+                        error!("❌ Error running ERC expiry sweep: {}", e);
+                    }
+                }
+            }
+            tokio::time::sleep(tokio::time::Duration::from_secs(erc_expiry_interval)).await;
+        }
+    });
+    info!("✅ ERC Expiry Sweep started");
+
+    // Start Balance Reconciliation Sampler
+    let reconciliation = app_state.reconciliation.clone();
+    let reconciliation_interval = std::env::var("RECONCILIATION_INTERVAL_SECS")
+        .ok()
+        .and_then(|v| v.parse::<u64>().ok())
+        .unwrap_or(3600);
+    let coordinator = job_coordinator.clone();
+    tokio::spawn(async move {
+        info!("🚀 Starting balance reconciliation (interval: {}s)", reconciliation_interval);
+        loop {
+            tokio::time::sleep(tokio::time::Duration::from_secs(reconciliation_interval)).await;
+            if !coordinator.is_leader() {
+                continue;
+            }
+            if let Err(e) = reconciliation.reconcile_sample().await {
+                error!("❌ Error running balance reconciliation: {}", e);
+            }
+        }
+    });
+    info!("✅ Balance Reconciliation started");
+
+    // Start Treasury Revenue Sweep
+    if config.treasury.sweep_enabled {
+        let treasury = app_state.treasury.clone();
+        let sweep_interval = config.treasury.sweep_interval_secs;
+        let coordinator = job_coordinator.clone();
+        tokio::spawn(async move {
+            info!("🚀 Starting treasury revenue sweep (interval: {}s)", sweep_interval);
+            loop {
+                tokio::time::sleep(tokio::time::Duration::from_secs(sweep_interval)).await;
+                if !coordinator.is_leader() {
+                    continue;
+                }
+                if let Err(e) = treasury.sweep_revenue().await {
+                    error!("❌ Error sweeping platform revenue: {}", e);
+                }
+            }
+        });
+        info!("✅ Treasury Revenue Sweep started");
+    } else {
+        info!("⏸️ Treasury revenue sweep disabled (TREASURY_SWEEP_ENABLED=false)");
+    }
+
+    // Start Meter Alert Digest (requires email)
+    if let Some(email_service) = app_state.email_service.clone() {
+        let digest_service = services::meter_alert_digest::MeterAlertDigestService::new(
+            app_state.db.clone(),
+            email_service,
+        );
+        let digest_interval = std::env::var("METER_ALERT_DIGEST_INTERVAL_SECS")
+            .ok()
+            .and_then(|v| v.parse::<u64>().ok())
+            .unwrap_or(86400);
+        let coordinator = job_coordinator.clone();
+        tokio::spawn(async move {
+            info!("🚀 Starting meter alert digest (interval: {}s)", digest_interval);
+            loop {
+                tokio::time::sleep(tokio::time::Duration::from_secs(digest_interval)).await;
+                if !coordinator.is_leader() {
+                    continue;
+                }
+                if let Err(e) = digest_service.send_daily_digests().await {
+                    error!("❌ Error sending meter alert digests: {}", e);
+                }
+            }
+        });
+        info!("✅ Meter Alert Digest started");
+    }
+
     // Start Blockchain Task Worker (Retry Queue)
     let blockchain_task_service = app_state.blockchain_task_service.clone();
     tokio::spawn(async move {
         info!("🚀 Starting blockchain task worker (interval: 10s)");
         loop {
-            if let Err(e) = blockchain_task_service.process_pending_tasks().await {
-                error!("❌ Error processing blockchain tasks: {}", e);
+            if job_coordinator.is_leader() {
+                if let Err(e) = blockchain_task_service.process_pending_tasks().await {
+                    error!("❌ Error processing blockchain tasks: {}", e);
+                }
             }
             tokio::time::sleep(tokio::time::Duration::from_secs(10)).await;
         }
@@ -409,6 +844,64 @@ pub async fn spawn_background_tasks(app_state: &AppState, _config: &Config) {
     info!("✅ Blockchain Task Worker started");
 }
 
+/// Re-read the hot-reloadable settings whenever the process receives SIGHUP.
+///
+/// See [`crate::config::reloadable`] for which values are picked up.
+#[cfg(unix)]
+fn spawn_config_reload_listener(runtime_config: ReloadableConfig) {
+    use tokio::signal::unix::{signal, SignalKind};
+
+    let mut hangup = match signal(SignalKind::hangup()) {
+        Ok(hangup) => hangup,
+        Err(e) => {
+            error!("Failed to install SIGHUP handler: {}", e);
+            return;
+        }
+    };
+
+    tokio::spawn(async move {
+        while hangup.recv().await.is_some() {
+            info!("Received SIGHUP signal, reloading configuration");
+            runtime_config.reload();
+        }
+    });
+    info!("✅ Config reload listener started (send SIGHUP to reload)");
+}
+
+/// SIGHUP does not exist here, so settings are only read at startup
+#[cfg(not(unix))]
+fn spawn_config_reload_listener(_runtime_config: ReloadableConfig) {
+    warn!("⚠️ Config hot-reload via SIGHUP is not supported on this platform");
+}
+
+/// Cancel background tasks and wait up to `timeout` for in-flight work
+/// (the current matching cycle and settlement batch) to finish recording.
+pub async fn drain_background_tasks(
+    shutdown: &CancellationToken,
+    tasks: &TaskTracker,
+    timeout: std::time::Duration,
+) {
+    let in_flight = tasks.len();
+    info!("⏳ Draining {} background tasks (timeout: {:?})", in_flight, timeout);
+
+    shutdown.cancel();
+    tasks.close();
+
+    match tokio::time::timeout(timeout, tasks.wait()).await {
+        Ok(()) => info!("✅ Drained {} background tasks cleanly", in_flight),
+        Err(_) => {
+            let forced = tasks.len();
+            warn!(
+                "⚠️ Drained {}/{} background tasks; {} force-stopped after {:?}",
+                in_flight - forced,
+                in_flight,
+                forced,
+                timeout
+            );
+        }
+    }
+}
+
 /// Wait for shutdown signal.
 pub async fn shutdown_signal() {
     use tokio::signal;
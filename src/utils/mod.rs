@@ -8,6 +8,7 @@ pub mod query_profiler;
 pub mod request_info;
 pub mod secrets;
 pub mod signature;
+pub mod units;
 pub mod validation;
 
 pub use pagination::{PaginationMeta, PaginationParams, SortOrder};
@@ -0,0 +1,104 @@
+//! Conversions between decimal token amounts and on-chain atomic units
+//!
+//! Amounts that cannot be represented are reported as [`ConversionError`]
+//! instead of collapsing to zero, so a bad amount never becomes a transfer
+//! that succeeds while moving nothing.
+
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::{Decimal, RoundingStrategy};
+
+/// Largest scale a [`Decimal`] can carry
+const MAX_DECIMALS: u8 = 28;
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum ConversionError {
+    #[error("amount {0} is negative")]
+    Negative(Decimal),
+
+    #[error("amount {amount} does not fit in u64 at {decimals} decimals")]
+    Overflow { amount: Decimal, decimals: u8 },
+
+    #[error("{0} decimals is beyond the supported precision")]
+    UnsupportedDecimals(u8),
+}
+
+/// `amount` in atomic units of a token with `decimals` decimals, truncating
+/// precision beyond the token's smallest unit
+pub fn to_atomic(amount: Decimal, decimals: u8) -> Result<u64, ConversionError> {
+    to_atomic_rounded(amount, decimals, RoundingStrategy::ToZero)
+}
+
+/// `amount` in atomic units, rounding precision beyond the token's smallest
+/// unit with `strategy`
+pub fn to_atomic_rounded(
+    amount: Decimal,
+    decimals: u8,
+    strategy: RoundingStrategy,
+) -> Result<u64, ConversionError> {
+    if amount.is_sign_negative() && !amount.is_zero() {
+        return Err(ConversionError::Negative(amount));
+    }
+    if decimals > MAX_DECIMALS {
+        return Err(ConversionError::UnsupportedDecimals(decimals));
+    }
+
+    let overflow = || ConversionError::Overflow { amount, decimals };
+    let scale = 10u64.checked_pow(decimals as u32).ok_or_else(overflow)?;
+    amount
+        .checked_mul(Decimal::from(scale))
+        .ok_or_else(overflow)?
+        .round_dp_with_strategy(0, strategy)
+        .to_u64()
+        .ok_or_else(overflow)
+}
+
+/// Decimal amount of `atomic` units of a token with `decimals` decimals
+pub fn from_atomic(atomic: u64, decimals: u8) -> Result<Decimal, ConversionError> {
+    Decimal::try_from_i128_with_scale(atomic as i128, decimals as u32)
+        .map_err(|_| ConversionError::UnsupportedDecimals(decimals))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    #[test]
+    fn test_round_trip_at_nine_decimals() {
+        let amount = Decimal::from_str("12.345678912").unwrap();
+        let atomic = to_atomic(amount, 9).unwrap();
+        assert_eq!(atomic, 12_345_678_912);
+        assert_eq!(from_atomic(atomic, 9).unwrap(), amount);
+    }
+
+    #[test]
+    fn test_sub_unit_precision_is_truncated() {
+        assert_eq!(to_atomic(Decimal::from_str("0.0000000019").unwrap(), 9).unwrap(), 1);
+        assert_eq!(to_atomic(Decimal::from_str("1.999").unwrap(), 0).unwrap(), 1);
+
+        let half = Decimal::from_str("0.0000000025").unwrap();
+        assert_eq!(to_atomic_rounded(half, 9, RoundingStrategy::MidpointAwayFromZero).unwrap(), 3);
+        assert_eq!(to_atomic_rounded(half, 9, RoundingStrategy::MidpointNearestEven).unwrap(), 2);
+    }
+
+    #[test]
+    fn test_negative_amount_is_rejected() {
+        assert!(matches!(
+            to_atomic(Decimal::from(-1), 9),
+            Err(ConversionError::Negative(_))
+        ));
+        assert_eq!(to_atomic(Decimal::ZERO, 9).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_overflow_is_an_error_not_zero() {
+        // u64::MAX atomic units at 9 decimals is ~18.4 billion tokens
+        let too_large = Decimal::from(20_000_000_000u64);
+        assert_eq!(
+            to_atomic(too_large, 9),
+            Err(ConversionError::Overflow { amount: too_large, decimals: 9 })
+        );
+        assert_eq!(to_atomic(Decimal::ONE, 20), Err(ConversionError::Overflow { amount: Decimal::ONE, decimals: 20 }));
+        assert_eq!(to_atomic(Decimal::ONE, 29), Err(ConversionError::UnsupportedDecimals(29)));
+    }
+}
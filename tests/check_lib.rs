@@ -1,15 +0,0 @@
-use anyhow::Result;
-use rust_decimal::Decimal;
-use serde_json::json;
-use sqlx::PgPool;
-use uuid::Uuid;
-
-// Note: We need to import the internal modules. 
-// Can't access them directly if they are not pub in lib.rs?
-// In Rust, integration tests under `tests/` treat the crate as an external library.
-// So we rely on public exports in `src/lib.rs` (or `main.rs` if binary only, but normally lib).
-// Assuming `gridtokenx_apigateway::services` is public.
-
-// Since the project structure seems to be a binary crate primarily (`src/main.rs`),
-// we might not be able to import internal modules easily unless there is a `lib.rs`.
-// Let's check if `src/lib.rs` exists. If not, I'll create a unit test module inside `src/services/blockchain_task/mod.rs` instead.
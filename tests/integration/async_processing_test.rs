@@ -107,3 +107,36 @@ async fn test_async_processing_flow_with_worker() -> Result<()> {
 
     Ok(())
 }
+
+#[tokio::test]
+async fn test_event_processor_cursor_resume() -> Result<()> {
+    let app_state = match setup_test_app().await {
+        Ok(state) => state,
+        Err(_) => {
+            println!("Skipping test: Database or Redis not available");
+            return Ok(());
+        }
+    };
+
+    // 1. Persist a cursor ahead of anything previously stored
+    let stored = app_state.event_processor.load_cursor().await?.unwrap_or(0);
+    let cursor = stored + 1_000;
+    app_state.event_processor.save_cursor(cursor).await?;
+
+    // 2. A fresh processor instance resumes from the stored cursor
+    let config = Config::from_env()?;
+    let restarted = api_gateway::services::EventProcessorService::new(
+        Arc::new(app_state.db.clone()),
+        config.solana_rpc_url.clone(),
+        config.event_processor.clone(),
+        config.energy_token_mint.clone(),
+    );
+    assert_eq!(restarted.load_cursor().await?, Some(cursor));
+
+    // 3. The cursor never moves backwards
+    restarted.save_cursor(cursor - 500).await?;
+    assert_eq!(restarted.load_cursor().await?, Some(cursor));
+    println!("✅ Event processor cursor persisted and resumed at slot {}", cursor);
+
+    Ok(())
+}
@@ -446,7 +446,7 @@ async fn test_trading_cycle_integration() -> Result<()> {
     // Test program ID validation
     println!("\n📋 Step 3: Program ID Validation");
     let energy_token_program_id = blockchain_service.energy_token_program_id()?;
-    let trading_program_id = blockchain_service.trading_program_id()?;
+    let trading_program_id = blockchain_service.trading_program_id();
     
     println!("✅ Energy Token Program: {}", energy_token_program_id);
     println!("✅ Trading Program: {}", trading_program_id);
@@ -457,3 +457,327 @@ async fn test_trading_cycle_integration() -> Result<()> {
 
     Ok(())
 }
+
+/// Helper to create a funded buyer and a 100 kWh @ 0.50 buy order
+async fn create_funded_buy_order(
+    db_pool: &PgPool,
+    market_clearing_service: &MarketClearingService,
+) -> Result<(Uuid, Uuid)> {
+    let users = create_test_users_and_wallets(db_pool, 1).await?;
+    let (user_id, _) = users[0];
+
+    sqlx::query("UPDATE users SET balance = 1000, locked_amount = 0 WHERE id = $1")
+        .bind(user_id)
+        .execute(db_pool)
+        .await?;
+
+    let order_id = market_clearing_service
+        .create_order(
+            user_id,
+            api_gateway::database::schema::types::OrderSide::Buy,
+            api_gateway::database::schema::types::OrderType::Limit,
+            Decimal::from(100),
+            Some(Decimal::from_str("0.50").unwrap()),
+            None,
+            None,
+            None,
+            None,
+            None,
+        )
+        .await?;
+
+    Ok((user_id, order_id))
+}
+
+async fn get_user_balances(db_pool: &PgPool, user_id: Uuid) -> Result<(Decimal, Decimal)> {
+    let row: (Decimal, Decimal) =
+        sqlx::query_as("SELECT balance, locked_amount FROM users WHERE id = $1")
+            .bind(user_id)
+            .fetch_one(db_pool)
+            .await?;
+    Ok(row)
+}
+
+#[tokio::test]
+async fn test_reduce_order_releases_escrow() -> Result<()> {
+    let (db_pool, _blockchain_service, _erc_service, _settlement_service, market_clearing_service) =
+        setup_trading_cycle_test().await?;
+
+    println!("\n✂️  ============================================");
+    println!("   Test: Partial Order Reduction");
+    println!("============================================\n");
+
+    println!("📋 Step 1: Create buy order (100 kWh @ 0.50)");
+    let (user_id, order_id) = create_funded_buy_order(&db_pool, &market_clearing_service).await?;
+    let (balance_before, locked_before) = get_user_balances(&db_pool, user_id).await?;
+    assert_eq!(locked_before, Decimal::from(50));
+
+    println!("\n📋 Step 2: Reduce order by 40 kWh");
+    market_clearing_service
+        .reduce_order(order_id, user_id, Decimal::from(40))
+        .await?;
+
+    let energy_amount: Decimal =
+        sqlx::query_scalar("SELECT energy_amount FROM trading_orders WHERE id = $1")
+            .bind(order_id)
+            .fetch_one(&db_pool)
+            .await?;
+    assert_eq!(energy_amount, Decimal::from(60));
+    println!("✅ Order resized to {} kWh", energy_amount);
+
+    println!("\n📋 Step 3: Verify escrow unlock");
+    let (balance_after, locked_after) = get_user_balances(&db_pool, user_id).await?;
+    assert_eq!(balance_after - balance_before, Decimal::from(20));
+    assert_eq!(locked_after, Decimal::from(30));
+
+    let released: Decimal = sqlx::query_scalar(
+        "SELECT COALESCE(SUM(amount), 0) FROM escrow_records WHERE order_id = $1 AND escrow_type = 'partial_release'",
+    )
+    .bind(order_id)
+    .fetch_one(&db_pool)
+    .await?;
+    assert_eq!(released, Decimal::from(20));
+    println!("✅ Released {} from escrow", released);
+
+    println!("\n🎉 ============================================");
+    println!("   Partial Order Reduction Test PASSED");
+    println!("============================================\n");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_reduce_order_rejects_over_reduction() -> Result<()> {
+    let (db_pool, _blockchain_service, _erc_service, _settlement_service, market_clearing_service) =
+        setup_trading_cycle_test().await?;
+
+    println!("\n🚫 ============================================");
+    println!("   Test: Over-Reduction Rejection");
+    println!("============================================\n");
+
+    let (user_id, order_id) = create_funded_buy_order(&db_pool, &market_clearing_service).await?;
+
+    // Simulate a partial fill of 70 kWh
+    sqlx::query(
+        "UPDATE trading_orders SET filled_amount = 70, status = 'partially_filled' WHERE id = $1",
+    )
+    .bind(order_id)
+    .execute(&db_pool)
+    .await?;
+    let (_, locked_before) = get_user_balances(&db_pool, user_id).await?;
+
+    println!("📋 Step 1: Attempt to reduce below filled amount");
+    let result = market_clearing_service
+        .reduce_order(order_id, user_id, Decimal::from(40))
+        .await;
+    assert!(result.is_err(), "Reduction below filled amount should fail");
+
+    let energy_amount: Decimal =
+        sqlx::query_scalar("SELECT energy_amount FROM trading_orders WHERE id = $1")
+            .bind(order_id)
+            .fetch_one(&db_pool)
+            .await?;
+    assert_eq!(energy_amount, Decimal::from(100));
+    let (_, locked_after) = get_user_balances(&db_pool, user_id).await?;
+    assert_eq!(locked_after, locked_before);
+    println!("✅ Over-reduction rejected, order and escrow unchanged");
+
+    println!("\n📋 Step 2: Reduce exactly to filled amount");
+    market_clearing_service
+        .reduce_order(order_id, user_id, Decimal::from(30))
+        .await?;
+    let status: String = sqlx::query_scalar("SELECT status::text FROM trading_orders WHERE id = $1")
+        .bind(order_id)
+        .fetch_one(&db_pool)
+        .await?;
+    assert_eq!(status, "filled");
+    println!("✅ Order reduced to its filled amount is marked filled");
+
+    println!("\n🎉 ============================================");
+    println!("   Over-Reduction Rejection Test PASSED");
+    println!("============================================\n");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_cancel_all_orders_cancels_active_orders() -> Result<()> {
+    let (db_pool, _blockchain_service, _erc_service, _settlement_service, market_clearing_service) =
+        setup_trading_cycle_test().await?;
+
+    println!("\n🧹 ============================================");
+    println!("   Test: Bulk Cancel of Active Orders");
+    println!("============================================\n");
+
+    let (user_id, order_id) = create_funded_buy_order(&db_pool, &market_clearing_service).await?;
+
+    // Orders the matching engine has picked up rest as 'active'
+    sqlx::query("UPDATE trading_orders SET status = 'active' WHERE id = $1")
+        .bind(order_id)
+        .execute(&db_pool)
+        .await?;
+    let (balance_before, locked_before) = get_user_balances(&db_pool, user_id).await?;
+    assert_eq!(locked_before, Decimal::from(50));
+
+    println!("📋 Step 1: Cancel all open orders");
+    let result = market_clearing_service
+        .cancel_all_orders(user_id, api_gateway::services::market_clearing::CancelFilter::default())
+        .await?;
+    assert_eq!(result.cancelled, 1);
+    assert!(result.failed.is_empty());
+    assert_eq!(result.refunded_funds, Decimal::from(50));
+
+    let status: String = sqlx::query_scalar("SELECT status::text FROM trading_orders WHERE id = $1")
+        .bind(order_id)
+        .fetch_one(&db_pool)
+        .await?;
+    assert_eq!(status, "cancelled");
+    println!("✅ Active order cancelled");
+
+    println!("\n📋 Step 2: Verify escrow refund");
+    let (balance_after, locked_after) = get_user_balances(&db_pool, user_id).await?;
+    assert_eq!(balance_after - balance_before, Decimal::from(50));
+    assert_eq!(locked_after, Decimal::ZERO);
+    println!("✅ Refunded {} to the buyer", result.refunded_funds);
+
+    println!("\n🎉 ============================================");
+    println!("   Bulk Cancel Test PASSED");
+    println!("============================================\n");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_create_order_is_idempotent_on_client_order_id() -> Result<()> {
+    let (db_pool, _blockchain_service, _erc_service, _settlement_service, market_clearing_service) =
+        setup_trading_cycle_test().await?;
+
+    println!("\n🔁 ============================================");
+    println!("   Test: Idempotent Order Creation");
+    println!("============================================\n");
+
+    let users = create_test_users_and_wallets(&db_pool, 1).await?;
+    let (user_id, _) = users[0];
+    sqlx::query("UPDATE users SET balance = 1000, locked_amount = 0 WHERE id = $1")
+        .bind(user_id)
+        .execute(&db_pool)
+        .await?;
+
+    let client_order_id = format!("retry-{}", Uuid::new_v4());
+    let create = || {
+        market_clearing_service.create_order(
+            user_id,
+            api_gateway::database::schema::types::OrderSide::Buy,
+            api_gateway::database::schema::types::OrderType::Limit,
+            Decimal::from(100),
+            Some(Decimal::from_str("0.50").unwrap()),
+            None,
+            None,
+            None,
+            None,
+            Some(client_order_id.as_str()),
+        )
+    };
+
+    println!("📋 Step 1: Create order with client_order_id");
+    let first_id = create().await?;
+    let (_, locked_after_first) = get_user_balances(&db_pool, user_id).await?;
+    assert_eq!(locked_after_first, Decimal::from(50));
+
+    println!("\n📋 Step 2: Retry with the same client_order_id");
+    let second_id = create().await?;
+    assert_eq!(first_id, second_id);
+
+    let order_count: i64 = sqlx::query_scalar(
+        "SELECT COUNT(*) FROM trading_orders WHERE user_id = $1 AND client_order_id = $2",
+    )
+    .bind(user_id)
+    .bind(&client_order_id)
+    .fetch_one(&db_pool)
+    .await?;
+    assert_eq!(order_count, 1);
+
+    let (_, locked_after_retry) = get_user_balances(&db_pool, user_id).await?;
+    assert_eq!(locked_after_retry, locked_after_first);
+    println!("✅ Retry returned order {} without locking funds again", second_id);
+
+    println!("\n🎉 ============================================");
+    println!("   Idempotent Order Creation Test PASSED");
+    println!("============================================\n");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_create_order_locks_escrow_and_rejects_overcommitment() -> Result<()> {
+    let (db_pool, _blockchain_service, _erc_service, _settlement_service, market_clearing_service) =
+        setup_trading_cycle_test().await?;
+
+    println!("\n🔒 ============================================");
+    println!("   Test: Escrow Lock at Order Creation");
+    println!("============================================\n");
+
+    let users = create_test_users_and_wallets(&db_pool, 1).await?;
+    let (user_id, _) = users[0];
+    sqlx::query("UPDATE users SET balance = 80, locked_amount = 0 WHERE id = $1")
+        .bind(user_id)
+        .execute(&db_pool)
+        .await?;
+
+    let place_buy = || {
+        market_clearing_service.create_order(
+            user_id,
+            api_gateway::database::schema::types::OrderSide::Buy,
+            api_gateway::database::schema::types::OrderType::Limit,
+            Decimal::from(100),
+            Some(Decimal::from_str("0.50").unwrap()),
+            None,
+            None,
+            None,
+            None,
+            None,
+        )
+    };
+
+    println!("📋 Step 1: First buy order locks 50 of the 80 balance");
+    let order_id = place_buy().await?;
+    let (balance, locked) = get_user_balances(&db_pool, user_id).await?;
+    assert_eq!(balance, Decimal::from(30));
+    assert_eq!(locked, Decimal::from(50));
+
+    let escrow_status: String = sqlx::query_scalar(
+        "SELECT status FROM escrow_records WHERE order_id = $1 AND escrow_type = 'buy_lock'",
+    )
+    .bind(order_id)
+    .fetch_one(&db_pool)
+    .await?;
+    assert_eq!(escrow_status, "locked");
+
+    println!("\n📋 Step 2: Second buy order exceeds the remaining balance");
+    let err = place_buy().await.expect_err("order exceeding balance must be rejected");
+    let rejected = err
+        .downcast_ref::<api_gateway::services::market_clearing::OrderRejected>()
+        .expect("rejection should carry a reason");
+    assert_eq!(
+        rejected.reason,
+        api_gateway::models::trading::OrderCloseReason::InsufficientFunds
+    );
+
+    let (balance_after, locked_after) = get_user_balances(&db_pool, user_id).await?;
+    assert_eq!(balance_after, Decimal::from(30));
+    assert_eq!(locked_after, Decimal::from(50));
+
+    let order_count: i64 =
+        sqlx::query_scalar("SELECT COUNT(*) FROM trading_orders WHERE user_id = $1")
+            .bind(user_id)
+            .fetch_one(&db_pool)
+            .await?;
+    assert_eq!(order_count, 1);
+    println!("✅ Rejected order left no row and no lock behind");
+
+    println!("\n🎉 ============================================");
+    println!("   Escrow Lock Test PASSED");
+    println!("============================================\n");
+
+    Ok(())
+}
@@ -10,6 +10,7 @@ use sqlx::PgPool;
 use uuid::Uuid;
 // use serde_json::json;
 // use chrono::Utc;
+use solana_sdk::pubkey::Pubkey;
 use solana_sdk::signature::{Keypair, Signer};
 use std::sync::Arc;
 // use api_gateway::services::validation::OracleValidator;
@@ -294,10 +295,10 @@ mod tests {
         
         // 2. Program ID Validation
         println!("🔑 Testing program ID validation...");
-        assert!(blockchain_service.registry_program_id().is_ok());
-        assert!(blockchain_service.governance_program_id().is_ok());
-        assert!(blockchain_service.energy_token_program_id().is_ok());
-        assert!(blockchain_service.trading_program_id().is_ok());
+        assert_ne!(blockchain_service.registry_program_id(), Pubkey::default());
+        assert_ne!(blockchain_service.governance_program_id(), Pubkey::default());
+        assert_ne!(blockchain_service.energy_token_program_id(), Pubkey::default());
+        assert_ne!(blockchain_service.trading_program_id(), Pubkey::default());
         
         // 3. Transaction Building
         println!("🔨 Testing transaction building...");
@@ -11,15 +11,21 @@ use anyhow::Result;
 use api_gateway::config::SolanaProgramsConfig;
 use api_gateway::services::{
     blockchain::BlockchainService,
+    fees::FeeSchedule,
     market_clearing::types::TradeMatch,
-    settlement::{SettlementConfig, SettlementService, SettlementStatus},
+    order_matching_engine::{types::ZoneClearingStrategy, OrderMatchingEngine},
+    settlement::{
+        EnergyRounding, RetryOverrides, RevenueSplits, SettlementConfig, SettlementPathStepStatus, SettlementSchedule, SettlementService,
+        SettlementStatus,
+    },
 };
 use chrono::Utc;
 use rust_decimal::Decimal;
-use solana_sdk::signature::Signer;
+use solana_sdk::signature::{Keypair, Signer};
 use sqlx::PgPool;
 use std::str::FromStr;
 use std::sync::Arc;
+use std::time::Duration;
 use uuid::Uuid;
 
 /// Helper to create a test epoch
@@ -150,11 +156,11 @@ async fn test_settlement_service_initialization() -> Result<()> {
 
     println!("\n📋 Step 2: Test default configuration");
     let default_config = SettlementConfig::default();
-    println!("✅ Default fee rate: {}", default_config.fee_rate);
+    println!("✅ Default fee rate: {}", default_config.fee_schedule.base_rate);
     println!("✅ Max retries: {}", default_config.retry_attempts);
     println!("✅ Retry delay: {:?}", default_config.retry_delay_secs);
 
-    assert_eq!(default_config.fee_rate, Decimal::from_str("0.01").unwrap()); // 1%
+    assert_eq!(default_config.fee_schedule.base_rate, Decimal::from_str("0.01").unwrap()); // 1%
     assert_eq!(default_config.retry_attempts, 3);
 
     println!("\n🎉 ============================================");
@@ -235,11 +241,18 @@ async fn test_settlement_fee_calculation() -> Result<()> {
         println!("\n📋 Testing: {}", description);
 
         let config = SettlementConfig {
-            fee_rate: Decimal::from_str(&fee_rate.to_string()).unwrap(),
+            fee_schedule: FeeSchedule::flat(Decimal::from_str(&fee_rate.to_string()).unwrap()),
             min_confirmation_blocks: 32,
             retry_attempts: 3,
             retry_delay_secs: 60,
             enable_real_blockchain: false,
+            batch_max_settlements: 1,
+            reconciliation_tolerance: Decimal::ZERO,
+            schedule: SettlementSchedule::default(),
+            retry_overrides: RetryOverrides::default(),
+            retry_overrides_refresh_secs: 60,
+            revenue_splits: RevenueSplits::default(),
+            energy_rounding: EnergyRounding::default(),
         };
 
         let encryption_secret = std::env::var("ENCRYPTION_SECRET")
@@ -565,3 +578,596 @@ async fn test_complete_settlement_workflow() -> Result<()> {
 
     Ok(())
 }
+
+#[tokio::test]
+async fn test_revenue_reconciliation_detects_discrepancy() -> Result<()> {
+    let (db_pool, _blockchain_service, settlement_service, epoch_id): (PgPool, Arc<BlockchainService>, SettlementService, Uuid) =
+        setup_settlement_test().await?;
+
+    println!("\n🧾 ============================================");
+    println!("   Test: Revenue Reconciliation");
+    println!("============================================\n");
+
+    let window_start = Utc::now() - chrono::Duration::seconds(1);
+
+    // Step 1: Seed completed settlements with their revenue rows
+    println!("📋 Step 1: Seed completed settlements");
+    let mut settlement_ids = Vec::new();
+    for _ in 0..2 {
+        let buyer_id = create_test_user(&db_pool).await?;
+        let seller_id = create_test_user(&db_pool).await?;
+        let trade = create_mock_trade(buyer_id, seller_id, 100.0, 0.15, epoch_id);
+        let settlement = settlement_service.create_settlement(&trade).await?;
+
+        settlement_service
+            .update_settlement_confirmed(settlement.id, "mock_tx_reconcile", SettlementStatus::Completed)
+            .await?;
+        settlement_service.finalize_escrow(&settlement).await?;
+        settlement_ids.push(settlement.id);
+    }
+
+    let window_end = Utc::now() + chrono::Duration::seconds(1);
+    let report = settlement_service.reconcile_revenue(window_start, window_end).await?;
+    assert!(report
+        .discrepancies
+        .iter()
+        .all(|d| !settlement_ids.contains(&d.settlement_id)));
+    println!("✅ Seeded settlements reconcile cleanly");
+
+    // Step 2: Inject a discrepancy by inflating one revenue row
+    println!("\n📋 Step 2: Inject revenue discrepancy");
+    let tampered_id = settlement_ids[0];
+    sqlx::query(
+        "INSERT INTO platform_revenue (settlement_id, amount, revenue_type, description) VALUES ($1, 5, 'platform_fee', 'injected')"
+    )
+    .bind(tampered_id)
+    .execute(&db_pool)
+    .await?;
+
+    let report = settlement_service.reconcile_revenue(window_start, window_end).await?;
+    let discrepancy = report
+        .discrepancies
+        .iter()
+        .find(|d| d.settlement_id == tampered_id)
+        .expect("injected discrepancy should be reported");
+
+    assert_eq!(discrepancy.difference, Decimal::from(5));
+    assert!(!report.is_balanced);
+    assert!(report
+        .discrepancies
+        .iter()
+        .all(|d| d.settlement_id != settlement_ids[1]));
+    println!("✅ Discrepancy detected for settlement {}", tampered_id);
+
+    println!("\n🎉 ============================================");
+    println!("   Revenue Reconciliation Test PASSED");
+    println!("============================================\n");
+
+    Ok(())
+}
+
+/// Helper to insert an active limit order for the matching engine
+async fn create_test_order(
+    pool: &PgPool,
+    user_id: Uuid,
+    epoch_id: Uuid,
+    side: &str,
+    energy_amount: Decimal,
+    price_per_kwh: Decimal,
+) -> Result<Uuid> {
+    let order_id = Uuid::new_v4();
+    sqlx::query(
+        r#"
+        INSERT INTO trading_orders (
+            id, user_id, order_type, side, energy_amount, price_per_kwh,
+            filled_amount, status, expires_at, created_at, epoch_id
+        ) VALUES ($1, $2, 'limit', $3::order_side, $4, $5, 0, 'active', $6, NOW(), $7)
+        "#,
+    )
+    .bind(order_id)
+    .bind(user_id)
+    .bind(side)
+    .bind(energy_amount)
+    .bind(price_per_kwh)
+    .bind(Utc::now() + chrono::Duration::hours(1))
+    .bind(epoch_id)
+    .execute(pool)
+    .await?;
+    Ok(order_id)
+}
+
+/// Serializes the tests that run matching cycles, which match every open
+/// order in the database
+static MATCHING_LOCK: tokio::sync::Mutex<()> = tokio::sync::Mutex::const_new(());
+
+/// Helper to create a limit order with the given time in force
+async fn create_test_order_with_tif(
+    pool: &PgPool,
+    user_id: Uuid,
+    epoch_id: Uuid,
+    side: &str,
+    energy_amount: Decimal,
+    price_per_kwh: Decimal,
+    time_in_force: &str,
+) -> Result<Uuid> {
+    let order_id = create_test_order(pool, user_id, epoch_id, side, energy_amount, price_per_kwh).await?;
+    sqlx::query("UPDATE trading_orders SET time_in_force = $2 WHERE id = $1")
+        .bind(order_id)
+        .bind(time_in_force)
+        .execute(pool)
+        .await?;
+    Ok(order_id)
+}
+
+/// Helper to read an order's status, filled amount and cancellation reason
+async fn order_state(pool: &PgPool, order_id: Uuid) -> Result<(String, Decimal, Option<String>)> {
+    let state = sqlx::query_as(
+        "SELECT status::text, COALESCE(filled_amount, 0), cancellation_reason FROM trading_orders WHERE id = $1",
+    )
+    .bind(order_id)
+    .fetch_one(pool)
+    .await?;
+    Ok(state)
+}
+
+/// Helper to count the matches and settlements recorded for a buy order
+async fn count_buy_matches(pool: &PgPool, buy_order_id: Uuid) -> Result<(i64, i64)> {
+    let matches = sqlx::query_scalar("SELECT COUNT(*) FROM order_matches WHERE buy_order_id = $1")
+        .bind(buy_order_id)
+        .fetch_one(pool)
+        .await?;
+    let settlements = sqlx::query_scalar("SELECT COUNT(*) FROM settlements WHERE buy_order_id = $1")
+        .bind(buy_order_id)
+        .fetch_one(pool)
+        .await?;
+    Ok((matches, settlements))
+}
+
+/// Helper to cancel the orders a test leaves resting, so later matching
+/// cycles do not pick them up
+async fn cancel_test_orders(pool: &PgPool, order_ids: &[Uuid]) -> Result<()> {
+    sqlx::query(
+        "UPDATE trading_orders SET status = 'cancelled' WHERE id = ANY($1) AND status IN ('pending', 'active', 'partially_filled')",
+    )
+    .bind(order_ids)
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_matching_warmup_creates_no_settlements() -> Result<()> {
+    let _matching = MATCHING_LOCK.lock().await;
+    let (db_pool, _blockchain_service, settlement_service, epoch_id) =
+        setup_settlement_test().await?;
+
+    println!("\n🔥 ============================================");
+    println!("   Test: Matching Warm-up Window");
+    println!("============================================\n");
+
+    println!("📋 Step 1: Create crossing buy and sell orders");
+    let buyer_id = create_test_user(&db_pool).await?;
+    let seller_id = create_test_user(&db_pool).await?;
+    let amount = Decimal::from(10);
+    let buy_order_id =
+        create_test_order(&db_pool, buyer_id, epoch_id, "buy", amount, Decimal::from(5)).await?;
+    let sell_order_id = create_test_order(
+        &db_pool,
+        seller_id,
+        epoch_id,
+        "sell",
+        amount,
+        Decimal::from_str("0.10").unwrap(),
+    )
+    .await?;
+
+    let warmup = Duration::from_secs(2);
+    let engine = OrderMatchingEngine::new(db_pool.clone())
+        .with_settlement(settlement_service)
+        .with_warmup(warmup);
+    // The warm-up counts from the engine's start
+    engine.start().await;
+
+    let count_settlements = || async {
+        sqlx::query_scalar::<_, i64>(
+            "SELECT COUNT(*) FROM settlements WHERE buy_order_id = $1 OR sell_order_id = $2",
+        )
+        .bind(buy_order_id)
+        .bind(sell_order_id)
+        .fetch_one(&db_pool)
+        .await
+    };
+
+    println!("\n📋 Step 2: Run matching cycle during warm-up");
+    assert!(engine.is_warming_up());
+    let (matches, volume) = engine.trigger_matching().await?;
+    assert_eq!(matches, 0);
+    assert_eq!(volume, Decimal::ZERO);
+    assert_eq!(count_settlements().await?, 0);
+
+    let buy_status: String =
+        sqlx::query_scalar("SELECT status::text FROM trading_orders WHERE id = $1")
+            .bind(buy_order_id)
+            .fetch_one(&db_pool)
+            .await?;
+    assert_eq!(buy_status, "active");
+    println!("✅ No settlements or order updates during warm-up");
+
+    println!("\n📋 Step 3: Run matching cycle after warm-up");
+    tokio::time::sleep(warmup).await;
+    assert!(!engine.is_warming_up());
+    engine.trigger_matching().await?;
+    assert!(count_settlements().await? > 0);
+    engine.stop().await;
+    println!("✅ Matching went live after warm-up");
+
+    println!("\n🎉 ============================================");
+    println!("   Matching Warm-up Test PASSED");
+    println!("============================================\n");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_time_in_force_against_partially_matchable_book() -> Result<()> {
+    let _matching = MATCHING_LOCK.lock().await;
+    let (db_pool, _blockchain_service, settlement_service, epoch_id) =
+        setup_settlement_test().await?;
+
+    println!("\n⏱️ ============================================");
+    println!("   Test: Time in Force Against a 7 kWh Book");
+    println!("============================================\n");
+
+    let cases = [
+        ("GTC", "partially_filled", Decimal::from(7), None, 2),
+        ("IOC", "cancelled", Decimal::from(7), Some("immediate_or_cancel"), 2),
+        ("FOK", "cancelled", Decimal::ZERO, Some("fill_or_kill"), 0),
+    ];
+
+    for (time_in_force, status, filled, reason, match_count) in cases {
+        println!("📋 {}: 10 kWh buy against 4 + 3 kWh of sells", time_in_force);
+        let buyer_id = create_test_user(&db_pool).await?;
+        let seller_id = create_test_user(&db_pool).await?;
+        let price = Decimal::from_str("0.10").unwrap();
+        let sells = [
+            create_test_order(&db_pool, seller_id, epoch_id, "sell", Decimal::from(4), price).await?,
+            create_test_order(&db_pool, seller_id, epoch_id, "sell", Decimal::from(3), price).await?,
+        ];
+        let buy_order_id = create_test_order_with_tif(
+            &db_pool,
+            buyer_id,
+            epoch_id,
+            "buy",
+            Decimal::from(10),
+            Decimal::from(5),
+            time_in_force,
+        )
+        .await?;
+
+        let engine = OrderMatchingEngine::new(db_pool.clone())
+            .with_settlement(settlement_service.clone())
+            .with_warmup(Duration::ZERO);
+        engine.trigger_matching().await?;
+
+        let (buy_status, buy_filled, buy_reason) = order_state(&db_pool, buy_order_id).await?;
+        assert_eq!(buy_status, status, "{} buy status", time_in_force);
+        assert_eq!(buy_filled, filled, "{} buy fill", time_in_force);
+        assert_eq!(buy_reason.as_deref(), reason, "{} cancellation reason", time_in_force);
+        assert_eq!(count_buy_matches(&db_pool, buy_order_id).await?, (match_count, match_count));
+
+        // A killed FOK buy leaves the book as it was
+        if time_in_force == "FOK" {
+            for sell_order_id in sells {
+                let (sell_status, sell_filled, _) = order_state(&db_pool, sell_order_id).await?;
+                assert_eq!((sell_status.as_str(), sell_filled), ("active", Decimal::ZERO));
+            }
+        }
+
+        cancel_test_orders(&db_pool, &[buy_order_id, sells[0], sells[1]]).await?;
+        println!("✅ {} buy ended {} with {} kWh filled", time_in_force, status, filled);
+    }
+
+    println!("\n🎉 ============================================");
+    println!("   Time in Force Test PASSED");
+    println!("============================================\n");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_fill_or_kill_reverts_partial_fill() -> Result<()> {
+    let _matching = MATCHING_LOCK.lock().await;
+    let (db_pool, _blockchain_service, settlement_service, epoch_id) =
+        setup_settlement_test().await?;
+
+    println!("\n🛑 ============================================");
+    println!("   Test: Fill-or-Kill Revert on a Failed Match");
+    println!("============================================\n");
+
+    println!("📋 Step 1: Create a fillable book whose second match fails");
+    let buyer_id = create_test_user(&db_pool).await?;
+    let seller_id = create_test_user(&db_pool).await?;
+    let first_sell = create_test_order(
+        &db_pool,
+        seller_id,
+        epoch_id,
+        "sell",
+        Decimal::from(6),
+        Decimal::from_str("0.10").unwrap(),
+    )
+    .await?;
+    let second_sell = create_test_order(
+        &db_pool,
+        seller_id,
+        epoch_id,
+        "sell",
+        Decimal::from(4),
+        Decimal::from_str("0.20").unwrap(),
+    )
+    .await?;
+    let buy_order_id = create_test_order_with_tif(
+        &db_pool,
+        buyer_id,
+        epoch_id,
+        "buy",
+        Decimal::from(10),
+        Decimal::from(5),
+        "FOK",
+    )
+    .await?;
+
+    sqlx::query(&format!(
+        r#"
+        CREATE OR REPLACE FUNCTION fail_test_order_match() RETURNS trigger AS $$
+        BEGIN
+            IF NEW.sell_order_id = '{}' THEN
+                RAISE EXCEPTION 'injected match failure';
+            END IF;
+            RETURN NEW;
+        END
+        $$ LANGUAGE plpgsql
+        "#,
+        second_sell
+    ))
+    .execute(&db_pool)
+    .await?;
+    sqlx::query("DROP TRIGGER IF EXISTS fail_test_order_match ON order_matches")
+        .execute(&db_pool)
+        .await?;
+    sqlx::query(
+        "CREATE TRIGGER fail_test_order_match BEFORE INSERT ON order_matches FOR EACH ROW EXECUTE FUNCTION fail_test_order_match()",
+    )
+    .execute(&db_pool)
+    .await?;
+
+    println!("\n📋 Step 2: Run a matching cycle");
+    let engine = OrderMatchingEngine::new(db_pool.clone())
+        .with_settlement(settlement_service)
+        .with_warmup(Duration::ZERO);
+    let outcome = engine.trigger_matching().await;
+
+    sqlx::query("DROP TRIGGER IF EXISTS fail_test_order_match ON order_matches")
+        .execute(&db_pool)
+        .await?;
+    sqlx::query("DROP FUNCTION IF EXISTS fail_test_order_match()")
+        .execute(&db_pool)
+        .await?;
+    outcome?;
+
+    println!("\n📋 Step 3: Verify the first fill was reverted");
+    let (buy_status, buy_filled, buy_reason) = order_state(&db_pool, buy_order_id).await?;
+    assert_eq!(buy_status, "cancelled");
+    assert_eq!(buy_filled, Decimal::ZERO);
+    assert_eq!(buy_reason.as_deref(), Some("fill_or_kill"));
+    assert_eq!(count_buy_matches(&db_pool, buy_order_id).await?, (0, 0));
+
+    for sell_order_id in [first_sell, second_sell] {
+        let (sell_status, sell_filled, _) = order_state(&db_pool, sell_order_id).await?;
+        assert_eq!((sell_status.as_str(), sell_filled), ("active", Decimal::ZERO));
+    }
+    println!("✅ No partial fill, match or settlement survived");
+
+    cancel_test_orders(&db_pool, &[first_sell, second_sell]).await?;
+
+    println!("\n🎉 ============================================");
+    println!("   Fill-or-Kill Revert Test PASSED");
+    println!("============================================\n");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_zonal_first_fill_or_kill_fills_across_passes() -> Result<()> {
+    let _matching = MATCHING_LOCK.lock().await;
+    let (db_pool, _blockchain_service, settlement_service, epoch_id) =
+        setup_settlement_test().await?;
+
+    println!("\n🗺️ ============================================");
+    println!("   Test: Zonal-First Fill-or-Kill Across Passes");
+    println!("============================================\n");
+
+    println!("📋 Step 1: 6 kWh in the buyer's zone, 4 kWh in the next zone");
+    let buyer_id = create_test_user(&db_pool).await?;
+    let seller_id = create_test_user(&db_pool).await?;
+    let price = Decimal::from_str("0.10").unwrap();
+    let local_sell = create_test_order(&db_pool, seller_id, epoch_id, "sell", Decimal::from(6), price).await?;
+    let remote_sell = create_test_order(&db_pool, seller_id, epoch_id, "sell", Decimal::from(4), price).await?;
+    let buy_order_id = create_test_order_with_tif(
+        &db_pool,
+        buyer_id,
+        epoch_id,
+        "buy",
+        Decimal::from(10),
+        Decimal::from(5),
+        "FOK",
+    )
+    .await?;
+    for (order_id, zone_id) in [(buy_order_id, 1), (local_sell, 1), (remote_sell, 2)] {
+        sqlx::query("UPDATE trading_orders SET zone_id = $2 WHERE id = $1")
+            .bind(order_id)
+            .bind(zone_id)
+            .execute(&db_pool)
+            .await?;
+    }
+
+    println!("\n📋 Step 2: Run a zonal-first matching cycle");
+    let engine = OrderMatchingEngine::new(db_pool.clone())
+        .with_settlement(settlement_service)
+        .with_zone_clearing(ZoneClearingStrategy::ZonalFirst)
+        .with_warmup(Duration::ZERO);
+    engine.trigger_matching().await?;
+
+    let (buy_status, buy_filled, buy_reason) = order_state(&db_pool, buy_order_id).await?;
+    assert_eq!(buy_status, "filled");
+    assert_eq!(buy_filled, Decimal::from(10));
+    assert_eq!(buy_reason, None);
+    assert_eq!(count_buy_matches(&db_pool, buy_order_id).await?, (2, 2));
+
+    let clearing_scopes: Vec<String> = sqlx::query_scalar(
+        "SELECT clearing_scope FROM order_matches WHERE buy_order_id = $1 ORDER BY sell_order_id = $2 DESC",
+    )
+    .bind(buy_order_id)
+    .bind(local_sell)
+    .fetch_all(&db_pool)
+    .await?;
+    assert_eq!(clearing_scopes, vec!["intra_zone", "cross_zone"]);
+    println!("✅ The FOK buy filled from both zones in one cycle");
+
+    println!("\n🎉 ============================================");
+    println!("   Zonal-First Fill-or-Kill Test PASSED");
+    println!("============================================\n");
+
+    Ok(())
+}
+
+/// Helper to create a user whose stored (legacy, unencrypted) key is `keypair`
+/// but whose registered wallet address is `wallet`
+async fn create_test_user_with_keypair(
+    pool: &PgPool,
+    keypair: &Keypair,
+    wallet: &str,
+) -> Result<Uuid> {
+    let user_id = Uuid::new_v4();
+    sqlx::query(
+        "INSERT INTO users (id, email, username, password_hash, wallet_address, encrypted_private_key, role, is_active) VALUES ($1, $2, $3, 'hash', $4, $5, 'user', true)"
+    )
+    .bind(user_id)
+    .bind(format!("user_{}@example.com", user_id))
+    .bind(format!("user_{}", user_id))
+    .bind(wallet)
+    .bind(keypair.to_bytes().to_vec())
+    .execute(pool)
+    .await?;
+    Ok(user_id)
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_settlement_path_validation_passes() -> Result<()> {
+    let (db_pool, blockchain_service, settlement_service, epoch_id) =
+        setup_settlement_test().await?;
+
+    println!("\n🧪 ============================================");
+    println!("   Test: Settlement Path Dry-Run (Valid)");
+    println!("============================================\n");
+
+    println!("📋 Step 1: Create funded seller and buyer");
+    let seller_keypair = Keypair::new();
+    let seller_id = create_test_user_with_keypair(
+        &db_pool,
+        &seller_keypair,
+        &seller_keypair.pubkey().to_string(),
+    )
+    .await?;
+    let buyer_keypair = Keypair::new();
+    let buyer_id = create_test_user_with_keypair(
+        &db_pool,
+        &buyer_keypair,
+        &buyer_keypair.pubkey().to_string(),
+    )
+    .await?;
+
+    let mint = BlockchainService::parse_pubkey(&std::env::var("ENERGY_TOKEN_MINT")?)?;
+    let authority = blockchain_service.get_authority_keypair().await?;
+    blockchain_service
+        .mint_spl_tokens(&authority, &seller_keypair.pubkey(), &mint, 500.0)
+        .await?;
+    println!("✅ Seller {} funded", seller_keypair.pubkey());
+
+    println!("\n📋 Step 2: Validate settlement path");
+    let trade = create_mock_trade(buyer_id, seller_id, 100.0, 0.15, epoch_id);
+    let settlement = settlement_service.create_settlement(&trade).await?;
+    let report = settlement_service.validate_settlement_path(settlement.id).await?;
+
+    for step in &report.steps {
+        println!("   {:?} {}: {}", step.status, step.name, step.detail);
+    }
+    assert!(report.all_passed);
+    assert!(report
+        .steps
+        .iter()
+        .all(|s| s.status == SettlementPathStepStatus::Passed));
+
+    let unchanged = settlement_service.get_settlement(settlement.id).await?;
+    assert_eq!(unchanged.status, SettlementStatus::Pending);
+    assert!(unchanged.blockchain_tx.is_none());
+    println!("✅ All steps passed, no transfer executed");
+
+    println!("\n🎉 ============================================");
+    println!("   Settlement Path Dry-Run (Valid) PASSED");
+    println!("============================================\n");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_settlement_path_validation_identity_mismatch() -> Result<()> {
+    let (db_pool, _blockchain_service, settlement_service, epoch_id) =
+        setup_settlement_test().await?;
+
+    println!("\n🧪 ============================================");
+    println!("   Test: Settlement Path Dry-Run (Identity Mismatch)");
+    println!("============================================\n");
+
+    println!("📋 Step 1: Create seller whose stored key differs from wallet");
+    let stored_keypair = Keypair::new();
+    let registered_wallet = Keypair::new().pubkey().to_string();
+    let seller_id =
+        create_test_user_with_keypair(&db_pool, &stored_keypair, &registered_wallet).await?;
+    let buyer_keypair = Keypair::new();
+    let buyer_id = create_test_user_with_keypair(
+        &db_pool,
+        &buyer_keypair,
+        &buyer_keypair.pubkey().to_string(),
+    )
+    .await?;
+
+    println!("\n📋 Step 2: Validate settlement path");
+    let trade = create_mock_trade(buyer_id, seller_id, 100.0, 0.15, epoch_id);
+    let settlement = settlement_service.create_settlement(&trade).await?;
+    let report = settlement_service.validate_settlement_path(settlement.id).await?;
+
+    assert!(!report.all_passed);
+    assert_eq!(
+        report.step_status("seller_key_decryption"),
+        Some(SettlementPathStepStatus::Passed)
+    );
+    assert_eq!(
+        report.step_status("seller_identity"),
+        Some(SettlementPathStepStatus::Failed)
+    );
+    assert_eq!(
+        report.step_status("seller_token_account"),
+        Some(SettlementPathStepStatus::Skipped)
+    );
+    assert_eq!(
+        report.step_status("seller_balance"),
+        Some(SettlementPathStepStatus::Skipped)
+    );
+    println!("✅ Identity mismatch reported, dependent steps skipped");
+
+    println!("\n🎉 ============================================");
+    println!("   Settlement Path Dry-Run (Identity Mismatch) PASSED");
+    println!("============================================\n");
+
+    Ok(())
+}